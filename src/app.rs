@@ -34,13 +34,6 @@ pub fn App() -> Element {
         }
     });
 
-    // Auto-show connection modal when not connected
-    use_effect(move || {
-        if matches!(*CONNECTION.read(), ConnectionState::Disconnected) {
-            *SHOW_CONNECTION_DIALOG.write() = true;
-        }
-    });
-
     rsx! {
         AppLayout {}
     }
@@ -1,8 +1,10 @@
+use crate::services::LlmSender;
 use crate::state::*;
 use dioxus::prelude::*;
 
 #[component]
 pub fn AiResultsPanel() -> Element {
+    let llm_tx = try_use_context::<LlmSender>();
     let ai_panel = AI_PANEL.read();
     let is_dark = *IS_DARK_MODE.read();
 
@@ -40,6 +42,8 @@ pub fn AiResultsPanel() -> Element {
     let content = ai_panel.content.clone();
     let suggested_sql = ai_panel.suggested_sql.clone();
     let is_loading = ai_panel.loading;
+    let session_usage = *LLM_SESSION_USAGE.read();
+    let session_cost = format!("${:.4}", session_usage.cost_usd);
 
     rsx! {
         div {
@@ -69,6 +73,13 @@ pub fn AiResultsPanel() -> Element {
                         class: "animate-spin h-4 w-4 border-2 border-blue-500 border-t-transparent rounded-full",
                     }
                     span { "Thinking..." }
+                    if let Some(tx) = llm_tx {
+                        button {
+                            class: "text-xs text-gray-500 hover:text-gray-700 transition-colors",
+                            onclick: move |_| cancel_llm_request(&tx),
+                            "Cancel"
+                        }
+                    }
                 }
             } else {
                 div {
@@ -123,6 +134,14 @@ pub fn AiResultsPanel() -> Element {
                     }
                 }
             }
+
+            if session_usage.total_tokens() > 0 {
+                div {
+                    class: "mt-3 text-xs text-gray-500",
+                    title: "{session_usage.prompt_tokens} prompt + {session_usage.completion_tokens} completion tokens",
+                    "This session: {session_usage.total_tokens()} tokens · {session_cost}"
+                }
+            }
         }
     }
 }
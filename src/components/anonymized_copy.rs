@@ -0,0 +1,343 @@
+use crate::components::connection_dialog::saved_connection_config;
+use crate::config::{ConnectionStore, Environment};
+use crate::db::{normalize_table_name, AnonymizeRule, CopyOptions, CopyTable, DbRequest};
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn AnonymizedCopyDialog() -> Element {
+    let state = ANONYMIZED_COPY.read().clone();
+    rsx! {
+        if let Some(state) = state {
+            AnonymizedCopyDialogContent { state }
+        }
+    }
+}
+
+/// Open the copy dialog with `table` selected.
+pub fn open_anonymized_copy(table: String) {
+    *ANONYMIZED_COPY.write() = Some(AnonymizedCopyState {
+        table,
+        running: false,
+        summary: None,
+        error: None,
+    });
+}
+
+/// Rules suggested for the columns of `table`, from their names.
+fn suggested_rules(table: &str) -> CopyTable {
+    let schema = SCHEMA.read();
+    let rules = schema
+        .tables
+        .iter()
+        .find(|t| t.name == table)
+        .map(|t| {
+            t.columns
+                .iter()
+                .map(|c| (c.name.clone(), AnonymizeRule::suggest(&c.name)))
+                .collect()
+        })
+        .unwrap_or_default();
+    CopyTable {
+        table: table.to_string(),
+        rules,
+    }
+}
+
+fn rule_from_label(label: &str) -> AnonymizeRule {
+    AnonymizeRule::ALL
+        .into_iter()
+        .find(|r| r.label() == label)
+        .unwrap_or_default()
+}
+
+/// Pick a target connection, the tables and a rule per column, then dry-run or copy.
+#[component]
+fn AnonymizedCopyDialogContent(state: AnonymizedCopyState) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let origin = normalize_table_name(&state.table);
+    let mut tables = use_signal(|| vec![suggested_rules(&origin)]);
+    let mut target = use_signal(String::new);
+    let mut row_limit = use_signal(String::new);
+    let mut replace_existing = use_signal(|| false);
+    let connections = use_hook(|| ConnectionStore::new().load_connections());
+    let schema = SCHEMA.read().clone();
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+
+    let limit_text = row_limit.read().trim().to_string();
+    let limit = limit_text.parse::<usize>().ok().filter(|&n| n > 0);
+    let limit_valid = limit_text.is_empty() || limit.is_some();
+    let target_connection = connections
+        .iter()
+        .find(|c| c.name == *target.read())
+        .cloned();
+    let can_run = !state.running && limit_valid && !tables.read().is_empty();
+
+    // Nulls would be rejected by the target, failing the whole copy
+    let null_conflicts: Vec<String> = tables
+        .read()
+        .iter()
+        .flat_map(|t| {
+            let info = schema.tables.iter().find(|info| info.name == t.table);
+            t.rules
+                .iter()
+                .filter(|(_, rule)| *rule == AnonymizeRule::Nullify)
+                .filter(move |(column, _)| {
+                    info.and_then(|info| info.columns.iter().find(|c| c.name == *column))
+                        .is_some_and(|c| !c.nullable)
+                })
+                .map(move |(column, _)| format!("{}.{}", t.table, column))
+        })
+        .collect();
+
+    let run = {
+        let target_connection = target_connection.clone();
+        move |dry_run: bool| {
+            let target = if dry_run {
+                None
+            } else {
+                let Some(conn) = target_connection.as_ref() else {
+                    return;
+                };
+                let Some(config) = saved_connection_config(conn) else {
+                    if let Some(state) = ANONYMIZED_COPY.write().as_mut() {
+                        state.error = Some(format!(
+                            "Save the password of {} to copy into it",
+                            conn.name
+                        ));
+                    }
+                    return;
+                };
+                Some(config)
+            };
+            if let Some(state) = ANONYMIZED_COPY.write().as_mut() {
+                state.running = true;
+                state.summary = None;
+                state.error = None;
+            }
+            send_db_request(DbRequest::CopyTables {
+                tables: tables.read().clone(),
+                options: CopyOptions {
+                    row_limit: limit,
+                    replace_existing: *replace_existing.read(),
+                },
+                target,
+            });
+        }
+    };
+    let dry_run = run.clone();
+    let copy = run;
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *ANONYMIZED_COPY.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[640px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-1 {text_color}",
+                    "Copy to Connection"
+                }
+                p {
+                    class: "text-xs {muted_text} mb-4",
+                    "Copies the selected tables into another saved connection in one transaction, parents first. Each column is kept, hashed, replaced with fake data or set to NULL."
+                }
+
+                label {
+                    class: "block text-sm font-medium {label_color} mb-1",
+                    "Target connection"
+                }
+                select {
+                    class: "w-full px-3 py-2 border rounded text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                    value: "{target}",
+                    onchange: move |e| target.set(e.value()),
+                    option { value: "", "Choose a saved connection" }
+                    for conn in connections.iter() {
+                        option { value: "{conn.name}", "{conn.name}" }
+                    }
+                }
+                if target_connection.as_ref().is_some_and(|c| c.environment == Some(Environment::Production)) {
+                    div { class: "text-xs text-red-500 mt-1", "The target is marked as production" }
+                }
+
+                label {
+                    class: "block text-sm font-medium {label_color} mt-4 mb-1",
+                    "Tables and column rules"
+                }
+                div {
+                    class: "flex-1 overflow-auto border {dialog_border} rounded p-2 space-y-1 min-h-24",
+                    for table in schema.tables.iter() {
+                        div {
+                            key: "{table.name}",
+                            label {
+                                class: "flex items-center space-x-2 text-sm {label_color}",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: tables.read().iter().any(|t| t.table == table.name),
+                                    onchange: {
+                                        let name = table.name.clone();
+                                        move |_| {
+                                            let existing = tables.read().iter().position(|t| t.table == name);
+                                            match existing {
+                                                Some(i) => {
+                                                    tables.write().remove(i);
+                                                }
+                                                None => {
+                                                    let rules = suggested_rules(&name);
+                                                    tables.write().push(rules);
+                                                }
+                                            }
+                                        }
+                                    },
+                                }
+                                span { class: "font-mono", "{table.name}" }
+                            }
+                            if let Some(t) = tables.read().iter().position(|t| t.table == table.name) {
+                                div {
+                                    class: "ml-6 mb-2 grid grid-cols-2 gap-x-4 gap-y-1",
+                                    for (c, (column, rule)) in tables.read()[t].rules.iter().cloned().enumerate() {
+                                        div {
+                                            key: "{column}",
+                                            class: "flex items-center justify-between space-x-2 text-xs {label_color}",
+                                            span { class: "font-mono truncate", title: "{column}", "{column}" }
+                                            select {
+                                                class: "px-1 py-0.5 border rounded text-xs focus:outline-none focus:border-blue-500 {input_class}",
+                                                value: "{rule.label()}",
+                                                onchange: move |e| tables.write()[t].rules[c].1 = rule_from_label(&e.value()),
+                                                for option_rule in AnonymizeRule::ALL {
+                                                    option { value: "{option_rule.label()}", "{option_rule.label()}" }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex items-center space-x-4 mt-4 text-sm {label_color}",
+                    label {
+                        class: "flex items-center space-x-2",
+                        span { "Rows per table" }
+                        input {
+                            class: "w-24 px-2 py-1 border rounded text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                            r#type: "number",
+                            min: "1",
+                            placeholder: "All",
+                            value: "{row_limit}",
+                            oninput: move |e| row_limit.set(e.value()),
+                        }
+                    }
+                    label {
+                        class: "flex items-center space-x-2",
+                        input {
+                            r#type: "checkbox",
+                            checked: *replace_existing.read(),
+                            onchange: move |_| {
+                                let value = !*replace_existing.read();
+                                replace_existing.set(value);
+                            },
+                        }
+                        span { "Delete existing rows in the target first" }
+                    }
+                }
+
+                if !null_conflicts.is_empty() {
+                    div {
+                        class: "text-xs text-yellow-500 mt-2",
+                        "Not nullable, so the copy will fail if these are set to NULL: {null_conflicts.join(\", \")}"
+                    }
+                }
+
+                if let Some(summary) = state.summary.as_ref() {
+                    div {
+                        class: "mt-4 border {dialog_border} rounded p-2 text-xs {label_color} max-h-40 overflow-auto",
+                        div {
+                            class: "font-medium {text_color} mb-1",
+                            if summary.copied { "Copied" } else { "Dry run: nothing was written" }
+                        }
+                        for table in summary.tables.iter() {
+                            div {
+                                key: "{table.table}",
+                                span { class: "font-mono", "{table.table}" }
+                                span { ": {table.rows} rows" }
+                                if table.transforms.is_empty() {
+                                    span { class: "{muted_text}", ", no columns transformed" }
+                                }
+                                for transform in table.transforms.iter() {
+                                    div {
+                                        key: "{transform.column}",
+                                        class: "ml-4",
+                                        span { class: "font-mono", "{transform.column}" }
+                                        span { " {transform.rule.label()}: {transform.changed} values changed" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if let Some(error) = state.error.as_ref() {
+                    div { class: "text-xs text-red-500 mt-2", "{error}" }
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *ANONYMIZED_COPY.write() = None,
+                        "Close"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded border {dialog_border} {label_color} hover:opacity-80 disabled:opacity-50",
+                        disabled: !can_run,
+                        onclick: move |_| dry_run(true),
+                        "Dry run"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                        disabled: !can_run || target_connection.is_none(),
+                        onclick: move |_| copy(false),
+                        if state.running { "Running..." } else { "Copy" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::state::*;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+/// Characters of the next statement shown in the panel
+const PREVIEW_CHARS: usize = 300;
+
+/// Status bar label for the running batch or import, e.g. "Batch 12/40".
+pub fn batch_progress_label() -> Option<String> {
+    BATCH_PROGRESS
+        .read()
+        .as_ref()
+        .map(|p| format!("Batch {}/{}", p.done, p.total))
+}
+
+/// Stop the running batch before its next statement.
+fn cancel_running_batch() {
+    if let Some(progress) = BATCH_PROGRESS.write().as_mut() {
+        progress.cancelling = true;
+    }
+    crate::db::cancel_batch();
+}
+
+/// Statements or rows done, elapsed time and the statement running next, for the
+/// batch or import in progress.
+#[component]
+pub fn BatchProgressPanel() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    // Re-render every second so the elapsed time keeps counting
+    let mut tick = use_signal(|| 0u64);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            *tick.write() += 1;
+        }
+    });
+    let _ = tick();
+
+    let Some(progress) = BATCH_PROGRESS.read().clone() else {
+        return rsx! {};
+    };
+
+    let panel_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let border_class = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let bar_track = if is_dark {
+        "bg-gray-900"
+    } else {
+        "bg-gray-100"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    let percent = progress.done * 100 / progress.total.max(1);
+    let preview: String = progress
+        .current
+        .trim()
+        .chars()
+        .take(PREVIEW_CHARS)
+        .collect();
+
+    rsx! {
+        div {
+            class: "absolute bottom-6 right-0 w-[400px] flex flex-col {panel_bg} border {border_class} rounded-lg shadow-2xl z-40",
+            onclick: move |e: MouseEvent| e.stop_propagation(),
+
+            div {
+                class: "flex items-center px-3 py-2 border-b {border_class}",
+                span {
+                    class: "text-sm font-semibold {text_color} flex-1",
+                    "Running batch"
+                }
+                if progress.cancelling {
+                    span { class: muted_text, "Cancelling..." }
+                } else {
+                    button {
+                        class: "{muted_text} hover:text-red-500",
+                        onclick: move |_| cancel_running_batch(),
+                        "Cancel"
+                    }
+                }
+                button {
+                    class: "{muted_text} hover:text-blue-500 ml-3",
+                    onclick: move |_| *SHOW_BATCH_PROGRESS.write() = false,
+                    "✕"
+                }
+            }
+
+            div {
+                class: "px-3 py-2 space-y-1",
+                div {
+                    class: "h-1.5 {bar_track}",
+                    div {
+                        class: "h-full bg-blue-500",
+                        style: "width: {percent}%",
+                    }
+                }
+                div {
+                    class: muted_text,
+                    "{progress.done} of {progress.total} {progress.unit} done, {progress.started.elapsed().as_secs()}s elapsed"
+                }
+                if !preview.is_empty() {
+                    pre {
+                        class: "{preview_bg} border {border_class} rounded p-2 font-mono {muted_text} whitespace-pre-wrap break-all max-h-24 overflow-auto",
+                        "{preview}"
+                    }
+                }
+            }
+        }
+    }
+}
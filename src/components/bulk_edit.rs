@@ -0,0 +1,251 @@
+use crate::components::results_table::SELECTED_ROWS;
+use crate::db::{format_bulk_update_sql, normalize_table_name, ColumnRules, DbRequest};
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn BulkEditDialog() -> Element {
+    rsx! {
+        if *SHOW_BULK_EDIT.read() {
+            BulkEditDialogContent {}
+        }
+    }
+}
+
+#[component]
+fn BulkEditDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut column = use_signal(String::new);
+    let mut value = use_signal(String::new);
+    let mut is_expression = use_signal(|| false);
+    let mut set_null = use_signal(|| false);
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    let Some(result) = EDITOR_TABS
+        .read()
+        .active_tab()
+        .and_then(|t| t.result.clone())
+    else {
+        return rsx! {};
+    };
+    let Some(table) = result.source_table.clone() else {
+        return rsx! {};
+    };
+
+    let mut selected: Vec<usize> = SELECTED_ROWS.read().iter().copied().collect();
+    selected.sort_unstable();
+
+    // Primary key values of each selected row, in primary_keys order
+    let pk_indices: Vec<usize> = result
+        .primary_keys
+        .iter()
+        .filter_map(|pk| result.columns.iter().position(|c| c == pk))
+        .collect();
+    let key_values: Vec<Vec<String>> = if pk_indices.len() == result.primary_keys.len() {
+        selected
+            .iter()
+            .filter_map(|&idx| result.rows.get(idx))
+            .map(|row| pk_indices.iter().map(|&i| row[i].clone()).collect())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    // Columns that can be written: not part of the key and not generated by the database
+    let table_info = {
+        let schema = SCHEMA.read();
+        let name = normalize_table_name(&table);
+        schema.tables.iter().find(|t| t.name == name).cloned()
+    };
+    let editable_columns: Vec<String> = result
+        .columns
+        .iter()
+        .filter(|c| !result.primary_keys.contains(c))
+        .filter(|c| {
+            table_info
+                .as_ref()
+                .and_then(|t| t.columns.iter().find(|col| &col.name == *c))
+                .is_none_or(|col| !col.is_generated())
+        })
+        .cloned()
+        .collect();
+
+    let current_column = if column.read().is_empty() {
+        editable_columns.first().cloned().unwrap_or_default()
+    } else {
+        column.read().clone()
+    };
+    let current_value = value.read().clone();
+    let expression = *is_expression.read();
+    let is_null = *set_null.read();
+    let new_value = (!is_null).then_some(current_value.as_str());
+
+    let validation_error = if expression && !is_null {
+        None
+    } else {
+        table_info
+            .as_ref()
+            .map(|t| ColumnRules::for_column(t, &current_column))
+            .and_then(|rules| rules.validate(new_value).err())
+    };
+    let db_type = CURRENT_DB_TYPE.read().unwrap_or(DatabaseType::PostgreSQL);
+    let sql = format_bulk_update_sql(
+        db_type,
+        &table,
+        &current_column,
+        new_value,
+        expression,
+        &result.primary_keys,
+        &key_values,
+    );
+    let can_execute = sql.is_some() && validation_error.is_none();
+    let preview = sql
+        .clone()
+        .unwrap_or_else(|| "-- Enter a value".to_string());
+    let row_count = key_values.len();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *SHOW_BULK_EDIT.write() = false,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[640px] max-w-[95vw] p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-4 {text_color}",
+                    "Bulk Edit · {row_count} rows in {table}"
+                }
+
+                div {
+                    class: "space-y-4",
+
+                    div {
+                        label {
+                            class: "block text-sm font-medium {label_color} mb-1",
+                            "Column"
+                        }
+                        select {
+                            class: "w-full px-3 py-2 border rounded focus:outline-none focus:border-blue-500 {input_class}",
+                            value: "{current_column}",
+                            onchange: move |e| column.set(e.value()),
+                            for name in editable_columns.iter() {
+                                option { value: "{name}", "{name}" }
+                            }
+                        }
+                    }
+
+                    div {
+                        label {
+                            class: "block text-sm font-medium {label_color} mb-1",
+                            "New value"
+                        }
+                        input {
+                            class: "w-full px-3 py-2 border rounded font-mono text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                            r#type: "text",
+                            placeholder: if is_null { "NULL" } else if expression { "e.g. price * 1.1" } else { "Value" },
+                            value: if is_null { "" } else { "{current_value}" },
+                            disabled: is_null,
+                            autofocus: true,
+                            oninput: move |e| value.set(e.value()),
+                        }
+                        div {
+                            class: "flex items-center space-x-4 mt-2",
+                            label {
+                                class: "flex items-center space-x-1 text-xs {muted_text}",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: is_null,
+                                    onchange: move |_| set_null.set(!is_null),
+                                }
+                                span { "Set to NULL" }
+                            }
+                            label {
+                                class: "flex items-center space-x-1 text-xs {muted_text}",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: expression,
+                                    disabled: is_null,
+                                    onchange: move |_| is_expression.set(!expression),
+                                }
+                                span { "SQL expression (not quoted)" }
+                            }
+                        }
+                    }
+
+                    div {
+                        label {
+                            class: "block text-sm font-medium {label_color} mb-1",
+                            "Preview"
+                        }
+                        pre {
+                            class: "{preview_bg} border {dialog_border} rounded p-3 font-mono text-xs {label_color} whitespace-pre-wrap break-all max-h-48 overflow-auto",
+                            "{preview}"
+                        }
+                    }
+
+                    if let Some(error) = validation_error {
+                        div {
+                            class: "text-xs text-red-500",
+                            "{error}"
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *SHOW_BULK_EDIT.write() = false,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                        disabled: !can_execute,
+                        onclick: move |_| {
+                            if let Some(sql) = sql.clone() {
+                                send_tab_request(DbRequest::ExecuteMutation(sql));
+                                *SELECTED_ROWS.write() = Default::default();
+                                *SHOW_BULK_EDIT.write() = false;
+                            }
+                        },
+                        "Update {row_count} rows"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use dioxus::prelude::*;
+
+/// What a text cell looks like it contains.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellKind {
+    Url,
+    Email,
+    Uuid,
+    Timestamp(DateTime<Local>),
+    Color,
+    Plain,
+}
+
+pub fn detect_cell_kind(value: &str) -> CellKind {
+    let value = value.trim();
+    if (value.starts_with("http://") || value.starts_with("https://"))
+        && !value.contains(char::is_whitespace)
+    {
+        CellKind::Url
+    } else if is_email(value) {
+        CellKind::Email
+    } else if is_uuid(value) {
+        CellKind::Uuid
+    } else if is_hex_color(value) {
+        CellKind::Color
+    } else if let Some(at) = parse_timestamp(value) {
+        CellKind::Timestamp(at)
+    } else {
+        CellKind::Plain
+    }
+}
+
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !value.contains(char::is_whitespace)
+        && !domain.contains('@')
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn is_hex_color(value: &str) -> bool {
+    value.strip_prefix('#').is_some_and(|hex| {
+        matches!(hex.len(), 3 | 6 | 8) && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// RFC 3339, `2024-01-31 12:00:00 UTC` as printed for timestamptz, or a naive local timestamp.
+fn parse_timestamp(value: &str) -> Option<DateTime<Local>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&Local));
+    }
+    if let Some(utc) = value.strip_suffix(" UTC") {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y-%m-%d %H:%M:%S%.f").ok()?;
+        return Some(Utc.from_utc_datetime(&naive).with_timezone(&Local));
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+}
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "avif"];
+
+/// Whether the value is a base64 image data URI or a URL whose path ends in an image extension.
+pub fn is_image_value(value: &str) -> bool {
+    let value = value.trim();
+    if value.starts_with("data:image/") {
+        return value.contains(";base64,");
+    }
+    detect_cell_kind(value) == CellKind::Url && image_extension_from_url(value).is_some()
+}
+
+/// The image extension of a URL's path, ignoring any query string or fragment.
+pub fn image_extension_from_url(url: &str) -> Option<&'static str> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let (_, extension) = path.rsplit_once('.')?;
+    IMAGE_EXTENSIONS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(extension))
+        .copied()
+}
+
+/// The file extension and decoded bytes of a `data:image/...;base64,` URI.
+pub fn decode_image_data_uri(value: &str) -> Result<(String, Vec<u8>), String> {
+    let rest = value
+        .trim()
+        .strip_prefix("data:image/")
+        .ok_or("Not an image data URI")?;
+    let (mime, data) = rest
+        .split_once(";base64,")
+        .ok_or("Only base64 data URIs are supported")?;
+    Ok((image_extension_for_mime(mime), decode_base64(data)?))
+}
+
+/// File extension for the subtype of an `image/...` MIME type.
+pub fn image_extension_for_mime(subtype: &str) -> String {
+    match subtype.trim().to_ascii_lowercase().as_str() {
+        "jpeg" | "pjpeg" => "jpg".to_string(),
+        "svg+xml" => "svg".to_string(),
+        "x-icon" | "vnd.microsoft.icon" => "ico".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn decode_base64(data: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in data.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            b' ' | b'\n' | b'\r' | b'\t' => continue,
+            _ => return Err(format!("Invalid base64 character '{}'", c as char)),
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+/// "3 hours ago" / "in 2 days"
+pub fn relative_time(at: DateTime<Local>, now: DateTime<Local>) -> String {
+    let seconds = (now - at).num_seconds();
+    let abs = seconds.unsigned_abs();
+    let (amount, unit) = match abs {
+        0..=59 => return "just now".to_string(),
+        60..=3_599 => (abs / 60, "minute"),
+        3_600..=86_399 => (abs / 3_600, "hour"),
+        86_400..=2_591_999 => (abs / 86_400, "day"),
+        2_592_000..=31_535_999 => (abs / 2_592_000, "month"),
+        _ => (abs / 31_536_000, "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    if seconds >= 0 {
+        format!("{} {}{} ago", amount, unit, plural)
+    } else {
+        format!("in {} {}{}", amount, unit, plural)
+    }
+}
+
+/// A text cell rendered according to its detected content.
+#[component]
+pub fn CellValue(value: String) -> Element {
+    match detect_cell_kind(&value) {
+        CellKind::Url => rsx! {
+            a {
+                class: "underline text-blue-500 hover:text-blue-400",
+                href: "{value.trim()}",
+                target: "_blank",
+                title: "Open in browser",
+                "{value}"
+            }
+        },
+        CellKind::Email => rsx! {
+            a {
+                class: "underline text-blue-500 hover:text-blue-400",
+                href: "mailto:{value.trim()}",
+                "{value}"
+            }
+        },
+        CellKind::Uuid => {
+            let copy_value = value.clone();
+            rsx! {
+                span {
+                    class: "group whitespace-nowrap",
+                    "{value}"
+                    button {
+                        class: "ml-1 opacity-0 group-hover:opacity-60 hover:opacity-100",
+                        title: "Copy",
+                        onclick: move |e: MouseEvent| {
+                            e.stop_propagation();
+                            let _ = document::eval(&format!(
+                                "navigator.clipboard.writeText('{}')",
+                                copy_value.trim()
+                            ));
+                        },
+                        "⧉"
+                    }
+                }
+            }
+        }
+        CellKind::Timestamp(at) => {
+            let relative = relative_time(at, Local::now());
+            rsx! {
+                span {
+                    class: "underline decoration-dotted",
+                    title: "{relative}",
+                    "{value}"
+                }
+            }
+        }
+        CellKind::Color => rsx! {
+            span {
+                class: "inline-flex items-center space-x-1.5",
+                span {
+                    class: "inline-block w-3 h-3 rounded-sm border border-gray-500",
+                    style: "background-color: {value.trim()}",
+                }
+                span { "{value}" }
+            }
+        },
+        CellKind::Plain => rsx! { "{value}" },
+    }
+}
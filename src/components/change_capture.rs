@@ -0,0 +1,282 @@
+use crate::components::open_cell_detail;
+use crate::db::{ChangeEvent, DbRequest};
+use crate::state::*;
+use dioxus::prelude::*;
+
+/// Start capturing row changes of `table` in addition to the tables already captured.
+pub fn capture_table_changes(table: &str) {
+    let mut tables = CAPTURE_TABLES.read().clone();
+    if !tables.iter().any(|t| t == table) {
+        tables.push(table.to_string());
+    }
+    send_db_request(DbRequest::StartCapture(tables));
+    *SHOW_CHANGE_CAPTURE.write() = true;
+}
+
+/// "Capturing (12)" with the number of changes received, while a capture runs.
+pub fn change_capture_label() -> Option<String> {
+    if CAPTURE_TABLES.read().is_empty() {
+        return None;
+    }
+    Some(format!("Capturing ({})", CHANGE_EVENTS.read().len()))
+}
+
+/// `col=value` pairs, with the old key first when an UPDATE changed it.
+fn describe_change(event: &ChangeEvent) -> String {
+    let format_pairs = |pairs: &[(String, String)]| {
+        pairs
+            .iter()
+            .map(|(column, value)| format!("{}={}", column, value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if event.old_key.is_empty() {
+        format_pairs(&event.values)
+    } else {
+        format!(
+            "{} → {}",
+            format_pairs(&event.old_key),
+            format_pairs(&event.values)
+        )
+    }
+}
+
+#[component]
+pub fn ChangeCaptureDialog() -> Element {
+    rsx! {
+        if *SHOW_CHANGE_CAPTURE.read() {
+            ChangeCaptureDialogContent {}
+        }
+    }
+}
+
+#[component]
+fn ChangeCaptureDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut search = use_signal(String::new);
+    let mut selected = use_signal(|| CAPTURE_TABLES.read().clone());
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let cell_text = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+
+    let capturing = CAPTURE_TABLES.read().clone();
+    let is_capturing = !capturing.is_empty();
+    let tables: Vec<String> = SCHEMA
+        .read()
+        .tables
+        .iter()
+        .map(|t| t.name.clone())
+        .collect();
+    let query = search.read().to_lowercase();
+    let events: Vec<_> = CHANGE_EVENTS
+        .read()
+        .iter()
+        .map(|event| {
+            (
+                event.received_at.format("%H:%M:%S").to_string(),
+                describe_change(event),
+                event.clone(),
+            )
+        })
+        .filter(|(_, row, event)| {
+            query.is_empty()
+                || row.to_lowercase().contains(&query)
+                || event.table.to_lowercase().contains(&query)
+        })
+        .collect();
+    let can_start = !selected.read().is_empty();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *SHOW_CHANGE_CAPTURE.write() = false,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[1000px] max-w-[95vw] max-h-[85vh] flex flex-col",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                div {
+                    class: "flex items-center space-x-3 px-4 py-3 border-b {dialog_border}",
+
+                    h2 {
+                        class: "text-lg font-semibold {text_color}",
+                        "Change Capture"
+                    }
+                    span {
+                        class: "text-xs px-1.5 py-0.5 rounded bg-yellow-900 bg-opacity-30 text-yellow-500",
+                        "Experimental"
+                    }
+                    div { class: "flex-1" }
+
+                    input {
+                        class: "w-64 px-3 py-1.5 border rounded text-sm focus:outline-none {input_class}",
+                        r#type: "text",
+                        placeholder: "Filter by table, column or value...",
+                        value: "{search}",
+                        oninput: move |e| search.set(e.value()),
+                    }
+                }
+
+                div {
+                    class: "px-4 py-2 border-b {dialog_border} space-y-2 text-sm",
+
+                    if is_capturing {
+                        div {
+                            class: "flex items-center space-x-2",
+                            span { class: "{muted_text}", "Capturing" }
+                            for table in capturing {
+                                span {
+                                    key: "{table}",
+                                    class: "px-2 py-0.5 rounded bg-blue-600 bg-opacity-20 text-blue-500 font-mono text-xs",
+                                    "{table}"
+                                }
+                            }
+                            div { class: "flex-1" }
+                            button {
+                                class: "text-xs px-2 py-1 rounded bg-red-700 hover:bg-red-600 text-white",
+                                onclick: move |_| send_db_request(DbRequest::StopCapture),
+                                "Stop"
+                            }
+                        }
+                    } else {
+                        div {
+                            class: "flex flex-wrap gap-x-4 gap-y-1 max-h-32 overflow-auto",
+                            for table in tables {
+                                label {
+                                    key: "{table}",
+                                    class: "flex items-center space-x-1 {cell_text}",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: selected.read().contains(&table),
+                                        onchange: {
+                                            let table = table.clone();
+                                            move |e: FormEvent| {
+                                                let mut tables = selected.read().clone();
+                                                if e.checked() {
+                                                    tables.push(table.clone());
+                                                } else {
+                                                    tables.retain(|t| t != &table);
+                                                }
+                                                selected.set(tables);
+                                            }
+                                        },
+                                    }
+                                    span { class: "font-mono text-xs", "{table}" }
+                                }
+                            }
+                        }
+                        div {
+                            class: "flex items-center space-x-3",
+                            span {
+                                class: "flex-1 text-xs {muted_text}",
+                                "Decodes the WAL through a temporary replication slot; needs wal_level = logical and the REPLICATION privilege. Only changes committed after starting are shown."
+                            }
+                            button {
+                                class: "text-xs px-2 py-1 rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                                disabled: !can_start,
+                                onclick: move |_| send_db_request(DbRequest::StartCapture(selected.read().clone())),
+                                "Start"
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex-1 overflow-auto",
+
+                    if events.is_empty() {
+                        div {
+                            class: "{muted_text} text-sm text-center py-8",
+                            if is_capturing {
+                                "Waiting for changes..."
+                            } else {
+                                "Pick tables and start capturing to see their row changes."
+                            }
+                        }
+                    } else {
+                        table {
+                            class: "w-full text-sm text-left",
+                            thead {
+                                class: "{muted_text} text-xs uppercase sticky top-0 {dialog_bg}",
+                                tr {
+                                    th { class: "px-4 py-2", "Time" }
+                                    th { class: "px-4 py-2", "Table" }
+                                    th { class: "px-4 py-2", "Operation" }
+                                    th { class: "px-4 py-2", "Row" }
+                                    th { class: "px-4 py-2", "LSN" }
+                                    th { class: "px-4 py-2", "Transaction" }
+                                }
+                            }
+                            tbody {
+                                for (at, row, event) in events {
+                                    {
+                                        let op_class = match event.operation.as_str() {
+                                            "INSERT" => "text-green-500",
+                                            "UPDATE" => "text-yellow-500",
+                                            "DELETE" | "TRUNCATE" => "text-red-500",
+                                            _ => muted_text,
+                                        };
+                                        let detail = row.clone();
+                                        rsx! {
+                                            tr {
+                                                td { class: "px-4 py-1 text-xs whitespace-nowrap {muted_text}", "{at}" }
+                                                td { class: "px-4 py-1 font-mono text-xs whitespace-nowrap {cell_text}", "{event.table}" }
+                                                td { class: "px-4 py-1 text-xs font-medium {op_class}", "{event.operation}" }
+                                                td {
+                                                    class: "px-4 py-1 font-mono text-xs break-all cursor-pointer hover:underline {cell_text}",
+                                                    title: "Click to open",
+                                                    onclick: move |_| open_cell_detail(detail.clone()),
+                                                    "{row}"
+                                                }
+                                                td { class: "px-4 py-1 font-mono text-xs whitespace-nowrap {muted_text}", "{event.lsn}" }
+                                                td { class: "px-4 py-1 font-mono text-xs {muted_text}", "{event.xid}" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex items-center justify-end px-4 py-2 border-t {dialog_border}",
+                    button {
+                        class: "text-xs {muted_text} hover:text-blue-500",
+                        onclick: move |_| CHANGE_EVENTS.write().clear(),
+                        "Clear"
+                    }
+                }
+            }
+        }
+    }
+}
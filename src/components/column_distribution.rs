@@ -0,0 +1,227 @@
+use crate::db::{normalize_table_name, DbRequest, DistributionKind};
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn ColumnDistributionDialog() -> Element {
+    let state = COLUMN_DISTRIBUTION.read().clone();
+    rsx! {
+        if let Some(state) = state {
+            ColumnDistributionDialogContent { state }
+        }
+    }
+}
+
+/// Open the distribution chart of one of `table`'s columns.
+pub fn open_column_distribution(table: String) {
+    *COLUMN_DISTRIBUTION.write() = Some(DistributionState {
+        table,
+        loading: false,
+        result: None,
+    });
+}
+
+fn kind_from_label(label: &str) -> DistributionKind {
+    DistributionKind::ALL
+        .into_iter()
+        .find(|k| k.label() == label)
+        .unwrap_or(DistributionKind::TopValues)
+}
+
+/// Pick a column and how to group it; its bars show how the rows are spread.
+#[component]
+fn ColumnDistributionDialogContent(state: DistributionState) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let db_type = CURRENT_DB_TYPE.read().unwrap_or_default();
+    let origin = normalize_table_name(&state.table);
+    let columns: Vec<(String, DistributionKind)> = SCHEMA
+        .read()
+        .tables
+        .iter()
+        .find(|t| t.name == origin)
+        .map(|t| {
+            t.columns
+                .iter()
+                .map(|c| {
+                    (
+                        c.name.clone(),
+                        DistributionKind::for_column(db_type, &c.data_type),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut column = use_signal(|| columns.first().map(|(name, _)| name.clone()));
+    let mut kind = use_signal(|| columns.first().map(|(_, kind)| *kind));
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+    let bar_bg = if is_dark {
+        "bg-gray-900"
+    } else {
+        "bg-gray-100"
+    };
+
+    let selected_kind = kind.read().unwrap_or(DistributionKind::TopValues);
+    let can_show = !state.loading && column.read().is_some();
+    let show = {
+        let table = state.table.clone();
+        move |_| {
+            let Some(column) = column.read().clone() else {
+                return;
+            };
+            if let Some(state) = COLUMN_DISTRIBUTION.write().as_mut() {
+                state.loading = true;
+                state.result = None;
+            }
+            send_db_request(DbRequest::FetchDistribution {
+                table: table.clone(),
+                column,
+                kind: selected_kind,
+            });
+        }
+    };
+
+    let distribution = state.result.clone();
+    let max_count = match &distribution {
+        Some(Ok(d)) => d.buckets.iter().map(|b| b.count).max().unwrap_or(0).max(1),
+        _ => 1,
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *COLUMN_DISTRIBUTION.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[640px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-1 {text_color}",
+                    "Distribution of {state.table}"
+                }
+                p {
+                    class: "text-xs {muted_text} mb-4",
+                    "Counts every row of the table, so this can take a while on large tables."
+                }
+
+                div {
+                    class: "flex items-center space-x-2",
+                    select {
+                        class: "flex-1 px-3 py-2 border rounded text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                        value: column.read().clone().unwrap_or_default(),
+                        onchange: {
+                            let columns = columns.clone();
+                            move |e: FormEvent| {
+                                let name = e.value();
+                                // A new column starts with the grouping that suits its type
+                                kind.set(columns.iter().find(|(c, _)| *c == name).map(|(_, k)| *k));
+                                column.set(Some(name));
+                            }
+                        },
+                        for (name, _) in columns.iter() {
+                            option { value: "{name}", "{name}" }
+                        }
+                    }
+                    select {
+                        class: "px-3 py-2 border rounded text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                        value: "{selected_kind.label()}",
+                        onchange: move |e| kind.set(Some(kind_from_label(&e.value()))),
+                        for option_kind in DistributionKind::ALL {
+                            option { value: "{option_kind.label()}", "{option_kind.label()}" }
+                        }
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                        disabled: !can_show,
+                        onclick: show,
+                        if state.loading { "Counting..." } else { "Show" }
+                    }
+                }
+
+                match distribution {
+                    Some(Ok(distribution)) => rsx! {
+                        div {
+                            class: "text-xs {muted_text} mt-4 mb-2",
+                            "{distribution.column}: {distribution.total} rows, {distribution.nulls} NULL"
+                            if distribution.other > 0 {
+                                ", {distribution.other} in other values"
+                            }
+                        }
+                        div {
+                            class: "flex-1 overflow-auto space-y-1 min-h-24",
+                            if distribution.buckets.is_empty() {
+                                div { class: "text-sm {muted_text}", "No values to show" }
+                            }
+                            for (i, bucket) in distribution.buckets.iter().enumerate() {
+                                div {
+                                    key: "{i}",
+                                    class: "flex items-center space-x-2 text-xs",
+                                    span {
+                                        class: "w-40 shrink-0 truncate font-mono {label_color}",
+                                        title: "{bucket.label}",
+                                        "{bucket.label}"
+                                    }
+                                    div {
+                                        class: "flex-1 h-4 rounded {bar_bg}",
+                                        div {
+                                            class: "h-4 rounded bg-blue-500",
+                                            style: "width: {bucket.count as f64 * 100.0 / max_count as f64}%",
+                                        }
+                                    }
+                                    span {
+                                        class: "w-20 shrink-0 text-right tabular-nums {label_color}",
+                                        "{bucket.count}"
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(error)) => rsx! {
+                        div { class: "text-xs text-red-500 mt-4", "{error}" }
+                    },
+                    None => rsx! {},
+                }
+
+                div {
+                    class: "flex justify-end mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *COLUMN_DISTRIBUTION.write() = None,
+                        "Close"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::db::{format_alter_column_sql, format_table_comment_sql, ColumnChange, DbRequest};
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn CommentDialog() -> Element {
+    let edit = EDITING_COMMENT.read().clone();
+    rsx! {
+        if let Some(edit) = edit {
+            CommentDialogContent { edit }
+        }
+    }
+}
+
+/// Open the comment editor for `table`, or for `column` of it.
+pub fn edit_comment(table: &str, column: Option<&str>) {
+    let schema = SCHEMA.read();
+    let Some(info) = schema.tables.iter().find(|t| t.name == table) else {
+        return;
+    };
+    let column = column.and_then(|name| info.columns.iter().find(|c| c.name == name));
+    let comment = match column {
+        Some(column) => column.comment.clone(),
+        None => info.comment.clone(),
+    };
+    let edit = CommentEdit {
+        table: table.to_string(),
+        column: column.cloned(),
+        comment: comment.unwrap_or_default(),
+    };
+    *EDITING_COMMENT.write() = Some(edit);
+}
+
+fn current_db_type() -> DatabaseType {
+    match *CONNECTION.read() {
+        ConnectionState::Connected { db_type, .. } => db_type,
+        _ => DatabaseType::PostgreSQL,
+    }
+}
+
+/// Edits a table or column comment and shows the COMMENT ON or ALTER TABLE statement
+/// that saves it. Saving reloads the schema so tooltips pick up the new text.
+#[component]
+fn CommentDialogContent(edit: CommentEdit) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let initial = edit.comment.clone();
+    let mut text = use_signal(move || initial);
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    let comment = text.read().trim().to_string();
+    let comment = (!comment.is_empty()).then_some(comment);
+    let db_type = current_db_type();
+    let sql = match &edit.column {
+        Some(column) => format_alter_column_sql(
+            db_type,
+            &edit.table,
+            column,
+            &ColumnChange::SetComment(comment),
+        ),
+        None => format_table_comment_sql(db_type, &edit.table, comment.as_deref()),
+    };
+    let target = match &edit.column {
+        Some(column) => format!("{}.{}", edit.table, column.name),
+        None => edit.table.clone(),
+    };
+    let title_target = target.clone();
+    // MySQL sets a column comment by redefining the whole column, so that statement
+    // opens in the editor for review instead
+    let needs_review = edit.column.is_some() && db_type == DatabaseType::MySQL;
+    let preview_sql = sql
+        .clone()
+        .unwrap_or_else(|| "-- This database has no comments".to_string());
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *EDITING_COMMENT.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[560px] max-w-[95vw] p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-4 {text_color}",
+                    "Comment on {title_target}"
+                }
+
+                textarea {
+                    class: "w-full h-24 px-3 py-2 border rounded text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                    placeholder: "No comment",
+                    value: "{text}",
+                    autofocus: true,
+                    oninput: move |e| text.set(e.value()),
+                }
+
+                pre {
+                    class: "mt-4 {preview_bg} border {dialog_border} rounded p-3 font-mono text-xs {label_color} whitespace-pre-wrap break-all",
+                    "{preview_sql}"
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *EDITING_COMMENT.write() = None,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                        disabled: sql.is_none(),
+                        onclick: move |_| {
+                            let Some(sql) = sql.clone() else {
+                                return;
+                            };
+                            if needs_review {
+                                let mut tabs = EDITOR_TABS.write();
+                                let id = tabs.add_tab(format!("Comment on {}", target));
+                                if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == id) {
+                                    tab.content = sql;
+                                }
+                            } else {
+                                // The worker runs these in order, so the schema is read after the change
+                                send_db_request(DbRequest::ExecuteMutation(sql));
+                                send_db_request(DbRequest::FetchSchema);
+                            }
+                            *EDITING_COMMENT.write() = None;
+                        },
+                        if needs_review { "Open in editor" } else { "Save" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,12 @@
+use crate::components::anonymized_copy::open_anonymized_copy;
+use crate::components::change_capture::capture_table_changes;
+use crate::components::column_distribution::open_column_distribution;
+use crate::components::seed_script::open_seed_script;
+use crate::config::{SoftDeleteStore, DEFAULT_SOFT_DELETE_COLUMN};
+use crate::db::{
+    format_notify_trigger_sql, format_select_all_sql, format_utf8mb4_conversion_sql,
+    notify_channel_name, DbRequest,
+};
 use crate::services::LlmSender;
 use crate::state::*;
 use dioxus::prelude::*;
@@ -57,10 +66,52 @@ pub fn ContextMenu() -> Element {
         "text-gray-400"
     };
 
+    // Offer the utf8mb4 conversion for MySQL tables still on another charset
+    let conversion_table = schema
+        .tables
+        .iter()
+        .find(|t| t.name == table_name)
+        .filter(|t| {
+            *CURRENT_DB_TYPE.read() == Some(DatabaseType::MySQL)
+                && (t
+                    .collation
+                    .as_deref()
+                    .is_some_and(|c| !c.starts_with("utf8mb4"))
+                    || t.columns
+                        .iter()
+                        .any(|c| c.charset().is_some_and(|cs| cs != "utf8mb4")))
+        })
+        .cloned();
+
+    // Soft delete marking: offered on saved connections when the table has the conventional
+    // column or is already marked
+    let marking_key = soft_delete_key(&table_name);
+    let soft_delete = soft_delete_column(&table_name);
+    let is_soft_delete = soft_delete.is_some();
+    let can_soft_delete = is_soft_delete
+        || marking_key.is_some()
+            && schema
+                .tables
+                .iter()
+                .find(|t| t.name == table_name)
+                .is_some_and(|t| {
+                    t.columns
+                        .iter()
+                        .any(|c| c.name == DEFAULT_SOFT_DELETE_COLUMN)
+                });
+
+    let can_notify = is_connected && *CURRENT_DB_TYPE.read() == Some(DatabaseType::PostgreSQL);
+
     // Clone for closures
+    let table_name_for_notify = table_name.clone();
+    let table_name_for_capture = table_name.clone();
     let table_name_for_select = table_name.clone();
+    let table_name_for_seed = table_name.clone();
+    let table_name_for_copy = table_name.clone();
+    let table_name_for_distribution = table_name.clone();
     let table_name_for_explain = table_name.clone();
     let table_name_for_suggest = table_name.clone();
+    let table_name_for_describe = table_name.clone();
     let llm_tx_explain = llm_tx.clone();
     let llm_tx_suggest = llm_tx.clone();
 
@@ -85,7 +136,13 @@ pub fn ContextMenu() -> Element {
                 button {
                     class: "w-full text-left px-3 py-2 text-sm {text_class} {hover_class} transition-colors flex items-center space-x-2",
                     onclick: move |_| {
-                        let sql = format!("SELECT * FROM \"{}\" LIMIT 100;", table_name_for_select);
+                        let db_type = CURRENT_DB_TYPE.read().unwrap_or(DatabaseType::PostgreSQL);
+                        let sql = format_select_all_sql(
+                            db_type,
+                            &table_name_for_select,
+                            100,
+                            soft_delete_column(&table_name_for_select).as_deref(),
+                        );
                         if let Some(tab) = EDITOR_TABS.write().active_tab_mut() {
                             tab.content = sql;
                             tab.unsaved_changes = true;
@@ -108,6 +165,200 @@ pub fn ContextMenu() -> Element {
                     span { "SELECT * FROM {table_name}" }
                 }
 
+                if is_connected {
+                    button {
+                        class: "w-full text-left px-3 py-2 text-sm {text_class} {hover_class} transition-colors flex items-center space-x-2",
+                        onclick: move |_| {
+                            open_seed_script(table_name_for_seed.clone(), None);
+                            hide_context_menu();
+                        },
+
+                        svg {
+                            class: "w-4 h-4 opacity-70",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M9 12h6m-6 4h6m2 5H7a2 2 0 01-2-2V5a2 2 0 012-2h5.586a1 1 0 01.707.293l5.414 5.414a1 1 0 01.293.707V19a2 2 0 01-2 2z",
+                            }
+                        }
+                        span { "Generate seed script..." }
+                    }
+
+                    button {
+                        class: "w-full text-left px-3 py-2 text-sm {text_class} {hover_class} transition-colors flex items-center space-x-2",
+                        onclick: move |_| {
+                            open_anonymized_copy(table_name_for_copy.clone());
+                            hide_context_menu();
+                        },
+
+                        svg {
+                            class: "w-4 h-4 opacity-70",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M8 7v8a2 2 0 002 2h6M8 7V5a2 2 0 012-2h4.586a1 1 0 01.707.293l4.414 4.414a1 1 0 01.293.707V15a2 2 0 01-2 2h-2M8 7H6a2 2 0 00-2 2v10a2 2 0 002 2h8a2 2 0 002-2v-2",
+                            }
+                        }
+                        span { "Copy to connection..." }
+                    }
+
+                    button {
+                        class: "w-full text-left px-3 py-2 text-sm {text_class} {hover_class} transition-colors flex items-center space-x-2",
+                        onclick: move |_| {
+                            open_column_distribution(table_name_for_distribution.clone());
+                            hide_context_menu();
+                        },
+
+                        svg {
+                            class: "w-4 h-4 opacity-70",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M9 19v-6a2 2 0 00-2-2H5a2 2 0 00-2 2v6a2 2 0 002 2h2a2 2 0 002-2zm0 0V9a2 2 0 012-2h2a2 2 0 012 2v10m-6 0a2 2 0 002 2h2a2 2 0 002-2m0 0V5a2 2 0 012-2h2a2 2 0 012 2v14a2 2 0 01-2 2h-2a2 2 0 01-2-2z",
+                            }
+                        }
+                        span { "Column distribution..." }
+                    }
+                }
+
+                if let Some(table) = conversion_table {
+                    button {
+                        class: "w-full text-left px-3 py-2 text-sm {text_class} {hover_class} transition-colors flex items-center space-x-2",
+                        onclick: move |_| {
+                            let sql = format_utf8mb4_conversion_sql(&table, "utf8mb4_unicode_ci");
+                            {
+                                let mut tabs = EDITOR_TABS.write();
+                                let id = tabs.add_tab(format!("{} → utf8mb4", table.name));
+                                if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == id) {
+                                    tab.content = sql;
+                                }
+                            }
+                            hide_context_menu();
+                        },
+
+                        svg {
+                            class: "w-4 h-4 opacity-70",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M4 4v5h.582m15.356 2A8.001 8.001 0 004.582 9m0 0H9m11 11v-5h-.581m0 0a8.003 8.003 0 01-15.357-2m15.357 2H15",
+                            }
+                        }
+                        span { "Convert to utf8mb4..." }
+                    }
+                }
+
+                if can_notify {
+                    button {
+                        class: "w-full text-left px-3 py-2 text-sm {text_class} {hover_class} transition-colors flex items-center space-x-2",
+                        onclick: move |_| {
+                            let channel = notify_channel_name(&table_name_for_notify);
+                            let sql = format_notify_trigger_sql(&table_name_for_notify, &channel);
+                            {
+                                let mut tabs = EDITOR_TABS.write();
+                                let id = tabs.add_tab(format!("{} notifications", table_name_for_notify));
+                                if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == id) {
+                                    tab.content = sql;
+                                }
+                            }
+                            send_db_request(DbRequest::Listen(channel));
+                            *SHOW_NOTIFICATIONS_PANEL.write() = true;
+                            hide_context_menu();
+                        },
+
+                        svg {
+                            class: "w-4 h-4 opacity-70",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M15 17h5l-1.405-1.405A2.032 2.032 0 0118 14.158V11a6.002 6.002 0 00-4-5.659V5a2 2 0 10-4 0v.341C7.67 6.165 6 8.388 6 11v3.159c0 .538-.214 1.055-.595 1.436L4 17h5m6 0v1a3 3 0 11-6 0v-1m6 0H9",
+                            }
+                        }
+                        span { "Notify on changes..." }
+                    }
+                    button {
+                        class: "w-full text-left px-3 py-2 text-sm {text_class} {hover_class} transition-colors flex items-center space-x-2",
+                        onclick: move |_| {
+                            capture_table_changes(&table_name_for_capture);
+                            hide_context_menu();
+                        },
+
+                        svg {
+                            class: "w-4 h-4 opacity-70",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M4 4v5h.582m15.356 2A8.001 8.001 0 004.582 9m0 0H9m11 11v-5h-.581m0 0a8.003 8.003 0 01-15.357-2m15.357 2H15",
+                            }
+                        }
+                        span { "Capture changes..." }
+                    }
+                }
+
+                if can_soft_delete {
+                    button {
+                        class: "w-full text-left px-3 py-2 text-sm {text_class} {hover_class} transition-colors flex items-center space-x-2",
+                        onclick: move |_| {
+                            let Some((connection, schema, table)) = marking_key.clone() else {
+                                return;
+                            };
+                            let store = SoftDeleteStore::new();
+                            let result = if is_soft_delete {
+                                store.remove(&connection, &schema, &table)
+                            } else {
+                                store.set(&connection, &schema, &table, DEFAULT_SOFT_DELETE_COLUMN)
+                            };
+                            if let Err(e) = result {
+                                tracing::error!("Failed to save soft delete settings: {}", e);
+                            }
+                            *SOFT_DELETE_TABLES.write() = store.load();
+                            hide_context_menu();
+                        },
+
+                        svg {
+                            class: "w-4 h-4 opacity-70",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M19 7l-.867 12.142A2 2 0 0116.138 21H7.862a2 2 0 01-1.995-1.858L5 7m5 4v6m4-6v6m1-10V4a1 1 0 00-1-1h-4a1 1 0 00-1 1v3M4 7h16",
+                            }
+                        }
+                        if let Some(column) = soft_delete {
+                            span { "Stop using soft deletes ({column})" }
+                        } else {
+                            span { "Use soft deletes ({DEFAULT_SOFT_DELETE_COLUMN})" }
+                        }
+                    }
+                }
+
                 // AI Actions section
                 if is_connected {
                     div {
@@ -133,10 +384,11 @@ pub fn ContextMenu() -> Element {
                                 suggested_sql: None,
                             };
                             let config = LLM_CONFIG.read().clone();
-                            let _ = llm_tx_explain.send(crate::llm::LlmRequest::Explain {
-                                sql,
-                                config,
-                            });
+                            send_llm_request(
+                                &llm_tx_explain,
+                                crate::llm::LlmRequest::Explain { sql, config },
+                                LlmOrigin::AiPanel,
+                            );
                             hide_context_menu();
                         },
 
@@ -166,10 +418,15 @@ pub fn ContextMenu() -> Element {
                                     table_name: Some(table_name_for_suggest.clone()),
                                 };
                                 let config = LLM_CONFIG.read().clone();
-                                let _ = llm_tx_suggest.send(crate::llm::LlmRequest::SuggestQueries {
-                                    table: table.clone(),
-                                    config,
-                                });
+                                send_llm_request(
+                                    &llm_tx_suggest,
+                                    crate::llm::LlmRequest::SuggestQueries {
+                                        table: table.clone(),
+                                        dialect: *CURRENT_DIALECT.read(),
+                                        config,
+                                    },
+                                    LlmOrigin::Suggestions(table_name_for_suggest.clone()),
+                                );
                             }
                             hide_context_menu();
                         },
@@ -188,6 +445,29 @@ pub fn ContextMenu() -> Element {
                         }
                         span { "Suggest Queries" }
                     }
+
+                    // Describe table action
+                    button {
+                        class: "w-full text-left px-3 py-2 text-sm {text_class} {hover_class} transition-colors flex items-center space-x-2",
+                        onclick: move |_| {
+                            describe_table(&table_name_for_describe);
+                            hide_context_menu();
+                        },
+
+                        svg {
+                            class: "w-4 h-4 opacity-70",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M4 6h16M4 12h16M4 18h7",
+                            }
+                        }
+                        span { "Describe Table" }
+                    }
                 }
             }
         }
@@ -0,0 +1,182 @@
+use super::connect_saved;
+use crate::config::{ConnectionStore, SavedConnection};
+use crate::db::OdbcMode;
+use crate::instance::DeepLink;
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn DeepLinkDialog() -> Element {
+    let link = PENDING_DEEP_LINK.read().clone();
+    rsx! {
+        if let Some(link) = link {
+            DeepLinkDialogContent { link }
+        }
+    }
+}
+
+/// Asks before acting on an `fbench://` link: it may switch the connection and load SQL
+/// written by someone else, so nothing happens until the user confirms.
+#[component]
+fn DeepLinkDialogContent(link: DeepLink) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    let saved: Option<SavedConnection> = link.connection.as_ref().and_then(|name| {
+        ConnectionStore::new()
+            .load_connections()
+            .into_iter()
+            .find(|c| &c.name == name)
+    });
+    let missing_connection = link.connection.is_some() && saved.is_none();
+    let target = saved.as_ref().map(|conn| {
+        if conn.db_type == DatabaseType::Odbc && conn.odbc_mode == OdbcMode::DriverString {
+            // The connection string may hold a password
+            format!("{:?} · connection string", conn.db_type)
+        } else if !conn.db_type.has_server() {
+            format!("{:?} · {}", conn.db_type, conn.database)
+        } else {
+            format!(
+                "{:?} · {}@{}:{}/{}",
+                conn.db_type, conn.user, conn.host, conn.port, conn.database
+            )
+        }
+    });
+    let current_db = match &*CONNECTION.read() {
+        ConnectionState::Connected { db_name, .. } if saved.is_some() => Some(db_name.clone()),
+        _ => None,
+    };
+    let confirm_label = match (&saved, &link.query) {
+        (Some(_), Some(_)) => "Connect and open query",
+        (Some(_), None) => "Connect",
+        (None, _) => "Open query",
+    };
+
+    let confirm = {
+        let link = link.clone();
+        let saved = saved.clone();
+        move |_| {
+            if let Some(conn) = &saved {
+                connect_saved(conn);
+            }
+            if let Some(query) = &link.query {
+                let mut tabs = EDITOR_TABS.write();
+                let id = tabs.add_tab("Linked query".to_string());
+                if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == id) {
+                    tab.content = query.clone();
+                }
+            }
+            *PENDING_DEEP_LINK.write() = None;
+        }
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *PENDING_DEEP_LINK.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[640px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-2 {text_color}",
+                    "Open link"
+                }
+                p {
+                    class: "text-sm {label_color} mb-4",
+                    "A link asks FBench to do the following. Only continue if you trust where it came from."
+                }
+
+                if let Some(name) = &link.connection {
+                    div {
+                        class: "mb-4",
+                        div {
+                            class: "text-xs uppercase {muted_text} mb-1",
+                            "Connect to"
+                        }
+                        div {
+                            class: "text-sm font-medium {text_color}",
+                            "{name}"
+                        }
+                        if let Some(target) = &target {
+                            div {
+                                class: "text-xs font-mono {label_color}",
+                                "{target}"
+                            }
+                        }
+                        if missing_connection {
+                            div {
+                                class: "text-xs text-red-500 mt-1",
+                                "There is no saved connection with this name."
+                            }
+                        }
+                        if let Some(current_db) = &current_db {
+                            div {
+                                class: "text-xs text-yellow-500 mt-1",
+                                "This replaces the current connection to {current_db}."
+                            }
+                        }
+                    }
+                }
+
+                if let Some(query) = &link.query {
+                    div {
+                        class: "text-xs uppercase {muted_text} mb-1",
+                        "Load query into a new tab"
+                    }
+                    pre {
+                        class: "{preview_bg} border {dialog_border} rounded p-3 font-mono text-xs {label_color} whitespace-pre-wrap break-all overflow-auto",
+                        "{query}"
+                    }
+                    div {
+                        class: "text-xs {muted_text} mt-1",
+                        "The query is not run until you run it."
+                    }
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *PENDING_DEEP_LINK.write() = None,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                        disabled: missing_connection && link.query.is_none(),
+                        onclick: confirm,
+                        "{confirm_label}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use crate::config::Environment;
+use crate::db::{normalize_table_name, DestructiveStatement};
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn DestructiveConfirmDialog() -> Element {
+    let pending = PENDING_DESTRUCTIVE_REQUEST
+        .read()
+        .as_ref()
+        .map(|(statements, _)| statements.clone());
+    rsx! {
+        if let Some(statements) = pending {
+            DestructiveConfirmDialogContent { statements }
+        }
+    }
+}
+
+/// Estimated rows of the table `name` from the loaded schema, when known.
+fn row_estimate(name: &str) -> Option<i64> {
+    let table = normalize_table_name(name);
+    SCHEMA
+        .read()
+        .tables
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(&table))
+        .map(|t| t.row_estimate)
+        .filter(|&rows| rows > 0)
+}
+
+/// Lists what each destructive statement drops, empties or changes, with the
+/// estimated row counts of affected tables, before any of the script runs.
+#[component]
+fn DestructiveConfirmDialogContent(statements: Vec<DestructiveStatement>) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let on_production = *CURRENT_ENVIRONMENT.read() == Some(Environment::Production);
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    let db_name = match &*CONNECTION.read() {
+        ConnectionState::Connected { db_name, .. } => db_name.clone(),
+        _ => String::new(),
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *PENDING_DESTRUCTIVE_REQUEST.write() = None,
+
+            div {
+                class: "{dialog_bg} border-2 border-red-600 rounded-lg shadow-2xl w-[640px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-2 {text_color}",
+                    if statements.len() == 1 {
+                        "Run a destructive statement?"
+                    } else {
+                        "Run {statements.len()} destructive statements?"
+                    }
+                }
+                p {
+                    class: "text-sm {label_color} mb-4",
+                    "Nothing has run yet. Check what each statement affects."
+                    if on_production {
+                        span {
+                            class: "text-red-500",
+                            if db_name.is_empty() {
+                                " This connection is tagged as production."
+                            } else {
+                                " {db_name} is tagged as production."
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex-1 overflow-auto space-y-3",
+                    for (i, statement) in statements.iter().enumerate() {
+                        div {
+                            key: "{i}",
+                            div {
+                                class: "flex items-baseline flex-wrap gap-x-3 text-sm mb-1",
+                                span { class: "font-semibold text-red-500", "{statement.kind.label()}" }
+                                for object in statement.objects.iter() {
+                                    span {
+                                        key: "{object}",
+                                        span { class: "font-mono {text_color}", "{object}" }
+                                        if statement.on_tables {
+                                            if let Some(rows) = row_estimate(object) {
+                                                span { class: "text-xs {muted_text} ml-1", "~{rows} rows" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            pre {
+                                class: "{preview_bg} border {dialog_border} rounded p-2 font-mono text-xs {label_color} whitespace-pre-wrap break-all max-h-32 overflow-auto",
+                                "{statement.statement.trim()}"
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *PENDING_DESTRUCTIVE_REQUEST.write() = None,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-red-600 hover:bg-red-500 text-white",
+                        onclick: move |_| {
+                            if let Some((_, request)) = PENDING_DESTRUCTIVE_REQUEST.write().take() {
+                                send_db_request(request);
+                            }
+                        },
+                        if on_production { "Run on production" } else { "Run" }
+                    }
+                }
+            }
+        }
+    }
+}
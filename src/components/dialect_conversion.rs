@@ -0,0 +1,183 @@
+use crate::components::json_viewer::{word_diff, DiffOp};
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn DialectConversionDialog() -> Element {
+    let conversion = DIALECT_CONVERSION.read().clone();
+    rsx! {
+        if let Some(conversion) = conversion {
+            DialectConversionDialogContent { conversion }
+        }
+    }
+}
+
+/// The converted statement as a diff against the original, from the model when it
+/// answered and from the rewrite rules otherwise. Nothing changes until Replace.
+#[component]
+fn DialectConversionDialogContent(conversion: DialectConversion) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut use_rules = use_signal(|| false);
+    let mut apply_error = use_signal(|| None::<String>);
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    let ai_sql = match &conversion.ai {
+        Some(Ok(sql)) => Some(sql.clone()),
+        _ => None,
+    };
+    let showing_rules = *use_rules.read() || ai_sql.is_none();
+    let converted = match (&ai_sql, showing_rules) {
+        (Some(sql), false) => sql.clone(),
+        _ => conversion.rule_based.sql.clone(),
+    };
+    let diff = word_diff(&conversion.original, &converted);
+    let unchanged = converted == conversion.original;
+    let ai_status = match &conversion.ai {
+        None => Some("Waiting for the AI conversion...".to_string()),
+        Some(Err(e)) => Some(format!(
+            "AI conversion unavailable ({}); showing the rule-based one",
+            e
+        )),
+        Some(Ok(_)) => None,
+    };
+    let has_ai = ai_sql.is_some();
+    let target = conversion.target.label();
+
+    let replace = {
+        let conversion = conversion.clone();
+        let converted = converted.clone();
+        move |_| {
+            let mut tabs = EDITOR_TABS.write();
+            let Some(tab) = tabs.query_tab_mut(conversion.tab_id.as_deref()) else {
+                apply_error.set(Some("The tab was closed".to_string()));
+                return;
+            };
+            let chars: Vec<char> = tab.content.chars().collect();
+            let current: Option<String> = chars
+                .get(conversion.range.clone())
+                .map(|c| c.iter().collect());
+            if current.as_deref() != Some(conversion.original.as_str()) {
+                apply_error.set(Some(
+                    "The statement was edited since; convert it again".to_string(),
+                ));
+                return;
+            }
+            let mut content: String = chars[..conversion.range.start].iter().collect();
+            content.push_str(&converted);
+            content.extend(&chars[conversion.range.end..]);
+            tab.content = content;
+            tab.unsaved_changes = true;
+            drop(tabs);
+            *DIALECT_CONVERSION.write() = None;
+        }
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *DIALECT_CONVERSION.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[720px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-2 {text_color}",
+                    "Convert to {target}"
+                }
+
+                div {
+                    class: "flex items-center space-x-3 text-xs mb-3",
+                    span { class: "px-1 bg-red-500/30 line-through", "original" }
+                    span { class: "px-1 bg-green-500/30", "converted" }
+                    div { class: "flex-1" }
+                    if has_ai {
+                        button {
+                            class: "{label_color} hover:opacity-80",
+                            onclick: move |_| {
+                                let rules = !*use_rules.read();
+                                use_rules.set(rules);
+                            },
+                            if showing_rules { "Show AI conversion" } else { "Show rule-based conversion" }
+                        }
+                    }
+                }
+
+                pre {
+                    class: "{preview_bg} border {dialog_border} rounded p-3 font-mono text-xs {label_color} whitespace-pre-wrap break-all overflow-auto flex-1",
+                    for (op, text) in diff {
+                        match op {
+                            DiffOp::Same => rsx! { span { "{text}" } },
+                            DiffOp::Removed => rsx! {
+                                span { class: "bg-red-500/30 line-through", "{text}" }
+                            },
+                            DiffOp::Added => rsx! {
+                                span { class: "bg-green-500/30", "{text}" }
+                            },
+                        }
+                    }
+                }
+
+                if let Some(status) = ai_status {
+                    div { class: "text-xs {muted_text} mt-2", "{status}" }
+                }
+                if unchanged {
+                    div { class: "text-xs {muted_text} mt-2", "Nothing needed changing." }
+                }
+                if showing_rules && !conversion.rule_based.notes.is_empty() {
+                    ul {
+                        class: "text-xs text-yellow-500 mt-2 list-disc pl-4 space-y-0.5",
+                        for note in conversion.rule_based.notes.iter() {
+                            li { "{note}" }
+                        }
+                    }
+                }
+                if let Some(error) = apply_error.read().as_ref() {
+                    div { class: "text-xs text-red-500 mt-2", "{error}" }
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *DIALECT_CONVERSION.write() = None,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                        disabled: unchanged,
+                        onclick: replace,
+                        "Replace statement"
+                    }
+                }
+            }
+        }
+    }
+}
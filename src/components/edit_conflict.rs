@@ -0,0 +1,136 @@
+use crate::components::save_edits;
+use crate::db::DbRequest;
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn EditConflictDialog() -> Element {
+    let conflict = EDIT_CONFLICT.read().clone();
+    rsx! {
+        if let Some(conflict) = conflict {
+            EditConflictDialogContent { conflict }
+        }
+    }
+}
+
+/// Primary key values of `row` in the tab's result, as `key = value` pairs.
+fn row_key(tab_id: Option<&str>, row: usize) -> Option<String> {
+    let tabs = EDITOR_TABS.read();
+    let tab = match tab_id {
+        Some(id) => tabs.tabs.iter().find(|t| t.id == id),
+        None => tabs.active_tab(),
+    };
+    let result = tab?.result.as_ref()?;
+    let keys: Vec<String> = result
+        .row_key(row)?
+        .iter()
+        .map(|(pk, value)| format!("{} = {}", pk, value))
+        .collect();
+    Some(keys.join(", "))
+}
+
+/// Shown when a grid save found a row changed or deleted by someone else since it was
+/// loaded. Nothing was saved; the edits can be kept, dropped for fresh rows, or forced.
+#[component]
+fn EditConflictDialogContent(conflict: EditConflict) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let key = conflict
+        .row
+        .and_then(|row| row_key(conflict.tab_id.as_deref(), row));
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+
+    let reload = {
+        let tab_id = conflict.tab_id.clone();
+        move |_| {
+            *EDIT_CONFLICT.write() = None;
+            let sql = {
+                let mut tabs = EDITOR_TABS.write();
+                let Some(tab) = tabs.query_tab_mut(tab_id.as_deref()) else {
+                    return;
+                };
+                tab.pending_edits.clear();
+                tab.edit_mode = false;
+                tab.result.as_ref().map(|r| r.sql.clone())
+            };
+            if let Some(sql) = sql {
+                send_request_for_tab(DbRequest::Execute(sql), tab_id.clone());
+            }
+        }
+    };
+    let overwrite = {
+        let tab_id = conflict.tab_id.clone();
+        move |_| {
+            *EDIT_CONFLICT.write() = None;
+            // The edits are saved from the active tab
+            if let Some(id) = tab_id.as_deref() {
+                EDITOR_TABS.write().set_active(id);
+            }
+            save_edits(false);
+        }
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *EDIT_CONFLICT.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[480px] max-w-[95vw] p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-2 {text_color}",
+                    "Row changed since it was loaded"
+                }
+                p {
+                    class: "text-sm {label_color}",
+                    match key {
+                        Some(key) => rsx! { "The row with {key} was changed or deleted by someone else after it was loaded." },
+                        None => rsx! { "An edited row was changed or deleted by someone else after it was loaded." },
+                    }
+                    " None of the edits were saved; they are still pending."
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *EDIT_CONFLICT.write() = None,
+                        "Keep editing"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded border {dialog_border} {label_color} hover:opacity-80",
+                        onclick: reload,
+                        "Discard and reload"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-red-600 hover:bg-red-500 text-white",
+                        onclick: overwrite,
+                        "Overwrite"
+                    }
+                }
+            }
+        }
+    }
+}
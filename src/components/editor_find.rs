@@ -0,0 +1,437 @@
+use crate::components::sql_editor::select_editor_range;
+use crate::state::*;
+use dioxus::prelude::*;
+use regex::{Captures, Regex, RegexBuilder};
+use std::ops::Range;
+
+/// Show the find bar and put the caret in its search field.
+pub fn open_editor_find() {
+    *SHOW_EDITOR_FIND.write() = true;
+    spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let _ = document::eval(
+            r#"
+            const input = document.getElementById('editor-find-input');
+            if (input) {
+                input.focus();
+                input.select();
+            }
+        "#,
+        )
+        .await;
+    });
+}
+
+/// The search as a regex: as typed, or the literal text escaped.
+fn find_regex(query: &str, is_regex: bool, match_case: bool) -> Result<Regex, String> {
+    let pattern = if is_regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!match_case)
+        .multi_line(true)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+fn byte_offset(text: &str, char_offset: usize) -> usize {
+    text.char_indices()
+        .nth(char_offset)
+        .map_or(text.len(), |(b, _)| b)
+}
+
+/// Byte range searched: the char range `scope`, or all of `text`.
+fn scope_bytes(text: &str, scope: Option<(usize, usize)>) -> Range<usize> {
+    match scope {
+        Some((start, end)) => byte_offset(text, start)..byte_offset(text, end),
+        None => 0..text.len(),
+    }
+}
+
+/// Char ranges of the non-empty matches within `scope`.
+fn find_matches(text: &str, regex: &Regex, scope: Option<(usize, usize)>) -> Vec<Range<usize>> {
+    let searched = scope_bytes(text, scope);
+    let mut chars = text[..searched.start].chars().count();
+    let mut counted_to = searched.start;
+    regex
+        .find_iter(&text[searched.clone()])
+        .filter(|m| !m.is_empty())
+        .map(|m| {
+            let start = searched.start + m.start();
+            chars += text[counted_to..start].chars().count();
+            let len = m.as_str().chars().count();
+            counted_to = start + m.len();
+            let range = chars..chars + len;
+            chars += len;
+            range
+        })
+        .collect()
+}
+
+/// What a match is replaced with. Regex replacements may refer to groups as `$1`
+/// or `${name}`.
+fn expansion(caps: &Captures, replacement: &str, is_regex: bool) -> String {
+    if is_regex {
+        let mut out = String::new();
+        caps.expand(replacement, &mut out);
+        out
+    } else {
+        replacement.to_string()
+    }
+}
+
+/// `text` with every match within `scope` replaced, and how many were.
+fn replace_all(
+    text: &str,
+    regex: &Regex,
+    replacement: &str,
+    is_regex: bool,
+    scope: Option<(usize, usize)>,
+) -> (String, usize) {
+    let searched = scope_bytes(text, scope);
+    let mut count = 0;
+    let replaced = regex.replace_all(&text[searched.clone()], |caps: &Captures| {
+        if caps[0].is_empty() {
+            return String::new();
+        }
+        count += 1;
+        expansion(caps, replacement, is_regex)
+    });
+    (
+        format!(
+            "{}{}{}",
+            &text[..searched.start],
+            replaced,
+            &text[searched.end..]
+        ),
+        count,
+    )
+}
+
+/// `text` with only the match at char range `at` replaced.
+fn replace_one(
+    text: &str,
+    regex: &Regex,
+    replacement: &str,
+    is_regex: bool,
+    scope: Option<(usize, usize)>,
+    at: &Range<usize>,
+) -> Option<String> {
+    let searched = scope_bytes(text, scope);
+    let from = byte_offset(text, at.start).checked_sub(searched.start)?;
+    // Searching the whole scope keeps anchors and word boundaries in context
+    let caps = regex.captures_at(&text[searched.clone()], from)?;
+    let matched = caps.get(0).filter(|m| m.start() == from && !m.is_empty())?;
+    Some(format!(
+        "{}{}{}",
+        &text[..searched.start + matched.start()],
+        expansion(&caps, replacement, is_regex),
+        &text[searched.start + matched.end()..]
+    ))
+}
+
+/// Find bar over the editor: plain text or regex, optionally case-sensitive and limited
+/// to the selection it was opened with, replacing one match at a time or all at once.
+#[component]
+pub fn EditorFindBar() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut query = use_signal(String::new);
+    let mut replacement = use_signal(String::new);
+    let mut is_regex = use_signal(|| false);
+    let mut match_case = use_signal(|| false);
+    // Char range of the selection searched, fixed when "In selection" is turned on
+    let mut scope = use_signal(|| None::<(usize, usize)>);
+    let mut current = use_signal(|| 0usize);
+
+    let content = EDITOR_TABS
+        .read()
+        .active_tab()
+        .map(|t| t.content.clone())
+        .unwrap_or_default();
+    let text = query.read().clone();
+    let (regex, error) = if text.is_empty() {
+        (None, None)
+    } else {
+        match find_regex(&text, *is_regex.read(), *match_case.read()) {
+            Ok(regex) => (Some(regex), None),
+            Err(e) => (None, Some(e)),
+        }
+    };
+    let matches = regex
+        .as_ref()
+        .map(|r| find_matches(&content, r, *scope.read()))
+        .unwrap_or_default();
+    let index = if matches.is_empty() {
+        0
+    } else {
+        *current.read() % matches.len()
+    };
+
+    let bar_bg = if is_dark { "bg-black" } else { "bg-gray-50" };
+    let bar_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-200"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let button_class = if is_dark {
+        "border-gray-800 text-gray-300 hover:bg-gray-900"
+    } else {
+        "border-gray-300 text-gray-700 hover:bg-gray-100"
+    };
+
+    let status = if let Some(error) = error.as_ref() {
+        error.lines().last().unwrap_or_default().to_string()
+    } else if text.is_empty() {
+        String::new()
+    } else if matches.is_empty() {
+        "No matches".to_string()
+    } else {
+        format!("{} of {}", index + 1, matches.len())
+    };
+    let status_color = if error.is_some() {
+        "text-red-500"
+    } else {
+        muted_text
+    };
+
+    let go_to = {
+        let matches = matches.clone();
+        move |step: isize| {
+            if matches.is_empty() {
+                return;
+            }
+            let len = matches.len() as isize;
+            let next = (index as isize + step).rem_euclid(len) as usize;
+            current.set(next);
+            let range = matches[next].clone();
+            select_editor_range(range.start, range.end);
+        }
+    };
+    let mut go_next = go_to.clone();
+    let mut go_previous = go_to.clone();
+    let mut go_keyboard = go_to;
+
+    // Replacing shifts the end of the searched selection by the change in length
+    let mut apply = {
+        let content = content.clone();
+        move |new_content: String| {
+            let old_len = content.chars().count();
+            let new_len = new_content.chars().count();
+            if let Some(tab) = EDITOR_TABS.write().active_tab_mut() {
+                tab.content = new_content;
+                tab.unsaved_changes = true;
+            }
+            let shifted = scope
+                .read()
+                .map(|(start, end)| (start, (end + new_len).saturating_sub(old_len).max(start)));
+            scope.set(shifted);
+        }
+    };
+    let mut apply_all = apply.clone();
+
+    let replace_current = {
+        let regex = regex.clone();
+        let content = content.clone();
+        let at = matches.get(index).cloned();
+        move |_| {
+            let (Some(regex), Some(at)) = (regex.as_ref(), at.as_ref()) else {
+                return;
+            };
+            let replaced = replace_one(
+                &content,
+                regex,
+                &replacement.read(),
+                *is_regex.read(),
+                *scope.read(),
+                at,
+            );
+            // The next match now has this one's index
+            if let Some(replaced) = replaced {
+                apply(replaced);
+            }
+        }
+    };
+    let replace_every = {
+        let regex = regex.clone();
+        let content = content.clone();
+        move |_| {
+            let Some(regex) = regex.as_ref() else {
+                return;
+            };
+            let (replaced, count) = replace_all(
+                &content,
+                regex,
+                &replacement.read(),
+                *is_regex.read(),
+                *scope.read(),
+            );
+            if count > 0 {
+                apply_all(replaced);
+                current.set(0);
+            }
+        }
+    };
+
+    let toggle_scope = move |_| {
+        if scope.read().is_some() {
+            scope.set(None);
+            return;
+        }
+        spawn(async move {
+            let mut eval = document::eval(
+                r#"
+                const textarea = document.getElementById('sql-editor-input');
+                if (!textarea) {
+                    dioxus.send([0, 0]);
+                } else {
+                    const start = Array.from(textarea.value.slice(0, textarea.selectionStart)).length;
+                    const end = Array.from(textarea.value.slice(0, textarea.selectionEnd)).length;
+                    dioxus.send([start, end]);
+                }
+            "#,
+            );
+            let (start, end) = eval.recv::<(usize, usize)>().await.unwrap_or_default();
+            if end > start {
+                scope.set(Some((start, end)));
+                current.set(0);
+            }
+        });
+    };
+
+    let close = move || {
+        *SHOW_EDITOR_FIND.write() = false;
+        spawn(async move {
+            let _ = document::eval(
+                r#"
+                const textarea = document.getElementById('sql-editor-input');
+                if (textarea) textarea.focus();
+            "#,
+            )
+            .await;
+        });
+    };
+    let close_keyboard = close;
+
+    rsx! {
+        div {
+            class: "{bar_bg} border-b {bar_border} px-3 py-1.5 space-y-1.5 text-xs",
+
+            div {
+                class: "flex items-center space-x-2",
+                input {
+                    id: "editor-find-input",
+                    class: "w-72 px-2 py-1 border rounded font-mono focus:outline-none focus:border-blue-500 {input_class}",
+                    placeholder: "Find",
+                    value: "{query}",
+                    autofocus: true,
+                    oninput: move |e| {
+                        query.set(e.value());
+                        current.set(0);
+                    },
+                    onkeydown: move |e: KeyboardEvent| {
+                        match e.key() {
+                            Key::Enter if e.modifiers().contains(Modifiers::SHIFT) => go_keyboard(-1),
+                            Key::Enter => go_keyboard(1),
+                            Key::Escape => close_keyboard(),
+                            _ => return,
+                        }
+                        e.prevent_default();
+                    },
+                }
+                label {
+                    class: "flex items-center space-x-1 {label_color}",
+                    input {
+                        r#type: "checkbox",
+                        checked: *is_regex.read(),
+                        onchange: move |_| {
+                            let value = !*is_regex.read();
+                            is_regex.set(value);
+                        },
+                    }
+                    span { "Regex" }
+                }
+                label {
+                    class: "flex items-center space-x-1 {label_color}",
+                    input {
+                        r#type: "checkbox",
+                        checked: *match_case.read(),
+                        onchange: move |_| {
+                            let value = !*match_case.read();
+                            match_case.set(value);
+                        },
+                    }
+                    span { "Match case" }
+                }
+                label {
+                    class: "flex items-center space-x-1 {label_color}",
+                    title: "Search only the text selected in the editor",
+                    input {
+                        r#type: "checkbox",
+                        checked: scope.read().is_some(),
+                        onchange: toggle_scope,
+                    }
+                    span { "In selection" }
+                }
+                span { class: "min-w-20 tabular-nums {status_color}", "{status}" }
+                div { class: "flex-1" }
+                button {
+                    class: "px-2 py-0.5 border rounded {button_class}",
+                    title: "Previous match (Shift+Enter)",
+                    onclick: move |_| go_previous(-1),
+                    "↑"
+                }
+                button {
+                    class: "px-2 py-0.5 border rounded {button_class}",
+                    title: "Next match (Enter)",
+                    onclick: move |_| go_next(1),
+                    "↓"
+                }
+                button {
+                    class: "px-2 py-0.5 {muted_text} hover:opacity-80",
+                    title: "Close (Esc)",
+                    onclick: move |_| close(),
+                    "✕"
+                }
+            }
+
+            div {
+                class: "flex items-center space-x-2",
+                input {
+                    class: "w-72 px-2 py-1 border rounded font-mono focus:outline-none focus:border-blue-500 {input_class}",
+                    placeholder: if *is_regex.read() { "Replace ($1 for groups)" } else { "Replace" },
+                    value: "{replacement}",
+                    oninput: move |e| replacement.set(e.value()),
+                }
+                button {
+                    class: "px-2 py-0.5 border rounded {button_class} disabled:opacity-50",
+                    disabled: matches.is_empty(),
+                    onclick: replace_current,
+                    "Replace"
+                }
+                button {
+                    class: "px-2 py-0.5 border rounded {button_class} disabled:opacity-50",
+                    disabled: matches.is_empty(),
+                    onclick: replace_every,
+                    "Replace all"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::state::*;
+use dioxus::prelude::*;
+
+/// Thin bar in the current connection's environment color; nothing when it has no tag.
+#[component]
+pub fn EnvironmentStripe() -> Element {
+    let environment = *CURRENT_ENVIRONMENT.read();
+    let connected = matches!(*CONNECTION.read(), ConnectionState::Connected { .. });
+    rsx! {
+        if let Some(environment) = environment.filter(|_| connected) {
+            div {
+                class: "h-1 shrink-0 {environment.color_class()}",
+                title: "Connected to a {environment.label()} database",
+            }
+        }
+    }
+}
+
+#[component]
+pub fn ProductionConfirmDialog() -> Element {
+    let pending = PENDING_PRODUCTION_REQUEST
+        .read()
+        .as_ref()
+        .map(|(sql, _)| sql.clone());
+    rsx! {
+        if let Some(sql) = pending {
+            ProductionConfirmDialogContent { sql }
+        }
+    }
+}
+
+/// Asks before a statement that changes data runs on a connection tagged as production.
+#[component]
+fn ProductionConfirmDialogContent(sql: String) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    let db_name = match &*CONNECTION.read() {
+        ConnectionState::Connected { db_name, .. } => db_name.clone(),
+        _ => String::new(),
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *PENDING_PRODUCTION_REQUEST.write() = None,
+
+            div {
+                class: "{dialog_bg} border-2 border-red-600 rounded-lg shadow-2xl w-[640px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-2 {text_color}",
+                    "Change production data?"
+                }
+                p {
+                    class: "text-sm {label_color} mb-4",
+                    if db_name.is_empty() {
+                        "This connection is tagged as production. The statement below changes data."
+                    } else {
+                        "{db_name} is tagged as production. The statement below changes data."
+                    }
+                }
+                pre {
+                    class: "{preview_bg} border {dialog_border} rounded p-3 font-mono text-xs {label_color} whitespace-pre-wrap break-all overflow-auto",
+                    "{sql}"
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *PENDING_PRODUCTION_REQUEST.write() = None,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-red-600 hover:bg-red-500 text-white",
+                        onclick: move |_| {
+                            if let Some((_, request)) = PENDING_PRODUCTION_REQUEST.write().take() {
+                                send_db_request(request);
+                            }
+                        },
+                        "Run on production"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,4 @@
 use crate::db::DbRequest;
-use crate::services::DbSender;
 use crate::state::*;
 use dioxus::prelude::*;
 
@@ -36,6 +35,7 @@ pub fn ExecutionPlanDialog() -> Element {
             .and_then(|t| t.execution_plan.clone())
             .unwrap_or_else(|| "No execution plan available".to_string())
     };
+    let diagram_url = distsql_diagram_url(&plan);
 
     rsx! {
         div {
@@ -77,6 +77,15 @@ pub fn ExecutionPlanDialog() -> Element {
                 div {
                     class: "flex-1 overflow-auto p-4",
 
+                    if let Some(url) = diagram_url {
+                        a {
+                            class: "inline-block mb-3 text-sm text-blue-500 hover:underline",
+                            href: "{url}",
+                            target: "_blank",
+                            "Open distributed plan diagram"
+                        }
+                    }
+
                     pre {
                         class: "font-mono text-sm {text_color} whitespace-pre-wrap",
                         "{plan}"
@@ -98,6 +107,17 @@ pub fn ExecutionPlanDialog() -> Element {
     }
 }
 
+/// Link to the DistSQL diagram that ends a CockroachDB plan.
+fn distsql_diagram_url(plan: &str) -> Option<String> {
+    plan.lines()
+        .filter_map(|line| {
+            line.find("https://cockroachdb.github.io/distsqlplan/")
+                .map(|i| &line[i..])
+        })
+        .map(|url| url.trim().to_string())
+        .next()
+}
+
 pub fn request_execution_plan() {
     let content = EDITOR_TABS
         .read()
@@ -106,8 +126,6 @@ pub fn request_execution_plan() {
         .unwrap_or_default();
 
     if !content.is_empty() {
-        if let Some(tx) = try_use_context::<DbSender>() {
-            let _ = tx.send(DbRequest::Explain(content));
-        }
+        send_tab_request(DbRequest::Explain(content));
     }
 }
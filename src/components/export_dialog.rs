@@ -0,0 +1,619 @@
+use crate::config::{ExportPreset, ExportStore};
+use crate::export::{
+    export_results, export_to_bucket, publish_to_kafka, CloudProvider, CsvEncoding, ExportFormat,
+    LineEnding, XmlLayout,
+};
+use crate::state::*;
+use dioxus::prelude::*;
+
+/// Where the dialog sends the export.
+#[derive(Clone, Copy, PartialEq)]
+enum Destination {
+    File,
+    Bucket,
+    Kafka,
+}
+
+#[component]
+pub fn ExportDialog() -> Element {
+    rsx! {
+        if *SHOW_EXPORT_DIALOG.read() {
+            ExportDialogContent {}
+        }
+    }
+}
+
+#[component]
+fn ExportDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut settings = use_signal(|| ExportStore::new().load());
+    let mut options = use_signal(|| settings.peek().last_used.clone());
+    let mut preset_name = use_signal(String::new);
+    let mut destination = use_signal(|| Destination::File);
+    let mut kafka = use_signal(|| settings.peek().kafka.clone());
+    let mut bucket = use_signal(|| settings.peek().bucket.clone());
+    let mut secret_key = use_signal(|| {
+        let access_key_id = settings.peek().bucket.access_key_id.clone();
+        if access_key_id.is_empty() {
+            String::new()
+        } else {
+            ExportStore::new()
+                .get_s3_secret(&access_key_id)
+                .unwrap_or_default()
+        }
+    });
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-500"
+    } else {
+        "text-gray-600"
+    };
+    let input_bg = if is_dark { "bg-black" } else { "bg-white" };
+
+    let current = options.read().clone();
+    let presets = settings.read().presets.clone();
+    let active_preset = presets
+        .iter()
+        .find(|p| p.options == current)
+        .map(|p| p.name.clone())
+        .unwrap_or_default();
+    let row_count = EDITOR_TABS
+        .read()
+        .active_tab()
+        .and_then(|tab| tab.result.as_ref().map(|r| r.rows.len()));
+    let result_columns: Vec<String> = EDITOR_TABS
+        .read()
+        .active_tab()
+        .and_then(|tab| tab.result.as_ref().map(|r| r.columns.clone()))
+        .unwrap_or_default();
+    let kafka_target = kafka.read().clone();
+    let bucket_target = bucket.read().clone();
+    let current_destination = *destination.read();
+    let kafka_mode = current_destination == Destination::Kafka;
+    let can_export = row_count.is_some()
+        && match current_destination {
+            Destination::File => true,
+            Destination::Bucket => {
+                !bucket_target.bucket.trim().is_empty() && !bucket_target.key.trim().is_empty()
+            }
+            Destination::Kafka => {
+                !kafka_target.brokers.trim().is_empty() && !kafka_target.topic.trim().is_empty()
+            }
+        };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *SHOW_EXPORT_DIALOG.write() = false,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[420px] max-w-[90vw]",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                div {
+                    class: "p-6 space-y-4",
+
+                    h2 {
+                        class: "text-lg font-semibold {text_color}",
+                        "Export Results"
+                    }
+
+                    div {
+                        label {
+                            class: "block text-sm font-medium {label_color} mb-1",
+                            "Destination"
+                        }
+                        div {
+                            class: "flex space-x-4",
+                            label {
+                                class: "flex items-center space-x-1.5 text-sm {text_color} cursor-pointer",
+                                input {
+                                    r#type: "radio",
+                                    name: "export-destination",
+                                    checked: current_destination == Destination::File,
+                                    onchange: move |_| destination.set(Destination::File),
+                                }
+                                span { "File" }
+                            }
+                            label {
+                                class: "flex items-center space-x-1.5 text-sm {text_color} cursor-pointer",
+                                input {
+                                    r#type: "radio",
+                                    name: "export-destination",
+                                    checked: current_destination == Destination::Bucket,
+                                    onchange: move |_| destination.set(Destination::Bucket),
+                                }
+                                span { "S3 / GCS bucket" }
+                            }
+                            label {
+                                class: "flex items-center space-x-1.5 text-sm {text_color} cursor-pointer",
+                                input {
+                                    r#type: "radio",
+                                    name: "export-destination",
+                                    checked: kafka_mode,
+                                    onchange: move |_| destination.set(Destination::Kafka),
+                                }
+                                span { "Kafka topic" }
+                            }
+                        }
+                    }
+
+                    if kafka_mode {
+                        div {
+                            label {
+                                class: "block text-sm font-medium {label_color} mb-1",
+                                "Brokers"
+                            }
+                            input {
+                                class: "w-full px-3 py-2 border rounded text-sm font-mono focus:outline-none {input_bg} {dialog_border} {text_color}",
+                                r#type: "text",
+                                placeholder: "localhost:9092",
+                                value: "{kafka_target.brokers}",
+                                oninput: move |e| kafka.write().brokers = e.value(),
+                            }
+                        }
+                        div {
+                            label {
+                                class: "block text-sm font-medium {label_color} mb-1",
+                                "Topic"
+                            }
+                            input {
+                                class: "w-full px-3 py-2 border rounded text-sm font-mono focus:outline-none {input_bg} {dialog_border} {text_color}",
+                                r#type: "text",
+                                value: "{kafka_target.topic}",
+                                oninput: move |e| kafka.write().topic = e.value(),
+                            }
+                        }
+                        div {
+                            label {
+                                class: "block text-sm font-medium {label_color} mb-1",
+                                "Message key"
+                            }
+                            select {
+                                class: "w-full px-3 py-2 rounded border text-sm {input_bg} {dialog_border} {text_color}",
+                                onchange: move |e: FormEvent| {
+                                    let value = e.value();
+                                    kafka.write().key_column = (!value.is_empty()).then_some(value);
+                                },
+                                option {
+                                    value: "",
+                                    selected: kafka_target.key_column.is_none(),
+                                    "No key"
+                                }
+                                for column in result_columns {
+                                    option {
+                                        value: "{column}",
+                                        selected: kafka_target.key_column.as_deref() == Some(column.as_str()),
+                                        "{column}"
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            class: "text-xs {label_color}",
+                            "Each row is sent as a JSON object."
+                        }
+                    } else {
+                        if current_destination == Destination::Bucket {
+                            div {
+                                label {
+                                    class: "block text-sm font-medium {label_color} mb-1",
+                                    "Storage"
+                                }
+                                select {
+                                    class: "w-full px-3 py-2 rounded border text-sm {input_bg} {dialog_border} {text_color}",
+                                    onchange: move |e: FormEvent| {
+                                        if let Some(provider) = CloudProvider::ALL.into_iter().find(|p| p.label() == e.value()) {
+                                            bucket.write().provider = provider;
+                                        }
+                                    },
+                                    for provider in CloudProvider::ALL {
+                                        option {
+                                            value: "{provider.label()}",
+                                            selected: bucket_target.provider == provider,
+                                            "{provider.label()}"
+                                        }
+                                    }
+                                }
+                            }
+                            div {
+                                class: "flex space-x-3",
+                                div {
+                                    class: "flex-1",
+                                    label {
+                                        class: "block text-sm font-medium {label_color} mb-1",
+                                        "Bucket"
+                                    }
+                                    input {
+                                        class: "w-full px-3 py-2 border rounded text-sm font-mono focus:outline-none {input_bg} {dialog_border} {text_color}",
+                                        r#type: "text",
+                                        value: "{bucket_target.bucket}",
+                                        oninput: move |e| bucket.write().bucket = e.value(),
+                                    }
+                                }
+                                div {
+                                    class: "flex-1",
+                                    label {
+                                        class: "block text-sm font-medium {label_color} mb-1",
+                                        "Object key"
+                                    }
+                                    input {
+                                        class: "w-full px-3 py-2 border rounded text-sm font-mono focus:outline-none {input_bg} {dialog_border} {text_color}",
+                                        r#type: "text",
+                                        placeholder: "extracts/export.csv",
+                                        value: "{bucket_target.key}",
+                                        oninput: move |e| bucket.write().key = e.value(),
+                                    }
+                                }
+                            }
+                            if bucket_target.provider == CloudProvider::S3 {
+                                div {
+                                    class: "flex space-x-3",
+                                    div {
+                                        class: "flex-1",
+                                        label {
+                                            class: "block text-sm font-medium {label_color} mb-1",
+                                            "Region"
+                                        }
+                                        input {
+                                            class: "w-full px-3 py-2 border rounded text-sm font-mono focus:outline-none {input_bg} {dialog_border} {text_color}",
+                                            r#type: "text",
+                                            placeholder: "us-east-1",
+                                            value: "{bucket_target.region}",
+                                            oninput: move |e| bucket.write().region = e.value(),
+                                        }
+                                    }
+                                    div {
+                                        class: "flex-1",
+                                        label {
+                                            class: "block text-sm font-medium {label_color} mb-1",
+                                            "Endpoint"
+                                        }
+                                        input {
+                                            class: "w-full px-3 py-2 border rounded text-sm font-mono focus:outline-none {input_bg} {dialog_border} {text_color}",
+                                            r#type: "text",
+                                            placeholder: "Default AWS endpoint",
+                                            value: "{bucket_target.endpoint}",
+                                            oninput: move |e| bucket.write().endpoint = e.value(),
+                                        }
+                                    }
+                                }
+                                div {
+                                    class: "flex space-x-3",
+                                    div {
+                                        class: "flex-1",
+                                        label {
+                                            class: "block text-sm font-medium {label_color} mb-1",
+                                            "Access key ID"
+                                        }
+                                        input {
+                                            class: "w-full px-3 py-2 border rounded text-sm font-mono focus:outline-none {input_bg} {dialog_border} {text_color}",
+                                            r#type: "text",
+                                            value: "{bucket_target.access_key_id}",
+                                            oninput: move |e| bucket.write().access_key_id = e.value(),
+                                        }
+                                    }
+                                    div {
+                                        class: "flex-1",
+                                        label {
+                                            class: "block text-sm font-medium {label_color} mb-1",
+                                            "Secret access key"
+                                        }
+                                        input {
+                                            class: "w-full px-3 py-2 border rounded text-sm font-mono focus:outline-none {input_bg} {dialog_border} {text_color}",
+                                            r#type: "password",
+                                            value: "{secret_key}",
+                                            oninput: move |e| secret_key.set(e.value()),
+                                        }
+                                    }
+                                }
+                            } else {
+                                div {
+                                    label {
+                                        class: "block text-sm font-medium {label_color} mb-1",
+                                        "Service account key"
+                                    }
+                                    div {
+                                        class: "flex space-x-2",
+                                        input {
+                                            class: "flex-1 px-3 py-2 border rounded text-sm font-mono focus:outline-none {input_bg} {dialog_border} {text_color}",
+                                            r#type: "text",
+                                            placeholder: "Application default credentials",
+                                            value: "{bucket_target.service_account_path}",
+                                            oninput: move |e| bucket.write().service_account_path = e.value(),
+                                        }
+                                        button {
+                                            class: if is_dark {
+                                                "px-3 py-2 text-sm rounded transition-colors bg-gray-900 hover:bg-gray-800 text-white"
+                                            } else {
+                                                "px-3 py-2 text-sm rounded transition-colors bg-gray-100 hover:bg-gray-200 text-gray-700"
+                                            },
+                                            onclick: move |_| {
+                                                spawn(async move {
+                                                    if let Some(file) = rfd::AsyncFileDialog::new()
+                                                        .add_filter("JSON key", &["json"])
+                                                        .pick_file()
+                                                        .await
+                                                    {
+                                                        bucket.write().service_account_path =
+                                                            file.path().to_string_lossy().to_string();
+                                                    }
+                                                });
+                                            },
+                                            "Browse"
+                                        }
+                                    }
+                                }
+                            }
+                            div {
+                                class: "text-xs {label_color}",
+                                "Uploaded in parts as it is written; nothing is saved locally. Empty credentials fall back to the provider's environment variables. The secret key is kept in the system keyring."
+                            }
+                        }
+
+                        if !presets.is_empty() {
+                            div {
+                                label {
+                                    class: "block text-sm font-medium {label_color} mb-1",
+                                    "Preset"
+                                }
+                                select {
+                                    class: "w-full px-3 py-2 rounded border text-sm {input_bg} {dialog_border} {text_color}",
+                                    onchange: move |e: FormEvent| {
+                                        let name = e.value();
+                                        let preset = settings.read().presets.iter().find(|p| p.name == name).cloned();
+                                        if let Some(preset) = preset {
+                                            options.set(preset.options);
+                                            preset_name.set(preset.name);
+                                        }
+                                    },
+                                    option { value: "", selected: active_preset.is_empty(), "Custom" }
+                                    for preset in presets.iter() {
+                                        option {
+                                            value: "{preset.name}",
+                                            selected: preset.name == active_preset,
+                                            "{preset.name}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        div {
+                            label {
+                                class: "block text-sm font-medium {label_color} mb-1",
+                                "Format"
+                            }
+                            div {
+                                class: "flex space-x-4",
+                                for format in ExportFormat::ALL {
+                                    label {
+                                        class: "flex items-center space-x-1.5 text-sm {text_color} cursor-pointer",
+                                        input {
+                                            r#type: "radio",
+                                            name: "export-format",
+                                            checked: current.format == format,
+                                            onchange: move |_| options.write().format = format,
+                                        }
+                                        span { "{format.label()}" }
+                                    }
+                                }
+                            }
+                        }
+
+                        if current.format == ExportFormat::Xml {
+                            div {
+                                label {
+                                    class: "block text-sm font-medium {label_color} mb-1",
+                                    "Columns as"
+                                }
+                                select {
+                                    class: "w-full px-3 py-2 rounded border text-sm {input_bg} {dialog_border} {text_color}",
+                                    onchange: move |e: FormEvent| {
+                                        options.write().xml_layout = if e.value() == "attributes" {
+                                            XmlLayout::Attributes
+                                        } else {
+                                            XmlLayout::Elements
+                                        };
+                                    },
+                                    option {
+                                        value: "elements",
+                                        selected: current.xml_layout == XmlLayout::Elements,
+                                        "Child elements"
+                                    }
+                                    option {
+                                        value: "attributes",
+                                        selected: current.xml_layout == XmlLayout::Attributes,
+                                        "Attributes"
+                                    }
+                                }
+                            }
+                        }
+
+                        if current.format == ExportFormat::Csv {
+                            div {
+                                class: "flex space-x-3",
+                                div {
+                                    class: "flex-1",
+                                    label {
+                                        class: "block text-sm font-medium {label_color} mb-1",
+                                        "Encoding"
+                                    }
+                                    select {
+                                        class: "w-full px-3 py-2 rounded border text-sm {input_bg} {dialog_border} {text_color}",
+                                        onchange: move |e: FormEvent| {
+                                            if let Some(encoding) = CsvEncoding::ALL.into_iter().find(|c| c.label() == e.value()) {
+                                                options.write().csv_encoding = encoding;
+                                            }
+                                        },
+                                        for encoding in CsvEncoding::ALL {
+                                            option {
+                                                value: "{encoding.label()}",
+                                                selected: current.csv_encoding == encoding,
+                                                "{encoding.label()}"
+                                            }
+                                        }
+                                    }
+                                }
+                                div {
+                                    class: "flex-1",
+                                    label {
+                                        class: "block text-sm font-medium {label_color} mb-1",
+                                        "Line endings"
+                                    }
+                                    select {
+                                        class: "w-full px-3 py-2 rounded border text-sm {input_bg} {dialog_border} {text_color}",
+                                        onchange: move |e: FormEvent| {
+                                            options.write().line_ending = if e.value() == "crlf" {
+                                                LineEnding::CrLf
+                                            } else {
+                                                LineEnding::Lf
+                                            };
+                                        },
+                                        option {
+                                            value: "lf",
+                                            selected: current.line_ending == LineEnding::Lf,
+                                            "LF (Unix, macOS)"
+                                        }
+                                        option {
+                                            value: "crlf",
+                                            selected: current.line_ending == LineEnding::CrLf,
+                                            "CRLF (Windows)"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        div {
+                            label {
+                                class: "block text-sm font-medium {label_color} mb-1",
+                                "Save as preset"
+                            }
+                            div {
+                                class: "flex space-x-2",
+                                input {
+                                    class: "flex-1 px-3 py-2 border rounded text-sm focus:outline-none {input_bg} {dialog_border} {text_color}",
+                                    r#type: "text",
+                                    placeholder: "Excel (Windows)",
+                                    value: "{preset_name}",
+                                    oninput: move |e| preset_name.set(e.value()),
+                                }
+                                button {
+                                    class: if is_dark {
+                                        "px-3 py-2 text-sm rounded transition-colors bg-gray-900 hover:bg-gray-800 text-white disabled:opacity-50"
+                                    } else {
+                                        "px-3 py-2 text-sm rounded transition-colors bg-gray-100 hover:bg-gray-200 text-gray-700 disabled:opacity-50"
+                                    },
+                                    disabled: preset_name.read().trim().is_empty(),
+                                    onclick: move |_| {
+                                        let name = preset_name.read().trim().to_string();
+                                        let preset = ExportPreset {
+                                            name: name.clone(),
+                                            options: options.read().clone(),
+                                        };
+                                        let mut updated = settings.read().clone();
+                                        updated.presets.retain(|p| p.name != name);
+                                        updated.presets.push(preset);
+                                        updated.presets.sort_by(|a, b| a.name.cmp(&b.name));
+                                        if let Err(e) = ExportStore::new().save(&updated) {
+                                            tracing::error!("Failed to save export preset: {}", e);
+                                        }
+                                        settings.set(updated);
+                                    },
+                                    "Save"
+                                }
+                            }
+                        }
+                    }
+
+                    match row_count {
+                        Some(count) => rsx! {
+                            div { class: "text-sm {label_color}", "{count} rows" }
+                        },
+                        None => rsx! {
+                            div { class: "text-sm text-red-500", "No query results to export" }
+                        },
+                    }
+
+                    div {
+                        class: "flex justify-end space-x-3 pt-4",
+
+                        button {
+                            class: if is_dark {
+                                "px-4 py-2 text-sm rounded transition-colors bg-gray-900 hover:bg-gray-800 text-white"
+                            } else {
+                                "px-4 py-2 text-sm rounded transition-colors bg-gray-100 hover:bg-gray-200 text-gray-700"
+                            },
+                            onclick: move |_| *SHOW_EXPORT_DIALOG.write() = false,
+                            "Cancel"
+                        }
+
+                        button {
+                            class: "px-4 py-2 text-sm rounded transition-colors bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                            disabled: !can_export,
+                            onclick: move |_| {
+                                let result = EDITOR_TABS
+                                    .read()
+                                    .active_tab()
+                                    .and_then(|tab| tab.result.clone());
+                                if let Some(result) = result {
+                                    tracing::info!("Exporting {} rows", result.rows.len());
+                                    let mut updated = settings.read().clone();
+                                    match *destination.read() {
+                                        Destination::File => {
+                                            let used = options.read().clone();
+                                            export_results(result, &used);
+                                            updated.last_used = used;
+                                        }
+                                        Destination::Bucket => {
+                                            let used = options.read().clone();
+                                            let target = bucket.read().clone();
+                                            let secret = secret_key.read().clone();
+                                            if !target.access_key_id.is_empty() && !secret.is_empty() {
+                                                if let Err(e) = ExportStore::new().set_s3_secret(&target.access_key_id, &secret) {
+                                                    tracing::error!("Failed to store S3 secret key: {}", e);
+                                                }
+                                            }
+                                            let secret = (target.provider == CloudProvider::S3 && !secret.is_empty())
+                                                .then_some(secret);
+                                            export_to_bucket(result, &used, target.clone(), secret);
+                                            updated.last_used = used;
+                                            updated.bucket = target;
+                                        }
+                                        Destination::Kafka => {
+                                            let target = kafka.read().clone();
+                                            publish_to_kafka(result, target.clone());
+                                            updated.kafka = target;
+                                        }
+                                    }
+                                    if let Err(e) = ExportStore::new().save(&updated) {
+                                        tracing::error!("Failed to save export settings: {}", e);
+                                    }
+                                }
+                                *SHOW_EXPORT_DIALOG.write() = false;
+                            },
+                            "Export"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
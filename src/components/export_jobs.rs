@@ -0,0 +1,158 @@
+use crate::export::{clear_finished_export_jobs, reveal_in_folder, ExportJobStatus};
+use crate::state::*;
+use dioxus::prelude::*;
+
+/// Status bar label for the export jobs, e.g. "Exporting 42%" while any are running.
+pub fn export_jobs_label() -> Option<String> {
+    let jobs = EXPORT_JOBS.read();
+    if jobs.is_empty() {
+        return None;
+    }
+    let running: Vec<_> = jobs
+        .iter()
+        .filter(|j| j.status == ExportJobStatus::Running)
+        .collect();
+    if running.is_empty() {
+        return Some("Exports".to_string());
+    }
+    let total: usize = running.iter().map(|j| j.total_rows).sum();
+    let written: usize = running.iter().map(|j| j.rows_written).sum();
+    Some(format!("Exporting {}%", written * 100 / total.max(1)))
+}
+
+#[component]
+pub fn ExportJobsPanel() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+
+    let panel_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let border_class = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let bar_track = if is_dark {
+        "bg-gray-900"
+    } else {
+        "bg-gray-100"
+    };
+
+    let jobs = EXPORT_JOBS.read().clone();
+
+    rsx! {
+        div {
+            class: "absolute bottom-6 right-0 w-[400px] max-h-[60vh] flex flex-col {panel_bg} border {border_class} rounded-lg shadow-2xl z-40",
+            onclick: move |e: MouseEvent| e.stop_propagation(),
+
+            div {
+                class: "flex items-center px-3 py-2 border-b {border_class}",
+                span {
+                    class: "text-sm font-semibold {text_color} flex-1",
+                    "Exports"
+                }
+                button {
+                    class: "{muted_text} hover:text-blue-500",
+                    onclick: move |_| clear_finished_export_jobs(),
+                    "Clear finished"
+                }
+                button {
+                    class: "{muted_text} hover:text-blue-500 ml-3",
+                    onclick: move |_| *SHOW_EXPORT_JOBS.write() = false,
+                    "✕"
+                }
+            }
+
+            div {
+                class: "flex-1 overflow-auto",
+                if jobs.is_empty() {
+                    div {
+                        class: "{muted_text} text-center py-6",
+                        "No exports"
+                    }
+                }
+                for job in jobs.into_iter().rev() {
+                    {
+                        let percent = job.rows_written * 100 / job.total_rows.max(1);
+                        let name = job.destination.name();
+                        let description = job.destination.description();
+                        let path = job.destination.path().map(|p| p.to_path_buf());
+                        let done_label = job.destination.done_label();
+                        let cancel_job = job.clone();
+                        let failure = match &job.status {
+                            ExportJobStatus::Failed(error) => Some(error.clone()),
+                            _ => None,
+                        };
+                        rsx! {
+                            div {
+                                key: "{job.id}",
+                                class: "px-3 py-2 border-b {border_class} space-y-1",
+                                div {
+                                    class: "flex items-center space-x-2",
+                                    span {
+                                        class: "flex-1 truncate {text_color}",
+                                        title: "{description}",
+                                        "{name}"
+                                    }
+                                    span { class: muted_text, "{job.format.label()}" }
+                                    match job.status.clone() {
+                                        ExportJobStatus::Running => rsx! {
+                                            button {
+                                                class: "{muted_text} hover:text-red-500",
+                                                onclick: move |_| cancel_job.cancel(),
+                                                "Cancel"
+                                            }
+                                        },
+                                        ExportJobStatus::Done => match path {
+                                            Some(path) => rsx! {
+                                                button {
+                                                    class: "text-blue-500 hover:text-blue-400",
+                                                    onclick: move |_| reveal_in_folder(&path),
+                                                    "Show in folder"
+                                                }
+                                            },
+                                            None => rsx! {
+                                                span { class: "text-green-500", "{done_label}" }
+                                            },
+                                        },
+                                        ExportJobStatus::Cancelled => rsx! {
+                                            span { class: muted_text, "Cancelled" }
+                                        },
+                                        ExportJobStatus::Failed(_) => rsx! {
+                                            span { class: "text-red-500", "Failed" }
+                                        },
+                                    }
+                                }
+                                if job.status == ExportJobStatus::Running {
+                                    div {
+                                        class: "h-1.5 {bar_track}",
+                                        div {
+                                            class: "h-full bg-blue-500",
+                                            style: "width: {percent}%",
+                                        }
+                                    }
+                                    div {
+                                        class: muted_text,
+                                        "{job.rows_written} of {job.total_rows} rows"
+                                    }
+                                } else if let Some(error) = failure {
+                                    div { class: "text-red-500 break-all", "{error}" }
+                                } else if job.status == ExportJobStatus::Done {
+                                    div { class: muted_text, "{job.total_rows} rows" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use crate::db::{DbRequest, ExtensionInfo};
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[derive(Clone, PartialEq)]
+enum ExtensionAction {
+    Install(String),
+    Update(String),
+}
+
+impl ExtensionAction {
+    fn sql(&self) -> String {
+        match self {
+            ExtensionAction::Install(name) => {
+                format!("CREATE EXTENSION IF NOT EXISTS \"{}\"", name)
+            }
+            ExtensionAction::Update(name) => format!("ALTER EXTENSION \"{}\" UPDATE", name),
+        }
+    }
+}
+
+#[component]
+pub fn ExtensionsDialog() -> Element {
+    rsx! {
+        if *SHOW_EXTENSIONS.read() {
+            ExtensionsDialogContent {}
+        }
+    }
+}
+
+#[component]
+fn ExtensionsDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut search = use_signal(String::new);
+    let mut pending = use_signal(|| None::<ExtensionAction>);
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let cell_text = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+
+    let extensions = EXTENSIONS.read().clone();
+    let query = search.read().to_lowercase();
+    let filtered: Vec<ExtensionInfo> = extensions
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .filter(|e| query.is_empty() || e.name.to_lowercase().contains(&query))
+        .cloned()
+        .collect();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *SHOW_EXTENSIONS.write() = false,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[800px] max-w-[95vw] max-h-[80vh] flex flex-col",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                div {
+                    class: "flex items-center space-x-3 px-4 py-3 border-b {dialog_border}",
+
+                    h2 {
+                        class: "text-lg font-semibold {text_color} flex-1",
+                        "Extensions"
+                    }
+
+                    input {
+                        class: "w-64 px-3 py-1.5 border rounded text-sm focus:outline-none {input_class}",
+                        r#type: "text",
+                        placeholder: "Search extensions...",
+                        value: "{search}",
+                        autofocus: true,
+                        oninput: move |e| search.set(e.value()),
+                    }
+                }
+
+                // Confirmation bar
+                if let Some(action) = pending.read().clone() {
+                    div {
+                        class: "flex items-center space-x-3 px-4 py-2 border-b {dialog_border} bg-yellow-900 bg-opacity-20",
+                        span {
+                            class: "flex-1 font-mono text-xs {cell_text}",
+                            "Run {action.sql()}?"
+                        }
+                        button {
+                            class: "text-xs px-2 py-1 rounded bg-blue-600 hover:bg-blue-500 text-white",
+                            onclick: move |_| {
+                                let request = match &action {
+                                    ExtensionAction::Install(name) => DbRequest::InstallExtension(name.clone()),
+                                    ExtensionAction::Update(name) => DbRequest::UpdateExtension(name.clone()),
+                                };
+                                send_db_request(request);
+                                pending.set(None);
+                            },
+                            "Confirm"
+                        }
+                        button {
+                            class: "text-xs px-2 py-1 rounded {cell_text} hover:opacity-80",
+                            onclick: move |_| pending.set(None),
+                            "Cancel"
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex-1 overflow-auto",
+
+                    if extensions.is_none() {
+                        div {
+                            class: "{muted_text} text-sm text-center py-8",
+                            "Loading..."
+                        }
+                    } else {
+                        table {
+                            class: "w-full text-sm text-left",
+                            thead {
+                                class: "{muted_text} text-xs uppercase sticky top-0 {dialog_bg}",
+                                tr {
+                                    th { class: "px-4 py-2", "Name" }
+                                    th { class: "px-4 py-2", "Installed" }
+                                    th { class: "px-4 py-2", "Available" }
+                                    th { class: "px-4 py-2", "Description" }
+                                    th { class: "px-4 py-2" }
+                                }
+                            }
+                            tbody {
+                                for ext in filtered {
+                                    {
+                                        let install_name = ext.name.clone();
+                                        let update_name = ext.name.clone();
+                                        let installed = ext.installed_version.clone().unwrap_or_else(|| "-".to_string());
+                                        let available = ext.default_version.clone().unwrap_or_default();
+                                        let comment = ext.comment.clone().unwrap_or_default();
+                                        rsx! {
+                                            tr {
+                                                key: "{ext.name}",
+                                                td {
+                                                    class: "px-4 py-1.5 font-mono text-xs {cell_text}",
+                                                    "{ext.name}"
+                                                }
+                                                td {
+                                                    class: "px-4 py-1.5 text-xs",
+                                                    class: if ext.installed_version.is_some() { "text-green-500" } else { muted_text },
+                                                    "{installed}"
+                                                }
+                                                td {
+                                                    class: "px-4 py-1.5 text-xs {muted_text}",
+                                                    "{available}"
+                                                }
+                                                td {
+                                                    class: "px-4 py-1.5 text-xs {muted_text}",
+                                                    "{comment}"
+                                                }
+                                                td {
+                                                    class: "px-4 py-1.5 text-xs text-right whitespace-nowrap",
+                                                    if ext.installed_version.is_none() {
+                                                        button {
+                                                            class: "{cell_text} hover:text-blue-500",
+                                                            onclick: move |_| pending.set(Some(ExtensionAction::Install(install_name.clone()))),
+                                                            "Install"
+                                                        }
+                                                    } else if ext.has_update() {
+                                                        button {
+                                                            class: "{cell_text} hover:text-blue-500",
+                                                            onclick: move |_| pending.set(Some(ExtensionAction::Update(update_name.clone()))),
+                                                            "Update"
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -19,6 +19,8 @@ pub fn FilterPanel() -> Element {
     let columns = result.map(|r| r.columns.clone()).unwrap_or_default();
     let column_types = result.map(|r| r.column_types.clone()).unwrap_or_default();
     let filter_state = active_tab.and_then(|t| t.filter_state.clone());
+    let soft_delete = soft_delete_column(&source_table);
+    let show_deleted = filter_state.as_ref().is_some_and(|s| s.show_deleted);
 
     let bg = if is_dark { "bg-gray-900" } else { "bg-gray-50" };
     let border = if is_dark {
@@ -72,6 +74,22 @@ pub fn FilterPanel() -> Element {
                         "Clear All"
                     }
                 }
+
+                if let Some(column) = soft_delete {
+                    label {
+                        class: "flex items-center space-x-1 text-xs {text}",
+                        title: "Rows with {column} set are hidden by default",
+                        input {
+                            r#type: "checkbox",
+                            checked: show_deleted,
+                            onchange: {
+                                let source_table = source_table.clone();
+                                move |_| toggle_show_deleted(&source_table)
+                            },
+                        }
+                        span { "Show deleted" }
+                    }
+                }
             }
         }
     }
@@ -181,12 +199,31 @@ fn FilterRow(
     }
 }
 
+fn new_filter_state(source_table: &str) -> FilterState {
+    let mut state = FilterState::new(source_table.to_string());
+    state.soft_delete_column = soft_delete_column(source_table);
+    state
+}
+
+fn toggle_show_deleted(source_table: &str) {
+    {
+        let mut tabs = EDITOR_TABS.write();
+        if let Some(tab) = tabs.active_tab_mut() {
+            let state = tab
+                .filter_state
+                .get_or_insert_with(|| new_filter_state(source_table));
+            state.show_deleted = !state.show_deleted;
+        }
+    }
+    apply_filters(source_table);
+}
+
 fn add_filter(source_table: &str) {
     let mut tabs = EDITOR_TABS.write();
     if let Some(tab) = tabs.active_tab_mut() {
         let state = tab
             .filter_state
-            .get_or_insert_with(|| FilterState::new(source_table.to_string()));
+            .get_or_insert_with(|| new_filter_state(source_table));
         state.filters.push(ColumnFilter {
             column: String::new(),
             operator: FilterOperator::Equal,
@@ -203,7 +240,7 @@ fn clear_filters(source_table: &str) {
         }
     }
     // Re-execute a simple select
-    let sql = format!("SELECT * FROM {} LIMIT 100", source_table);
+    let sql = new_filter_state(source_table).to_sql();
     execute_filter_sql(&sql);
 }
 
@@ -272,7 +309,7 @@ fn apply_filters(source_table: &str) {
         };
         match &tab.filter_state {
             Some(state) => state.to_sql(),
-            None => format!("SELECT * FROM {} LIMIT 100", source_table),
+            None => new_filter_state(source_table).to_sql(),
         }
     };
     execute_filter_sql(&sql);
@@ -285,7 +322,7 @@ fn execute_filter_sql(sql: &str) {
             tab.content = sql.to_string();
         }
     }
-    send_db_request(crate::db::DbRequest::Execute(sql.to_string()));
+    send_tab_request(crate::db::DbRequest::Execute(sql.to_string()));
 }
 
 pub fn toggle_sort(column: String) {
@@ -324,7 +361,7 @@ pub fn toggle_sort(column: String) {
         if let Some(tab) = tabs.active_tab_mut() {
             let state = tab
                 .filter_state
-                .get_or_insert_with(|| FilterState::new(source_table.clone()));
+                .get_or_insert_with(|| new_filter_state(&source_table));
             state.sort = new_sort;
         }
     }
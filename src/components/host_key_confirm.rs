@@ -0,0 +1,112 @@
+use crate::db::{trust_host_key, DbRequest, UnknownHostKey};
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn HostKeyConfirmDialog() -> Element {
+    let pending = PENDING_HOST_KEY.read().as_ref().map(|(key, _)| key.clone());
+    rsx! {
+        if let Some(unknown) = pending {
+            HostKeyConfirmDialogContent { unknown }
+        }
+    }
+}
+
+/// End the held-back connect or test with `message`, as if it had failed.
+fn fail_pending(message: String) {
+    let Some((_, request)) = PENDING_HOST_KEY.write().take() else {
+        return;
+    };
+    if matches!(request, DbRequest::Connect(_)) {
+        *CONNECTION.write() = ConnectionState::Error(message.clone());
+        *CURRENT_ENVIRONMENT.write() = None;
+        *CURRENT_SAVED_CONNECTION.write() = None;
+    }
+    *TEST_CONNECTION_STATUS.write() = TestConnectionStatus::Failed(message);
+}
+
+/// Shows the fingerprint of an SSH server met for the first time, to compare with the
+/// one its administrator publishes before the key goes into known_hosts.
+#[component]
+fn HostKeyConfirmDialogContent(unknown: UnknownHostKey) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    let host = if unknown.port == 22 {
+        unknown.host.clone()
+    } else {
+        format!("{}:{}", unknown.host, unknown.port)
+    };
+    let fingerprint = unknown.fingerprint.clone();
+    let rejected = format!("SSH tunnel: the host key of {} was not trusted", host);
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: {
+                let rejected = rejected.clone();
+                move |_| fail_pending(rejected.clone())
+            },
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[520px] max-w-[95vw] p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-2 {text_color}",
+                    "Trust the SSH server {host}?"
+                }
+                p {
+                    class: "text-sm {label_color} mb-4",
+                    "Its host key isn't in ~/.ssh/known_hosts yet. Compare the fingerprint with the one its administrator gives you; a different one means the connection may be intercepted."
+                }
+                pre {
+                    class: "{preview_bg} border {dialog_border} rounded p-2 font-mono text-xs {text_color} whitespace-pre-wrap break-all",
+                    "{fingerprint}"
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| fail_pending(rejected.clone()),
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white",
+                        onclick: move |_| {
+                            if let Err(e) = trust_host_key(&unknown) {
+                                fail_pending(format!("SSH tunnel: {}", e));
+                            } else if let Some((_, request)) = PENDING_HOST_KEY.write().take() {
+                                send_db_request(request);
+                            }
+                        },
+                        "Trust and continue"
+                    }
+                }
+            }
+        }
+    }
+}
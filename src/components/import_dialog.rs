@@ -1,19 +1,33 @@
-use crate::import::{self, ImportData};
+use crate::config::ImportProfileStore;
+use crate::db::{fk_dependency_order, ConflictStrategy, DatabaseType, DbRequest, TableImport};
+use crate::import::{self, HttpSource, ImportCoercion, ImportColumn, ImportData, ImportProfile};
 use crate::state::*;
 use dioxus::prelude::*;
+use std::collections::HashMap;
 
 #[component]
 pub fn ImportDialog() -> Element {
-    let show = *SHOW_IMPORT_DIALOG.read();
-    if !show {
-        return rsx! {};
+    rsx! {
+        if *SHOW_IMPORT_DIALOG.read() {
+            ImportDialogContent {}
+        }
     }
+}
 
+#[component]
+fn ImportDialogContent() -> Element {
     let mut step = use_signal(|| 0usize);
     let mut import_data = use_signal(|| None::<ImportData>);
     let mut target_table = use_signal(String::new);
     let mut column_mapping = use_signal(Vec::<(usize, String)>::new);
+    let mut coercions = use_signal(HashMap::<usize, ImportCoercion>::new);
+    let mut conflict = use_signal(ConflictStrategy::default);
+    let mut profiles = use_signal(|| ImportProfileStore::new().load());
+    let mut profile_name = use_signal(String::new);
     let mut error_msg = use_signal(|| None::<String>);
+    let mut multi_files = use_signal(Vec::<(String, ImportData)>::new);
+    let mut http_source = use_signal(HttpSource::default);
+    let mut fetching = use_signal(|| false);
 
     let is_dark = *IS_DARK_MODE.read();
     let progress = *IMPORT_PROGRESS.read();
@@ -82,13 +96,13 @@ pub fn ImportDialog() -> Element {
                 if *step.read() == 0 {
                     div {
                         class: "space-y-4",
-                        p { class: "{muted} text-sm", "Select a CSV or JSON file to import." }
+                        p { class: "{muted} text-sm", "Select a CSV, JSON or Parquet file to import." }
                         button {
                             class: "px-4 py-2 rounded bg-blue-600 text-white hover:bg-blue-500",
                             onclick: move |_| {
                                 spawn(async move {
                                     let file = rfd::AsyncFileDialog::new()
-                                        .add_filter("Data files", &["csv", "json"])
+                                        .add_filter("Data files", &["csv", "json", "parquet"])
                                         .pick_file()
                                         .await;
 
@@ -109,6 +123,112 @@ pub fn ImportDialog() -> Element {
                             },
                             "Choose File..."
                         }
+                        p {
+                            class: "{muted} text-sm",
+                            "Or fetch JSON from a URL, such as API fixtures."
+                        }
+                        div {
+                            class: "space-y-2",
+                            input {
+                                class: "w-full px-3 py-2 rounded {input_bg} {input_border} {text} border font-mono text-sm",
+                                r#type: "text",
+                                placeholder: "https://api.example.com/users",
+                                value: "{http_source.read().url}",
+                                oninput: move |e| http_source.write().url = e.value(),
+                            }
+                            textarea {
+                                class: "w-full px-3 py-2 rounded {input_bg} {input_border} {text} border font-mono text-sm",
+                                rows: "2",
+                                placeholder: "Headers, one Name: value per line",
+                                value: "{http_source.read().headers}",
+                                oninput: move |e| http_source.write().headers = e.value(),
+                            }
+                            div {
+                                class: "flex space-x-2",
+                                input {
+                                    class: "flex-1 px-3 py-2 rounded {input_bg} {input_border} {text} border font-mono text-sm",
+                                    r#type: "password",
+                                    placeholder: "Bearer token (optional)",
+                                    value: "{http_source.read().bearer_token}",
+                                    oninput: move |e| http_source.write().bearer_token = e.value(),
+                                }
+                                button {
+                                    class: "px-4 py-2 rounded border {input_border} {text} hover:opacity-80 disabled:opacity-50",
+                                    disabled: *fetching.read() || http_source.read().url.trim().is_empty(),
+                                    onclick: move |_| {
+                                        let source = http_source.read().clone();
+                                        fetching.set(true);
+                                        spawn(async move {
+                                            match import::fetch_json(&source).await {
+                                                Ok(data) => {
+                                                    *import_data.write() = Some(data);
+                                                    *error_msg.write() = None;
+                                                    *step.write() = 1;
+                                                }
+                                                Err(e) => {
+                                                    *error_msg.write() = Some(e.to_string());
+                                                }
+                                            }
+                                            fetching.set(false);
+                                        });
+                                    },
+                                    if *fetching.read() { "Fetching..." } else { "Fetch" }
+                                }
+                            }
+                        }
+                        p {
+                            class: "{muted} text-sm",
+                            "Or pick one file per table to import related tables in foreign key order."
+                        }
+                        button {
+                            class: "px-4 py-2 rounded border {input_border} {text} hover:opacity-80",
+                            onclick: move |_| {
+                                spawn(async move {
+                                    let Some(files) = rfd::AsyncFileDialog::new()
+                                        .add_filter("Data files", &["csv", "json", "parquet"])
+                                        .pick_files()
+                                        .await
+                                    else {
+                                        return;
+                                    };
+
+                                    let mut parsed = Vec::with_capacity(files.len());
+                                    for file in files {
+                                        match import::parse_file(file.path()) {
+                                            Ok(data) => parsed.push((file.file_name(), data)),
+                                            Err(e) => {
+                                                *error_msg.write() = Some(format!("{}: {}", file.file_name(), e));
+                                                return;
+                                            }
+                                        }
+                                    }
+                                    *error_msg.write() = None;
+                                    multi_files.set(parsed);
+                                    *step.write() = 4;
+                                });
+                            },
+                            "Choose Files for Several Tables..."
+                        }
+                        p {
+                            class: "{muted} text-sm",
+                            "Or import new files dropped into a folder using a saved profile."
+                        }
+                        button {
+                            class: "px-4 py-2 rounded border {input_border} {text} hover:opacity-80",
+                            onclick: move |_| {
+                                close_dialog();
+                                *SHOW_IMPORT_WATCHES.write() = true;
+                            },
+                            "Watched Folders..."
+                        }
+                    }
+                }
+
+                // Several files, each into its own table
+                if *step.read() == 4 {
+                    MultiTableImport {
+                        files: multi_files.read().clone(),
+                        on_back: move |_| *step.write() = 0,
                     }
                 }
 
@@ -119,13 +239,20 @@ pub fn ImportDialog() -> Element {
                         let data = import_data.read();
                         let file_cols = data.as_ref().map(|d| d.columns.len()).unwrap_or(0);
                         let file_rows = data.as_ref().map(|d| d.rows.len()).unwrap_or(0);
+                        let selected_table = target_table.read().clone();
+                        let table_profiles: Vec<ImportProfile> = profiles
+                            .read()
+                            .iter()
+                            .filter(|p| !selected_table.is_empty() && p.table == selected_table)
+                            .cloned()
+                            .collect();
 
                         rsx! {
                             div {
                                 class: "space-y-4",
                                 p {
                                     class: "{muted} text-sm",
-                                    "File has {file_cols} columns and {file_rows} rows. Select a target table."
+                                    "Data has {file_cols} columns and {file_rows} rows. Select a target table."
                                 }
 
                                 select {
@@ -133,6 +260,8 @@ pub fn ImportDialog() -> Element {
                                     value: "{target_table}",
                                     onchange: move |evt: FormEvent| {
                                         *target_table.write() = evt.value();
+                                        coercions.write().clear();
+                                        profile_name.set(String::new());
                                         // Auto-map columns
                                         let table_name = evt.value();
                                         let schema = SCHEMA.read();
@@ -152,6 +281,43 @@ pub fn ImportDialog() -> Element {
                                     }
                                 }
 
+                                if !table_profiles.is_empty() {
+                                    div {
+                                        class: "space-y-1",
+                                        p { class: "{muted} text-sm", "Use a saved profile for this table:" }
+                                        select {
+                                            class: "w-full px-3 py-2 rounded {input_bg} {input_border} {text} border",
+                                            onchange: move |evt: FormEvent| {
+                                                let name = evt.value();
+                                                let table_name = target_table.read().clone();
+                                                let profile = profiles
+                                                    .read()
+                                                    .iter()
+                                                    .find(|p| p.table == table_name && p.name == name)
+                                                    .cloned();
+                                                let Some(profile) = profile else {
+                                                    return;
+                                                };
+                                                if let Some(data) = import_data.read().as_ref() {
+                                                    let (mapping, profile_coercions) = profile.resolve(&data.columns);
+                                                    *column_mapping.write() = mapping;
+                                                    *coercions.write() = profile_coercions;
+                                                }
+                                                conflict.set(profile.conflict);
+                                                profile_name.set(profile.name);
+                                            },
+                                            option { value: "", "No profile" }
+                                            for profile in table_profiles.iter() {
+                                                option {
+                                                    value: "{profile.name}",
+                                                    selected: *profile_name.read() == profile.name,
+                                                    "{profile.name} ({profile.columns.len()} columns)"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+
                                 div {
                                     class: "flex justify-between",
                                     button {
@@ -198,6 +364,7 @@ pub fn ImportDialog() -> Element {
                                                 .find(|(i, _)| *i == idx)
                                                 .map(|(_, c)| c.clone())
                                                 .unwrap_or_default();
+                                            let coercion = coercions.read().get(&idx).copied().unwrap_or_default();
                                             rsx! {
                                                 div {
                                                     class: "flex items-center space-x-3",
@@ -223,6 +390,29 @@ pub fn ImportDialog() -> Element {
                                                             }
                                                         }
                                                     }
+                                                    select {
+                                                        class: "w-40 px-2 py-1 rounded text-sm {input_bg} {input_border} {text} border disabled:opacity-50",
+                                                        title: "Conversion applied to each value",
+                                                        disabled: mapped_to.is_empty(),
+                                                        onchange: move |evt: FormEvent| {
+                                                            let choice = ImportCoercion::ALL
+                                                                .into_iter()
+                                                                .find(|c| c.label() == evt.value())
+                                                                .unwrap_or_default();
+                                                            if choice == ImportCoercion::None {
+                                                                coercions.write().remove(&idx);
+                                                            } else {
+                                                                coercions.write().insert(idx, choice);
+                                                            }
+                                                        },
+                                                        for choice in ImportCoercion::ALL {
+                                                            option {
+                                                                value: "{choice.label()}",
+                                                                selected: coercion == choice,
+                                                                "{choice.label()}"
+                                                            }
+                                                        }
+                                                    }
                                                 }
                                             }
                                         }
@@ -254,6 +444,8 @@ pub fn ImportDialog() -> Element {
                         let data = import_data.read();
                         let mapping = column_mapping.read().clone();
                         let table_name = target_table.read().clone();
+                        let current_coercions = coercions.read().clone();
+                        let current_conflict = *conflict.read();
 
                         // Build preview: mapped columns and first 10 rows
                         let mapped_cols: Vec<String> = mapping.iter().map(|(_, c)| c.clone()).collect();
@@ -266,9 +458,7 @@ pub fn ImportDialog() -> Element {
                                     .map(|row| {
                                         mapping
                                             .iter()
-                                            .map(|(idx, _)| {
-                                                row.get(*idx).cloned().unwrap_or_else(|| "NULL".to_string())
-                                            })
+                                            .map(|(idx, _)| coerced_value(row, *idx, &current_coercions))
                                             .collect()
                                     })
                                     .collect()
@@ -309,6 +499,65 @@ pub fn ImportDialog() -> Element {
                                     }
                                 }
 
+                                div {
+                                    class: "flex items-center space-x-3",
+                                    span { class: "text-sm {muted}", "Existing rows:" }
+                                    select {
+                                        class: "flex-1 px-2 py-1 rounded text-sm {input_bg} {input_border} {text} border",
+                                        onchange: move |evt: FormEvent| {
+                                            if let Some(choice) = ConflictStrategy::ALL
+                                                .into_iter()
+                                                .find(|c| c.label() == evt.value())
+                                            {
+                                                conflict.set(choice);
+                                            }
+                                        },
+                                        for choice in ConflictStrategy::ALL {
+                                            option {
+                                                value: "{choice.label()}",
+                                                selected: current_conflict == choice,
+                                                "{choice.label()}"
+                                            }
+                                        }
+                                    }
+                                }
+
+                                div {
+                                    class: "flex items-center space-x-3",
+                                    input {
+                                        class: "flex-1 px-2 py-1 rounded text-sm {input_bg} {input_border} {text} border",
+                                        r#type: "text",
+                                        placeholder: "Profile name, e.g. Weekly feed",
+                                        value: "{profile_name}",
+                                        oninput: move |evt| profile_name.set(evt.value()),
+                                    }
+                                    button {
+                                        class: "px-3 py-1 rounded text-sm {muted} border {input_border} hover:opacity-80 disabled:opacity-50",
+                                        disabled: profile_name.read().trim().is_empty(),
+                                        onclick: move |_| {
+                                            let Some(profile) = build_profile(
+                                                profile_name.read().trim(),
+                                                &target_table.read(),
+                                                &column_mapping.read(),
+                                                &coercions.read(),
+                                                *conflict.read(),
+                                                &import_data.read(),
+                                            ) else {
+                                                return;
+                                            };
+                                            let store = ImportProfileStore::new();
+                                            match store.upsert(profile) {
+                                                Ok(()) => {
+                                                    profiles.set(store.load());
+                                                    *error_msg.write() = None;
+                                                }
+                                                Err(e) => *error_msg.write() = Some(format!("Could not save profile: {}", e)),
+                                            }
+                                        },
+                                        "Save profile"
+                                    }
+                                }
+
                                 // Progress bar
                                 if let Some((inserted, total)) = progress {
                                     div {
@@ -335,6 +584,8 @@ pub fn ImportDialog() -> Element {
                                     }
                                 }
 
+                                ImportValidationReport {}
+
                                 div {
                                     class: "flex justify-between",
                                     button {
@@ -342,6 +593,30 @@ pub fn ImportDialog() -> Element {
                                         onclick: move |_| *step.write() = 2,
                                         "Back"
                                     }
+                                    div { class: "flex-1" }
+                                    if import_message.is_none() {
+                                        button {
+                                            class: "px-4 py-2 mr-2 rounded border {input_border} {text} hover:opacity-80 disabled:opacity-50",
+                                            title: "Insert inside a transaction that is rolled back, and report failing rows",
+                                            disabled: progress.is_some() || *VALIDATING_IMPORT.read(),
+                                            onclick: {
+                                                let table_name = table_name.clone();
+                                                let mapping = mapping.clone();
+                                                move |_| {
+                                                    if let Some(data) = import_data.read().as_ref() {
+                                                        validate_import(vec![table_import(
+                                                            &table_name,
+                                                            &mapping,
+                                                            &coercions.read(),
+                                                            *conflict.read(),
+                                                            data,
+                                                        )]);
+                                                    }
+                                                }
+                                            },
+                                            "Validate only"
+                                        }
+                                    }
                                     button {
                                         class: if import_message.is_some() {
                                             "px-4 py-2 rounded bg-gray-700 text-white hover:bg-gray-600"
@@ -360,6 +635,8 @@ pub fn ImportDialog() -> Element {
                                                     execute_import(
                                                         &table_name,
                                                         &mapping,
+                                                        &coercions.read(),
+                                                        *conflict.read(),
                                                         &import_data.read(),
                                                     );
                                                 }
@@ -381,37 +658,430 @@ pub fn ImportDialog() -> Element {
     }
 }
 
-fn execute_import(table_name: &str, mapping: &[(usize, String)], data: &Option<ImportData>) {
-    let data = match data {
-        Some(d) => d,
-        None => return,
-    };
+/// File value at `idx` after its column's coercion.
+fn coerced_value(row: &[String], idx: usize, coercions: &HashMap<usize, ImportCoercion>) -> String {
+    let value = row.get(idx).map(String::as_str).unwrap_or("NULL");
+    coercions
+        .get(&idx)
+        .map(|c| c.apply(value))
+        .unwrap_or_else(|| value.to_string())
+}
 
-    let columns: Vec<String> = mapping.iter().map(|(_, c)| c.clone()).collect();
-    let rows: Vec<Vec<String>> = data
-        .rows
+fn build_profile(
+    name: &str,
+    table: &str,
+    mapping: &[(usize, String)],
+    coercions: &HashMap<usize, ImportCoercion>,
+    conflict: ConflictStrategy,
+    data: &Option<ImportData>,
+) -> Option<ImportProfile> {
+    let data = data.as_ref()?;
+    let columns = mapping
         .iter()
-        .map(|row| {
-            mapping
-                .iter()
-                .map(|(idx, _)| row.get(*idx).cloned().unwrap_or_else(|| "NULL".to_string()))
-                .collect()
+        .filter_map(|(idx, target)| {
+            Some(ImportColumn {
+                source: data.columns.get(*idx)?.clone(),
+                target: target.clone(),
+                coercion: coercions.get(idx).copied().unwrap_or_default(),
+            })
         })
         .collect();
+    Some(ImportProfile {
+        name: name.to_string(),
+        table: table.to_string(),
+        columns,
+        conflict,
+    })
+}
+
+/// Rows of the file as they will be inserted into `table`.
+fn table_import(
+    table: &str,
+    mapping: &[(usize, String)],
+    coercions: &HashMap<usize, ImportCoercion>,
+    conflict: ConflictStrategy,
+    data: &ImportData,
+) -> TableImport {
+    TableImport {
+        table: table.to_string(),
+        columns: mapping.iter().map(|(_, c)| c.clone()).collect(),
+        rows: data
+            .rows
+            .iter()
+            .map(|row| {
+                mapping
+                    .iter()
+                    .map(|(idx, _)| coerced_value(row, *idx, coercions))
+                    .collect()
+            })
+            .collect(),
+        conflict,
+    }
+}
+
+fn execute_import(
+    table_name: &str,
+    mapping: &[(usize, String)],
+    coercions: &HashMap<usize, ImportCoercion>,
+    conflict: ConflictStrategy,
+    data: &Option<ImportData>,
+) {
+    let data = match data {
+        Some(d) => d,
+        None => return,
+    };
+    let import = table_import(table_name, mapping, coercions, conflict, data);
 
     *IMPORT_MESSAGE.write() = None;
+    *IMPORT_VALIDATION.write() = None;
 
     let batch_size = 100;
-    send_db_request(crate::db::DbRequest::ImportData {
-        table: table_name.to_string(),
-        columns,
-        rows,
+    send_db_request(DbRequest::ImportData {
+        table: import.table,
+        columns: import.columns,
+        rows: import.rows,
         batch_size,
+        conflict,
     });
 }
 
+/// Dry-run the import and report which rows would fail.
+fn validate_import(tables: Vec<TableImport>) {
+    *IMPORT_MESSAGE.write() = None;
+    *IMPORT_VALIDATION.write() = None;
+    *VALIDATING_IMPORT.write() = true;
+    send_db_request(DbRequest::ValidateImport(tables));
+}
+
 fn close_dialog() {
     *SHOW_IMPORT_DIALOG.write() = false;
     *IMPORT_PROGRESS.write() = None;
     *IMPORT_MESSAGE.write() = None;
+    *IMPORT_VALIDATION.write() = None;
+}
+
+/// Outcome of the last "Validate only" run.
+#[component]
+fn ImportValidationReport() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let muted = if is_dark {
+        "text-gray-500"
+    } else {
+        "text-gray-400"
+    };
+
+    if *VALIDATING_IMPORT.read() {
+        return rsx! {
+            div { class: "text-sm {muted}", "Validating... nothing will be written." }
+        };
+    }
+
+    match IMPORT_VALIDATION.read().clone() {
+        None => rsx! {},
+        Some(Err(e)) => rsx! {
+            div {
+                class: "p-2 rounded bg-red-900 bg-opacity-30 text-red-400 text-sm",
+                "Validation failed: {e}"
+            }
+        },
+        Some(Ok(validation)) => {
+            let hidden = validation.failed - validation.failures.len();
+            rsx! {
+                div {
+                    class: "space-y-1",
+                    div {
+                        class: "text-sm",
+                        span { class: "text-green-500", "{validation.succeeded} rows would import" }
+                        if validation.failed > 0 {
+                            span { class: "{muted}", ", " }
+                            span { class: "text-red-500", "{validation.failed} would fail" }
+                        }
+                        span { class: "{muted}", " (rolled back)" }
+                    }
+                    if !validation.failures.is_empty() {
+                        div {
+                            class: "max-h-32 overflow-auto text-xs font-mono space-y-0.5",
+                            for (table, row, error) in validation.failures {
+                                div {
+                                    class: "text-red-400",
+                                    span { class: "{muted}", "{table} row {row}: " }
+                                    "{error}"
+                                }
+                            }
+                            if hidden > 0 {
+                                div { class: "{muted}", "...and {hidden} more" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Mapping, coercions and conflict handling for a file: the table's first saved
+/// profile, or columns matched by name.
+fn resolve_file_import(
+    data: &ImportData,
+    table: &str,
+    profiles: &[ImportProfile],
+) -> (
+    Vec<(usize, String)>,
+    HashMap<usize, ImportCoercion>,
+    ConflictStrategy,
+) {
+    if let Some(profile) = profiles.iter().find(|p| p.table == table) {
+        let (mapping, coercions) = profile.resolve(&data.columns);
+        return (mapping, coercions, profile.conflict);
+    }
+    let schema = SCHEMA.read();
+    let mapping = schema
+        .tables
+        .iter()
+        .find(|t| t.name == table)
+        .map(|t| import::auto_map_columns(&data.columns, &t.columns))
+        .unwrap_or_default();
+    (mapping, HashMap::new(), ConflictStrategy::default())
+}
+
+/// Table whose name matches the file name, e.g. `order_items.csv` -> `order_items`.
+fn guess_table(file_name: &str) -> String {
+    let stem = file_name
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .unwrap_or(file_name)
+        .to_lowercase();
+    SCHEMA
+        .read()
+        .tables
+        .iter()
+        .find(|t| t.name.to_lowercase() == stem)
+        .map(|t| t.name.clone())
+        .unwrap_or_default()
+}
+
+#[component]
+fn MultiTableImport(files: Vec<(String, ImportData)>, on_back: EventHandler<()>) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut tables = use_signal({
+        let files = files.clone();
+        move || {
+            files
+                .iter()
+                .map(|(name, _)| guess_table(name))
+                .collect::<Vec<_>>()
+        }
+    });
+    let profiles = use_signal(|| ImportProfileStore::new().load());
+    let mut defer_constraints = use_signal(|| false);
+
+    let text = if is_dark {
+        "text-gray-300"
+    } else {
+        "text-gray-700"
+    };
+    let muted = if is_dark {
+        "text-gray-500"
+    } else {
+        "text-gray-400"
+    };
+    let input_bg = if is_dark { "bg-gray-800" } else { "bg-gray-50" };
+    let input_border = if is_dark {
+        "border-gray-700"
+    } else {
+        "border-gray-300"
+    };
+
+    let is_postgres = matches!(
+        *CONNECTION.read(),
+        ConnectionState::Connected {
+            db_type: DatabaseType::PostgreSQL,
+            ..
+        }
+    );
+    let progress = *IMPORT_PROGRESS.read();
+    let import_message = IMPORT_MESSAGE.read().clone();
+    let schema_tables: Vec<String> = SCHEMA
+        .read()
+        .tables
+        .iter()
+        .map(|t| t.name.clone())
+        .collect();
+
+    let selected = tables.read().clone();
+    let all_selected = selected.iter().all(|t| !t.is_empty());
+    let (order, has_cycle) = fk_dependency_order(&selected, &SCHEMA.read());
+    let order_label = order
+        .iter()
+        .map(|&i| selected[i].clone())
+        .collect::<Vec<_>>()
+        .join(" \u{2192} ");
+    let summaries: Vec<String> = files
+        .iter()
+        .zip(selected.iter())
+        .map(|((_, data), table)| {
+            if table.is_empty() {
+                return "Pick a table".to_string();
+            }
+            let (mapping, _, _) = resolve_file_import(data, table, &profiles.read());
+            let source = if profiles.read().iter().any(|p| &p.table == table) {
+                "profile"
+            } else {
+                "matched by name"
+            };
+            format!(
+                "{} rows, {} of {} columns ({})",
+                data.rows.len(),
+                mapping.len(),
+                data.columns.len(),
+                source
+            )
+        })
+        .collect();
+    let total_rows: usize = files.iter().map(|(_, d)| d.rows.len()).sum();
+    let import_files = files.clone();
+    let validate_files = files.clone();
+
+    rsx! {
+        div {
+            class: "space-y-4",
+            p {
+                class: "{muted} text-sm",
+                "Choose the target table of each file. Parents are imported before the tables that reference them."
+            }
+
+            div {
+                class: "space-y-2 max-h-64 overflow-auto",
+                for (idx, (file_name, _)) in files.iter().enumerate() {
+                    div {
+                        class: "flex items-center space-x-3",
+                        div {
+                            class: "w-56 min-w-0",
+                            div { class: "text-sm {text} truncate", "{file_name}" }
+                            div { class: "text-xs {muted} truncate", "{summaries[idx]}" }
+                        }
+                        span { class: "{muted}", "\u{2192}" }
+                        select {
+                            class: "flex-1 px-2 py-1 rounded text-sm {input_bg} {input_border} {text} border",
+                            onchange: move |evt: FormEvent| {
+                                if let Some(table) = tables.write().get_mut(idx) {
+                                    *table = evt.value();
+                                }
+                            },
+                            option { value: "", "Select table..." }
+                            for table_name in schema_tables.iter() {
+                                option {
+                                    value: "{table_name}",
+                                    selected: selected[idx] == *table_name,
+                                    "{table_name}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if all_selected {
+                div {
+                    class: "text-sm {text}",
+                    span { class: "{muted}", "Import order: " }
+                    "{order_label}"
+                }
+            }
+            if all_selected && has_cycle {
+                div {
+                    class: "text-sm text-yellow-500",
+                    "These tables reference each other, so no order satisfies every foreign key. Defer constraints to import them together."
+                }
+            }
+
+            label {
+                class: "flex items-center space-x-2 text-sm {text}",
+                class: if is_postgres { "" } else { "opacity-50" },
+                title: if is_postgres {
+                    "Run the whole import in one transaction and check foreign keys at commit (only DEFERRABLE constraints)"
+                } else {
+                    "MySQL checks foreign keys immediately"
+                },
+                input {
+                    r#type: "checkbox",
+                    disabled: !is_postgres,
+                    checked: *defer_constraints.read(),
+                    onchange: move |evt: FormEvent| defer_constraints.set(evt.checked()),
+                }
+                span { "Defer constraints until commit" }
+            }
+
+            if let Some((inserted, total)) = progress {
+                div {
+                    class: "text-sm {text}",
+                    "Importing... {inserted}/{total}"
+                }
+            }
+
+            ImportValidationReport {}
+
+            div {
+                class: "flex justify-between",
+                button {
+                    class: "px-3 py-1 rounded {muted} hover:opacity-80",
+                    onclick: move |_| on_back.call(()),
+                    "Back"
+                }
+                div { class: "flex-1" }
+                if import_message.is_some() {
+                    button {
+                        class: "px-4 py-2 rounded bg-gray-700 text-white hover:bg-gray-600",
+                        onclick: move |_| close_dialog(),
+                        "Close"
+                    }
+                } else {
+                    button {
+                        class: "px-4 py-2 mr-2 rounded border {input_border} {text} hover:opacity-80 disabled:opacity-50",
+                        title: "Insert inside a transaction that is rolled back, and report failing rows",
+                        disabled: !all_selected || progress.is_some() || *VALIDATING_IMPORT.read(),
+                        onclick: move |_| {
+                            let selected = tables.read().clone();
+                            validate_import(ordered_imports(&validate_files, &selected, &profiles.read()));
+                        },
+                        "Validate only"
+                    }
+                    button {
+                        class: "px-4 py-2 rounded bg-green-700 text-white hover:bg-green-600 disabled:opacity-50",
+                        disabled: !all_selected || progress.is_some(),
+                        onclick: move |_| {
+                            let selected = tables.read().clone();
+                            let imports = ordered_imports(&import_files, &selected, &profiles.read());
+                            *IMPORT_MESSAGE.write() = None;
+                            *IMPORT_VALIDATION.write() = None;
+                            *IMPORT_PROGRESS.write() = Some((0, total_rows));
+                            send_db_request(DbRequest::ImportTables {
+                                tables: imports,
+                                batch_size: 100,
+                                defer_constraints: *defer_constraints.read(),
+                            });
+                        },
+                        "Import {total_rows} rows"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Table imports for the files in foreign key order.
+fn ordered_imports(
+    files: &[(String, ImportData)],
+    tables: &[String],
+    profiles: &[ImportProfile],
+) -> Vec<TableImport> {
+    let (order, _) = fk_dependency_order(tables, &SCHEMA.read());
+    order
+        .into_iter()
+        .map(|i| {
+            let (_, data) = &files[i];
+            let (mapping, coercions, conflict) = resolve_file_import(data, &tables[i], profiles);
+            table_import(&tables[i], &mapping, &coercions, conflict, data)
+        })
+        .collect()
 }
@@ -0,0 +1,319 @@
+use crate::config::{ImportProfileStore, ImportWatch, ImportWatchStore, WatchOutcome};
+use crate::import::{approve_watched_file, dismiss_watched_file};
+use crate::state::*;
+use dioxus::prelude::*;
+
+const SHOWN_LOG_ENTRIES: usize = 50;
+
+/// "2 files waiting" while watched files are queued for approval.
+pub fn queued_watched_files_label() -> Option<String> {
+    let queued = WATCHED_FILES.read().iter().filter(|f| !f.importing).count();
+    match queued {
+        0 => None,
+        1 => Some("1 file waiting".to_string()),
+        n => Some(format!("{} files waiting", n)),
+    }
+}
+
+#[component]
+pub fn ImportWatchesDialog() -> Element {
+    rsx! {
+        if *SHOW_IMPORT_WATCHES.read() {
+            ImportWatchesDialogContent {}
+        }
+    }
+}
+
+#[component]
+fn ImportWatchesDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut watches = use_signal(|| ImportWatchStore::new().load());
+    let profiles = use_signal(|| ImportProfileStore::new().load());
+    let mut folder = use_signal(String::new);
+    let mut profile_key = use_signal(String::new);
+    let mut auto_import = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let _ = IMPORT_WATCH_LOG_REVISION.read();
+    let log: Vec<_> = ImportWatchStore::new()
+        .load_log()
+        .into_iter()
+        .take(SHOWN_LOG_ENTRIES)
+        .map(|entry| (entry.processed_at.format("%b %d %H:%M").to_string(), entry))
+        .collect();
+    let files = WATCHED_FILES.read().clone();
+
+    let bg = if is_dark { "bg-gray-900" } else { "bg-white" };
+    let text = if is_dark {
+        "text-gray-300"
+    } else {
+        "text-gray-700"
+    };
+    let muted = if is_dark {
+        "text-gray-500"
+    } else {
+        "text-gray-400"
+    };
+    let input_bg = if is_dark { "bg-gray-800" } else { "bg-gray-50" };
+    let input_border = if is_dark {
+        "border-gray-700"
+    } else {
+        "border-gray-300"
+    };
+
+    let mut save = move |updated: Vec<ImportWatch>| match ImportWatchStore::new().save(&updated) {
+        Ok(()) => {
+            watches.set(updated);
+            error.set(None);
+        }
+        Err(e) => error.set(Some(e)),
+    };
+
+    // Profiles are chosen as "table/name"
+    let profile_options: Vec<(String, String)> = profiles
+        .read()
+        .iter()
+        .map(|p| {
+            (
+                format!("{}/{}", p.table, p.name),
+                format!("{} ({})", p.table, p.name),
+            )
+        })
+        .collect();
+    let can_add = !folder.read().is_empty() && !profile_key.read().is_empty();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50",
+            onclick: move |_| *SHOW_IMPORT_WATCHES.write() = false,
+
+            div {
+                class: "{bg} rounded-lg shadow-xl w-11/12 max-w-3xl overflow-auto p-6 space-y-5",
+                style: "max-height: 80vh",
+                onclick: move |evt| evt.stop_propagation(),
+
+                div {
+                    class: "flex items-center justify-between",
+                    div {
+                        h2 { class: "text-lg font-semibold {text}", "Watched Folders" }
+                        p {
+                            class: "text-xs {muted}",
+                            "New CSV, JSON and Parquet files are checked against the table's import profile while connected."
+                        }
+                    }
+                    button {
+                        class: "{muted} hover:opacity-80 text-xl",
+                        onclick: move |_| *SHOW_IMPORT_WATCHES.write() = false,
+                        "✕"
+                    }
+                }
+
+                if let Some(e) = error.read().as_ref() {
+                    div {
+                        class: "p-2 rounded bg-red-900 bg-opacity-30 text-red-400 text-sm",
+                        "{e}"
+                    }
+                }
+
+                div {
+                    class: "space-y-2",
+                    if watches.read().is_empty() {
+                        div { class: "text-sm {muted}", "No folders are watched yet." }
+                    }
+                    for (idx, watch) in watches.read().iter().cloned().enumerate() {
+                        div {
+                            key: "{watch.folder}-{watch.table}",
+                            class: "flex items-center space-x-3 text-sm",
+                            span { class: "flex-1 truncate font-mono {text}", title: "{watch.folder}", "{watch.folder}" }
+                            span { class: "{muted}", "→" }
+                            span { class: "w-40 truncate {text}", "{watch.table} ({watch.profile})" }
+                            label {
+                                class: "flex items-center space-x-1 {muted}",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: watch.auto_import,
+                                    onchange: move |evt: FormEvent| {
+                                        let mut updated = watches.read().clone();
+                                        updated[idx].auto_import = evt.checked();
+                                        save(updated);
+                                    },
+                                }
+                                span { "Auto-import" }
+                            }
+                            button {
+                                class: "{muted} hover:text-red-500",
+                                onclick: move |_| {
+                                    let mut updated = watches.read().clone();
+                                    updated.remove(idx);
+                                    save(updated);
+                                },
+                                "Remove"
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex items-center space-x-2",
+                    button {
+                        class: "px-3 py-1 rounded text-sm border {input_border} {text} hover:opacity-80 truncate max-w-xs",
+                        title: "{folder}",
+                        onclick: move |_| {
+                            spawn(async move {
+                                if let Some(picked) = rfd::AsyncFileDialog::new().pick_folder().await {
+                                    folder.set(picked.path().to_string_lossy().to_string());
+                                }
+                            });
+                        },
+                        if folder.read().is_empty() {
+                            "Choose Folder..."
+                        } else {
+                            "{folder}"
+                        }
+                    }
+                    select {
+                        class: "flex-1 px-2 py-1 rounded text-sm {input_bg} {input_border} {text} border",
+                        value: "{profile_key}",
+                        onchange: move |evt| profile_key.set(evt.value()),
+                        option { value: "", "Import profile..." }
+                        for (key, label) in profile_options {
+                            option { value: "{key}", "{label}" }
+                        }
+                    }
+                    label {
+                        class: "flex items-center space-x-1 text-sm {muted}",
+                        input {
+                            r#type: "checkbox",
+                            checked: *auto_import.read(),
+                            onchange: move |evt: FormEvent| auto_import.set(evt.checked()),
+                        }
+                        span { "Auto-import" }
+                    }
+                    button {
+                        class: "px-3 py-1 rounded text-sm bg-blue-600 text-white hover:bg-blue-500 disabled:opacity-50",
+                        disabled: !can_add,
+                        onclick: move |_| {
+                            let key = profile_key.read().clone();
+                            let Some(profile) = profiles
+                                .read()
+                                .iter()
+                                .find(|p| format!("{}/{}", p.table, p.name) == key)
+                                .cloned()
+                            else {
+                                return;
+                            };
+                            let mut updated = watches.read().clone();
+                            updated.push(ImportWatch {
+                                folder: folder.read().clone(),
+                                table: profile.table,
+                                profile: profile.name,
+                                auto_import: *auto_import.read(),
+                            });
+                            save(updated);
+                            folder.set(String::new());
+                            profile_key.set(String::new());
+                        },
+                        "Watch"
+                    }
+                }
+                if profiles.read().is_empty() {
+                    p {
+                        class: "text-xs {muted}",
+                        "Save an import profile from the import wizard first; it decides which files are accepted."
+                    }
+                }
+
+                if !files.is_empty() {
+                    div {
+                        class: "space-y-1",
+                        div { class: "text-xs uppercase {muted}", "Incoming files" }
+                        for file in files {
+                            WatchedFileRow { key: "{file.path.display()}", file: file.clone() }
+                        }
+                    }
+                }
+
+                div {
+                    class: "space-y-1",
+                    div {
+                        class: "flex items-center justify-between",
+                        span { class: "text-xs uppercase {muted}", "Processed files" }
+                        if !log.is_empty() {
+                            button {
+                                class: "text-xs {muted} hover:text-red-500",
+                                onclick: move |_| {
+                                    ImportWatchStore::new().clear_log();
+                                    *IMPORT_WATCH_LOG_REVISION.write() += 1;
+                                },
+                                "Clear log"
+                            }
+                        }
+                    }
+                    if log.is_empty() {
+                        div { class: "text-sm {muted}", "Nothing processed yet." }
+                    }
+                    for (at, entry) in log {
+                        div {
+                            class: "flex items-center space-x-3 text-xs",
+                            span { class: "w-28 {muted}", "{at}" }
+                            span { class: "flex-1 truncate font-mono {text}", title: "{entry.path}", "{entry.path}" }
+                            span { class: "w-32 truncate {muted}", "{entry.table}" }
+                            match entry.outcome {
+                                WatchOutcome::Imported(rows) => rsx! {
+                                    span { class: "text-green-500", "{rows} rows imported" }
+                                },
+                                WatchOutcome::Failed(e) => rsx! {
+                                    span { class: "text-red-400 truncate max-w-xs", title: "{e}", "{e}" }
+                                },
+                                WatchOutcome::Dismissed => rsx! {
+                                    span { class: "{muted}", "Dismissed" }
+                                },
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn WatchedFileRow(file: crate::import::WatchedFile) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let text = if is_dark {
+        "text-gray-300"
+    } else {
+        "text-gray-700"
+    };
+    let muted = if is_dark {
+        "text-gray-500"
+    } else {
+        "text-gray-400"
+    };
+    let name = file.file_name();
+    let rows = file.import.rows.len();
+    let approve_path = file.path.clone();
+    let dismiss_path = file.path.clone();
+
+    rsx! {
+        div {
+            class: "flex items-center space-x-3 text-sm",
+            span { class: "flex-1 truncate font-mono {text}", "{name}" }
+            span { class: "{muted}", "{rows} rows → {file.import.table}" }
+            if file.importing {
+                span { class: "{muted}", "Importing..." }
+            } else {
+                button {
+                    class: "px-2 py-0.5 rounded bg-green-700 text-white hover:bg-green-600",
+                    onclick: move |_| approve_watched_file(&approve_path),
+                    "Import"
+                }
+                button {
+                    class: "{muted} hover:text-red-500",
+                    onclick: move |_| dismiss_watched_file(&dismiss_path),
+                    "Dismiss"
+                }
+            }
+        }
+    }
+}
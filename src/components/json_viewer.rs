@@ -1,15 +1,54 @@
+use crate::components::cell_renderers::{
+    decode_image_data_uri, detect_cell_kind, image_extension_for_mime, image_extension_from_url,
+    is_image_value, CellKind,
+};
 use crate::state::*;
 use dioxus::prelude::*;
 
+/// Largest token grid compared word by word; beyond it the differing middle is shown as replaced.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Show a cell's full value in the detail modal, or diff it against the
+/// previous one when a comparison cell is being picked.
+pub fn open_cell_detail(value: String) {
+    if *PICKING_COMPARE_CELL.read() {
+        *PICKING_COMPARE_CELL.write() = false;
+        *JSON_VIEWER_COMPARE.write() = Some(value);
+    } else {
+        *JSON_VIEWER_CONTENT.write() = value;
+        *JSON_VIEWER_COMPARE.write() = None;
+    }
+    *SHOW_JSON_VIEWER.write() = true;
+}
+
+/// Hide the modal until another cell is double-clicked.
+fn start_cell_compare() {
+    *PICKING_COMPARE_CELL.write() = true;
+    *SHOW_JSON_VIEWER.write() = false;
+}
+
+pub fn cancel_cell_compare() {
+    *PICKING_COMPARE_CELL.write() = false;
+}
+
 #[component]
 pub fn JsonViewer() -> Element {
-    let show = *SHOW_JSON_VIEWER.read();
+    rsx! {
+        if *SHOW_JSON_VIEWER.read() {
+            JsonViewerContent {}
+        }
+    }
+}
+
+#[component]
+fn JsonViewerContent() -> Element {
     let content = JSON_VIEWER_CONTENT.read().clone();
+    let compare = JSON_VIEWER_COMPARE.read().clone();
     let is_dark = *IS_DARK_MODE.read();
-
-    if !show {
-        return rsx! {};
-    }
+    let mut preview_url = use_signal(|| false);
+    let mut dimensions = use_signal(|| None::<(u64, u64)>);
+    let mut image_error = use_signal(|| None::<String>);
+    let mut save_status = use_signal(|| None::<Result<String, String>>);
 
     let bg_class = if is_dark {
         "bg-black/80"
@@ -28,15 +67,35 @@ pub fn JsonViewer() -> Element {
         "text-gray-700"
     };
 
-    // Try to parse and pretty-print JSON
-    let formatted = if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-        serde_json::to_string_pretty(&json).unwrap_or(content.clone())
-    } else {
-        content.clone()
-    };
+    let formatted = pretty_json(&content);
 
     let is_json = serde_json::from_str::<serde_json::Value>(&content).is_ok();
 
+    // Avatar URLs often have no extension, so any other URL can be previewed on request
+    let is_url = detect_cell_kind(&content) == CellKind::Url;
+    let image_src = (is_image_value(&content) || (is_url && *preview_url.read()))
+        .then(|| content.trim().to_string());
+    let is_data_uri = content.trim().starts_with("data:");
+    let save_src = image_src.clone().unwrap_or_default();
+    let diff = compare
+        .as_ref()
+        .map(|other| word_diff(&pretty_json(&content), &pretty_json(other)));
+    let is_comparing = diff.is_some();
+    let title = if is_comparing {
+        "Compare Cells"
+    } else if image_src.is_some() {
+        "Image"
+    } else if is_json {
+        "JSON Viewer"
+    } else {
+        "Cell Content"
+    };
+    let muted_text = if is_dark {
+        "text-gray-500"
+    } else {
+        "text-gray-400"
+    };
+
     rsx! {
         div {
             class: "fixed inset-0 {bg_class} flex items-center justify-center z-50",
@@ -52,7 +111,7 @@ pub fn JsonViewer() -> Element {
 
                     h3 {
                         class: "text-lg font-medium {text_color}",
-                        if is_json { "JSON Viewer" } else { "Cell Content" }
+                        "{title}"
                     }
 
                     button {
@@ -77,7 +136,60 @@ pub fn JsonViewer() -> Element {
                 div {
                     class: "flex-1 overflow-auto p-4",
 
-                    if is_json {
+                    if let Some(diff) = diff {
+                        div {
+                            class: "flex items-center space-x-3 text-xs mb-3",
+                            span { class: "px-1 bg-red-500/30 line-through", "first cell" }
+                            span { class: "px-1 bg-green-500/30", "second cell" }
+                        }
+                        pre {
+                            class: "font-mono text-sm {text_color} whitespace-pre-wrap break-all",
+                            for (op, text) in diff {
+                                match op {
+                                    DiffOp::Same => rsx! { span { "{text}" } },
+                                    DiffOp::Removed => rsx! {
+                                        span { class: "bg-red-500/30 line-through", "{text}" }
+                                    },
+                                    DiffOp::Added => rsx! {
+                                        span { class: "bg-green-500/30", "{text}" }
+                                    },
+                                }
+                            }
+                        }
+                    } else if let Some(src) = image_src {
+                        div {
+                            class: "flex flex-col items-center space-y-3",
+                            img {
+                                id: "cell-image-preview",
+                                class: "max-w-full max-h-[55vh] object-contain border {border_color}",
+                                src: "{src}",
+                                onload: move |_| {
+                                    spawn(async move {
+                                        dimensions.set(measure_preview().await);
+                                    });
+                                },
+                                onerror: move |_| {
+                                    image_error.set(Some("The image could not be loaded".to_string()));
+                                },
+                            }
+                            if let Some(error) = image_error.read().as_ref() {
+                                div { class: "text-sm text-red-500", "{error}" }
+                            } else if let Some((width, height)) = *dimensions.read() {
+                                div { class: "text-sm {text_color}", "{width} × {height} px" }
+                            }
+                            if is_data_uri {
+                                div {
+                                    class: "text-xs {muted_text}",
+                                    "Data URI · {content.len()} characters"
+                                }
+                            } else {
+                                div {
+                                    class: "text-xs font-mono {muted_text} break-all",
+                                    "{content}"
+                                }
+                            }
+                        }
+                    } else if is_json {
                         pre {
                             class: "font-mono text-sm {text_color} whitespace-pre-wrap",
                             "{formatted}"
@@ -94,6 +206,74 @@ pub fn JsonViewer() -> Element {
                 div {
                     class: "flex items-center justify-end px-4 py-3 border-t {border_color} space-x-2",
 
+                    match save_status.read().as_ref() {
+                        Some(Ok(message)) => rsx! {
+                            span { class: "flex-1 text-xs text-green-500", "{message}" }
+                        },
+                        Some(Err(error)) => rsx! {
+                            span { class: "flex-1 text-xs text-red-500 truncate", "{error}" }
+                        },
+                        None => rsx! {},
+                    }
+
+                    if is_url && !is_image_value(&content) {
+                        button {
+                            class: "px-3 py-1.5 text-sm rounded transition-colors",
+                            class: if is_dark {
+                                "bg-gray-800 hover:bg-gray-700 text-gray-300"
+                            } else {
+                                "bg-gray-100 hover:bg-gray-200 text-gray-700"
+                            },
+                            onclick: move |_| {
+                                let show = !*preview_url.read();
+                                preview_url.set(show);
+                                dimensions.set(None);
+                                image_error.set(None);
+                            },
+                            if *preview_url.read() { "Show as text" } else { "Preview as image" }
+                        }
+                    }
+
+                    if !save_src.is_empty() {
+                        button {
+                            class: "px-3 py-1.5 text-sm rounded transition-colors",
+                            class: if is_dark {
+                                "bg-gray-800 hover:bg-gray-700 text-gray-300"
+                            } else {
+                                "bg-gray-100 hover:bg-gray-200 text-gray-700"
+                            },
+                            onclick: move |_| {
+                                let source = save_src.clone();
+                                spawn(async move {
+                                    match save_image(&source).await {
+                                        Ok(Some(path)) => save_status.set(Some(Ok(format!("Saved to {}", path)))),
+                                        Ok(None) => {}
+                                        Err(e) => save_status.set(Some(Err(e))),
+                                    }
+                                });
+                            },
+                            "Save image"
+                        }
+                    }
+
+                    button {
+                        class: "px-3 py-1.5 text-sm rounded transition-colors",
+                        class: if is_dark {
+                            "bg-gray-800 hover:bg-gray-700 text-gray-300"
+                        } else {
+                            "bg-gray-100 hover:bg-gray-200 text-gray-700"
+                        },
+                        title: if is_comparing { "" } else { "Double-click another result cell to diff against this one" },
+                        onclick: move |_| {
+                            if is_comparing {
+                                *JSON_VIEWER_COMPARE.write() = None;
+                            } else {
+                                start_cell_compare();
+                            }
+                        },
+                        if is_comparing { "Back" } else { "Compare with another cell" }
+                    }
+
                     button {
                         class: "px-3 py-1.5 text-sm rounded transition-colors",
                         class: if is_dark {
@@ -121,3 +301,168 @@ pub fn JsonViewer() -> Element {
         }
     }
 }
+
+/// Pretty-print JSON, leaving anything else as is.
+fn pretty_json(content: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(content)
+        .ok()
+        .and_then(|json| serde_json::to_string_pretty(&json).ok())
+        .unwrap_or_else(|| content.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffOp {
+    Same,
+    Removed,
+    Added,
+}
+
+/// Split into words, whitespace runs and single punctuation characters.
+fn diff_tokens(text: &str) -> Vec<&str> {
+    fn class(c: char) -> u8 {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    }
+
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous = None;
+    for (i, c) in text.char_indices() {
+        let kind = class(c);
+        if i > start && (previous != Some(kind) || kind == 2) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        previous = Some(kind);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// Word-level diff of two texts, with consecutive tokens of the same kind merged.
+pub fn word_diff(old: &str, new: &str) -> Vec<(DiffOp, String)> {
+    let old_tokens = diff_tokens(old);
+    let new_tokens = diff_tokens(new);
+    let prefix = old_tokens
+        .iter()
+        .zip(&new_tokens)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_tokens[prefix..]
+        .iter()
+        .rev()
+        .zip(new_tokens[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old_tokens[prefix..old_tokens.len() - suffix];
+    let b = &new_tokens[prefix..new_tokens.len() - suffix];
+
+    let mut ops: Vec<(DiffOp, &str)> = old_tokens[..prefix]
+        .iter()
+        .map(|t| (DiffOp::Same, *t))
+        .collect();
+    if a.len() * b.len() > MAX_DIFF_CELLS {
+        ops.extend(a.iter().map(|t| (DiffOp::Removed, *t)));
+        ops.extend(b.iter().map(|t| (DiffOp::Added, *t)));
+    } else {
+        // lcs[i * width + j] is the longest common subsequence of a[i..] and b[j..]
+        let width = b.len() + 1;
+        let mut lcs = vec![0u32; (a.len() + 1) * width];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i * width + j] = if a[i] == b[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() && j < b.len() {
+            if a[i] == b[j] {
+                ops.push((DiffOp::Same, a[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                ops.push((DiffOp::Removed, a[i]));
+                i += 1;
+            } else {
+                ops.push((DiffOp::Added, b[j]));
+                j += 1;
+            }
+        }
+        ops.extend(a[i..].iter().map(|t| (DiffOp::Removed, *t)));
+        ops.extend(b[j..].iter().map(|t| (DiffOp::Added, *t)));
+    }
+    ops.extend(
+        old_tokens[old_tokens.len() - suffix..]
+            .iter()
+            .map(|t| (DiffOp::Same, *t)),
+    );
+
+    let mut merged: Vec<(DiffOp, String)> = Vec::new();
+    for (op, token) in ops {
+        match merged.last_mut() {
+            Some((last, text)) if *last == op => text.push_str(token),
+            _ => merged.push((op, token.to_string())),
+        }
+    }
+    merged
+}
+
+/// Natural size of the previewed image.
+async fn measure_preview() -> Option<(u64, u64)> {
+    let mut eval = document::eval(
+        r#"
+        const img = document.getElementById('cell-image-preview');
+        dioxus.send(img ? [img.naturalWidth, img.naturalHeight] : null);
+    "#,
+    );
+    let size = eval.recv::<serde_json::Value>().await.ok()?;
+    Some((size.get(0)?.as_u64()?, size.get(1)?.as_u64()?))
+}
+
+/// Decode or download the image and write it where the user picks.
+/// Returns the saved path, or None when the dialog was cancelled.
+async fn save_image(source: &str) -> Result<Option<String>, String> {
+    let (extension, bytes) = if source.starts_with("data:") {
+        decode_image_data_uri(source)?
+    } else {
+        let response = reqwest::get(source).await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("{} returned {}", source, response.status()));
+        }
+        let extension = image_extension_from_url(source)
+            .map(str::to_string)
+            .or_else(|| {
+                response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|t| t.split(';').next())
+                    .and_then(|t| t.strip_prefix("image/"))
+                    .map(image_extension_for_mime)
+            })
+            .unwrap_or_else(|| "png".to_string());
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        (extension, bytes.to_vec())
+    };
+
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .add_filter("Images", &[extension.as_str()])
+        .set_file_name(format!("image.{}", extension))
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+    file.write(&bytes).await.map_err(|e| e.to_string())?;
+    Ok(Some(file.path().display().to_string()))
+}
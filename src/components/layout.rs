@@ -1,6 +1,7 @@
 use crate::components::*;
-use crate::config::{SessionState, SessionStore};
+use crate::config::{DisplayStore, SessionState, SessionStore};
 use crate::state::*;
+use dioxus::desktop::use_muda_event_handler;
 use dioxus::prelude::*;
 
 const APP_STYLE: &str = r#"
@@ -67,6 +68,27 @@ const APP_STYLE: &str = r#"
     }
 "#;
 
+const ZOOM_STEP: f64 = 0.1;
+/// Extra zoom on top of the UI zoom while presenting
+const PRESENTATION_ZOOM: f64 = 1.3;
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 2.0;
+
+/// Set and persist the zoom factor of the whole UI.
+pub fn set_ui_zoom(zoom: f64) {
+    // Round so repeated steps land on whole percentages
+    let zoom = (zoom.clamp(MIN_ZOOM, MAX_ZOOM) * 100.0).round() / 100.0;
+    let mut settings = DISPLAY_SETTINGS.read().clone();
+    if settings.ui_zoom == zoom {
+        return;
+    }
+    settings.ui_zoom = zoom;
+    if let Err(e) = DisplayStore::new().save(&settings) {
+        tracing::error!("Failed to save display settings: {}", e);
+    }
+    *DISPLAY_SETTINGS.write() = settings;
+}
+
 #[component]
 pub fn AppLayout() -> Element {
     use_hook(|| {
@@ -75,6 +97,14 @@ pub fn AppLayout() -> Element {
         });
     });
 
+    use_muda_event_handler(|event| match event.id().0.as_str() {
+        MENU_OPEN_FILE => open_sql_files(),
+        MENU_SAVE_FILE => save_active_tab(false),
+        MENU_SAVE_FILE_AS => save_active_tab(true),
+        MENU_EXPORT_DIAGNOSTICS => export_diagnostics(),
+        _ => {}
+    });
+
     // Initialize system theme detection
     use_effect(|| {
         spawn(async move {
@@ -101,6 +131,7 @@ pub fn AppLayout() -> Element {
     };
 
     let editor_height = *EDITOR_PANEL_HEIGHT.read();
+    let presenting = *PRESENTATION_MODE.read();
     let is_resizing = *IS_RESIZING_PANELS.read();
     let is_dark = *IS_DARK_MODE.read();
 
@@ -110,6 +141,17 @@ pub fn AppLayout() -> Element {
         });
     });
 
+    // Scale the webview rather than the CSS so the editor keeps its own font size setting
+    use_effect(|| {
+        let mut zoom = DISPLAY_SETTINGS.read().ui_zoom;
+        if *PRESENTATION_MODE.read() {
+            zoom *= PRESENTATION_ZOOM;
+        }
+        if let Err(e) = dioxus::desktop::window().webview.zoom(zoom) {
+            tracing::warn!("Failed to zoom the UI: {}", e);
+        }
+    });
+
     // Save session state when UI changes
     use_effect(move || {
         let is_resizing = *IS_RESIZING_PANELS.read();
@@ -157,13 +199,29 @@ pub fn AppLayout() -> Element {
 
         div {
             class: "h-screen w-screen flex flex-col overflow-hidden {theme_class}",
-            // Global keyboard shortcut for quick switcher
+            // Global keyboard shortcuts for the quick switcher, workspace search, opening
+            // and saving files and UI zoom
             onkeydown: move |e: KeyboardEvent| {
-                if e.key() == Key::Character("p".to_string()) &&
-                   e.modifiers().contains(Modifiers::CONTROL) {
-                    e.prevent_default();
-                    *SHOW_QUICK_SWITCHER.write() = true;
+                if !e.modifiers().contains(Modifiers::CONTROL) {
+                    return;
                 }
+                let Key::Character(key) = e.key() else {
+                    return;
+                };
+                let zoom = DISPLAY_SETTINGS.read().ui_zoom;
+                match key.as_str() {
+                    "p" => *SHOW_QUICK_SWITCHER.write() = true,
+                    "o" => open_sql_files(),
+                    "s" | "S" => save_active_tab(e.modifiers().contains(Modifiers::SHIFT)),
+                    "f" | "F" if e.modifiers().contains(Modifiers::SHIFT) => {
+                        *SHOW_WORKSPACE_SEARCH.write() = true
+                    }
+                    "=" | "+" => set_ui_zoom(zoom + ZOOM_STEP),
+                    "-" => set_ui_zoom(zoom - ZOOM_STEP),
+                    "0" => set_ui_zoom(1.0),
+                    _ => return,
+                }
+                e.prevent_default();
             },
             // Global mouse events for resizing
             onmousemove: move |e: MouseEvent| {
@@ -182,15 +240,42 @@ pub fn AppLayout() -> Element {
             onmouseleave: move |_| {
                 *IS_RESIZING_PANELS.write() = false;
             },
+            // .sql files and workbooks dropped anywhere on the window open in new tabs
+            ondragover: move |e: DragEvent| e.prevent_default(),
+            ondrop: move |e: DragEvent| {
+                let paths: Vec<_> = e
+                    .files()
+                    .iter()
+                    .map(|file| file.path())
+                    .filter(|path| crate::instance::is_openable(path))
+                    .collect();
+                if paths.is_empty() {
+                    return;
+                }
+                e.prevent_default();
+                for path in paths {
+                    if let Err(e) = crate::services::open_file(&path) {
+                        tracing::error!("Failed to open {}: {}", path.display(), e);
+                    }
+                }
+            },
 
-            MenuBar {}
+            if presenting {
+                PresentationBar {}
+            } else {
+                MenuBar {}
+            }
 
             div {
                 class: "flex-1 flex overflow-hidden",
-                Sidebar {}
+                if !presenting {
+                    Sidebar {}
+                }
                 div {
                     class: "flex-1 flex flex-col min-w-0",
-                    LlmPanel {}
+                    if !presenting {
+                        LlmPanel {}
+                    }
                     // SQL Editor with fixed height
                     div {
                         class: "flex flex-col border-b min-h-0",
@@ -200,7 +285,9 @@ pub fn AppLayout() -> Element {
                     }
 
                     // AI Results Panel (collapsible)
-                    AiResultsPanel {}
+                    if !presenting {
+                        AiResultsPanel {}
+                    }
 
                     // Resize handle
                     div {
@@ -218,12 +305,14 @@ pub fn AppLayout() -> Element {
                     div {
                         class: "flex-1 flex flex-col min-h-0",
                         class: if is_dark { "bg-black" } else { "bg-white" },
-                        ResultsTable {}
+                        ResultsArea {}
                     }
                 }
             }
 
-            StatusBar {}
+            if !presenting {
+                StatusBar {}
+            }
             QuickSwitcher {}
         }
 
@@ -240,6 +329,111 @@ pub fn AppLayout() -> Element {
         ExecutionPlanDialog {}
 
         ImportDialog {}
+
+        ImportWatchesDialog {}
+
+        ExportDialog {}
+
+        ServerVariablesDialog {}
+
+        ExtensionsDialog {}
+
+        ChangeCaptureDialog {}
+
+        BulkEditDialog {}
+
+        QueryTestsDialog {}
+
+        SeedScriptDialog {}
+
+        AnonymizedCopyDialog {}
+
+        ParameterPromptDialog {}
+
+        ColumnDistributionDialog {}
+
+        WorkspaceSearchDialog {}
+
+        ReplaceValuesDialog {}
+
+        RowHistoryDialog {}
+
+        ReferencingRowsDialog {}
+
+        UsageStatsDialog {}
+
+        DeepLinkDialog {}
+
+        DialectConversionDialog {}
+
+        RunningQueryDialog {}
+
+        ProductionConfirmDialog {}
+
+        DestructiveConfirmDialog {}
+
+        HostKeyConfirmDialog {}
+
+        EditConflictDialog {}
+
+        RowDeleteConfirmDialog {}
+
+        CommentDialog {}
+
+        UnsavedTabDialog {}
+
+        SnippetsDialog {}
+
+        SystemTray {}
+    }
+}
+
+/// The results of the active tab, or the welcome screen while disconnected with nothing
+/// to show yet. Kept apart so typing in the editor doesn't re-render the whole layout.
+#[component]
+fn ResultsArea() -> Element {
+    let disconnected = matches!(
+        *CONNECTION.read(),
+        ConnectionState::Disconnected | ConnectionState::Error(_)
+    );
+    let empty = EDITOR_TABS
+        .read()
+        .active_tab()
+        .is_none_or(|t| t.result.is_none() && t.last_error.is_none());
+    rsx! {
+        if disconnected && empty {
+            WelcomeScreen {}
+        } else {
+            ResultsTable {}
+        }
+    }
+}
+
+/// Replaces the menu bar while presenting: the connection in use and a way back.
+#[component]
+fn PresentationBar() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let border_class = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-200"
+    };
+    let button_class = if is_dark {
+        "text-gray-400 hover:text-white hover:bg-gray-900"
+    } else {
+        "text-gray-600 hover:text-gray-900 hover:bg-gray-100"
+    };
+
+    rsx! {
+        div {
+            class: "h-10 border-b {border_class} flex items-center justify-between px-3",
+            ConnectionStatus {}
+            button {
+                class: "px-3 py-1.5 text-sm rounded transition-colors {button_class}",
+                onclick: move |_| *PRESENTATION_MODE.write() = false,
+                "Exit presentation"
+            }
+        }
     }
 }
 
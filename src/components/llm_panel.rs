@@ -63,11 +63,17 @@ pub fn LlmPanel() -> Element {
             *LLM_GENERATING.write() = true;
             *LLM_STATUS.write() = LlmStatus::None;
 
-            let _ = llm_tx_clone.send(crate::llm::LlmRequest::Generate {
-                prompt: prompt_text,
-                schema,
-                config,
-            });
+            let tab_id = EDITOR_TABS.read().active_tab_id.clone().unwrap_or_default();
+            send_llm_request(
+                &llm_tx_clone,
+                crate::llm::LlmRequest::Generate {
+                    prompt: prompt_text,
+                    schema,
+                    dialect: *CURRENT_DIALECT.read(),
+                    config,
+                },
+                LlmOrigin::Tab(tab_id),
+            );
         }
     };
 
@@ -84,15 +90,24 @@ pub fn LlmPanel() -> Element {
                 *LLM_GENERATING.write() = true;
                 *LLM_STATUS.write() = LlmStatus::None;
 
-                let _ = llm_tx_clone2.send(crate::llm::LlmRequest::Generate {
-                    prompt: prompt_text,
-                    schema,
-                    config,
-                });
+                let tab_id = EDITOR_TABS.read().active_tab_id.clone().unwrap_or_default();
+                send_llm_request(
+                    &llm_tx_clone2,
+                    crate::llm::LlmRequest::Generate {
+                        prompt: prompt_text,
+                        schema,
+                        dialect: *CURRENT_DIALECT.read(),
+                        config,
+                    },
+                    LlmOrigin::Tab(tab_id),
+                );
             }
         }
     };
 
+    let llm_tx_cancel = llm_tx.clone();
+    let on_cancel = move |_| cancel_llm_request(&llm_tx_cancel);
+
     let on_settings_click = move |_| {
         *SHOW_LLM_SETTINGS.write() = true;
     };
@@ -204,6 +219,15 @@ pub fn LlmPanel() -> Element {
                     div {
                         class: "animate-spin h-5 w-5 border-2 border-blue-500 border-t-transparent rounded-full",
                     }
+                    button {
+                        class: if is_dark {
+                            "px-3 py-2 text-sm text-gray-400 hover:text-white transition-colors"
+                        } else {
+                            "px-3 py-2 text-sm text-gray-600 hover:text-gray-900 transition-colors"
+                        },
+                        onclick: on_cancel,
+                        "Cancel"
+                    }
                 }
             }
 
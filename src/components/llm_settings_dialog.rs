@@ -1,4 +1,7 @@
-use crate::llm::{LlmConfig, LlmProvider};
+use crate::config::LlmSpendStore;
+use crate::llm::{
+    LlmAction, LlmConfig, LlmProvider, DEFAULT_LLM_RETRIES, DEFAULT_LLM_TIMEOUT_SECS,
+};
 use crate::state::*;
 use dioxus::prelude::*;
 
@@ -42,6 +45,21 @@ fn LlmSettingsDialogContent() -> Element {
     let mut ollama_model = use_signal(|| LLM_CONFIG.read().ollama_model.clone());
     let mut openrouter_key = use_signal(|| LLM_CONFIG.read().openrouter_key.clone());
     let mut openrouter_model = use_signal(|| LLM_CONFIG.read().openrouter_model.clone());
+    let mut monthly_budget = use_signal(|| {
+        LLM_CONFIG
+            .read()
+            .monthly_budget_usd
+            .map(|b| b.to_string())
+            .unwrap_or_default()
+    });
+    let month_spend = use_hook(|| LlmSpendStore::new().current_month());
+    let mut temperature = use_signal(|| optional_text(LLM_CONFIG.read().temperature));
+    let mut max_tokens = use_signal(|| optional_text(LLM_CONFIG.read().max_tokens));
+    let mut top_p = use_signal(|| optional_text(LLM_CONFIG.read().top_p));
+    let mut timeout_secs = use_signal(|| optional_text(LLM_CONFIG.read().timeout_secs));
+    let mut max_retries = use_signal(|| optional_text(LLM_CONFIG.read().max_retries));
+    let mut system_prompts = use_signal(|| LLM_CONFIG.read().system_prompts.clone());
+    let mut prompt_action = use_signal(|| LlmAction::Generate);
 
     let text_color = if is_dark {
         "text-gray-300"
@@ -61,12 +79,58 @@ fn LlmSettingsDialogContent() -> Element {
     };
 
     let on_save = move |_| {
+        let budget_text = monthly_budget.read().trim().to_string();
+        let monthly_budget_usd = if budget_text.is_empty() {
+            None
+        } else {
+            match budget_text.trim_start_matches('$').parse::<f64>() {
+                Ok(budget) if budget > 0.0 => Some(budget),
+                _ => {
+                    *LLM_STATUS.write() =
+                        LlmStatus::Error("Monthly budget must be a positive amount".into());
+                    return;
+                }
+            }
+        };
+        let parameters = (
+            parse_optional::<f32>(&temperature.read(), "Temperature"),
+            parse_optional::<u32>(&max_tokens.read(), "Max tokens"),
+            parse_optional::<f32>(&top_p.read(), "Top P"),
+        );
+        let (temperature, max_tokens, top_p) = match parameters {
+            (Ok(t), Ok(m), Ok(p)) => (t, m, p),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                *LLM_STATUS.write() = LlmStatus::Error(e);
+                return;
+            }
+        };
+        let limits = (
+            parse_optional::<u64>(&timeout_secs.read(), "Timeout"),
+            parse_optional::<u32>(&max_retries.read(), "Retries"),
+        );
+        let (timeout_secs, max_retries) = match limits {
+            (Ok(t), Ok(r)) => (t, r),
+            (Err(e), _) | (_, Err(e)) => {
+                *LLM_STATUS.write() = LlmStatus::Error(e);
+                return;
+            }
+        };
+        let mut system_prompts = system_prompts.read().clone();
+        system_prompts.retain(|_, prompt| !prompt.trim().is_empty());
+
         let new_config = LlmConfig {
             provider: provider.read().clone(),
             ollama_url: ollama_url.read().clone(),
             ollama_model: ollama_model.read().clone(),
             openrouter_key: openrouter_key.read().clone(),
             openrouter_model: openrouter_model.read().clone(),
+            monthly_budget_usd,
+            temperature,
+            max_tokens,
+            top_p,
+            system_prompts,
+            timeout_secs,
+            max_retries,
         };
 
         if let Err(e) = new_config.save() {
@@ -87,6 +151,20 @@ fn LlmSettingsDialogContent() -> Element {
     let ollama_model_value = ollama_model.read().clone();
     let openrouter_key_value = openrouter_key.read().clone();
     let openrouter_model_value = openrouter_model.read().clone();
+    let monthly_budget_value = monthly_budget.read().clone();
+    let month_cost = format!("${:.2}", month_spend.cost_usd);
+    let month_tokens = month_spend.total_tokens();
+    let temperature_value = temperature.read().clone();
+    let max_tokens_value = max_tokens.read().clone();
+    let top_p_value = top_p.read().clone();
+    let timeout_value = timeout_secs.read().clone();
+    let retries_value = max_retries.read().clone();
+    let current_action = *prompt_action.read();
+    let system_prompt_value = system_prompts
+        .read()
+        .get(&current_action)
+        .cloned()
+        .unwrap_or_default();
 
     rsx! {
         h2 {
@@ -209,9 +287,152 @@ fn LlmSettingsDialogContent() -> Element {
                         },
                     }
                 }
+
+                // Monthly budget
+                div {
+                    class: "mb-4",
+
+                    label {
+                        class: "block text-sm font-medium {text_color} mb-2",
+                        "Monthly budget (USD)"
+                    }
+
+                    input {
+                        class: "w-full px-3 py-2 text-sm border rounded {input_bg} {input_border} {text_color} focus:outline-none focus:ring-2 focus:ring-blue-500",
+                        r#type: "text",
+                        placeholder: "No limit",
+                        value: "{monthly_budget_value}",
+                        oninput: move |e| {
+                            monthly_budget.set(e.value().clone());
+                        },
+                    }
+
+                    p {
+                        class: "mt-1 text-xs text-gray-500",
+                        "Spent this month: {month_cost} ({month_tokens} tokens). A warning shows from 80% of the budget."
+                    }
+                }
             },
         }
 
+        // Request parameters, left blank to use the provider's defaults
+        div {
+            class: "mb-4 grid grid-cols-3 gap-3",
+
+            div {
+                label {
+                    class: "block text-sm font-medium {text_color} mb-2",
+                    "Temperature"
+                }
+                input {
+                    class: "w-full px-3 py-2 text-sm border rounded {input_bg} {input_border} {text_color} focus:outline-none focus:ring-2 focus:ring-blue-500",
+                    r#type: "text",
+                    placeholder: "Default",
+                    value: "{temperature_value}",
+                    oninput: move |e| temperature.set(e.value()),
+                }
+            }
+
+            div {
+                label {
+                    class: "block text-sm font-medium {text_color} mb-2",
+                    "Max tokens"
+                }
+                input {
+                    class: "w-full px-3 py-2 text-sm border rounded {input_bg} {input_border} {text_color} focus:outline-none focus:ring-2 focus:ring-blue-500",
+                    r#type: "text",
+                    placeholder: "Default",
+                    value: "{max_tokens_value}",
+                    oninput: move |e| max_tokens.set(e.value()),
+                }
+            }
+
+            div {
+                label {
+                    class: "block text-sm font-medium {text_color} mb-2",
+                    "Top P"
+                }
+                input {
+                    class: "w-full px-3 py-2 text-sm border rounded {input_bg} {input_border} {text_color} focus:outline-none focus:ring-2 focus:ring-blue-500",
+                    r#type: "text",
+                    placeholder: "Default",
+                    value: "{top_p_value}",
+                    oninput: move |e| top_p.set(e.value()),
+                }
+            }
+
+            div {
+                label {
+                    class: "block text-sm font-medium {text_color} mb-2",
+                    "Timeout (s)"
+                }
+                input {
+                    class: "w-full px-3 py-2 text-sm border rounded {input_bg} {input_border} {text_color} focus:outline-none focus:ring-2 focus:ring-blue-500",
+                    r#type: "text",
+                    placeholder: "{DEFAULT_LLM_TIMEOUT_SECS}",
+                    value: "{timeout_value}",
+                    oninput: move |e| timeout_secs.set(e.value()),
+                }
+            }
+
+            div {
+                label {
+                    class: "block text-sm font-medium {text_color} mb-2",
+                    "Retries"
+                }
+                input {
+                    class: "w-full px-3 py-2 text-sm border rounded {input_bg} {input_border} {text_color} focus:outline-none focus:ring-2 focus:ring-blue-500",
+                    r#type: "text",
+                    title: "Retries after a rate limit (429) or server error (5xx)",
+                    placeholder: "{DEFAULT_LLM_RETRIES}",
+                    value: "{retries_value}",
+                    oninput: move |e| max_retries.set(e.value()),
+                }
+            }
+        }
+
+        // System prompt per action
+        div {
+            class: "mb-4",
+
+            div {
+                class: "flex items-center justify-between mb-2",
+
+                label {
+                    class: "text-sm font-medium {text_color}",
+                    "System prompt"
+                }
+
+                select {
+                    class: "px-2 py-1 text-xs border rounded focus:outline-none {select_class}",
+                    onchange: move |e| {
+                        let action = LlmAction::ALL
+                            .into_iter()
+                            .find(|a| a.label() == e.value())
+                            .unwrap_or(LlmAction::Generate);
+                        prompt_action.set(action);
+                    },
+                    for action in LlmAction::ALL {
+                        option {
+                            class: if is_dark { "bg-black text-white" } else { "bg-white text-gray-900" },
+                            value: action.label(),
+                            selected: action == current_action,
+                            "{action.label()}"
+                        }
+                    }
+                }
+            }
+
+            textarea {
+                class: "w-full h-20 px-3 py-2 text-sm border rounded {input_bg} {input_border} {text_color} focus:outline-none focus:ring-2 focus:ring-blue-500",
+                placeholder: "None",
+                value: "{system_prompt_value}",
+                oninput: move |e| {
+                    system_prompts.write().insert(current_action, e.value());
+                },
+            }
+        }
+
         // Buttons
         div {
             class: "flex justify-end space-x-3 mt-6",
@@ -230,3 +451,18 @@ fn LlmSettingsDialogContent() -> Element {
         }
     }
 }
+
+fn optional_text<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Blank means "not set"; anything else has to parse.
+fn parse_optional<T: std::str::FromStr>(text: &str, name: &str) -> Result<Option<T>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse()
+        .map(Some)
+        .map_err(|_| format!("{} is not a valid number", name))
+}
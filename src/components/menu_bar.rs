@@ -1,10 +1,84 @@
-use crate::export::{export_results, ExportFormat};
+use crate::components::save_active_tab;
+use crate::config::{get_diagnostic_templates, QueryTemplate};
 use crate::state::*;
 use dioxus::prelude::*;
 
+/// File menu items. They carry no accelerators, as the layout already handles Ctrl+O
+/// and Ctrl+S
+pub const MENU_OPEN_FILE: &str = "file-open";
+pub const MENU_SAVE_FILE: &str = "file-save";
+pub const MENU_SAVE_FILE_AS: &str = "file-save-as";
+
+/// Help menu item that zips the recent log and crash reports for a bug report
+pub const MENU_EXPORT_DIAGNOSTICS: &str = "help-export-diagnostics";
+
+/// Ask where to save the diagnostics bundle and write it there.
+pub fn export_diagnostics() {
+    spawn(async move {
+        let file_name = format!(
+            "fbench-diagnostics-{}.zip",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let Some(file) = rfd::AsyncFileDialog::new()
+            .add_filter("Zip", &["zip"])
+            .set_file_name(file_name)
+            .save_file()
+            .await
+        else {
+            return;
+        };
+        let path = file.path().to_path_buf();
+        let written = tokio::task::spawn_blocking({
+            let path = path.clone();
+            move || crate::crash::write_diagnostics_bundle(&path)
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result);
+        match written {
+            Ok(()) => tracing::info!("Diagnostics written to {}", path.display()),
+            Err(e) => tracing::error!("Failed to export diagnostics: {}", e),
+        }
+    });
+}
+
+/// Pick .sql files and open each in a tab backed by the file.
+pub fn open_sql_files() {
+    spawn(async move {
+        let Some(files) = rfd::AsyncFileDialog::new()
+            .add_filter("SQL", &["sql"])
+            .pick_files()
+            .await
+        else {
+            return;
+        };
+        for file in files {
+            if let Err(e) = crate::services::open_file(file.path()) {
+                tracing::error!("Failed to open {}: {}", file.path().display(), e);
+            }
+        }
+    });
+}
+
 #[component]
 pub fn MenuBar() -> Element {
     let is_dark = *IS_DARK_MODE.read();
+    let is_connected = matches!(*CONNECTION.read(), ConnectionState::Connected { .. });
+    let is_postgres = matches!(
+        *CONNECTION.read(),
+        ConnectionState::Connected {
+            db_type: DatabaseType::PostgreSQL,
+            ..
+        }
+    );
+
+    let supports_transactions = is_connected
+        && matches!(
+            *CURRENT_DB_TYPE.read(),
+            Some(DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::SQLite)
+        );
+    let manual_commit = *MANUAL_COMMIT.read();
+    let pending_statements = OPEN_TRANSACTION.read().unwrap_or(0);
 
     let bg_class = if is_dark { "bg-black" } else { "bg-white" };
     let border_class = if is_dark {
@@ -22,6 +96,11 @@ pub fn MenuBar() -> Element {
     } else {
         "hover:text-gray-900 hover:bg-gray-100"
     };
+    let commit_mode_class = if manual_commit {
+        "text-yellow-500"
+    } else {
+        text_class
+    };
     let divider_class = if is_dark {
         "bg-gray-800"
     } else {
@@ -52,6 +131,44 @@ pub fn MenuBar() -> Element {
 
             div { class: "w-px h-6 {divider_class} mx-2" }
 
+            button {
+                class: "px-3 py-1.5 text-sm {text_class} {hover_class} rounded flex items-center space-x-1.5 transition-colors",
+                title: "Open .sql files in new tabs (Ctrl+O)",
+                onclick: move |_| open_sql_files(),
+                svg {
+                    class: "w-4 h-4",
+                    fill: "none",
+                    stroke: "currentColor",
+                    view_box: "0 0 24 24",
+                    path {
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                        stroke_width: "2",
+                        d: "M5 19a2 2 0 01-2-2V7a2 2 0 012-2h4l2 2h4a2 2 0 012 2v1M5 19h14a2 2 0 002-2v-5a2 2 0 00-2-2H9a2 2 0 00-2 2v5a2 2 0 01-2 2z",
+                    }
+                }
+                span { "Open" }
+            }
+
+            button {
+                class: "px-3 py-1.5 text-sm {text_class} {hover_class} rounded flex items-center space-x-1.5 transition-colors",
+                title: "Save the tab to its .sql file (Ctrl+S, Ctrl+Shift+S to save as)",
+                onclick: move |_| save_active_tab(false),
+                svg {
+                    class: "w-4 h-4",
+                    fill: "none",
+                    stroke: "currentColor",
+                    view_box: "0 0 24 24",
+                    path {
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                        stroke_width: "2",
+                        d: "M5 5a2 2 0 012-2h8l4 4v12a2 2 0 01-2 2H7a2 2 0 01-2-2V5zm3-2v4h6V3m-6 18v-6h8v6",
+                    }
+                }
+                span { "Save File" }
+            }
+
             button {
                 class: "px-3 py-1.5 text-sm {text_class} {hover_class} rounded flex items-center space-x-1.5 transition-colors",
                 onclick: move |_| {
@@ -77,17 +194,7 @@ pub fn MenuBar() -> Element {
                 class: "px-3 py-1.5 text-sm {text_class} {hover_class} rounded flex items-center space-x-1.5 transition-colors",
                 onclick: move |_| {
                     tracing::info!("Export button clicked");
-                    let result = EDITOR_TABS
-                        .read()
-                        .active_tab()
-                        .and_then(|tab| tab.result.clone());
-                    tracing::info!("Active tab result exists: {}", result.is_some());
-                    if let Some(result) = result {
-                        tracing::info!("Exporting {} rows", result.rows.len());
-                        export_results(result, ExportFormat::Csv);
-                    } else {
-                        tracing::warn!("No query results to export");
-                    }
+                    *SHOW_EXPORT_DIALOG.write() = true;
                 },
                 svg {
                     class: "w-4 h-4",
@@ -124,6 +231,140 @@ pub fn MenuBar() -> Element {
                 span { "Import" }
             }
 
+            if is_connected {
+                button {
+                    class: "px-3 py-1.5 text-sm {text_class} {hover_class} rounded flex items-center space-x-1.5 transition-colors",
+                    onclick: move |_| {
+                        *SERVER_VARIABLES.write() = None;
+                        *SHOW_SERVER_VARIABLES.write() = true;
+                        send_db_request(crate::db::DbRequest::FetchServerVariables);
+                    },
+                    svg {
+                        class: "w-4 h-4",
+                        fill: "none",
+                        stroke: "currentColor",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            stroke_width: "2",
+                            d: "M12 6V4m0 2a2 2 0 100 4m0-4a2 2 0 110 4m-6 8a2 2 0 100-4m0 4a2 2 0 110-4m0 4v2m0-6V4m6 6v10m6-2a2 2 0 100-4m0 4a2 2 0 110-4m0 4v2m0-6V4",
+                        }
+                    }
+                    span { "Variables" }
+                }
+            }
+
+            if is_connected {
+                DiagnosticsMenu {}
+            }
+
+            if supports_transactions {
+                button {
+                    class: "px-3 py-1.5 text-sm {commit_mode_class} {hover_class} rounded flex items-center space-x-1.5 transition-colors disabled:opacity-50",
+                    disabled: pending_statements > 0,
+                    title: if pending_statements > 0 { "Commit or roll back the open transaction first" } else if manual_commit { "Switch back to committing every statement" } else { "Keep changes in a transaction until you commit or roll back" },
+                    onclick: move |_| {
+                        if manual_commit {
+                            *MANUAL_COMMIT.write() = false;
+                            send_db_request(crate::db::DbRequest::Rollback);
+                        } else {
+                            *MANUAL_COMMIT.write() = true;
+                            send_db_request(crate::db::DbRequest::Begin);
+                        }
+                    },
+                    svg {
+                        class: "w-4 h-4",
+                        fill: "none",
+                        stroke: "currentColor",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            stroke_width: "2",
+                            d: "M8 7h12m0 0l-4-4m4 4l-4 4m0 6H4m0 0l4 4m-4-4l4-4",
+                        }
+                    }
+                    span { if manual_commit { "Manual commit" } else { "Auto-commit" } }
+                }
+                if manual_commit {
+                    button {
+                        class: "px-3 py-1.5 text-sm text-green-500 {hover_class} rounded transition-colors disabled:opacity-50",
+                        disabled: pending_statements == 0,
+                        onclick: move |_| send_db_request(crate::db::DbRequest::Commit),
+                        "Commit"
+                    }
+                    button {
+                        class: "px-3 py-1.5 text-sm text-red-500 {hover_class} rounded transition-colors disabled:opacity-50",
+                        disabled: pending_statements == 0,
+                        onclick: move |_| send_db_request(crate::db::DbRequest::Rollback),
+                        "Rollback"
+                    }
+                }
+            }
+
+            if is_postgres {
+                button {
+                    class: "px-3 py-1.5 text-sm {text_class} {hover_class} rounded flex items-center space-x-1.5 transition-colors",
+                    onclick: move |_| {
+                        *EXTENSIONS.write() = None;
+                        *SHOW_EXTENSIONS.write() = true;
+                        send_db_request(crate::db::DbRequest::FetchExtensions);
+                    },
+                    svg {
+                        class: "w-4 h-4",
+                        fill: "none",
+                        stroke: "currentColor",
+                        view_box: "0 0 24 24",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            stroke_width: "2",
+                            d: "M11 4a2 2 0 114 0v1a1 1 0 001 1h3a1 1 0 011 1v3a1 1 0 01-1 1h-1a2 2 0 100 4h1a1 1 0 011 1v3a1 1 0 01-1 1h-3a1 1 0 01-1-1v-1a2 2 0 10-4 0v1a1 1 0 01-1 1H7a1 1 0 01-1-1v-3a1 1 0 00-1-1H4a2 2 0 110-4h1a1 1 0 001-1V7a1 1 0 011-1h3a1 1 0 001-1V4z",
+                        }
+                    }
+                    span { "Extensions" }
+                }
+            }
+
+            button {
+                class: "px-3 py-1.5 text-sm {text_class} {hover_class} rounded flex items-center space-x-1.5 transition-colors",
+                title: "Your local usage stats",
+                onclick: move |_| *SHOW_USAGE_STATS.write() = true,
+                svg {
+                    class: "w-4 h-4",
+                    fill: "none",
+                    stroke: "currentColor",
+                    view_box: "0 0 24 24",
+                    path {
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                        stroke_width: "2",
+                        d: "M9 19v-6a2 2 0 00-2-2H5a2 2 0 00-2 2v6a2 2 0 002 2h2a2 2 0 002-2zm0 0V9a2 2 0 012-2h2a2 2 0 012 2v10m-6 0a2 2 0 002 2h2a2 2 0 002-2m0 0V5a2 2 0 012-2h2a2 2 0 012 2v14a2 2 0 01-2 2h-2a2 2 0 01-2-2z",
+                    }
+                }
+                span { "Stats" }
+            }
+
+            button {
+                class: "px-3 py-1.5 text-sm {text_class} {hover_class} rounded flex items-center space-x-1.5 transition-colors",
+                title: "Show only the editor and results, in larger type",
+                onclick: move |_| *PRESENTATION_MODE.write() = true,
+                svg {
+                    class: "w-4 h-4",
+                    fill: "none",
+                    stroke: "currentColor",
+                    view_box: "0 0 24 24",
+                    path {
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                        stroke_width: "2",
+                        d: "M9.75 17L9 20l-1 1h8l-1-1-.75-3M3 13h18M5 17h14a2 2 0 002-2V5a2 2 0 00-2-2H5a2 2 0 00-2 2v10a2 2 0 002 2z",
+                    }
+                }
+                span { "Present" }
+            }
+
             div { class: "flex-1" }
 
             ConnectionStatus {}
@@ -131,8 +372,120 @@ pub fn MenuBar() -> Element {
     }
 }
 
+/// Open a diagnostic template in a new tab, with its variables at their defaults.
+fn open_diagnostic(template: &QueryTemplate) {
+    let values: Vec<(String, String)> = template
+        .variables
+        .iter()
+        .map(|v| (v.name.clone(), v.default_value.clone().unwrap_or_default()))
+        .collect();
+    let sql = format!("-- {}\n{}", template.description, template.apply(&values));
+    let mut tabs = EDITOR_TABS.write();
+    let id = tabs.add_tab(template.name.clone());
+    if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == id) {
+        tab.content = sql;
+    }
+}
+
+/// Bundled health checks for the connected database, each opened in a new tab.
 #[component]
-fn ConnectionStatus() -> Element {
+fn DiagnosticsMenu() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut open = use_signal(|| false);
+    let Some(db_type) = *CURRENT_DB_TYPE.read() else {
+        return rsx! {};
+    };
+    let templates = get_diagnostic_templates(db_type);
+    if templates.is_empty() {
+        return rsx! {};
+    }
+
+    let text_class = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-600"
+    };
+    let hover_class = if is_dark {
+        "hover:text-white hover:bg-gray-900"
+    } else {
+        "hover:text-gray-900 hover:bg-gray-100"
+    };
+    let popover_class = if is_dark {
+        "bg-black border-gray-800"
+    } else {
+        "bg-white border-gray-300"
+    };
+    let name_class = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let item_hover = if is_dark {
+        "hover:bg-gray-900"
+    } else {
+        "hover:bg-gray-100"
+    };
+
+    rsx! {
+        div {
+            class: "relative",
+            button {
+                class: "px-3 py-1.5 text-sm {text_class} {hover_class} rounded flex items-center space-x-1.5 transition-colors",
+                onclick: move |_| {
+                    let current = *open.read();
+                    open.set(!current);
+                },
+                svg {
+                    class: "w-4 h-4",
+                    fill: "none",
+                    stroke: "currentColor",
+                    view_box: "0 0 24 24",
+                    path {
+                        stroke_linecap: "round",
+                        stroke_linejoin: "round",
+                        stroke_width: "2",
+                        d: "M4.318 6.318a4.5 4.5 0 000 6.364L12 20.364l7.682-7.682a4.5 4.5 0 00-6.364-6.364L12 7.636l-1.318-1.318a4.5 4.5 0 00-6.364 0z",
+                    }
+                }
+                span { "Diagnostics" }
+            }
+
+            if *open.read() {
+                // Closes the menu on a click anywhere else
+                div {
+                    class: "fixed inset-0 z-40",
+                    onclick: move |_| open.set(false),
+                }
+                div {
+                    class: "absolute left-0 top-9 z-50 w-72 border rounded shadow-lg py-1 {popover_class}",
+                    for template in templates {
+                        button {
+                            key: "{template.name}",
+                            class: "w-full text-left px-3 py-1.5 {item_hover}",
+                            onclick: {
+                                let template = template.clone();
+                                move |_| {
+                                    open.set(false);
+                                    open_diagnostic(&template);
+                                }
+                            },
+                            div { class: "text-sm {name_class}", "{template.name}" }
+                            div { class: "text-xs {muted_text}", "{template.description}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn ConnectionStatus() -> Element {
     let is_dark = *IS_DARK_MODE.read();
     let disconnected_bg = if is_dark {
         "bg-gray-700"
@@ -154,8 +507,13 @@ fn ConnectionStatus() -> Element {
             ref db_name,
         } => {
             let db_label = match db_type {
-                DatabaseType::PostgreSQL => "PostgreSQL",
-                DatabaseType::MySQL => "MySQL",
+                // PostgreSQL or CockroachDB, MySQL or MariaDB
+                DatabaseType::PostgreSQL | DatabaseType::MySQL => CURRENT_DIALECT.read().label(),
+                DatabaseType::SQLite => "SQLite",
+                DatabaseType::DuckDB => "DuckDB",
+                DatabaseType::ClickHouse => "ClickHouse",
+                DatabaseType::BigQuery => "BigQuery",
+                DatabaseType::Odbc => "ODBC",
             };
             let text = if db_name.is_empty() {
                 db_label.to_string()
@@ -164,9 +522,11 @@ fn ConnectionStatus() -> Element {
             };
             ("bg-green-500", text, "text-green-500")
         }
-        ConnectionState::ConnectionLost => {
-            ("bg-red-500", "Connection Lost".to_string(), "text-red-500")
-        }
+        ConnectionState::ConnectionLost => (
+            "bg-red-500 animate-pulse",
+            "Offline · reconnecting...".to_string(),
+            "text-red-500",
+        ),
         ConnectionState::Error(_) => ("bg-red-500", "Error".to_string(), "text-red-500"),
     };
 
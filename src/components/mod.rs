@@ -1,43 +1,113 @@
 pub mod ai_results_panel;
+pub mod anonymized_copy;
+pub mod batch_progress;
+pub mod bulk_edit;
+pub mod cell_renderers;
+pub mod change_capture;
+pub mod column_distribution;
+pub mod comment_dialog;
 pub mod connection_dialog;
 pub mod context_menu;
+pub mod deep_link_dialog;
+pub mod destructive_confirm;
+pub mod dialect_conversion;
+pub mod edit_conflict;
+pub mod editor_find;
+pub mod environment;
 pub mod execution_plan;
+pub mod export_dialog;
+pub mod export_jobs;
+pub mod extensions_dialog;
 pub mod filter_panel;
 pub mod history_panel;
+pub mod host_key_confirm;
 pub mod import_dialog;
+pub mod import_watches;
 pub mod json_viewer;
 pub mod layout;
 pub mod llm_panel;
 pub mod llm_settings_dialog;
 pub mod menu_bar;
+pub mod notifications_panel;
+pub mod parameter_prompt;
 pub mod queries_panel;
+pub mod query_tests;
 pub mod quick_switcher;
+pub mod referencing_rows;
+pub mod replace_dialog;
 pub mod results_table;
+pub mod row_delete_confirm;
+pub mod row_history;
+pub mod running_query;
 pub mod save_query_dialog;
 pub mod schema_panel;
+pub mod seed_script;
+pub mod server_variables;
+pub mod session_panel;
 pub mod sidebar;
+pub mod snippets;
 pub mod sql_editor;
 pub mod status_bar;
+pub mod system_tray;
 pub mod tab_bar;
 pub mod template_selector;
+pub mod unsaved_tab_dialog;
+pub mod usage_stats;
+pub mod welcome;
+pub mod workspace_search;
 
 pub use ai_results_panel::*;
+pub use anonymized_copy::*;
+pub use batch_progress::*;
+pub use bulk_edit::*;
+pub use cell_renderers::*;
+pub use change_capture::*;
+pub use column_distribution::*;
+pub use comment_dialog::*;
 pub use connection_dialog::*;
 pub use context_menu::*;
+pub use deep_link_dialog::*;
+pub use destructive_confirm::*;
+pub use dialect_conversion::*;
+pub use edit_conflict::*;
+pub use editor_find::*;
+pub use environment::*;
 pub use execution_plan::*;
+pub use export_dialog::*;
+pub use export_jobs::*;
+pub use extensions_dialog::*;
 pub use history_panel::*;
+pub use host_key_confirm::*;
 pub use import_dialog::*;
+pub use import_watches::*;
 pub use json_viewer::*;
 pub use llm_panel::*;
 pub use llm_settings_dialog::*;
 pub use menu_bar::*;
+pub use notifications_panel::*;
+pub use parameter_prompt::*;
 pub use queries_panel::*;
+pub use query_tests::*;
 pub use quick_switcher::*;
+pub use referencing_rows::*;
+pub use replace_dialog::*;
 pub use results_table::*;
+pub use row_delete_confirm::*;
+pub use row_history::*;
+pub use running_query::*;
 pub use save_query_dialog::*;
 pub use schema_panel::*;
+pub use seed_script::*;
+pub use server_variables::*;
+pub use session_panel::*;
 pub use sidebar::*;
+pub use snippets::*;
 pub use sql_editor::*;
 pub use status_bar::*;
+pub use system_tray::*;
 pub use tab_bar::*;
 pub use template_selector::*;
+pub use unsaved_tab_dialog::*;
+pub use usage_stats::*;
+pub use welcome::*;
+pub use workspace_search::*;
@@ -0,0 +1,154 @@
+use crate::components::open_cell_detail;
+use crate::db::DbRequest;
+use crate::state::*;
+use dioxus::prelude::*;
+
+/// "Listening (3)" with the number of notifications received, when any channel is listened on.
+pub fn notifications_label() -> Option<String> {
+    if LISTEN_CHANNELS.read().is_empty() {
+        return None;
+    }
+    Some(format!("Listening ({})", NOTIFICATIONS.read().len()))
+}
+
+/// Live feed of NOTIFY messages on the channels the connection LISTENs on.
+#[component]
+pub fn NotificationsPanel() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut channel = use_signal(String::new);
+
+    let panel_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let border_class = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let cell_text = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-gray-900 border-gray-700 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+
+    let channels = LISTEN_CHANNELS.read().clone();
+    let notifications: Vec<_> = NOTIFICATIONS
+        .read()
+        .iter()
+        .map(|n| (n.received_at.format("%H:%M:%S").to_string(), n.clone()))
+        .collect();
+
+    let mut listen = move || {
+        let name = channel.read().trim().to_string();
+        if !name.is_empty() {
+            send_db_request(DbRequest::Listen(name));
+            channel.set(String::new());
+        }
+    };
+
+    rsx! {
+        div {
+            class: "absolute bottom-6 right-0 w-[560px] max-h-[60vh] flex flex-col {panel_bg} border {border_class} rounded-lg shadow-2xl z-40",
+            onclick: move |e: MouseEvent| e.stop_propagation(),
+
+            div {
+                class: "flex items-center px-3 py-2 border-b {border_class}",
+                span {
+                    class: "text-sm font-semibold {text_color} flex-1",
+                    "Notifications"
+                }
+                if !notifications.is_empty() {
+                    button {
+                        class: "{muted_text} hover:text-blue-500",
+                        onclick: move |_| NOTIFICATIONS.write().clear(),
+                        "Clear"
+                    }
+                }
+                button {
+                    class: "{muted_text} hover:text-blue-500 ml-3",
+                    onclick: move |_| *SHOW_NOTIFICATIONS_PANEL.write() = false,
+                    "✕"
+                }
+            }
+
+            div {
+                class: "flex flex-wrap items-center gap-1 px-3 py-2 border-b {border_class}",
+                for name in channels {
+                    span {
+                        key: "{name}",
+                        class: "inline-flex items-center px-2 py-0.5 rounded bg-blue-600 bg-opacity-20 text-blue-500 font-mono",
+                        "{name}"
+                        button {
+                            class: "ml-1 hover:text-red-500",
+                            title: "UNLISTEN",
+                            onclick: {
+                                let name = name.clone();
+                                move |_| send_db_request(DbRequest::Unlisten(name.clone()))
+                            },
+                            "×"
+                        }
+                    }
+                }
+                input {
+                    class: "flex-1 min-w-[120px] px-2 py-0.5 rounded border font-mono outline-none {input_class}",
+                    placeholder: "Channel to LISTEN on",
+                    value: "{channel}",
+                    oninput: move |e| channel.set(e.value()),
+                    onkeydown: move |e: KeyboardEvent| {
+                        if e.key() == Key::Enter {
+                            listen();
+                        }
+                    },
+                }
+                button {
+                    class: "px-2 py-0.5 rounded bg-blue-600 text-white hover:bg-blue-500",
+                    onclick: move |_| listen(),
+                    "Listen"
+                }
+            }
+
+            div {
+                class: "flex-1 overflow-auto",
+                if notifications.is_empty() {
+                    div {
+                        class: "{muted_text} text-center py-6",
+                        "Waiting for notifications..."
+                    }
+                }
+                for (at, notification) in notifications {
+                    div {
+                        class: "flex items-start space-x-2 px-3 py-1 border-b {border_class} font-mono",
+                        span { class: "{muted_text} whitespace-nowrap", "{at}" }
+                        span { class: "text-blue-500 whitespace-nowrap", "{notification.channel}" }
+                        span {
+                            class: "flex-1 break-all cursor-pointer hover:underline {cell_text}",
+                            title: "Sent by backend {notification.process_id}. Click to open",
+                            onclick: {
+                                let payload = notification.payload.clone();
+                                move |_| open_cell_detail(payload.clone())
+                            },
+                            if notification.payload.is_empty() {
+                                "(no payload)"
+                            } else {
+                                "{notification.payload}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use crate::config::{QueryParamStore, QueryParamValues};
+use crate::db::{bind_placeholders, query_hash, query_placeholders, DbRequest, ParamValue};
+use crate::state::*;
+use dioxus::prelude::*;
+
+/// Run `sql` for `tab_id`, first asking for the values of any placeholders it has.
+/// Values are bound by the driver rather than pasted into the statement.
+pub fn run_with_parameters(sql: String, tab_id: Option<String>) {
+    let db_type = *CURRENT_DB_TYPE.read();
+    let placeholders = match db_type {
+        Some(db_type @ (DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::SQLite)) => {
+            query_placeholders(&sql, db_type)
+        }
+        _ => Vec::new(),
+    };
+    if placeholders.is_empty() {
+        send_request_for_tab(DbRequest::Execute(sql), tab_id);
+        return;
+    }
+    *PARAMETER_PROMPT.write() = Some(ParameterPrompt {
+        sql,
+        tab_id,
+        placeholders,
+    });
+}
+
+#[component]
+pub fn ParameterPromptDialog() -> Element {
+    let prompt = PARAMETER_PROMPT.read().clone();
+    rsx! {
+        if let Some(prompt) = prompt {
+            ParameterPromptDialogContent { prompt }
+        }
+    }
+}
+
+#[component]
+fn ParameterPromptDialogContent(prompt: ParameterPrompt) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let hash = query_hash(&prompt.sql);
+    // Last-used values of this statement
+    let mut values = use_signal({
+        let hash = hash.clone();
+        let placeholders = prompt.placeholders.clone();
+        move || {
+            let saved = QueryParamStore::new().load_values(&hash);
+            placeholders
+                .iter()
+                .map(|p| saved.values.get(&p.label()).cloned().unwrap_or_default())
+                .collect::<Vec<String>>()
+        }
+    });
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+
+    let run = {
+        let prompt = prompt.clone();
+        move || {
+            let Some(db_type) = *CURRENT_DB_TYPE.read() else {
+                return;
+            };
+            let entered = values.read().clone();
+            let saved = QueryParamValues {
+                values: prompt
+                    .placeholders
+                    .iter()
+                    .zip(&entered)
+                    .map(|(p, v)| (p.label(), v.clone()))
+                    .collect(),
+            };
+            if let Err(e) = QueryParamStore::new().save_values(&hash, saved) {
+                tracing::warn!("Could not save the parameter values: {}", e);
+            }
+            let bound: Vec<_> = prompt
+                .placeholders
+                .iter()
+                .cloned()
+                .zip(entered.iter().map(|v| ParamValue::guess(v)))
+                .collect();
+            let (sql, params) = bind_placeholders(&prompt.sql, db_type, &bound);
+            *PARAMETER_PROMPT.write() = None;
+            send_request_for_tab(
+                DbRequest::ExecuteWithParams { sql, params },
+                prompt.tab_id.clone(),
+            );
+        }
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *PARAMETER_PROMPT.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[480px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-1 {text_color}",
+                    "Query Parameters"
+                }
+                p {
+                    class: "text-xs {muted_text} mb-4",
+                    "Numbers and true/false are bound with their type, NULL as null and anything else as text."
+                }
+
+                div {
+                    class: "flex-1 overflow-auto space-y-2",
+                    for (i, placeholder) in prompt.placeholders.iter().enumerate() {
+                        div {
+                            key: "{placeholder.label()}",
+                            class: "flex items-center space-x-3",
+                            label {
+                                class: "w-28 text-sm font-mono {label_color} truncate",
+                                title: "{placeholder.label()}",
+                                "{placeholder.label()}"
+                            }
+                            input {
+                                class: "flex-1 px-3 py-1.5 border rounded text-sm font-mono focus:outline-none focus:border-blue-500 {input_class}",
+                                autofocus: i == 0,
+                                value: "{values.read()[i]}",
+                                oninput: move |e| values.write()[i] = e.value(),
+                                onkeydown: {
+                                    let run = run.clone();
+                                    move |e: KeyboardEvent| {
+                                        if e.key() == Key::Enter {
+                                            run();
+                                        }
+                                    }
+                                },
+                            }
+                            span {
+                                class: "w-14 text-xs {muted_text}",
+                                "{ParamValue::guess(&values.read()[i]).type_label()}"
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *PARAMETER_PROMPT.write() = None,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white",
+                        onclick: move |_| run(),
+                        "Run"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,15 @@
-use crate::config::QueryStore;
-use crate::state::{EDITOR_TABS, IS_DARK_MODE, QUERIES_REVISION, SHOW_SAVE_QUERY_DIALOG};
+use crate::config::{
+    query_trend, QueryStore, QueryTrend, TeamLibrarySettings, TeamLibrarySource, TeamLibraryStore,
+    UsageLog, DEFAULT_TEAM_LIBRARY_REFRESH_MINUTES,
+};
+use crate::db::query_hash;
+use crate::services::refresh_team_library;
+use crate::state::{
+    EDITOR_TABS, HISTORY_REVISION, IS_DARK_MODE, QUERIES_REVISION, SHOW_QUERY_TESTS,
+    SHOW_SAVE_QUERY_DIALOG, TEAM_LIBRARY_STATUS, TEAM_QUERIES,
+};
 use dioxus::prelude::*;
+use std::collections::HashMap;
 
 #[component]
 pub fn QueriesPanel() -> Element {
@@ -11,6 +20,21 @@ pub fn QueriesPanel() -> Element {
         let _ = *QUERIES_REVISION.read();
         query_store.read().load_queries()
     });
+    let usage_events = use_resource(move || async move {
+        // Reload when a query finishes, so trends include the latest run
+        let _ = *HISTORY_REVISION.read();
+        UsageLog::new().load()
+    });
+    let trends: HashMap<String, QueryTrend> =
+        match (queries.read().as_ref(), usage_events.read().as_ref()) {
+            (Some(queries), Some(events)) => queries
+                .iter()
+                .filter_map(|q| {
+                    query_trend(events, &query_hash(&q.sql)).map(|t| (q.name.clone(), t))
+                })
+                .collect(),
+            _ => HashMap::new(),
+        };
     let is_dark = *IS_DARK_MODE.read();
 
     // Theme-aware classes
@@ -57,22 +81,32 @@ pub fn QueriesPanel() -> Element {
                     "Saved Queries"
                 }
 
-                button {
-                    class: "text-xs {button_text} {button_hover} flex items-center space-x-1 transition-colors",
-                    onclick: move |_| *SHOW_SAVE_QUERY_DIALOG.write() = true,
-                    svg {
-                        class: "w-3.5 h-3.5",
-                        fill: "none",
-                        stroke: "currentColor",
-                        view_box: "0 0 24 24",
-                        path {
-                            stroke_linecap: "round",
-                            stroke_linejoin: "round",
-                            stroke_width: "2",
-                            d: "M12 4v16m8-8H4",
+                div {
+                    class: "flex items-center space-x-3",
+                    button {
+                        class: "text-xs {button_text} {button_hover} transition-colors",
+                        title: "Test saved queries against fixtures",
+                        onclick: move |_| *SHOW_QUERY_TESTS.write() = true,
+                        "Tests"
+                    }
+
+                    button {
+                        class: "text-xs {button_text} {button_hover} flex items-center space-x-1 transition-colors",
+                        onclick: move |_| *SHOW_SAVE_QUERY_DIALOG.write() = true,
+                        svg {
+                            class: "w-3.5 h-3.5",
+                            fill: "none",
+                            stroke: "currentColor",
+                            view_box: "0 0 24 24",
+                            path {
+                                stroke_linecap: "round",
+                                stroke_linejoin: "round",
+                                stroke_width: "2",
+                                d: "M12 4v16m8-8H4",
+                            }
                         }
+                        span { "Save Current" }
                     }
-                    span { "Save Current" }
                 }
             }
 
@@ -102,6 +136,10 @@ pub fn QueriesPanel() -> Element {
                             "{query.name}"
                         }
 
+                        if let Some(trend) = trends.get(&query.name) {
+                            QueryTrendIcon { trend: trend.clone() }
+                        }
+
                         // Bookmark button
                         button {
                             class: "opacity-0 group-hover:opacity-100 {muted_text} hover:text-yellow-500 transition-colors mr-1",
@@ -157,6 +195,376 @@ pub fn QueriesPanel() -> Element {
                 }
             }
             }
+
+            TeamLibrarySection {
+                local_names: queries
+                    .read()
+                    .as_ref()
+                    .map(|qs| qs.iter().map(|q| q.name.clone()).collect())
+                    .unwrap_or_default(),
+            }
+        }
+    }
+}
+
+/// Polyline of `runs` scaled into a `width` x `height` box, slowest run at the top.
+fn sparkline_points(
+    runs: &[(chrono::DateTime<chrono::Local>, u64)],
+    width: f64,
+    height: f64,
+) -> String {
+    let max = runs.iter().map(|(_, ms)| *ms).max().unwrap_or(0).max(1) as f64;
+    let step = width / (runs.len().max(2) - 1) as f64;
+    runs.iter()
+        .enumerate()
+        .map(|(i, (_, ms))| {
+            format!(
+                "{:.1},{:.1}",
+                i as f64 * step,
+                height - *ms as f64 / max * (height - 1.0) - 0.5
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Duration sparkline of a saved query's recent runs, red when the latest run took over
+/// twice the median. Clicking it shows each run.
+#[component]
+fn QueryTrendIcon(trend: QueryTrend) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut open = use_signal(|| false);
+
+    let regressed = trend.is_regression();
+    let line_color = if regressed {
+        "text-red-500"
+    } else if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let popover_class = if is_dark {
+        "bg-black border-gray-800 text-gray-400"
+    } else {
+        "bg-white border-gray-300 text-gray-600"
+    };
+    let bar_bg = if is_dark {
+        "bg-gray-900"
+    } else {
+        "bg-gray-100"
+    };
+    let latest_ms = trend.latest_ms();
+    let title = if regressed {
+        format!(
+            "Slower than usual: {} ms, median {} ms",
+            latest_ms, trend.median_ms
+        )
+    } else {
+        format!(
+            "Last {} runs, latest {} ms, median {} ms",
+            trend.runs.len(),
+            latest_ms,
+            trend.median_ms
+        )
+    };
+    let points = sparkline_points(&trend.runs, 24.0, 12.0);
+    let max_ms = trend
+        .runs
+        .iter()
+        .map(|(_, ms)| *ms)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    rsx! {
+        div {
+            class: "relative mr-1",
+            button {
+                class: "flex items-center {line_color}",
+                title: "{title}",
+                onclick: move |e: MouseEvent| {
+                    e.stop_propagation();
+                    let current = *open.read();
+                    open.set(!current);
+                },
+                svg {
+                    class: "w-6 h-3",
+                    fill: "none",
+                    stroke: "currentColor",
+                    view_box: "0 0 24 12",
+                    polyline {
+                        points: "{points}",
+                        stroke_width: "1.5",
+                        stroke_linejoin: "round",
+                    }
+                }
+                if regressed {
+                    span { class: "text-xs ml-0.5", "↑" }
+                }
+            }
+
+            if *open.read() {
+                div {
+                    class: "absolute right-0 top-5 z-20 w-64 border rounded shadow-lg p-2 text-xs {popover_class}",
+                    onclick: move |e: MouseEvent| e.stop_propagation(),
+                    div { class: "mb-1", "{title}" }
+                    div {
+                        class: "max-h-48 overflow-auto space-y-0.5",
+                        for (i, (at, ms)) in trend.runs.iter().enumerate().rev() {
+                            div {
+                                key: "{i}",
+                                class: "flex items-center space-x-2",
+                                span { class: "w-20 shrink-0 tabular-nums", "{at.format(\"%m-%d %H:%M\")}" }
+                                div {
+                                    class: "flex-1 h-2 rounded {bar_bg}",
+                                    div {
+                                        class: if *ms > trend.median_ms * 2 { "h-2 rounded bg-red-500" } else { "h-2 rounded bg-blue-500" },
+                                        style: "width: {*ms as f64 * 100.0 / max_ms as f64}%",
+                                    }
+                                }
+                                span { class: "w-14 shrink-0 text-right tabular-nums", "{ms} ms" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Read-only queries shared through the team library. A saved query with the same name
+/// is a local override and is opened instead.
+#[component]
+fn TeamLibrarySection(local_names: Vec<String>) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut show_settings = use_signal(|| false);
+
+    let header_text = "text-gray-500";
+    let button_text = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-600"
+    };
+    let button_hover = if is_dark {
+        "hover:text-white"
+    } else {
+        "hover:text-gray-900"
+    };
+    let item_text = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-600"
+    };
+    let item_hover = if is_dark {
+        "hover:bg-gray-900 hover:text-white"
+    } else {
+        "hover:bg-gray-100 hover:text-gray-900"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+
+    let team_queries = TEAM_QUERIES.read().clone();
+    let status = TEAM_LIBRARY_STATUS.read().clone();
+    let configured = TeamLibraryStore::new().load().source.is_some();
+
+    rsx! {
+        div {
+            class: "flex items-center justify-between mt-6 mb-3",
+            h3 {
+                class: "text-xs font-semibold {header_text} uppercase tracking-wider",
+                "Team Library"
+            }
+            div {
+                class: "flex items-center space-x-2",
+                if configured {
+                    button {
+                        class: "text-xs {button_text} {button_hover} transition-colors",
+                        title: "Refresh now",
+                        onclick: move |_| {
+                            spawn(refresh_team_library());
+                        },
+                        "↻"
+                    }
+                }
+                button {
+                    class: "text-xs {button_text} {button_hover} transition-colors",
+                    onclick: move |_| {
+                        let current = *show_settings.read();
+                        show_settings.set(!current);
+                    },
+                    if configured { "Settings" } else { "Set up" }
+                }
+            }
+        }
+
+        if *show_settings.read() {
+            TeamLibrarySettingsForm { on_saved: move |_| show_settings.set(false) }
+        }
+
+        match status {
+            Some(Ok(time)) => rsx! {
+                div { class: "text-xs {muted_text} px-2", "Synced at {time}" }
+            },
+            Some(Err(error)) => rsx! {
+                div { class: "text-xs text-red-500 px-2 break-words", "Sync failed: {error}" }
+            },
+            None => rsx! {},
+        }
+
+        if configured && team_queries.is_empty() {
+            div {
+                class: "{muted_text} text-sm text-center py-4",
+                "No team queries"
+            }
+        }
+
+        for query in team_queries {
+            {
+                let overridden = local_names.contains(&query.name);
+                let open_query = query.clone();
+                let override_query = query.clone();
+                rsx! {
+                    div {
+                        class: "group flex items-center justify-between px-2 py-2 rounded {item_hover} transition-colors",
+                        key: "team-{query.name}",
+
+                        button {
+                            class: "flex-1 text-left text-sm {item_text} truncate",
+                            title: if overridden { "Opens your local override" } else { "Read-only team query" },
+                            onclick: move |_| {
+                                let sql = if overridden {
+                                    QueryStore::new()
+                                        .load_queries()
+                                        .into_iter()
+                                        .find(|q| q.name == open_query.name)
+                                        .map(|q| q.sql)
+                                        .unwrap_or_else(|| open_query.sql.clone())
+                                } else {
+                                    open_query.sql.clone()
+                                };
+                                if let Some(tab) = EDITOR_TABS.write().active_tab_mut() {
+                                    tab.content = sql;
+                                    tab.unsaved_changes = true;
+                                }
+                            },
+                            "{query.name}"
+                        }
+
+                        if overridden {
+                            span { class: "text-xs text-yellow-500 mr-1", "local" }
+                        } else {
+                            button {
+                                class: "opacity-0 group-hover:opacity-100 text-xs {muted_text} hover:text-blue-500 transition-colors",
+                                title: "Copy into your saved queries to edit locally",
+                                onclick: move |_| {
+                                    let store = QueryStore::new();
+                                    let mut queries = store.load_queries();
+                                    queries.push(override_query.clone());
+                                    if let Err(e) = store.save_queries(&queries) {
+                                        tracing::error!("Failed to save override: {}", e);
+                                    }
+                                    *QUERIES_REVISION.write() += 1;
+                                },
+                                "Override"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TeamLibrarySettingsForm(on_saved: EventHandler<()>) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let initial = use_hook(|| TeamLibraryStore::new().load());
+    let mut is_git = use_signal(|| !matches!(initial.source, Some(TeamLibrarySource::Url(_))));
+    let mut location = use_signal(|| {
+        initial
+            .source
+            .as_ref()
+            .map(|s| s.location().to_string())
+            .unwrap_or_default()
+    });
+    let mut refresh_minutes = use_signal(|| initial.refresh_minutes.to_string());
+
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+
+    rsx! {
+        div {
+            class: "space-y-2 px-2 pb-3 text-xs",
+            select {
+                class: "w-full px-2 py-1 border rounded focus:outline-none {input_class}",
+                value: if *is_git.read() { "git" } else { "url" },
+                onchange: move |e| is_git.set(e.value() == "git"),
+                option { value: "git", "Git repository" }
+                option { value: "url", "HTTPS URL" }
+            }
+            input {
+                class: "w-full px-2 py-1 border rounded font-mono focus:outline-none {input_class}",
+                r#type: "text",
+                placeholder: if *is_git.read() { "https://github.com/team/queries.git" } else { "https://example.com/queries/index.txt" },
+                value: "{location}",
+                oninput: move |e| location.set(e.value()),
+            }
+            if !*is_git.read() {
+                div {
+                    class: "{muted_text}",
+                    "A .sql file, or an index listing one .sql path per line."
+                }
+            }
+            div {
+                class: "flex items-center space-x-2",
+                span { class: "{muted_text}", "Refresh every" }
+                input {
+                    class: "w-16 px-2 py-1 border rounded focus:outline-none {input_class}",
+                    r#type: "number",
+                    min: "1",
+                    value: "{refresh_minutes}",
+                    oninput: move |e| refresh_minutes.set(e.value()),
+                }
+                span { class: "{muted_text}", "min" }
+                div { class: "flex-1" }
+                button {
+                    class: "px-2 py-1 rounded bg-blue-600 hover:bg-blue-500 text-white",
+                    onclick: move |_| {
+                        let location = location.read().trim().to_string();
+                        let source = if location.is_empty() {
+                            None
+                        } else if *is_git.read() {
+                            Some(TeamLibrarySource::Git(location))
+                        } else {
+                            Some(TeamLibrarySource::Url(location))
+                        };
+                        let settings = TeamLibrarySettings {
+                            source,
+                            refresh_minutes: refresh_minutes
+                                .read()
+                                .parse()
+                                .unwrap_or(DEFAULT_TEAM_LIBRARY_REFRESH_MINUTES),
+                        };
+                        if let Err(e) = TeamLibraryStore::new().save(&settings) {
+                            tracing::error!("Failed to save team library settings: {}", e);
+                        }
+                        spawn(refresh_team_library());
+                        on_saved.call(());
+                    },
+                    "Save"
+                }
+            }
         }
     }
 }
@@ -0,0 +1,471 @@
+use crate::config::{QueryStore, QueryTest, QueryTestStore, SavedQuery};
+use crate::db::{parse_test_csv, DbRequest, FixtureData, QueryTestCase, TestFixture};
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn QueryTestsDialog() -> Element {
+    rsx! {
+        if *SHOW_QUERY_TESTS.read() {
+            QueryTestsDialogContent {}
+        }
+    }
+}
+
+/// Tests of saved queries: fixtures to insert, the rows to expect, and a run over all of
+/// them inside rolled-back transactions.
+#[component]
+fn QueryTestsDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut tests = use_signal(|| QueryTestStore::new().load_tests());
+    let queries = use_signal(|| QueryStore::new().load_queries());
+    // The test being edited and the name it was saved under, None for a new one
+    let mut editing = use_signal(|| None::<(Option<String>, QueryTest)>);
+    let mut save_error = use_signal(|| None::<String>);
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let row_border = if is_dark {
+        "border-gray-900"
+    } else {
+        "border-gray-200"
+    };
+
+    let close = move |_| {
+        *SHOW_QUERY_TESTS.write() = false;
+        *QUERY_TEST_RESULTS.write() = None;
+    };
+
+    if let Some((original_name, draft)) = editing.read().clone() {
+        let on_save = move |test: QueryTest| {
+            let mut all = tests.read().clone();
+            if all
+                .iter()
+                .any(|t| t.name == test.name && Some(&t.name) != original_name.as_ref())
+            {
+                save_error.set(Some(format!("A test named '{}' exists", test.name)));
+                return;
+            }
+            match all
+                .iter()
+                .position(|t| Some(&t.name) == original_name.as_ref())
+            {
+                Some(i) => all[i] = test,
+                None => all.push(test),
+            }
+            match QueryTestStore::new().save_tests(&all) {
+                Ok(()) => {
+                    tests.set(all);
+                    editing.set(None);
+                    save_error.set(None);
+                }
+                Err(e) => save_error.set(Some(e)),
+            }
+        };
+        return rsx! {
+            div {
+                class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+                onclick: close,
+
+                div {
+                    class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[720px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                    onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                    QueryTestForm {
+                        draft,
+                        queries: queries.read().clone(),
+                        error: save_error.read().clone(),
+                        on_save,
+                        on_cancel: move |_| {
+                            editing.set(None);
+                            save_error.set(None);
+                        },
+                    }
+                }
+            }
+        };
+    }
+
+    let results = QUERY_TEST_RESULTS.read().clone();
+    let running = *RUNNING_QUERY_TESTS.read();
+    let run_error = match &results {
+        Some(Err(e)) => Some(e.clone()),
+        _ => None,
+    };
+    let outcomes = match results {
+        Some(Ok(outcomes)) => outcomes,
+        _ => Vec::new(),
+    };
+    let passed = outcomes.iter().filter(|o| o.result.is_ok()).count();
+    let test_list = tests.read().clone();
+    let query_list = queries.read().clone();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: close,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[720px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-1 {text_color}",
+                    "Query Tests"
+                }
+                p {
+                    class: "text-xs {muted_text} mb-4",
+                    "Each test inserts its fixtures, runs a saved query and compares the rows. Everything is rolled back afterwards. On MySQL and MariaDB the query can only select or change rows, as other statements commit."
+                }
+
+                div {
+                    class: "flex-1 overflow-auto",
+                    if test_list.is_empty() {
+                        div {
+                            class: "{muted_text} text-sm text-center py-8",
+                            "No query tests"
+                        }
+                    }
+                    for test in test_list.iter() {
+                        div {
+                            key: "{test.name}",
+                            class: "group flex items-start justify-between py-2 border-b {row_border}",
+                            div {
+                                class: "min-w-0 flex-1",
+                                div {
+                                    class: "text-sm {text_color}",
+                                    "{test.name} "
+                                    span { class: "text-xs {muted_text}", "· {test.query}" }
+                                }
+                                if !query_list.iter().any(|q| q.name == test.query) {
+                                    div {
+                                        class: "text-xs text-red-500",
+                                        "Saved query '{test.query}' not found"
+                                    }
+                                } else if let Some(outcome) = outcomes.iter().find(|o| o.name == test.name) {
+                                    match &outcome.result {
+                                        Ok(()) => rsx! {
+                                            div { class: "text-xs text-green-500", "Passed" }
+                                        },
+                                        Err(e) => rsx! {
+                                            div { class: "text-xs text-red-500 break-all", "Failed: {e}" }
+                                        },
+                                    }
+                                }
+                            }
+                            button {
+                                class: "opacity-0 group-hover:opacity-100 text-xs {label_color} hover:opacity-80 ml-2",
+                                onclick: {
+                                    let test = test.clone();
+                                    move |_| editing.set(Some((Some(test.name.clone()), test.clone())))
+                                },
+                                "Edit"
+                            }
+                            button {
+                                class: "opacity-0 group-hover:opacity-100 text-xs text-red-500 hover:opacity-80 ml-2",
+                                onclick: {
+                                    let name = test.name.clone();
+                                    move |_| {
+                                        let mut all = tests.read().clone();
+                                        all.retain(|t| t.name != name);
+                                        if QueryTestStore::new().save_tests(&all).is_ok() {
+                                            tests.set(all);
+                                        }
+                                    }
+                                },
+                                "Delete"
+                            }
+                        }
+                    }
+                }
+
+                if let Some(error) = run_error {
+                    div { class: "text-xs text-red-500 mt-2", "{error}" }
+                } else if !outcomes.is_empty() {
+                    div {
+                        class: "text-xs mt-2",
+                        class: if passed == outcomes.len() { "text-green-500" } else { "text-red-500" },
+                        "{passed} of {outcomes.len()} passed"
+                    }
+                }
+
+                div {
+                    class: "flex justify-between mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| {
+                            let query = queries.read().first().map(|q| q.name.clone()).unwrap_or_default();
+                            editing.set(Some((None, QueryTest {
+                                name: String::new(),
+                                query,
+                                fixtures: Vec::new(),
+                                expected_csv: String::new(),
+                                ordered: false,
+                            })));
+                        },
+                        "New test"
+                    }
+                    div {
+                        class: "flex space-x-2",
+                        button {
+                            class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                            onclick: close,
+                            "Close"
+                        }
+                        button {
+                            class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                            disabled: running || test_list.is_empty(),
+                            onclick: move |_| run_query_tests(&tests.read(), &queries.read()),
+                            if running { "Running..." } else { "Run tests" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Send every test whose saved query still exists; the others are flagged in the list.
+fn run_query_tests(tests: &[QueryTest], queries: &[SavedQuery]) {
+    let cases: Vec<QueryTestCase> = tests
+        .iter()
+        .filter_map(|test| {
+            let query = queries.iter().find(|q| q.name == test.query)?;
+            Some(QueryTestCase {
+                name: test.name.clone(),
+                sql: query.sql.clone(),
+                fixtures: test.fixtures.clone(),
+                expected_csv: test.expected_csv.clone(),
+                ordered: test.ordered,
+            })
+        })
+        .collect();
+    if cases.is_empty() {
+        return;
+    }
+    *QUERY_TEST_RESULTS.write() = None;
+    *RUNNING_QUERY_TESTS.write() = true;
+    send_db_request(DbRequest::RunQueryTests(cases));
+}
+
+#[component]
+fn QueryTestForm(
+    draft: QueryTest,
+    queries: Vec<SavedQuery>,
+    error: Option<String>,
+    on_save: EventHandler<QueryTest>,
+    on_cancel: EventHandler<()>,
+) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut test = use_signal(|| draft.clone());
+
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+
+    let current = test.read().clone();
+    let csv_error = if current.expected_csv.trim().is_empty() {
+        None
+    } else {
+        parse_test_csv(&current.expected_csv).err()
+    };
+    let can_save = !current.name.trim().is_empty()
+        && !current.query.is_empty()
+        && !current.expected_csv.trim().is_empty()
+        && csv_error.is_none();
+
+    rsx! {
+        h2 {
+            class: "text-lg font-semibold mb-4 {text_color}",
+            if draft.name.is_empty() { "New Query Test" } else { "Edit Query Test" }
+        }
+
+        div {
+            class: "flex-1 overflow-auto space-y-4 pr-1",
+
+            div {
+                class: "flex space-x-3",
+                div {
+                    class: "flex-1",
+                    label { class: "block text-sm font-medium {label_color} mb-1", "Name" }
+                    input {
+                        class: "w-full px-3 py-2 border rounded text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                        r#type: "text",
+                        value: "{current.name}",
+                        autofocus: true,
+                        oninput: move |e| test.write().name = e.value(),
+                    }
+                }
+                div {
+                    class: "flex-1",
+                    label { class: "block text-sm font-medium {label_color} mb-1", "Saved query" }
+                    select {
+                        class: "w-full px-3 py-2 border rounded text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                        value: "{current.query}",
+                        onchange: move |e| test.write().query = e.value(),
+                        for query in queries.iter() {
+                            option { value: "{query.name}", "{query.name}" }
+                        }
+                    }
+                }
+            }
+
+            div {
+                div {
+                    class: "flex items-center justify-between mb-1",
+                    label { class: "text-sm font-medium {label_color}", "Fixtures" }
+                    button {
+                        class: "text-xs {label_color} hover:opacity-80",
+                        onclick: move |_| test.write().fixtures.push(TestFixture {
+                            table: String::new(),
+                            data: FixtureData::Values(String::new()),
+                        }),
+                        "+ Add fixture"
+                    }
+                }
+                if current.fixtures.is_empty() {
+                    div { class: "text-xs {muted_text}", "No fixtures; the query runs against the existing data." }
+                }
+                for (i, fixture) in current.fixtures.iter().enumerate() {
+                    div {
+                        key: "{i}",
+                        class: "space-y-1 mb-3",
+                        div {
+                            class: "flex space-x-2",
+                            input {
+                                class: "flex-1 px-2 py-1 border rounded text-sm font-mono focus:outline-none focus:border-blue-500 {input_class}",
+                                r#type: "text",
+                                placeholder: "Table",
+                                value: "{fixture.table}",
+                                oninput: move |e| test.write().fixtures[i].table = e.value(),
+                            }
+                            select {
+                                class: "px-2 py-1 border rounded text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                                value: if matches!(fixture.data, FixtureData::Csv(_)) { "csv" } else { "values" },
+                                onchange: move |e| {
+                                    let mut test = test.write();
+                                    let text = test.fixtures[i].data.text().to_string();
+                                    test.fixtures[i].data = if e.value() == "csv" {
+                                        FixtureData::Csv(text)
+                                    } else {
+                                        FixtureData::Values(text)
+                                    };
+                                },
+                                option { value: "values", "VALUES" }
+                                option { value: "csv", "CSV" }
+                            }
+                            button {
+                                class: "text-xs text-red-500 hover:opacity-80",
+                                onclick: move |_| {
+                                    test.write().fixtures.remove(i);
+                                },
+                                "Remove"
+                            }
+                        }
+                        textarea {
+                            class: "w-full px-2 py-1 border rounded text-xs font-mono h-20 focus:outline-none focus:border-blue-500 {input_class}",
+                            placeholder: if matches!(fixture.data, FixtureData::Csv(_)) { "id,name\n1,Alice" } else { "(1, 'Alice'), (2, 'Bob')" },
+                            value: "{fixture.data.text()}",
+                            oninput: move |e| {
+                                let mut test = test.write();
+                                test.fixtures[i].data = match test.fixtures[i].data {
+                                    FixtureData::Csv(_) => FixtureData::Csv(e.value()),
+                                    FixtureData::Values(_) => FixtureData::Values(e.value()),
+                                };
+                            },
+                        }
+                    }
+                }
+            }
+
+            div {
+                label { class: "block text-sm font-medium {label_color} mb-1", "Expected rows (CSV with header)" }
+                textarea {
+                    class: "w-full px-2 py-1 border rounded text-xs font-mono h-28 focus:outline-none focus:border-blue-500 {input_class}",
+                    placeholder: "id,name\n1,Alice",
+                    value: "{current.expected_csv}",
+                    oninput: move |e| test.write().expected_csv = e.value(),
+                }
+                if let Some(e) = csv_error {
+                    div { class: "text-xs text-red-500", "{e}" }
+                }
+                label {
+                    class: "flex items-center space-x-1 mt-1 text-xs {muted_text}",
+                    input {
+                        r#type: "checkbox",
+                        checked: current.ordered,
+                        onchange: move |_| {
+                            let ordered = !test.read().ordered;
+                            test.write().ordered = ordered;
+                        },
+                    }
+                    span { "Rows must come in this order" }
+                }
+            }
+
+            if let Some(error) = error {
+                div { class: "text-xs text-red-500", "{error}" }
+            }
+        }
+
+        div {
+            class: "flex justify-end space-x-2 mt-6",
+            button {
+                class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                onclick: move |_| on_cancel.call(()),
+                "Cancel"
+            }
+            button {
+                class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                disabled: !can_save,
+                onclick: move |_| {
+                    let mut saved = test.read().clone();
+                    saved.name = saved.name.trim().to_string();
+                    on_save.call(saved);
+                },
+                "Save test"
+            }
+        }
+    }
+}
@@ -109,9 +109,12 @@ pub fn QuickSwitcher() -> Element {
             let idx = *selected_index.read();
             if let Some(item) = items.read().get(idx) {
                 let sql = match item {
-                    SwitcherItem::Table { name } => {
-                        format_select_all_sql(current_db_type(), name, 100)
-                    }
+                    SwitcherItem::Table { name } => format_select_all_sql(
+                        current_db_type(),
+                        name,
+                        100,
+                        soft_delete_column(name).as_deref(),
+                    ),
                     SwitcherItem::Query { sql, .. } => sql.clone(),
                     SwitcherItem::History { sql, .. } => sql.clone(),
                 };
@@ -204,7 +207,12 @@ pub fn QuickSwitcher() -> Element {
                                         onclick: move |_| {
                                             let sql = match &item {
                                                 SwitcherItem::Table { name } => {
-                                                    format_select_all_sql(current_db_type(), name, 100)
+                                                    format_select_all_sql(
+                            current_db_type(),
+                            name,
+                            100,
+                            soft_delete_column(name).as_deref(),
+                        )
                                                 }
                                                 SwitcherItem::Query { sql, .. } => sql.clone(),
                                                 SwitcherItem::History { sql, .. } => sql.clone(),
@@ -0,0 +1,116 @@
+use crate::components::results_table::open_query_tab;
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn ReferencingRowsDialog() -> Element {
+    let state = REFERENCING_ROWS.read().clone();
+    let Some((label, tables)) = state else {
+        return rsx! {};
+    };
+
+    let is_dark = *IS_DARK_MODE.read();
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let cell_text = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let row_hover = if is_dark {
+        "hover:bg-gray-900"
+    } else {
+        "hover:bg-gray-100"
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *REFERENCING_ROWS.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[560px] max-w-[95vw] max-h-[80vh] flex flex-col",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                div {
+                    class: "px-4 py-3 border-b {dialog_border}",
+                    h2 {
+                        class: "text-lg font-semibold {text_color}",
+                        "Referencing rows"
+                    }
+                    div {
+                        class: "text-xs font-mono {muted_text} truncate",
+                        "{label}"
+                    }
+                }
+
+                div {
+                    class: "flex-1 overflow-auto py-1",
+
+                    if tables.is_empty() {
+                        div {
+                            class: "{muted_text} text-sm text-center py-8",
+                            "No tables reference this row"
+                        }
+                    }
+
+                    for entry in tables {
+                        {
+                            let count_label = match &entry.count {
+                                None => "...".to_string(),
+                                Some(Ok(n)) => n.to_string(),
+                                Some(Err(_)) => "error".to_string(),
+                            };
+                            let count_title = match &entry.count {
+                                Some(Err(e)) => e.clone(),
+                                _ => String::new(),
+                            };
+                            let has_rows = !matches!(entry.count, Some(Ok(0)));
+                            let columns = entry.columns.join(", ");
+                            let title = format!("{} ← {}", entry.table, label);
+                            let sql = entry.select_sql.clone();
+                            rsx! {
+                                button {
+                                    class: "w-full text-left px-4 py-2 flex items-center justify-between text-sm {row_hover}",
+                                    class: if has_rows { "" } else { "opacity-50" },
+                                    onclick: move |_| {
+                                        open_query_tab(title.clone(), sql.clone());
+                                        *REFERENCING_ROWS.write() = None;
+                                    },
+                                    div {
+                                        span { class: "{cell_text} font-mono", "{entry.table}" }
+                                        span { class: "{muted_text} text-xs ml-2", "via {columns}" }
+                                    }
+                                    span {
+                                        class: "text-xs {muted_text}",
+                                        title: "{count_title}",
+                                        "{count_label}"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use crate::db::{format_replace_count_sql, format_replace_sql, DbRequest};
+use crate::state::*;
+use dioxus::prelude::*;
+
+const PREVIEW_LIMIT: usize = 20;
+/// Typing pause before the matching rows are counted on the server
+const COUNT_DEBOUNCE_MS: u64 = 400;
+
+#[component]
+pub fn ReplaceValuesDialog() -> Element {
+    let column = REPLACE_COLUMN.read().clone();
+    rsx! {
+        if let Some(column) = column {
+            ReplaceValuesDialogContent { column }
+        }
+    }
+}
+
+#[component]
+fn ReplaceValuesDialogContent(column: String) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut find = use_signal(String::new);
+    let mut replace = use_signal(String::new);
+    let mut count_generation = use_signal(|| 0u64);
+
+    // Count the rows the UPDATE would change once typing pauses
+    use_effect({
+        let column = column.clone();
+        move || {
+            let find_text = find.read().clone();
+            let generation = {
+                let mut current = count_generation.write();
+                *current += 1;
+                *current
+            };
+            let count_sql = EDITOR_TABS
+                .read()
+                .active_tab()
+                .and_then(|t| t.result.as_ref()?.source_table.clone())
+                .and_then(|table| {
+                    let db_type = CURRENT_DB_TYPE.read().unwrap_or(DatabaseType::PostgreSQL);
+                    format_replace_count_sql(db_type, &table, &column, &find_text)
+                });
+            let Some(count_sql) = count_sql else {
+                *REPLACE_MATCH_COUNT.write() = None;
+                return;
+            };
+            *REPLACE_MATCH_COUNT.write() = Some((count_sql.clone(), None));
+            spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_millis(COUNT_DEBOUNCE_MS)).await;
+                if *count_generation.peek() == generation {
+                    send_db_request(DbRequest::FetchRowCount(count_sql));
+                }
+            });
+        }
+    });
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    let Some(result) = EDITOR_TABS
+        .read()
+        .active_tab()
+        .and_then(|t| t.result.clone())
+    else {
+        return rsx! {};
+    };
+    let Some(table) = result.source_table.clone() else {
+        return rsx! {};
+    };
+
+    let db_type = CURRENT_DB_TYPE.read().unwrap_or(DatabaseType::PostgreSQL);
+    let find_text = find.read().clone();
+    let replace_text = replace.read().clone();
+    let sql = format_replace_sql(db_type, &table, &column, &find_text, &replace_text);
+    let count_sql = format_replace_count_sql(db_type, &table, &column, &find_text);
+
+    let table_count = REPLACE_MATCH_COUNT
+        .read()
+        .as_ref()
+        .filter(|(pending, _)| Some(pending) == count_sql.as_ref())
+        .and_then(|(_, count)| count.clone());
+
+    // Preview the change against the rows already loaded in the grid
+    let col_idx = result.columns.iter().position(|c| c == &column);
+    let matches: Vec<(String, String)> = match col_idx {
+        Some(idx) if !find_text.is_empty() => result
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(row_idx, _)| !result.is_null(*row_idx, idx))
+            .filter_map(|(_, row)| row.get(idx))
+            .filter(|value| value.contains(&find_text))
+            .map(|value| (value.clone(), value.replace(&find_text, &replace_text)))
+            .collect(),
+        _ => Vec::new(),
+    };
+    let match_count = matches.len();
+    let loaded_count = result.rows.len();
+    let count_text = match table_count {
+        Some(Ok(count)) => format!(
+            "{} rows in the table match and will be updated ({} of the {} loaded rows).",
+            count, match_count, loaded_count
+        ),
+        Some(Err(e)) => format!("Couldn't count the matching rows: {}", e),
+        None => "Counting matching rows...".to_string(),
+    };
+    let preview_sql = sql
+        .clone()
+        .unwrap_or_else(|| "-- Enter text to find".to_string());
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *REPLACE_COLUMN.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[720px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-4 {text_color}",
+                    "Replace values in {table}.{column}"
+                }
+
+                div {
+                    class: "grid grid-cols-2 gap-4",
+                    div {
+                        label {
+                            class: "block text-sm font-medium {label_color} mb-1",
+                            "Find"
+                        }
+                        input {
+                            class: "w-full px-3 py-2 border rounded font-mono text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                            r#type: "text",
+                            value: "{find_text}",
+                            autofocus: true,
+                            oninput: move |e| find.set(e.value()),
+                        }
+                    }
+                    div {
+                        label {
+                            class: "block text-sm font-medium {label_color} mb-1",
+                            "Replace with"
+                        }
+                        input {
+                            class: "w-full px-3 py-2 border rounded font-mono text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                            r#type: "text",
+                            value: "{replace_text}",
+                            oninput: move |e| replace.set(e.value()),
+                        }
+                    }
+                }
+
+                pre {
+                    class: "mt-4 {preview_bg} border {dialog_border} rounded p-3 font-mono text-xs {label_color} whitespace-pre-wrap break-all",
+                    "{preview_sql}"
+                }
+
+                if !find_text.is_empty() {
+                    div {
+                        class: "mt-4 text-xs {muted_text}",
+                        "{count_text}"
+                    }
+                    div {
+                        class: "mt-2 flex-1 overflow-auto border {dialog_border} rounded",
+                        table {
+                            class: "w-full text-xs text-left font-mono",
+                            thead {
+                                class: "{muted_text} uppercase sticky top-0 {dialog_bg}",
+                                tr {
+                                    th { class: "px-3 py-1.5", "Before" }
+                                    th { class: "px-3 py-1.5", "After" }
+                                }
+                            }
+                            tbody {
+                                for (before, after) in matches.into_iter().take(PREVIEW_LIMIT) {
+                                    tr {
+                                        td { class: "px-3 py-1 {label_color} line-through opacity-60", "{before}" }
+                                        td { class: "px-3 py-1 text-green-500", "{after}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *REPLACE_COLUMN.write() = None,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                        disabled: sql.is_none(),
+                        onclick: move |_| {
+                            if let Some(sql) = sql.clone() {
+                                send_tab_request(DbRequest::ExecuteMutation(sql));
+                                *REPLACE_COLUMN.write() = None;
+                            }
+                        },
+                        "Replace"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,15 +1,34 @@
+use crate::components::cell_renderers::CellValue;
+use crate::components::environment::EnvironmentStripe;
 use crate::components::filter_panel::{toggle_sort, FilterPanel};
-use crate::db::{normalize_table_name, quote_identifier};
+use crate::components::json_viewer::{cancel_cell_compare, open_cell_detail};
+use crate::components::seed_script::open_seed_script;
+use crate::config::{DisplayStore, EmptyStringDisplay};
+use crate::db::{
+    format_bytes, format_count, normalize_table_name, quote_identifier, ColumnRules,
+    IsolationLevel, QueryTiming, ScriptResult,
+};
 use crate::filter::SortDirection;
-use crate::state::tabs::CellEdit;
+use crate::state::tabs::{CellEdit, SavingChange};
 use crate::state::*;
 use dioxus::prelude::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 pub static EDITING_CELL: GlobalSignal<Option<(usize, usize)>> = Signal::global(|| None);
 pub static SELECTED_ROWS: GlobalSignal<std::collections::HashSet<usize>> =
     Signal::global(Default::default);
 pub static INSERTING_ROW: GlobalSignal<bool> = Signal::global(|| false);
+pub static CELL_EDIT_ERROR: GlobalSignal<Option<String>> = Signal::global(|| None);
+/// Choices offered for how NULL cells read
+const NULL_TEXT_PRESETS: [&str; 4] = ["NULL", "(null)", "<null>", "—"];
+/// Rows skipped by Page Up / Page Down in the grid
+const PAGE_ROWS: isize = 20;
+/// Cell moved with the arrow keys while the grid has focus
+pub static SELECTED_CELL: GlobalSignal<Option<(usize, usize)>> = Signal::global(|| None);
+/// Set when the statement at the cursor is run, so its result is brought into view
+pub static RESULTS_FOCUS_PENDING: GlobalSignal<bool> = Signal::global(|| false);
+/// FK cell under the pointer and the cache key of its preview
+static FK_HOVER: GlobalSignal<Option<((usize, usize), String)>> = Signal::global(|| None);
 
 struct FkLink {
     foreign_table: String,
@@ -44,6 +63,15 @@ fn detect_fk_columns(source_table: &str, result_columns: &[String]) -> HashMap<u
             continue;
         };
 
+        // A composite key can only be followed when the result has all of its columns
+        if !constraint
+            .columns
+            .iter()
+            .all(|c| result_columns.contains(c))
+        {
+            continue;
+        }
+
         for (local_col, _foreign_col) in constraint.columns.iter().zip(foreign_columns.iter()) {
             if let Some(col_idx) = result_columns.iter().position(|c| c == local_col) {
                 let mapping: Vec<(String, String)> = constraint
@@ -67,15 +95,35 @@ fn detect_fk_columns(source_table: &str, result_columns: &[String]) -> HashMap<u
     fk_map
 }
 
-fn navigate_fk(
-    foreign_table: &str,
+/// Comments of the columns of `source_table` that have one, by column name.
+fn column_comments(source_table: &str) -> HashMap<String, String> {
+    let schema = SCHEMA.read();
+    let source_table = normalize_table_name(source_table);
+    schema
+        .tables
+        .iter()
+        .find(|t| t.name == source_table)
+        .map(|table| {
+            table
+                .columns
+                .iter()
+                .filter_map(|c| Some((c.name.clone(), c.comment.clone()?)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// WHERE conditions selecting the row a foreign key points at, one per key column.
+/// `None` when a column is missing or NULL, since the key then points at no row and
+/// matching on the remaining columns of a composite key would find the wrong ones.
+fn fk_conditions(
     column_mapping: &[(String, String)],
     row: &[String],
     result_columns: &[String],
-) {
-    let conditions: Vec<String> = column_mapping
+) -> Option<Vec<String>> {
+    column_mapping
         .iter()
-        .filter_map(|(local_col, foreign_col)| {
+        .map(|(local_col, foreign_col)| {
             let col_idx = result_columns.iter().position(|c| c == local_col)?;
             let value = row.get(col_idx)?;
             if value == "NULL" {
@@ -88,11 +136,75 @@ fn navigate_fk(
                 ))
             }
         })
+        .collect()
+}
+
+/// Cache key of the FK preview for a row, fetching it if this session has not seen it yet.
+fn request_fk_preview(
+    foreign_table: &str,
+    column_mapping: &[(String, String)],
+    row: &[String],
+    result_columns: &[String],
+) -> Option<String> {
+    let conditions = fk_conditions(column_mapping, row, result_columns)?;
+    let key = format!("{}|{}", foreign_table, conditions.join(" AND "));
+    if !FK_PREVIEWS.read().contains_key(&key) {
+        FK_PREVIEWS.write().insert(key.clone(), FkPreview::Loading);
+        send_db_request(crate::db::DbRequest::FetchFkPreview {
+            key: key.clone(),
+            sql: format!(
+                "SELECT * FROM {} WHERE {} LIMIT 1",
+                quote_identifier(current_db_type(), foreign_table),
+                conditions.join(" AND ")
+            ),
+        });
+    }
+    Some(key)
+}
+
+/// Columns worth showing on an FK hover card: the key plus descriptive columns.
+fn fk_preview_fields(row: &[(String, String)], key_columns: &[String]) -> Vec<(String, String)> {
+    const DESCRIPTIVE: &[&str] = &[
+        "name",
+        "title",
+        "email",
+        "username",
+        "label",
+        "display_name",
+        "full_name",
+        "first_name",
+        "last_name",
+        "code",
+        "slug",
+    ];
+    let mut fields: Vec<(String, String)> = row
+        .iter()
+        .filter(|(c, _)| {
+            key_columns.contains(c) || DESCRIPTIVE.contains(&c.to_lowercase().as_str())
+        })
+        .cloned()
         .collect();
+    // No descriptive columns: fall back to the first few others
+    if fields.len() <= key_columns.len() {
+        fields.extend(
+            row.iter()
+                .filter(|(c, _)| !key_columns.contains(c))
+                .take(3)
+                .cloned(),
+        );
+    }
+    fields
+}
 
-    if conditions.is_empty() {
+fn navigate_fk(
+    foreign_table: &str,
+    column_mapping: &[(String, String)],
+    row: &[String],
+    result_columns: &[String],
+) {
+    let Some(conditions) = fk_conditions(column_mapping, row, result_columns) else {
         return;
-    }
+    };
 
     let sql = format!(
         "SELECT * FROM {} WHERE {}",
@@ -100,33 +212,134 @@ fn navigate_fk(
         conditions.join(" AND ")
     );
 
-    let tab_title = format!(
-        "{} [{}]",
-        foreign_table,
-        conditions
-            .first()
-            .map(|c| c.split(" = ").last().unwrap_or("?").trim_matches('\''))
-            .unwrap_or("?")
-    );
+    // Every value of a composite key, so tabs for sibling rows can be told apart
+    let key_values: Vec<&str> = column_mapping
+        .iter()
+        .filter_map(|(local_col, _)| {
+            let col_idx = result_columns.iter().position(|c| c == local_col)?;
+            row.get(col_idx).map(String::as_str)
+        })
+        .collect();
+    let tab_title = format!("{} [{}]", foreign_table, key_values.join(", "));
 
+    open_query_tab(tab_title, sql);
+}
+
+/// Open `sql` in a new tab and run it.
+pub fn open_query_tab(title: String, sql: String) {
     let tab_sql = sql.clone();
     {
         let mut tabs = EDITOR_TABS.write();
-        let id = tabs.add_tab(tab_title);
+        let id = tabs.add_tab(title);
         if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == id) {
             tab.content = tab_sql;
         }
     }
 
-    send_db_request(crate::db::DbRequest::Execute(sql));
+    send_tab_request(crate::db::DbRequest::Execute(sql));
+}
+
+/// Child tables whose foreign keys point at `row` of `source_table` — the inverse of `navigate_fk`.
+fn referencing_tables(
+    source_table: &str,
+    row: &[String],
+    result_columns: &[String],
+) -> Vec<ReferencingTable> {
+    let schema = SCHEMA.read();
+    let source_table = normalize_table_name(source_table);
+    let db_type = current_db_type();
+
+    let mut tables = Vec::new();
+    for child in &schema.tables {
+        for constraint in &child.constraints {
+            if constraint.constraint_type != "FOREIGN KEY"
+                || constraint
+                    .foreign_table
+                    .as_deref()
+                    .is_none_or(|t| normalize_table_name(t) != source_table)
+            {
+                continue;
+            }
+            let Some(ref foreign_columns) = constraint.foreign_columns else {
+                continue;
+            };
+            let mapping: Vec<(String, String)> = foreign_columns
+                .iter()
+                .cloned()
+                .zip(constraint.columns.iter().cloned())
+                .collect();
+            let Some(conditions) = fk_conditions(&mapping, row, result_columns) else {
+                continue;
+            };
+            let table = quote_identifier(db_type, &child.name);
+            let where_clause = conditions.join(" AND ");
+            tables.push(ReferencingTable {
+                table: child.name.clone(),
+                columns: constraint.columns.clone(),
+                select_sql: format!("SELECT * FROM {} WHERE {}", table, where_clause),
+                count: None,
+            });
+        }
+    }
+    tables
+}
+
+fn show_referencing_rows() {
+    let Some(row_idx) = SELECTED_ROWS.read().iter().next().copied() else {
+        return;
+    };
+    let (label, tables) = {
+        let tabs = EDITOR_TABS.read();
+        let Some(result) = tabs.active_tab().and_then(|t| t.result.as_ref()) else {
+            return;
+        };
+        let (Some(table), Some(row)) = (&result.source_table, result.rows.get(row_idx)) else {
+            return;
+        };
+        let key = result
+            .row_key(row_idx)
+            .unwrap_or_default()
+            .iter()
+            .map(|(pk, value)| format!("{} = {}", pk, value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        (
+            format!("{} [{}]", normalize_table_name(table), key),
+            referencing_tables(table, row, &result.columns),
+        )
+    };
+
+    let counts: Vec<String> = tables
+        .iter()
+        .map(|t| t.select_sql.replacen("SELECT *", "SELECT COUNT(*)", 1))
+        .collect();
+    *REFERENCING_ROWS.write() = Some((label, tables));
+    if !counts.is_empty() {
+        send_db_request(crate::db::DbRequest::FetchReferencingCounts(counts));
+    }
 }
 
 #[component]
 pub fn ResultsTable() -> Element {
+    use_effect(move || {
+        let failed = LAST_ERROR.read().is_some();
+        let _ = QUERY_RESULT.read();
+        *SELECTED_CELL.write() = None;
+        if *RESULTS_FOCUS_PENDING.peek() {
+            *RESULTS_FOCUS_PENDING.write() = false;
+            if !failed {
+                follow_to_results();
+            }
+        }
+    });
+
     let tabs = EDITOR_TABS.read();
     let active_tab = tabs.active_tab();
     let result = active_tab.and_then(|t| t.result.clone());
+    let script = active_tab.and_then(|t| t.script.clone());
+    let script_statement = active_tab.map(|t| t.script_statement).unwrap_or(0);
     let error = active_tab.and_then(|t| t.last_error.clone());
+    let timed_out = active_tab.is_some_and(|t| t.timed_out);
     let exec_time = active_tab.and_then(|t| t.execution_time_ms);
     let current_sort = active_tab
         .and_then(|t| t.filter_state.as_ref())
@@ -137,14 +350,28 @@ pub fn ResultsTable() -> Element {
         .unwrap_or(false);
     let can_edit = result
         .as_ref()
-        .map(|r| r.source_table.is_some() && !r.primary_keys.is_empty())
+        .map(|r| r.source_table.is_some() && r.has_full_key())
         .unwrap_or(false);
     let edit_mode = active_tab.map(|t| t.edit_mode).unwrap_or(false);
     let pending_edits = active_tab
         .map(|t| t.pending_edits.clone())
         .unwrap_or_default();
     let selected_rows = SELECTED_ROWS.read().clone();
+    let soft_delete = result
+        .as_ref()
+        .and_then(|r| r.source_table.as_deref())
+        .and_then(soft_delete_column);
     let inserting = *INSERTING_ROW.read();
+    let detect_cell_types = DISPLAY_SETTINGS.read().detect_cell_types;
+    let focus_on_run = DISPLAY_SETTINGS.read().focus_results_on_run;
+    let null_text = DISPLAY_SETTINGS.read().null_text.clone();
+    let empty_string = DISPLAY_SETTINGS.read().empty_string;
+    let empty_marker = empty_string.marker();
+    let grid_size = result
+        .as_ref()
+        .map(|r| (r.rows.len(), r.columns.len()))
+        .unwrap_or_default();
+    let selected_cell = *SELECTED_CELL.read();
     let is_dark = *IS_DARK_MODE.read();
 
     // Theme-aware classes
@@ -180,13 +407,60 @@ pub fn ResultsTable() -> Element {
         div {
             class: "flex flex-col h-full",
 
+            EnvironmentStripe {}
+
+            if let Some(script) = script {
+                ScriptResultsBar { script, selected: script_statement }
+            }
+
             div {
                 class: "h-8 {header_bg} border-b {header_border} flex items-center px-3 justify-between",
 
-                if let Some(error) = error {
+                if let Some(error) = error.as_ref().filter(|_| timed_out) {
+                    span {
+                        class: "text-yellow-500 text-sm",
+                        title: "Raise the statement timeout in the connection settings to let it finish",
+                        "⏱ {error}"
+                    }
+                } else if let Some(error) = error {
                     span { class: "text-red-500 text-sm", "{error}" }
                 } else if let Some(ref result) = result {
-                    span { class: "{header_text} text-sm", "{result.rows.len()} rows" }
+                    div {
+                        class: "flex items-center",
+                        if let Some(limit) = result.limited_to {
+                            span {
+                                class: "{header_text} text-sm",
+                                "showing first {format_count(limit)} rows —"
+                            }
+                            button {
+                                class: "text-sm text-blue-500 hover:text-blue-400 ml-1",
+                                title: "Run the query again with a larger limit",
+                                onclick: {
+                                    let sql = result.sql.clone();
+                                    move |_| {
+                                        let step = QUERY_SETTINGS.read().auto_limit.unwrap_or(limit);
+                                        send_tab_request(crate::db::DbRequest::FetchMore {
+                                            sql: sql.clone(),
+                                            limit: limit + step,
+                                        });
+                                    }
+                                },
+                                "fetch more"
+                            }
+                        } else {
+                            span {
+                                class: "{header_text} text-sm",
+                                "{result.rows.len()} rows"
+                                if result.fetch_stopped {
+                                    span {
+                                        class: "{muted_text} ml-1",
+                                        title: "Fetching was stopped; run the query again to get every row",
+                                        "(fetching stopped)"
+                                    }
+                                }
+                            }
+                        }
+                    }
                 } else {
                     span { class: "{muted_text} text-sm", "No results" }
                 }
@@ -194,10 +468,26 @@ pub fn ResultsTable() -> Element {
                 div {
                     class: "flex items-center space-x-3",
 
-                    if let Some(exec_time) = exec_time {
+                    if let Some(bytes) = result.as_ref().and_then(|r| r.bytes_processed) {
                         span {
                             class: "text-xs {muted_text}",
-                            "{exec_time}ms"
+                            title: "{bytes} bytes processed",
+                            "{format_bytes(bytes)} processed"
+                        }
+                    }
+
+                    if let Some(exec_time) = exec_time {
+                        if let Some(result) = &result {
+                            TimingBadge {
+                                sql: result.sql.clone(),
+                                total_ms: exec_time,
+                                timing: result.timing,
+                            }
+                        } else {
+                            span {
+                                class: "text-xs {muted_text}",
+                                "{exec_time}ms"
+                            }
                         }
                     }
 
@@ -222,6 +512,7 @@ pub fn ResultsTable() -> Element {
                                         *SELECTED_ROWS.write() = Default::default();
                                         *EDITING_CELL.write() = None;
                                         *INSERTING_ROW.write() = false;
+                                        *CELL_EDIT_ERROR.write() = None;
                                     },
                                     if edit_mode { "Editing" } else { "Edit" }
                                 }
@@ -229,6 +520,17 @@ pub fn ResultsTable() -> Element {
                         }
                     }
 
+                    if let Some(edit_error) = CELL_EDIT_ERROR.read().clone() {
+                        span {
+                            class: "text-xs text-red-500",
+                            "{edit_error}"
+                        }
+                    }
+
+                    if edit_mode {
+                        TransactionIndicator {}
+                    }
+
                     // Save/Discard when there are pending edits
                     if !pending_edits.is_empty() {
                         span {
@@ -254,15 +556,88 @@ pub fn ResultsTable() -> Element {
                             onclick: move |_| *INSERTING_ROW.write() = true,
                             "+ Row"
                         }
+                        if selected_rows.len() == 1 {
+                            button {
+                                class: "text-xs px-2 py-1 rounded {header_text} hover:opacity-80",
+                                title: "Show this row's changes from its audit table",
+                                onclick: move |_| show_selected_row_history(),
+                                "History"
+                            }
+                            button {
+                                class: "text-xs px-2 py-1 rounded {header_text} hover:opacity-80",
+                                title: "Show rows in other tables that reference this row",
+                                onclick: move |_| show_referencing_rows(),
+                                "Referenced By"
+                            }
+                        }
                         if !selected_rows.is_empty() {
+                            button {
+                                class: "text-xs px-2 py-1 rounded {header_text} hover:opacity-80",
+                                onclick: move |_| *SHOW_BULK_EDIT.write() = true,
+                                "Bulk Edit ({selected_rows.len()})"
+                            }
                             button {
                                 class: "text-xs px-2 py-1 rounded text-red-500 hover:text-red-400",
                                 onclick: move |_| delete_selected_rows(),
-                                "Delete ({selected_rows.len()})"
+                                if soft_delete.is_some() {
+                                    "Soft Delete ({selected_rows.len()})"
+                                } else {
+                                    "Delete ({selected_rows.len()})"
+                                }
                             }
                         }
                     }
 
+                    if result.is_some() && !edit_mode {
+                        button {
+                            class: "text-xs px-2 py-1 rounded hover:opacity-80",
+                            class: if detect_cell_types { "text-blue-500" } else { header_text },
+                            title: "Detect URLs, emails, UUIDs, timestamps and colors in cells",
+                            onclick: move |_| toggle_cell_type_detection(),
+                            "Smart cells"
+                        }
+                    }
+
+                    if result.is_some() {
+                        select {
+                            class: "text-xs px-1 py-1 rounded {header_bg} {header_text} border {header_border}",
+                            title: "How NULL is shown",
+                            value: "{null_text}",
+                            onchange: move |evt: FormEvent| set_null_text(evt.value()),
+                            for preset in NULL_TEXT_PRESETS {
+                                option { value: "{preset}", "NULL as {preset}" }
+                            }
+                            if !NULL_TEXT_PRESETS.contains(&null_text.as_str()) {
+                                option { value: "{null_text}", "NULL as {null_text}" }
+                            }
+                        }
+                        button {
+                            class: "text-xs px-2 py-1 rounded {header_text} hover:opacity-80",
+                            title: "Show empty strings as ∅ or ''",
+                            onclick: move |_| toggle_empty_string_display(),
+                            "Empty as {empty_marker}"
+                        }
+                        button {
+                            class: "text-xs px-2 py-1 rounded hover:opacity-80",
+                            class: if focus_on_run { "text-blue-500" } else { header_text },
+                            title: "Move keyboard focus to the grid after Ctrl+Shift+Enter",
+                            onclick: move |_| toggle_focus_on_run(),
+                            "Focus on run"
+                        }
+                    }
+
+                    if let Some(result) = result.clone().filter(|r| r.source_table.is_some() && !edit_mode) {
+                        button {
+                            class: "text-xs px-2 py-1 rounded {header_text} hover:opacity-80",
+                            title: "INSERT script of these rows, for test fixtures",
+                            onclick: move |_| {
+                                let table = result.source_table.clone().unwrap_or_default();
+                                open_seed_script(table, Some(result.clone()));
+                            },
+                            "Seed script"
+                        }
+                    }
+
                     // Explain button (only when we have results)
                     if result.is_some() {
                         button {
@@ -274,11 +649,152 @@ pub fn ResultsTable() -> Element {
                 }
             }
 
+            if let Some((budget_sql, message)) = PLAN_BUDGET_WARNING.read().clone() {
+                div {
+                    class: "flex items-center space-x-3 px-3 py-2 border-b {header_border} bg-yellow-900 bg-opacity-20 text-xs",
+                    span {
+                        class: "flex-1 text-yellow-500",
+                        "Query not run: {message}."
+                    }
+                    button {
+                        class: "px-2 py-1 rounded bg-yellow-600 text-white hover:bg-yellow-500",
+                        onclick: move |_| {
+                            *PLAN_BUDGET_WARNING.write() = None;
+                            send_tab_request(crate::db::DbRequest::ExecuteOverBudget(budget_sql.clone()));
+                        },
+                        "Run anyway"
+                    }
+                    button {
+                        class: "px-2 py-1 rounded {header_text} hover:opacity-80",
+                        onclick: move |_| *PLAN_BUDGET_WARNING.write() = None,
+                        "Cancel"
+                    }
+                }
+            }
+
+            if let Some(retry) = TRANSACTION_RETRY.read().clone() {
+                match retry {
+                    TransactionRetry::Succeeded(retries) => rsx! {
+                        div {
+                            class: "flex items-center space-x-3 px-3 py-2 border-b {header_border} text-xs",
+                            span {
+                                class: "flex-1 text-green-500",
+                                "Changes saved after {retries} retry(s) due to deadlocks or serialization failures."
+                            }
+                            button {
+                                class: "px-2 py-1 rounded {header_text} hover:opacity-80",
+                                onclick: move |_| *TRANSACTION_RETRY.write() = None,
+                                "Dismiss"
+                            }
+                        }
+                    },
+                    TransactionRetry::Failed { statements, options, row_edits, error, retries } => rsx! {
+                        div {
+                            class: "flex items-center space-x-3 px-3 py-2 border-b {header_border} bg-red-900 bg-opacity-20 text-xs",
+                            span {
+                                class: "flex-1 text-red-500",
+                                if retries > 0 {
+                                    "Changes not saved after {retries} retry(s): {error}"
+                                } else {
+                                    "Changes not saved: {error}"
+                                }
+                            }
+                            button {
+                                class: "px-2 py-1 rounded bg-blue-600 text-white hover:bg-blue-500",
+                                onclick: move |_| {
+                                    *TRANSACTION_RETRY.write() = None;
+                                    let statements = statements.clone();
+                                    send_tab_request(if row_edits {
+                                        crate::db::DbRequest::SaveRowEdits(statements, options)
+                                    } else {
+                                        crate::db::DbRequest::ExecuteBatch(statements, options)
+                                    });
+                                },
+                                "Retry"
+                            }
+                            button {
+                                class: "px-2 py-1 rounded {header_text} hover:opacity-80",
+                                onclick: move |_| *TRANSACTION_RETRY.write() = None,
+                                "Dismiss"
+                            }
+                        }
+                    },
+                }
+            }
+
+            if *PICKING_COMPARE_CELL.read() {
+                div {
+                    class: "flex items-center space-x-3 px-3 py-2 border-b {header_border} text-xs",
+                    span {
+                        class: "flex-1 text-blue-500",
+                        "Double-click a cell to compare it with the one you were viewing."
+                    }
+                    button {
+                        class: "px-2 py-1 rounded {header_text} hover:opacity-80",
+                        onclick: move |_| cancel_cell_compare(),
+                        "Cancel"
+                    }
+                }
+            }
+
             // Filter panel (only for single-table queries)
             FilterPanel {}
 
             div {
-                class: "flex-1 overflow-auto",
+                id: "results-grid",
+                class: "flex-1 overflow-auto focus:outline-none",
+                tabindex: "0",
+                onkeydown: move |evt: KeyboardEvent| {
+                    if EDITING_CELL.read().is_some() || *INSERTING_ROW.read() {
+                        return;
+                    }
+                    let ctrl = evt.modifiers().contains(keyboard_types::Modifiers::CONTROL);
+                    let (row, col) = SELECTED_CELL
+                        .read()
+                        .map(|(r, c)| (r as isize, c as isize))
+                        .unwrap_or_default();
+                    let target = match evt.key() {
+                        Key::ArrowUp => Some((row - 1, col)),
+                        Key::ArrowDown => Some((row + 1, col)),
+                        Key::ArrowLeft => Some((row, col - 1)),
+                        Key::ArrowRight => Some((row, col + 1)),
+                        Key::PageUp => Some((row - PAGE_ROWS, col)),
+                        Key::PageDown => Some((row + PAGE_ROWS, col)),
+                        Key::Home if ctrl => Some((0, 0)),
+                        Key::Home => Some((row, 0)),
+                        Key::End if ctrl => Some((isize::MAX, isize::MAX)),
+                        Key::End => Some((row, isize::MAX)),
+                        _ => None,
+                    };
+                    if let Some(target) = target {
+                        evt.prevent_default();
+                        select_cell(target, grid_size);
+                        return;
+                    }
+                    match evt.key() {
+                        Key::Enter => {
+                            if let Some(value) = selected_cell_value() {
+                                evt.prevent_default();
+                                open_cell_detail(value);
+                            }
+                        }
+                        Key::F2 if edit_mode => {
+                            if let Some(cell) = *SELECTED_CELL.read() {
+                                evt.prevent_default();
+                                *EDITING_CELL.write() = Some(cell);
+                            }
+                        }
+                        Key::Character(c) if ctrl && c.eq_ignore_ascii_case("c") => {
+                            evt.prevent_default();
+                            copy_selection();
+                        }
+                        Key::Escape => {
+                            *SELECTED_CELL.write() = None;
+                            focus_editor();
+                        }
+                        _ => {}
+                    }
+                },
 
                 if let Some(result) = result {
                     {
@@ -288,6 +804,11 @@ pub fn ResultsTable() -> Element {
                             .as_ref()
                             .map(|t| detect_fk_columns(t, &result.columns))
                             .unwrap_or_default();
+                        let column_comments = result
+                            .source_table
+                            .as_ref()
+                            .map(|t| column_comments(t))
+                            .unwrap_or_default();
 
                         let total_rows = result.rows.len();
                         rsx! {
@@ -332,9 +853,11 @@ pub fn ResultsTable() -> Element {
                                                 } else {
                                                     ""
                                                 };
+                                                let comment = column_comments.get(&col).cloned();
                                                 rsx! {
                                                     th {
                                                         class: "px-4 py-2 font-medium border-b {header_border}{clickable}",
+                                                        title: comment,
                                                         onclick: {
                                                             let col = col.clone();
                                                             move |_| {
@@ -347,6 +870,20 @@ pub fn ResultsTable() -> Element {
                                                         if let Some(indicator) = sort_indicator {
                                                             span { class: "ml-1", "{indicator}" }
                                                         }
+                                                        if edit_mode {
+                                                            button {
+                                                                class: "ml-2 opacity-50 hover:opacity-100 hover:text-blue-500",
+                                                                title: "Find and replace in this column",
+                                                                onclick: {
+                                                                    let col = col.clone();
+                                                                    move |e: MouseEvent| {
+                                                                        e.stop_propagation();
+                                                                        *REPLACE_COLUMN.write() = Some(col.clone());
+                                                                    }
+                                                                },
+                                                                "⇄"
+                                                            }
+                                                        }
                                                     }
                                                 }
                                             }
@@ -381,61 +918,127 @@ pub fn ResultsTable() -> Element {
 
                                             for (col_idx, cell) in row.iter().enumerate() {
                                                 {
-                                                    let is_null = cell == "NULL";
-                                                    let has_fk = !is_null && fk_map.contains_key(&col_idx);
                                                     let col_name = result.columns.get(col_idx).cloned().unwrap_or_default();
-                                                    let has_edit = pending_edits.iter().any(|e| {
-                                                        e.row_idx == row_idx && e.column == col_name
-                                                    });
-                                                    let display_value = if has_edit {
-                                                        pending_edits
-                                                            .iter()
-                                                            .find(|e| e.row_idx == row_idx && e.column == col_name)
-                                                            .map(|e| e.new_value.clone())
-                                                            .unwrap_or_else(|| cell.clone())
-                                                    } else {
-                                                        cell.clone()
+                                                    let edit = pending_edits
+                                                        .iter()
+                                                        .find(|e| e.row_idx == row_idx && e.column == col_name);
+                                                    let has_edit = edit.is_some();
+                                                    let (display_value, is_null) = match edit {
+                                                        Some(e) => (e.new_value.clone().unwrap_or_default(), e.new_value.is_none()),
+                                                        None if result.is_null(row_idx, col_idx) => (String::new(), true),
+                                                        None => (cell.clone(), false),
                                                     };
-                                                    let highlight_class = if has_edit {
+                                                    let has_fk = !is_null && fk_map.contains_key(&col_idx);
+                                                    let edit_class = if has_edit {
                                                         "bg-yellow-900 bg-opacity-30 border-l-2 border-yellow-500"
                                                     } else {
                                                         ""
                                                     };
+                                                    let highlight_class = if selected_cell == Some((row_idx, col_idx)) {
+                                                        format!("{edit_class} selected-cell ring-2 ring-inset ring-blue-500")
+                                                    } else {
+                                                        edit_class.to_string()
+                                                    };
                                                     let editing_this = *EDITING_CELL.read() == Some((row_idx, col_idx));
 
                                                     if editing_this && edit_mode {
-                                                        let original_value = cell.clone();
+                                                        let original_value = (!result.is_null(row_idx, col_idx)).then(|| cell.clone());
                                                         let col_for_commit = col_name.clone();
+                                                        let rules = column_rules(
+                                                            result.source_table.as_deref().unwrap_or_default(),
+                                                            &col_name,
+                                                        );
+                                                        let enum_values = rules.enum_values.clone();
+                                                        let hints = rules.hints.clone();
                                                         rsx! {
                                                             td {
                                                                 class: "px-4 py-2 {cell_text} font-mono {highlight_class}",
+                                                                if !enum_values.is_empty() {
+                                                                    datalist {
+                                                                        id: "cell-allowed-values",
+                                                                        for value in enum_values {
+                                                                            option { value: "{value}" }
+                                                                        }
+                                                                    }
+                                                                }
                                                                 input {
                                                                     class: "w-full bg-transparent border border-blue-500 px-1 outline-none {cell_text} font-mono text-sm",
                                                                     value: "{display_value}",
+                                                                    placeholder: if is_null { "NULL" } else { "" },
+                                                                    list: "cell-allowed-values",
                                                                     autofocus: true,
                                                                     onblur: move |_| {
                                                                         // Commit is handled via oninput tracking
                                                                         *EDITING_CELL.write() = None;
                                                                     },
                                                                     onkeydown: move |evt: KeyboardEvent| {
-                                                                        if evt.key() == Key::Escape {
-                                                                            *EDITING_CELL.write() = None;
+                                                                        // Keep the grid's own keys out of the editor
+                                                                        evt.stop_propagation();
+                                                                        match evt.key() {
+                                                                            Key::Escape => {
+                                                                                *EDITING_CELL.write() = None;
+                                                                                focus_grid();
+                                                                            }
+                                                                            // Blurring commits the value through onchange
+                                                                            Key::Enter => focus_grid(),
+                                                                            _ => {}
                                                                         }
                                                                     },
                                                                     onchange: {
                                                                         let original_value = original_value.clone();
                                                                         let col_for_commit = col_for_commit.clone();
+                                                                        let rules = rules.clone();
                                                                         move |evt: FormEvent| {
                                                                             let new_val = evt.value();
+                                                                            if let Err(e) = rules.validate(Some(&new_val)) {
+                                                                                *CELL_EDIT_ERROR.write() = Some(format!("{}: {}", col_for_commit, e));
+                                                                                *EDITING_CELL.write() = None;
+                                                                                return;
+                                                                            }
+                                                                            *CELL_EDIT_ERROR.write() = None;
+                                                                            commit_cell_edit(
+                                                                                row_idx,
+                                                                                &col_for_commit,
+                                                                                original_value.as_deref(),
+                                                                                Some(&new_val),
+                                                                            );
+                                                                            *EDITING_CELL.write() = None;
+                                                                        }
+                                                                    },
+                                                                }
+                                                                button {
+                                                                    class: "text-xs {muted_text} hover:text-blue-400 mt-1",
+                                                                    title: "Set the cell to NULL",
+                                                                    // Keep the input focused so its blur doesn't close the editor first
+                                                                    onmousedown: move |evt| evt.prevent_default(),
+                                                                    onclick: {
+                                                                        let original_value = original_value.clone();
+                                                                        let col_for_commit = col_for_commit.clone();
+                                                                        let rules = rules.clone();
+                                                                        move |_| {
+                                                                            if let Err(e) = rules.validate(None) {
+                                                                                *CELL_EDIT_ERROR.write() = Some(format!("{}: {}", col_for_commit, e));
+                                                                                *EDITING_CELL.write() = None;
+                                                                                return;
+                                                                            }
+                                                                            *CELL_EDIT_ERROR.write() = None;
                                                                             commit_cell_edit(
                                                                                 row_idx,
                                                                                 &col_for_commit,
-                                                                                &original_value,
-                                                                                &new_val,
+                                                                                original_value.as_deref(),
+                                                                                None,
                                                                             );
                                                                             *EDITING_CELL.write() = None;
+                                                                            focus_grid();
                                                                         }
                                                                     },
+                                                                    "Set NULL"
+                                                                }
+                                                                for hint in hints {
+                                                                    div {
+                                                                        class: "text-xs {muted_text} whitespace-normal mt-1",
+                                                                        "{hint}"
+                                                                    }
                                                                 }
                                                             }
                                                         }
@@ -443,12 +1046,17 @@ pub fn ResultsTable() -> Element {
                                                         rsx! {
                                                             td {
                                                                 class: "px-4 py-2 {cell_text} font-mono italic opacity-50 {highlight_class}",
+                                                                title: "NULL",
+                                                                onclick: move |_| *SELECTED_CELL.write() = Some((row_idx, col_idx)),
                                                                 ondoubleclick: move |_| {
                                                                     if edit_mode {
                                                                         *EDITING_CELL.write() = Some((row_idx, col_idx));
                                                                     }
                                                                 },
-                                                                "NULL"
+                                                                span {
+                                                                    class: if is_dark { "px-1 rounded bg-gray-900" } else { "px-1 rounded bg-gray-100" },
+                                                                    "{null_text}"
+                                                                }
                                                             }
                                                         }
                                                     } else if has_fk && !edit_mode {
@@ -457,9 +1065,64 @@ pub fn ResultsTable() -> Element {
                                                         let column_mapping = fk.column_mapping.clone();
                                                         let row_data = row.clone();
                                                         let columns = result.columns.clone();
+                                                        let key_columns: Vec<String> = column_mapping
+                                                            .iter()
+                                                            .map(|(_, f)| f.clone())
+                                                            .collect();
+                                                        let hover_preview = FK_HOVER
+                                                            .read()
+                                                            .as_ref()
+                                                            .filter(|(cell, _)| *cell == (row_idx, col_idx))
+                                                            .and_then(|(_, key)| FK_PREVIEWS.read().get(key).cloned());
+                                                        let hover_table = foreign_table.clone();
+                                                        let card_table = foreign_table.clone();
+                                                        let hover_mapping = column_mapping.clone();
+                                                        let hover_row = row_data.clone();
+                                                        let hover_columns = columns.clone();
                                                         rsx! {
                                                             td {
-                                                                class: "px-4 py-2 {cell_text} font-mono {highlight_class}",
+                                                                class: "px-4 py-2 {cell_text} font-mono {highlight_class} relative",
+                                                                onmouseenter: move |_| {
+                                                                    if let Some(key) = request_fk_preview(
+                                                                        &hover_table,
+                                                                        &hover_mapping,
+                                                                        &hover_row,
+                                                                        &hover_columns,
+                                                                    ) {
+                                                                        *FK_HOVER.write() = Some(((row_idx, col_idx), key));
+                                                                    }
+                                                                },
+                                                                onmouseleave: move |_| *FK_HOVER.write() = None,
+                                                                if let Some(preview) = hover_preview {
+                                                                    div {
+                                                                        class: "absolute left-2 top-full z-30 min-w-[180px] max-w-[320px] rounded border shadow-xl px-3 py-2 text-xs whitespace-normal pointer-events-none",
+                                                                        class: if is_dark { "bg-black border-gray-800" } else { "bg-white border-gray-200" },
+                                                                        div {
+                                                                            class: "{muted_text} mb-1",
+                                                                            "{card_table}"
+                                                                        }
+                                                                        match preview {
+                                                                            FkPreview::Loading => rsx! {
+                                                                                div { class: "{muted_text}", "Loading..." }
+                                                                            },
+                                                                            FkPreview::Missing => rsx! {
+                                                                                div { class: "text-yellow-500", "Referenced row not found" }
+                                                                            },
+                                                                            FkPreview::Failed(e) => rsx! {
+                                                                                div { class: "text-red-500", "{e}" }
+                                                                            },
+                                                                            FkPreview::Row(fields) => rsx! {
+                                                                                for (name, value) in fk_preview_fields(&fields, &key_columns) {
+                                                                                    div {
+                                                                                        class: "truncate",
+                                                                                        span { class: "{muted_text}", "{name}: " }
+                                                                                        span { class: "{cell_text}", "{value}" }
+                                                                                    }
+                                                                                }
+                                                                            },
+                                                                        }
+                                                                    }
+                                                                }
                                                                 a {
                                                                     class: "underline text-blue-500 hover:text-blue-400 cursor-pointer",
                                                                     onclick: move |_| {
@@ -475,15 +1138,29 @@ pub fn ResultsTable() -> Element {
                                                             }
                                                         }
                                                     } else {
+                                                        let detail_value = display_value.clone();
                                                         rsx! {
                                                             td {
                                                                 class: "px-4 py-2 {cell_text} font-mono {highlight_class}",
+                                                                onclick: move |_| *SELECTED_CELL.write() = Some((row_idx, col_idx)),
                                                                 ondoubleclick: move |_| {
                                                                     if edit_mode {
                                                                         *EDITING_CELL.write() = Some((row_idx, col_idx));
+                                                                    } else {
+                                                                        open_cell_detail(detail_value.clone());
                                                                     }
                                                                 },
-                                                                "{display_value}"
+                                                                if display_value.is_empty() {
+                                                                    span {
+                                                                        class: "{muted_text}",
+                                                                        title: "Empty string",
+                                                                        "{empty_marker}"
+                                                                    }
+                                                                } else if detect_cell_types && !edit_mode {
+                                                                    CellValue { value: display_value }
+                                                                } else {
+                                                                    "{display_value}"
+                                                                }
                                                             }
                                                         }
                                                     }
@@ -509,6 +1186,176 @@ pub fn ResultsTable() -> Element {
     }
 }
 
+/// Execution time that opens a breakdown of where it went.
+#[component]
+fn TimingBadge(sql: String, total_ms: u64, timing: QueryTiming) -> Element {
+    let mut expanded = use_signal(|| false);
+    let is_dark = *IS_DARK_MODE.read();
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let popup_class = if is_dark {
+        "bg-black border-gray-800 text-gray-300"
+    } else {
+        "bg-white border-gray-200 text-gray-700"
+    };
+    let profile = TIMING_PROFILE
+        .read()
+        .as_ref()
+        .filter(|(profiled, _)| *profiled == sql)
+        .map(|(_, timing)| timing.clone());
+    let can_profile = matches!(
+        *CONNECTION.read(),
+        ConnectionState::Connected {
+            db_type: DatabaseType::PostgreSQL | DatabaseType::MySQL,
+            ..
+        }
+    );
+
+    rsx! {
+        div {
+            class: "relative",
+            button {
+                class: "text-xs {muted_text} hover:opacity-80",
+                title: "Show timing breakdown",
+                onclick: move |_| expanded.set(!expanded()),
+                "{total_ms}ms"
+            }
+            if expanded() {
+                div {
+                    class: "absolute right-0 top-full mt-1 z-30 w-64 rounded border shadow-xl p-3 text-xs space-y-1 {popup_class}",
+                    TimingLine { label: "Fetch", ms: timing.fetch_ms }
+                    if let Some(format_ms) = timing.format_ms {
+                        TimingLine { label: "Format", ms: format_ms }
+                    }
+                    match profile {
+                        Some(Some(Ok(server))) => rsx! {
+                            div { class: "{muted_text} pt-1", "Server (EXPLAIN ANALYZE)" }
+                            if let Some(planning_ms) = server.planning_ms {
+                                TimingLine { label: "Planning", ms: planning_ms }
+                            }
+                            TimingLine { label: "Execution", ms: server.execution_ms }
+                        },
+                        Some(Some(Err(e))) => rsx! {
+                            div { class: "text-red-500 pt-1", "{e}" }
+                        },
+                        Some(None) => rsx! {
+                            div { class: "{muted_text} pt-1", "Measuring..." }
+                        },
+                        None if can_profile => rsx! {
+                            button {
+                                class: "text-blue-500 hover:opacity-80 pt-1",
+                                title: "Runs the query again under EXPLAIN ANALYZE",
+                                onclick: {
+                                    let sql = sql.clone();
+                                    move |_| {
+                                        *TIMING_PROFILE.write() = Some((sql.clone(), None));
+                                        send_db_request(crate::db::DbRequest::ProfileTiming(sql.clone()));
+                                    }
+                                },
+                                "Measure planning and execution"
+                            }
+                        },
+                        None => rsx! {},
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Show statement `index` of the active tab's script in the grid.
+fn show_script_statement(index: usize) {
+    let shown = EDITOR_TABS.write().active_tab_mut().map(|tab| {
+        tab.show_script_statement(index);
+        (tab.result.clone(), tab.last_error.clone())
+    });
+    if let Some((result, error)) = shown {
+        *QUERY_RESULT.write() = result;
+        *LAST_ERROR.write() = error;
+    }
+}
+
+/// The statements of the tab's last script with their status, rows and time; the
+/// picked one's result is shown below.
+#[component]
+fn ScriptResultsBar(script: ScriptResult, selected: usize) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let bar_bg = if is_dark { "bg-black" } else { "bg-gray-50" };
+    let bar_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-200"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let item_class = if is_dark {
+        "border-gray-800 text-gray-400 hover:text-white"
+    } else {
+        "border-gray-200 text-gray-600 hover:text-gray-900"
+    };
+    let selected_class = if is_dark {
+        "border-white text-white"
+    } else {
+        "border-blue-500 text-gray-900"
+    };
+    let skipped = script.total - script.statements.len();
+
+    rsx! {
+        div {
+            class: "{bar_bg} border-b {bar_border} flex items-center px-3 py-1 space-x-2 overflow-x-auto text-xs",
+            for (i, statement) in script.statements.iter().enumerate() {
+                {
+                    let first_line = statement.sql.lines().next().unwrap_or_default();
+                    let summary = match &statement.outcome {
+                        Ok(result) if result.columns.is_empty() => "done".to_string(),
+                        Ok(result) => format!("{} rows", format_count(result.rows.len())),
+                        Err(_) => "failed".to_string(),
+                    };
+                    let border = if i == selected { selected_class } else { item_class };
+                    rsx! {
+                        button {
+                            key: "{i}",
+                            class: "shrink-0 max-w-72 flex items-center space-x-1.5 px-2 py-0.5 rounded border {border}",
+                            title: "{statement.sql}",
+                            onclick: move |_| show_script_statement(i),
+                            if statement.outcome.is_ok() {
+                                span { class: "text-green-500", "✓" }
+                            } else {
+                                span { class: "text-red-500", "✗" }
+                            }
+                            span { class: "font-mono truncate", "{i + 1}. {first_line}" }
+                            span { class: "shrink-0 {muted_text}", "{summary} · {statement.execution_time_ms}ms" }
+                        }
+                    }
+                }
+            }
+            if skipped > 0 {
+                span {
+                    class: "shrink-0 {muted_text}",
+                    "{skipped} more not run after the failure"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TimingLine(label: &'static str, ms: f64) -> Element {
+    rsx! {
+        div {
+            class: "flex justify-between",
+            span { "{label}" }
+            span { class: "font-mono", "{ms:.1} ms" }
+        }
+    }
+}
+
 #[component]
 fn InsertRowForm(columns: Vec<String>, source_table: String) -> Element {
     let is_dark = *IS_DARK_MODE.read();
@@ -519,7 +1366,9 @@ fn InsertRowForm(columns: Vec<String>, source_table: String) -> Element {
     };
     let input_bg = if is_dark { "bg-gray-800" } else { "bg-white" };
 
-    let mut values = use_signal(|| vec![String::new(); columns.len()]);
+    // None inserts NULL; an empty value leaves the column to its default
+    let mut values = use_signal(|| vec![Some(String::new()); columns.len()]);
+    let auto_filled = auto_filled_columns(&source_table, &columns);
 
     rsx! {
         tr {
@@ -529,13 +1378,45 @@ fn InsertRowForm(columns: Vec<String>, source_table: String) -> Element {
             for (idx, col) in columns.iter().enumerate() {
                 td {
                     class: "px-4 py-1",
-                    input {
-                        class: "w-full text-xs px-1 py-1 rounded {input_bg} {cell_text} border border-green-700 font-mono",
-                        placeholder: "{col}",
-                        value: "{values.read()[idx]}",
-                        oninput: move |evt: FormEvent| {
-                            values.write()[idx] = evt.value();
-                        },
+                    if let Some(reason) = auto_filled[idx] {
+                        input {
+                            class: "w-full text-xs px-1 py-1 rounded {input_bg} {cell_text} border border-gray-700 font-mono italic opacity-50",
+                            placeholder: "{col} ({reason})",
+                            disabled: true,
+                        }
+                    } else {
+                        div {
+                            class: "flex items-center space-x-1",
+                            if values.read()[idx].is_some() {
+                                input {
+                                    class: "w-full text-xs px-1 py-1 rounded {input_bg} {cell_text} border border-green-700 font-mono",
+                                    placeholder: "{col}",
+                                    value: "{values.read()[idx].clone().unwrap_or_default()}",
+                                    oninput: move |evt: FormEvent| {
+                                        values.write()[idx] = Some(evt.value());
+                                    },
+                                }
+                            } else {
+                                input {
+                                    class: "w-full text-xs px-1 py-1 rounded {input_bg} {cell_text} border border-green-700 font-mono italic opacity-50",
+                                    placeholder: "{col} (NULL)",
+                                    disabled: true,
+                                }
+                            }
+                            button {
+                                class: "text-xs px-1 rounded",
+                                class: if values.read()[idx].is_none() { "bg-green-700 text-white" } else { "{cell_text} hover:text-white" },
+                                title: "Insert NULL into {col}",
+                                onclick: move |_| {
+                                    let mut values = values.write();
+                                    values[idx] = match values[idx] {
+                                        Some(_) => None,
+                                        None => Some(String::new()),
+                                    };
+                                },
+                                "NULL"
+                            }
+                        }
                     }
                 }
             }
@@ -552,8 +1433,16 @@ fn InsertRowForm(columns: Vec<String>, source_table: String) -> Element {
                         onclick: {
                             let source_table = source_table.clone();
                             let columns = columns.clone();
+                            let auto_filled = auto_filled.clone();
                             move |_| {
-                                insert_row(&source_table, &columns, &values.read());
+                                let (columns, values): (Vec<String>, Vec<Option<String>>) = columns
+                                    .iter()
+                                    .zip(values.read().iter())
+                                    .zip(auto_filled.iter())
+                                    .filter(|(_, reason)| reason.is_none())
+                                    .map(|((c, v), _)| (c.clone(), v.clone()))
+                                    .unzip();
+                                insert_row(&source_table, &columns, &values);
                                 *INSERTING_ROW.write() = false;
                             }
                         },
@@ -570,7 +1459,123 @@ fn InsertRowForm(columns: Vec<String>, source_table: String) -> Element {
     }
 }
 
-fn commit_cell_edit(row_idx: usize, column: &str, old_value: &str, new_value: &str) {
+/// Upper bound offered for automatic reruns of a conflicting save
+const MAX_AUTO_RETRIES: u32 = 5;
+
+/// Isolation level, access mode and retry policy of the transaction that saves changes.
+#[component]
+fn TransactionIndicator() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-500"
+    };
+    let select_class = if is_dark {
+        "bg-black border-gray-800 text-gray-400"
+    } else {
+        "bg-white border-gray-300 text-gray-700"
+    };
+
+    let options = *TRANSACTION_OPTIONS.read();
+    let isolation = options
+        .isolation
+        .map(|level| level.label())
+        .unwrap_or("DEFAULT");
+    let badge_class = if options.read_only || options.isolation.is_some() {
+        "text-blue-500"
+    } else {
+        muted_text
+    };
+
+    rsx! {
+        div {
+            class: "flex items-center space-x-1 text-xs",
+            title: "Transaction used when saving changes",
+            span { class: "{badge_class}", if options.read_only { "TX · READ ONLY" } else { "TX" } }
+            select {
+                class: "px-1 py-0.5 border rounded focus:outline-none {select_class}",
+                value: "{isolation}",
+                onchange: move |e| {
+                    let value = e.value();
+                    TRANSACTION_OPTIONS.write().isolation = IsolationLevel::ALL
+                        .into_iter()
+                        .find(|level| level.label() == value);
+                },
+                option { value: "DEFAULT", "Default isolation" }
+                for level in IsolationLevel::ALL {
+                    option { value: "{level.label()}", "{level.label()}" }
+                }
+            }
+            label {
+                class: "flex items-center space-x-1 {muted_text}",
+                input {
+                    r#type: "checkbox",
+                    checked: options.read_only,
+                    onchange: move |_| {
+                        let read_only = TRANSACTION_OPTIONS.read().read_only;
+                        TRANSACTION_OPTIONS.write().read_only = !read_only;
+                    },
+                }
+                span { "Read only" }
+            }
+            select {
+                class: "px-1 py-0.5 border rounded focus:outline-none {select_class}",
+                title: "Rerun the save automatically after a deadlock or serialization failure",
+                value: "{options.max_retries}",
+                onchange: move |e| {
+                    TRANSACTION_OPTIONS.write().max_retries = e.value().parse().unwrap_or(0);
+                },
+                option { value: "0", "No auto-retry" }
+                for n in 1..=MAX_AUTO_RETRIES {
+                    option { value: "{n}", "Retry {n}×" }
+                }
+            }
+        }
+    }
+}
+
+/// For each result column, why the database fills it in itself (if it does).
+fn auto_filled_columns(source_table: &str, columns: &[String]) -> Vec<Option<&'static str>> {
+    let schema = SCHEMA.read();
+    let source_table = normalize_table_name(source_table);
+    let table = schema.tables.iter().find(|t| t.name == source_table);
+
+    columns
+        .iter()
+        .map(|name| {
+            let col = table?
+                .columns
+                .iter()
+                .find(|c| &c.name == name)
+                .filter(|c| c.is_auto_filled())?;
+            Some(if col.is_generated() {
+                "generated"
+            } else {
+                "identity"
+            })
+        })
+        .collect()
+}
+
+fn column_rules(source_table: &str, column: &str) -> ColumnRules {
+    let schema = SCHEMA.read();
+    let source_table = normalize_table_name(source_table);
+    schema
+        .tables
+        .iter()
+        .find(|t| t.name == source_table)
+        .map(|t| ColumnRules::for_column(t, column))
+        .unwrap_or_default()
+}
+
+/// Record an edit of a cell loaded as `old_value`; None stands for SQL NULL on either side.
+fn commit_cell_edit(
+    row_idx: usize,
+    column: &str,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+) {
     if old_value == new_value {
         return;
     }
@@ -581,14 +1586,56 @@ fn commit_cell_edit(row_idx: usize, column: &str, old_value: &str, new_value: &s
         tab.pending_edits.push(CellEdit {
             row_idx,
             column: column.to_string(),
-            old_value: old_value.to_string(),
-            new_value: new_value.to_string(),
+            old_value: old_value.unwrap_or("NULL").to_string(),
+            new_value: new_value.map(str::to_string),
         });
     }
 }
 
 fn save_pending_edits() {
-    let (table, primary_keys, edits, result_rows, result_columns) = {
+    save_edits(true);
+}
+
+/// Whether a column's displayed value can be matched with `=` to find its row again.
+/// Only types whose text round-trips exactly qualify: integers, strings, dates and
+/// booleans. Floats are rounded for display, and timestamps, numerics and structured
+/// types may be formatted differently from how the database compares them.
+fn matchable_column(data_type: &str) -> bool {
+    let lower = data_type.to_lowercase();
+    let base = lower.split(['(', ' ']).next().unwrap_or_default();
+    matches!(
+        base,
+        "int"
+            | "integer"
+            | "int2"
+            | "int4"
+            | "int8"
+            | "tinyint"
+            | "smallint"
+            | "mediumint"
+            | "bigint"
+            | "text"
+            | "tinytext"
+            | "mediumtext"
+            | "longtext"
+            | "varchar"
+            | "character"
+            | "char"
+            | "bpchar"
+            | "nvarchar"
+            | "nchar"
+            | "date"
+            | "bool"
+            | "boolean"
+    )
+}
+
+/// Save the active tab's pending edits in one transaction. With `check_original` each
+/// UPDATE also matches the row version it was loaded with (PostgreSQL), or else the
+/// values the edited columns were loaded with, so a row changed since then isn't
+/// overwritten and the save reports a conflict instead.
+pub fn save_edits(check_original: bool) {
+    let (table, edits, result) = {
         let tabs = EDITOR_TABS.read();
         let tab = match tabs.active_tab() {
             Some(t) => t,
@@ -602,70 +1649,86 @@ fn save_pending_edits() {
             Some(t) => t.clone(),
             None => return,
         };
-        if result.primary_keys.is_empty() {
+        if !result.has_full_key() {
             return;
         }
-        (
-            table,
-            result.primary_keys.clone(),
-            tab.pending_edits.clone(),
-            result.rows.clone(),
-            result.columns.clone(),
-        )
+        (table, tab.pending_edits.clone(), result.clone())
     };
 
     // Group edits by row
-    let mut edits_by_row: HashMap<usize, Vec<&CellEdit>> = HashMap::new();
+    let mut edits_by_row: BTreeMap<usize, Vec<&CellEdit>> = BTreeMap::new();
     for edit in &edits {
         edits_by_row.entry(edit.row_idx).or_default().push(edit);
     }
 
     let mut statements = Vec::new();
+    let mut statement_rows = Vec::new();
     for (row_idx, row_edits) in &edits_by_row {
-        let row = match result_rows.get(*row_idx) {
-            Some(r) => r,
-            None => continue,
+        let Some(key) = result.row_key(*row_idx) else {
+            continue;
         };
 
         let set_clauses: Vec<String> = row_edits
             .iter()
-            .map(|e| {
-                if e.new_value == "NULL" {
-                    format!("{} = NULL", e.column)
-                } else {
-                    format!("{} = '{}'", e.column, e.new_value.replace('\'', "''"))
-                }
+            .map(|e| match &e.new_value {
+                Some(value) => format!("{} = '{}'", e.column, value.replace('\'', "''")),
+                None => format!("{} = NULL", e.column),
             })
             .collect();
 
-        let where_clauses: Vec<String> = primary_keys
+        let mut where_clauses: Vec<String> = key
             .iter()
-            .filter_map(|pk| {
-                let col_idx = result_columns.iter().position(|c| c == pk)?;
-                let value = row.get(col_idx)?;
-                Some(format!("{} = '{}'", pk, value.replace('\'', "''")))
-            })
+            .map(|(pk, value)| format!("{} = '{}'", pk, value.replace('\'', "''")))
             .collect();
+        if let Some(version) = result.row_versions.get(*row_idx).filter(|_| check_original) {
+            // PostgreSQL gives the row a new version whenever it is written
+            where_clauses.push(format!("xmin = '{}'", version.replace('\'', "''")));
+        } else if check_original {
+            where_clauses.extend(row_edits.iter().filter_map(|e| {
+                let col_idx = result.columns.iter().position(|c| *c == e.column)?;
+                if result.null_cells.contains(&(*row_idx, col_idx)) {
+                    return Some(format!("{} IS NULL", e.column));
+                }
+                let data_type = result.column_types.get(col_idx)?;
+                if !matchable_column(data_type) || e.old_value.ends_with("...[truncated]") {
+                    return None;
+                }
+                Some(format!(
+                    "{} = '{}'",
+                    e.column,
+                    e.old_value.replace('\'', "''")
+                ))
+            }));
+        }
 
-        if !set_clauses.is_empty() && !where_clauses.is_empty() {
+        if !set_clauses.is_empty() {
             statements.push(format!(
                 "UPDATE {} SET {} WHERE {}",
                 table,
                 set_clauses.join(", "),
                 where_clauses.join(" AND ")
             ));
+            statement_rows.push(*row_idx);
         }
     }
 
-    if !statements.is_empty() {
-        send_db_request(crate::db::DbRequest::ExecuteBatch(statements));
-    }
-
     if let Some(tab) = EDITOR_TABS.write().active_tab_mut() {
+        tab.saving = (!statements.is_empty()).then_some(SavingChange::Edits {
+            edits,
+            rows: statement_rows,
+        });
         tab.pending_edits.clear();
         tab.edit_mode = false;
     }
     *EDITING_CELL.write() = None;
+    if !statements.is_empty() {
+        let options = *TRANSACTION_OPTIONS.read();
+        send_tab_request(if check_original {
+            crate::db::DbRequest::SaveRowEdits(statements, options)
+        } else {
+            crate::db::DbRequest::ExecuteBatch(statements, options)
+        });
+    }
 }
 
 fn discard_pending_edits() {
@@ -673,15 +1736,17 @@ fn discard_pending_edits() {
         tab.pending_edits.clear();
     }
     *EDITING_CELL.write() = None;
+    *CELL_EDIT_ERROR.write() = None;
 }
 
+/// Build the statements deleting the selected rows and ask for confirmation first.
 fn delete_selected_rows() {
     let selected = SELECTED_ROWS.read().clone();
     if selected.is_empty() {
         return;
     }
 
-    let (table, primary_keys, result_rows, result_columns) = {
+    let (tab_id, table, result) = {
         let tabs = EDITOR_TABS.read();
         let tab = match tabs.active_tab() {
             Some(t) => t,
@@ -695,54 +1760,91 @@ fn delete_selected_rows() {
             Some(t) => t.clone(),
             None => return,
         };
-        if result.primary_keys.is_empty() {
+        if !result.has_full_key() {
             return;
         }
-        (
-            table,
-            result.primary_keys.clone(),
-            result.rows.clone(),
-            result.columns.clone(),
-        )
+        (tab.id.clone(), table, result.clone())
     };
 
+    let soft_delete = soft_delete_column(&table);
     let mut statements = Vec::new();
+    let mut keys = Vec::new();
+    let mut rows = Vec::new();
+    let mut selected: Vec<usize> = selected.into_iter().collect();
+    selected.sort_unstable();
     for row_idx in &selected {
-        let row = match result_rows.get(*row_idx) {
-            Some(r) => r,
-            None => continue,
+        let Some(key_values) = result.row_key(*row_idx) else {
+            continue;
         };
-
-        let where_clauses: Vec<String> = primary_keys
+        let where_clauses: Vec<String> = key_values
             .iter()
-            .filter_map(|pk| {
-                let col_idx = result_columns.iter().position(|c| c == pk)?;
-                let value = row.get(col_idx)?;
-                Some(format!("{} = '{}'", pk, value.replace('\'', "''")))
-            })
+            .map(|(pk, value)| format!("{} = '{}'", pk, value.replace('\'', "''")))
             .collect();
 
-        if !where_clauses.is_empty() {
-            statements.push(format!(
+        // Soft-delete tables keep the row and stamp the delete column instead
+        statements.push(match &soft_delete {
+            Some(column) => format!(
+                "UPDATE {} SET {} = CURRENT_TIMESTAMP WHERE {}",
+                table,
+                column,
+                where_clauses.join(" AND ")
+            ),
+            None => format!(
                 "DELETE FROM {} WHERE {}",
                 table,
                 where_clauses.join(" AND ")
-            ));
-        }
+            ),
+        });
+        keys.push(
+            key_values
+                .iter()
+                .map(|(pk, value)| format!("{} = {}", pk, value))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+        rows.push(*row_idx);
     }
 
     if !statements.is_empty() {
-        send_db_request(crate::db::DbRequest::ExecuteBatch(statements));
+        *PENDING_ROW_DELETE.write() = Some(RowDelete {
+            tab_id,
+            table,
+            statements,
+            keys,
+            rows,
+            soft_delete: soft_delete.is_some(),
+        });
     }
+}
 
-    *SELECTED_ROWS.write() = Default::default();
+fn show_selected_row_history() {
+    let Some(row_idx) = SELECTED_ROWS.read().iter().next().copied() else {
+        return;
+    };
+    let (table, keys) = {
+        let tabs = EDITOR_TABS.read();
+        let Some(result) = tabs.active_tab().and_then(|t| t.result.as_ref()) else {
+            return;
+        };
+        let (Some(table), Some(key)) = (&result.source_table, result.row_key(row_idx)) else {
+            return;
+        };
+        let keys: Vec<(String, String)> = key
+            .into_iter()
+            .map(|(pk, value)| (pk.to_string(), value.to_string()))
+            .collect();
+        (table.clone(), keys)
+    };
+    crate::components::row_history::open_row_history(table, keys);
 }
 
-fn insert_row(table: &str, columns: &[String], values: &[String]) {
-    let non_empty: Vec<(&String, &String)> = columns
+/// INSERT the given column values into `table`; None inserts NULL and columns left
+/// empty are omitted so they get their default.
+fn insert_row(table: &str, columns: &[String], values: &[Option<String>]) {
+    let non_empty: Vec<(&String, &Option<String>)> = columns
         .iter()
         .zip(values.iter())
-        .filter(|(_, v)| !v.is_empty())
+        .filter(|(_, v)| v.as_deref() != Some(""))
         .collect();
 
     if non_empty.is_empty() {
@@ -752,12 +1854,9 @@ fn insert_row(table: &str, columns: &[String], values: &[String]) {
     let col_list: Vec<&str> = non_empty.iter().map(|(c, _)| c.as_str()).collect();
     let val_list: Vec<String> = non_empty
         .iter()
-        .map(|(_, v)| {
-            if v.to_uppercase() == "NULL" {
-                "NULL".to_string()
-            } else {
-                format!("'{}'", v.replace('\'', "''"))
-            }
+        .map(|(_, v)| match v {
+            Some(v) => format!("'{}'", v.replace('\'', "''")),
+            None => "NULL".to_string(),
         })
         .collect();
 
@@ -768,7 +1867,136 @@ fn insert_row(table: &str, columns: &[String], values: &[String]) {
         val_list.join(", ")
     );
 
-    send_db_request(crate::db::DbRequest::ExecuteMutation(sql));
+    send_tab_request(crate::db::DbRequest::ExecuteMutation(sql));
+}
+
+fn toggle_cell_type_detection() {
+    let mut settings = DISPLAY_SETTINGS.read().clone();
+    settings.detect_cell_types = !settings.detect_cell_types;
+    if let Err(e) = DisplayStore::new().save(&settings) {
+        tracing::error!("Failed to save display settings: {}", e);
+    }
+    *DISPLAY_SETTINGS.write() = settings;
+}
+
+fn set_null_text(text: String) {
+    let mut settings = DISPLAY_SETTINGS.read().clone();
+    settings.null_text = text;
+    if let Err(e) = DisplayStore::new().save(&settings) {
+        tracing::error!("Failed to save display settings: {}", e);
+    }
+    *DISPLAY_SETTINGS.write() = settings;
+}
+
+fn toggle_empty_string_display() {
+    let mut settings = DISPLAY_SETTINGS.read().clone();
+    settings.empty_string = match settings.empty_string {
+        EmptyStringDisplay::Symbol => EmptyStringDisplay::Quotes,
+        EmptyStringDisplay::Quotes => EmptyStringDisplay::Symbol,
+    };
+    if let Err(e) = DisplayStore::new().save(&settings) {
+        tracing::error!("Failed to save display settings: {}", e);
+    }
+    *DISPLAY_SETTINGS.write() = settings;
+}
+
+fn toggle_focus_on_run() {
+    let mut settings = DISPLAY_SETTINGS.read().clone();
+    settings.focus_results_on_run = !settings.focus_results_on_run;
+    if let Err(e) = DisplayStore::new().save(&settings) {
+        tracing::error!("Failed to save display settings: {}", e);
+    }
+    *DISPLAY_SETTINGS.write() = settings;
+}
+
+/// Scroll the grid into view and, when turned on, give it keyboard focus on the first cell.
+fn follow_to_results() {
+    let focus = DISPLAY_SETTINGS.read().focus_results_on_run;
+    if focus {
+        *SELECTED_CELL.write() = Some((0, 0));
+    }
+    let focus_js = if focus { "grid.focus();" } else { "" };
+    spawn(async move {
+        let _ = document::eval(&format!(
+            r#"
+            const grid = document.getElementById('results-grid');
+            if (grid) {{
+                grid.scrollIntoView({{ block: 'nearest' }});
+                {focus_js}
+            }}
+            "#
+        ))
+        .await;
+    });
+}
+
+/// Select the cell at `(row, col)`, clamped to the grid, and scroll it into view.
+fn select_cell((row, col): (isize, isize), (row_count, col_count): (usize, usize)) {
+    if row_count == 0 || col_count == 0 {
+        return;
+    }
+    let row = row.clamp(0, row_count as isize - 1) as usize;
+    let col = col.clamp(0, col_count as isize - 1) as usize;
+    *SELECTED_CELL.write() = Some((row, col));
+    spawn(async move {
+        let _ = document::eval(
+            r#"
+            requestAnimationFrame(() => {
+                const cell = document.querySelector('#results-grid .selected-cell');
+                if (cell) cell.scrollIntoView({ block: 'nearest', inline: 'nearest' });
+            });
+            "#,
+        )
+        .await;
+    });
+}
+
+/// Copy the checked rows as tab-separated lines, or else the selected cell.
+fn copy_selection() {
+    let text = {
+        let tabs = EDITOR_TABS.read();
+        let Some(result) = tabs.active_tab().and_then(|t| t.result.as_ref()) else {
+            return;
+        };
+        let mut checked: Vec<usize> = SELECTED_ROWS.read().iter().copied().collect();
+        if checked.is_empty() {
+            match selected_cell_value() {
+                Some(value) => value,
+                None => return,
+            }
+        } else {
+            checked.sort_unstable();
+            checked
+                .into_iter()
+                .filter_map(|idx| result.rows.get(idx))
+                .map(|row| row.join("\t"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    };
+    let text = serde_json::to_string(&text).unwrap_or_default();
+    spawn(async move {
+        let _ = document::eval(&format!("navigator.clipboard.writeText({text})")).await;
+    });
+}
+
+fn selected_cell_value() -> Option<String> {
+    let (row, col) = (*SELECTED_CELL.read())?;
+    let tabs = EDITOR_TABS.read();
+    let rows = &tabs.active_tab()?.result.as_ref()?.rows;
+    rows.get(row)?.get(col).cloned()
+}
+
+fn focus_grid() {
+    spawn(async move {
+        let _ = document::eval("document.getElementById('results-grid')?.focus();").await;
+    });
+}
+
+fn focus_editor() {
+    spawn(async move {
+        let _ = document::eval("document.getElementById('sql-editor-input')?.focus();").await;
+    });
 }
 
 fn show_execution_plan() {
@@ -0,0 +1,156 @@
+use crate::components::results_table::SELECTED_ROWS;
+use crate::db::DbRequest;
+use crate::state::*;
+use dioxus::prelude::*;
+
+/// Deleting more rows than this needs a second confirmation
+const BULK_DELETE_ROWS: usize = 50;
+/// Keys listed before the rest are summarized
+const PREVIEW_KEYS: usize = 20;
+
+#[component]
+pub fn RowDeleteConfirmDialog() -> Element {
+    let pending = PENDING_ROW_DELETE.read().clone();
+    rsx! {
+        if let Some(delete) = pending {
+            RowDeleteConfirmDialogContent { delete }
+        }
+    }
+}
+
+fn confirm_delete(delete: RowDelete) {
+    *PENDING_ROW_DELETE.write() = None;
+    // A soft delete keeps the row, so it stays until the refresh shows its new state
+    if !delete.soft_delete {
+        if let Some(tab) = EDITOR_TABS.write().query_tab_mut(Some(&delete.tab_id)) {
+            tab.saving = Some(SavingChange::Deletes(delete.rows));
+        }
+    }
+    send_request_for_tab(
+        DbRequest::ExecuteBatch(delete.statements, *TRANSACTION_OPTIONS.read()),
+        Some(delete.tab_id),
+    );
+    *SELECTED_ROWS.write() = Default::default();
+}
+
+/// Lists the keys of the selected rows before deleting them. Large deletes need an
+/// extra tick before the button enables.
+#[component]
+fn RowDeleteConfirmDialogContent(delete: RowDelete) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut acknowledged = use_signal(|| false);
+    let count = delete.statements.len();
+    let is_bulk = count > BULK_DELETE_ROWS;
+    let can_delete = !is_bulk || *acknowledged.read();
+    let verb = if delete.soft_delete {
+        "Soft delete"
+    } else {
+        "Delete"
+    };
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *PENDING_ROW_DELETE.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[560px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-2 {text_color}",
+                    if count == 1 {
+                        "{verb} 1 row from {delete.table}?"
+                    } else {
+                        "{verb} {count} rows from {delete.table}?"
+                    }
+                }
+                p {
+                    class: "text-sm {label_color} mb-3",
+                    if count == 1 {
+                        "1 statement runs in one transaction."
+                    } else {
+                        "{count} statements run in one transaction."
+                    }
+                    if delete.soft_delete {
+                        " The rows are kept and stamped as deleted."
+                    }
+                }
+
+                div {
+                    class: "flex-1 overflow-auto {preview_bg} border {dialog_border} rounded p-2 font-mono text-xs {label_color} space-y-0.5",
+                    for (i, key) in delete.keys.iter().take(PREVIEW_KEYS).enumerate() {
+                        div { key: "{i}", class: "truncate", "{key}" }
+                    }
+                    if delete.keys.len() > PREVIEW_KEYS {
+                        div {
+                            class: muted_text,
+                            "and {delete.keys.len() - PREVIEW_KEYS} more"
+                        }
+                    }
+                }
+
+                if is_bulk {
+                    label {
+                        class: "flex items-center space-x-2 text-sm text-red-500 mt-3",
+                        input {
+                            r#type: "checkbox",
+                            checked: *acknowledged.read(),
+                            onchange: move |_| {
+                                let value = !*acknowledged.read();
+                                acknowledged.set(value);
+                            },
+                        }
+                        span { "Yes, {verb.to_lowercase()} all {count} rows" }
+                    }
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *PENDING_ROW_DELETE.write() = None,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-red-600 hover:bg-red-500 text-white disabled:opacity-50",
+                        disabled: !can_delete,
+                        onclick: {
+                            let delete = delete.clone();
+                            move |_| confirm_delete(delete.clone())
+                        },
+                        "{verb}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use crate::config::{AuditSettings, AuditStore};
+use crate::db::{DbRequest, QueryResult};
+use crate::state::*;
+use dioxus::prelude::*;
+
+/// Column names commonly used for the kind of change in audit tables
+const ACTION_COLUMNS: &[&str] = &[
+    "action",
+    "operation",
+    "op",
+    "event",
+    "change_type",
+    "audit_action",
+];
+
+/// Open the history dialog for a row and look up its audit entries.
+pub fn open_row_history(table: String, keys: Vec<(String, String)>) {
+    let patterns = AuditStore::new().load().patterns;
+    *ROW_HISTORY.write() = Some(RowHistoryState {
+        table: table.clone(),
+        keys: keys.clone(),
+        audit_table: None,
+        entries: None,
+        error: None,
+    });
+    send_db_request(DbRequest::FetchRowHistory {
+        table,
+        keys,
+        patterns,
+    });
+}
+
+#[component]
+pub fn RowHistoryDialog() -> Element {
+    let state = ROW_HISTORY.read().clone();
+    rsx! {
+        if let Some(state) = state {
+            RowHistoryDialogContent { state }
+        }
+    }
+}
+
+#[component]
+fn RowHistoryDialogContent(state: RowHistoryState) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut patterns = use_signal(|| AuditStore::new().load().patterns.join(", "));
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let cell_text = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+
+    let key_label = state
+        .keys
+        .iter()
+        .map(|(k, v)| format!("{} = {}", k, v))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let subtitle = match &state.audit_table {
+        Some(audit_table) => format!("{} · from {}", key_label, audit_table),
+        None => key_label,
+    };
+    let entries = state.entries.as_ref().map(timeline_entries);
+
+    let retry_table = state.table.clone();
+    let retry_keys = state.keys.clone();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *ROW_HISTORY.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[720px] max-w-[95vw] max-h-[85vh] flex flex-col",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                div {
+                    class: "px-4 py-3 border-b {dialog_border}",
+                    h2 {
+                        class: "text-lg font-semibold {text_color}",
+                        "Row history · {state.table}"
+                    }
+                    div {
+                        class: "text-xs font-mono {muted_text} truncate",
+                        "{subtitle}"
+                    }
+                }
+
+                div {
+                    class: "flex-1 overflow-auto px-4 py-3",
+
+                    if let Some(error) = state.error.clone() {
+                        div {
+                            class: "text-sm text-red-500 mb-3",
+                            "{error}"
+                        }
+                        div {
+                            class: "text-xs {muted_text} mb-1",
+                            "Audit table naming convention ({{table}} is replaced by the table name):"
+                        }
+                        div {
+                            class: "flex items-center space-x-2",
+                            input {
+                                class: "flex-1 px-3 py-1.5 border rounded font-mono text-xs focus:outline-none {input_class}",
+                                r#type: "text",
+                                value: "{patterns}",
+                                oninput: move |e| patterns.set(e.value()),
+                            }
+                            button {
+                                class: "text-xs px-3 py-1.5 rounded bg-blue-600 hover:bg-blue-500 text-white",
+                                onclick: move |_| {
+                                    let settings = AuditSettings {
+                                        patterns: patterns
+                                            .read()
+                                            .split(',')
+                                            .map(|p| p.trim().to_string())
+                                            .filter(|p| !p.is_empty())
+                                            .collect(),
+                                    };
+                                    if let Err(e) = AuditStore::new().save(&settings) {
+                                        tracing::error!("Failed to save audit settings: {}", e);
+                                    }
+                                    open_row_history(retry_table.clone(), retry_keys.clone());
+                                },
+                                "Save & Retry"
+                            }
+                        }
+                    } else if let Some(entries) = entries {
+                        if entries.is_empty() {
+                            div {
+                                class: "{muted_text} text-sm text-center py-8",
+                                "No history recorded for this row"
+                            }
+                        } else {
+                            div {
+                                class: "border-l-2 {dialog_border} ml-2 space-y-4",
+                                for (idx, entry) in entries.into_iter().enumerate() {
+                                    div {
+                                        key: "{idx}",
+                                        class: "relative pl-4",
+                                        div {
+                                            class: "absolute -left-[5px] top-1 w-2 h-2 rounded-full bg-blue-500",
+                                        }
+                                        div {
+                                            class: "flex items-center space-x-2 text-xs",
+                                            span { class: "{cell_text} font-medium", "{entry.when}" }
+                                            if let Some(action) = entry.action.clone() {
+                                                span {
+                                                    class: "px-1.5 rounded bg-blue-600 bg-opacity-20 text-blue-500 uppercase",
+                                                    "{action}"
+                                                }
+                                            }
+                                        }
+                                        div {
+                                            class: "mt-1 font-mono text-xs space-y-0.5",
+                                            for (name, value, changed) in entry.fields.iter() {
+                                                div {
+                                                    class: if *changed { "text-yellow-500" } else { muted_text },
+                                                    "{name}: {value}"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    } else {
+                        div {
+                            class: "{muted_text} text-sm text-center py-8",
+                            "Loading..."
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct TimelineEntry {
+    when: String,
+    action: Option<String>,
+    /// (column, value, changed since the previous entry)
+    fields: Vec<(String, String, bool)>,
+}
+
+/// Turn audit rows into timeline entries, highlighting values that changed between entries.
+fn timeline_entries(result: &QueryResult) -> Vec<TimelineEntry> {
+    let time_idx = result.column_types.iter().position(|t| {
+        let t = t.to_lowercase();
+        t.contains("timestamp") || t.contains("datetime")
+    });
+    let action_idx = result
+        .columns
+        .iter()
+        .position(|c| ACTION_COLUMNS.contains(&c.to_lowercase().as_str()));
+
+    let mut previous: Option<&Vec<String>> = None;
+    let mut entries = Vec::with_capacity(result.rows.len());
+    for (row_idx, row) in result.rows.iter().enumerate() {
+        let when = time_idx
+            .and_then(|i| row.get(i).cloned())
+            .unwrap_or_else(|| format!("#{}", row_idx + 1));
+        let action = action_idx.and_then(|i| row.get(i).cloned());
+        let fields = result
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != time_idx && Some(*i) != action_idx)
+            .map(|(i, name)| {
+                let value = row.get(i).cloned().unwrap_or_default();
+                let changed = previous.is_some_and(|p| p.get(i) != Some(&value));
+                (name.clone(), value, changed)
+            })
+            .collect();
+        entries.push(TimelineEntry {
+            when,
+            action,
+            fields,
+        });
+        previous = Some(row);
+    }
+    entries
+}
@@ -0,0 +1,129 @@
+use crate::state::*;
+use dioxus::prelude::*;
+use std::time::Duration;
+
+#[component]
+pub fn RunningQueryDialog() -> Element {
+    rsx! {
+        if *SHOW_RUNNING_QUERY.read() {
+            RunningQueryDialogContent {}
+        }
+    }
+}
+
+#[component]
+fn RunningQueryDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    // Re-render every second so the elapsed time keeps counting
+    let mut tick = use_signal(|| 0u64);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            *tick.write() += 1;
+        }
+    });
+    let _ = tick();
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let preview_bg = if is_dark { "bg-gray-950" } else { "bg-gray-50" };
+
+    let running = RUNNING_QUERIES.read().clone();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *SHOW_RUNNING_QUERY.write() = false,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[640px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-4 {text_color}",
+                    "Running queries"
+                }
+
+                if running.is_empty() {
+                    div {
+                        class: "text-sm {muted_text}",
+                        "No query is running."
+                    }
+                }
+                div {
+                    class: "overflow-auto space-y-3",
+                    for query in running {
+                        div {
+                            key: "{query.id}",
+                            div {
+                                class: "flex items-center justify-between mb-1",
+                                span {
+                                    class: "text-xs {muted_text}",
+                                    "Running for {query.started.elapsed().as_secs()}s"
+                                    if let Some((done, total)) = query.statements {
+                                        ", {done} of {total} statements done"
+                                    }
+                                    if query.rows_fetched > 0 {
+                                        ", {query.rows_fetched} rows fetched"
+                                    }
+                                }
+                                div {
+                                    class: "flex items-center space-x-3",
+                                    if query.rows_fetched > 0 {
+                                        button {
+                                            class: "text-xs {label_color} hover:opacity-80",
+                                            onclick: move |_| stop_fetching(query.id),
+                                            "Stop fetching"
+                                        }
+                                    }
+                                    button {
+                                        class: "text-xs text-red-500 hover:text-red-400",
+                                        onclick: move |_| cancel_query(query.id),
+                                        "Cancel"
+                                    }
+                                }
+                            }
+                            pre {
+                                class: "{preview_bg} border {dialog_border} rounded p-3 font-mono text-xs {label_color} whitespace-pre-wrap break-all overflow-auto",
+                                "{query.sql}"
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex justify-end mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *SHOW_RUNNING_QUERY.write() = false,
+                        "Close"
+                    }
+                }
+            }
+        }
+    }
+}
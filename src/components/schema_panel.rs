@@ -1,6 +1,10 @@
 use crate::components::context_menu::show_table_context_menu;
+use crate::components::edit_comment;
 use crate::config::RecentTablesStore;
-use crate::db::format_select_all_sql;
+use crate::db::{
+    collation_mismatches, format_alter_column_sql, format_select_all_sql, format_table_comment_sql,
+    ColumnChange, ColumnInfo,
+};
 use crate::services::LlmSender;
 use crate::state::*;
 use dioxus::prelude::*;
@@ -8,7 +12,7 @@ use dioxus::prelude::*;
 fn current_db_type() -> DatabaseType {
     match *CONNECTION.read() {
         ConnectionState::Connected { db_type, .. } => db_type,
-        _ => DatabaseType::PostgreSQL,
+        _ => CURRENT_DB_TYPE.read().unwrap_or(DatabaseType::PostgreSQL),
     }
 }
 
@@ -16,7 +20,9 @@ fn current_db_type() -> DatabaseType {
 pub fn SchemaPanel() -> Element {
     let schema = SCHEMA.read();
     let is_dark = *IS_DARK_MODE.read();
-    let is_connected = matches!(*CONNECTION.read(), ConnectionState::Connected { .. });
+    let is_offline = *CONNECTION.read() == ConnectionState::ConnectionLost;
+    let is_connected =
+        is_offline || matches!(*CONNECTION.read(), ConnectionState::Connected { .. });
     let _llm_tx = use_context::<LlmSender>();
 
     let muted_text = if is_dark {
@@ -41,6 +47,13 @@ pub fn SchemaPanel() -> Element {
                     "No tables found"
                 }
             } else {
+                if is_offline {
+                    div {
+                        class: "text-xs text-red-500 border border-red-500 border-opacity-40 rounded px-2 py-1.5 mb-2",
+                        "Offline - showing cached schema. Reconnecting automatically..."
+                    }
+                }
+
                 h3 {
                     class: "text-xs font-semibold {header_text} uppercase tracking-wider mb-2",
                     "Tables ({schema.tables.len()})"
@@ -61,6 +74,18 @@ pub fn SchemaPanel() -> Element {
                     }
                 }
 
+                if !schema.sequences.is_empty() {
+                    h3 {
+                        class: "text-xs font-semibold {header_text} uppercase tracking-wider mb-2 mt-4",
+                        "Sequences ({schema.sequences.len()})"
+                    }
+
+                    // SELECT on a MariaDB sequence shows its current state
+                    for sequence in &schema.sequences {
+                        ViewItem { view: sequence.clone() }
+                    }
+                }
+
                 // AI Suggestions section
                 SuggestionsSection {}
             }
@@ -122,10 +147,15 @@ fn SuggestionsSection() -> Element {
                                     table_name: Some(table_name.clone()),
                                 };
                                 let config = LLM_CONFIG.read().clone();
-                                let _ = llm_tx.send(crate::llm::LlmRequest::SuggestQueries {
-                                    table: table.clone(),
-                                    config,
-                                });
+                                send_llm_request(
+                                    &llm_tx,
+                                    crate::llm::LlmRequest::SuggestQueries {
+                                        table: table.clone(),
+                                        dialect: *CURRENT_DIALECT.read(),
+                                        config,
+                                    },
+                                    LlmOrigin::Suggestions(table_name.clone()),
+                                );
                             }
                         },
                         "↻ Refresh"
@@ -186,6 +216,8 @@ fn SuggestionButton(suggestion: crate::llm::QuerySuggestion) -> Element {
 #[component]
 fn TableItem(table: crate::db::TableInfo) -> Element {
     let mut is_expanded = use_signal(|| false);
+    // Column whose ALTER actions are open
+    let mut altering_column = use_signal(|| None::<String>);
     let is_dark = *IS_DARK_MODE.read();
 
     let item_text = if is_dark {
@@ -229,10 +261,34 @@ fn TableItem(table: crate::db::TableInfo) -> Element {
         "text-yellow-500"
     };
 
+    let collation_warnings = collation_mismatches(&SCHEMA.read(), &table);
+    let primary_key = table.primary_key();
+    let description = TABLE_DESCRIPTIONS.read().get(&table.name).cloned();
+    let can_describe = matches!(*CONNECTION.read(), ConnectionState::Connected { .. });
+    let can_comment =
+        can_describe && format_table_comment_sql(current_db_type(), &table.name, None).is_some();
+    let can_alter = can_describe
+        && !matches!(
+            current_db_type(),
+            DatabaseType::BigQuery | DatabaseType::Odbc
+        );
+
+    // Open the details when a description is asked for from the context menu
+    let table_name_for_effect = table.name.clone();
+    use_effect(move || {
+        if matches!(
+            TABLE_DESCRIPTIONS.read().get(&table_name_for_effect),
+            Some(TableDescriptionState::Loading)
+        ) {
+            is_expanded.set(true);
+        }
+    });
+
     // Clone table name for use in closures
     let table_name_for_context_menu = table.name.clone();
     let table_name_for_tracking = table.name.clone();
     let table_name_for_select = table.name.clone();
+    let table_name_for_describe = table.name.clone();
 
     rsx! {
         div {
@@ -243,6 +299,9 @@ fn TableItem(table: crate::db::TableInfo) -> Element {
                     onclick: move |_| {
                         let current = *is_expanded.read();
                         is_expanded.set(!current);
+                        if !current {
+                            load_cached_description(&table_name_for_tracking);
+                        }
                         // Track table access
                         let store = RecentTablesStore::new();
                         let _ = store.add(&table_name_for_tracking);
@@ -283,7 +342,7 @@ fn TableItem(table: crate::db::TableInfo) -> Element {
                     }
                 }
 
-                span { "{table.name}" }
+                span { title: table.comment.clone(), "{table.name}" }
 
                 if table.row_estimate > 0 {
                     span {
@@ -297,9 +356,58 @@ fn TableItem(table: crate::db::TableInfo) -> Element {
                 div {
                     class: "ml-6 space-y-0.5",
 
+                    if table.comment.is_some() || can_comment {
+                        div {
+                            class: "flex items-start space-x-2 px-2 py-1 text-xs {col_muted}",
+                            if let Some(ref comment) = table.comment {
+                                span { class: "italic whitespace-pre-wrap", "{comment}" }
+                            } else {
+                                span { class: "italic", "No comment" }
+                            }
+                            if can_comment {
+                                button {
+                                    class: "hover:text-blue-500 transition-colors",
+                                    onclick: {
+                                        let table = table.name.clone();
+                                        move |_| edit_comment(&table, None)
+                                    },
+                                    "Edit"
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some(ref collation) = table.collation {
+                        div {
+                            class: "px-2 py-1 text-xs {col_muted}",
+                            "Collation: {collation}"
+                        }
+                    }
+
+                    if !primary_key.is_empty() {
+                        div {
+                            class: "flex items-center space-x-1 px-2 py-1 text-xs {col_muted}",
+                            title: "Rows are identified by these columns, in this order",
+                            span {
+                                class: "px-1 rounded border border-current {pk_color}",
+                                if primary_key.len() > 1 { "Composite key" } else { "Primary key" }
+                            }
+                            span { class: "truncate", "{primary_key.join(\", \")}" }
+                        }
+                    }
+
+                    for warning in &collation_warnings {
+                        div {
+                            class: "px-2 py-1 text-xs text-yellow-500",
+                            title: "Joins on these columns compare different collations",
+                            "⚠ Collation mismatch: {warning}"
+                        }
+                    }
+
                     for col in &table.columns {
                         div {
-                            class: "flex items-center space-x-2 px-2 py-1 text-xs",
+                            class: "group flex items-center space-x-2 px-2 py-1 text-xs",
+                            title: col.comment.clone(),
 
                             if col.is_primary_key {
                                 svg {
@@ -322,13 +430,61 @@ fn TableItem(table: crate::db::TableInfo) -> Element {
                                 class: col_muted,
                                 "{col.data_type}"
                             }
+                            if let Some(ref collation) = col.collation {
+                                if table.collation.as_ref() != Some(collation) {
+                                    span {
+                                        class: "{col_muted} opacity-75",
+                                        title: "Collation",
+                                        "{collation}"
+                                    }
+                                }
+                            }
+                            if let Some(ref expr) = col.generation_expression {
+                                span {
+                                    class: "{col_muted} italic truncate",
+                                    title: "GENERATED ALWAYS AS ({expr})",
+                                    "= {expr}"
+                                }
+                            } else if let Some(ref identity) = col.identity_generation {
+                                span {
+                                    class: "{col_muted} italic",
+                                    title: "Identity column",
+                                    "{identity.to_lowercase()}"
+                                }
+                            }
+                            if can_alter {
+                                button {
+                                    class: "ml-auto {col_muted} hover:text-blue-500 opacity-0 group-hover:opacity-100 transition-opacity",
+                                    title: "Alter column",
+                                    onclick: {
+                                        let name = col.name.clone();
+                                        move |_| {
+                                            let open = altering_column.read().as_ref() == Some(&name);
+                                            altering_column.set((!open).then(|| name.clone()));
+                                        }
+                                    },
+                                    "⋯"
+                                }
+                            }
+                        }
+                        if altering_column.read().as_ref() == Some(&col.name) {
+                            ColumnAlterActions {
+                                table: table.name.clone(),
+                                column: col.clone(),
+                                columns: table.columns.iter().map(|c| c.name.clone()).collect::<Vec<_>>(),
+                            }
                         }
                     }
 
                     button {
                         class: "mt-2 px-2 py-1 text-xs {item_text} hover:text-blue-500 text-left transition-colors",
                         onclick: move |_| {
-                            let sql = format_select_all_sql(current_db_type(), &table_name_for_select, 100);
+                            let sql = format_select_all_sql(
+                                current_db_type(),
+                                &table_name_for_select,
+                                100,
+                                soft_delete_column(&table_name_for_select).as_deref(),
+                            );
                             if let Some(tab) = EDITOR_TABS.write().active_tab_mut() {
                                 tab.content = sql;
                                 tab.unsaved_changes = true;
@@ -341,6 +497,263 @@ fn TableItem(table: crate::db::TableInfo) -> Element {
                         },
                         "SELECT * FROM {table.name}"
                     }
+
+                    // AI description
+                    match description {
+                        Some(TableDescriptionState::Loading) => rsx! {
+                            div {
+                                class: "flex items-center space-x-2 px-2 py-1 text-xs {col_muted}",
+                                div {
+                                    class: "animate-spin h-3 w-3 border-2 border-blue-500 border-t-transparent rounded-full",
+                                }
+                                span { "Describing..." }
+                            }
+                        },
+                        Some(TableDescriptionState::Ready(description)) => {
+                            let described_on = description.described_at.format("%Y-%m-%d").to_string();
+                            rsx! {
+                                div {
+                                    class: "px-2 py-1 text-xs {col_name_color} whitespace-pre-wrap",
+                                    "{description.text}"
+                                }
+                                div {
+                                    class: "flex items-center space-x-2 px-2 text-xs {col_muted}",
+                                    span { "Described {described_on}" }
+                                    if can_describe {
+                                        button {
+                                            class: "hover:text-blue-500 transition-colors",
+                                            onclick: move |_| describe_table(&table_name_for_describe),
+                                            "↻ Describe again"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        Some(TableDescriptionState::Failed(error)) => rsx! {
+                            div {
+                                class: "px-2 py-1 text-xs text-red-400",
+                                "Could not describe the table: {error}"
+                            }
+                            if can_describe {
+                                button {
+                                    class: "px-2 text-xs {item_text} hover:text-blue-500 transition-colors",
+                                    onclick: move |_| describe_table(&table_name_for_describe),
+                                    "↻ Try again"
+                                }
+                            }
+                        },
+                        None if can_describe => rsx! {
+                            button {
+                                class: "block px-2 py-1 text-xs {item_text} hover:text-blue-500 text-left transition-colors",
+                                onclick: move |_| describe_table(&table_name_for_describe),
+                                "Describe with AI"
+                            }
+                        },
+                        None => rsx! {},
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Column actions that ask for a value before their statement is built
+#[derive(Clone, Copy, PartialEq)]
+enum AlterInput {
+    Rename,
+    Type,
+    Default,
+}
+
+/// Open the ALTER statement for a column change in a new tab, to review before running.
+fn preview_column_change(table: &str, column: &ColumnInfo, change: &ColumnChange) {
+    let Some(sql) = format_alter_column_sql(current_db_type(), table, column, change) else {
+        return;
+    };
+    let mut tabs = EDITOR_TABS.write();
+    let id = tabs.add_tab(format!("ALTER {}.{}", table, column.name));
+    if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == id) {
+        tab.content = sql;
+    }
+}
+
+/// Rename, retype, default, NOT NULL, move and drop actions for one column. Each builds
+/// an ALTER statement and opens it in a tab instead of running it.
+#[component]
+fn ColumnAlterActions(
+    table: String,
+    column: ColumnInfo,
+    // Names of all the table's columns, in order
+    columns: Vec<String>,
+) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let db_type = current_db_type();
+    let mut input = use_signal(|| None::<AlterInput>);
+    let mut value = use_signal(String::new);
+
+    let col_muted = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let border_class = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let input_class = if is_dark {
+        "bg-gray-950 border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+
+    let supports =
+        |change: &ColumnChange| format_alter_column_sql(db_type, &table, &column, change).is_some();
+    let inputs: Vec<(&str, AlterInput, String)> = [
+        (
+            "Rename",
+            AlterInput::Rename,
+            ColumnChange::Rename(column.name.clone()),
+            column.name.clone(),
+        ),
+        (
+            "Type",
+            AlterInput::Type,
+            ColumnChange::SetType(column.data_type.clone()),
+            column.data_type.clone(),
+        ),
+        (
+            "Default",
+            AlterInput::Default,
+            ColumnChange::SetDefault(None),
+            column.default_value.clone().unwrap_or_default(),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, _, change, _)| supports(change))
+    .map(|(label, kind, _, initial)| (label, kind, initial))
+    .collect();
+
+    let mut actions = vec![(
+        if column.nullable {
+            "Set NOT NULL"
+        } else {
+            "Allow NULL"
+        },
+        ColumnChange::SetNullable(!column.nullable),
+    )];
+    if let Some(i) = columns.iter().position(|c| *c == column.name) {
+        // Moving up puts the column after the one two places back
+        if i > 0 {
+            let after = i.checked_sub(2).map(|j| columns[j].clone());
+            actions.push(("Move up", ColumnChange::MoveAfter(after)));
+        }
+        if let Some(next) = columns.get(i + 1) {
+            actions.push(("Move down", ColumnChange::MoveAfter(Some(next.clone()))));
+        }
+    }
+    actions.retain(|(_, change)| supports(change));
+    let can_drop = supports(&ColumnChange::Drop);
+    let can_comment = supports(&ColumnChange::SetComment(None));
+
+    let submit = {
+        let table = table.clone();
+        let column = column.clone();
+        move || {
+            let Some(kind) = *input.read() else {
+                return;
+            };
+            let text = value.read().trim().to_string();
+            let change = match kind {
+                AlterInput::Rename if !text.is_empty() => ColumnChange::Rename(text),
+                AlterInput::Type if !text.is_empty() => ColumnChange::SetType(text),
+                AlterInput::Default => ColumnChange::SetDefault((!text.is_empty()).then_some(text)),
+                _ => return,
+            };
+            preview_column_change(&table, &column, &change);
+            input.set(None);
+        }
+    };
+    let mut submit_on_enter = submit.clone();
+    let mut submit_on_click = submit;
+
+    rsx! {
+        div {
+            class: "ml-5 mb-1 px-2 py-1 text-xs {col_muted} space-y-1",
+
+            div {
+                class: "flex flex-wrap items-center gap-1",
+                for (label, kind, initial) in inputs {
+                    button {
+                        key: "{label}",
+                        class: "px-1.5 py-0.5 rounded border {border_class} hover:text-blue-500",
+                        onclick: move |_| {
+                            value.set(initial.clone());
+                            input.set(Some(kind));
+                        },
+                        "{label}"
+                    }
+                }
+                for (label, change) in actions {
+                    button {
+                        key: "{label}",
+                        class: "px-1.5 py-0.5 rounded border {border_class} hover:text-blue-500",
+                        onclick: {
+                            let table = table.clone();
+                            let column = column.clone();
+                            move |_| preview_column_change(&table, &column, &change)
+                        },
+                        "{label}"
+                    }
+                }
+                if can_comment {
+                    button {
+                        class: "px-1.5 py-0.5 rounded border {border_class} hover:text-blue-500",
+                        onclick: {
+                            let table = table.clone();
+                            let name = column.name.clone();
+                            move |_| edit_comment(&table, Some(&name))
+                        },
+                        "Comment"
+                    }
+                }
+                if can_drop {
+                    button {
+                        class: "px-1.5 py-0.5 rounded border {border_class} hover:text-red-500",
+                        onclick: {
+                            let table = table.clone();
+                            let column = column.clone();
+                            move |_| preview_column_change(&table, &column, &ColumnChange::Drop)
+                        },
+                        "Drop"
+                    }
+                }
+            }
+
+            if let Some(kind) = *input.read() {
+                div {
+                    class: "flex items-center gap-1",
+                    input {
+                        class: "flex-1 min-w-0 px-1.5 py-0.5 rounded border {input_class} font-mono",
+                        placeholder: match kind {
+                            AlterInput::Rename => "New name",
+                            AlterInput::Type => "New type",
+                            AlterInput::Default => "Default expression, empty to drop",
+                        },
+                        value: "{value}",
+                        autofocus: true,
+                        oninput: move |e| value.set(e.value()),
+                        onkeydown: move |e: KeyboardEvent| match e.key() {
+                            Key::Enter => submit_on_enter(),
+                            Key::Escape => input.set(None),
+                            _ => {}
+                        },
+                    }
+                    button {
+                        class: "px-1.5 py-0.5 rounded border {border_class} hover:text-blue-500",
+                        onclick: move |_| submit_on_click(),
+                        "Preview"
+                    }
                 }
             }
         }
@@ -370,7 +783,7 @@ fn ViewItem(view: String) -> Element {
             button {
                 class: "w-full flex items-center space-x-2 px-2 py-1.5 rounded text-sm {item_text} {item_hover} text-left transition-colors",
                 onclick: move |_| {
-                    let sql = format_select_all_sql(current_db_type(), &view, 100);
+                    let sql = format_select_all_sql(current_db_type(), &view, 100, None);
                     if let Some(tab) = EDITOR_TABS.write().active_tab_mut() {
                         tab.content = sql;
                         tab.unsaved_changes = true;
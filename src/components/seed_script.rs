@@ -0,0 +1,209 @@
+use crate::db::{normalize_table_name, seed_select_sql, DbRequest};
+use crate::state::*;
+use dioxus::prelude::*;
+
+/// Rows fetched per table when the script isn't built from a result
+const DEFAULT_SEED_ROWS: usize = 100;
+
+#[component]
+pub fn SeedScriptDialog() -> Element {
+    let state = SEED_SCRIPT.read().clone();
+    rsx! {
+        if let Some(state) = state {
+            SeedScriptDialogContent { state }
+        }
+    }
+}
+
+/// Open the seed script dialog on a table, or on a result whose rows should be used as is.
+pub fn open_seed_script(table: String, result: Option<crate::db::QueryResult>) {
+    *SEED_SCRIPT.write() = Some(SeedScriptState {
+        table,
+        result,
+        generating: None,
+        error: None,
+    });
+}
+
+/// Pick the tables and options of a seed script; the script opens in a new tab.
+#[component]
+fn SeedScriptDialogContent(state: SeedScriptState) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let origin = normalize_table_name(&state.table);
+    let mut selected = use_signal(|| vec![origin.clone()]);
+    let mut row_limit = use_signal(|| DEFAULT_SEED_ROWS.to_string());
+    let mut anonymize = use_signal(|| false);
+    let schema = SCHEMA.read().clone();
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+
+    let generating = state.generating.is_some();
+    let from_result = state.result.as_ref().map(|r| r.rows.len());
+    let limit = row_limit
+        .read()
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0);
+    let can_generate = !generating && limit.is_some() && !selected.read().is_empty();
+
+    let generate = {
+        let state = state.clone();
+        let origin = origin.clone();
+        move |_| {
+            let Some(limit) = limit else {
+                return;
+            };
+            let db_type = CURRENT_DB_TYPE.read().unwrap_or(DatabaseType::PostgreSQL);
+            let schema = SCHEMA.read();
+            let queries: Vec<(String, String)> = selected
+                .read()
+                .iter()
+                // A filtered result stands in for its own table
+                .filter(|t| state.result.is_none() || **t != origin)
+                .filter_map(|t| schema.tables.iter().find(|info| &info.name == t))
+                .map(|info| (info.name.clone(), seed_select_sql(db_type, info, limit)))
+                .collect();
+            let mut pending = state.clone();
+            if !selected.read().contains(&origin) {
+                pending.result = None;
+            }
+            pending.generating = Some(*anonymize.read());
+            pending.error = None;
+            *SEED_SCRIPT.write() = Some(pending);
+            send_db_request(DbRequest::FetchSeedRows(queries));
+        }
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *SEED_SCRIPT.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[520px] max-w-[95vw] max-h-[85vh] flex flex-col p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-1 {text_color}",
+                    "Generate Seed Script"
+                }
+                p {
+                    class: "text-xs {muted_text} mb-4",
+                    "INSERT statements in foreign key order, with rows sorted by primary key so the script only changes when the data does."
+                }
+
+                label {
+                    class: "block text-sm font-medium {label_color} mb-1",
+                    "Tables"
+                }
+                div {
+                    class: "flex-1 overflow-auto border {dialog_border} rounded p-2 space-y-1 min-h-24",
+                    for table in schema.tables.iter() {
+                        label {
+                            key: "{table.name}",
+                            class: "flex items-center space-x-2 text-sm {label_color}",
+                            input {
+                                r#type: "checkbox",
+                                checked: selected.read().contains(&table.name),
+                                onchange: {
+                                    let name = table.name.clone();
+                                    move |_| {
+                                        let mut tables = selected.write();
+                                        if let Some(i) = tables.iter().position(|t| *t == name) {
+                                            tables.remove(i);
+                                        } else {
+                                            tables.push(name.clone());
+                                        }
+                                    }
+                                },
+                            }
+                            span { class: "font-mono", "{table.name}" }
+                            if table.name == origin {
+                                if let Some(rows) = from_result {
+                                    span { class: "text-xs {muted_text}", "({rows} rows from the result)" }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "flex items-center space-x-4 mt-4 text-sm {label_color}",
+                    label {
+                        class: "flex items-center space-x-2",
+                        span { "Rows per table" }
+                        input {
+                            class: "w-20 px-2 py-1 border rounded text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                            r#type: "number",
+                            min: "1",
+                            value: "{row_limit}",
+                            oninput: move |e| row_limit.set(e.value()),
+                        }
+                    }
+                    label {
+                        class: "flex items-center space-x-2",
+                        input {
+                            r#type: "checkbox",
+                            checked: *anonymize.read(),
+                            onchange: move |_| {
+                                let value = !*anonymize.read();
+                                anonymize.set(value);
+                            },
+                        }
+                        span { "Anonymize text columns" }
+                    }
+                }
+
+                if let Some(error) = state.error.as_ref() {
+                    div { class: "text-xs text-red-500 mt-2", "{error}" }
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *SEED_SCRIPT.write() = None,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white disabled:opacity-50",
+                        disabled: !can_generate,
+                        onclick: generate,
+                        if generating { "Generating..." } else { "Generate" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::db::{DbRequest, ServerVariable};
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn ServerVariablesDialog() -> Element {
+    rsx! {
+        if *SHOW_SERVER_VARIABLES.read() {
+            ServerVariablesDialogContent {}
+        }
+    }
+}
+
+#[component]
+fn ServerVariablesDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut search = use_signal(String::new);
+    let mut changed_only = use_signal(|| false);
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+
+    let variables = SERVER_VARIABLES.read().clone();
+    let query = search.read().to_lowercase();
+    let only_changed = *changed_only.read();
+    let filtered: Vec<ServerVariable> = variables
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .filter(|v| query.is_empty() || v.name.to_lowercase().contains(&query))
+        .filter(|v| !only_changed || v.default_value.as_ref() != Some(&v.value))
+        .cloned()
+        .collect();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *SHOW_SERVER_VARIABLES.write() = false,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[900px] max-w-[95vw] max-h-[80vh] flex flex-col",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                div {
+                    class: "flex items-center space-x-3 px-4 py-3 border-b {dialog_border}",
+
+                    h2 {
+                        class: "text-lg font-semibold {text_color} flex-1",
+                        "Server Variables"
+                    }
+
+                    label {
+                        class: "flex items-center space-x-1 text-xs {muted_text}",
+                        input {
+                            r#type: "checkbox",
+                            checked: only_changed,
+                            onchange: move |_| {
+                                let current = *changed_only.read();
+                                changed_only.set(!current);
+                            },
+                        }
+                        span { "Changed only" }
+                    }
+
+                    input {
+                        class: "w-64 px-3 py-1.5 border rounded text-sm focus:outline-none {input_class}",
+                        r#type: "text",
+                        placeholder: "Search variables...",
+                        value: "{search}",
+                        autofocus: true,
+                        oninput: move |e| search.set(e.value()),
+                    }
+                }
+
+                div {
+                    class: "flex-1 overflow-auto",
+
+                    if variables.is_none() {
+                        div {
+                            class: "{muted_text} text-sm text-center py-8",
+                            "Loading..."
+                        }
+                    } else {
+                        table {
+                            class: "w-full text-sm text-left",
+                            thead {
+                                class: "{muted_text} text-xs uppercase sticky top-0 {dialog_bg}",
+                                tr {
+                                    th { class: "px-4 py-2", "Name" }
+                                    th { class: "px-4 py-2", "Value" }
+                                    th { class: "px-4 py-2", "Default" }
+                                    th { class: "px-4 py-2", "Unit" }
+                                    th { class: "px-4 py-2" }
+                                }
+                            }
+                            tbody {
+                                for variable in filtered {
+                                    VariableRow { key: "{variable.name}", variable: variable.clone() }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "px-4 py-2 border-t {dialog_border} text-xs {muted_text}",
+                    "Changes apply to this session only and are reset on reconnect."
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn VariableRow(variable: ServerVariable) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut editing = use_signal(|| false);
+    let mut draft = use_signal(|| variable.value.clone());
+
+    let cell_text = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let changed = variable.default_value.as_ref() != Some(&variable.value);
+    let value_class = if changed {
+        "text-yellow-500"
+    } else {
+        cell_text
+    };
+
+    let name_for_apply = variable.name.clone();
+    let name_for_reset = variable.name.clone();
+
+    rsx! {
+        tr {
+            td {
+                class: "px-4 py-1.5 font-mono text-xs {cell_text}",
+                title: variable.description.clone().unwrap_or_default(),
+                "{variable.name}"
+            }
+            td {
+                class: "px-4 py-1.5 font-mono text-xs {value_class}",
+                if *editing.read() {
+                    input {
+                        class: "w-full bg-transparent border border-blue-500 px-1 outline-none font-mono text-xs {cell_text}",
+                        value: "{draft}",
+                        autofocus: true,
+                        oninput: move |e| draft.set(e.value()),
+                        onkeydown: move |e: KeyboardEvent| {
+                            if e.key() == Key::Enter {
+                                send_db_request(DbRequest::SetSessionVariable {
+                                    name: name_for_apply.clone(),
+                                    value: draft.read().clone(),
+                                });
+                                editing.set(false);
+                            } else if e.key() == Key::Escape {
+                                editing.set(false);
+                            }
+                        },
+                    }
+                } else {
+                    "{variable.value}"
+                }
+            }
+            td {
+                class: "px-4 py-1.5 font-mono text-xs {muted_text}",
+                "{variable.default_value.clone().unwrap_or_default()}"
+            }
+            td {
+                class: "px-4 py-1.5 text-xs {muted_text}",
+                "{variable.unit.clone().unwrap_or_default()}"
+            }
+            td {
+                class: "px-4 py-1.5 text-xs text-right whitespace-nowrap",
+                if variable.session_settable && !*editing.read() {
+                    button {
+                        class: "{cell_text} hover:text-blue-500 mr-2",
+                        onclick: move |_| editing.set(true),
+                        "Set"
+                    }
+                    if changed {
+                        button {
+                            class: "{cell_text} hover:text-blue-500",
+                            onclick: move |_| {
+                                send_db_request(DbRequest::SetSessionVariable {
+                                    name: name_for_reset.clone(),
+                                    value: "DEFAULT".to_string(),
+                                });
+                            },
+                            "Reset"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use crate::db::{DbRequest, SessionVariable};
+use crate::state::*;
+use dioxus::prelude::*;
+
+/// Open or close the session panel, fetching fresh values when it opens.
+pub fn toggle_session_panel() {
+    let show = !*SHOW_SESSION_PANEL.read();
+    *SHOW_SESSION_PANEL.write() = show;
+    if show {
+        send_db_request(DbRequest::FetchSessionVariables);
+    }
+}
+
+/// Suggested values for variables with a fixed set of choices.
+fn suggestions(name: &str) -> &'static [&'static str] {
+    match name {
+        "default_transaction_isolation" => &[
+            "read committed",
+            "repeatable read",
+            "serializable",
+            "read uncommitted",
+        ],
+        "transaction_isolation" | "tx_isolation" => &[
+            "READ-COMMITTED",
+            "REPEATABLE-READ",
+            "SERIALIZABLE",
+            "READ-UNCOMMITTED",
+        ],
+        "default_transaction_read_only" => &["on", "off"],
+        "transaction_read_only" | "sql_safe_updates" => &["ON", "OFF"],
+        "TimeZone" | "time_zone" => &["UTC", "SYSTEM", "+00:00"],
+        _ => &[],
+    }
+}
+
+#[component]
+pub fn SessionPanel() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+
+    let panel_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let border_class = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+
+    let variables = SESSION_VARIABLES.read().clone();
+
+    rsx! {
+        div {
+            class: "absolute bottom-6 right-0 w-[480px] max-h-[60vh] flex flex-col {panel_bg} border {border_class} rounded-lg shadow-2xl z-40",
+            onclick: move |e: MouseEvent| e.stop_propagation(),
+
+            div {
+                class: "flex items-center px-3 py-2 border-b {border_class}",
+                span {
+                    class: "text-sm font-semibold {text_color} flex-1",
+                    "Session"
+                }
+                button {
+                    class: "{muted_text} hover:text-blue-500",
+                    title: "Refresh",
+                    onclick: move |_| send_db_request(DbRequest::FetchSessionVariables),
+                    "↻"
+                }
+                button {
+                    class: "{muted_text} hover:text-blue-500 ml-3",
+                    onclick: move |_| *SHOW_SESSION_PANEL.write() = false,
+                    "✕"
+                }
+            }
+
+            div {
+                class: "flex-1 overflow-auto",
+                match variables {
+                    None => rsx! {
+                        div {
+                            class: "{muted_text} text-center py-6",
+                            "Loading..."
+                        }
+                    },
+                    Some(variables) => rsx! {
+                        table {
+                            class: "w-full text-left",
+                            tbody {
+                                for variable in variables {
+                                    SessionVariableRow { key: "{variable.name}", variable: variable.clone() }
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+
+            div {
+                class: "px-3 py-1.5 border-t {border_class} {muted_text}",
+                "Changes apply to every query on this connection until reset or reconnect."
+            }
+        }
+    }
+}
+
+#[component]
+fn SessionVariableRow(variable: SessionVariable) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut editing = use_signal(|| false);
+    let mut draft = use_signal(|| variable.value.clone());
+
+    let cell_text = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let value_class = if variable.overridden {
+        "text-yellow-500"
+    } else {
+        cell_text
+    };
+
+    let list_id = format!("session-{}", variable.name);
+    let options = suggestions(&variable.name);
+    let name_for_apply = variable.name.clone();
+    let name_for_reset = variable.name.clone();
+    let current_value = variable.value.clone();
+
+    rsx! {
+        tr {
+            td {
+                class: "px-3 py-1 font-mono whitespace-nowrap {cell_text}",
+                "{variable.name}"
+            }
+            td {
+                class: "px-3 py-1 font-mono w-full {value_class}",
+                if *editing.read() {
+                    input {
+                        class: "w-full bg-transparent border border-blue-500 px-1 outline-none font-mono {cell_text}",
+                        value: "{draft}",
+                        list: "{list_id}",
+                        autofocus: true,
+                        oninput: move |e| draft.set(e.value()),
+                        onkeydown: move |e: KeyboardEvent| {
+                            if e.key() == Key::Enter {
+                                send_db_request(DbRequest::SetSessionVariable {
+                                    name: name_for_apply.clone(),
+                                    value: draft.read().clone(),
+                                });
+                                editing.set(false);
+                            } else if e.key() == Key::Escape {
+                                editing.set(false);
+                            }
+                        },
+                    }
+                    datalist {
+                        id: "{list_id}",
+                        for choice in options.iter() {
+                            option { value: "{choice}" }
+                        }
+                    }
+                } else {
+                    span {
+                        class: "cursor-pointer hover:underline",
+                        title: "Click to edit",
+                        onclick: move |_| {
+                            draft.set(current_value.clone());
+                            editing.set(true);
+                        },
+                        if variable.value.is_empty() {
+                            "(empty)"
+                        } else {
+                            "{variable.value}"
+                        }
+                    }
+                }
+            }
+            td {
+                class: "px-3 py-1 text-right whitespace-nowrap",
+                if variable.overridden && !*editing.read() {
+                    button {
+                        class: "{cell_text} hover:text-blue-500",
+                        onclick: move |_| {
+                            send_db_request(DbRequest::SetSessionVariable {
+                                name: name_for_reset.clone(),
+                                value: "DEFAULT".to_string(),
+                            });
+                        },
+                        "Reset"
+                    }
+                }
+            }
+        }
+    }
+}
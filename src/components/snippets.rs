@@ -0,0 +1,586 @@
+use crate::config::{Snippet, SnippetStore};
+use crate::state::*;
+use dioxus::prelude::*;
+use std::ops::Range;
+
+/// Letters typed before suggestions show, so single keystrokes don't pop them up
+const MIN_COMPLETION_CHARS: usize = 2;
+
+/// Char range of the word ending at `caret`.
+fn word_before(chars: &[char], caret: usize) -> Range<usize> {
+    let caret = caret.min(chars.len());
+    let start = chars[..caret]
+        .iter()
+        .rposition(|c| !(c.is_alphanumeric() || *c == '_'))
+        .map_or(0, |i| i + 1);
+    start..caret
+}
+
+/// Suggest the snippets whose trigger starts with the word before the caret. Runs after
+/// each edit; the popup closes when nothing matches.
+pub async fn refresh_completion() {
+    let mut eval = document::eval(
+        r#"
+        const textarea = document.getElementById('sql-editor-input');
+        dioxus.send(textarea
+            ? [Array.from(textarea.value.slice(0, textarea.selectionStart)).length, textarea.scrollTop, textarea.scrollLeft]
+            : [0, 0, 0]);
+    "#,
+    );
+    let Ok((caret, scroll_top, scroll_left)) = eval.recv::<(usize, f64, f64)>().await else {
+        return;
+    };
+
+    let content = EDITOR_TABS
+        .read()
+        .active_tab()
+        .map(|t| t.content.clone())
+        .unwrap_or_default();
+    let chars: Vec<char> = content.chars().collect();
+    let word = word_before(&chars, caret);
+    let typed: String = chars[word.clone()]
+        .iter()
+        .collect::<String>()
+        .to_lowercase();
+    let items: Vec<CompletionItem> = if typed.chars().count() < MIN_COMPLETION_CHARS {
+        Vec::new()
+    } else {
+        SNIPPETS
+            .read()
+            .iter()
+            .filter(|s| s.trigger.to_lowercase().starts_with(&typed))
+            .map(|s| CompletionItem {
+                label: s.trigger.clone(),
+                detail: if s.description.is_empty() {
+                    s.body.lines().next().unwrap_or_default().to_string()
+                } else {
+                    s.description.clone()
+                },
+                kind: CompletionKind::Snippet,
+            })
+            .collect()
+    };
+    if items.is_empty() {
+        *COMPLETION.write() = None;
+        return;
+    }
+
+    let line = chars[..word.start].iter().filter(|c| **c == '\n').count();
+    let column = word.start
+        - chars[..word.start]
+            .iter()
+            .rposition(|c| *c == '\n')
+            .map_or(0, |i| i + 1);
+    let mut completion = COMPLETION.write();
+    // Keep the highlighted item while the same list narrows the same word
+    let selected = completion
+        .as_ref()
+        .filter(|c| c.items == items)
+        .map_or(0, |c| c.selected);
+    *completion = Some(Completion {
+        word,
+        line,
+        column,
+        scroll: (scroll_top, scroll_left),
+        items,
+        selected,
+    });
+}
+
+/// Handle a key pressed in the editor for the completion popup and snippets. Returns
+/// true when the key was used, so the textarea must not act on it. `caret` is the
+/// editor's tracked caret, kept on the selected tab stop.
+pub fn handle_snippet_key(key: &Key, shift: bool, caret: Signal<Option<usize>>) -> bool {
+    let completion = COMPLETION.peek().clone();
+    if let Some(completion) = completion {
+        match key {
+            Key::ArrowDown | Key::ArrowUp => {
+                let len = completion.items.len();
+                let selected = if *key == Key::ArrowDown {
+                    (completion.selected + 1) % len
+                } else {
+                    (completion.selected + len - 1) % len
+                };
+                if let Some(completion) = COMPLETION.write().as_mut() {
+                    completion.selected = selected;
+                }
+                return true;
+            }
+            Key::Enter | Key::Tab if !shift => {
+                accept_completion(&completion, completion.selected, caret);
+                return true;
+            }
+            Key::Escape => {
+                *COMPLETION.write() = None;
+                return true;
+            }
+            Key::ArrowLeft | Key::ArrowRight | Key::Home | Key::End => {
+                *COMPLETION.write() = None;
+            }
+            _ => {}
+        }
+    }
+
+    match key {
+        Key::Tab if !shift => next_tab_stop(caret) || expand_trigger(caret),
+        Key::Escape => {
+            *SNIPPET_SESSION.write() = None;
+            false
+        }
+        _ => false,
+    }
+}
+
+fn accept_completion(completion: &Completion, index: usize, caret: Signal<Option<usize>>) {
+    let Some(item) = completion.items.get(index) else {
+        return;
+    };
+    let snippet = SNIPPETS
+        .peek()
+        .iter()
+        .find(|s| s.trigger == item.label)
+        .cloned();
+    *COMPLETION.write() = None;
+    if let Some(snippet) = snippet {
+        insert_snippet(&snippet, completion.word.clone(), caret);
+    }
+}
+
+/// Expand the snippet whose trigger is the word before the caret.
+fn expand_trigger(caret: Signal<Option<usize>>) -> bool {
+    let Some(position) = *caret.peek() else {
+        return false;
+    };
+    let chars: Vec<char> = EDITOR_TABS
+        .peek()
+        .active_tab()
+        .map(|t| t.content.chars().collect())
+        .unwrap_or_default();
+    let word = word_before(&chars, position);
+    if word.is_empty() {
+        return false;
+    }
+    let trigger: String = chars[word.clone()].iter().collect();
+    let snippet = SNIPPETS
+        .peek()
+        .iter()
+        .find(|s| s.trigger == trigger)
+        .cloned();
+    match snippet {
+        Some(snippet) => {
+            insert_snippet(&snippet, word, caret);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Replace `word` in the active tab with the expanded snippet and select its first stop.
+fn insert_snippet(snippet: &Snippet, word: Range<usize>, caret: Signal<Option<usize>>) {
+    let expansion = snippet.expand();
+    let (tab_id, content_len) = {
+        let mut tabs = EDITOR_TABS.write();
+        let Some(tab) = tabs.active_tab_mut() else {
+            return;
+        };
+        let chars: Vec<char> = tab.content.chars().collect();
+        if word.end > chars.len() {
+            return;
+        }
+        let mut content: String = chars[..word.start].iter().collect();
+        content.push_str(&expansion.text);
+        content.extend(&chars[word.end..]);
+        tab.content = content;
+        tab.unsaved_changes = true;
+        (tab.id.clone(), tab.content.chars().count())
+    };
+
+    let stops: Vec<Range<usize>> = expansion
+        .stops
+        .iter()
+        .map(|stop| stop.start + word.start..stop.end + word.start)
+        .collect();
+    let first = stops[0].clone();
+    *SNIPPET_SESSION.write() = (stops.len() > 1).then(|| SnippetSession {
+        tab_id: Some(tab_id),
+        stops,
+        current: 0,
+        content_len,
+    });
+    select_stop(first, caret);
+}
+
+/// Move to the next stop of the snippet being filled in, if the caret is still on the
+/// current one. Anywhere else ends the snippet.
+fn next_tab_stop(caret: Signal<Option<usize>>) -> bool {
+    let Some(session) = SNIPPET_SESSION.peek().clone() else {
+        return false;
+    };
+    let (active_id, content_len) = {
+        let tabs = EDITOR_TABS.peek();
+        (
+            tabs.active_tab_id.clone(),
+            tabs.active_tab().map_or(0, |t| t.content.chars().count()),
+        )
+    };
+    let delta = content_len as isize - session.content_len as isize;
+    let stop = &session.stops[session.current];
+    let stop_end = stop.end.saturating_add_signed(delta).max(stop.start);
+    let on_stop = caret
+        .peek()
+        .is_some_and(|c| (stop.start..=stop_end).contains(&c));
+    if session.tab_id != active_id || !on_stop {
+        *SNIPPET_SESSION.write() = None;
+        return false;
+    }
+
+    // What was typed into the current stop moves the stops after it in the text
+    let stops: Vec<Range<usize>> = session
+        .stops
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            if s.start > stop.start || (s.start == stop.start && i > session.current) {
+                s.start.saturating_add_signed(delta)..s.end.saturating_add_signed(delta)
+            } else {
+                s.clone()
+            }
+        })
+        .collect();
+    let current = session.current + 1;
+    let next = stops[current].clone();
+    *SNIPPET_SESSION.write() = (current + 1 < stops.len()).then(|| SnippetSession {
+        current,
+        stops,
+        content_len,
+        ..session
+    });
+    select_stop(next, caret);
+    true
+}
+
+/// Select a tab stop once the editor shows the content just changed.
+fn select_stop(stop: Range<usize>, mut caret: Signal<Option<usize>>) {
+    spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let _ = document::eval(&format!(
+            r#"
+            const textarea = document.getElementById('sql-editor-input');
+            if (textarea) {{
+                // Char offsets to the UTF-16 units the textarea counts in
+                const chars = Array.from(textarea.value);
+                const from = chars.slice(0, {start}).join('').length;
+                const to = from + chars.slice({start}, {end}).join('').length;
+                textarea.focus();
+                textarea.setSelectionRange(from, to);
+            }}
+        "#,
+            start = stop.start,
+            end = stop.end,
+        ))
+        .await;
+        caret.set(Some(stop.start));
+    });
+}
+
+/// Suggestions under the word being typed in the editor. Arrow keys pick, Enter or Tab
+/// accepts and Escape closes, all from the editor.
+#[component]
+pub fn CompletionPopup(caret: Signal<Option<usize>>) -> Element {
+    let Some(completion) = COMPLETION.read().clone() else {
+        return rsx! {};
+    };
+    let is_dark = *IS_DARK_MODE.read();
+
+    let popup_class = if is_dark {
+        "bg-gray-900 border-gray-700 text-gray-300"
+    } else {
+        "bg-white border-gray-300 text-gray-700"
+    };
+    let selected_bg = if is_dark {
+        "bg-blue-900"
+    } else {
+        "bg-blue-100"
+    };
+    let muted_text = if is_dark {
+        "text-gray-500"
+    } else {
+        "text-gray-400"
+    };
+    // Under the word's start, in the editor's own font so `ch` matches its columns
+    let (scroll_top, scroll_left) = completion.scroll;
+    let style = format!(
+        "top: calc(1rem + {}rem - {}px); left: calc(1rem + {}ch - {}px);",
+        (completion.line + 1) as f64 * 1.5,
+        scroll_top,
+        completion.column,
+        scroll_left,
+    );
+
+    rsx! {
+        div {
+            class: "absolute z-20 min-w-[16rem] max-w-md max-h-60 overflow-auto border rounded shadow-lg font-mono text-sm {popup_class}",
+            style: "{style}",
+            for (index, item) in completion.items.iter().enumerate() {
+                {
+                    let row_bg = if index == completion.selected { selected_bg } else { "" };
+                    let completion = completion.clone();
+                    rsx! {
+                        div {
+                            key: "{item.label}",
+                            class: "flex items-center px-2 py-1 cursor-pointer space-x-2 {row_bg}",
+                            // Mouse down, so the editor keeps its focus and caret
+                            onmousedown: move |e: MouseEvent| {
+                                e.prevent_default();
+                                accept_completion(&completion, index, caret);
+                            },
+                            span { class: "shrink-0", "{item.label}" }
+                            span { class: "flex-1 truncate font-sans text-xs {muted_text}", "{item.detail}" }
+                            span { class: "shrink-0 font-sans text-xs {muted_text}", "{item.kind.label()}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn SnippetsDialog() -> Element {
+    rsx! {
+        if *SHOW_SNIPPETS_DIALOG.read() {
+            SnippetsDialogContent {}
+        }
+    }
+}
+
+/// Adds, edits and removes the snippets Tab expands. Changes apply on Save.
+#[component]
+fn SnippetsDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut snippets = use_signal(|| SNIPPETS.peek().clone());
+    let mut selected = use_signal(|| 0usize);
+    let mut error = use_signal(|| None::<String>);
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+    let item_class = if is_dark {
+        "text-gray-300 hover:bg-gray-900"
+    } else {
+        "text-gray-700 hover:bg-gray-100"
+    };
+    let selected_bg = if is_dark {
+        "bg-gray-900"
+    } else {
+        "bg-gray-100"
+    };
+
+    let current = *selected.read();
+    let editing = snippets.read().get(current).cloned();
+    let triggers: Vec<String> = snippets.read().iter().map(|s| s.trigger.clone()).collect();
+    let error_text = error.read().clone();
+
+    let save = move |_| {
+        let list = snippets.read().clone();
+        let mut triggers = std::collections::HashSet::new();
+        for snippet in &list {
+            if snippet.trigger.is_empty()
+                || snippet
+                    .trigger
+                    .chars()
+                    .any(|c| !(c.is_alphanumeric() || c == '_'))
+            {
+                error.set(Some(format!(
+                    "\"{}\" is not a valid trigger: use letters, digits and _",
+                    snippet.trigger
+                )));
+                return;
+            }
+            if !triggers.insert(snippet.trigger.clone()) {
+                error.set(Some(format!("\"{}\" is used twice", snippet.trigger)));
+                return;
+            }
+        }
+        match SnippetStore::new().save(&list) {
+            Ok(()) => {
+                *SNIPPETS.write() = list;
+                *SHOW_SNIPPETS_DIALOG.write() = false;
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *SHOW_SNIPPETS_DIALOG.write() = false,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[760px] max-w-[95vw] p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 { class: "text-lg font-semibold mb-1 {text_color}", "Snippets" }
+                p {
+                    class: "text-xs {muted_text} mb-4",
+                    "Type a trigger and press Tab to expand it. $1, $2 or ${{1:default}} mark where Tab stops next, $0 where it ends."
+                }
+
+                div {
+                    class: "flex space-x-4 h-80",
+
+                    div {
+                        class: "w-48 flex flex-col border {dialog_border} rounded",
+                        div {
+                            class: "flex-1 overflow-auto py-1",
+                            for (index, trigger) in triggers.into_iter().enumerate() {
+                                {
+                                    let row_bg = if index == current { selected_bg } else { "" };
+                                    rsx! {
+                                        button {
+                                            key: "{index}",
+                                            class: "w-full px-3 py-1 text-left text-sm font-mono truncate {item_class} {row_bg}",
+                                            onclick: move |_| selected.set(index),
+                                            if trigger.is_empty() {
+                                                span { class: "{muted_text}", "(no trigger)" }
+                                            } else {
+                                                "{trigger}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        button {
+                            class: "px-3 py-2 text-sm text-left text-blue-500 hover:text-blue-400 border-t {dialog_border}",
+                            onclick: move |_| {
+                                let mut list = snippets.write();
+                                list.push(Snippet {
+                                    trigger: String::new(),
+                                    body: String::new(),
+                                    description: String::new(),
+                                });
+                                selected.set(list.len() - 1);
+                            },
+                            "+ New snippet"
+                        }
+                    }
+
+                    if let Some(snippet) = editing {
+                        div {
+                            class: "flex-1 flex flex-col space-y-3",
+                            div {
+                                class: "flex space-x-3",
+                                div {
+                                    class: "w-40",
+                                    label { class: "block text-xs mb-1 {label_color}", "Trigger" }
+                                    input {
+                                        class: "w-full px-3 py-2 border rounded text-sm font-mono focus:outline-none focus:border-blue-500 {input_class}",
+                                        value: "{snippet.trigger}",
+                                        oninput: move |e| {
+                                            let value = e.value();
+                                            if let Some(snippet) = snippets.write().get_mut(current) {
+                                                snippet.trigger = value.trim().to_string();
+                                            }
+                                        },
+                                    }
+                                }
+                                div {
+                                    class: "flex-1",
+                                    label { class: "block text-xs mb-1 {label_color}", "Description" }
+                                    input {
+                                        class: "w-full px-3 py-2 border rounded text-sm focus:outline-none focus:border-blue-500 {input_class}",
+                                        value: "{snippet.description}",
+                                        oninput: move |e| {
+                                            let value = e.value();
+                                            if let Some(snippet) = snippets.write().get_mut(current) {
+                                                snippet.description = value;
+                                            }
+                                        },
+                                    }
+                                }
+                            }
+                            div {
+                                class: "flex-1 flex flex-col",
+                                label { class: "block text-xs mb-1 {label_color}", "Body" }
+                                textarea {
+                                    class: "flex-1 w-full px-3 py-2 border rounded text-sm font-mono resize-none focus:outline-none focus:border-blue-500 {input_class}",
+                                    spellcheck: "false",
+                                    value: "{snippet.body}",
+                                    oninput: move |e| {
+                                        let value = e.value();
+                                        if let Some(snippet) = snippets.write().get_mut(current) {
+                                            snippet.body = value;
+                                        }
+                                    },
+                                }
+                            }
+                            button {
+                                class: "self-start text-sm text-red-500 hover:opacity-80",
+                                onclick: move |_| {
+                                    let mut list = snippets.write();
+                                    if current < list.len() {
+                                        list.remove(current);
+                                    }
+                                    selected.set(current.min(list.len().saturating_sub(1)));
+                                },
+                                "Delete snippet"
+                            }
+                        }
+                    } else {
+                        div {
+                            class: "flex-1 flex items-center justify-center text-sm {muted_text}",
+                            "No snippets yet."
+                        }
+                    }
+                }
+
+                if let Some(error) = error_text {
+                    p { class: "text-sm text-red-500 mt-3", "{error}" }
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *SHOW_SNIPPETS_DIALOG.write() = false,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white",
+                        onclick: save,
+                        "Save"
+                    }
+                }
+            }
+        }
+    }
+}
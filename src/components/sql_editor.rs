@@ -1,16 +1,29 @@
-use crate::components::{TabBar, TemplateSelector};
+use crate::components::{
+    handle_snippet_key, open_editor_find, refresh_completion, run_with_parameters, CompletionPopup,
+    EditorFindBar, EnvironmentStripe, FileChangedBar, TabBar, TemplateSelector,
+    RESULTS_FOCUS_PENDING,
+};
 use crate::config::{DraftData, DraftStore, TabDraft};
-use crate::hooks::use_shiki::use_shiki;
-use crate::services::DbSender;
+use crate::db::{
+    alias_spans, matching_paren, parse_regions, split_statements, statement_at, statement_range_at,
+    translate_sql, SqlDialect,
+};
+use crate::hooks::use_shiki::{shiki_language, use_shiki};
+use crate::llm::LlmRequest;
+use crate::services::LlmSender;
 use crate::state::*;
 use dioxus::prelude::*;
 
 #[component]
 pub fn SqlEditor() -> Element {
     let shiki = use_shiki();
+    let llm_tx = use_context::<LlmSender>();
+    let llm_tx_convert = llm_tx.clone();
     let mut highlighted = use_signal(String::new);
     let mut highlight_generation = use_signal(|| 0u64);
     let mut draft_save_generation = use_signal(|| 0u64);
+    // Caret as a char offset, for the statement and bracket marks
+    let mut caret = use_signal(|| None::<usize>);
     let is_dark = *IS_DARK_MODE.read();
 
     // Theme-aware classes
@@ -35,6 +48,21 @@ pub fn SqlEditor() -> Element {
             .unwrap_or_default()
     };
 
+    let regions = parse_regions(&content);
+    let marks = caret
+        .read()
+        .map(|caret| editor_marks(&content, caret, is_dark))
+        .unwrap_or_default();
+    let track_caret = move || {
+        spawn(async move {
+            caret.set(Some(caret_offset().await));
+        });
+    };
+    let converts_from_mysql = matches!(
+        *CURRENT_DIALECT.read(),
+        SqlDialect::MySQL | SqlDialect::MariaDB
+    );
+
     // Track both content changes AND shiki readiness
     use_effect(move || {
         let code = EDITOR_TABS
@@ -43,6 +71,7 @@ pub fn SqlEditor() -> Element {
             .map(|t| t.content.clone())
             .unwrap_or_default();
         let is_ready = shiki.is_ready();
+        let lang = shiki_language(*CURRENT_DIALECT.read());
 
         if !is_ready || code.is_empty() {
             highlighted.set(String::new());
@@ -61,7 +90,8 @@ pub fn SqlEditor() -> Element {
                 return;
             }
 
-            if let Some(html) = shiki.highlight(&code).await {
+            let names = alias_spans(&code);
+            if let Some(html) = shiki.highlight(&code, lang, &names).await {
                 if *highlight_generation.read() == generation {
                     highlighted.set(html);
                 }
@@ -86,6 +116,8 @@ pub fn SqlEditor() -> Element {
                     .map(|tab| TabDraft {
                         title: tab.title.clone(),
                         content: tab.content.clone(),
+                        file_path: tab.file_path.clone(),
+                        file_modified: tab.file_modified,
                     })
                     .collect(),
                 active_tab_index: active_index,
@@ -126,8 +158,13 @@ pub fn SqlEditor() -> Element {
                 }
 
                 const syncScroll = () => {
-                    highlight.scrollTop = textarea.scrollTop;
-                    highlight.scrollLeft = textarea.scrollLeft;
+                    const marks = document.getElementById('sql-editor-marks');
+                    for (const layer of [highlight, marks]) {
+                        if (layer) {
+                            layer.scrollTop = textarea.scrollTop;
+                            layer.scrollLeft = textarea.scrollLeft;
+                        }
+                    }
                 };
 
                 if (!textarea.dataset.scrollSyncBound) {
@@ -147,16 +184,25 @@ pub fn SqlEditor() -> Element {
         div {
             class: "flex flex-col h-full",
 
-            // Tab bar
+            // Tab bar, under the connection's environment color
+            EnvironmentStripe {}
             TabBar {}
+            FileChangedBar {}
 
             div {
                 class: "h-10 {toolbar_bg} border-b {toolbar_border} flex items-center px-3 space-x-3",
 
+                RunButton {}
+
+                // Format button
                 button {
                     class: "px-3 py-1.5 text-sm rounded flex items-center space-x-1.5 transition-colors",
-                    class: if is_dark { "bg-white hover:bg-gray-200 text-black" } else { "bg-blue-600 hover:bg-blue-500 text-white" },
-                    onclick: move |_| execute_query(),
+                    class: if is_dark {
+                        "bg-gray-900 hover:bg-gray-800 text-gray-300"
+                    } else {
+                        "bg-gray-100 hover:bg-gray-200 text-gray-700"
+                    },
+                    onclick: move |_| format_current_query(),
                     svg {
                         class: "w-3.5 h-3.5",
                         fill: "none",
@@ -166,19 +212,13 @@ pub fn SqlEditor() -> Element {
                             stroke_linecap: "round",
                             stroke_linejoin: "round",
                             stroke_width: "2",
-                            d: "M14.752 11.168l-3.197-2.132A1 1 0 0010 9.87v4.263a1 1 0 001.555.832l3.197-2.132a1 1 0 000-1.664z",
-                        }
-                        path {
-                            stroke_linecap: "round",
-                            stroke_linejoin: "round",
-                            stroke_width: "2",
-                            d: "M21 12a9 9 0 11-18 0 9 9 0 0118 0z",
+                            d: "M4 6h16M4 12h16m-7 6h7",
                         }
                     }
-                    span { "Run" }
+                    span { "Format" }
                 }
 
-                // Format button
+                // Explain the highlighted fragment with the AI
                 button {
                     class: "px-3 py-1.5 text-sm rounded flex items-center space-x-1.5 transition-colors",
                     class: if is_dark {
@@ -186,7 +226,8 @@ pub fn SqlEditor() -> Element {
                     } else {
                         "bg-gray-100 hover:bg-gray-200 text-gray-700"
                     },
-                    onclick: move |_| format_current_query(),
+                    title: "Explain the selected SQL, or the statement under the caret",
+                    onclick: move |_| explain_selection(llm_tx.clone()),
                     svg {
                         class: "w-3.5 h-3.5",
                         fill: "none",
@@ -196,22 +237,97 @@ pub fn SqlEditor() -> Element {
                             stroke_linecap: "round",
                             stroke_linejoin: "round",
                             stroke_width: "2",
-                            d: "M4 6h16M4 12h16m-7 6h7",
+                            d: "M13 16h-1v-4h-1m1-4h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z",
                         }
                     }
-                    span { "Format" }
+                    span { "Explain selection" }
+                }
+
+                select {
+                    class: "px-3 py-1.5 text-sm rounded border focus:outline-none appearance-none",
+                    class: if is_dark {
+                        "bg-black border-gray-800 text-white focus:border-white"
+                    } else {
+                        "bg-white border-gray-300 text-gray-900 focus:border-blue-500"
+                    },
+                    title: "Convert the statement under the caret to another dialect",
+                    value: "",
+                    onchange: move |e| {
+                        let target = match e.value().as_str() {
+                            "mysql" => SqlDialect::MySQL,
+                            "postgresql" => SqlDialect::PostgreSQL,
+                            _ => return,
+                        };
+                        convert_statement(target, llm_tx_convert.clone());
+                    },
+                    option { value: "", disabled: true, selected: true, "Convert to..." }
+                    if !converts_from_mysql {
+                        option { value: "mysql", "MySQL" }
+                    }
+                    if converts_from_mysql {
+                        option { value: "postgresql", "PostgreSQL" }
+                    }
                 }
 
                 div { class: "flex-1" }
 
                 TemplateSelector {}
 
+                button {
+                    class: "px-3 py-1.5 text-sm rounded transition-colors",
+                    class: if is_dark {
+                        "bg-gray-900 hover:bg-gray-800 text-gray-300"
+                    } else {
+                        "bg-gray-100 hover:bg-gray-200 text-gray-700"
+                    },
+                    title: "Edit the snippets Tab expands",
+                    onclick: move |_| *SHOW_SNIPPETS_DIALOG.write() = true,
+                    "Snippets"
+                }
+
                 span {
                     class: "text-xs {hint_text}",
-                    "Ctrl+Enter to run"
+                    "Ctrl+Enter to run · Ctrl+Shift+Enter for the current statement"
+                }
+            }
+
+            // Outline of `-- #region` sections
+            if !regions.is_empty() {
+                div {
+                    class: "{toolbar_bg} border-b {toolbar_border} flex items-center px-3 py-1 space-x-3 overflow-x-auto text-xs",
+                    span { class: "{hint_text}", "Regions" }
+                    for (idx, region) in regions.into_iter().enumerate() {
+                        {
+                            let line = region.line;
+                            let body = region.body.clone();
+                            let indent = "›".repeat(region.depth);
+                            rsx! {
+                                div {
+                                    key: "{idx}",
+                                    class: "flex items-center space-x-1 whitespace-nowrap",
+                                    button {
+                                        class: "{hint_text} hover:text-blue-500",
+                                        title: "Run this region",
+                                        onclick: move |_| execute_region(&body),
+                                        "▶"
+                                    }
+                                    button {
+                                        class: if is_dark { "text-gray-400 hover:text-white" } else { "text-gray-700 hover:text-black" },
+                                        title: "Go to line {line + 1}",
+                                        onclick: move |_| scroll_to_line(line),
+                                        "{indent}{region.name}"
+                                    }
+                                }
+                            }
+                        }
+                    }
                 }
             }
 
+            if *SHOW_EDITOR_FIND.read() {
+                EditorFindBar {}
+            }
+
             div {
                 class: "flex-1 relative overflow-hidden {editor_bg}",
 
@@ -222,6 +338,15 @@ pub fn SqlEditor() -> Element {
                     dangerous_inner_html: "{highlighted}",
                 }
 
+                // Current statement and matching brackets, over the highlighted code
+                div {
+                    id: "sql-editor-marks",
+                    class: "absolute inset-0 p-4 font-mono text-sm leading-6 overflow-hidden whitespace-pre text-transparent pointer-events-none select-none",
+                    for (i, (text, style)) in marks.into_iter().enumerate() {
+                        span { key: "{i}", style: "{style}", "{text}" }
+                    }
+                }
+
                 // Textarea for input (on top)
                 textarea {
                     id: "sql-editor-input",
@@ -237,32 +362,402 @@ pub fn SqlEditor() -> Element {
                             tab.content = e.value().clone();
                             tab.unsaved_changes = true;
                         }
+                        track_caret();
+                        spawn(refresh_completion());
                     },
+                    onkeyup: move |_| track_caret(),
+                    onmouseup: move |_| {
+                        *COMPLETION.write() = None;
+                        track_caret();
+                    },
+                    onblur: move |_| *COMPLETION.write() = None,
                     onkeydown: move |e| {
-                        if e.data.key() == Key::Enter && e.data.modifiers().contains(keyboard_types::Modifiers::CONTROL) {
+                        let modifiers = e.data.modifiers();
+                        if modifiers.contains(keyboard_types::Modifiers::CONTROL) {
+                            if let Key::Character(key) = e.data.key() {
+                                if matches!(key.as_str(), "f" | "h") {
+                                    e.prevent_default();
+                                    open_editor_find();
+                                    return;
+                                }
+                            }
+                        }
+                        if e.data.key() == Key::Enter && modifiers.contains(keyboard_types::Modifiers::CONTROL) {
+                            e.prevent_default();
+                            if modifiers.contains(keyboard_types::Modifiers::SHIFT) {
+                                execute_statement_at_cursor();
+                            } else {
+                                execute_query();
+                            }
+                            return;
+                        }
+                        let plain = !modifiers.intersects(
+                            keyboard_types::Modifiers::CONTROL
+                                | keyboard_types::Modifiers::ALT
+                                | keyboard_types::Modifiers::META,
+                        );
+                        if plain
+                            && handle_snippet_key(
+                                &e.data.key(),
+                                modifiers.contains(keyboard_types::Modifiers::SHIFT),
+                                caret,
+                            )
+                        {
                             e.prevent_default();
-                            execute_query();
                         }
                     },
                     spellcheck: "false",
                     placeholder: "Enter your SQL query here...",
                 }
+
+                CompletionPopup { caret }
+            }
+        }
+    }
+}
+
+/// Runs the query, and while the active tab's query is in flight turns into a
+/// Cancel button counting the seconds it has been running.
+#[component]
+fn RunButton() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    // Re-render every second so the elapsed time keeps counting
+    let mut tick = use_signal(|| 0u64);
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            *tick.write() += 1;
+        }
+    });
+    let _ = tick();
+
+    let active_tab = EDITOR_TABS.read().active_tab_id.clone();
+    if let Some(query) = running_query_in(active_tab.as_deref()) {
+        let elapsed = query.started.elapsed().as_secs();
+        let fetch_class = if is_dark {
+            "border-gray-700 text-gray-300 hover:text-white"
+        } else {
+            "border-gray-300 text-gray-700 hover:text-gray-900"
+        };
+        return rsx! {
+            div {
+                class: "flex items-center space-x-2",
+                if query.rows_fetched > 0 {
+                    button {
+                        class: "px-3 py-1.5 text-sm rounded border transition-colors {fetch_class}",
+                        title: "Show the rows received so far and stop fetching the rest",
+                        onclick: move |_| stop_fetching(query.id),
+                        span { class: "tabular-nums", "{query.rows_fetched} rows" }
+                        span { class: "ml-1.5", "· Stop fetching" }
+                    }
+                }
+                button {
+                    class: "px-3 py-1.5 text-sm rounded flex items-center space-x-1.5 transition-colors bg-red-600 hover:bg-red-500 text-white",
+                    title: "Stop the running query",
+                    onclick: move |_| cancel_query(query.id),
+                    svg {
+                        class: "w-3.5 h-3.5",
+                        fill: "currentColor",
+                        view_box: "0 0 24 24",
+                        rect { x: "6", y: "6", width: "12", height: "12", rx: "1" }
+                    }
+                    span { "Cancel" }
+                    if let Some((done, total)) = query.statements {
+                        span { class: "tabular-nums opacity-80", "{(done + 1).min(total)}/{total}" }
+                    }
+                    span { class: "tabular-nums opacity-80", "{elapsed}s" }
+                }
             }
+        };
+    }
+
+    rsx! {
+        button {
+            class: "px-3 py-1.5 text-sm rounded flex items-center space-x-1.5 transition-colors",
+            class: if is_dark { "bg-white hover:bg-gray-200 text-black" } else { "bg-blue-600 hover:bg-blue-500 text-white" },
+            onclick: move |_| execute_query(),
+            svg {
+                class: "w-3.5 h-3.5",
+                fill: "none",
+                stroke: "currentColor",
+                view_box: "0 0 24 24",
+                path {
+                    stroke_linecap: "round",
+                    stroke_linejoin: "round",
+                    stroke_width: "2",
+                    d: "M14.752 11.168l-3.197-2.132A1 1 0 0010 9.87v4.263a1 1 0 001.555.832l3.197-2.132a1 1 0 000-1.664z",
+                }
+                path {
+                    stroke_linecap: "round",
+                    stroke_linejoin: "round",
+                    stroke_width: "2",
+                    d: "M21 12a9 9 0 11-18 0 9 9 0 0118 0z",
+                }
+            }
+            span { "Run" }
         }
     }
 }
 
 fn execute_query() {
-    let content = EDITOR_TABS
+    let (tab_id, content) = EDITOR_TABS
         .read()
         .active_tab()
-        .map(|t| t.content.clone())
+        .map(|t| (Some(t.id.clone()), t.content.clone()))
         .unwrap_or_default();
     if !content.is_empty() {
-        if let Some(tx) = try_use_context::<DbSender>() {
-            let _ = tx.send(crate::db::DbRequest::Execute(content));
+        run_with_parameters(content, tab_id);
+    }
+}
+
+/// Run only the statement under the caret, then bring its result into view.
+fn execute_statement_at_cursor() {
+    let (tab_id, content) = EDITOR_TABS
+        .read()
+        .active_tab()
+        .map(|t| (Some(t.id.clone()), t.content.clone()))
+        .unwrap_or_default();
+    spawn(async move {
+        let cursor = caret_offset().await;
+        if let Some(statement) = statement_at(&content, cursor) {
+            *RESULTS_FOCUS_PENDING.write() = true;
+            // The caret lookup awaits, so run it for the tab it was read from
+            run_with_parameters(statement, tab_id);
+        }
+    });
+}
+
+/// Select 1-based `line` in the editor and scroll it into view, once the editor shows
+/// the content just put in the active tab.
+pub fn reveal_line(line: usize) {
+    spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let _ = document::eval(&format!(
+            r#"
+            const textarea = document.getElementById('sql-editor-input');
+            if (textarea) {{
+                const lines = textarea.value.split('\n');
+                const line = Math.min({line}, lines.length) - 1;
+                let start = 0;
+                for (let i = 0; i < line; i++) start += lines[i].length + 1;
+                textarea.focus();
+                textarea.setSelectionRange(start, start + lines[line].length);
+                const height = parseFloat(getComputedStyle(textarea).lineHeight) || 20;
+                textarea.scrollTop = Math.max(0, (line - 3) * height);
+            }}
+        "#
+        ))
+        .await;
+    });
+}
+
+/// Select the char range `start..end` in the editor and scroll it into view, leaving
+/// the focus where it is.
+pub fn select_editor_range(start: usize, end: usize) {
+    spawn(async move {
+        let _ = document::eval(&format!(
+            r#"
+            const textarea = document.getElementById('sql-editor-input');
+            if (textarea) {{
+                // Char offsets to the UTF-16 units the textarea counts in
+                const chars = Array.from(textarea.value);
+                const from = chars.slice(0, {start}).join('').length;
+                const to = from + chars.slice({start}, {end}).join('').length;
+                const focused = document.activeElement;
+                textarea.focus();
+                textarea.setSelectionRange(from, to);
+                const line = textarea.value.slice(0, from).split('\n').length - 1;
+                const height = parseFloat(getComputedStyle(textarea).lineHeight) || 20;
+                textarea.scrollTop = Math.max(0, (line - 3) * height);
+                if (focused && focused !== textarea) focused.focus();
+            }}
+        "#
+        ))
+        .await;
+    });
+}
+
+/// `content` cut into pieces for the marks layer, each with its background style: the
+/// statement under the caret (what Ctrl+Shift+Enter runs) and the parenthesis beside
+/// the caret with its partner.
+fn editor_marks(content: &str, caret: usize, is_dark: bool) -> Vec<(String, &'static str)> {
+    let statement = statement_range_at(content, caret).unwrap_or(0..0);
+    let parens = matching_paren(content, caret);
+    let statement_style = if is_dark {
+        "background-color: rgba(255, 255, 255, 0.05)"
+    } else {
+        "background-color: rgba(59, 130, 246, 0.06)"
+    };
+    let paren_style = if is_dark {
+        "background-color: rgba(96, 165, 250, 0.4); border-radius: 2px"
+    } else {
+        "background-color: rgba(59, 130, 246, 0.3); border-radius: 2px"
+    };
+    let style_at = |i: usize| {
+        if parens.is_some_and(|(a, b)| i == a || i == b) {
+            paren_style
+        } else if statement.contains(&i) {
+            statement_style
+        } else {
+            ""
         }
+    };
+
+    let mut pieces: Vec<(String, &'static str)> = Vec::new();
+    for (i, c) in content.chars().enumerate() {
+        let style = style_at(i);
+        match pieces.last_mut() {
+            // Each parenthesis gets its own piece, so two together don't merge
+            Some((text, last)) if *last == style && style != paren_style => text.push(c),
+            _ => pieces.push((c.to_string(), style)),
+        }
+    }
+    pieces
+}
+
+/// The caret position in the editor as a char offset.
+async fn caret_offset() -> usize {
+    // selectionStart counts UTF-16 units; convert to a char offset
+    let mut eval = document::eval(
+        r#"
+        const textarea = document.getElementById('sql-editor-input');
+        dioxus.send(textarea ? Array.from(textarea.value.slice(0, textarea.selectionStart)).length : 0);
+    "#,
+    );
+    eval.recv::<usize>().await.unwrap_or_default()
+}
+
+/// Convert the statement under the caret to `target`. The rule-based result shows
+/// right away; the model's answer joins it in the dialog when it arrives.
+fn convert_statement(target: SqlDialect, llm_tx: LlmSender) {
+    let (tab_id, content) = EDITOR_TABS
+        .read()
+        .active_tab()
+        .map(|t| (Some(t.id.clone()), t.content.clone()))
+        .unwrap_or_default();
+    let from = *CURRENT_DIALECT.read();
+    spawn(async move {
+        let cursor = caret_offset().await;
+        let Some(range) = statement_range_at(&content, cursor) else {
+            return;
+        };
+        let original: String = content
+            .chars()
+            .skip(range.start)
+            .take(range.len())
+            .collect();
+        *DIALECT_CONVERSION.write() = Some(DialectConversion {
+            tab_id,
+            range,
+            original: original.clone(),
+            target,
+            rule_based: translate_sql(&original, from, target),
+            ai: None,
+        });
+        let config = LLM_CONFIG.read().clone();
+        send_llm_request(
+            &llm_tx,
+            LlmRequest::Translate {
+                sql: original.clone(),
+                from,
+                to: target,
+                config,
+            },
+            LlmOrigin::DialectConversion(original),
+        );
+    });
+}
+
+/// Ask the AI to explain the selected fragment, sending the statement it
+/// sits in along as context. Without a selection the whole statement under
+/// the caret is explained.
+fn explain_selection(llm_tx: LlmSender) {
+    let content = EDITOR_TABS
+        .read()
+        .active_tab()
+        .map(|t| t.content.clone())
+        .unwrap_or_default();
+    spawn(async move {
+        let mut eval = document::eval(
+            r#"
+            const textarea = document.getElementById('sql-editor-input');
+            if (!textarea) {
+                dioxus.send([0, 0]);
+            } else {
+                const start = Array.from(textarea.value.slice(0, textarea.selectionStart)).length;
+                const end = Array.from(textarea.value.slice(0, textarea.selectionEnd)).length;
+                dioxus.send([start, end]);
+            }
+        "#,
+        );
+        let (start, end) = eval.recv::<(usize, usize)>().await.unwrap_or_default();
+        let Some(statement) = statement_at(&content, start) else {
+            return;
+        };
+        let fragment: String = content
+            .chars()
+            .skip(start)
+            .take(end.saturating_sub(start))
+            .collect();
+        let fragment = fragment.trim();
+
+        let config = LLM_CONFIG.read().clone();
+        let (title, request) = if fragment.is_empty()
+            || fragment.trim_end_matches(';') == statement.trim().trim_end_matches(';')
+        {
+            (
+                "Explaining...",
+                LlmRequest::Explain {
+                    sql: statement,
+                    config,
+                },
+            )
+        } else {
+            (
+                "Explaining selection...",
+                LlmRequest::ExplainFragment {
+                    fragment: fragment.to_string(),
+                    context: statement,
+                    config,
+                },
+            )
+        };
+        *AI_PANEL.write() = AiPanelState {
+            visible: true,
+            loading: true,
+            title: title.to_string(),
+            content: String::new(),
+            suggested_sql: None,
+        };
+        send_llm_request(&llm_tx, request, LlmOrigin::AiPanel);
+    });
+}
+
+/// Run a region the way the editor runs its content: one statement as a query,
+/// several as a script with a result per statement.
+fn execute_region(body: &str) {
+    if split_statements(body).is_empty() {
+        return;
     }
+    let tab_id = EDITOR_TABS.read().active_tab().map(|t| t.id.clone());
+    run_with_parameters(body.to_string(), tab_id);
+}
+
+fn scroll_to_line(line: usize) {
+    // Matches the editor's leading-6 line height
+    let top = line * 24;
+    spawn(async move {
+        let _ = document::eval(&format!(
+            r#"
+            const textarea = document.getElementById('sql-editor-input');
+            if (textarea) {{
+                textarea.scrollTop = {top};
+                textarea.dispatchEvent(new Event('scroll'));
+            }}
+            "#
+        ))
+        .await;
+    });
 }
 
 fn format_current_query() {
@@ -1,3 +1,10 @@
+use crate::components::batch_progress::{batch_progress_label, BatchProgressPanel};
+use crate::components::change_capture::change_capture_label;
+use crate::components::export_jobs::{export_jobs_label, ExportJobsPanel};
+use crate::components::import_watches::queued_watched_files_label;
+use crate::components::layout::set_ui_zoom;
+use crate::components::notifications_panel::{notifications_label, NotificationsPanel};
+use crate::components::session_panel::{toggle_session_panel, SessionPanel};
 use crate::state::*;
 use dioxus::prelude::*;
 
@@ -23,8 +30,17 @@ pub fn StatusBar() -> Element {
             ref db_name,
         } => {
             let db_label = match db_type {
-                DatabaseType::PostgreSQL => "PostgreSQL",
-                DatabaseType::MySQL => "MySQL",
+                // PostgreSQL or CockroachDB, MySQL or MariaDB
+                DatabaseType::PostgreSQL | DatabaseType::MySQL => CURRENT_DIALECT.read().label(),
+                DatabaseType::SQLite => "SQLite",
+                DatabaseType::DuckDB => "DuckDB",
+                DatabaseType::ClickHouse => "ClickHouse",
+                DatabaseType::BigQuery => "BigQuery",
+                DatabaseType::Odbc => "ODBC",
+            };
+            let db_label = match SERVER_VERSION.read().as_ref() {
+                Some(version) => format!("{} {}", db_label, version),
+                None => db_label.to_string(),
             };
             if db_name.is_empty() {
                 format!("Connected to {}", db_label)
@@ -32,7 +48,25 @@ pub fn StatusBar() -> Element {
                 format!("Connected to {} / {}", db_label, db_name)
             }
         }
-        ConnectionState::ConnectionLost => "Connection lost".to_string(),
+        ConnectionState::ConnectionLost => {
+            let queued = OFFLINE_QUEUE.read().len();
+            let mut text = if queued > 0 {
+                format!(
+                    "Offline - showing cached data, {} request(s) queued",
+                    queued
+                )
+            } else {
+                "Offline - showing cached data".to_string()
+            };
+            match *RECONNECT_STATUS.read() {
+                Some((attempt, retry_in_secs)) => text.push_str(&format!(
+                    " - reconnect attempt {} failed, retrying in {}s",
+                    attempt, retry_in_secs
+                )),
+                None => text.push_str(" - reconnecting"),
+            }
+            text
+        }
         ConnectionState::Disconnected => "Not connected".to_string(),
         ConnectionState::Connecting => "Connecting...".to_string(),
         ConnectionState::Error(ref e) => format!("Error: {}", e),
@@ -51,6 +85,19 @@ pub fn StatusBar() -> Element {
         .map(|result| result.rows.len());
     let execution_time_ms = active_tab.and_then(|tab| tab.execution_time_ms);
     let import_message = IMPORT_MESSAGE.read().clone();
+    let is_connected = matches!(*CONNECTION.read(), ConnectionState::Connected { .. });
+    let health = CONNECTION_HEALTH.read().filter(|_| is_connected);
+    let keepalive = KEEPALIVE.read().filter(|_| is_connected);
+    let environment = CURRENT_ENVIRONMENT.read().filter(|_| is_connected);
+    let open_transaction = OPEN_TRANSACTION.read().filter(|_| is_connected);
+    let export_label = export_jobs_label();
+    let batch_label = batch_progress_label();
+    let watched_label = queued_watched_files_label();
+    let is_postgres = is_connected && *CURRENT_DB_TYPE.read() == Some(DatabaseType::PostgreSQL);
+    let listen_label = notifications_label().unwrap_or_else(|| "Listen".to_string());
+    let capture_label = change_capture_label().unwrap_or_else(|| "Capture".to_string());
+    let ui_zoom = DISPLAY_SETTINGS.read().ui_zoom;
+    let zoom_percent = (ui_zoom * 100.0).round() as i64;
 
     rsx! {
         div {
@@ -58,11 +105,54 @@ pub fn StatusBar() -> Element {
 
             div {
                 class: "flex items-center space-x-4 min-w-0",
+                if let Some(environment) = environment {
+                    span {
+                        class: "px-1.5 rounded text-white font-medium uppercase {environment.color_class()}",
+                        title: "Environment of this connection",
+                        "{environment.label()}"
+                    }
+                }
                 span {
                     class: status_color,
                     "{status_text}"
                 }
 
+                if let Some(health) = health {
+                    span {
+                        class: if health.latency_ms > 200 { "text-yellow-500" } else { muted_text },
+                        title: "Round-trip latency of the last health check",
+                        "{health.latency_ms} ms"
+                    }
+                    if let Some((latency_ms, connections)) = keepalive {
+                        span {
+                            class: if latency_ms > 200 { "text-yellow-500" } else { muted_text },
+                            title: "Slowest round trip of the last keepalive, over {connections} idle connection(s)",
+                            "keepalive {latency_ms} ms"
+                        }
+                    }
+                    if health.is_replica == Some(true) {
+                        span {
+                            class: "text-yellow-500 font-medium",
+                            title: "Server is a standby replica",
+                            "REPLICA"
+                        }
+                    } else if health.read_only {
+                        span {
+                            class: "text-yellow-500 font-medium",
+                            title: "Server is read-only",
+                            "READ-ONLY"
+                        }
+                    }
+                }
+
+                if let Some(statements) = open_transaction {
+                    span {
+                        class: if statements > 0 { "text-yellow-500 font-medium" } else { muted_text },
+                        title: "Manual-commit mode: changes stay uncommitted until you commit or roll back",
+                        if statements == 1 { "in transaction (1 statement)" } else { "in transaction ({statements} statements)" }
+                    }
+                }
+
                 if let Some(message) = import_message {
                     span {
                         class: "text-green-500 truncate",
@@ -81,6 +171,99 @@ pub fn StatusBar() -> Element {
                 if let Some(time) = execution_time_ms {
                     span { class: muted_text, "{time}ms" }
                 }
+
+                if ui_zoom != 1.0 {
+                    button {
+                        class: "{muted_text} hover:text-blue-500",
+                        title: "UI zoom (Ctrl+= / Ctrl+-). Click or press Ctrl+0 to reset",
+                        onclick: move |_| set_ui_zoom(1.0),
+                        "{zoom_percent}%"
+                    }
+                }
+
+                if let Some(label) = watched_label {
+                    button {
+                        class: "text-yellow-500 hover:text-blue-500",
+                        title: "Files from watched folders waiting for approval",
+                        onclick: move |_| *SHOW_IMPORT_WATCHES.write() = true,
+                        "{label}"
+                    }
+                }
+
+                if let Some(label) = batch_label {
+                    div {
+                        class: "relative",
+                        button {
+                            class: "text-blue-500 hover:text-blue-400",
+                            title: "Running batch",
+                            onclick: move |_| {
+                                let show = !*SHOW_BATCH_PROGRESS.read();
+                                *SHOW_BATCH_PROGRESS.write() = show;
+                            },
+                            "{label}"
+                        }
+                        if *SHOW_BATCH_PROGRESS.read() {
+                            BatchProgressPanel {}
+                        }
+                    }
+                }
+
+                if let Some(label) = export_label {
+                    div {
+                        class: "relative",
+                        button {
+                            class: "{muted_text} hover:text-blue-500",
+                            title: "Background exports",
+                            onclick: move |_| {
+                                let show = !*SHOW_EXPORT_JOBS.read();
+                                *SHOW_EXPORT_JOBS.write() = show;
+                            },
+                            "{label}"
+                        }
+                        if *SHOW_EXPORT_JOBS.read() {
+                            ExportJobsPanel {}
+                        }
+                    }
+                }
+
+                if is_postgres {
+                    div {
+                        class: "relative",
+                        button {
+                            class: "{muted_text} hover:text-blue-500",
+                            title: "LISTEN/NOTIFY feed",
+                            onclick: move |_| {
+                                let show = !*SHOW_NOTIFICATIONS_PANEL.read();
+                                *SHOW_NOTIFICATIONS_PANEL.write() = show;
+                            },
+                            "{listen_label}"
+                        }
+                        if *SHOW_NOTIFICATIONS_PANEL.read() {
+                            NotificationsPanel {}
+                        }
+                    }
+                    button {
+                        class: "{muted_text} hover:text-blue-500",
+                        title: "Row changes decoded from the WAL",
+                        onclick: move |_| *SHOW_CHANGE_CAPTURE.write() = true,
+                        "{capture_label}"
+                    }
+                }
+
+                if is_connected {
+                    div {
+                        class: "relative",
+                        button {
+                            class: "{muted_text} hover:text-blue-500",
+                            title: "Session variables in effect for this connection",
+                            onclick: move |_| toggle_session_panel(),
+                            "Session"
+                        }
+                        if *SHOW_SESSION_PANEL.read() {
+                            SessionPanel {}
+                        }
+                    }
+                }
             }
         }
     }
@@ -0,0 +1,131 @@
+use super::connect_saved;
+use crate::config::{ConnectionStore, TrayStore};
+use crate::state::*;
+use dioxus::desktop::muda::{Menu, MenuItem, PredefinedMenuItem};
+use dioxus::desktop::tao::event::{Event, WindowEvent};
+use dioxus::desktop::trayicon::init_tray_icon;
+use dioxus::desktop::{use_muda_event_handler, use_tray_menu_event_handler, use_wry_event_handler};
+use dioxus::prelude::*;
+
+/// Window menu check items
+pub const MENU_TRAY_ICON: &str = "tray-icon";
+pub const MENU_MINIMIZE_TO_TRAY: &str = "minimize-to-tray";
+
+const TRAY_NEW_QUERY: &str = "tray-new-query";
+const TRAY_RUN_JOBS: &str = "tray-run-jobs";
+const TRAY_RUNNING_QUERY: &str = "tray-running-query";
+const TRAY_SHOW: &str = "tray-show";
+const TRAY_QUIT: &str = "tray-quit";
+
+/// Tray icon with quick actions, shown when turned on from the Window menu.
+#[component]
+pub fn SystemTray() -> Element {
+    let tray = use_hook(|| {
+        let menu = Menu::new();
+        let _ = menu.append_items(&[
+            &MenuItem::with_id(TRAY_NEW_QUERY, "New Query on Last Connection", true, None),
+            &MenuItem::with_id(TRAY_RUN_JOBS, "Run Scheduled Jobs Now", true, None),
+            &MenuItem::with_id(TRAY_RUNNING_QUERY, "Show Running Queries", true, None),
+            &PredefinedMenuItem::separator(),
+            &MenuItem::with_id(TRAY_SHOW, "Show FBench", true, None),
+            &MenuItem::with_id(TRAY_QUIT, "Quit", true, None),
+        ]);
+        init_tray_icon(menu, None)
+    });
+
+    use_effect({
+        let tray = tray.clone();
+        move || {
+            let _ = tray.set_visible(TRAY_SETTINGS.read().show_tray_icon);
+        }
+    });
+
+    use_effect(move || {
+        let tooltip = match RUNNING_QUERIES.read().len() {
+            0 => "FBench".to_string(),
+            1 => "FBench: a query is running".to_string(),
+            n => format!("FBench: {} queries are running", n),
+        };
+        let _ = tray.set_tooltip(Some(tooltip));
+    });
+
+    use_tray_menu_event_handler(move |event| match event.id().0.as_str() {
+        TRAY_NEW_QUERY => new_query_on_last_connection(),
+        TRAY_RUN_JOBS => {
+            // Watched folders import into the current connection
+            if matches!(*CONNECTION.read(), ConnectionState::Connected { .. }) {
+                spawn(crate::import::scan_watched_folders());
+            }
+            spawn(crate::services::refresh_team_library());
+        }
+        TRAY_RUNNING_QUERY => {
+            show_window();
+            *SHOW_RUNNING_QUERY.write() = true;
+        }
+        TRAY_SHOW => show_window(),
+        TRAY_QUIT => dioxus::desktop::window().close(),
+        _ => {}
+    });
+
+    use_muda_event_handler(move |event| {
+        let id = event.id().0.as_str();
+        if id != MENU_TRAY_ICON && id != MENU_MINIMIZE_TO_TRAY {
+            return;
+        }
+        let mut settings = TRAY_SETTINGS.read().clone();
+        if id == MENU_TRAY_ICON {
+            settings.show_tray_icon = !settings.show_tray_icon;
+        } else {
+            settings.minimize_to_tray = !settings.minimize_to_tray;
+        }
+        if let Err(e) = TrayStore::new().save(&settings) {
+            tracing::error!("Failed to save tray settings: {}", e);
+        }
+        *TRAY_SETTINGS.write() = settings;
+    });
+
+    // Minimizing hides the window; the tray menu brings it back
+    use_wry_event_handler(move |event, _| {
+        if let Event::WindowEvent {
+            event: WindowEvent::Resized(_),
+            ..
+        } = event
+        {
+            let settings = TRAY_SETTINGS.peek();
+            let window = dioxus::desktop::window();
+            if settings.show_tray_icon && settings.minimize_to_tray && window.is_minimized() {
+                window.set_visible(false);
+            }
+        }
+    });
+
+    rsx! {}
+}
+
+fn show_window() {
+    let window = dioxus::desktop::window();
+    window.set_visible(true);
+    window.set_minimized(false);
+    window.set_focus();
+}
+
+/// Open a tab, connecting to the last used connection first unless already connected.
+fn new_query_on_last_connection() {
+    show_window();
+    if !matches!(*CONNECTION.read(), ConnectionState::Connected { .. }) {
+        let store = ConnectionStore::new();
+        let last_used = store.get_last_used().and_then(|name| {
+            store
+                .load_connections()
+                .into_iter()
+                .find(|c| c.name == name)
+        });
+        match last_used {
+            Some(conn) => connect_saved(&conn),
+            None => *SHOW_CONNECTION_DIALOG.write() = true,
+        }
+    }
+    let mut tabs = EDITOR_TABS.write();
+    let count = tabs.tabs.len() + 1;
+    tabs.add_tab(format!("Query {}", count));
+}
@@ -1,3 +1,4 @@
+use crate::config::RecentFilesStore;
 use crate::state::*;
 use dioxus::prelude::*;
 
@@ -47,12 +48,20 @@ pub fn TabBar() -> Element {
                         let tab_bg = if is_active { active_bg } else { inactive_bg };
                         let tab_id = tab.id.clone();
                         let close_id = tab.id.clone();
-                        let has_changes = tab.unsaved_changes;
+                        // A file-backed tab's dot means changes not yet written to the file
+                        let has_changes = if tab.file_path.is_some() {
+                            tab.is_modified()
+                        } else {
+                            tab.unsaved_changes
+                        };
+                        let is_modified = tab.is_modified();
+                        let file_path = tab.file_path.as_ref().map(|p| p.display().to_string());
 
                         rsx! {
                             div {
                                 class: "flex items-center px-3 py-2 cursor-pointer border-r {border_color} {tab_bg} hover:opacity-90 transition-opacity min-w-[120px] max-w-[200px]",
                                 class: if is_active { "border-t-2 border-t-blue-500" } else { "" },
+                                title: file_path,
                                 onclick: move |_| {
                                     EDITOR_TABS.write().set_active(&tab_id);
                                 },
@@ -72,7 +81,11 @@ pub fn TabBar() -> Element {
                                         class: "ml-2 p-0.5 rounded hover:bg-gray-600/20 {muted_color}",
                                         onclick: move |e| {
                                             e.stop_propagation();
-                                            EDITOR_TABS.write().close_tab(&close_id);
+                                            if is_modified {
+                                                *CLOSING_TAB.write() = Some(close_id.clone());
+                                            } else {
+                                                EDITOR_TABS.write().close_tab(&close_id);
+                                            }
                                         },
                                         svg {
                                             class: "w-3 h-3",
@@ -118,3 +131,134 @@ pub fn TabBar() -> Element {
         }
     }
 }
+
+/// Write a tab to its file, or to one picked by the user when it has none or `save_as`
+/// is set. Returns whether the tab was saved.
+pub async fn save_tab(tab_id: String, save_as: bool) -> bool {
+    let (title, file_path) = {
+        let tabs = EDITOR_TABS.read();
+        let Some(tab) = tabs.tabs.iter().find(|t| t.id == tab_id) else {
+            return false;
+        };
+        (tab.title.clone(), tab.file_path.clone())
+    };
+    let path = match file_path.filter(|_| !save_as) {
+        Some(path) => path,
+        None => {
+            let file_name = if title.to_lowercase().ends_with(".sql") {
+                title
+            } else {
+                format!("{}.sql", title)
+            };
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .add_filter("SQL", &["sql"])
+                .set_file_name(file_name)
+                .save_file()
+                .await
+            else {
+                return false;
+            };
+            file.path().to_path_buf()
+        }
+    };
+    let mut tabs = EDITOR_TABS.write();
+    let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == tab_id) else {
+        return false;
+    };
+    if tab.write_file(path.clone()).is_err() {
+        return false;
+    }
+    if let Err(e) = RecentFilesStore::new().add(&path) {
+        tracing::warn!("Failed to remember recent file: {}", e);
+    }
+    true
+}
+
+/// Save the active tab; see [`save_tab`].
+pub fn save_active_tab(save_as: bool) {
+    let Some(tab_id) = EDITOR_TABS.read().active_tab_id.clone() else {
+        return;
+    };
+    spawn(async move {
+        save_tab(tab_id, save_as).await;
+    });
+}
+
+/// Offers to reload the active tab's file after another program changed it, and shows
+/// why reading or writing the file failed.
+#[component]
+pub fn FileChangedBar() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let Some((tab_id, name, changed, error)) = EDITOR_TABS.read().active_tab().and_then(|tab| {
+        let path = tab.file_path.as_ref()?;
+        (tab.file_changed || tab.file_error.is_some()).then(|| {
+            (
+                tab.id.clone(),
+                path.display().to_string(),
+                tab.file_changed,
+                tab.file_error.clone(),
+            )
+        })
+    }) else {
+        return rsx! {};
+    };
+
+    let bar_class = if is_dark {
+        "bg-yellow-900 bg-opacity-30 border-gray-800 text-yellow-400"
+    } else {
+        "bg-yellow-50 border-gray-200 text-yellow-800"
+    };
+    let reload_id = tab_id.clone();
+    let dismiss_id = tab_id.clone();
+    let reload_name = name.clone();
+
+    rsx! {
+        div {
+            class: "flex items-center px-3 py-1.5 text-xs border-b space-x-3 {bar_class}",
+            span {
+                class: "flex-1 truncate",
+                if let Some(ref e) = error {
+                    "{e}"
+                } else {
+                    "{name} changed on disk."
+                }
+            }
+            if changed {
+                button {
+                    class: "font-medium hover:underline",
+                    onclick: move |_| {
+                        let mut tabs = EDITOR_TABS.write();
+                        if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == reload_id) {
+                            if let Err(e) = tab.reload_file() {
+                                tab.file_error = Some(format!("Could not reload {}: {}", reload_name, e));
+                            }
+                        }
+                    },
+                    "Reload"
+                }
+                button {
+                    class: "hover:underline",
+                    onclick: move |_| {
+                        let mut tabs = EDITOR_TABS.write();
+                        if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == tab_id) {
+                            tab.keep_content();
+                            tab.file_error = None;
+                        }
+                    },
+                    "Keep my version"
+                }
+            } else {
+                button {
+                    class: "hover:underline",
+                    onclick: move |_| {
+                        let mut tabs = EDITOR_TABS.write();
+                        if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == dismiss_id) {
+                            tab.file_error = None;
+                        }
+                    },
+                    "Dismiss"
+                }
+            }
+        }
+    }
+}
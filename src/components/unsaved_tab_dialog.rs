@@ -0,0 +1,102 @@
+use crate::components::save_tab;
+use crate::state::*;
+use dioxus::prelude::*;
+
+#[component]
+pub fn UnsavedTabDialog() -> Element {
+    let closing = CLOSING_TAB.read().clone();
+    let title = closing.as_ref().and_then(|id| {
+        EDITOR_TABS
+            .read()
+            .tabs
+            .iter()
+            .find(|t| t.id == *id)
+            .map(|t| t.title.clone())
+    });
+    rsx! {
+        if let (Some(tab_id), Some(title)) = (closing, title) {
+            UnsavedTabDialogContent { tab_id, title }
+        }
+    }
+}
+
+/// Asks whether to write a file-backed tab's changes before closing it.
+#[component]
+fn UnsavedTabDialogContent(tab_id: String, title: String) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let discard_id = tab_id.clone();
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *CLOSING_TAB.write() = None,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[420px] max-w-[95vw] p-6",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                h2 {
+                    class: "text-lg font-semibold mb-2 {text_color}",
+                    "Save changes to {title}?"
+                }
+                p {
+                    class: "text-sm {label_color}",
+                    "Your changes are lost if you close the tab without saving."
+                }
+
+                div {
+                    class: "flex justify-end space-x-2 mt-6",
+                    button {
+                        class: "px-4 py-2 text-sm rounded {label_color} hover:opacity-80",
+                        onclick: move |_| *CLOSING_TAB.write() = None,
+                        "Cancel"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded text-red-500 hover:opacity-80",
+                        onclick: move |_| {
+                            *CLOSING_TAB.write() = None;
+                            EDITOR_TABS.write().close_tab(&discard_id);
+                        },
+                        "Don't save"
+                    }
+                    button {
+                        class: "px-4 py-2 text-sm rounded bg-blue-600 hover:bg-blue-500 text-white",
+                        onclick: move |_| {
+                            *CLOSING_TAB.write() = None;
+                            let tab_id = tab_id.clone();
+                            spawn(async move {
+                                // A failed write leaves the tab open with the error on it
+                                if save_tab(tab_id.clone(), false).await {
+                                    EDITOR_TABS.write().close_tab(&tab_id);
+                                }
+                            });
+                        },
+                        "Save"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use crate::config::{UsageEvent, UsageKind, UsageLog};
+use crate::state::*;
+use chrono::{Duration, Local, NaiveDate};
+use dioxus::prelude::*;
+use std::collections::HashMap;
+
+const STATS_DAYS: i64 = 30;
+const TOP_TABLES: usize = 8;
+
+#[derive(Clone, PartialEq, Default)]
+struct UsageSummary {
+    queries: usize,
+    errors: usize,
+    ai_requests: usize,
+    avg_ms: Option<u64>,
+    /// Query count for each of the last STATS_DAYS days, oldest first
+    per_day: Vec<(NaiveDate, usize)>,
+    /// (table, queries, average ms), busiest first
+    busiest_tables: Vec<(String, usize, u64)>,
+}
+
+/// Summarize the events of the last `days` days up to and including `today`.
+fn summarize(events: &[UsageEvent], today: NaiveDate, days: i64) -> UsageSummary {
+    let first_day = today - Duration::days(days - 1);
+    let mut summary = UsageSummary::default();
+    let mut total_ms = 0u64;
+    let mut per_day: HashMap<NaiveDate, usize> = HashMap::new();
+    let mut tables: HashMap<&str, (usize, u64)> = HashMap::new();
+
+    for event in events {
+        let day = event.at.date_naive();
+        if day < first_day || day > today {
+            continue;
+        }
+        match &event.kind {
+            UsageKind::Query {
+                tables: names,
+                execution_time_ms,
+                ..
+            } => {
+                summary.queries += 1;
+                total_ms += execution_time_ms;
+                *per_day.entry(day).or_default() += 1;
+                for name in names {
+                    let entry = tables.entry(name).or_default();
+                    entry.0 += 1;
+                    entry.1 += execution_time_ms;
+                }
+            }
+            UsageKind::QueryError => summary.errors += 1,
+            UsageKind::AiRequest => summary.ai_requests += 1,
+        }
+    }
+
+    summary.avg_ms = (summary.queries > 0).then(|| total_ms / summary.queries as u64);
+    summary.per_day = (0..days)
+        .map(|offset| {
+            let day = first_day + Duration::days(offset);
+            (day, per_day.get(&day).copied().unwrap_or(0))
+        })
+        .collect();
+    let mut busiest: Vec<(String, usize, u64)> = tables
+        .into_iter()
+        .map(|(name, (count, ms))| (name.to_string(), count, ms / count as u64))
+        .collect();
+    busiest.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    busiest.truncate(TOP_TABLES);
+    summary.busiest_tables = busiest;
+    summary
+}
+
+#[component]
+pub fn UsageStatsDialog() -> Element {
+    rsx! {
+        if *SHOW_USAGE_STATS.read() {
+            UsageStatsDialogContent {}
+        }
+    }
+}
+
+#[component]
+fn UsageStatsDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut summary = use_signal(|| {
+        summarize(
+            &UsageLog::new().load(),
+            Local::now().date_naive(),
+            STATS_DAYS,
+        )
+    });
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let cell_text = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let bar_track = if is_dark {
+        "bg-gray-900"
+    } else {
+        "bg-gray-100"
+    };
+
+    let stats = summary.read().clone();
+    let attempts = stats.queries + stats.errors;
+    let error_rate = if attempts > 0 {
+        format!("{:.1}%", stats.errors as f64 * 100.0 / attempts as f64)
+    } else {
+        "-".to_string()
+    };
+    let avg_time = stats
+        .avg_ms
+        .map(|ms| format!("{} ms", ms))
+        .unwrap_or_else(|| "-".to_string());
+    let max_per_day = stats
+        .per_day
+        .iter()
+        .map(|(_, n)| *n)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    // (tooltip, bar height in percent)
+    let day_bars: Vec<(String, usize)> = stats
+        .per_day
+        .iter()
+        .map(|(day, count)| {
+            (
+                format!("{}: {}", day.format("%b %d"), count),
+                count * 100 / max_per_day,
+            )
+        })
+        .collect();
+    let max_table = stats
+        .busiest_tables
+        .first()
+        .map(|(_, n, _)| *n)
+        .unwrap_or(1);
+    let table_bars: Vec<(String, usize, u64, usize)> = stats
+        .busiest_tables
+        .iter()
+        .map(|(name, count, avg_ms)| (name.clone(), *count, *avg_ms, count * 100 / max_table))
+        .collect();
+    let cards = [
+        ("Queries", stats.queries.to_string()),
+        ("Avg time", avg_time),
+        ("Error rate", error_rate),
+        ("AI requests", stats.ai_requests.to_string()),
+    ];
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-center justify-center z-50",
+            onclick: move |_| *SHOW_USAGE_STATS.write() = false,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[720px] max-w-[95vw] max-h-[85vh] flex flex-col",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                div {
+                    class: "flex items-center px-4 py-3 border-b {dialog_border}",
+                    div {
+                        class: "flex-1",
+                        h2 {
+                            class: "text-lg font-semibold {text_color}",
+                            "Your Stats"
+                        }
+                        div {
+                            class: "text-xs {muted_text}",
+                            "Last {STATS_DAYS} days · stored only on this machine"
+                        }
+                    }
+                    button {
+                        class: "text-xs {cell_text} hover:text-red-500",
+                        onclick: move |_| {
+                            UsageLog::new().clear();
+                            summary.set(summarize(&[], Local::now().date_naive(), STATS_DAYS));
+                        },
+                        "Clear"
+                    }
+                }
+
+                div {
+                    class: "flex-1 overflow-auto px-4 py-4 space-y-6",
+
+                    div {
+                        class: "grid grid-cols-4 gap-3",
+                        for (label, value) in cards {
+                            div {
+                                class: "border {dialog_border} rounded p-3",
+                                div { class: "text-xs {muted_text} uppercase", "{label}" }
+                                div { class: "text-xl font-semibold {text_color}", "{value}" }
+                            }
+                        }
+                    }
+
+                    div {
+                        div { class: "text-xs {muted_text} uppercase mb-2", "Queries per day" }
+                        div {
+                            class: "flex items-end h-24 space-x-0.5",
+                            for (tooltip, height) in day_bars {
+                                div {
+                                    class: "flex-1 h-full flex items-end {bar_track}",
+                                    title: "{tooltip}",
+                                    div {
+                                        class: "w-full bg-blue-500",
+                                        style: "height: {height}%",
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        div { class: "text-xs {muted_text} uppercase mb-2", "Busiest tables" }
+                        if stats.busiest_tables.is_empty() {
+                            div { class: "text-sm {muted_text}", "No queries recorded yet" }
+                        }
+                        for (name, count, avg_ms, width) in table_bars {
+                            div {
+                                key: "{name}",
+                                class: "flex items-center space-x-3 text-xs mb-1",
+                                span { class: "w-40 truncate font-mono {cell_text}", "{name}" }
+                                div {
+                                    class: "flex-1 h-2 {bar_track}",
+                                    div {
+                                        class: "h-full bg-blue-500",
+                                        style: "width: {width}%",
+                                    }
+                                }
+                                span { class: "w-16 text-right {cell_text}", "{count}×" }
+                                span {
+                                    class: "w-20 text-right",
+                                    class: if avg_ms > 1000 { "text-yellow-500" } else { muted_text },
+                                    "{avg_ms} ms avg"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use crate::components::{connect_saved, relative_time};
+use crate::config::{ConnectionStore, RecentFileEntry, RecentFilesStore, SavedConnection};
+use crate::state::*;
+use chrono::Local;
+use dioxus::prelude::*;
+use std::path::Path;
+
+/// Saved connections listed before the rest are left to the connection dialog
+const MAX_CONNECTIONS: usize = 12;
+/// Entries listed under recent workspaces and recent files each
+const MAX_RECENT: usize = 8;
+
+/// Startup screen in place of the empty results while disconnected: saved connections,
+/// most recently used first, and recently opened workbooks and .sql files, each a click
+/// away.
+#[component]
+pub fn WelcomeScreen() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let (connections, recent_files) = use_hook(|| {
+        let store = ConnectionStore::new();
+        let last_used = store.last_used_times();
+        let mut connections: Vec<_> = store
+            .load_connections()
+            .into_iter()
+            .map(|conn| {
+                let used_at = last_used.get(&conn.name).copied();
+                (conn, used_at)
+            })
+            .collect();
+        // Most recent first; never-used connections keep their saved order at the end
+        connections.sort_by(|a, b| b.1.cmp(&a.1));
+        connections.truncate(MAX_CONNECTIONS);
+        (connections, RecentFilesStore::new().load())
+    });
+    let (workbooks, sql_files): (Vec<RecentFileEntry>, Vec<RecentFileEntry>) =
+        recent_files.into_iter().partition(|f| f.is_workbook());
+
+    let heading = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let item_class = if is_dark {
+        "text-gray-300 hover:bg-gray-900"
+    } else {
+        "text-gray-700 hover:bg-gray-100"
+    };
+    let card_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-200"
+    };
+    let now = Local::now();
+
+    rsx! {
+        div {
+            class: "flex-1 overflow-auto p-8",
+            div {
+                class: "max-w-4xl mx-auto",
+                h1 { class: "text-xl font-semibold mb-1 {heading}", "Welcome to fbench" }
+                p {
+                    class: "text-sm {muted_text} mb-6",
+                    "Connect to a saved database, or pick up a file where you left it."
+                }
+
+                div {
+                    class: "grid grid-cols-2 gap-6",
+
+                    div {
+                        class: "border {card_border} rounded-lg p-4",
+                        div {
+                            class: "flex items-center justify-between mb-2",
+                            h2 { class: "text-xs uppercase tracking-wide text-gray-500", "Connections" }
+                            button {
+                                class: "text-xs text-blue-500 hover:text-blue-400",
+                                onclick: move |_| *SHOW_CONNECTION_DIALOG.write() = true,
+                                "New connection"
+                            }
+                        }
+                        if connections.is_empty() {
+                            p { class: "text-sm {muted_text} py-2", "No saved connections yet." }
+                        }
+                        for (conn, used_at) in connections {
+                            button {
+                                key: "{conn.name}",
+                                class: "w-full flex items-center px-2 py-1.5 rounded text-left text-sm {item_class}",
+                                title: "{connection_target(&conn)}",
+                                onclick: {
+                                    let conn = conn.clone();
+                                    move |_| connect_saved(&conn)
+                                },
+                                span { class: "flex-1 truncate", "{conn.name}" }
+                                if let Some(environment) = conn.environment {
+                                    span {
+                                        class: "px-1.5 ml-2 rounded text-xs text-white font-medium uppercase {environment.color_class()}",
+                                        "{environment.label()}"
+                                    }
+                                }
+                                span {
+                                    class: "ml-2 w-24 text-right text-xs {muted_text} shrink-0",
+                                    if let Some(used_at) = used_at {
+                                        "{relative_time(used_at.with_timezone(&Local), now)}"
+                                    } else {
+                                        "never"
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "space-y-6",
+                        RecentFileList {
+                            label: "Recent workspaces",
+                            empty: "Workbooks you open appear here.",
+                            files: workbooks,
+                        }
+                        RecentFileList {
+                            label: "Recent files",
+                            empty: ".sql files you open or save appear here.",
+                            files: sql_files,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Where a saved connection points, shown as its tooltip.
+fn connection_target(conn: &SavedConnection) -> String {
+    if conn.db_type.is_file_based() {
+        conn.database.clone()
+    } else if conn.user.is_empty() {
+        format!("{}:{}/{}", conn.host, conn.port, conn.database)
+    } else {
+        format!(
+            "{}@{}:{}/{}",
+            conn.user, conn.host, conn.port, conn.database
+        )
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[component]
+fn RecentFileList(
+    label: &'static str,
+    empty: &'static str,
+    files: Vec<RecentFileEntry>,
+) -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let item_class = if is_dark {
+        "text-gray-300 hover:bg-gray-900"
+    } else {
+        "text-gray-700 hover:bg-gray-100"
+    };
+    let card_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-200"
+    };
+    let now = Local::now();
+
+    rsx! {
+        div {
+            class: "border {card_border} rounded-lg p-4",
+            h2 { class: "text-xs uppercase tracking-wide text-gray-500 mb-2", "{label}" }
+            if files.is_empty() {
+                p { class: "text-sm {muted_text} py-2", "{empty}" }
+            }
+            for file in files.into_iter().take(MAX_RECENT) {
+                button {
+                    key: "{file.path.display()}",
+                    class: "w-full flex items-center px-2 py-1.5 rounded text-left text-sm {item_class}",
+                    title: "{file.path.display()}",
+                    onclick: {
+                        let path = file.path.clone();
+                        move |_| {
+                            if let Err(e) = crate::services::open_file(&path) {
+                                tracing::error!("Failed to open {}: {}", path.display(), e);
+                            }
+                        }
+                    },
+                    span { class: "flex-1 truncate", "{file_name(&file.path)}" }
+                    span {
+                        class: "ml-2 text-xs {muted_text} shrink-0",
+                        "{relative_time(file.opened_at.with_timezone(&Local), now)}"
+                    }
+                }
+            }
+        }
+    }
+}
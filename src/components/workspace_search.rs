@@ -0,0 +1,304 @@
+use crate::components::sql_editor::reveal_line;
+use crate::config::{get_builtin_templates, QueryHistory, QueryStore};
+use crate::state::*;
+use dioxus::prelude::*;
+use regex::Regex;
+
+/// Matching lines shown per searched item
+const MAX_LINES_PER_ITEM: usize = 20;
+
+#[derive(Clone, Debug, PartialEq)]
+enum SearchSource {
+    Tab { id: String, title: String },
+    SavedQuery { name: String },
+    Template { name: String },
+    History { executed_at: String },
+}
+
+impl SearchSource {
+    fn group(&self) -> &'static str {
+        match self {
+            SearchSource::Tab { .. } => "Open tabs",
+            SearchSource::SavedQuery { .. } => "Saved queries",
+            SearchSource::Template { .. } => "Templates",
+            SearchSource::History { .. } => "History",
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            SearchSource::Tab { title, .. } => title.clone(),
+            SearchSource::SavedQuery { name } | SearchSource::Template { name } => name.clone(),
+            SearchSource::History { executed_at } => executed_at.clone(),
+        }
+    }
+}
+
+/// A searched item with the 1-based numbers and text of its matching lines.
+#[derive(Clone, Debug, PartialEq)]
+struct SearchHit {
+    source: SearchSource,
+    sql: String,
+    lines: Vec<(usize, String)>,
+}
+
+enum Matcher {
+    /// Lowercased needle, matched case-insensitively
+    Text(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn new(query: &str, is_regex: bool) -> Result<Self, String> {
+        if is_regex {
+            Regex::new(query)
+                .map(Matcher::Regex)
+                .map_err(|e| e.to_string())
+        } else {
+            Ok(Matcher::Text(query.to_lowercase()))
+        }
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        match self {
+            Matcher::Text(needle) => line.to_lowercase().contains(needle),
+            Matcher::Regex(regex) => regex.is_match(line),
+        }
+    }
+}
+
+/// Everything searchable: open tabs, saved queries, templates and history, in that order.
+fn searchable_items() -> Vec<(SearchSource, String)> {
+    let mut items: Vec<(SearchSource, String)> = EDITOR_TABS
+        .read()
+        .tabs
+        .iter()
+        .map(|t| {
+            (
+                SearchSource::Tab {
+                    id: t.id.clone(),
+                    title: t.title.clone(),
+                },
+                t.content.clone(),
+            )
+        })
+        .collect();
+    items.extend(
+        QueryStore::new()
+            .load_queries()
+            .into_iter()
+            .map(|q| (SearchSource::SavedQuery { name: q.name }, q.sql)),
+    );
+    items.extend(
+        get_builtin_templates()
+            .into_iter()
+            .map(|t| (SearchSource::Template { name: t.name }, t.sql)),
+    );
+    items.extend(QueryHistory::new().get_entries().iter().map(|e| {
+        (
+            SearchSource::History {
+                executed_at: e.executed_at.format("%Y-%m-%d %H:%M").to_string(),
+            },
+            e.sql.clone(),
+        )
+    }));
+    items
+}
+
+fn search(items: &[(SearchSource, String)], matcher: &Matcher) -> Vec<SearchHit> {
+    items
+        .iter()
+        .filter_map(|(source, sql)| {
+            let lines: Vec<(usize, String)> = sql
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| matcher.is_match(line))
+                .take(MAX_LINES_PER_ITEM)
+                .map(|(i, line)| (i + 1, line.trim().to_string()))
+                .collect();
+            (!lines.is_empty()).then(|| SearchHit {
+                source: source.clone(),
+                sql: sql.clone(),
+                lines,
+            })
+        })
+        .collect()
+}
+
+/// Show `hit` in the editor with `line` selected. Open tabs are switched to; anything
+/// else opens in a new tab.
+fn open_hit(hit: &SearchHit, line: usize) {
+    {
+        let mut tabs = EDITOR_TABS.write();
+        match &hit.source {
+            SearchSource::Tab { id, .. } => tabs.set_active(id),
+            source => {
+                let title = match source {
+                    SearchSource::History { .. } => "History".to_string(),
+                    other => other.title(),
+                };
+                tabs.add_tab(title);
+                if let Some(tab) = tabs.active_tab_mut() {
+                    tab.content = hit.sql.clone();
+                }
+            }
+        }
+    }
+    *SHOW_WORKSPACE_SEARCH.write() = false;
+    reveal_line(line);
+}
+
+#[component]
+pub fn WorkspaceSearchDialog() -> Element {
+    rsx! {
+        if *SHOW_WORKSPACE_SEARCH.read() {
+            WorkspaceSearchDialogContent {}
+        }
+    }
+}
+
+/// Search the SQL of every tab, saved query, template and history entry, grouped by
+/// where it was found.
+#[component]
+fn WorkspaceSearchDialogContent() -> Element {
+    let is_dark = *IS_DARK_MODE.read();
+    let mut query = use_signal(String::new);
+    let mut is_regex = use_signal(|| false);
+    // Read once per opening, so typing doesn't reload the stores
+    let items = use_hook(searchable_items);
+
+    let text = query.read().clone();
+    let (hits, error) = if text.is_empty() {
+        (Vec::new(), None)
+    } else {
+        match Matcher::new(&text, *is_regex.read()) {
+            Ok(matcher) => (search(&items, &matcher), None),
+            Err(e) => (Vec::new(), Some(e)),
+        }
+    };
+    let mut groups: Vec<(&'static str, Vec<SearchHit>)> = Vec::new();
+    for hit in hits {
+        let group = hit.source.group();
+        match groups.iter_mut().find(|(name, _)| *name == group) {
+            Some((_, hits)) => hits.push(hit),
+            None => groups.push((group, vec![hit])),
+        }
+    }
+
+    let overlay_bg = if is_dark {
+        "bg-black bg-opacity-80"
+    } else {
+        "bg-black bg-opacity-50"
+    };
+    let dialog_bg = if is_dark { "bg-black" } else { "bg-white" };
+    let dialog_border = if is_dark {
+        "border-gray-800"
+    } else {
+        "border-gray-300"
+    };
+    let text_color = if is_dark {
+        "text-white"
+    } else {
+        "text-gray-900"
+    };
+    let label_color = if is_dark {
+        "text-gray-400"
+    } else {
+        "text-gray-700"
+    };
+    let muted_text = if is_dark {
+        "text-gray-600"
+    } else {
+        "text-gray-400"
+    };
+    let input_class = if is_dark {
+        "bg-black border-gray-800 text-white"
+    } else {
+        "bg-white border-gray-300 text-gray-900"
+    };
+    let hover_bg = if is_dark {
+        "hover:bg-gray-900"
+    } else {
+        "hover:bg-gray-100"
+    };
+
+    rsx! {
+        div {
+            class: "fixed inset-0 {overlay_bg} flex items-start justify-center pt-24 z-50",
+            onclick: move |_| *SHOW_WORKSPACE_SEARCH.write() = false,
+
+            div {
+                class: "{dialog_bg} border {dialog_border} rounded-lg shadow-2xl w-[720px] max-w-[95vw] max-h-[75vh] flex flex-col p-4",
+                onclick: move |e: MouseEvent| e.stop_propagation(),
+
+                div {
+                    class: "flex items-center space-x-3",
+                    input {
+                        class: "flex-1 px-3 py-2 border rounded text-sm font-mono focus:outline-none focus:border-blue-500 {input_class}",
+                        placeholder: "Search all SQL...",
+                        value: "{query}",
+                        autofocus: true,
+                        oninput: move |e| query.set(e.value()),
+                        onkeydown: move |e: KeyboardEvent| {
+                            if e.key() == Key::Escape {
+                                *SHOW_WORKSPACE_SEARCH.write() = false;
+                            }
+                        },
+                    }
+                    label {
+                        class: "flex items-center space-x-1 text-xs {label_color}",
+                        input {
+                            r#type: "checkbox",
+                            checked: *is_regex.read(),
+                            onchange: move |_| {
+                                let current = *is_regex.read();
+                                is_regex.set(!current);
+                            },
+                        }
+                        span { "Regex" }
+                    }
+                }
+
+                if let Some(error) = error {
+                    div { class: "text-xs text-red-500 mt-2", "{error}" }
+                }
+
+                div {
+                    class: "flex-1 overflow-auto mt-3",
+                    if !text.is_empty() && groups.is_empty() {
+                        div { class: "text-sm {muted_text} text-center py-6", "No matches" }
+                    }
+                    for (group, hits) in groups {
+                        div {
+                            key: "{group}",
+                            class: "mb-3",
+                            div {
+                                class: "text-xs font-semibold uppercase tracking-wider {muted_text} mb-1",
+                                "{group} ({hits.len()})"
+                            }
+                            for (i, hit) in hits.into_iter().enumerate() {
+                                div {
+                                    key: "{i}",
+                                    class: "mb-1",
+                                    div { class: "text-sm {text_color} truncate", "{hit.source.title()}" }
+                                    for (line, content) in hit.lines.clone() {
+                                        button {
+                                            key: "{line}",
+                                            class: "w-full text-left flex items-baseline space-x-2 px-2 py-0.5 rounded text-xs font-mono {label_color} {hover_bg}",
+                                            onclick: {
+                                                let hit = hit.clone();
+                                                move |_| open_hit(&hit, line)
+                                            },
+                                            span { class: "w-8 shrink-0 text-right {muted_text}", "{line}" }
+                                            span { class: "truncate", "{content}" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
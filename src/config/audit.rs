@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Naming convention used to find the audit/history table of a table.
+/// Each pattern has `{table}` replaced by the table name and may be schema-qualified.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditSettings {
+    pub patterns: Vec<String>,
+}
+
+impl Default for AuditSettings {
+    fn default() -> Self {
+        Self {
+            patterns: vec![
+                "{table}_audit".to_string(),
+                "{table}_history".to_string(),
+                "audit.{table}".to_string(),
+            ],
+        }
+    }
+}
+
+pub struct AuditStore {
+    config_path: PathBuf,
+}
+
+impl AuditStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("audit.json"),
+        }
+    }
+
+    pub fn load(&self) -> AuditSettings {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, settings: &AuditSettings) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for AuditStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
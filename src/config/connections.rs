@@ -1,5 +1,7 @@
-use crate::db::DatabaseType;
+use crate::db::{ConnectionUrl, DatabaseType, OdbcMode, PlanBudget, PoolSettings, SshTunnel};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -18,6 +20,75 @@ pub struct SavedConnection {
     pub save_password: bool,
     #[serde(default)]
     pub password: Option<String>,
+    #[serde(default)]
+    pub plan_budget: PlanBudget,
+    #[serde(default)]
+    pub odbc_mode: OdbcMode,
+    #[serde(default)]
+    pub ssh_tunnel: Option<SshTunnel>,
+    #[serde(default)]
+    pub pool: PoolSettings,
+    /// Folder path such as `Production/EU`; empty for the top level
+    #[serde(default)]
+    pub folder: String,
+    #[serde(default)]
+    pub environment: Option<Environment>,
+    /// Extra URL parameters such as `sslmode=require`; PostgreSQL and MySQL only
+    #[serde(default)]
+    pub url_params: String,
+}
+
+impl SavedConnection {
+    /// `postgres://` or `mysql://` URL for sharing, without the password.
+    /// `None` for databases that are not reached through such a URL.
+    pub fn url(&self) -> Option<String> {
+        matches!(self.db_type, DatabaseType::PostgreSQL | DatabaseType::MySQL).then(|| {
+            ConnectionUrl {
+                db_type: self.db_type,
+                host: self.host.clone(),
+                port: self.port,
+                user: self.user.clone(),
+                password: String::new(),
+                database: self.database.clone(),
+                params: self.url_params.clone(),
+            }
+            .to_url()
+        })
+    }
+}
+
+/// Which kind of server a connection points at, shown as a colored stripe while connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Environment {
+    Development,
+    Staging,
+    /// Data-changing statements ask for confirmation first
+    Production,
+}
+
+impl Environment {
+    pub const ALL: [Environment; 3] = [
+        Environment::Development,
+        Environment::Staging,
+        Environment::Production,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Environment::Development => "dev",
+            Environment::Staging => "staging",
+            Environment::Production => "prod",
+        }
+    }
+
+    /// Tailwind background class of the environment's color
+    pub fn color_class(self) -> &'static str {
+        match self {
+            Environment::Development => "bg-green-600",
+            Environment::Staging => "bg-yellow-500",
+            Environment::Production => "bg-red-600",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -25,6 +96,44 @@ struct ConnectionsFile {
     connections: Vec<SavedConnection>,
     #[serde(default)]
     last_used: Option<String>,
+    /// When each connection was last connected to, by name
+    #[serde(default)]
+    last_used_at: HashMap<String, DateTime<Utc>>,
+    /// Folder paths collapsed in the saved connections list
+    #[serde(default)]
+    collapsed_folders: Vec<String>,
+}
+
+/// Whether `folder` is `ancestor` or lies somewhere below it.
+pub fn folder_within(folder: &str, ancestor: &str) -> bool {
+    ancestor.is_empty()
+        || folder == ancestor
+        || folder
+            .strip_prefix(ancestor)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Move the connection called `name` into `folder`, just before the connection called
+/// `before`, or after the last connection in that folder when `before` is `None`.
+pub fn move_connection(
+    connections: &mut Vec<SavedConnection>,
+    name: &str,
+    folder: &str,
+    before: Option<&str>,
+) {
+    let Some(from) = connections.iter().position(|c| c.name == name) else {
+        return;
+    };
+    let mut conn = connections.remove(from);
+    conn.folder = folder.to_string();
+    let to = match before.and_then(|b| connections.iter().position(|c| c.name == b)) {
+        Some(index) => index,
+        None => connections
+            .iter()
+            .rposition(|c| folder_within(&c.folder, folder))
+            .map_or(connections.len(), |index| index + 1),
+    };
+    connections.insert(to, conn);
 }
 
 pub struct ConnectionStore {
@@ -67,6 +176,25 @@ impl ConnectionStore {
     pub fn set_last_used(&self, name: &str) -> Result<(), String> {
         let mut file = self.load_file();
         file.last_used = Some(name.to_string());
+        file.last_used_at.insert(name.to_string(), Utc::now());
+        self.save_file(&file)
+    }
+
+    /// When each saved connection was last connected to; never-used ones are missing.
+    pub fn last_used_times(&self) -> HashMap<String, DateTime<Utc>> {
+        self.load_file().last_used_at
+    }
+
+    pub fn collapsed_folders(&self) -> Vec<String> {
+        self.load_file().collapsed_folders
+    }
+
+    pub fn set_folder_collapsed(&self, folder: &str, collapsed: bool) -> Result<(), String> {
+        let mut file = self.load_file();
+        file.collapsed_folders.retain(|f| f != folder);
+        if collapsed {
+            file.collapsed_folders.push(folder.to_string());
+        }
         self.save_file(&file)
     }
 
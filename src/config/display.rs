@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// How the UI is scaled and values are presented in the result grid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    /// Render URLs, emails, UUIDs, timestamps and color codes in text cells
+    pub detect_cell_types: bool,
+    /// Zoom factor for the whole window, separate from the editor font size
+    pub ui_zoom: f64,
+    /// Move keyboard focus to the result grid after running the statement at the cursor
+    pub focus_results_on_run: bool,
+    /// Shown in place of SQL NULL, styled apart from real values
+    pub null_text: String,
+    pub empty_string: EmptyStringDisplay,
+}
+
+/// How an empty string is shown in the grid, so it can't be mistaken for NULL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EmptyStringDisplay {
+    #[default]
+    Symbol,
+    Quotes,
+}
+
+impl EmptyStringDisplay {
+    pub fn marker(self) -> &'static str {
+        match self {
+            EmptyStringDisplay::Symbol => "∅",
+            EmptyStringDisplay::Quotes => "''",
+        }
+    }
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            detect_cell_types: true,
+            ui_zoom: 1.0,
+            focus_results_on_run: true,
+            null_text: "NULL".to_string(),
+            empty_string: EmptyStringDisplay::default(),
+        }
+    }
+}
+
+pub struct DisplayStore {
+    config_path: PathBuf,
+}
+
+impl DisplayStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("display.json"),
+        }
+    }
+
+    pub fn load(&self) -> DisplaySettings {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, settings: &DisplaySettings) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for DisplayStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
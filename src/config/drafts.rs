@@ -1,11 +1,17 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TabDraft {
     pub title: String,
     pub content: String,
+    /// File backing the tab, reopened with the draft
+    #[serde(default)]
+    pub file_path: Option<PathBuf>,
+    #[serde(default)]
+    pub file_modified: Option<SystemTime>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
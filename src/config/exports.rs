@@ -0,0 +1,73 @@
+use crate::export::{BucketTarget, ExportOptions, KafkaTarget};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportPreset {
+    pub name: String,
+    pub options: ExportOptions,
+}
+
+/// Export options used last time plus the named presets.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportSettings {
+    pub last_used: ExportOptions,
+    pub presets: Vec<ExportPreset>,
+    /// Topic used for the last Kafka export
+    pub kafka: KafkaTarget,
+    /// Bucket and credentials used for the last cloud storage export
+    pub bucket: BucketTarget,
+}
+
+pub struct ExportStore {
+    config_path: PathBuf,
+}
+
+impl ExportStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("exports.json"),
+        }
+    }
+
+    pub fn load(&self) -> ExportSettings {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, settings: &ExportSettings) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+
+    pub fn get_s3_secret(&self, access_key_id: &str) -> Option<String> {
+        let entry = keyring::Entry::new("fbench", &Self::s3_secret_entry(access_key_id)).ok()?;
+        entry.get_password().ok()
+    }
+
+    pub fn set_s3_secret(&self, access_key_id: &str, secret: &str) -> Result<(), String> {
+        let entry = keyring::Entry::new("fbench", &Self::s3_secret_entry(access_key_id))
+            .map_err(|e| e.to_string())?;
+        entry.set_password(secret).map_err(|e| e.to_string())
+    }
+
+    fn s3_secret_entry(access_key_id: &str) -> String {
+        format!("s3-export:{}", access_key_id)
+    }
+}
+
+impl Default for ExportStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,9 +1,15 @@
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::Write;
 use std::path::PathBuf;
 
 const MAX_HISTORY_ITEMS: usize = 50;
+const MAX_USAGE_EVENTS: usize = 20_000;
+/// Runs shown in a saved query's duration trend
+const TREND_RUNS: usize = 30;
+/// Earlier runs needed before the latest one can count as a regression
+const MIN_TREND_BASELINE: usize = 3;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HistoryEntry {
@@ -95,3 +101,143 @@ impl Default for QueryHistory {
         Self::new()
     }
 }
+
+/// One entry in the local usage log behind the stats view. It never leaves this machine.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsageEvent {
+    pub at: DateTime<Local>,
+    pub kind: UsageKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UsageKind {
+    Query {
+        tables: Vec<String>,
+        execution_time_ms: u64,
+        /// `crate::db::query_hash` of the statement, for per-query trends. Missing in
+        /// events recorded before trends existed.
+        #[serde(default)]
+        query_hash: Option<String>,
+    },
+    QueryError,
+    AiRequest,
+}
+
+/// Append-only log of executions and AI requests, kept next to the query history.
+/// Unlike the history it is not deduplicated, so it can be counted per day.
+pub struct UsageLog {
+    log_path: PathBuf,
+}
+
+impl UsageLog {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            log_path: config_dir.join("usage.jsonl"),
+        }
+    }
+
+    pub fn record(&self, kind: UsageKind) {
+        let event = UsageEvent {
+            at: Local::now(),
+            kind,
+        };
+        let Ok(line) = serde_json::to_string(&event) else {
+            return;
+        };
+        let result = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        if let Err(e) = result {
+            tracing::error!("Failed to record usage: {}", e);
+        }
+    }
+
+    /// All recorded events, oldest first. Trims the log to the newest MAX_USAGE_EVENTS.
+    pub fn load(&self) -> Vec<UsageEvent> {
+        let mut events: Vec<UsageEvent> = fs::read_to_string(&self.log_path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+
+        if events.len() > MAX_USAGE_EVENTS {
+            events.drain(..events.len() - MAX_USAGE_EVENTS);
+            let content: String = events
+                .iter()
+                .filter_map(|e| serde_json::to_string(e).ok())
+                .map(|line| line + "\n")
+                .collect();
+            let _ = fs::write(&self.log_path, content);
+        }
+
+        events
+    }
+
+    pub fn clear(&self) {
+        let _ = fs::remove_file(&self.log_path);
+    }
+}
+
+impl Default for UsageLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Durations of the recent runs of one statement, from the usage log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryTrend {
+    /// (run time, duration in ms), oldest first
+    pub runs: Vec<(DateTime<Local>, u64)>,
+    /// Median duration of the runs before the latest one
+    pub median_ms: u64,
+}
+
+impl QueryTrend {
+    pub fn latest_ms(&self) -> u64 {
+        self.runs.last().map_or(0, |(_, ms)| *ms)
+    }
+
+    /// The latest run took more than twice the median of the earlier ones.
+    pub fn is_regression(&self) -> bool {
+        self.runs.len() > MIN_TREND_BASELINE && self.latest_ms() > self.median_ms * 2
+    }
+}
+
+/// Trend of the statement with `query_hash`, or None when it ran fewer than twice.
+pub fn query_trend(events: &[UsageEvent], query_hash: &str) -> Option<QueryTrend> {
+    let mut runs: Vec<(DateTime<Local>, u64)> = events
+        .iter()
+        .filter_map(|event| match &event.kind {
+            UsageKind::Query {
+                execution_time_ms,
+                query_hash: Some(hash),
+                ..
+            } if hash == query_hash => Some((event.at, *execution_time_ms)),
+            _ => None,
+        })
+        .collect();
+    if runs.len() < 2 {
+        return None;
+    }
+    runs.drain(..runs.len().saturating_sub(TREND_RUNS));
+
+    let mut earlier: Vec<u64> = runs[..runs.len() - 1].iter().map(|(_, ms)| *ms).collect();
+    earlier.sort_unstable();
+    let mid = earlier.len() / 2;
+    let median_ms = if earlier.len() % 2 == 0 {
+        (earlier[mid - 1] + earlier[mid]) / 2
+    } else {
+        earlier[mid]
+    };
+    Some(QueryTrend { runs, median_ms })
+}
@@ -0,0 +1,48 @@
+use crate::import::ImportProfile;
+use std::fs;
+use std::path::PathBuf;
+
+pub struct ImportProfileStore {
+    config_path: PathBuf,
+}
+
+impl ImportProfileStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("import_profiles.json"),
+        }
+    }
+
+    pub fn load(&self) -> Vec<ImportProfile> {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, profiles: &[ImportProfile]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(profiles).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+
+    /// Store `profile`, replacing one with the same table and name.
+    pub fn upsert(&self, profile: ImportProfile) -> Result<(), String> {
+        let mut profiles = self.load();
+        profiles.retain(|p| !(p.table == profile.table && p.name == profile.name));
+        profiles.push(profile);
+        profiles.sort_by(|a, b| a.table.cmp(&b.table).then_with(|| a.name.cmp(&b.name)));
+        self.save(&profiles)
+    }
+}
+
+impl Default for ImportProfileStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
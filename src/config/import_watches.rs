@@ -0,0 +1,94 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const MAX_WATCH_LOG_ENTRIES: usize = 500;
+
+/// A folder whose new CSV, JSON and Parquet files are imported into one table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportWatch {
+    pub folder: String,
+    pub table: String,
+    /// Name of the table's import profile that files must match
+    pub profile: String,
+    /// Import valid files right away instead of queueing them for approval
+    pub auto_import: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WatchOutcome {
+    Imported(usize),
+    Failed(String),
+    Dismissed,
+}
+
+/// A file the watcher has finished with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessedImportFile {
+    pub path: String,
+    /// Modification time in seconds since the epoch, so a replaced file is picked up again
+    pub modified: u64,
+    pub table: String,
+    pub processed_at: DateTime<Local>,
+    pub outcome: WatchOutcome,
+}
+
+/// Watched folders plus the log of files they have processed.
+pub struct ImportWatchStore {
+    config_path: PathBuf,
+    log_path: PathBuf,
+}
+
+impl ImportWatchStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("import_watches.json"),
+            log_path: config_dir.join("import_watch_log.json"),
+        }
+    }
+
+    pub fn load(&self) -> Vec<ImportWatch> {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, watches: &[ImportWatch]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(watches).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+
+    /// Processed files, newest first.
+    pub fn load_log(&self) -> Vec<ProcessedImportFile> {
+        fs::read_to_string(&self.log_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn record(&self, entry: ProcessedImportFile) -> Result<(), String> {
+        let mut log = self.load_log();
+        log.insert(0, entry);
+        log.truncate(MAX_WATCH_LOG_ENTRIES);
+        let json = serde_json::to_string_pretty(&log).map_err(|e| e.to_string())?;
+        fs::write(&self.log_path, json).map_err(|e| e.to_string())
+    }
+
+    pub fn clear_log(&self) {
+        let _ = fs::remove_file(&self.log_path);
+    }
+}
+
+impl Default for ImportWatchStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
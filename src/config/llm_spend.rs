@@ -0,0 +1,60 @@
+use crate::llm::TokenUsage;
+use chrono::Local;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// LLM token usage and cost per calendar month ("YYYY-MM"), behind the budget warning.
+pub struct LlmSpendStore {
+    config_path: PathBuf,
+}
+
+impl LlmSpendStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("llm_spend.json"),
+        }
+    }
+
+    fn load(&self) -> BTreeMap<String, TokenUsage> {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn current_month(&self) -> TokenUsage {
+        self.load()
+            .get(&Self::month_key())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Adds to this month's total and returns the new total.
+    pub fn record(&self, usage: TokenUsage) -> Result<TokenUsage, String> {
+        let mut months = self.load();
+        let total = months.entry(Self::month_key()).or_default();
+        total.add(usage);
+        let total = *total;
+
+        let json = serde_json::to_string_pretty(&months).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())?;
+        Ok(total)
+    }
+
+    fn month_key() -> String {
+        Local::now().format("%Y-%m").to_string()
+    }
+}
+
+impl Default for LlmSpendStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
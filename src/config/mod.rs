@@ -1,15 +1,45 @@
+mod audit;
 mod connections;
+mod display;
 mod drafts;
+mod exports;
 mod history;
+mod import_profiles;
+mod import_watches;
+mod llm_spend;
 mod queries;
+mod query_params;
+mod query_settings;
+mod query_tests;
+mod recent_files;
 mod recent_tables;
 mod session;
+mod snippets;
+mod soft_delete;
+mod table_descriptions;
+mod team_library;
 mod templates;
+mod tray;
 
+pub use audit::*;
 pub use connections::*;
+pub use display::*;
 pub use drafts::*;
+pub use exports::*;
 pub use history::*;
+pub use import_profiles::*;
+pub use import_watches::*;
+pub use llm_spend::*;
 pub use queries::*;
+pub use query_params::*;
+pub use query_settings::*;
+pub use query_tests::*;
+pub use recent_files::*;
 pub use recent_tables::*;
 pub use session::*;
+pub use snippets::*;
+pub use soft_delete::*;
+pub use table_descriptions::*;
+pub use team_library::*;
 pub use templates::*;
+pub use tray::*;
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Parameter values last entered for a statement, by placeholder label (`:id`, `$1`, `?1`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueryParamValues {
+    pub values: HashMap<String, String>,
+}
+
+/// Last-used parameter values, keyed by `crate::db::query_hash` of the statement.
+pub struct QueryParamStore {
+    config_path: PathBuf,
+}
+
+impl QueryParamStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("query_params.json"),
+        }
+    }
+
+    fn load(&self) -> HashMap<String, QueryParamValues> {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn load_values(&self, query_hash: &str) -> QueryParamValues {
+        self.load().remove(query_hash).unwrap_or_default()
+    }
+
+    pub fn save_values(&self, query_hash: &str, values: QueryParamValues) -> Result<(), String> {
+        let mut all = self.load();
+        all.insert(query_hash.to_string(), values);
+        let json = serde_json::to_string_pretty(&all).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for QueryParamStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Query defaults shared by every connection.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuerySettings {
+    /// Statement timeout for connections that don't set their own; unset or 0 is no limit
+    pub statement_timeout_secs: Option<u64>,
+    /// Rows a SELECT without a LIMIT is cut at when run from the editor; unset is off
+    pub auto_limit: Option<usize>,
+}
+
+pub struct QuerySettingsStore {
+    config_path: PathBuf,
+}
+
+impl QuerySettingsStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("query_settings.json"),
+        }
+    }
+
+    pub fn load(&self) -> QuerySettings {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, settings: &QuerySettings) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for QuerySettingsStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,53 @@
+use crate::db::TestFixture;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// A test of a saved query: the fixtures it inserts and the rows it should return.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryTest {
+    pub name: String,
+    /// Name of the saved query under test
+    pub query: String,
+    pub fixtures: Vec<TestFixture>,
+    /// CSV with a header row; `NULL` matches a null cell
+    pub expected_csv: String,
+    #[serde(default)]
+    pub ordered: bool,
+}
+
+pub struct QueryTestStore {
+    config_path: PathBuf,
+}
+
+impl QueryTestStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("query_tests.json"),
+        }
+    }
+
+    pub fn load_tests(&self) -> Vec<QueryTest> {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_tests(&self, tests: &[QueryTest]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(tests).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for QueryTestStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
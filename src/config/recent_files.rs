@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_RECENT_FILES: usize = 20;
+
+/// A .sql file or .fbench workbook opened or saved in the app
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentFileEntry {
+    pub path: PathBuf,
+    pub opened_at: DateTime<Utc>,
+}
+
+impl RecentFileEntry {
+    pub fn is_workbook(&self) -> bool {
+        self.path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("fbench"))
+    }
+}
+
+pub struct RecentFilesStore {
+    config_path: PathBuf,
+}
+
+impl RecentFilesStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("recent_files.json"),
+        }
+    }
+
+    /// Most recent first
+    pub fn load(&self) -> Vec<RecentFileEntry> {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn add(&self, path: &Path) -> Result<(), String> {
+        let mut entries = self.load();
+        entries.retain(|e| e.path != path);
+        entries.insert(
+            0,
+            RecentFileEntry {
+                path: path.to_path_buf(),
+                opened_at: Utc::now(),
+            },
+        );
+        entries.truncate(MAX_RECENT_FILES);
+        self.save(&entries)
+    }
+
+    fn save(&self, entries: &[RecentFileEntry]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for RecentFilesStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// Text typed in the editor that Tab expands into `body`. The body marks where the caret
+/// stops with `$1`, `$2`, ... or `${1:default}`, and where it ends up with `$0`. `$$` is
+/// left as it is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snippet {
+    pub trigger: String,
+    pub body: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// A snippet's body with its placeholders filled in by their defaults
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnippetExpansion {
+    pub text: String,
+    /// Char ranges of the tab stops in `text`, in the order Tab visits them. The last
+    /// one is where the caret ends up: `$0`, or the end of the text.
+    pub stops: Vec<Range<usize>>,
+}
+
+impl Snippet {
+    pub fn expand(&self) -> SnippetExpansion {
+        let chars: Vec<char> = self.body.chars().collect();
+        let mut text = String::new();
+        let mut len = 0;
+        // (number, position in the body, range in the text)
+        let mut stops: Vec<(usize, usize, Range<usize>)> = Vec::new();
+        let mut has_final_stop = false;
+        let mut i = 0;
+        while i < chars.len() {
+            // `$$` is PostgreSQL dollar quoting, not a placeholder
+            if chars[i] == '$' && chars.get(i + 1) == Some(&'$') {
+                text.push_str("$$");
+                len += 2;
+                i += 2;
+                continue;
+            }
+            if chars[i] == '$' {
+                if let Some((number, default, next)) = placeholder(&chars, i + 1) {
+                    let start = len;
+                    text.push_str(&default);
+                    len += default.chars().count();
+                    stops.push((number, i, start..len));
+                    has_final_stop |= number == 0;
+                    i = next;
+                    continue;
+                }
+            }
+            text.push(chars[i]);
+            len += 1;
+            i += 1;
+        }
+
+        // $0 comes after every numbered stop
+        stops.sort_by_key(|(number, position, _)| (*number == 0, *number, *position));
+        let mut stops: Vec<Range<usize>> = stops.into_iter().map(|(_, _, range)| range).collect();
+        if !has_final_stop {
+            stops.push(len..len);
+        }
+        SnippetExpansion { text, stops }
+    }
+}
+
+/// The stop number and default of a placeholder whose `$` sits just before `start`,
+/// and where the body continues after it.
+fn placeholder(chars: &[char], start: usize) -> Option<(usize, String, usize)> {
+    let digits = |from: usize| {
+        let end = chars[from..]
+            .iter()
+            .position(|c| !c.is_ascii_digit())
+            .map_or(chars.len(), |n| from + n);
+        let number = chars[from..end].iter().collect::<String>().parse().ok()?;
+        Some((number, end))
+    };
+    match chars.get(start)? {
+        '{' => {
+            let (number, end) = digits(start + 1)?;
+            match chars.get(end)? {
+                '}' => Some((number, String::new(), end + 1)),
+                ':' => {
+                    let close = chars[end..].iter().position(|c| *c == '}')? + end;
+                    Some((number, chars[end + 1..close].iter().collect(), close + 1))
+                }
+                _ => None,
+            }
+        }
+        c if c.is_ascii_digit() => {
+            let (number, end) = digits(start)?;
+            Some((number, String::new(), end))
+        }
+        _ => None,
+    }
+}
+
+fn default_snippets() -> Vec<Snippet> {
+    [
+        (
+            "sel",
+            "SELECT * FROM $1 WHERE $2;",
+            "SELECT with a WHERE clause",
+        ),
+        (
+            "selc",
+            "SELECT COUNT(*) FROM $1 WHERE $2;",
+            "Count matching rows",
+        ),
+        (
+            "ins",
+            "INSERT INTO $1 (${2:columns}) VALUES (${3:values});",
+            "INSERT a row",
+        ),
+        (
+            "upd",
+            "UPDATE $1 SET ${2:column} = ${3:value} WHERE $4;",
+            "UPDATE with a WHERE clause",
+        ),
+        (
+            "del",
+            "DELETE FROM $1 WHERE $2;",
+            "DELETE with a WHERE clause",
+        ),
+        (
+            "cte",
+            "WITH ${1:name} AS (\n    $2\n)\nSELECT * FROM $3;",
+            "Common table expression",
+        ),
+        ("lj", "LEFT JOIN $1 ON $2 = $3", "LEFT JOIN clause"),
+    ]
+    .into_iter()
+    .map(|(trigger, body, description)| Snippet {
+        trigger: trigger.to_string(),
+        body: body.to_string(),
+        description: description.to_string(),
+    })
+    .collect()
+}
+
+pub struct SnippetStore {
+    config_path: PathBuf,
+}
+
+impl SnippetStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("snippets.json"),
+        }
+    }
+
+    /// The saved snippets, or a starter set before any are saved.
+    pub fn load(&self) -> Vec<Snippet> {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_else(default_snippets)
+    }
+
+    pub fn save(&self, snippets: &[Snippet]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(snippets).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for SnippetStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Column soft deletes are detected on by default.
+pub const DEFAULT_SOFT_DELETE_COLUMN: &str = "deleted_at";
+
+/// A table whose rows are hidden rather than removed, by setting `column` to a timestamp.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoftDeleteTable {
+    /// Name of the saved connection the table belongs to
+    #[serde(default)]
+    pub connection: String,
+    /// Schema of the table; empty for the connection's default schema
+    #[serde(default)]
+    pub schema: String,
+    pub table_name: String,
+    pub column: String,
+}
+
+impl SoftDeleteTable {
+    pub fn is_table(&self, connection: &str, schema: &str, table_name: &str) -> bool {
+        self.connection == connection && self.schema == schema && self.table_name == table_name
+    }
+}
+
+pub struct SoftDeleteStore {
+    config_path: PathBuf,
+}
+
+impl SoftDeleteStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("soft_delete.json"),
+        }
+    }
+
+    pub fn load(&self) -> Vec<SoftDeleteTable> {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set(
+        &self,
+        connection: &str,
+        schema: &str,
+        table_name: &str,
+        column: &str,
+    ) -> Result<(), String> {
+        let mut entries = self.load();
+        entries.retain(|e| !e.is_table(connection, schema, table_name));
+        entries.push(SoftDeleteTable {
+            connection: connection.to_string(),
+            schema: schema.to_string(),
+            table_name: table_name.to_string(),
+            column: column.to_string(),
+        });
+        self.save(&entries)
+    }
+
+    pub fn remove(&self, connection: &str, schema: &str, table_name: &str) -> Result<(), String> {
+        let mut entries = self.load();
+        entries.retain(|e| !e.is_table(connection, schema, table_name));
+        self.save(&entries)
+    }
+
+    fn save(&self, entries: &[SoftDeleteTable]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for SoftDeleteStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableDescription {
+    pub text: String,
+    pub described_at: DateTime<Local>,
+}
+
+/// AI descriptions of tables, kept so they are not asked for again on every visit.
+/// Keyed by database name and table name.
+pub struct TableDescriptionStore {
+    config_path: PathBuf,
+}
+
+impl TableDescriptionStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("table_descriptions.json"),
+        }
+    }
+
+    fn load(&self) -> HashMap<String, TableDescription> {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, database: &str, table: &str) -> Option<TableDescription> {
+        self.load().remove(&Self::key(database, table))
+    }
+
+    pub fn set(
+        &self,
+        database: &str,
+        table: &str,
+        description: &TableDescription,
+    ) -> Result<(), String> {
+        let mut descriptions = self.load();
+        descriptions.insert(Self::key(database, table), description.clone());
+        let json = serde_json::to_string_pretty(&descriptions).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+
+    fn key(database: &str, table: &str) -> String {
+        format!("{}/{}", database, table)
+    }
+}
+
+impl Default for TableDescriptionStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,96 @@
+use super::SavedQuery;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+pub const DEFAULT_TEAM_LIBRARY_REFRESH_MINUTES: u64 = 15;
+
+/// Where the team's shared .sql files live.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TeamLibrarySource {
+    /// A git repository, cloned and pulled into the config directory
+    Git(String),
+    /// An HTTPS URL of a single .sql file, or of an index listing one .sql path per line
+    Url(String),
+}
+
+impl TeamLibrarySource {
+    pub fn location(&self) -> &str {
+        match self {
+            TeamLibrarySource::Git(location) | TeamLibrarySource::Url(location) => location,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamLibrarySettings {
+    pub source: Option<TeamLibrarySource>,
+    pub refresh_minutes: u64,
+}
+
+impl Default for TeamLibrarySettings {
+    fn default() -> Self {
+        Self {
+            source: None,
+            refresh_minutes: DEFAULT_TEAM_LIBRARY_REFRESH_MINUTES,
+        }
+    }
+}
+
+/// Team library settings plus the last fetched queries, so they stay available offline.
+pub struct TeamLibraryStore {
+    config_path: PathBuf,
+    cache_path: PathBuf,
+    checkout_dir: PathBuf,
+}
+
+impl TeamLibraryStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("team_library.json"),
+            cache_path: config_dir.join("team_queries.json"),
+            checkout_dir: config_dir.join("team_library"),
+        }
+    }
+
+    pub fn load(&self) -> TeamLibrarySettings {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, settings: &TeamLibrarySettings) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+
+    pub fn load_cached(&self) -> Vec<SavedQuery> {
+        fs::read_to_string(&self.cache_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_cached(&self, queries: &[SavedQuery]) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(queries).map_err(|e| e.to_string())?;
+        fs::write(&self.cache_path, json).map_err(|e| e.to_string())
+    }
+
+    /// Local clone of a git team library
+    pub fn checkout_dir(&self) -> PathBuf {
+        self.checkout_dir.clone()
+    }
+}
+
+impl Default for TeamLibraryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,3 +1,4 @@
+use crate::db::DatabaseType;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,3 +130,215 @@ pub fn get_builtin_templates() -> Vec<QueryTemplate> {
         },
     ]
 }
+
+/// Health checks for the connected database: bloat, missing indexes, locks, long-running
+/// queries and slow tables, as far as the database exposes them.
+pub fn get_diagnostic_templates(db_type: DatabaseType) -> Vec<QueryTemplate> {
+    let min_seconds = || TemplateVariable {
+        name: "min_seconds".to_string(),
+        placeholder: "30".to_string(),
+        default_value: Some("30".to_string()),
+    };
+    let diagnostic = |name: &str, description: &str, sql: &str| QueryTemplate {
+        name: name.to_string(),
+        description: description.to_string(),
+        sql: sql.to_string(),
+        variables: vec![],
+    };
+
+    match db_type {
+        DatabaseType::PostgreSQL => vec![
+            diagnostic(
+                "Bloat check",
+                "Tables with the most dead rows that VACUUM has not reclaimed",
+                "SELECT schemaname, relname AS table_name, n_live_tup, n_dead_tup,
+       round(100.0 * n_dead_tup / NULLIF(n_live_tup + n_dead_tup, 0), 1) AS dead_pct,
+       pg_size_pretty(pg_total_relation_size(relid)) AS total_size,
+       last_autovacuum, last_vacuum
+FROM pg_stat_user_tables
+WHERE n_dead_tup > 0
+ORDER BY n_dead_tup DESC
+LIMIT 50;",
+            ),
+            diagnostic(
+                "Missing indexes",
+                "Tables over 10 MB scanned sequentially more often than by index",
+                "SELECT schemaname, relname AS table_name, seq_scan, idx_scan, seq_tup_read,
+       seq_tup_read / NULLIF(seq_scan, 0) AS rows_per_seq_scan,
+       pg_size_pretty(pg_relation_size(relid)) AS table_size
+FROM pg_stat_user_tables
+WHERE seq_scan > COALESCE(idx_scan, 0)
+  AND pg_relation_size(relid) > 10 * 1024 * 1024
+ORDER BY seq_tup_read DESC
+LIMIT 50;",
+            ),
+            diagnostic(
+                "Lock monitor",
+                "Sessions waiting on a lock and the sessions holding it",
+                "SELECT blocked.pid AS blocked_pid, blocked.usename AS blocked_user,
+       now() - blocked.query_start AS waiting_for, blocked.query AS blocked_query,
+       blocking.pid AS blocking_pid, blocking.usename AS blocking_user,
+       blocking.state AS blocking_state, blocking.query AS blocking_query
+FROM pg_stat_activity blocked
+CROSS JOIN LATERAL unnest(pg_blocking_pids(blocked.pid)) AS b(pid)
+JOIN pg_stat_activity blocking ON blocking.pid = b.pid
+ORDER BY waiting_for DESC;",
+            ),
+            QueryTemplate {
+                variables: vec![min_seconds()],
+                ..diagnostic(
+                    "Long-running queries",
+                    "Statements that have been running for over 30 seconds",
+                    "SELECT pid, usename, datname, state, now() - query_start AS duration,
+       wait_event_type, wait_event, query
+FROM pg_stat_activity
+WHERE state <> 'idle'
+  AND pid <> pg_backend_pid()
+  AND query_start < now() - interval '${min_seconds} seconds'
+ORDER BY duration DESC;",
+                )
+            },
+            diagnostic(
+                "Slowest tables",
+                "Tables read most often from disk instead of the buffer cache",
+                "SELECT schemaname, relname AS table_name, heap_blks_read, heap_blks_hit,
+       round(100.0 * heap_blks_hit / NULLIF(heap_blks_hit + heap_blks_read, 0), 1) AS cache_hit_pct,
+       idx_blks_read, idx_blks_hit
+FROM pg_statio_user_tables
+WHERE heap_blks_read > 0
+ORDER BY heap_blks_read DESC
+LIMIT 50;",
+            ),
+        ],
+        DatabaseType::MySQL => vec![
+            diagnostic(
+                "Bloat check",
+                "Tables with the most allocated but unused space",
+                "SELECT TABLE_SCHEMA, TABLE_NAME, ENGINE,
+       ROUND(DATA_LENGTH / 1024 / 1024, 1) AS data_mb,
+       ROUND(INDEX_LENGTH / 1024 / 1024, 1) AS index_mb,
+       ROUND(DATA_FREE / 1024 / 1024, 1) AS free_mb,
+       ROUND(100 * DATA_FREE / NULLIF(DATA_LENGTH + INDEX_LENGTH + DATA_FREE, 0), 1) AS free_pct
+FROM information_schema.TABLES
+WHERE TABLE_SCHEMA NOT IN ('mysql', 'information_schema', 'performance_schema', 'sys')
+  AND DATA_FREE > 0
+ORDER BY DATA_FREE DESC
+LIMIT 50;",
+            ),
+            diagnostic(
+                "Missing indexes",
+                "Tables with the most rows read without an index",
+                "SELECT OBJECT_SCHEMA, OBJECT_NAME AS table_name, COUNT_READ AS rows_read_without_index
+FROM performance_schema.table_io_waits_summary_by_index_usage
+WHERE INDEX_NAME IS NULL
+  AND COUNT_READ > 0
+  AND OBJECT_SCHEMA NOT IN ('mysql', 'performance_schema', 'sys')
+ORDER BY COUNT_READ DESC
+LIMIT 50;",
+            ),
+            diagnostic(
+                "Lock monitor",
+                "InnoDB transactions waiting on a lock and the ones holding it",
+                "SELECT wait_age, locked_table, locked_type,
+       waiting_pid, waiting_query, blocking_pid, blocking_query
+FROM sys.innodb_lock_waits
+ORDER BY wait_age_secs DESC;",
+            ),
+            QueryTemplate {
+                variables: vec![min_seconds()],
+                ..diagnostic(
+                    "Long-running queries",
+                    "Statements that have been running for over 30 seconds",
+                    "SELECT ID, USER, HOST, DB, COMMAND, TIME AS seconds, STATE, INFO AS query
+FROM information_schema.PROCESSLIST
+WHERE COMMAND <> 'Sleep'
+  AND ID <> CONNECTION_ID()
+  AND TIME >= ${min_seconds}
+ORDER BY TIME DESC;",
+                )
+            },
+            diagnostic(
+                "Slowest tables",
+                "Tables with the most time spent on I/O since the server started",
+                "SELECT OBJECT_SCHEMA, OBJECT_NAME AS table_name, COUNT_STAR AS operations,
+       ROUND(SUM_TIMER_WAIT / 1e12, 2) AS total_wait_s,
+       ROUND(AVG_TIMER_WAIT / 1e9, 3) AS avg_wait_ms
+FROM performance_schema.table_io_waits_summary_by_table
+WHERE OBJECT_SCHEMA NOT IN ('mysql', 'performance_schema', 'sys')
+ORDER BY SUM_TIMER_WAIT DESC
+LIMIT 50;",
+            ),
+        ],
+        // One writer and no server, so there are no locks or sessions to watch
+        DatabaseType::SQLite => vec![
+            diagnostic(
+                "Bloat check",
+                "Free pages that VACUUM would give back",
+                "SELECT f.freelist_count, p.page_count,
+       ROUND(100.0 * f.freelist_count / p.page_count, 1) AS free_pct
+FROM pragma_freelist_count() f, pragma_page_count() p;",
+            ),
+            diagnostic(
+                "Missing indexes",
+                "Foreign key columns that no index starts with",
+                "SELECT m.name AS table_name, f.\"from\" AS column_name, f.\"table\" AS references_table
+FROM sqlite_master m
+JOIN pragma_foreign_key_list(m.name) f
+WHERE m.type = 'table'
+  AND NOT EXISTS (
+    SELECT 1
+    FROM pragma_index_list(m.name) il
+    JOIN pragma_index_info(il.name) ii
+    WHERE ii.seqno = 0 AND ii.name = f.\"from\"
+  )
+ORDER BY m.name;",
+            ),
+        ],
+        DatabaseType::ClickHouse => vec![
+            diagnostic(
+                "Bloat check",
+                "Tables with the most active parts waiting to be merged",
+                "SELECT database, table, count() AS active_parts, sum(rows) AS rows,
+       formatReadableSize(sum(bytes_on_disk)) AS size
+FROM system.parts
+WHERE active
+GROUP BY database, table
+ORDER BY active_parts DESC
+LIMIT 50;",
+            ),
+            diagnostic(
+                "Lock monitor",
+                "Mutations that have not finished, and why the last attempt failed",
+                "SELECT database, table, mutation_id, command, create_time, parts_to_do,
+       latest_fail_reason
+FROM system.mutations
+WHERE NOT is_done
+ORDER BY create_time;",
+            ),
+            QueryTemplate {
+                variables: vec![min_seconds()],
+                ..diagnostic(
+                    "Long-running queries",
+                    "Queries that have been running for over 30 seconds",
+                    "SELECT query_id, user, elapsed, formatReadableSize(memory_usage) AS memory,
+       read_rows, query
+FROM system.processes
+WHERE elapsed >= ${min_seconds}
+ORDER BY elapsed DESC;",
+                )
+            },
+            diagnostic(
+                "Slowest tables",
+                "Tables whose queries took longest on average over the last day",
+                "SELECT arrayJoin(tables) AS table_name, count() AS queries,
+       round(avg(query_duration_ms)) AS avg_ms, max(query_duration_ms) AS max_ms
+FROM system.query_log
+WHERE type = 'QueryFinish' AND event_time > now() - INTERVAL 1 DAY
+GROUP BY table_name
+ORDER BY avg_ms DESC
+LIMIT 50;",
+            ),
+        ],
+        DatabaseType::DuckDB | DatabaseType::BigQuery | DatabaseType::Odbc => vec![],
+    }
+}
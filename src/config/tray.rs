@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// System tray presence. Both are off unless turned on from the Window menu.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraySettings {
+    pub show_tray_icon: bool,
+    /// Hide the window instead of minimizing it while the tray icon is shown
+    pub minimize_to_tray: bool,
+}
+
+pub struct TrayStore {
+    config_path: PathBuf,
+}
+
+impl TrayStore {
+    pub fn new() -> Self {
+        let config_dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+            .map(|d| d.config_dir().to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        fs::create_dir_all(&config_dir).ok();
+
+        Self {
+            config_path: config_dir.join("tray.json"),
+        }
+    }
+
+    pub fn load(&self) -> TraySettings {
+        fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, settings: &TraySettings) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+        fs::write(&self.config_path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for TrayStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
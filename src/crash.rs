@@ -0,0 +1,240 @@
+//! Crash reports and the diagnostics bundle. The latest log lines are kept in memory so a
+//! report shows what led up to a panic; everything written out has secrets redacted.
+
+use regex::Regex;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// Log lines kept for crash reports and the diagnostics bundle
+const MAX_LOG_LINES: usize = 500;
+/// Crash reports kept on disk; older ones are removed when a new one is written
+const MAX_CRASH_REPORTS: usize = 20;
+
+static RECENT_LOG: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Passwords in URLs, `password=...` style settings, bearer tokens and API keys
+static SECRET_PATTERNS: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    [
+        (r"(?i)([a-z][a-z0-9+.-]*://[^:/@\s]+):[^@\s]+@", "$1:***@"),
+        (
+            r#"(?i)\b(password|passwd|pwd|secret|token|api[_-]?key)("?\s*[=:]\s*)(["']?)[^\s"',;&]+"#,
+            "$1$2$3***",
+        ),
+        (r"(?i)\b(bearer\s+)[a-z0-9._~+/=-]+", "$1***"),
+        (r"\bsk-[A-Za-z0-9_-]{10,}", "sk-***"),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(pattern).unwrap(), replacement))
+    .collect()
+});
+
+/// Mask anything in `text` that looks like a password, token or key.
+pub fn redact(text: &str) -> String {
+    SECRET_PATTERNS
+        .iter()
+        .fold(text.to_string(), |text, (pattern, replacement)| {
+            pattern.replace_all(&text, *replacement).into_owned()
+        })
+}
+
+/// Writes each log event to stderr and keeps its lines in memory.
+struct RecentLog;
+
+struct LogEvent(Vec<u8>);
+
+impl Write for LogEvent {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LogEvent {
+    fn drop(&mut self) {
+        let _ = std::io::stderr().write_all(&self.0);
+        let text = String::from_utf8_lossy(&self.0);
+        // A panic while the log is locked must not take the crash report down with it
+        if let Ok(mut log) = RECENT_LOG.try_lock() {
+            for line in text.lines() {
+                if log.len() == MAX_LOG_LINES {
+                    log.pop_front();
+                }
+                log.push_back(line.to_string());
+            }
+        }
+    }
+}
+
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for RecentLog {
+    type Writer = LogEvent;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogEvent(Vec::new())
+    }
+}
+
+/// Log to stderr while keeping the latest lines for crash reports. Runs before launch,
+/// so the default logger finds a subscriber already set.
+pub fn init_logging() {
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_ansi(false)
+        .with_writer(RecentLog)
+        .try_init();
+}
+
+/// Write a crash report for every panic, then run the default hook.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let backtrace = std::backtrace::Backtrace::force_capture();
+        let report = format!(
+            "FBench crash report\n\n{}\nPanic:\n{}\n\nBacktrace:\n{}\n\nState:\n{}\nRecent log:\n{}\n",
+            environment_summary(),
+            info,
+            backtrace,
+            state_summary(),
+            recent_log(),
+        );
+        match write_crash_report(&redact(&report)) {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {}", e),
+        }
+        default_hook(info);
+    }));
+}
+
+fn crash_dir() -> PathBuf {
+    directories::ProjectDirs::from("com", "fbench", "fbench")
+        .map(|d| d.data_local_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("crashes")
+}
+
+fn write_crash_report(report: &str) -> Result<PathBuf, String> {
+    let dir = crash_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!(
+        "crash-{}.txt",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::write(&path, report).map_err(|e| e.to_string())?;
+
+    let mut reports = crash_reports();
+    if reports.len() > MAX_CRASH_REPORTS {
+        reports.truncate(reports.len() - MAX_CRASH_REPORTS);
+        for old in reports {
+            let _ = std::fs::remove_file(old);
+        }
+    }
+    Ok(path)
+}
+
+/// Crash reports on disk, oldest first
+fn crash_reports() -> Vec<PathBuf> {
+    let mut reports: Vec<PathBuf> = std::fs::read_dir(crash_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|e| e == "txt"))
+                .collect()
+        })
+        .unwrap_or_default();
+    // The timestamp in the name sorts them by time
+    reports.sort();
+    reports
+}
+
+fn environment_summary() -> String {
+    format!(
+        "Version: {}\nTime: {}\nOS: {} {}\n",
+        env!("CARGO_PKG_VERSION"),
+        chrono::Local::now().to_rfc3339(),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    )
+}
+
+/// Saved connections and settings, read from the config files so it is safe to call
+/// from the panic hook. Hosts, users and passwords are left out.
+fn state_summary() -> String {
+    use crate::config::{ConnectionStore, DraftStore, QuerySettingsStore};
+
+    let mut summary = String::new();
+    let connections = ConnectionStore::new().load_connections();
+    summary.push_str(&format!("Saved connections: {}\n", connections.len()));
+    for conn in connections {
+        summary.push_str(&format!(
+            "  {} ({:?}{}{}{})\n",
+            conn.name,
+            conn.db_type,
+            conn.environment
+                .map(|e| format!(", {}", e.label()))
+                .unwrap_or_default(),
+            if conn.ssh_tunnel.is_some() {
+                ", SSH tunnel"
+            } else {
+                ""
+            },
+            if conn.save_password {
+                ", saved password"
+            } else {
+                ""
+            },
+        ));
+    }
+    let settings = QuerySettingsStore::new().load();
+    summary.push_str(&format!(
+        "Statement timeout: {:?} s\nAuto limit: {:?}\n",
+        settings.statement_timeout_secs, settings.auto_limit
+    ));
+    let tabs = DraftStore::new().load_tabs().map_or(0, |d| d.tabs.len());
+    summary.push_str(&format!("Editor tabs: {}\n", tabs));
+    summary
+}
+
+fn recent_log() -> String {
+    RECENT_LOG
+        .try_lock()
+        .map(|log| log.iter().cloned().collect::<Vec<_>>().join("\n"))
+        .unwrap_or_else(|_| "(log unavailable)".to_string())
+}
+
+/// Zip a summary of the app, the recent log and the crash reports into `path`, for
+/// attaching to a bug report.
+pub fn write_diagnostics_bundle(path: &Path) -> Result<(), String> {
+    use zip::write::SimpleFileOptions;
+
+    let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut files = vec![
+        (
+            "summary.txt".to_string(),
+            format!("{}\n{}", environment_summary(), state_summary()),
+        ),
+        ("log.txt".to_string(), recent_log()),
+    ];
+    for report in crash_reports() {
+        let (Some(name), Ok(content)) = (report.file_name(), std::fs::read_to_string(&report))
+        else {
+            continue;
+        };
+        files.push((format!("crashes/{}", name.to_string_lossy()), content));
+    }
+
+    for (name, content) in files {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(redact(&content).as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use super::QueryResult;
+use serde::{Deserialize, Serialize};
+
+/// What happens to a column's values when a table is copied to another connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AnonymizeRule {
+    #[default]
+    Keep,
+    /// Salted hash, so equal values stay equal and joins on them still work
+    Hash,
+    /// Plausible stand-in picked from the column name (email, name, phone, ...)
+    Fake,
+    Nullify,
+}
+
+impl AnonymizeRule {
+    pub const ALL: [AnonymizeRule; 4] = [
+        AnonymizeRule::Keep,
+        AnonymizeRule::Hash,
+        AnonymizeRule::Fake,
+        AnonymizeRule::Nullify,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AnonymizeRule::Keep => "Keep",
+            AnonymizeRule::Hash => "Hash",
+            AnonymizeRule::Fake => "Fake",
+            AnonymizeRule::Nullify => "Nullify",
+        }
+    }
+
+    /// Starting rule for a column, from names that usually hold personal data.
+    pub fn suggest(column: &str) -> Self {
+        let lower = column.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| lower.contains(w));
+        if has(&["password", "secret", "token", "api_key", "salt"]) {
+            AnonymizeRule::Nullify
+        } else if has(&["ssn", "tax_id", "iban", "card", "passport"]) {
+            AnonymizeRule::Hash
+        } else if has(&[
+            "email", "name", "phone", "mobile", "address", "street", "city", "zip", "postal",
+            "birth", "ip_addr",
+        ]) {
+            AnonymizeRule::Fake
+        } else {
+            AnonymizeRule::Keep
+        }
+    }
+}
+
+/// A table to copy and the rules of its columns; columns not listed are kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyTable {
+    pub table: String,
+    pub rules: Vec<(String, AnonymizeRule)>,
+}
+
+/// Options of a copy to another connection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopyOptions {
+    /// Rows copied per table; None copies all of them
+    pub row_limit: Option<usize>,
+    /// Delete the target tables' rows first, children before parents
+    pub replace_existing: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnTransform {
+    pub column: String,
+    pub rule: AnonymizeRule,
+    /// Values the rule changed; NULLs are left alone by hashing and faking
+    pub changed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableCopySummary {
+    pub table: String,
+    pub rows: usize,
+    pub transforms: Vec<ColumnTransform>,
+}
+
+/// Outcome of a copy or of its dry run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopySummary {
+    pub tables: Vec<TableCopySummary>,
+    /// False for a dry run, which reads and transforms the rows without writing them
+    pub copied: bool,
+}
+
+const FIRST_NAMES: [&str; 16] = [
+    "Alex", "Sam", "Jordan", "Taylor", "Morgan", "Casey", "Riley", "Jamie", "Robin", "Avery",
+    "Quinn", "Drew", "Kai", "Noor", "Sasha", "Eli",
+];
+const LAST_NAMES: [&str; 16] = [
+    "Smith", "Garcia", "Chen", "Müller", "Okafor", "Silva", "Novak", "Kim", "Haddad", "Larsen",
+    "Rossi", "Tanaka", "Kowalski", "Dubois", "Singh", "Murphy",
+];
+const CITIES: [&str; 8] = [
+    "Springfield",
+    "Riverton",
+    "Fairview",
+    "Lakewood",
+    "Greenville",
+    "Milton",
+    "Ashford",
+    "Kingsport",
+];
+
+/// Applies anonymization rules with one salt, so a value maps to the same replacement
+/// in every table of a copy and to a different one in the next copy.
+pub struct Anonymizer {
+    salt: u64,
+}
+
+impl Anonymizer {
+    pub fn new(salt: u64) -> Self {
+        Self { salt }
+    }
+
+    /// Replace the values of the ruled columns in place and report what changed.
+    pub fn apply(
+        &self,
+        result: &mut QueryResult,
+        rules: &[(String, AnonymizeRule)],
+    ) -> Vec<ColumnTransform> {
+        let mut transforms = Vec::new();
+        for (column, rule) in rules {
+            if *rule == AnonymizeRule::Keep {
+                continue;
+            }
+            let Some(c) = result.columns.iter().position(|name| name == column) else {
+                continue;
+            };
+            let mut changed = 0;
+            for r in 0..result.rows.len() {
+                if result.null_cells.contains(&(r, c)) {
+                    continue;
+                }
+                if *rule == AnonymizeRule::Nullify {
+                    result.null_cells.insert((r, c));
+                    result.rows[r][c] = "NULL".to_string();
+                    changed += 1;
+                    continue;
+                }
+                let value = &result.rows[r][c];
+                let replacement = match rule {
+                    AnonymizeRule::Hash => self.hash_value(value),
+                    _ => self.fake_value(column, value),
+                };
+                if replacement != *value {
+                    result.rows[r][c] = replacement;
+                    changed += 1;
+                }
+            }
+            transforms.push(ColumnTransform {
+                column: column.clone(),
+                rule: *rule,
+                changed,
+            });
+        }
+        transforms
+    }
+
+    fn hash(&self, value: &str) -> u64 {
+        // FNV-1a over the salt and the value
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in self.salt.to_le_bytes().iter().chain(value.as_bytes()) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        hash
+    }
+
+    /// Numbers hash to numbers of the same length so numeric columns still accept them.
+    fn hash_value(&self, value: &str) -> String {
+        let hash = self.hash(value);
+        if !value.is_empty() && value.len() < 19 && value.bytes().all(|b| b.is_ascii_digit()) {
+            let modulus = 10u64.pow(value.len() as u32);
+            format!("{:0width$}", hash % modulus, width = value.len())
+        } else {
+            format!("{:016x}", hash)
+        }
+    }
+
+    fn fake_value(&self, column: &str, value: &str) -> String {
+        let hash = self.hash(value);
+        let pick =
+            |list: &[&'static str], shift: u32| list[((hash >> shift) as usize) % list.len()];
+        let lower = column.to_lowercase();
+        if lower.contains("email") || value.contains('@') {
+            format!("user{}@example.com", hash % 1_000_000)
+        } else if lower.contains("first") {
+            pick(&FIRST_NAMES, 0).to_string()
+        } else if lower.contains("last") || lower.contains("surname") {
+            pick(&LAST_NAMES, 8).to_string()
+        } else if lower.contains("name") {
+            format!("{} {}", pick(&FIRST_NAMES, 0), pick(&LAST_NAMES, 8))
+        } else if lower.contains("phone") || lower.contains("mobile") {
+            format!("555-{:04}", hash % 10_000)
+        } else if lower.contains("city") {
+            pick(&CITIES, 16).to_string()
+        } else if lower.contains("address") || lower.contains("street") {
+            format!("{} Main Street", hash % 9_999 + 1)
+        } else if lower == "ip" || lower.contains("ip_addr") {
+            format!(
+                "10.{}.{}.{}",
+                (hash >> 16) % 256,
+                (hash >> 8) % 256,
+                hash % 256
+            )
+        } else if is_date_like(value) {
+            // Keeps any time part, so the value still parses as a date or timestamp
+            format!(
+                "{:04}-{:02}-{:02}{}",
+                1950 + hash % 50,
+                (hash >> 8) % 12 + 1,
+                (hash >> 16) % 28 + 1,
+                &value[10..]
+            )
+        } else {
+            // Postal codes and anything else keep their shape: digits and letters
+            // are swapped for others of the same kind
+            let mut state = hash;
+            value
+                .chars()
+                .map(|ch| {
+                    state = state.rotate_left(5) ^ 0x9e37_79b9_7f4a_7c15;
+                    if ch.is_ascii_digit() {
+                        char::from(b'0' + (state % 10) as u8)
+                    } else if ch.is_ascii_lowercase() {
+                        char::from(b'a' + (state % 26) as u8)
+                    } else if ch.is_ascii_uppercase() {
+                        char::from(b'A' + (state % 26) as u8)
+                    } else {
+                        ch
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+/// Starts with `YYYY-MM-DD`.
+fn is_date_like(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() >= 10
+        && bytes[..10].iter().enumerate().all(|(i, b)| {
+            if i == 4 || i == 7 {
+                *b == b'-'
+            } else {
+                b.is_ascii_digit()
+            }
+        })
+}
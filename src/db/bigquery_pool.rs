@@ -0,0 +1,649 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+use super::connection::{format_float, truncate_value};
+use super::{
+    modifies_data, split_statements, ColumnInfo, ConnectionConfig, IndexInfo, SchemaInfo, TableInfo,
+};
+
+/// Columns, column types, formatted rows (NULL cells as None) and bytes processed of a query.
+pub(super) type BigQueryRows = (
+    Vec<String>,
+    Vec<String>,
+    Vec<Vec<Option<String>>>,
+    Option<u64>,
+);
+
+const API_URL: &str = "https://bigquery.googleapis.com/bigquery/v2";
+const TOKEN_SCOPE: &str = "https://www.googleapis.com/auth/bigquery";
+/// `urn:ietf:params:oauth:grant-type:jwt-bearer`, form-encoded
+const JWT_BEARER_GRANT: &str = "urn%3Aietf%3Aparams%3Aoauth%3Agrant-type%3Ajwt-bearer";
+/// Lifetime requested for access tokens; they are renewed a minute before expiring
+const TOKEN_LIFETIME_SECS: u64 = 3600;
+/// How long each request waits on a running job before polling again
+const JOB_WAIT_MS: u64 = 10_000;
+/// Rows fetched per results page
+const PAGE_ROWS: u32 = 10_000;
+
+pub(super) const READ_ONLY_ERROR: &str = "BigQuery connections are read-only";
+
+/// The parts of a service account JSON key used to sign token requests.
+#[derive(Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    #[serde(default)]
+    project_id: String,
+    #[serde(default = "default_token_uri")]
+    token_uri: String,
+}
+
+fn default_token_uri() -> String {
+    "https://oauth2.googleapis.com/token".to_string()
+}
+
+#[derive(Serialize)]
+struct TokenClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase", default)]
+struct JobReference {
+    job_id: String,
+    location: Option<String>,
+}
+
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+struct Field {
+    name: String,
+    #[serde(rename = "type")]
+    field_type: String,
+    mode: String,
+    fields: Vec<Field>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TableSchema {
+    fields: Vec<Field>,
+}
+
+#[derive(Deserialize)]
+struct Row {
+    f: Vec<Cell>,
+}
+
+#[derive(Deserialize)]
+struct Cell {
+    v: Value,
+}
+
+/// Body of `jobs.query` and `jobs.getQueryResults`.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct QueryResponse {
+    job_complete: bool,
+    job_reference: JobReference,
+    schema: Option<TableSchema>,
+    rows: Vec<Row>,
+    page_token: Option<String>,
+    total_bytes_processed: Option<String>,
+}
+
+/// A Google BigQuery project, authenticated with a service account key. Queries run as
+/// jobs through the REST API; the connection only reads.
+#[derive(Clone)]
+pub(super) struct BigQueryPool {
+    client: reqwest::Client,
+    key: Arc<ServiceAccountKey>,
+    project: String,
+    /// Default dataset for unqualified table names
+    dataset: Option<String>,
+    /// Access token and when it expires
+    token: Arc<Mutex<Option<(String, Instant)>>>,
+}
+
+impl BigQueryPool {
+    /// `config.user` holds the path of the key file, `database` the project (the key's own
+    /// project when empty) and `schema` the default dataset.
+    pub(super) async fn open(config: &ConnectionConfig) -> Result<Self, String> {
+        let key_json = tokio::fs::read_to_string(config.user.trim())
+            .await
+            .map_err(|e| format!("Could not read the service account key: {}", e))?;
+        let key: ServiceAccountKey = serde_json::from_str(&key_json)
+            .map_err(|e| format!("Invalid service account key: {}", e))?;
+        let project = match config.database.trim() {
+            "" => key.project_id.clone(),
+            project => project.to_string(),
+        };
+        if project.is_empty() {
+            return Err("Project ID is required".to_string());
+        }
+        let dataset = Some(config.schema.trim().to_string()).filter(|d| !d.is_empty());
+
+        let pool = Self {
+            client: reqwest::Client::new(),
+            key: Arc::new(key),
+            project,
+            dataset,
+            token: Arc::default(),
+        };
+        pool.ping().await?;
+        Ok(pool)
+    }
+
+    /// Cheapest authenticated call, used to check the project and credentials.
+    pub(super) async fn ping(&self) -> Result<(), String> {
+        self.get(
+            &format!("/projects/{}/datasets", self.project),
+            &[("maxResults", "1")],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn access_token(&self) -> Result<String, String> {
+        let mut token = self.token.lock().await;
+        if let Some((access_token, expires)) = token.as_ref() {
+            if *expires > Instant::now() + Duration::from_secs(60) {
+                return Ok(access_token.clone());
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_secs();
+        let claims = TokenClaims {
+            iss: &self.key.client_email,
+            scope: TOKEN_SCOPE,
+            aud: &self.key.token_uri,
+            iat: now,
+            exp: now + TOKEN_LIFETIME_SECS,
+        };
+        let signing_key = jsonwebtoken::EncodingKey::from_rsa_pem(self.key.private_key.as_bytes())
+            .map_err(|e| format!("Invalid service account private key: {}", e))?;
+        let assertion = jsonwebtoken::encode(
+            &jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256),
+            &claims,
+            &signing_key,
+        )
+        .map_err(|e| e.to_string())?;
+
+        // The signed JWT only contains URL-safe characters
+        let response = self
+            .client
+            .post(&self.key.token_uri)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(format!(
+                "grant_type={}&assertion={}",
+                JWT_BEARER_GRANT, assertion
+            ))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!(
+                "Authentication failed ({}): {}",
+                status,
+                body.trim()
+            ));
+        }
+        let response: TokenResponse = response.json().await.map_err(|e| e.to_string())?;
+        let expires = Instant::now() + Duration::from_secs(response.expires_in);
+        *token = Some((response.access_token.clone(), expires));
+        Ok(response.access_token)
+    }
+
+    async fn get(&self, path: &str, params: &[(&str, &str)]) -> Result<Value, String> {
+        let mut url =
+            reqwest::Url::parse(&format!("{}{}", API_URL, path)).map_err(|e| e.to_string())?;
+        url.query_pairs_mut().extend_pairs(params);
+        let request = self.client.get(url).bearer_auth(self.access_token().await?);
+        read_response(request.send().await.map_err(|e| e.to_string())?).await
+    }
+
+    async fn post(&self, path: &str, body: &Value) -> Result<Value, String> {
+        let request = self
+            .client
+            .post(format!("{}{}", API_URL, path))
+            .bearer_auth(self.access_token().await?)
+            .json(body);
+        read_response(request.send().await.map_err(|e| e.to_string())?).await
+    }
+
+    /// Run a SELECT as a query job, wait for it to finish and fetch every page of results.
+    pub(super) async fn query(&self, sql: &str) -> Result<BigQueryRows, String> {
+        if !is_read_query(sql) {
+            return Err(format!("{}; only SELECT queries can run", READ_ONLY_ERROR));
+        }
+        let mut body = json!({
+            "query": sql.trim().trim_end_matches(';'),
+            "useLegacySql": false,
+            "timeoutMs": JOB_WAIT_MS,
+            "maxResults": PAGE_ROWS,
+        });
+        if let Some(dataset) = &self.dataset {
+            body["defaultDataset"] = json!({ "projectId": self.project, "datasetId": dataset });
+        }
+        let mut page: QueryResponse = parse(
+            self.post(&format!("/projects/{}/queries", self.project), &body)
+                .await?,
+        )?;
+        let job = page.job_reference.clone();
+        while !page.job_complete {
+            page = self.results(&job, None).await?;
+        }
+
+        let fields = page.schema.take().unwrap_or_default().fields;
+        let bytes_processed = page
+            .total_bytes_processed
+            .as_deref()
+            .and_then(|b| b.parse().ok());
+        let mut rows = format_rows(std::mem::take(&mut page.rows), &fields);
+        while let Some(token) = page.page_token.take() {
+            page = self.results(&job, Some(&token)).await?;
+            rows.extend(format_rows(std::mem::take(&mut page.rows), &fields));
+        }
+
+        let (columns, types) = fields
+            .iter()
+            .map(|field| (field.name.clone(), type_name(field)))
+            .unzip();
+        Ok((columns, types, rows, bytes_processed))
+    }
+
+    async fn results(
+        &self,
+        job: &JobReference,
+        page_token: Option<&str>,
+    ) -> Result<QueryResponse, String> {
+        let wait = JOB_WAIT_MS.to_string();
+        let page_rows = PAGE_ROWS.to_string();
+        let mut params = vec![
+            ("timeoutMs", wait.as_str()),
+            ("maxResults", page_rows.as_str()),
+        ];
+        if let Some(location) = &job.location {
+            params.push(("location", location.as_str()));
+        }
+        if let Some(page_token) = page_token {
+            params.push(("pageToken", page_token));
+        }
+        parse(
+            self.get(
+                &format!("/projects/{}/queries/{}", self.project, job.job_id),
+                &params,
+            )
+            .await?,
+        )
+    }
+
+    /// Bytes the query would process, from a dry run that costs nothing.
+    pub(super) async fn dry_run(&self, sql: &str) -> Result<u64, String> {
+        let mut query = json!({
+            "query": sql.trim().trim_end_matches(';'),
+            "useLegacySql": false,
+        });
+        if let Some(dataset) = &self.dataset {
+            query["defaultDataset"] = json!({ "projectId": self.project, "datasetId": dataset });
+        }
+        let job = self
+            .post(
+                &format!("/projects/{}/jobs", self.project),
+                &json!({ "configuration": { "query": query, "dryRun": true } }),
+            )
+            .await?;
+        job["statistics"]["totalBytesProcessed"]
+            .as_str()
+            .and_then(|b| b.parse().ok())
+            .ok_or_else(|| "The dry run reported no statistics".to_string())
+    }
+
+    /// Tables and views of the default dataset, or of every dataset in the project with
+    /// `dataset.table` names.
+    pub(super) async fn schema(&self) -> Result<SchemaInfo, String> {
+        let datasets = match &self.dataset {
+            Some(dataset) => vec![dataset.clone()],
+            None => self
+                .list(&format!("/projects/{}/datasets", self.project), "datasets")
+                .await?
+                .iter()
+                .filter_map(|d| d["datasetReference"]["datasetId"].as_str())
+                .map(str::to_string)
+                .collect(),
+        };
+
+        let mut tables = Vec::new();
+        let mut views = Vec::new();
+        for dataset in datasets {
+            let entries = self
+                .list(
+                    &format!("/projects/{}/datasets/{}/tables", self.project, dataset),
+                    "tables",
+                )
+                .await?;
+            for entry in entries {
+                let Some(table) = entry["tableReference"]["tableId"].as_str() else {
+                    continue;
+                };
+                let name = match &self.dataset {
+                    Some(_) => table.to_string(),
+                    None => format!("{}.{}", dataset, table),
+                };
+                match entry["type"].as_str() {
+                    Some("VIEW") | Some("MATERIALIZED_VIEW") => views.push(name),
+                    _ => tables.push(self.table_in(&dataset, table, name).await?),
+                }
+            }
+        }
+
+        Ok(SchemaInfo {
+            tables,
+            views,
+            sequences: Vec::new(),
+        })
+    }
+
+    /// Every item of a paged list endpoint.
+    async fn list(&self, path: &str, items_key: &str) -> Result<Vec<Value>, String> {
+        let mut items = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut params = vec![("maxResults", "1000")];
+            if let Some(token) = &page_token {
+                params.push(("pageToken", token.as_str()));
+            }
+            let mut page = self.get(path, &params).await?;
+            if let Value::Array(page_items) = page[items_key].take() {
+                items.extend(page_items);
+            }
+            match page["nextPageToken"].as_str() {
+                Some(token) => page_token = Some(token.to_string()),
+                None => return Ok(items),
+            }
+        }
+    }
+
+    /// `table` or `dataset.table`.
+    pub(super) async fn table_info(&self, table: &str) -> Result<TableInfo, String> {
+        let (dataset, name) = self.split_table(table)?;
+        self.table_in(&dataset, &name, table.to_string()).await
+    }
+
+    /// Columns (name, data type) of a table.
+    pub(super) async fn columns(&self, table: &str) -> Result<Vec<(String, String)>, String> {
+        Ok(self
+            .table_info(table)
+            .await?
+            .columns
+            .into_iter()
+            .map(|c| (c.name, c.data_type))
+            .collect())
+    }
+
+    fn split_table(&self, table: &str) -> Result<(String, String), String> {
+        let table = table.trim_matches('`');
+        match (table.rsplit_once('.'), &self.dataset) {
+            (Some((dataset, name)), _) => Ok((
+                // project.dataset.table keeps only the dataset
+                dataset.rsplit('.').next().unwrap_or(dataset).to_string(),
+                name.to_string(),
+            )),
+            (None, Some(dataset)) => Ok((dataset.clone(), table.to_string())),
+            (None, None) => Err(format!(
+                "Table {} needs a dataset, e.g. my_dataset.{}",
+                table, table
+            )),
+        }
+    }
+
+    async fn table_in(
+        &self,
+        dataset: &str,
+        table: &str,
+        name: String,
+    ) -> Result<TableInfo, String> {
+        let resource = self
+            .get(
+                &format!(
+                    "/projects/{}/datasets/{}/tables/{}",
+                    self.project, dataset, table
+                ),
+                &[],
+            )
+            .await?;
+        let fields: Vec<Field> =
+            serde_json::from_value(resource["schema"]["fields"].clone()).unwrap_or_default();
+        let key_columns = |value: &Value| -> Vec<String> {
+            value
+                .as_array()
+                .map(|columns| {
+                    columns
+                        .iter()
+                        .filter_map(|c| c.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        // Primary keys are declared but not enforced
+        let primary_key = key_columns(&resource["tableConstraints"]["primaryKey"]["columns"]);
+
+        let columns = fields
+            .iter()
+            .map(|field| ColumnInfo {
+                name: field.name.clone(),
+                data_type: type_name(field),
+                nullable: field.mode != "REQUIRED",
+                default_value: None,
+                is_primary_key: primary_key.contains(&field.name),
+                identity_generation: None,
+                generation_expression: None,
+                enum_values: Vec::new(),
+                collation: None,
+                comment: field.description.clone(),
+                column_type: None,
+                extra: None,
+            })
+            .collect();
+
+        // Partitioning and clustering are what prune scanned bytes, so they stand in for indexes
+        let mut indexes = Vec::new();
+        if !primary_key.is_empty() {
+            indexes.push(IndexInfo {
+                name: "PRIMARY KEY".to_string(),
+                columns: primary_key,
+                is_unique: false,
+                is_primary: true,
+                index_type: "NOT ENFORCED".to_string(),
+            });
+        }
+        let partitioning = &resource["timePartitioning"];
+        if partitioning.is_object() {
+            indexes.push(IndexInfo {
+                name: "PARTITION BY".to_string(),
+                // Ingestion-time partitioning has no column
+                columns: vec![partitioning["field"]
+                    .as_str()
+                    .unwrap_or("_PARTITIONTIME")
+                    .to_string()],
+                is_unique: false,
+                is_primary: false,
+                index_type: partitioning["type"].as_str().unwrap_or("DAY").to_string(),
+            });
+        } else if let Some(field) = resource["rangePartitioning"]["field"].as_str() {
+            indexes.push(IndexInfo {
+                name: "PARTITION BY".to_string(),
+                columns: vec![field.to_string()],
+                is_unique: false,
+                is_primary: false,
+                index_type: "RANGE".to_string(),
+            });
+        }
+        let clustering = key_columns(&resource["clustering"]["fields"]);
+        if !clustering.is_empty() {
+            indexes.push(IndexInfo {
+                name: "CLUSTER BY".to_string(),
+                columns: clustering,
+                is_unique: false,
+                is_primary: false,
+                index_type: "CLUSTER".to_string(),
+            });
+        }
+
+        Ok(TableInfo {
+            name,
+            columns,
+            indexes,
+            constraints: Vec::new(),
+            row_estimate: resource["numRows"]
+                .as_str()
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(0),
+            collation: None,
+            comment: resource["description"].as_str().map(str::to_string),
+        })
+    }
+}
+
+/// The JSON body of a successful response, or the API's error message.
+async fn read_response(response: reqwest::Response) -> Result<Value, String> {
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or(Value::Null);
+    if status.is_success() {
+        return Ok(body);
+    }
+    Err(body["error"]["message"]
+        .as_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Server returned {}", status)))
+}
+
+fn parse<T: serde::de::DeserializeOwned>(value: Value) -> Result<T, String> {
+    serde_json::from_value(value).map_err(|e| format!("Unexpected response: {}", e))
+}
+
+/// Only queries run on a read-only connection; scripts and DML are refused. A job
+/// runs every statement it is given, so `SELECT 1; DELETE ...` is a script too.
+fn is_read_query(sql: &str) -> bool {
+    let statements = split_statements(sql);
+    let [statement] = statements.as_slice() else {
+        return false;
+    };
+    let lower = statement
+        .trim_start()
+        .trim_start_matches('(')
+        .to_lowercase();
+    (lower.starts_with("select") || lower.starts_with("with")) && !modifies_data(statement)
+}
+
+/// Standard SQL name of a field's type, e.g. `ARRAY<STRUCT<id INT64, tags ARRAY<STRING>>>`.
+fn type_name(field: &Field) -> String {
+    let base = match field.field_type.as_str() {
+        "RECORD" | "STRUCT" => format!(
+            "STRUCT<{}>",
+            field
+                .fields
+                .iter()
+                .map(|f| format!("{} {}", f.name, type_name(f)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        "INTEGER" => "INT64".to_string(),
+        "FLOAT" => "FLOAT64".to_string(),
+        "BOOLEAN" => "BOOL".to_string(),
+        other => other.to_string(),
+    };
+    if field.mode == "REPEATED" {
+        format!("ARRAY<{}>", base)
+    } else {
+        base
+    }
+}
+
+fn format_rows(rows: Vec<Row>, fields: &[Field]) -> Vec<Vec<Option<String>>> {
+    rows.into_iter()
+        .map(|row| {
+            row.f
+                .iter()
+                .zip(fields)
+                .map(|(cell, field)| {
+                    let text = match cell_value(&cell.v, field, field.mode == "REPEATED") {
+                        Value::Null => return None,
+                        Value::String(s) => s,
+                        // Arrays and structs
+                        other => other.to_string(),
+                    };
+                    Some(truncate_value(text))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Every value arrives as a string; repeated fields as `[{"v": ..}]` and records as
+/// `{"f": [{"v": ..}]}`. Timestamps are seconds since the epoch.
+fn cell_value(value: &Value, field: &Field, repeated: bool) -> Value {
+    if value.is_null() {
+        return Value::Null;
+    }
+    if repeated {
+        return Value::Array(
+            value
+                .as_array()
+                .map(|items| {
+                    items
+                        .iter()
+                        .map(|item| cell_value(&item["v"], field, false))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        );
+    }
+    match field.field_type.as_str() {
+        "RECORD" | "STRUCT" => Value::Object(
+            field
+                .fields
+                .iter()
+                .zip(value["f"].as_array().into_iter().flatten())
+                .map(|(f, cell)| {
+                    (
+                        f.name.clone(),
+                        cell_value(&cell["v"], f, f.mode == "REPEATED"),
+                    )
+                })
+                .collect(),
+        ),
+        "TIMESTAMP" => value
+            .as_str()
+            .and_then(|s| s.parse::<f64>().ok())
+            .and_then(|secs| chrono::DateTime::from_timestamp_micros((secs * 1e6).round() as i64))
+            .map(|t| Value::String(t.format("%Y-%m-%d %H:%M:%S%.f UTC").to_string()))
+            .unwrap_or_else(|| value.clone()),
+        "FLOAT" | "FLOAT64" => value
+            .as_str()
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|n| n.is_finite())
+            .map(|n| Value::String(format_float(n)))
+            .unwrap_or_else(|| value.clone()),
+        _ => value.clone(),
+    }
+}
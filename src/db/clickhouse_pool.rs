@@ -0,0 +1,433 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::connection::{format_float, truncate_value, BatchReporter};
+use super::{ColumnInfo, ConnectionConfig, IndexInfo, SchemaInfo, TableInfo};
+
+/// Columns, column types and formatted rows of a query, with NULL cells as None.
+pub(super) type ClickHouseRows = (Vec<String>, Vec<String>, Vec<Vec<Option<String>>>);
+
+/// Seconds an idle server-side session (and its SET values) is kept
+const SESSION_TIMEOUT_SECS: u32 = 3600;
+
+/// A ClickHouse server reached over its HTTP interface. Requests share one server-side
+/// session, so SET and temporary tables carry over between queries.
+#[derive(Clone)]
+pub(super) struct ClickHousePool {
+    client: reqwest::Client,
+    url: reqwest::Url,
+    user: String,
+    password: String,
+    database: String,
+    session_id: String,
+}
+
+#[derive(Deserialize)]
+struct ColumnMeta {
+    name: String,
+    #[serde(rename = "type")]
+    data_type: String,
+}
+
+/// Body of a `FORMAT JSONCompact` response.
+#[derive(Deserialize)]
+struct CompactResult {
+    meta: Vec<ColumnMeta>,
+    data: Vec<Vec<Value>>,
+}
+
+impl ClickHousePool {
+    /// Connect to the HTTP interface; ports 443 and 8443 use HTTPS.
+    pub(super) async fn open(config: &ConnectionConfig) -> Result<Self, String> {
+        let scheme = if matches!(config.port, 443 | 8443) {
+            "https"
+        } else {
+            "http"
+        };
+        let url = reqwest::Url::parse(&format!("{}://{}:{}/", scheme, config.host, config.port))
+            .map_err(|e| e.to_string())?;
+        let pool = Self {
+            client: reqwest::Client::new(),
+            url,
+            user: config.user.clone(),
+            password: config.password.clone(),
+            database: config.database.clone(),
+            session_id: uuid::Uuid::new_v4().to_string(),
+        };
+        pool.send("SELECT 1").await?;
+        Ok(pool)
+    }
+
+    async fn send(&self, sql: &str) -> Result<reqwest::Response, String> {
+        let mut url = self.url.clone();
+        {
+            let mut params = url.query_pairs_mut();
+            params
+                .append_pair("default_format", "JSONCompact")
+                .append_pair("session_id", &self.session_id)
+                .append_pair("session_timeout", &SESSION_TIMEOUT_SECS.to_string());
+            if !self.database.is_empty() {
+                params.append_pair("database", &self.database);
+            }
+        }
+        let mut request = self.client.post(url).body(sql.to_string());
+        if !self.user.is_empty() {
+            request = request
+                .header("X-ClickHouse-User", &self.user)
+                .header("X-ClickHouse-Key", &self.password);
+        }
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            return Err(if message.trim().is_empty() {
+                format!("Server returned {}", status)
+            } else {
+                message.trim().to_string()
+            });
+        }
+        Ok(response)
+    }
+
+    /// Raw result of a query; None for statements without a result set.
+    async fn fetch(&self, sql: &str) -> Result<Option<CompactResult>, String> {
+        let body = self
+            .send(sql)
+            .await?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+        if body.trim().is_empty() {
+            return Ok(None);
+        }
+        serde_json::from_str(&body)
+            .map(Some)
+            .map_err(|e| format!("Unexpected response: {}", e))
+    }
+
+    pub(super) async fn query(&self, sql: &str) -> Result<ClickHouseRows, String> {
+        let Some(result) = self.fetch(sql.trim().trim_end_matches(';')).await? else {
+            return Ok((Vec::new(), Vec::new(), Vec::new()));
+        };
+        let rows = result
+            .data
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .zip(&result.meta)
+                    .map(|(value, column)| match value {
+                        Value::Null => None,
+                        value => Some(format_clickhouse_value(value, &column.data_type)),
+                    })
+                    .collect()
+            })
+            .collect();
+        let (columns, types) = result
+            .meta
+            .into_iter()
+            .map(|column| (column.name, column.data_type))
+            .unzip();
+        Ok((columns, types, rows))
+    }
+
+    /// Run a statement, returning the rows it wrote.
+    pub(super) async fn execute(&self, sql: &str) -> Result<u64, String> {
+        let response = self.send(sql.trim().trim_end_matches(';')).await?;
+        // Progress summary, e.g. {"written_rows":"3",...}
+        let written = response
+            .headers()
+            .get("X-ClickHouse-Summary")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| serde_json::from_str::<Value>(v).ok())
+            .and_then(|summary| match &summary["written_rows"] {
+                Value::String(n) => n.parse().ok(),
+                n => n.as_u64(),
+            })
+            .unwrap_or(0);
+        Ok(written)
+    }
+
+    /// Run statements in order. ClickHouse has no transactions, so statements that ran
+    /// before a failing or cancelled one stay applied.
+    pub(super) async fn batch(
+        &self,
+        statements: &[String],
+        reporter: &BatchReporter,
+    ) -> Result<u64, (&'static str, String)> {
+        let mut total_affected = 0;
+        for (i, sql) in statements.iter().enumerate() {
+            reporter.start(i, sql).map_err(|e| ("Batch cancelled", e))?;
+            total_affected += self.execute(sql).await.map_err(|e| ("Batch failed", e))?;
+        }
+        Ok(total_affected)
+    }
+
+    /// Tables of `database` (the connection's database when None) with their columns
+    /// and indexes, plus its views.
+    pub(super) async fn schema(&self, database: Option<String>) -> Result<SchemaInfo, String> {
+        let database = database_expr(database.as_deref());
+        let names = self
+            .strings(&format!(
+                "SELECT name, toString(ifNull(total_rows, 0)) FROM system.tables \
+                 WHERE database = {} AND NOT is_temporary \
+                   AND engine NOT IN ('View', 'MaterializedView', 'LiveView', 'WindowView') \
+                 ORDER BY name",
+                database
+            ))
+            .await?;
+
+        let mut tables = Vec::with_capacity(names.len());
+        for row in names {
+            let mut table = self.table_info_in(&row[0], &database).await?;
+            table.row_estimate = row[1].parse().unwrap_or(0);
+            tables.push(table);
+        }
+
+        let views = self
+            .strings(&format!(
+                "SELECT name FROM system.tables \
+                 WHERE database = {} AND engine IN ('View', 'MaterializedView') \
+                 ORDER BY name",
+                database
+            ))
+            .await?
+            .into_iter()
+            .filter_map(|row| row.into_iter().next())
+            .collect();
+
+        Ok(SchemaInfo {
+            tables,
+            views,
+            sequences: Vec::new(),
+        })
+    }
+
+    /// (name, value, default, description) of every setting.
+    pub(super) async fn settings(&self) -> Result<Vec<Vec<String>>, String> {
+        self.strings("SELECT name, value, default, description FROM system.settings ORDER BY name")
+            .await
+    }
+
+    /// Columns (name, data type) of a table; empty when it does not exist.
+    pub(super) async fn columns(
+        &self,
+        table: &str,
+        database: Option<String>,
+    ) -> Result<Vec<(String, String)>, String> {
+        Ok(self
+            .strings(&format!(
+                "SELECT name, type FROM system.columns \
+                 WHERE database = {} AND table = {} ORDER BY position",
+                database_expr(database.as_deref()),
+                quote_literal(table)
+            ))
+            .await?
+            .into_iter()
+            .map(|row| (row[0].clone(), row[1].clone()))
+            .collect())
+    }
+
+    pub(super) async fn table_info(
+        &self,
+        table: &str,
+        database: Option<String>,
+    ) -> Result<TableInfo, String> {
+        self.table_info_in(table, &database_expr(database.as_deref()))
+            .await
+    }
+
+    async fn table_info_in(&self, table: &str, database: &str) -> Result<TableInfo, String> {
+        let table_literal = quote_literal(table);
+        let columns: Vec<ColumnInfo> = self
+            .strings(&format!(
+                "SELECT name, type, default_kind, default_expression, \
+                        toString(is_in_primary_key), comment \
+                 FROM system.columns WHERE database = {} AND table = {} ORDER BY position",
+                database, table_literal
+            ))
+            .await?
+            .into_iter()
+            .map(|row| {
+                let [name, data_type, default_kind, expression, is_primary_key, comment] =
+                    <[String; 6]>::try_from(row).unwrap_or_default();
+                // MATERIALIZED and ALIAS columns are computed rather than inserted
+                let (default_value, generation_expression) = match default_kind.as_str() {
+                    "" => (None, None),
+                    "DEFAULT" => (Some(expression), None),
+                    _ => (None, Some(expression)),
+                };
+                ColumnInfo {
+                    nullable: data_type.starts_with("Nullable("),
+                    default_value,
+                    is_primary_key: is_primary_key == "1",
+                    identity_generation: None,
+                    generation_expression,
+                    enum_values: enum_values(&data_type),
+                    collation: None,
+                    comment: (!comment.is_empty()).then_some(comment),
+                    column_type: None,
+                    extra: None,
+                    name,
+                    data_type,
+                }
+            })
+            .collect();
+
+        let keys = self
+            .strings(&format!(
+                "SELECT engine, primary_key, sorting_key, comment FROM system.tables \
+                 WHERE database = {} AND name = {}",
+                database, table_literal
+            ))
+            .await?;
+        let mut indexes = Vec::new();
+        let mut comment = None;
+        if let Some([engine, primary_key, sorting_key, table_comment]) = keys
+            .into_iter()
+            .next()
+            .and_then(|row| <[String; 4]>::try_from(row).ok())
+        {
+            comment = (!table_comment.is_empty()).then_some(table_comment);
+            if !primary_key.is_empty() {
+                indexes.push(IndexInfo {
+                    name: "PRIMARY KEY".to_string(),
+                    columns: split_key(&primary_key),
+                    is_unique: false,
+                    is_primary: true,
+                    index_type: engine.clone(),
+                });
+            }
+            if !sorting_key.is_empty() && sorting_key != primary_key {
+                indexes.push(IndexInfo {
+                    name: "ORDER BY".to_string(),
+                    columns: split_key(&sorting_key),
+                    is_unique: false,
+                    is_primary: false,
+                    index_type: engine,
+                });
+            }
+        }
+        let skipping_indexes = self
+            .strings(&format!(
+                "SELECT name, expr, type FROM system.data_skipping_indices \
+                 WHERE database = {} AND table = {} ORDER BY name",
+                database, table_literal
+            ))
+            .await?;
+        indexes.extend(skipping_indexes.into_iter().filter_map(|row| {
+            let [name, expr, index_type] = <[String; 3]>::try_from(row).ok()?;
+            Some(IndexInfo {
+                name,
+                columns: split_key(&expr),
+                is_unique: false,
+                is_primary: false,
+                index_type,
+            })
+        }));
+
+        Ok(TableInfo {
+            name: table.to_string(),
+            columns,
+            indexes,
+            // Primary keys don't enforce uniqueness and there are no foreign keys
+            constraints: Vec::new(),
+            row_estimate: 0,
+            collation: None,
+            comment,
+        })
+    }
+
+    /// Rows of a catalog query with every value as text.
+    async fn strings(&self, sql: &str) -> Result<Vec<Vec<String>>, String> {
+        Ok(self
+            .fetch(sql)
+            .await?
+            .map(|result| {
+                result
+                    .data
+                    .into_iter()
+                    .map(|row| {
+                        row.into_iter()
+                            .map(|value| match value {
+                                Value::String(s) => s,
+                                Value::Null => String::new(),
+                                other => other.to_string(),
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+/// The given database as a literal, or the session's current database.
+fn database_expr(database: Option<&str>) -> String {
+    match database {
+        Some(database) => quote_literal(database),
+        None => "currentDatabase()".to_string(),
+    }
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Columns of a key expression such as `(id, toDate(created_at))`.
+fn split_key(key: &str) -> Vec<String> {
+    key.trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// Labels of an `Enum8('a' = 1, 'b' = 2)` type, wrapped or not.
+fn enum_values(data_type: &str) -> Vec<String> {
+    let inner = base_type(data_type);
+    if !inner.starts_with("Enum") {
+        return Vec::new();
+    }
+    inner
+        .split('\'')
+        .skip(1)
+        .step_by(2)
+        .map(|label| label.to_string())
+        .collect()
+}
+
+/// Type without its `Nullable(..)` and `LowCardinality(..)` wrappers.
+fn base_type(data_type: &str) -> &str {
+    let mut data_type = data_type;
+    for wrapper in ["LowCardinality(", "Nullable("] {
+        if let Some(inner) = data_type
+            .strip_prefix(wrapper)
+            .and_then(|t| t.strip_suffix(')'))
+        {
+            data_type = inner;
+        }
+    }
+    data_type
+}
+
+fn format_clickhouse_value(value: Value, data_type: &str) -> String {
+    let data_type = base_type(data_type);
+    let text = match value {
+        Value::Null => return "NULL".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) if data_type.starts_with("Float") => n
+            .as_f64()
+            .map(format_float)
+            .unwrap_or_else(|| n.to_string()),
+        Value::Number(n) => n.to_string(),
+        // FixedString values are padded with zero bytes
+        Value::String(s) if data_type.starts_with("FixedString") => {
+            s.trim_end_matches('\0').to_string()
+        }
+        Value::String(s) => s,
+        // Arrays, tuples and maps
+        other => other.to_string(),
+    };
+    truncate_value(text)
+}
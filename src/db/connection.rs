@@ -1,18 +1,100 @@
+use futures_util::TryStreamExt;
 use sqlx::{
-    mysql::{MySqlPool, MySqlRow},
-    postgres::{PgPool, PgPoolOptions, PgRow},
-    Column, Row, ValueRef,
+    mysql::{MySqlPool, MySqlPoolOptions, MySqlRow},
+    postgres::{PgConnection, PgListener, PgNotification, PgPool, PgPoolOptions, PgRow},
+    sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow},
+    Column, Connection, Row, ValueRef,
 };
-use tokio::sync::mpsc;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::{mpsc, Semaphore};
 use tokio::time::{interval, Duration};
 
+use super::bigquery_pool::{BigQueryPool, READ_ONLY_ERROR};
+use super::clickhouse_pool::ClickHousePool;
+use super::duckdb_pool::DuckDbPool;
+use super::odbc_pool::OdbcPool;
+use super::ssh_tunnel::{self, ForwardedPort, TunnelError};
 use super::{
-    ColumnInfo, ConnectionConfig, ConstraintInfo, DatabaseType, DbRequest, DbResponse, IndexInfo,
-    QueryResult, SchemaInfo, TableInfo,
+    auto_limit_sql, check_test_result, distribution_buckets, distribution_sql,
+    distribution_stats_sql, fk_dependency_order, format_set_session_sql, import_insert_sql,
+    modifies_data, next_request_id, only_dml, parse_enum_values, parse_test_csv,
+    parse_test_decoding, plan_estimate, quote_identifier, split_nulls, split_statements,
+    table_insert_statements, with_row_version, Anonymizer, ChangeEvent, ColumnInfo,
+    ConflictStrategy, ConnectionConfig, ConstraintInfo, CopyOptions, CopySummary, CopyTable,
+    DatabaseType, DbRequest, DbResponse, Distribution, DistributionKind, FixtureData,
+    ImportValidation, IndexInfo, Notification, OdbcMode, ParamValue, PlanBudget, QueryResult,
+    QueryTestCase, QueryTestOutcome, RequestId, SchemaInfo, ScriptResult, ServerVariable,
+    SessionVariable, SqlDialect, StatementResult, TableCopySummary, TableImport, TableInfo,
+    TransactionOptions, DEFAULT_QUERY_CONCURRENCY,
 };
 
 const MAX_VALUE_LEN: usize = 10_000;
+/// How often a streaming fetch sends the rows received so far and checks for a stop
+const FETCH_REPORT_MS: u64 = 250;
 const HEALTH_CHECK_INTERVAL_SECS: u64 = 5;
+/// Wait before the first reconnect attempt after the connection is lost; doubles per failure
+const RECONNECT_INITIAL_SECS: u64 = 1;
+const RECONNECT_MAX_SECS: u64 = 60;
+/// Failing rows listed in a dry-run import report
+const MAX_REPORTED_IMPORT_FAILURES: usize = 100;
+
+/// Variables shown in the session panel, in display order
+const PG_SESSION_VARIABLES: &[&str] = &[
+    "search_path",
+    "TimeZone",
+    "default_transaction_isolation",
+    "default_transaction_read_only",
+    "statement_timeout",
+    "lock_timeout",
+    "idle_in_transaction_session_timeout",
+    "work_mem",
+    "application_name",
+];
+/// How often the change capture slot is read
+const CAPTURE_POLL_MS: u64 = 1000;
+/// Upper bound on changes consumed per poll, so a bulk load doesn't stall the worker
+const MAX_CHANGES_PER_POLL: i32 = 1000;
+/// Pragmas shown in the session panel and server variables dialog
+const SQLITE_PRAGMAS: &[&str] = &[
+    "foreign_keys",
+    "journal_mode",
+    "synchronous",
+    "busy_timeout",
+    "cache_size",
+    "temp_store",
+    "query_only",
+    "recursive_triggers",
+];
+const MYSQL_SESSION_VARIABLES: &[&str] = &[
+    "time_zone",
+    "transaction_isolation",
+    "tx_isolation",
+    "transaction_read_only",
+    "sql_mode",
+    "max_execution_time",
+    "innodb_lock_wait_timeout",
+    "sql_safe_updates",
+];
+const DUCKDB_SESSION_VARIABLES: &[&str] = &[
+    "threads",
+    "memory_limit",
+    "TimeZone",
+    "default_order",
+    "preserve_insertion_order",
+    "temp_directory",
+    "enable_progress_bar",
+];
+const CLICKHOUSE_SESSION_VARIABLES: &[&str] = &[
+    "readonly",
+    "max_threads",
+    "max_memory_usage",
+    "max_execution_time",
+    "max_result_rows",
+    "join_use_nulls",
+    "session_timezone",
+];
 
 type PostgresConstraintRow = (
     String,
@@ -24,6 +106,73 @@ type PostgresConstraintRow = (
     Option<String>,
 );
 
+type PostgresColumnRow = (
+    String,
+    String,
+    String,
+    bool,
+    Option<String>,
+    bool,
+    Option<String>,
+    Option<String>,
+    Option<Vec<String>>,
+    Option<String>,
+    Option<String>,
+);
+
+type MySqlColumnRow = (
+    String,
+    String,
+    String,
+    bool,
+    Option<String>,
+    bool,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+type PostgresTableColumnRow = (
+    String,
+    String,
+    bool,
+    Option<String>,
+    bool,
+    Option<String>,
+    Option<String>,
+    Option<Vec<String>>,
+    Option<String>,
+    Option<String>,
+);
+
+type MySqlTableColumnRow = (
+    String,
+    String,
+    bool,
+    Option<String>,
+    bool,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+type PostgresSettingRow = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    bool,
+);
+
 type MySqlConstraintRow = (
     String,
     String,
@@ -34,16 +183,212 @@ type MySqlConstraintRow = (
     Option<String>,
 );
 
+/// SET statements run on every new pooled connection, keyed by variable name.
+type SessionSettings = Arc<RwLock<Vec<(String, String)>>>;
+
+/// What it takes to stop an editor query running on its own task.
+#[derive(Default)]
+struct QueryHandle {
+    /// Server-side id of the connection running it (PostgreSQL and MySQL)
+    backend_pid: Option<u64>,
+    task: Option<tokio::task::AbortHandle>,
+    /// Asked to stop receiving rows and answer with those fetched so far
+    stop_fetching: bool,
+}
+
+/// Rows received by `DbWorker::collect_rows`, with their cells as text.
+struct FetchedRows<R> {
+    rows: Vec<R>,
+    cells: Vec<Vec<Option<String>>>,
+    /// Fetching was stopped with `StopFetching`, so only the rows until then are here
+    stopped: bool,
+    /// Time spent turning cells into text, included in the fetch
+    format_ms: f64,
+}
+
+/// Editor queries in flight, by request id
+type QueryHandles = Arc<std::sync::Mutex<HashMap<RequestId, QueryHandle>>>;
+
+/// Transaction opened by `DbRequest::Begin`; dropping it rolls it back.
+enum OpenTransaction {
+    Postgres(sqlx::Transaction<'static, sqlx::Postgres>),
+    MySQL(sqlx::Transaction<'static, sqlx::MySql>),
+    SQLite(sqlx::Transaction<'static, sqlx::Sqlite>),
+}
+
+struct ManualTransaction {
+    tx: OpenTransaction,
+    /// Statements that ran in it so far
+    statements: usize,
+    /// Server-side id of its connection, so its queries can be cancelled
+    backend_pid: Option<u64>,
+}
+
+/// Held by the worker and every query runner; locked while a statement runs in it
+type SharedTransaction = Arc<tokio::sync::Mutex<Option<ManualTransaction>>>;
+
+/// Answer to worker requests that need the manual transaction while an editor query
+/// runs in it. The worker never waits for it, so Cancel can still reach that query.
+const TRANSACTION_BUSY: &str =
+    "A query is still running in the transaction; wait for it to finish or cancel it";
+
+fn bind_params<'q, DB: sqlx::Database>(
+    mut query: sqlx::query::Query<'q, DB, <DB as sqlx::Database>::Arguments<'q>>,
+    params: &[ParamValue],
+) -> sqlx::query::Query<'q, DB, <DB as sqlx::Database>::Arguments<'q>>
+where
+    bool: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+    i64: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+    f64: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+    Option<String>: sqlx::Encode<'q, DB> + sqlx::Type<DB>,
+{
+    for param in params {
+        query = match param {
+            ParamValue::Null => query.bind(None::<String>),
+            ParamValue::Bool(b) => query.bind(*b),
+            ParamValue::Int(n) => query.bind(*n),
+            ParamValue::Float(f) => query.bind(*f),
+            ParamValue::Text(text) => query.bind(Some(text.clone())),
+        };
+    }
+    query
+}
+
+/// Wait for the next notification, or forever when nothing is listened on.
+async fn next_notification(
+    listener: &mut Option<PgListener>,
+) -> Result<PgNotification, sqlx::Error> {
+    match listener {
+        Some(listener) => listener.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Ping the pool's idle connections. Each is held until all are pinged, so `try_acquire`
+/// hands out a different one every time and never waits for a busy one.
+async fn ping_idle<DB: sqlx::Database>(
+    pool: &sqlx::Pool<DB>,
+) -> Result<Option<(u64, usize)>, String> {
+    use sqlx::Connection as _;
+
+    let mut pinged = Vec::new();
+    let mut slowest_ms = 0;
+    while let Some(mut conn) = pool.try_acquire() {
+        let start = std::time::Instant::now();
+        conn.ping().await.map_err(|e| e.to_string())?;
+        slowest_ms = slowest_ms.max(start.elapsed().as_millis() as u64);
+        pinged.push(conn);
+    }
+    Ok((!pinged.is_empty()).then_some((slowest_ms, pinged.len())))
+}
+
+fn session_statements(settings: &SessionSettings) -> Vec<String> {
+    settings
+        .read()
+        .map(|s| s.iter().map(|(_, sql)| sql.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// Names of variables currently overridden.
+fn overridden_variables(settings: &SessionSettings) -> Vec<String> {
+    settings
+        .read()
+        .map(|s| s.iter().map(|(name, _)| name.to_lowercase()).collect())
+        .unwrap_or_default()
+}
+
+/// Run statements on the pool's idle connections. Each is held until all have run
+/// them, so `try_acquire` hands out a different one every time.
+async fn execute_on_idle<DB: sqlx::Database>(
+    pool: &sqlx::Pool<DB>,
+    statements: &[String],
+) -> Result<(), sqlx::Error>
+where
+    for<'c> &'c mut DB::Connection: sqlx::Executor<'c, Database = DB>,
+{
+    let mut done = Vec::new();
+    while let Some(mut conn) = pool.try_acquire() {
+        for sql in statements {
+            sqlx::Executor::execute(&mut *conn, sql.as_str()).await?;
+        }
+        done.push(conn);
+    }
+    Ok(())
+}
+
+/// Logical decoding session behind the change capture viewer.
+struct ChangeCapture {
+    /// Holds the temporary slot, which the server drops when this connection closes
+    conn: PgConnection,
+    slot: String,
+    tables: Vec<String>,
+}
+
+#[derive(Clone)]
 enum DbPool {
     Postgres(PgPool),
     MySQL(MySqlPool),
+    SQLite(SqlitePool),
+    DuckDB(DuckDbPool),
+    ClickHouse(ClickHousePool),
+    BigQuery(BigQueryPool),
+    Odbc(OdbcPool),
+}
+
+/// When to next try to reconnect after the connection was lost.
+#[derive(Clone, Copy)]
+struct Backoff {
+    at: tokio::time::Instant,
+    delay: Duration,
+    attempt: u32,
+}
+
+impl Backoff {
+    fn start() -> Self {
+        let delay = Duration::from_secs(RECONNECT_INITIAL_SECS);
+        Self {
+            at: tokio::time::Instant::now() + delay,
+            delay,
+            attempt: 1,
+        }
+    }
+
+    fn next(self) -> Self {
+        let delay = (self.delay * 2).min(Duration::from_secs(RECONNECT_MAX_SECS));
+        Self {
+            at: tokio::time::Instant::now() + delay,
+            delay,
+            attempt: self.attempt + 1,
+        }
+    }
 }
 
 pub struct DbWorker {
     pool: Option<DbPool>,
+    /// Settings of the open connection, used to reconnect after it is lost
+    last_config: Option<ConnectionConfig>,
     db_type: Option<DatabaseType>,
+    dialect: SqlDialect,
     schema: Option<String>,
-    cached_schema: Option<SchemaInfo>,
+    cached_schema: Option<Arc<SchemaInfo>>,
+    session_settings: SessionSettings,
+    plan_budget: PlanBudget,
+    /// Statement timeout for connections that don't set one
+    default_statement_timeout_secs: Option<u64>,
+    /// Statement timeout of the open connection, reported when a query hits it
+    statement_timeout_secs: Option<u64>,
+    /// Rows editor SELECTs without a LIMIT are cut at
+    auto_limit: Option<usize>,
+    /// Permits for editor queries, which run on their own tasks
+    query_slots: Arc<Semaphore>,
+    running_queries: QueryHandles,
+    transaction: SharedTransaction,
+    /// SSH tunnel the pool connects through, kept open while connected
+    tunnel: Option<ForwardedPort>,
+    /// Dedicated connection for LISTEN, opened on the first channel
+    listener: Option<PgListener>,
+    listen_channels: Vec<String>,
+    capture: Option<ChangeCapture>,
     request_rx: mpsc::UnboundedReceiver<DbRequest>,
     response_tx: mpsc::UnboundedSender<DbResponse>,
 }
@@ -55,9 +400,23 @@ impl DbWorker {
     ) -> Self {
         Self {
             pool: None,
+            last_config: None,
             db_type: None,
+            dialect: SqlDialect::default(),
             schema: None,
             cached_schema: None,
+            session_settings: Arc::default(),
+            plan_budget: PlanBudget::default(),
+            default_statement_timeout_secs: None,
+            auto_limit: None,
+            statement_timeout_secs: None,
+            query_slots: Arc::new(Semaphore::new(DEFAULT_QUERY_CONCURRENCY)),
+            running_queries: QueryHandles::default(),
+            transaction: SharedTransaction::default(),
+            tunnel: None,
+            listener: None,
+            listen_channels: Vec::new(),
+            capture: None,
             request_rx,
             response_tx,
         }
@@ -65,52 +424,340 @@ impl DbWorker {
 
     pub async fn run(mut self) {
         let mut health_check_interval = interval(Duration::from_secs(HEALTH_CHECK_INTERVAL_SECS));
+        let mut capture_interval = interval(Duration::from_millis(CAPTURE_POLL_MS));
         let mut connection_lost_notified = false;
+        let mut reconnect: Option<Backoff> = None;
+        let mut next_keepalive: Option<tokio::time::Instant> = None;
 
         loop {
             tokio::select! {
                 Some(request) = self.request_rx.recv() => {
+                    let (tag, request) = match request {
+                        DbRequest::Tagged { id, request } => (Some(id), *request),
+                        request => (None, request),
+                    };
+                    let query_id = match request.query_sql() {
+                        Some(sql) => {
+                            let id = tag.unwrap_or_else(next_request_id);
+                            let _ = self.response_tx.send(DbResponse::QueryStarted(id, sql));
+                            Some(id)
+                        }
+                        None => tag,
+                    };
                     let response = match request {
+                        DbRequest::Tagged { .. } => {
+                            DbResponse::Error("Tagged requests cannot be nested".into())
+                        }
+                        DbRequest::SetDefaultStatementTimeout(secs) => {
+                            self.default_statement_timeout_secs = secs;
+                            continue;
+                        }
+                        DbRequest::SetAutoLimit(limit) => {
+                            self.auto_limit = limit;
+                            continue;
+                        }
                         DbRequest::Connect(config) => {
                             connection_lost_notified = false;
-                            self.connect(config).await
+                            reconnect = None;
+                            self.stop_listening();
+                            self.stop_capture().await;
+                            let response = self.connect(config).await;
+                            next_keepalive = self.keepalive_at();
+                            response
                         }
                         DbRequest::TestConnection(config) => self.test_connection(config).await,
-                        DbRequest::Execute(sql) => self.execute(&sql).await,
+                        DbRequest::Execute(sql) if self.pool.is_some() => {
+                            let id = query_id.unwrap_or_default();
+                            self.spawn_query(id, sql, Vec::new(), true, self.auto_limit);
+                            continue; // the query task sends its own response
+                        }
+                        DbRequest::ExecuteOverBudget(sql) if self.pool.is_some() => {
+                            let id = query_id.unwrap_or_default();
+                            self.spawn_query(id, sql, Vec::new(), false, self.auto_limit);
+                            continue;
+                        }
+                        // EXPLAIN can't plan a statement without its parameters, so no budget
+                        // check, and no auto-limit since fetching more would need them again
+                        DbRequest::ExecuteWithParams { sql, params } if self.pool.is_some() => {
+                            let id = query_id.unwrap_or_default();
+                            self.spawn_query(id, sql, params, false, None);
+                            continue;
+                        }
+                        DbRequest::FetchMore { sql, limit } if self.pool.is_some() => {
+                            let id = query_id.unwrap_or_default();
+                            self.spawn_query(id, sql, Vec::new(), false, Some(limit));
+                            continue;
+                        }
+                        DbRequest::Execute(_)
+                        | DbRequest::ExecuteOverBudget(_)
+                        | DbRequest::ExecuteWithParams { .. }
+                        | DbRequest::FetchMore { .. } => {
+                            DbResponse::Error("Not connected".into())
+                        }
+                        DbRequest::StopFetching(id) => {
+                            if let Some(handle) = self.running_queries.lock().unwrap().get_mut(&id) {
+                                handle.stop_fetching = true;
+                            }
+                            continue; // the query answers with the rows it has
+                        }
+                        // On its own task, so nothing the worker runs can hold it up
+                        DbRequest::Cancel(id) => {
+                            let runner = self.query_runner();
+                            tokio::spawn(async move {
+                                // Otherwise the query answers with its own error
+                                if let Err(e) = runner.cancel_query(id).await {
+                                    let _ = runner.response_tx.send(DbResponse::Error(format!(
+                                        "Could not cancel the query: {}",
+                                        e
+                                    )));
+                                }
+                            });
+                            continue;
+                        }
                         DbRequest::Explain(sql) => self.explain(&sql).await,
+                        DbRequest::ProfileTiming(sql) => DbResponse::TimingProfile {
+                            timing: self.profile_timing(&sql).await,
+                            sql,
+                        },
                         DbRequest::ListTables => self.list_tables().await,
                         DbRequest::FetchSchema => self.fetch_schema().await,
                         DbRequest::FetchTableDetails(table) => self.fetch_table_details(&table).await,
                         DbRequest::Disconnect => {
                             connection_lost_notified = false;
+                            reconnect = None;
+                            next_keepalive = None;
+                            self.stop_listening();
+                            self.stop_capture().await;
                             self.disconnect().await
                         }
                         DbRequest::ExecuteMutation(sql) => {
                             self.execute_mutation(&sql).await
                         }
-                        DbRequest::ExecuteBatch(statements) => {
-                            self.execute_batch(&statements).await
+                        DbRequest::ExecuteBatch(statements, options) => {
+                            let reporter = self.batch_reporter(statements.len());
+                            self.execute_batch(&statements, options, false, &reporter)
+                                .await
+                        }
+                        DbRequest::SaveRowEdits(statements, options) => {
+                            let reporter = self.batch_reporter(statements.len());
+                            self.execute_batch(&statements, options, true, &reporter)
+                                .await
                         }
-                        DbRequest::ImportData { table, columns, rows, batch_size } => {
-                            self.execute_import(&table, &columns, &rows, batch_size).await;
+                        DbRequest::Begin => self.begin_transaction().await,
+                        DbRequest::Commit => self.end_transaction(true).await,
+                        DbRequest::Rollback => self.end_transaction(false).await,
+                        DbRequest::ImportData {
+                            table,
+                            columns,
+                            rows,
+                            batch_size,
+                            conflict,
+                        } => {
+                            self.execute_import(&table, &columns, &rows, batch_size, conflict)
+                                .await;
+                            continue; // import sends its own responses
+                        }
+                        DbRequest::ImportTables {
+                            tables,
+                            batch_size,
+                            defer_constraints,
+                        } => {
+                            self.execute_table_imports(&tables, batch_size, defer_constraints)
+                                .await;
                             continue; // import sends its own responses
                         }
+                        DbRequest::ValidateImport(tables) => self.validate_import(&tables).await,
+                        DbRequest::RunQueryTests(tests) => self.run_query_tests(&tests).await,
+                        DbRequest::ImportWatchedFile { path, import } => {
+                            let rows = self.import_watched_file(&import).await;
+                            DbResponse::WatchedFileImported { path, rows }
+                        }
+                        DbRequest::FetchServerVariables => self.fetch_server_variables().await,
+                        DbRequest::FetchSessionVariables => self.fetch_session_variables().await,
+                        DbRequest::SetSessionVariable { name, value } => {
+                            self.set_session_variable(&name, &value).await
+                        }
+                        DbRequest::FetchExtensions => self.fetch_extensions().await,
+                        DbRequest::InstallExtension(name) => {
+                            let sql = format!(
+                                "CREATE EXTENSION IF NOT EXISTS {}",
+                                quote_identifier(DatabaseType::PostgreSQL, &name)
+                            );
+                            self.change_extension(&sql).await
+                        }
+                        DbRequest::UpdateExtension(name) => {
+                            let sql = format!(
+                                "ALTER EXTENSION {} UPDATE",
+                                quote_identifier(DatabaseType::PostgreSQL, &name)
+                            );
+                            self.change_extension(&sql).await
+                        }
+                        DbRequest::FetchRowHistory { table, keys, patterns } => {
+                            self.fetch_row_history(&table, &keys, &patterns).await
+                        }
+                        DbRequest::FetchFkPreview { key, sql } => match self.execute(&sql).await {
+                            DbResponse::QueryResult(result) => DbResponse::FkPreview {
+                                key,
+                                row: Ok(result.rows.into_iter().next().map(|row| {
+                                    result.columns.iter().cloned().zip(row).collect()
+                                })),
+                            },
+                            DbResponse::Error(e) => DbResponse::FkPreview { key, row: Err(e) },
+                            other => other,
+                        },
+                        DbRequest::FetchReferencingCounts(statements) => {
+                            self.fetch_referencing_counts(&statements).await
+                        }
+                        DbRequest::FetchRowCount(sql) => match self.execute(&sql).await {
+                            DbResponse::QueryResult(result) => DbResponse::RowCount {
+                                sql,
+                                count: first_count(&result),
+                            },
+                            DbResponse::Error(e) => DbResponse::RowCount { sql, count: Err(e) },
+                            other => other,
+                        },
+                        DbRequest::FetchTableSample { table, sql } => match self.execute(&sql).await {
+                            DbResponse::QueryResult(result) => DbResponse::TableSample {
+                                table,
+                                sample: Ok(result),
+                            },
+                            DbResponse::Error(e) => DbResponse::TableSample {
+                                table,
+                                sample: Err(e),
+                            },
+                            other => other,
+                        },
+                        DbRequest::FetchSeedRows(queries) => self.fetch_seed_rows(queries).await,
+                        DbRequest::FetchDistribution {
+                            table,
+                            column,
+                            kind,
+                        } => DbResponse::Distribution(
+                            self.fetch_distribution(table, column, kind).await,
+                        ),
+                        DbRequest::CopyTables {
+                            tables,
+                            options,
+                            target,
+                        } => DbResponse::TablesCopied(
+                            self.copy_tables(&tables, &options, target).await,
+                        ),
+                        DbRequest::Listen(channel) => self.listen(channel).await,
+                        DbRequest::Unlisten(channel) => self.unlisten(&channel).await,
+                        DbRequest::StartCapture(tables) => self.start_capture(tables).await,
+                        DbRequest::StopCapture => {
+                            self.stop_capture().await;
+                            DbResponse::Capturing(Vec::new())
+                        }
                     };
 
                     // Reset connection_lost_notified on successful operations
-                    if matches!(response, DbResponse::QueryResult(_) | DbResponse::Schema(_) | DbResponse::TableDetails(_)) {
+                    if matches!(response, DbResponse::Connected(..) | DbResponse::QueryResult(_) | DbResponse::Schema(_) | DbResponse::TableDetails(_)) {
                         connection_lost_notified = false;
                     }
+                    // Let the health check probe for the server coming back, and rebuild the pool meanwhile
+                    if matches!(response, DbResponse::ConnectionLost) {
+                        connection_lost_notified = true;
+                        reconnect.get_or_insert_with(Backoff::start);
+                    }
 
+                    let response = match query_id {
+                        Some(id) => DbResponse::Finished {
+                            id,
+                            response: Box::new(response),
+                        },
+                        None => response,
+                    };
                     let _ = self.response_tx.send(response);
                 }
+                notification = next_notification(&mut self.listener) => match notification {
+                    Ok(notification) => {
+                        let _ = self.response_tx.send(DbResponse::Notification(Notification {
+                            channel: notification.channel().to_string(),
+                            payload: notification.payload().to_string(),
+                            process_id: notification.process_id(),
+                            received_at: chrono::Local::now(),
+                        }));
+                    }
+                    // The listener reconnects and re-subscribes on the next recv
+                    Err(e) => tracing::warn!("LISTEN connection error: {}", e),
+                },
+                _ = capture_interval.tick(), if self.capture.is_some() => {
+                    match self.poll_changes().await {
+                        Ok(events) if events.is_empty() => {}
+                        Ok(events) => {
+                            let _ = self.response_tx.send(DbResponse::ChangeEvents(events));
+                        }
+                        Err(e) => {
+                            self.stop_capture().await;
+                            let _ = self
+                                .response_tx
+                                .send(DbResponse::Error(format!("Change capture stopped: {}", e)));
+                            let _ = self.response_tx.send(DbResponse::Capturing(Vec::new()));
+                        }
+                    }
+                }
                 _ = health_check_interval.tick() => {
-                    // Only check health if we're connected
-                    if self.pool.is_some() && !connection_lost_notified {
-                        if let Err(e) = self.health_check().await {
-                            tracing::warn!("Health check failed: {}", e);
-                            connection_lost_notified = true;
-                            let _ = self.response_tx.send(DbResponse::ConnectionLost);
+                    // Only check health if we're connected; after a loss keep probing until the server returns
+                    if self.pool.is_some() {
+                        match self.health_check().await {
+                            Ok(Some(health)) => {
+                                if connection_lost_notified {
+                                    tracing::info!("Connection restored");
+                                    connection_lost_notified = false;
+                                    reconnect = None;
+                                    let _ = self.response_tx.send(DbResponse::ConnectionRestored);
+                                }
+                                let _ = self.response_tx.send(DbResponse::Health(health));
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                if !connection_lost_notified {
+                                    tracing::warn!("Health check failed: {}", e);
+                                    connection_lost_notified = true;
+                                    let _ = self.response_tx.send(DbResponse::ConnectionLost);
+                                }
+                                reconnect.get_or_insert_with(Backoff::start);
+                            }
+                        }
+                    }
+                }
+                _ = tokio::time::sleep_until(next_keepalive.unwrap_or_else(tokio::time::Instant::now)),
+                    if next_keepalive.is_some() && reconnect.is_none() =>
+                {
+                    // A failed ping is left to the health check, which notices a lost connection
+                    match self.keepalive().await {
+                        Ok(Some((latency_ms, connections))) => {
+                            let _ = self
+                                .response_tx
+                                .send(DbResponse::Keepalive { latency_ms, connections });
+                        }
+                        Ok(None) => {}
+                        Err(e) => tracing::warn!("Keepalive failed: {}", e),
+                    }
+                    next_keepalive = self.keepalive_at();
+                }
+                _ = tokio::time::sleep_until(reconnect.map_or_else(tokio::time::Instant::now, |b| b.at)),
+                    if reconnect.is_some() =>
+                {
+                    let Some(backoff) = reconnect.take() else {
+                        continue;
+                    };
+                    match self.reconnect().await {
+                        Ok(()) => {
+                            tracing::info!("Reconnected after {} attempt(s)", backoff.attempt);
+                            connection_lost_notified = false;
+                            next_keepalive = self.keepalive_at();
+                            let _ = self.response_tx.send(DbResponse::ConnectionRestored);
+                        }
+                        Err(e) => {
+                            tracing::warn!("Reconnect attempt {} failed: {}", backoff.attempt, e);
+                            let next = backoff.next();
+                            let _ = self.response_tx.send(DbResponse::Reconnecting {
+                                attempt: backoff.attempt,
+                                retry_in_secs: next.delay.as_secs(),
+                            });
+                            reconnect = Some(next);
                         }
                     }
                 }
@@ -119,558 +766,1137 @@ impl DbWorker {
         }
     }
 
-    async fn health_check(&self) -> Result<(), String> {
-        match (&self.pool, self.db_type) {
-            (Some(DbPool::Postgres(pool)), Some(DatabaseType::PostgreSQL)) => {
-                sqlx::query("SELECT 1")
-                    .fetch_optional(pool)
-                    .await
-                    .map_err(|e| e.to_string())?;
-                Ok(())
+    /// Open a fresh pool from the last connection's settings. The schema search path is
+    /// set again by the pool, and the session's SET variables are carried over.
+    async fn reconnect(&mut self) -> Result<(), String> {
+        let config = self
+            .last_config
+            .clone()
+            .ok_or_else(|| "No connection to restore".to_string())?;
+        let settings = self
+            .session_settings
+            .read()
+            .map(|s| s.clone())
+            .unwrap_or_default();
+        // The transaction's connection is gone with the old pool
+        if self.close_transaction().await {
+            let _ = self.response_tx.send(DbResponse::TransactionStatus(None));
+        }
+        match self.connect(config).await {
+            DbResponse::Connected(..) => {}
+            DbResponse::ConnectionFailed(e) => return Err(e),
+            DbResponse::UnknownHostKey { key, .. } => {
+                return Err(format!("SSH tunnel: {} is not in known_hosts", key.host))
             }
-            (Some(DbPool::MySQL(pool)), Some(DatabaseType::MySQL)) => {
-                sqlx::query("SELECT 1")
-                    .fetch_optional(pool)
-                    .await
-                    .map_err(|e| e.to_string())?;
-                Ok(())
+            other => return Err(format!("Unexpected response: {:?}", other)),
+        }
+        let statements: Vec<String> = settings.iter().map(|(_, sql)| sql.clone()).collect();
+        if let Ok(mut current) = self.session_settings.write() {
+            *current = settings;
+        }
+        // The new pool's first connections opened before the variables were restored
+        let applied = match &self.pool {
+            Some(DbPool::Postgres(pool)) => execute_on_idle(pool, &statements).await,
+            Some(DbPool::MySQL(pool)) => execute_on_idle(pool, &statements).await,
+            Some(DbPool::SQLite(pool)) => execute_on_idle(pool, &statements).await,
+            _ => Ok(()),
+        };
+        applied.map_err(|e| e.to_string())?;
+        // LISTEN subscriptions lived on a connection of the old pool
+        let channels = std::mem::take(&mut self.listen_channels);
+        self.stop_listening();
+        if !channels.is_empty() {
+            let mut response = DbResponse::Listening(Vec::new());
+            for channel in channels {
+                response = self.listen(channel).await;
             }
-            _ => Ok(()), // Not connected, nothing to check
+            let _ = self.response_tx.send(response);
         }
+        Ok(())
     }
 
-    async fn test_connection(&self, config: ConnectionConfig) -> DbResponse {
-        let result = match config.db_type {
-            DatabaseType::PostgreSQL => {
-                PgPool::connect(&config.connection_string()).await.map(|p| {
-                    let _ = p;
-                })
-            }
-            DatabaseType::MySQL => MySqlPool::connect(&config.connection_string())
-                .await
-                .map(|p| {
-                    let _ = p;
-                }),
-        };
+    /// When the next keepalive is due, if the connection has them. Only server connections
+    /// that hold sockets open can be cut off while idle.
+    fn keepalive_at(&self) -> Option<tokio::time::Instant> {
+        let secs = self.last_config.as_ref()?.pool.keepalive_secs?;
+        let keeps_sockets = matches!(
+            self.db_type,
+            Some(DatabaseType::PostgreSQL | DatabaseType::MySQL | DatabaseType::Odbc)
+        );
+        (secs > 0 && keeps_sockets && self.pool.is_some())
+            .then(|| tokio::time::Instant::now() + Duration::from_secs(secs))
+    }
 
-        match result {
-            Ok(_) => DbResponse::TestResult(Ok(())),
-            Err(e) => DbResponse::TestResult(Err(e.to_string())),
+    /// Ping every idle connection so firewalls and load balancers see traffic on it.
+    /// Returns the slowest round trip and how many connections answered, or `None` when
+    /// all of them are busy anyway.
+    async fn keepalive(&self) -> Result<Option<(u64, usize)>, String> {
+        match &self.pool {
+            Some(DbPool::Postgres(pool)) => ping_idle(pool).await,
+            Some(DbPool::MySQL(pool)) => ping_idle(pool).await,
+            Some(DbPool::Odbc(pool)) => {
+                let start = std::time::Instant::now();
+                pool.ping().await?;
+                Ok(Some((start.elapsed().as_millis() as u64, 1)))
+            }
+            _ => Ok(None),
         }
     }
 
-    async fn connect(&mut self, config: ConnectionConfig) -> DbResponse {
-        let db_type = config.db_type;
-        let database = config.database.clone();
-        let schema = config.schema.clone();
+    /// Run an editor query on its own task so schema and table lookups are not stuck
+    /// behind it; at most `query_slots` queries run at once. An unbounded SELECT is
+    /// cut at `row_limit` rows. A script of several statements runs them one by one.
+    fn spawn_query(
+        &self,
+        id: RequestId,
+        sql: String,
+        params: Vec<ParamValue>,
+        within_budget: bool,
+        row_limit: Option<usize>,
+    ) {
+        let runner = self.query_runner();
+        let slots = self.query_slots.clone();
+        // Held until the handle is stored, so the task cannot finish and clean up first
+        let mut running = self.running_queries.lock().unwrap();
+        let task = tokio::spawn(async move {
+            let statements = if params.is_empty() {
+                split_statements(&sql)
+            } else {
+                Vec::new()
+            };
+            let response = match slots.acquire_owned().await {
+                // EXPLAIN plans one statement, so a script skips the budget check
+                Ok(_permit) if statements.len() > 1 => {
+                    runner.execute_script(id, statements, row_limit).await
+                }
+                Ok(_permit) => {
+                    let over_budget = if within_budget {
+                        runner.check_budget(&sql).await
+                    } else {
+                        None
+                    };
+                    match over_budget {
+                        Some(response) => response,
+                        None => runner.execute_limited(id, &sql, &params, row_limit).await,
+                    }
+                }
+                Err(e) => DbResponse::Error(e.to_string()),
+            };
+            runner.running_queries.lock().unwrap().remove(&id);
+            let _ = runner.response_tx.send(DbResponse::Finished {
+                id,
+                response: Box::new(response),
+            });
+        });
+        running.entry(id).or_default().task = Some(task.abort_handle());
+    }
 
-        let result = match db_type {
-            DatabaseType::PostgreSQL => {
-                let pool_result = if !schema.is_empty() {
-                    let search_path = format!("SET search_path TO \"{}\", public", schema);
-                    PgPoolOptions::new()
-                        .after_connect(move |conn, _meta| {
-                            let sql = search_path.clone();
-                            Box::pin(async move {
-                                sqlx::query(&sql).execute(&mut *conn).await?;
-                                Ok(())
-                            })
-                        })
-                        .connect(&config.connection_string())
-                        .await
-                } else {
-                    PgPool::connect(&config.connection_string()).await
-                };
-                pool_result.map(DbPool::Postgres)
+    /// Run a script's statements in order, reporting each as it finishes. The run stops
+    /// at the first failure; losing the connection answers with that instead.
+    async fn execute_script(
+        &self,
+        id: RequestId,
+        statements: Vec<String>,
+        row_limit: Option<usize>,
+    ) -> DbResponse {
+        let total = statements.len();
+        let mut results = Vec::with_capacity(total);
+        for sql in statements {
+            let start = std::time::Instant::now();
+            let outcome = match self.execute_limited(id, &sql, &[], row_limit).await {
+                DbResponse::QueryResult(result) => Ok(result),
+                DbResponse::Error(e) => Err(e),
+                DbResponse::QueryTimedOut {
+                    timeout_secs: Some(secs),
+                } => Err(format!("Query timed out after {}s", secs)),
+                DbResponse::QueryTimedOut { timeout_secs: None } => {
+                    Err("Query timed out".to_string())
+                }
+                other => return other,
+            };
+            // Stopping the fetch of one statement doesn't skip the ones after it
+            if let Some(handle) = self.running_queries.lock().unwrap().get_mut(&id) {
+                handle.stop_fetching = false;
             }
-            DatabaseType::MySQL => MySqlPool::connect(&config.connection_string())
-                .await
-                .map(DbPool::MySQL),
-        };
-
-        match result {
-            Ok(pool) => {
-                self.pool = Some(pool);
-                self.db_type = Some(db_type);
-                self.schema = if schema.is_empty() {
-                    None
-                } else {
-                    Some(schema)
-                };
-                DbResponse::Connected(db_type, database)
+            let failed = outcome.is_err();
+            results.push(StatementResult {
+                sql,
+                outcome,
+                execution_time_ms: start.elapsed().as_millis() as u64,
+            });
+            let _ = self.response_tx.send(DbResponse::StatementFinished {
+                id,
+                done: results.len(),
+                total,
+            });
+            if failed {
+                break;
             }
-            Err(e) => DbResponse::ConnectionFailed(e.to_string()),
         }
+        DbResponse::ScriptResult(ScriptResult {
+            statements: results,
+            total,
+        })
     }
 
-    async fn fetch_schema(&mut self) -> DbResponse {
-        let resp = match (&self.pool, self.db_type) {
-            (Some(DbPool::Postgres(pool)), Some(DatabaseType::PostgreSQL)) => {
-                self.fetch_schema_postgres(pool).await
+    /// `execute_cancellable` with a LIMIT of `row_limit` appended to an unbounded
+    /// SELECT. One extra row is fetched to tell whether the query has more.
+    async fn execute_limited(
+        &self,
+        id: RequestId,
+        sql: &str,
+        params: &[ParamValue],
+        row_limit: Option<usize>,
+    ) -> DbResponse {
+        // ODBC drivers don't all understand LIMIT
+        let limited = row_limit
+            .filter(|_| self.db_type != Some(DatabaseType::Odbc))
+            .and_then(|limit| auto_limit_sql(sql, limit).map(|limited| (limited, limit)));
+        let Some((limited_sql, limit)) = limited else {
+            return self.execute_cancellable(id, sql, params).await;
+        };
+        match self.execute_cancellable(id, &limited_sql, params).await {
+            DbResponse::QueryResult(mut result) => {
+                // History and the editor know the query as it was written
+                result.sql = sql.to_string();
+                if result.rows.len() > limit {
+                    result.rows.truncate(limit);
+                    result.null_cells.retain(|(row, _)| *row < limit);
+                    result.row_versions.truncate(limit);
+                    result.limited_to = Some(limit);
+                }
+                DbResponse::QueryResult(result)
             }
-            (Some(DbPool::MySQL(pool)), Some(DatabaseType::MySQL)) => {
-                self.fetch_schema_mysql(pool).await
+            other => other,
+        }
+    }
+
+    /// Run a query on a connection of its own whose backend id is recorded, so
+    /// `cancel_query` can stop it on the server.
+    async fn execute_cancellable(
+        &self,
+        id: RequestId,
+        sql: &str,
+        params: &[ParamValue],
+    ) -> DbResponse {
+        let register = |pid: u64| {
+            if let Some(handle) = self.running_queries.lock().unwrap().get_mut(&id) {
+                handle.backend_pid = Some(pid);
             }
-            _ => DbResponse::Error("Not connected".into()),
         };
-        if let DbResponse::Schema(ref schema) = resp {
-            self.cached_schema = Some(schema.clone());
+        if let Some(response) = self.execute_in_transaction(id, sql, params).await {
+            return response;
+        }
+        match &self.pool {
+            // CockroachDB cancels with CANCEL QUERY on its own query ids instead
+            Some(DbPool::Postgres(pool)) if self.dialect != SqlDialect::CockroachDB => {
+                let mut conn = match pool.acquire().await {
+                    Ok(conn) => conn,
+                    Err(e) => return Self::query_error(e.to_string()),
+                };
+                match sqlx::query_scalar::<_, i32>("SELECT pg_backend_pid()")
+                    .fetch_one(&mut *conn)
+                    .await
+                {
+                    Ok(pid) => register(pid as u64),
+                    Err(e) => tracing::warn!("Could not read the backend pid: {}", e),
+                }
+                self.execute_postgres(&mut *conn, sql, params, Some(id))
+                    .await
+            }
+            Some(DbPool::MySQL(pool)) => {
+                let mut conn = match pool.acquire().await {
+                    Ok(conn) => conn,
+                    Err(e) => return Self::query_error(e.to_string()),
+                };
+                match sqlx::query_scalar::<_, u64>("SELECT CONNECTION_ID()")
+                    .fetch_one(&mut *conn)
+                    .await
+                {
+                    Ok(pid) => register(pid),
+                    Err(e) => tracing::warn!("Could not read the connection id: {}", e),
+                }
+                self.execute_mysql(&mut *conn, sql, params, Some(id)).await
+            }
+            Some(DbPool::Postgres(pool)) => {
+                self.execute_postgres(pool, sql, params, Some(id)).await
+            }
+            Some(DbPool::SQLite(pool)) => self.execute_sqlite(pool, sql, params).await,
+            _ if !params.is_empty() => DbResponse::Error(
+                "Bound parameters are only supported on PostgreSQL, MySQL and SQLite".into(),
+            ),
+            _ => self.execute(sql).await,
         }
-        resp
     }
 
-    async fn fetch_schema_postgres(&self, pool: &PgPool) -> DbResponse {
-        // Build schema filter conditions
-        let schema_filter = match &self.schema {
-            Some(schema) => format!("AND t.table_schema = '{}'", schema),
-            None => "AND t.table_schema NOT IN ('pg_catalog', 'information_schema')".to_string(),
+    async fn begin_transaction(&self) -> DbResponse {
+        let Ok(mut transaction) = self.transaction.try_lock() else {
+            return DbResponse::Error(TRANSACTION_BUSY.into());
         };
-        let views_schema_filter = match &self.schema {
-            Some(schema) => format!("WHERE table_schema = '{}'", schema),
-            None => "WHERE table_schema NOT IN ('pg_catalog', 'information_schema')".to_string(),
+        if let Some(open) = transaction.as_ref() {
+            return DbResponse::TransactionStatus(Some(open.statements));
+        }
+        let begun = match &self.pool {
+            Some(DbPool::Postgres(pool)) => match pool.begin().await {
+                Ok(mut tx) => {
+                    let pid = if self.dialect == SqlDialect::CockroachDB {
+                        None
+                    } else {
+                        sqlx::query_scalar::<_, i32>("SELECT pg_backend_pid()")
+                            .fetch_one(&mut *tx)
+                            .await
+                            .ok()
+                            .map(|pid| pid as u64)
+                    };
+                    Ok((OpenTransaction::Postgres(tx), pid))
+                }
+                Err(e) => Err(e),
+            },
+            Some(DbPool::MySQL(pool)) => match pool.begin().await {
+                Ok(mut tx) => {
+                    let pid = sqlx::query_scalar::<_, u64>("SELECT CONNECTION_ID()")
+                        .fetch_one(&mut *tx)
+                        .await
+                        .ok();
+                    Ok((OpenTransaction::MySQL(tx), pid))
+                }
+                Err(e) => Err(e),
+            },
+            Some(DbPool::SQLite(pool)) => pool
+                .begin()
+                .await
+                .map(|tx| (OpenTransaction::SQLite(tx), None)),
+            Some(_) => {
+                return DbResponse::Error(
+                    "Manual transactions are only supported on PostgreSQL, MySQL and SQLite".into(),
+                )
+            }
+            None => return DbResponse::Error("Not connected".into()),
         };
-        let columns_schema_filter = match &self.schema {
-            Some(schema) => format!("WHERE c.table_schema = '{}'", schema),
-            None => "WHERE c.table_schema NOT IN ('pg_catalog', 'information_schema')".to_string(),
+        match begun {
+            Ok((tx, backend_pid)) => {
+                *transaction = Some(ManualTransaction {
+                    tx,
+                    statements: 0,
+                    backend_pid,
+                });
+                DbResponse::TransactionStatus(Some(0))
+            }
+            Err(e) => DbResponse::Error(format!("Begin failed: {}", e)),
+        }
+    }
+
+    /// Commit or roll back the manual transaction. A failed commit still ends it.
+    async fn end_transaction(&self, commit: bool) -> DbResponse {
+        let Ok(mut transaction) = self.transaction.try_lock() else {
+            return DbResponse::Error(TRANSACTION_BUSY.into());
         };
-        let namespace_filter = match &self.schema {
-            Some(schema) => format!("WHERE ns.nspname = '{}'", schema),
-            None => "WHERE ns.nspname NOT IN ('pg_catalog', 'information_schema')".to_string(),
+        let Some(open) = transaction.take() else {
+            return DbResponse::TransactionStatus(None);
         };
-        let constraints_schema_filter = match &self.schema {
-            Some(schema) => format!("WHERE tc.table_schema = '{}'", schema),
-            None => "WHERE tc.table_schema NOT IN ('pg_catalog', 'information_schema')".to_string(),
+        drop(transaction);
+        let result = match (open.tx, commit) {
+            (OpenTransaction::Postgres(tx), true) => tx.commit().await,
+            (OpenTransaction::Postgres(tx), false) => tx.rollback().await,
+            (OpenTransaction::MySQL(tx), true) => tx.commit().await,
+            (OpenTransaction::MySQL(tx), false) => tx.rollback().await,
+            (OpenTransaction::SQLite(tx), true) => tx.commit().await,
+            (OpenTransaction::SQLite(tx), false) => tx.rollback().await,
         };
+        match result {
+            Ok(()) => DbResponse::TransactionStatus(None),
+            Err(e) => {
+                let _ = self.response_tx.send(DbResponse::TransactionStatus(None));
+                let action = if commit { "Commit" } else { "Rollback" };
+                DbResponse::Error(format!("{} failed: {}", action, e))
+            }
+        }
+    }
 
-        let tables_sql = format!(
-            r#"
-            SELECT 
-                t.table_name::TEXT,
-                COALESCE(s.n_live_tup, 0)::BIGINT as row_estimate
-            FROM information_schema.tables t
-            LEFT JOIN pg_stat_user_tables s 
-                ON t.table_name = s.relname AND t.table_schema = s.schemaname
-            WHERE t.table_type = 'BASE TABLE' 
-              {}
-            ORDER BY t.table_schema, t.table_name
-        "#,
-            schema_filter
-        );
-
-        let views_sql = format!(
-            r#"
-            SELECT table_name::TEXT 
-            FROM information_schema.views 
-            {}
-            ORDER BY table_name
-        "#,
-            views_schema_filter
-        );
-
-        let columns_sql = format!(
-            r#"
-            SELECT 
-                c.table_name::TEXT,
-                c.column_name::TEXT,
-                c.data_type::TEXT,
-                (c.is_nullable = 'YES') as nullable,
-                c.column_default::TEXT,
-                COALESCE(pk.is_pk, false) as is_primary_key
-            FROM information_schema.columns c
-            LEFT JOIN (
-                SELECT kcu.table_name, kcu.column_name, true as is_pk
-                FROM information_schema.table_constraints tc
-                JOIN information_schema.key_column_usage kcu 
-                    ON tc.constraint_name = kcu.constraint_name
-                    AND tc.table_schema = kcu.table_schema
-                    AND tc.table_name = kcu.table_name
-                WHERE tc.constraint_type = 'PRIMARY KEY'
-            ) pk ON c.table_name = pk.table_name AND c.column_name = pk.column_name
-            {}
-            ORDER BY c.table_name, c.ordinal_position
-        "#,
-            columns_schema_filter
-        );
-
-        let indexes_sql = format!(
-            r#"
-            SELECT 
-                t.relname::TEXT as table_name,
-                i.relname::TEXT as index_name,
-                COALESCE(array_agg(a.attname::TEXT ORDER BY x.n), ARRAY[]::TEXT[]) as columns,
-                ix.indisunique as is_unique,
-                ix.indisprimary as is_primary,
-                COALESCE(am.amname::TEXT, 'unknown') as index_type
-            FROM pg_index ix
-            JOIN pg_class t ON t.oid = ix.indrelid
-            JOIN pg_namespace ns ON ns.oid = t.relnamespace
-            JOIN pg_class i ON i.oid = ix.indexrelid
-            LEFT JOIN pg_am am ON am.oid = i.relam
-            CROSS JOIN LATERAL unnest(ix.indkey) WITH ORDINALITY AS x(attnum, n)
-            JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = x.attnum
-            {}
-            GROUP BY t.relname, i.relname, ix.indisunique, ix.indisprimary, am.amname
-            ORDER BY t.relname, i.relname
-        "#,
-            namespace_filter
-        );
-
-        let constraints_sql = format!(
-            r#"
-            SELECT 
-                tc.table_name::TEXT,
-                tc.constraint_name::TEXT,
-                tc.constraint_type::TEXT,
-                COALESCE(
-                    array_agg(DISTINCT kcu.column_name::TEXT) FILTER (WHERE kcu.column_name IS NOT NULL),
-                    ARRAY[]::TEXT[]
-                ) as columns,
-                ccu.table_name::TEXT as foreign_table,
-                COALESCE(
-                    array_agg(DISTINCT ccu.column_name::TEXT) FILTER (
-                        WHERE ccu.column_name IS NOT NULL AND tc.constraint_type = 'FOREIGN KEY'
-                    ),
-                    ARRAY[]::TEXT[]
-                ) as foreign_columns,
-                cc.check_clause::TEXT
-            FROM information_schema.table_constraints tc
-            LEFT JOIN information_schema.key_column_usage kcu 
-                ON tc.constraint_name = kcu.constraint_name
-                AND tc.table_schema = kcu.table_schema
-                AND tc.table_name = kcu.table_name
-            LEFT JOIN information_schema.constraint_column_usage ccu 
-                ON tc.constraint_name = ccu.constraint_name
-                AND tc.constraint_type = 'FOREIGN KEY'
-            LEFT JOIN information_schema.check_constraints cc 
-                ON tc.constraint_name = cc.constraint_name
-            {}
-            GROUP BY tc.table_name, tc.constraint_name, tc.constraint_type, ccu.table_name, cc.check_clause
-            ORDER BY tc.table_name, tc.constraint_type, tc.constraint_name
-        "#,
-            constraints_schema_filter
-        );
-
-        let tables: Vec<(String, i64)> = match sqlx::query_as(&tables_sql).fetch_all(pool).await {
-            Ok(t) => t,
-            Err(e) => return DbResponse::Error(e.to_string()),
+    /// Run an editor query in the manual transaction, or None when there is none.
+    async fn execute_in_transaction(
+        &self,
+        id: RequestId,
+        sql: &str,
+        params: &[ParamValue],
+    ) -> Option<DbResponse> {
+        let mut transaction = self.transaction.lock().await;
+        let open = transaction.as_mut()?;
+        if let Some(pid) = open.backend_pid {
+            if let Some(handle) = self.running_queries.lock().unwrap().get_mut(&id) {
+                handle.backend_pid = Some(pid);
+            }
+        }
+        let response = match &mut open.tx {
+            OpenTransaction::Postgres(tx) => {
+                self.execute_postgres(&mut **tx, sql, params, Some(id))
+                    .await
+            }
+            OpenTransaction::MySQL(tx) => {
+                self.execute_mysql(&mut **tx, sql, params, Some(id)).await
+            }
+            OpenTransaction::SQLite(tx) => self.execute_sqlite(&mut **tx, sql, params).await,
         };
+        if matches!(response, DbResponse::QueryResult(_)) {
+            open.statements += 1;
+        }
+        let _ = self
+            .response_tx
+            .send(DbResponse::TransactionStatus(Some(open.statements)));
+        Some(response)
+    }
 
-        let views: Vec<String> = match sqlx::query_scalar(&views_sql).fetch_all(pool).await {
-            Ok(v) => v,
-            Err(e) => return DbResponse::Error(e.to_string()),
+    /// Run statements in the manual transaction under a savepoint, so they apply
+    /// together or not at all. None when there is no manual transaction.
+    async fn run_in_transaction(
+        &self,
+        statements: &[String],
+        row_edits: bool,
+        reporter: &BatchReporter,
+    ) -> Option<Result<BatchOutcome, String>> {
+        let Ok(mut transaction) = self.transaction.try_lock() else {
+            return Some(Err(TRANSACTION_BUSY.to_string()));
         };
+        let open = transaction.as_mut()?;
+        // Dropping a savepoint without committing it rolls it back
+        let result: Result<BatchOutcome, sqlx::Error> = async {
+            let mut affected = 0;
+            match &mut open.tx {
+                OpenTransaction::Postgres(tx) => {
+                    let mut savepoint = Connection::begin(&mut **tx).await?;
+                    for (i, sql) in statements.iter().enumerate() {
+                        reporter.start(i, sql).map_err(driver_error)?;
+                        let rows = sqlx::query(sql)
+                            .execute(&mut *savepoint)
+                            .await?
+                            .rows_affected();
+                        if row_edits && rows == 0 {
+                            return Ok(BatchOutcome::Unmatched(i));
+                        }
+                        affected += rows;
+                    }
+                    savepoint.commit().await?;
+                }
+                OpenTransaction::MySQL(tx) => {
+                    let mut savepoint = Connection::begin(&mut **tx).await?;
+                    for (i, sql) in statements.iter().enumerate() {
+                        reporter.start(i, sql).map_err(driver_error)?;
+                        let rows = sqlx::query(sql)
+                            .execute(&mut *savepoint)
+                            .await?
+                            .rows_affected();
+                        if row_edits && rows == 0 {
+                            return Ok(BatchOutcome::Unmatched(i));
+                        }
+                        affected += rows;
+                    }
+                    savepoint.commit().await?;
+                }
+                OpenTransaction::SQLite(tx) => {
+                    let mut savepoint = Connection::begin(&mut **tx).await?;
+                    for (i, sql) in statements.iter().enumerate() {
+                        reporter.start(i, sql).map_err(driver_error)?;
+                        let rows = sqlx::query(sql)
+                            .execute(&mut *savepoint)
+                            .await?
+                            .rows_affected();
+                        if row_edits && rows == 0 {
+                            return Ok(BatchOutcome::Unmatched(i));
+                        }
+                        affected += rows;
+                    }
+                    savepoint.commit().await?;
+                }
+            }
+            Ok(BatchOutcome::Committed(affected))
+        }
+        .await;
+        if matches!(result, Ok(BatchOutcome::Committed(_))) {
+            open.statements += statements.len();
+        }
+        let _ = self
+            .response_tx
+            .send(DbResponse::TransactionStatus(Some(open.statements)));
+        Some(result.map_err(|e| e.to_string()))
+    }
 
-        let columns: Vec<(String, String, String, bool, Option<String>, bool)> =
-            match sqlx::query_as(&columns_sql).fetch_all(pool).await {
-                Ok(c) => c,
-                Err(e) => return DbResponse::Error(e.to_string()),
-            };
-
-        let indexes: Vec<(String, String, Vec<String>, bool, bool, String)> =
-            match sqlx::query_as(&indexes_sql).fetch_all(pool).await {
-                Ok(i) => i,
-                Err(e) => return DbResponse::Error(e.to_string()),
-            };
+    /// Stop the editor query `id`. The server is asked to cancel it where the backend
+    /// is known, and the query then fails with the server's error; otherwise its task
+    /// is dropped and answered here.
+    async fn cancel_query(&self, id: RequestId) -> Result<(), String> {
+        let (backend_pid, task) = match self.running_queries.lock().unwrap().get(&id) {
+            Some(handle) => (handle.backend_pid, handle.task.clone()),
+            None => return Ok(()), // already finished
+        };
+        match (&self.pool, backend_pid) {
+            (Some(DbPool::Postgres(pool)), Some(pid)) => {
+                let cancelled: bool = sqlx::query_scalar("SELECT pg_cancel_backend($1)")
+                    .bind(pid as i32)
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                if !cancelled {
+                    return Err(format!("backend {} was not found", pid));
+                }
+                Ok(())
+            }
+            (Some(DbPool::MySQL(pool)), Some(pid)) => {
+                sqlx::query(&format!("KILL QUERY {}", pid))
+                    .execute(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(())
+            }
+            _ => {
+                if let Some(task) = task {
+                    task.abort();
+                }
+                self.running_queries.lock().unwrap().remove(&id);
+                let _ = self.response_tx.send(DbResponse::Finished {
+                    id,
+                    response: Box::new(DbResponse::Error("Query cancelled".into())),
+                });
+                Ok(())
+            }
+        }
+    }
 
-        let constraints: Vec<PostgresConstraintRow> =
-            match sqlx::query_as(&constraints_sql).fetch_all(pool).await {
-                Ok(c) => c,
-                Err(e) => return DbResponse::Error(e.to_string()),
-            };
+    /// Drop the manual transaction, rolling it back, and tell whether there was one.
+    /// Editor queries still running are stopped first rather than waited for, as the
+    /// connection they run on is going away.
+    async fn close_transaction(&self) -> bool {
+        let mut transaction = match self.transaction.try_lock() {
+            Ok(transaction) => transaction,
+            Err(_) => {
+                let running: Vec<(RequestId, QueryHandle)> =
+                    self.running_queries.lock().unwrap().drain().collect();
+                for (id, handle) in running {
+                    if let Some(task) = handle.task {
+                        task.abort();
+                    }
+                    let _ = self.response_tx.send(DbResponse::Finished {
+                        id,
+                        response: Box::new(DbResponse::Error(
+                            "Query stopped: the connection was closed".into(),
+                        )),
+                    });
+                }
+                // Aborted tasks release the lock as they are dropped
+                self.transaction.lock().await
+            }
+        };
+        transaction.take().is_some()
+    }
 
-        let mut table_infos: Vec<TableInfo> = tables
-            .into_iter()
-            .map(|(name, row_estimate)| TableInfo {
-                name,
-                row_estimate,
-                columns: Vec::new(),
-                indexes: Vec::new(),
-                constraints: Vec::new(),
-            })
-            .collect();
+    /// A worker sharing this one's pool and session state, without its listener,
+    /// change capture or requests.
+    fn query_runner(&self) -> DbWorker {
+        let (_, request_rx) = mpsc::unbounded_channel();
+        DbWorker {
+            pool: self.pool.clone(),
+            last_config: None,
+            db_type: self.db_type,
+            dialect: self.dialect,
+            schema: self.schema.clone(),
+            cached_schema: self.cached_schema.clone(),
+            session_settings: self.session_settings.clone(),
+            plan_budget: self.plan_budget,
+            default_statement_timeout_secs: self.default_statement_timeout_secs,
+            auto_limit: self.auto_limit,
+            statement_timeout_secs: self.statement_timeout_secs,
+            query_slots: self.query_slots.clone(),
+            running_queries: self.running_queries.clone(),
+            transaction: self.transaction.clone(),
+            tunnel: None,
+            listener: None,
+            listen_channels: Vec::new(),
+            capture: None,
+            request_rx,
+            response_tx: self.response_tx.clone(),
+        }
+    }
 
-        for (table_name, col_name, data_type, nullable, default_value, is_pk) in columns {
-            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
-                table.columns.push(ColumnInfo {
-                    name: col_name,
-                    data_type,
-                    nullable,
-                    default_value,
-                    is_primary_key: is_pk,
-                });
+    async fn listen(&mut self, channel: String) -> DbResponse {
+        let Some(DbPool::Postgres(pool)) = &self.pool else {
+            return DbResponse::Error("LISTEN is only supported on PostgreSQL".to_string());
+        };
+        if self.listen_channels.contains(&channel) {
+            return DbResponse::Listening(self.listen_channels.clone());
+        }
+        if self.listener.is_none() {
+            match PgListener::connect_with(pool).await {
+                Ok(listener) => self.listener = Some(listener),
+                Err(e) => return DbResponse::Error(format!("LISTEN failed: {}", e)),
             }
         }
-
-        for (table_name, name, columns, is_unique, is_primary, index_type) in indexes {
-            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
-                table.indexes.push(IndexInfo {
-                    name,
-                    columns,
-                    is_unique,
-                    is_primary,
-                    index_type,
-                });
+        if let Some(listener) = self.listener.as_mut() {
+            if let Err(e) = listener.listen(&channel).await {
+                return DbResponse::Error(format!("LISTEN {} failed: {}", channel, e));
             }
         }
+        self.listen_channels.push(channel);
+        DbResponse::Listening(self.listen_channels.clone())
+    }
 
-        for (
-            table_name,
-            name,
-            constraint_type,
-            columns,
-            foreign_table,
-            foreign_columns,
-            check_clause,
-        ) in constraints
-        {
-            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
-                table.constraints.push(ConstraintInfo {
-                    name,
-                    constraint_type,
-                    columns,
-                    foreign_table,
-                    foreign_columns: if foreign_columns.is_empty() {
-                        None
-                    } else {
-                        Some(foreign_columns)
-                    },
-                    check_clause,
-                });
+    async fn unlisten(&mut self, channel: &str) -> DbResponse {
+        self.listen_channels.retain(|c| c != channel);
+        if self.listen_channels.is_empty() {
+            self.stop_listening();
+        } else if let Some(listener) = self.listener.as_mut() {
+            if let Err(e) = listener.unlisten(channel).await {
+                return DbResponse::Error(format!("UNLISTEN {} failed: {}", channel, e));
             }
         }
+        DbResponse::Listening(self.listen_channels.clone())
+    }
 
-        DbResponse::Schema(SchemaInfo {
-            tables: table_infos,
-            views,
-        })
+    /// Drop the LISTEN connection; its channels belong to the previous server.
+    fn stop_listening(&mut self) {
+        self.listener = None;
+        self.listen_channels.clear();
     }
 
-    async fn fetch_schema_mysql(&self, pool: &MySqlPool) -> DbResponse {
-        let db_name_sql = "SELECT DATABASE()";
-        let db_name: Option<String> = match sqlx::query_scalar(db_name_sql).fetch_one(pool).await {
-            Ok(name) => name,
-            Err(e) => return DbResponse::Error(e.to_string()),
+    /// Open a temporary `test_decoding` slot on a dedicated connection. Only changes made
+    /// after this point are seen.
+    async fn start_capture(&mut self, tables: Vec<String>) -> DbResponse {
+        let Some(DbPool::Postgres(pool)) = &self.pool else {
+            return DbResponse::Error("Change capture is only supported on PostgreSQL".to_string());
         };
+        let pool = pool.clone();
+        self.stop_capture().await;
+        if tables.is_empty() {
+            return DbResponse::Capturing(Vec::new());
+        }
 
-        let db_name = match db_name {
-            Some(n) => n,
-            None => return DbResponse::Error("No database selected".into()),
+        let wal_level: String = match sqlx::query_scalar("SHOW wal_level").fetch_one(&pool).await {
+            Ok(level) => level,
+            Err(e) => return DbResponse::Error(format!("Change capture failed: {}", e)),
         };
+        if wal_level != "logical" {
+            return DbResponse::Error(format!(
+                "Change capture needs wal_level = logical (server has {})",
+                wal_level
+            ));
+        }
 
-        let tables_sql = r#"
-            SELECT 
-                t.TABLE_NAME as table_name,
-                COALESCE(t.TABLE_ROWS, 0) as row_estimate
-            FROM information_schema.TABLES t
-            WHERE t.TABLE_SCHEMA = ?
-              AND t.TABLE_TYPE = 'BASE TABLE'
-            ORDER BY t.TABLE_NAME
-        "#;
+        let mut conn = match pool.acquire().await {
+            Ok(conn) => conn.detach(),
+            Err(e) => return DbResponse::Error(format!("Change capture failed: {}", e)),
+        };
+        let slot = format!("fbench_cdc_{}", std::process::id());
+        if let Err(e) =
+            sqlx::query("SELECT pg_create_logical_replication_slot($1, 'test_decoding', true)")
+                .bind(&slot)
+                .execute(&mut conn)
+                .await
+        {
+            let _ = conn.close().await;
+            return DbResponse::Error(format!(
+                "Could not create a replication slot (needs the REPLICATION privilege): {}",
+                e
+            ));
+        }
 
-        let views_sql = r#"
-            SELECT TABLE_NAME 
-            FROM information_schema.VIEWS 
-            WHERE TABLE_SCHEMA = ?
-            ORDER BY TABLE_NAME
-        "#;
+        self.capture = Some(ChangeCapture {
+            conn,
+            slot,
+            tables: tables.clone(),
+        });
+        DbResponse::Capturing(tables)
+    }
 
-        let columns_sql = r#"
-            SELECT 
-                c.TABLE_NAME as table_name,
-                c.COLUMN_NAME as column_name,
-                c.DATA_TYPE as data_type,
-                (c.IS_NULLABLE = 'YES') as nullable,
-                c.COLUMN_DEFAULT as default_value,
-                (c.COLUMN_KEY = 'PRI') as is_primary_key
-            FROM information_schema.COLUMNS c
-            WHERE c.TABLE_SCHEMA = ?
-            ORDER BY c.TABLE_NAME, c.ORDINAL_POSITION
-        "#;
+    /// Consume the changes decoded since the last poll, keeping those of the captured tables.
+    async fn poll_changes(&mut self) -> Result<Vec<ChangeEvent>, sqlx::Error> {
+        let Some(capture) = self.capture.as_mut() else {
+            return Ok(Vec::new());
+        };
+        let rows: Vec<(String, String, String)> = sqlx::query_as(
+            "SELECT lsn::TEXT, xid::TEXT, data \
+             FROM pg_logical_slot_get_changes($1, NULL, $2, 'skip-empty-xacts', '1')",
+        )
+        .bind(&capture.slot)
+        .bind(MAX_CHANGES_PER_POLL)
+        .fetch_all(&mut capture.conn)
+        .await?;
+        Ok(rows
+            .iter()
+            .filter_map(|(lsn, xid, data)| parse_test_decoding(lsn, xid, data))
+            .filter(|event| event.matches_table(&capture.tables))
+            .collect())
+    }
 
-        let indexes_sql = r#"
-            SELECT 
-                TABLE_NAME as table_name,
-                INDEX_NAME as name,
-                GROUP_CONCAT(COLUMN_NAME ORDER BY SEQ_IN_INDEX) as columns,
-                (NON_UNIQUE = 0) as is_unique,
-                (INDEX_NAME = 'PRIMARY') as is_primary,
-                INDEX_TYPE as index_type
-            FROM information_schema.STATISTICS
-            WHERE TABLE_SCHEMA = ?
-            GROUP BY TABLE_NAME, INDEX_NAME, NON_UNIQUE, INDEX_TYPE
-            ORDER BY TABLE_NAME, INDEX_NAME
-        "#;
+    /// Close the capture connection, which drops its temporary slot.
+    async fn stop_capture(&mut self) {
+        if let Some(capture) = self.capture.take() {
+            let _ = capture.conn.close().await;
+        }
+    }
 
-        let constraints_sql = r#"
-            SELECT 
-                tc.TABLE_NAME as table_name,
-                tc.CONSTRAINT_NAME as name,
-                tc.CONSTRAINT_TYPE as constraint_type,
-                GROUP_CONCAT(DISTINCT kcu.COLUMN_NAME ORDER BY kcu.ORDINAL_POSITION) as columns,
-                kcu.REFERENCED_TABLE_NAME as foreign_table,
-                GROUP_CONCAT(
-                    DISTINCT kcu.REFERENCED_COLUMN_NAME
-                    ORDER BY kcu.POSITION_IN_UNIQUE_CONSTRAINT
-                ) as foreign_columns,
-                NULL as check_clause
-            FROM information_schema.TABLE_CONSTRAINTS tc
-            LEFT JOIN information_schema.KEY_COLUMN_USAGE kcu 
-                ON tc.CONSTRAINT_NAME = kcu.CONSTRAINT_NAME 
-                AND tc.TABLE_SCHEMA = kcu.TABLE_SCHEMA
-                AND tc.TABLE_NAME = kcu.TABLE_NAME
-            WHERE tc.TABLE_SCHEMA = ?
-            GROUP BY tc.TABLE_NAME, tc.CONSTRAINT_NAME, tc.CONSTRAINT_TYPE, kcu.REFERENCED_TABLE_NAME
-            ORDER BY tc.TABLE_NAME, tc.CONSTRAINT_TYPE, tc.CONSTRAINT_NAME
-        "#;
+    /// Ping the server, timing the round trip and reading its replication/read-only state.
+    async fn health_check(&self) -> Result<Option<ConnectionHealth>, String> {
+        let start = std::time::Instant::now();
+        match (&self.pool, self.db_type) {
+            (Some(DbPool::Postgres(pool)), Some(DatabaseType::PostgreSQL)) => {
+                let (in_recovery, read_only): (bool, bool) = sqlx::query_as(
+                    "SELECT pg_is_in_recovery(), current_setting('transaction_read_only') = 'on'",
+                )
+                .fetch_one(pool)
+                .await
+                .map_err(|e| e.to_string())?;
+                Ok(Some(ConnectionHealth {
+                    latency_ms: start.elapsed().as_millis() as u64,
+                    is_replica: Some(in_recovery),
+                    read_only: read_only || in_recovery,
+                }))
+            }
+            (Some(DbPool::MySQL(pool)), Some(DatabaseType::MySQL)) => {
+                let read_only: i64 =
+                    sqlx::query_scalar("SELECT CAST(@@global.read_only AS SIGNED)")
+                        .fetch_one(pool)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                Ok(Some(ConnectionHealth {
+                    latency_ms: start.elapsed().as_millis() as u64,
+                    is_replica: mysql_is_replica(pool).await,
+                    read_only: read_only != 0,
+                }))
+            }
+            (Some(DbPool::SQLite(pool)), Some(DatabaseType::SQLite)) => {
+                let query_only: i64 = sqlx::query_scalar("PRAGMA query_only")
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(Some(ConnectionHealth {
+                    latency_ms: start.elapsed().as_millis() as u64,
+                    is_replica: None,
+                    read_only: query_only != 0,
+                }))
+            }
+            (Some(DbPool::DuckDB(pool)), Some(DatabaseType::DuckDB)) => {
+                pool.query("SELECT 1").await?;
+                Ok(Some(ConnectionHealth {
+                    latency_ms: start.elapsed().as_millis() as u64,
+                    is_replica: None,
+                    read_only: false,
+                }))
+            }
+            (Some(DbPool::ClickHouse(pool)), Some(DatabaseType::ClickHouse)) => {
+                let (_, _, rows) = pool
+                    .query("SELECT toString(getSetting('readonly'))")
+                    .await?;
+                let readonly = rows
+                    .first()
+                    .and_then(|r| r.first())
+                    .and_then(Option::as_deref);
+                Ok(Some(ConnectionHealth {
+                    latency_ms: start.elapsed().as_millis() as u64,
+                    is_replica: None,
+                    read_only: readonly.is_some_and(|v| v != "0"),
+                }))
+            }
+            (Some(DbPool::BigQuery(pool)), Some(DatabaseType::BigQuery)) => {
+                pool.ping().await?;
+                Ok(Some(ConnectionHealth {
+                    latency_ms: start.elapsed().as_millis() as u64,
+                    is_replica: None,
+                    read_only: true,
+                }))
+            }
+            (Some(DbPool::Odbc(pool)), Some(DatabaseType::Odbc)) => {
+                pool.ping().await?;
+                Ok(Some(ConnectionHealth {
+                    latency_ms: start.elapsed().as_millis() as u64,
+                    is_replica: None,
+                    read_only: false,
+                }))
+            }
+            _ => Ok(None), // Not connected, nothing to check
+        }
+    }
 
-        let tables: Vec<(String, i64)> = match sqlx::query_as(tables_sql)
-            .bind(&db_name)
-            .fetch_all(pool)
-            .await
-        {
-            Ok(t) => t,
-            Err(e) => return DbResponse::Error(e.to_string()),
+    async fn test_connection(&self, config: ConnectionConfig) -> DbResponse {
+        let request = DbRequest::TestConnection(config.clone());
+        let config = config.with_file_password();
+        // Held until the test is done
+        let (config, _tunnel) = match ssh_tunnel::forward(config).await {
+            Ok(forwarded) => forwarded,
+            Err(TunnelError::UnknownHostKey(key)) => {
+                return DbResponse::UnknownHostKey {
+                    key,
+                    request: Box::new(request),
+                }
+            }
+            Err(TunnelError::Failed(e)) => {
+                return DbResponse::TestResult(Err(format!("SSH tunnel: {}", e)))
+            }
         };
-
-        let views: Vec<String> = match sqlx::query_scalar(views_sql)
-            .bind(&db_name)
-            .fetch_all(pool)
-            .await
-        {
-            Ok(v) => v,
-            Err(e) => return DbResponse::Error(e.to_string()),
+        let result = match config.db_type {
+            DatabaseType::PostgreSQL => {
+                PgPool::connect(&config.connection_string()).await.map(|p| {
+                    let _ = p;
+                })
+            }
+            DatabaseType::MySQL => MySqlPool::connect(&config.connection_string())
+                .await
+                .map(|p| {
+                    let _ = p;
+                }),
+            // Closed right away so the database file isn't held open
+            DatabaseType::SQLite => match SqlitePool::connect_with(sqlite_options(&config)).await {
+                Ok(pool) => {
+                    pool.close().await;
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            },
+            DatabaseType::DuckDB => DuckDbPool::open(&config.database)
+                .await
+                .map(|_| ())
+                .map_err(driver_error),
+            DatabaseType::ClickHouse => ClickHousePool::open(&config)
+                .await
+                .map(|_| ())
+                .map_err(driver_error),
+            DatabaseType::BigQuery => BigQueryPool::open(&config)
+                .await
+                .map(|_| ())
+                .map_err(driver_error),
+            DatabaseType::Odbc => OdbcPool::open(&config)
+                .await
+                .map(|_| ())
+                .map_err(driver_error),
         };
 
-        let columns: Vec<(String, String, String, bool, Option<String>, bool)> =
-            match sqlx::query_as(columns_sql)
-                .bind(&db_name)
-                .fetch_all(pool)
-                .await
-            {
-                Ok(c) => c,
-                Err(e) => return DbResponse::Error(e.to_string()),
-            };
+        match result {
+            Ok(_) => DbResponse::TestResult(Ok(())),
+            Err(e) => DbResponse::TestResult(Err(e.to_string())),
+        }
+    }
 
-        let indexes: Vec<(String, String, String, bool, bool, String)> =
-            match sqlx::query_as(indexes_sql)
-                .bind(&db_name)
-                .fetch_all(pool)
-                .await
-            {
-                Ok(i) => i,
-                Err(e) => return DbResponse::Error(e.to_string()),
+    async fn connect(&mut self, config: ConnectionConfig) -> DbResponse {
+        // Rolls back a manual transaction left open on the previous connection
+        self.close_transaction().await;
+        let db_type = config.db_type;
+        let original_config = config.clone();
+        // A driver string can carry a password, so it is never shown as the database name
+        let database =
+            if db_type == DatabaseType::Odbc && config.odbc_mode == OdbcMode::DriverString {
+                String::new()
+            } else {
+                config.database.clone()
             };
+        let schema = config.schema.clone();
+        self.plan_budget = config.plan_budget;
+        self.query_slots = Arc::new(Semaphore::new(config.pool.query_concurrency()));
+        let statement_timeout_secs = config
+            .pool
+            .statement_timeout_secs
+            .or(self.default_statement_timeout_secs);
+        self.statement_timeout_secs = statement_timeout_secs.filter(|&secs| {
+            secs > 0 && matches!(db_type, DatabaseType::PostgreSQL | DatabaseType::MySQL)
+        });
 
-        let constraints: Vec<MySqlConstraintRow> = match sqlx::query_as(constraints_sql)
-            .bind(&db_name)
-            .fetch_all(pool)
-            .await
-        {
-            Ok(c) => c,
-            Err(e) => return DbResponse::Error(e.to_string()),
-        };
-
-        let mut table_infos: Vec<TableInfo> = tables
-            .into_iter()
-            .map(|(name, row_estimate)| TableInfo {
-                name,
-                row_estimate,
-                columns: Vec::new(),
-                indexes: Vec::new(),
-                constraints: Vec::new(),
-            })
-            .collect();
+        // Session variables belong to the previous server
+        if let Ok(mut settings) = self.session_settings.write() {
+            settings.clear();
+        }
 
-        for (table_name, col_name, data_type, nullable, default_value, is_pk) in columns {
-            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
-                table.columns.push(ColumnInfo {
-                    name: col_name,
-                    data_type,
-                    nullable,
-                    default_value,
-                    is_primary_key: is_pk,
-                });
+        // Looked up on every connect, so an edited file is picked up on reconnect
+        let config = config.with_file_password();
+        let (config, tunnel) = match ssh_tunnel::forward(config).await {
+            Ok(forwarded) => forwarded,
+            Err(TunnelError::UnknownHostKey(key)) => {
+                return DbResponse::UnknownHostKey {
+                    key,
+                    request: Box::new(DbRequest::Connect(original_config)),
+                }
             }
-        }
+            Err(TunnelError::Failed(e)) => {
+                return DbResponse::ConnectionFailed(format!("SSH tunnel: {}", e))
+            }
+        };
 
-        for (table_name, name, columns_str, is_unique, is_primary, index_type) in indexes {
-            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
-                table.indexes.push(IndexInfo {
-                    name,
-                    columns: columns_str.split(',').map(|s| s.to_string()).collect(),
-                    is_unique,
-                    is_primary,
-                    index_type,
-                });
+        let result = match db_type {
+            DatabaseType::PostgreSQL => {
+                let search_path = if schema.is_empty() {
+                    None
+                } else {
+                    Some(format!("SET search_path TO \"{}\", public", schema))
+                };
+                let statement_timeout = statement_timeout_secs
+                    .map(|secs| format!("SET statement_timeout = {}", secs * 1000));
+                let on_connect = self.session_settings.clone();
+                config
+                    .pool
+                    .apply(PgPoolOptions::new())
+                    .after_connect(move |conn, _meta| {
+                        let mut statements: Vec<String> = search_path.iter().cloned().collect();
+                        statements.extend(statement_timeout.clone());
+                        statements.extend(session_statements(&on_connect));
+                        Box::pin(async move {
+                            for sql in statements {
+                                sqlx::query(&sql).execute(&mut *conn).await?;
+                            }
+                            Ok(())
+                        })
+                    })
+                    .connect(&config.connection_string())
+                    .await
+                    .map(DbPool::Postgres)
             }
-        }
+            DatabaseType::MySQL => {
+                let statement_timeout = statement_timeout_secs
+                    .map(|secs| format!("SET SESSION max_execution_time = {}", secs * 1000));
+                let on_connect = self.session_settings.clone();
+                config
+                    .pool
+                    .apply(MySqlPoolOptions::new())
+                    .after_connect(move |conn, _meta| {
+                        let mut statements: Vec<String> =
+                            statement_timeout.iter().cloned().collect();
+                        statements.extend(session_statements(&on_connect));
+                        Box::pin(async move {
+                            for sql in statements {
+                                sqlx::query(&sql).execute(&mut *conn).await?;
+                            }
+                            Ok(())
+                        })
+                    })
+                    .connect(&config.connection_string())
+                    .await
+                    .map(DbPool::MySQL)
+            }
+            DatabaseType::SQLite => {
+                let on_connect = self.session_settings.clone();
+                config
+                    .pool
+                    .apply(SqlitePoolOptions::new())
+                    .after_connect(move |conn, _meta| {
+                        let statements = session_statements(&on_connect);
+                        Box::pin(async move {
+                            for sql in statements {
+                                sqlx::query(&sql).execute(&mut *conn).await?;
+                            }
+                            Ok(())
+                        })
+                    })
+                    .connect_with(sqlite_options(&config))
+                    .await
+                    .map(DbPool::SQLite)
+            }
+            DatabaseType::DuckDB => DuckDbPool::open(&config.database)
+                .await
+                .map(DbPool::DuckDB)
+                .map_err(driver_error),
+            DatabaseType::ClickHouse => ClickHousePool::open(&config)
+                .await
+                .map(DbPool::ClickHouse)
+                .map_err(driver_error),
+            DatabaseType::BigQuery => BigQueryPool::open(&config)
+                .await
+                .map(DbPool::BigQuery)
+                .map_err(driver_error),
+            DatabaseType::Odbc => OdbcPool::open(&config)
+                .await
+                .map(DbPool::Odbc)
+                .map_err(driver_error),
+        };
 
-        for (
-            table_name,
-            name,
-            constraint_type,
-            columns_str,
-            foreign_table,
-            foreign_columns_str,
-            check_clause,
-        ) in constraints
-        {
-            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
-                table.constraints.push(ConstraintInfo {
-                    name,
-                    constraint_type,
-                    columns: columns_str
-                        .map(|s| s.split(',').map(|c| c.to_string()).collect())
-                        .unwrap_or_default(),
-                    foreign_table,
-                    foreign_columns: foreign_columns_str
-                        .map(|s| s.split(',').map(|c| c.to_string()).collect()),
-                    check_clause,
-                });
+        match result {
+            Ok(pool) => {
+                let (dialect, server_version) = detect_server(&pool).await;
+                self.dialect = dialect;
+                self.pool = Some(pool);
+                self.last_config = Some(original_config);
+                self.tunnel = tunnel;
+                self.db_type = Some(db_type);
+                self.schema = if schema.is_empty() {
+                    None
+                } else {
+                    Some(schema)
+                };
+                DbResponse::Connected(db_type, database, self.dialect, server_version)
             }
+            Err(e) => DbResponse::ConnectionFailed(e.to_string()),
         }
-
-        DbResponse::Schema(SchemaInfo {
-            tables: table_infos,
-            views,
-        })
     }
 
-    async fn fetch_table_details(&self, table_name: &str) -> DbResponse {
-        match (&self.pool, self.db_type) {
+    async fn fetch_schema(&mut self) -> DbResponse {
+        let resp = match (&self.pool, self.db_type) {
             (Some(DbPool::Postgres(pool)), Some(DatabaseType::PostgreSQL)) => {
-                self.fetch_table_details_postgres(pool, table_name).await
+                self.fetch_schema_postgres(pool).await
             }
             (Some(DbPool::MySQL(pool)), Some(DatabaseType::MySQL)) => {
-                self.fetch_table_details_mysql(pool, table_name).await
+                self.fetch_schema_mysql(pool).await
             }
-            _ => DbResponse::Error("Not connected".into()),
-        }
-    }
-
-    async fn fetch_table_details_postgres(&self, pool: &PgPool, table_name: &str) -> DbResponse {
-        let columns_sql = r#"
+            (Some(DbPool::SQLite(pool)), Some(DatabaseType::SQLite)) => {
+                self.fetch_schema_sqlite(pool).await
+            }
+            (Some(DbPool::DuckDB(pool)), Some(DatabaseType::DuckDB)) => {
+                match pool.schema(self.schema.clone()).await {
+                    Ok(schema) => DbResponse::Schema(schema),
+                    Err(e) => DbResponse::Error(e),
+                }
+            }
+            (Some(DbPool::ClickHouse(pool)), Some(DatabaseType::ClickHouse)) => {
+                match pool.schema(self.schema.clone()).await {
+                    Ok(schema) => DbResponse::Schema(schema),
+                    Err(e) => DbResponse::Error(e),
+                }
+            }
+            (Some(DbPool::BigQuery(pool)), Some(DatabaseType::BigQuery)) => {
+                match pool.schema().await {
+                    Ok(schema) => DbResponse::Schema(schema),
+                    Err(e) => DbResponse::Error(e),
+                }
+            }
+            (Some(DbPool::Odbc(pool)), Some(DatabaseType::Odbc)) => {
+                match pool.schema(self.schema.clone()).await {
+                    Ok(schema) => DbResponse::Schema(schema),
+                    Err(e) => DbResponse::Error(e),
+                }
+            }
+            _ => DbResponse::Error("Not connected".into()),
+        };
+        if let DbResponse::Schema(ref schema) = resp {
+            self.cached_schema = Some(Arc::new(schema.clone()));
+        }
+        resp
+    }
+
+    async fn fetch_schema_postgres(&self, pool: &PgPool) -> DbResponse {
+        // Build schema filter conditions
+        let schema_filter = match &self.schema {
+            Some(schema) => format!("AND t.table_schema = '{}'", schema),
+            None => "AND t.table_schema NOT IN ('pg_catalog', 'information_schema')".to_string(),
+        };
+        let views_schema_filter = match &self.schema {
+            Some(schema) => format!("WHERE table_schema = '{}'", schema),
+            None => "WHERE table_schema NOT IN ('pg_catalog', 'information_schema')".to_string(),
+        };
+        let columns_schema_filter = match &self.schema {
+            Some(schema) => format!("WHERE c.table_schema = '{}'", schema),
+            None => "WHERE c.table_schema NOT IN ('pg_catalog', 'information_schema')".to_string(),
+        };
+        let namespace_filter = match &self.schema {
+            Some(schema) => format!("WHERE ns.nspname = '{}'", schema),
+            None => "WHERE ns.nspname NOT IN ('pg_catalog', 'information_schema')".to_string(),
+        };
+        let constraints_schema_filter = match &self.schema {
+            Some(schema) => format!("WHERE tc.table_schema = '{}'", schema),
+            None => "WHERE tc.table_schema NOT IN ('pg_catalog', 'information_schema')".to_string(),
+        };
+
+        // CockroachDB keeps no pg_stat counters; its optimizer statistics hold the estimates
+        let tables_sql = if self.dialect == SqlDialect::CockroachDB {
+            format!(
+                r#"
+            SELECT
+                t.table_name::TEXT,
+                COALESCE(s.estimated_row_count, 0)::BIGINT as row_estimate,
+                NULL::TEXT as collation,
+                obj_description(
+                    (quote_ident(t.table_schema) || '.' || quote_ident(t.table_name))::regclass,
+                    'pg_class'
+                ) as comment
+            FROM information_schema.tables t
+            LEFT JOIN crdb_internal.tables ct
+                ON ct.database_name = current_database()
+                AND ct.schema_name = t.table_schema
+                AND ct.name = t.table_name
+            LEFT JOIN crdb_internal.table_row_statistics s ON s.table_id = ct.table_id
+            WHERE t.table_type = 'BASE TABLE'
+              {}
+            ORDER BY t.table_schema, t.table_name
+        "#,
+                schema_filter
+            )
+        } else {
+            format!(
+                r#"
+            SELECT 
+                t.table_name::TEXT,
+                COALESCE(s.n_live_tup, 0)::BIGINT as row_estimate,
+                NULL::TEXT as collation,
+                obj_description(
+                    (quote_ident(t.table_schema) || '.' || quote_ident(t.table_name))::regclass,
+                    'pg_class'
+                ) as comment
+            FROM information_schema.tables t
+            LEFT JOIN pg_stat_user_tables s 
+                ON t.table_name = s.relname AND t.table_schema = s.schemaname
+            WHERE t.table_type = 'BASE TABLE' 
+              {}
+            ORDER BY t.table_schema, t.table_name
+        "#,
+                schema_filter
+            )
+        };
+
+        let views_sql = format!(
+            r#"
+            SELECT table_name::TEXT 
+            FROM information_schema.views 
+            {}
+            ORDER BY table_name
+        "#,
+            views_schema_filter
+        );
+
+        let columns_sql = format!(
+            r#"
             SELECT 
+                c.table_name::TEXT,
                 c.column_name::TEXT,
                 c.data_type::TEXT,
                 (c.is_nullable = 'YES') as nullable,
                 c.column_default::TEXT,
-                COALESCE(pk.is_pk, false) as is_primary_key
+                COALESCE(pk.is_pk, false) as is_primary_key,
+                CASE WHEN c.is_identity = 'YES' THEN c.identity_generation::TEXT END,
+                CASE WHEN c.is_generated = 'ALWAYS' THEN c.generation_expression::TEXT END,
+                (
+                    SELECT array_agg(e.enumlabel::TEXT ORDER BY e.enumsortorder)
+                    FROM pg_type t
+                    JOIN pg_namespace tn ON tn.oid = t.typnamespace
+                    JOIN pg_enum e ON e.enumtypid = t.oid
+                    WHERE t.typname = c.udt_name AND tn.nspname = c.udt_schema
+                ) as enum_values,
+                c.collation_name::TEXT,
+                col_description(
+                    (quote_ident(c.table_schema) || '.' || quote_ident(c.table_name))::regclass,
+                    c.ordinal_position::INT
+                ) as comment
             FROM information_schema.columns c
             LEFT JOIN (
-                SELECT kcu.column_name, true as is_pk
+                SELECT kcu.table_name, kcu.column_name, true as is_pk
                 FROM information_schema.table_constraints tc
                 JOIN information_schema.key_column_usage kcu 
                     ON tc.constraint_name = kcu.constraint_name
-                WHERE tc.constraint_type = 'PRIMARY KEY' AND tc.table_name = $1
-            ) pk ON c.column_name = pk.column_name
-            WHERE c.table_name = $1 
-              AND c.table_schema NOT IN ('pg_catalog', 'information_schema')
-            ORDER BY c.ordinal_position
-        "#;
+                    AND tc.table_schema = kcu.table_schema
+                    AND tc.table_name = kcu.table_name
+                WHERE tc.constraint_type = 'PRIMARY KEY'
+            ) pk ON c.table_name = pk.table_name AND c.column_name = pk.column_name
+            {}
+            ORDER BY c.table_name, c.ordinal_position
+        "#,
+            columns_schema_filter
+        );
 
-        let indexes_sql = r#"
+        let indexes_sql = if self.dialect == SqlDialect::CockroachDB {
+            let filter = match &self.schema {
+                Some(schema) => format!("table_schema = '{}'", schema),
+                None => {
+                    "table_schema NOT IN ('pg_catalog', 'information_schema', 'crdb_internal', \
+                         'pg_extension')"
+                        .to_string()
+                }
+            };
+            cockroach_indexes_sql(&filter, true)
+        } else {
+            format!(
+                r#"
             SELECT 
+                t.relname::TEXT as table_name,
                 i.relname::TEXT as index_name,
                 COALESCE(array_agg(a.attname::TEXT ORDER BY x.n), ARRAY[]::TEXT[]) as columns,
                 ix.indisunique as is_unique,
@@ -678,274 +1904,732 @@ impl DbWorker {
                 COALESCE(am.amname::TEXT, 'unknown') as index_type
             FROM pg_index ix
             JOIN pg_class t ON t.oid = ix.indrelid
+            JOIN pg_namespace ns ON ns.oid = t.relnamespace
             JOIN pg_class i ON i.oid = ix.indexrelid
             LEFT JOIN pg_am am ON am.oid = i.relam
             CROSS JOIN LATERAL unnest(ix.indkey) WITH ORDINALITY AS x(attnum, n)
             JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = x.attnum
-            WHERE t.relname = $1
-            GROUP BY i.relname, ix.indisunique, ix.indisprimary, am.amname
-            ORDER BY i.relname
-        "#;
+            {}
+            GROUP BY t.relname, i.relname, ix.indisunique, ix.indisprimary, am.amname
+            ORDER BY t.relname, i.relname
+        "#,
+                namespace_filter
+            )
+        };
 
-        let constraints_sql = r#"
+        let constraints_sql = format!(
+            r#"
             SELECT 
+                tc.table_name::TEXT,
                 tc.constraint_name::TEXT,
                 tc.constraint_type::TEXT,
                 COALESCE(
-                    array_agg(DISTINCT kcu.column_name::TEXT) FILTER (WHERE kcu.column_name IS NOT NULL),
+                    (
+                        SELECT array_agg(kcu.column_name::TEXT ORDER BY kcu.ordinal_position)
+                        FROM information_schema.key_column_usage kcu
+                        WHERE kcu.constraint_name = tc.constraint_name
+                            AND kcu.table_schema = tc.table_schema
+                            AND kcu.table_name = tc.table_name
+                    ),
                     ARRAY[]::TEXT[]
                 ) as columns,
-                ccu.table_name::TEXT as foreign_table,
+                (
+                    SELECT ccu.table_name::TEXT
+                    FROM information_schema.constraint_column_usage ccu
+                    WHERE ccu.constraint_name = tc.constraint_name
+                        AND ccu.constraint_schema = tc.constraint_schema
+                        AND tc.constraint_type = 'FOREIGN KEY'
+                    LIMIT 1
+                ) as foreign_table,
                 COALESCE(
-                    array_agg(DISTINCT ccu.column_name::TEXT) FILTER (WHERE ccu.column_name IS NOT NULL AND tc.constraint_type = 'FOREIGN KEY'),
+                    (
+                        SELECT array_agg(rk.column_name::TEXT ORDER BY kcu.ordinal_position)
+                        FROM information_schema.key_column_usage kcu
+                        JOIN information_schema.referential_constraints rc
+                            ON rc.constraint_name = kcu.constraint_name
+                            AND rc.constraint_schema = kcu.constraint_schema
+                        JOIN information_schema.key_column_usage rk
+                            ON rk.constraint_name = rc.unique_constraint_name
+                            AND rk.constraint_schema = rc.unique_constraint_schema
+                            AND rk.ordinal_position = kcu.position_in_unique_constraint
+                        WHERE kcu.constraint_name = tc.constraint_name
+                            AND kcu.table_schema = tc.table_schema
+                            AND kcu.table_name = tc.table_name
+                    ),
                     ARRAY[]::TEXT[]
                 ) as foreign_columns,
                 cc.check_clause::TEXT
             FROM information_schema.table_constraints tc
-            LEFT JOIN information_schema.key_column_usage kcu 
-                ON tc.constraint_name = kcu.constraint_name
-                AND tc.table_schema = kcu.table_schema
-            LEFT JOIN information_schema.constraint_column_usage ccu 
-                ON tc.constraint_name = ccu.constraint_name 
-                AND tc.constraint_type = 'FOREIGN KEY'
             LEFT JOIN information_schema.check_constraints cc 
                 ON tc.constraint_name = cc.constraint_name
-            WHERE tc.table_name = $1
-              AND tc.table_schema NOT IN ('pg_catalog', 'information_schema')
-            GROUP BY tc.constraint_name, tc.constraint_type, ccu.table_name, cc.check_clause
-            ORDER BY tc.constraint_type, tc.constraint_name
-        "#;
+                AND tc.constraint_schema = cc.constraint_schema
+            {}
+            ORDER BY tc.table_name, tc.constraint_type, tc.constraint_name
+        "#,
+            constraints_schema_filter
+        );
 
-        let columns: Vec<ColumnInfo> =
-            match sqlx::query_as::<_, (String, String, bool, Option<String>, bool)>(columns_sql)
-                .bind(table_name)
-                .fetch_all(pool)
-                .await
-            {
-                Ok(rows) => rows
-                    .into_iter()
-                    .map(
-                        |(name, data_type, nullable, default_value, is_primary_key)| ColumnInfo {
-                            name,
-                            data_type,
-                            nullable,
-                            default_value,
-                            is_primary_key,
-                        },
-                    )
-                    .collect(),
+        let tables: Vec<(String, i64, Option<String>, Option<String>)> =
+            match sqlx::query_as(&tables_sql).fetch_all(pool).await {
+                Ok(t) => t,
                 Err(e) => return DbResponse::Error(e.to_string()),
             };
 
-        let indexes: Vec<IndexInfo> =
-            match sqlx::query_as::<_, (String, Vec<String>, bool, bool, String)>(indexes_sql)
-                .bind(table_name)
-                .fetch_all(pool)
-                .await
-            {
-                Ok(rows) => rows
-                    .into_iter()
-                    .map(
-                        |(name, columns, is_unique, is_primary, index_type)| IndexInfo {
-                            name,
-                            columns,
-                            is_unique,
-                            is_primary,
-                            index_type,
-                        },
-                    )
-                    .collect(),
+        let views: Vec<String> = match sqlx::query_scalar(&views_sql).fetch_all(pool).await {
+            Ok(v) => v,
+            Err(e) => return DbResponse::Error(e.to_string()),
+        };
+
+        let columns: Vec<PostgresColumnRow> =
+            match sqlx::query_as(&columns_sql).fetch_all(pool).await {
+                Ok(c) => c,
                 Err(e) => return DbResponse::Error(e.to_string()),
             };
 
-        let constraints: Vec<ConstraintInfo> = match sqlx::query_as::<
-            _,
-            (
-                String,
-                String,
-                Vec<String>,
-                Option<String>,
-                Vec<String>,
-                Option<String>,
-            ),
-        >(constraints_sql)
-        .bind(table_name)
-        .fetch_all(pool)
-        .await
-        {
-            Ok(rows) => rows
-                .into_iter()
-                .map(
-                    |(
-                        name,
-                        constraint_type,
-                        columns,
-                        foreign_table,
-                        foreign_columns,
-                        check_clause,
-                    )| {
-                        let foreign_columns = if foreign_columns.is_empty() {
-                            None
-                        } else {
-                            Some(foreign_columns)
-                        };
+        let indexes: Vec<(String, String, Vec<String>, bool, bool, String)> =
+            match sqlx::query_as(&indexes_sql).fetch_all(pool).await {
+                Ok(i) => i,
+                Err(e) => return DbResponse::Error(e.to_string()),
+            };
 
-                        ConstraintInfo {
-                            name,
-                            constraint_type,
-                            columns,
-                            foreign_table,
-                            foreign_columns,
-                            check_clause,
-                        }
-                    },
-                )
-                .collect(),
-            Err(e) => return DbResponse::Error(e.to_string()),
-        };
+        let constraints: Vec<PostgresConstraintRow> =
+            match sqlx::query_as(&constraints_sql).fetch_all(pool).await {
+                Ok(c) => c,
+                Err(e) => return DbResponse::Error(e.to_string()),
+            };
 
-        DbResponse::TableDetails(TableInfo {
-            name: table_name.to_string(),
-            columns,
-            indexes,
-            constraints,
-            row_estimate: 0,
-        })
-    }
+        let mut table_infos: Vec<TableInfo> = tables
+            .into_iter()
+            .map(|(name, row_estimate, collation, comment)| TableInfo {
+                name,
+                row_estimate,
+                collation,
+                comment,
+                columns: Vec::new(),
+                indexes: Vec::new(),
+                constraints: Vec::new(),
+            })
+            .collect();
 
-    async fn fetch_table_details_mysql(&self, pool: &MySqlPool, table_name: &str) -> DbResponse {
-        let db_name: Option<String> = match sqlx::query_scalar("SELECT DATABASE()")
-            .fetch_one(pool)
-            .await
+        for (
+            table_name,
+            col_name,
+            data_type,
+            nullable,
+            default_value,
+            is_pk,
+            identity_generation,
+            generation_expression,
+            enum_values,
+            collation,
+            comment,
+        ) in columns
         {
-            Ok(name) => name,
-            Err(e) => return DbResponse::Error(e.to_string()),
-        };
-
-        let db_name = match db_name {
-            Some(n) => n,
-            None => return DbResponse::Error("No database selected".into()),
-        };
+            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
+                table.columns.push(ColumnInfo {
+                    name: col_name,
+                    data_type,
+                    nullable,
+                    default_value,
+                    is_primary_key: is_pk,
+                    identity_generation,
+                    generation_expression,
+                    enum_values: enum_values.unwrap_or_default(),
+                    collation,
+                    comment,
+                    column_type: None,
+                    extra: None,
+                });
+            }
+        }
 
-        let columns_sql = r#"
-            SELECT 
-                COLUMN_NAME as name,
-                DATA_TYPE as data_type,
-                (IS_NULLABLE = 'YES') as nullable,
-                COLUMN_DEFAULT as default_value,
-                (COLUMN_KEY = 'PRI') as is_primary_key
-            FROM information_schema.COLUMNS
-            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
-            ORDER BY ORDINAL_POSITION
+        for (table_name, name, columns, is_unique, is_primary, index_type) in indexes {
+            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
+                table.indexes.push(IndexInfo {
+                    name,
+                    columns,
+                    is_unique,
+                    is_primary,
+                    index_type,
+                });
+            }
+        }
+
+        for (
+            table_name,
+            name,
+            constraint_type,
+            columns,
+            foreign_table,
+            foreign_columns,
+            check_clause,
+        ) in constraints
+        {
+            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
+                table.constraints.push(ConstraintInfo {
+                    name,
+                    constraint_type,
+                    columns,
+                    foreign_table,
+                    foreign_columns: if foreign_columns.is_empty() {
+                        None
+                    } else {
+                        Some(foreign_columns)
+                    },
+                    check_clause,
+                });
+            }
+        }
+
+        DbResponse::Schema(SchemaInfo {
+            tables: table_infos,
+            views,
+            sequences: Vec::new(),
+        })
+    }
+
+    async fn fetch_schema_mysql(&self, pool: &MySqlPool) -> DbResponse {
+        let db_name_sql = "SELECT DATABASE()";
+        let db_name: Option<String> = match sqlx::query_scalar(db_name_sql).fetch_one(pool).await {
+            Ok(name) => name,
+            Err(e) => return DbResponse::Error(e.to_string()),
+        };
+
+        let db_name = match db_name {
+            Some(n) => n,
+            None => return DbResponse::Error("No database selected".into()),
+        };
+
+        // MariaDB lists system-versioned tables under their own type
+        let tables_sql = r#"
+            SELECT 
+                t.TABLE_NAME as table_name,
+                COALESCE(t.TABLE_ROWS, 0) as row_estimate,
+                t.TABLE_COLLATION as collation,
+                NULLIF(t.TABLE_COMMENT, '') as comment
+            FROM information_schema.TABLES t
+            WHERE t.TABLE_SCHEMA = ?
+              AND t.TABLE_TYPE IN ('BASE TABLE', 'SYSTEM VERSIONED')
+            ORDER BY t.TABLE_NAME
+        "#;
+
+        let views_sql = r#"
+            SELECT TABLE_NAME 
+            FROM information_schema.VIEWS 
+            WHERE TABLE_SCHEMA = ?
+            ORDER BY TABLE_NAME
         "#;
 
+        let columns_sql = format!(
+            r#"
+            SELECT 
+                c.TABLE_NAME as table_name,
+                c.COLUMN_NAME as column_name,
+                c.DATA_TYPE as data_type,
+                (c.IS_NULLABLE = 'YES') as nullable,
+                {} as default_value,
+                (c.COLUMN_KEY = 'PRI') as is_primary_key,
+                IF(c.EXTRA LIKE '%auto_increment%', 'auto_increment', NULL) as identity_generation,
+                NULLIF(c.GENERATION_EXPRESSION, '') as generation_expression,
+                IF(c.DATA_TYPE = 'enum', c.COLUMN_TYPE, NULL) as enum_values,
+                c.COLLATION_NAME as collation,
+                NULLIF(c.COLUMN_COMMENT, '') as comment,
+                c.COLUMN_TYPE as column_type,
+                NULLIF(c.EXTRA, '') as extra
+            FROM information_schema.COLUMNS c
+            WHERE c.TABLE_SCHEMA = ?
+            ORDER BY c.TABLE_NAME, c.ORDINAL_POSITION
+        "#,
+            self.mysql_column_default("c.COLUMN_DEFAULT")
+        );
+
         let indexes_sql = r#"
             SELECT 
+                TABLE_NAME as table_name,
                 INDEX_NAME as name,
                 GROUP_CONCAT(COLUMN_NAME ORDER BY SEQ_IN_INDEX) as columns,
                 (NON_UNIQUE = 0) as is_unique,
                 (INDEX_NAME = 'PRIMARY') as is_primary,
                 INDEX_TYPE as index_type
             FROM information_schema.STATISTICS
-            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
-            GROUP BY INDEX_NAME, NON_UNIQUE, INDEX_TYPE
-            ORDER BY INDEX_NAME
+            WHERE TABLE_SCHEMA = ?
+            GROUP BY TABLE_NAME, INDEX_NAME, NON_UNIQUE, INDEX_TYPE
+            ORDER BY TABLE_NAME, INDEX_NAME
         "#;
 
         let constraints_sql = r#"
             SELECT 
+                tc.TABLE_NAME as table_name,
                 tc.CONSTRAINT_NAME as name,
                 tc.CONSTRAINT_TYPE as constraint_type,
-                GROUP_CONCAT(DISTINCT kcu.COLUMN_NAME) as columns,
+                GROUP_CONCAT(kcu.COLUMN_NAME ORDER BY kcu.ORDINAL_POSITION) as columns,
                 kcu.REFERENCED_TABLE_NAME as foreign_table,
-                GROUP_CONCAT(DISTINCT kcu.REFERENCED_COLUMN_NAME) as foreign_columns,
+                GROUP_CONCAT(kcu.REFERENCED_COLUMN_NAME ORDER BY kcu.ORDINAL_POSITION) as foreign_columns,
                 NULL as check_clause
             FROM information_schema.TABLE_CONSTRAINTS tc
             LEFT JOIN information_schema.KEY_COLUMN_USAGE kcu 
                 ON tc.CONSTRAINT_NAME = kcu.CONSTRAINT_NAME 
                 AND tc.TABLE_SCHEMA = kcu.TABLE_SCHEMA
                 AND tc.TABLE_NAME = kcu.TABLE_NAME
-            WHERE tc.TABLE_SCHEMA = ? AND tc.TABLE_NAME = ?
-            GROUP BY tc.CONSTRAINT_NAME, tc.CONSTRAINT_TYPE, kcu.REFERENCED_TABLE_NAME
-            ORDER BY tc.CONSTRAINT_TYPE, tc.CONSTRAINT_NAME
+            WHERE tc.TABLE_SCHEMA = ?
+            GROUP BY tc.TABLE_NAME, tc.CONSTRAINT_NAME, tc.CONSTRAINT_TYPE, kcu.REFERENCED_TABLE_NAME
+            ORDER BY tc.TABLE_NAME, tc.CONSTRAINT_TYPE, tc.CONSTRAINT_NAME
         "#;
 
-        let columns: Vec<ColumnInfo> =
-            match sqlx::query_as::<_, (String, String, bool, Option<String>, bool)>(columns_sql)
+        let tables: Vec<(String, i64, Option<String>, Option<String>)> =
+            match sqlx::query_as(tables_sql)
                 .bind(&db_name)
-                .bind(table_name)
                 .fetch_all(pool)
                 .await
             {
-                Ok(rows) => rows
-                    .into_iter()
-                    .map(
-                        |(name, data_type, nullable, default_value, is_primary_key)| ColumnInfo {
-                            name,
-                            data_type,
-                            nullable,
-                            default_value,
-                            is_primary_key,
-                        },
-                    )
-                    .collect(),
+                Ok(t) => t,
                 Err(e) => return DbResponse::Error(e.to_string()),
             };
 
-        let indexes: Vec<IndexInfo> =
-            match sqlx::query_as::<_, (String, String, bool, bool, String)>(indexes_sql)
+        let views: Vec<String> = match sqlx::query_scalar(views_sql)
+            .bind(&db_name)
+            .fetch_all(pool)
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => return DbResponse::Error(e.to_string()),
+        };
+
+        let sequences: Vec<String> = if self.dialect == SqlDialect::MariaDB {
+            match sqlx::query_scalar(
+                "SELECT TABLE_NAME FROM information_schema.TABLES \
+                 WHERE TABLE_SCHEMA = ? AND TABLE_TYPE = 'SEQUENCE' ORDER BY TABLE_NAME",
+            )
+            .bind(&db_name)
+            .fetch_all(pool)
+            .await
+            {
+                Ok(s) => s,
+                Err(e) => return DbResponse::Error(e.to_string()),
+            }
+        } else {
+            Vec::new()
+        };
+
+        let columns: Vec<MySqlColumnRow> = match sqlx::query_as(&columns_sql)
+            .bind(&db_name)
+            .fetch_all(pool)
+            .await
+        {
+            Ok(c) => c,
+            Err(e) => return DbResponse::Error(e.to_string()),
+        };
+
+        let indexes: Vec<(String, String, String, bool, bool, String)> =
+            match sqlx::query_as(indexes_sql)
                 .bind(&db_name)
-                .bind(table_name)
                 .fetch_all(pool)
                 .await
             {
-                Ok(rows) => rows
-                    .into_iter()
-                    .map(
-                        |(name, columns_str, is_unique, is_primary, index_type)| IndexInfo {
-                            name,
-                            columns: columns_str.split(',').map(|s| s.to_string()).collect(),
-                            is_unique,
-                            is_primary,
-                            index_type,
-                        },
-                    )
-                    .collect(),
+                Ok(i) => i,
                 Err(e) => return DbResponse::Error(e.to_string()),
             };
 
-        let constraints: Vec<ConstraintInfo> = match sqlx::query_as::<
-            _,
-            (
-                String,
-                String,
-                Option<String>,
-                Option<String>,
-                Option<String>,
-                Option<String>,
-            ),
-        >(constraints_sql)
-        .bind(&db_name)
-        .bind(table_name)
+        let constraints: Vec<MySqlConstraintRow> = match sqlx::query_as(constraints_sql)
+            .bind(&db_name)
+            .fetch_all(pool)
+            .await
+        {
+            Ok(c) => c,
+            Err(e) => return DbResponse::Error(e.to_string()),
+        };
+
+        let mut table_infos: Vec<TableInfo> = tables
+            .into_iter()
+            .map(|(name, row_estimate, collation, comment)| TableInfo {
+                name,
+                row_estimate,
+                collation,
+                comment,
+                columns: Vec::new(),
+                indexes: Vec::new(),
+                constraints: Vec::new(),
+            })
+            .collect();
+
+        for (
+            table_name,
+            col_name,
+            data_type,
+            nullable,
+            default_value,
+            is_pk,
+            identity_generation,
+            generation_expression,
+            enum_values,
+            collation,
+            comment,
+            column_type,
+            extra,
+        ) in columns
+        {
+            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
+                table.columns.push(ColumnInfo {
+                    name: col_name,
+                    data_type,
+                    nullable,
+                    default_value,
+                    is_primary_key: is_pk,
+                    identity_generation,
+                    generation_expression,
+                    enum_values: enum_values
+                        .as_deref()
+                        .map(parse_enum_values)
+                        .unwrap_or_default(),
+                    collation,
+                    comment,
+                    column_type,
+                    extra,
+                });
+            }
+        }
+
+        for (table_name, name, columns_str, is_unique, is_primary, index_type) in indexes {
+            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
+                table.indexes.push(IndexInfo {
+                    name,
+                    columns: columns_str.split(',').map(|s| s.to_string()).collect(),
+                    is_unique,
+                    is_primary,
+                    index_type,
+                });
+            }
+        }
+
+        for (
+            table_name,
+            name,
+            constraint_type,
+            columns_str,
+            foreign_table,
+            foreign_columns_str,
+            check_clause,
+        ) in constraints
+        {
+            if let Some(table) = table_infos.iter_mut().find(|t| t.name == table_name) {
+                table.constraints.push(ConstraintInfo {
+                    name,
+                    constraint_type,
+                    columns: columns_str
+                        .map(|s| s.split(',').map(|c| c.to_string()).collect())
+                        .unwrap_or_default(),
+                    foreign_table,
+                    foreign_columns: foreign_columns_str
+                        .map(|s| s.split(',').map(|c| c.to_string()).collect()),
+                    check_clause,
+                });
+            }
+        }
+
+        DbResponse::Schema(SchemaInfo {
+            tables: table_infos,
+            views,
+            sequences,
+        })
+    }
+
+    async fn fetch_schema_sqlite(&self, pool: &SqlitePool) -> DbResponse {
+        let objects: Vec<(String, String)> = match sqlx::query_as(
+            "SELECT name, type FROM sqlite_master \
+             WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%' \
+             ORDER BY name",
+        )
         .fetch_all(pool)
         .await
         {
-            Ok(rows) => rows
-                .into_iter()
-                .map(
-                    |(
-                        name,
-                        constraint_type,
-                        columns_str,
-                        foreign_table,
-                        foreign_columns_str,
+            Ok(o) => o,
+            Err(e) => return DbResponse::Error(e.to_string()),
+        };
+
+        let mut tables = Vec::new();
+        let mut views = Vec::new();
+        for (name, kind) in objects {
+            if kind == "view" {
+                views.push(name);
+                continue;
+            }
+            match sqlite_table_info(pool, &name).await {
+                Ok(table) => tables.push(table),
+                Err(e) => return DbResponse::Error(e.to_string()),
+            }
+        }
+
+        DbResponse::Schema(SchemaInfo {
+            tables,
+            views,
+            sequences: Vec::new(),
+        })
+    }
+
+    async fn fetch_table_details(&self, table_name: &str) -> DbResponse {
+        match (&self.pool, self.db_type) {
+            (Some(DbPool::Postgres(pool)), Some(DatabaseType::PostgreSQL)) => {
+                self.fetch_table_details_postgres(pool, table_name).await
+            }
+            (Some(DbPool::MySQL(pool)), Some(DatabaseType::MySQL)) => {
+                self.fetch_table_details_mysql(pool, table_name).await
+            }
+            (Some(DbPool::SQLite(pool)), Some(DatabaseType::SQLite)) => {
+                match sqlite_table_info(pool, &super::normalize_table_name(table_name)).await {
+                    Ok(table) => DbResponse::TableDetails(table),
+                    Err(e) => DbResponse::Error(e.to_string()),
+                }
+            }
+            (Some(DbPool::DuckDB(pool)), Some(DatabaseType::DuckDB)) => {
+                let (schema, table) = match table_name.split_once('.') {
+                    Some((schema, table)) => (Some(schema.to_string()), table),
+                    None => (self.schema.clone(), table_name),
+                };
+                match pool
+                    .table_info(&super::normalize_table_name(table), schema)
+                    .await
+                {
+                    Ok(table) => DbResponse::TableDetails(table),
+                    Err(e) => DbResponse::Error(e),
+                }
+            }
+            // database.table names another database
+            (Some(DbPool::ClickHouse(pool)), Some(DatabaseType::ClickHouse)) => {
+                let (database, table) = match table_name.split_once('.') {
+                    Some((database, table)) => (Some(database.to_string()), table),
+                    None => (self.schema.clone(), table_name),
+                };
+                match pool
+                    .table_info(&super::normalize_table_name(table), database)
+                    .await
+                {
+                    Ok(table) => DbResponse::TableDetails(table),
+                    Err(e) => DbResponse::Error(e),
+                }
+            }
+            // dataset.table names another dataset
+            (Some(DbPool::BigQuery(pool)), Some(DatabaseType::BigQuery)) => {
+                match pool.table_info(table_name).await {
+                    Ok(table) => DbResponse::TableDetails(table),
+                    Err(e) => DbResponse::Error(e),
+                }
+            }
+            (Some(DbPool::Odbc(pool)), Some(DatabaseType::Odbc)) => {
+                let (schema, table) = match table_name.split_once('.') {
+                    Some((schema, table)) => (Some(schema.to_string()), table),
+                    None => (self.schema.clone(), table_name),
+                };
+                match pool
+                    .table_info(&super::normalize_table_name(table), schema)
+                    .await
+                {
+                    Ok(table) => DbResponse::TableDetails(table),
+                    Err(e) => DbResponse::Error(e),
+                }
+            }
+            _ => DbResponse::Error("Not connected".into()),
+        }
+    }
+
+    async fn fetch_table_details_postgres(&self, pool: &PgPool, table_name: &str) -> DbResponse {
+        let columns_sql = r#"
+            SELECT 
+                c.column_name::TEXT,
+                c.data_type::TEXT,
+                (c.is_nullable = 'YES') as nullable,
+                c.column_default::TEXT,
+                COALESCE(pk.is_pk, false) as is_primary_key,
+                CASE WHEN c.is_identity = 'YES' THEN c.identity_generation::TEXT END,
+                CASE WHEN c.is_generated = 'ALWAYS' THEN c.generation_expression::TEXT END,
+                (
+                    SELECT array_agg(e.enumlabel::TEXT ORDER BY e.enumsortorder)
+                    FROM pg_type t
+                    JOIN pg_namespace tn ON tn.oid = t.typnamespace
+                    JOIN pg_enum e ON e.enumtypid = t.oid
+                    WHERE t.typname = c.udt_name AND tn.nspname = c.udt_schema
+                ) as enum_values,
+                c.collation_name::TEXT,
+                col_description(
+                    (quote_ident(c.table_schema) || '.' || quote_ident(c.table_name))::regclass,
+                    c.ordinal_position::INT
+                ) as comment
+            FROM information_schema.columns c
+            LEFT JOIN (
+                SELECT kcu.column_name, true as is_pk
+                FROM information_schema.table_constraints tc
+                JOIN information_schema.key_column_usage kcu 
+                    ON tc.constraint_name = kcu.constraint_name
+                WHERE tc.constraint_type = 'PRIMARY KEY' AND tc.table_name = $1
+            ) pk ON c.column_name = pk.column_name
+            WHERE c.table_name = $1 
+              AND c.table_schema NOT IN ('pg_catalog', 'information_schema')
+            ORDER BY c.ordinal_position
+        "#;
+
+        let cockroach_indexes = cockroach_indexes_sql("table_name = $1", false);
+        let indexes_sql = if self.dialect == SqlDialect::CockroachDB {
+            cockroach_indexes.as_str()
+        } else {
+            r#"
+            SELECT 
+                i.relname::TEXT as index_name,
+                COALESCE(array_agg(a.attname::TEXT ORDER BY x.n), ARRAY[]::TEXT[]) as columns,
+                ix.indisunique as is_unique,
+                ix.indisprimary as is_primary,
+                COALESCE(am.amname::TEXT, 'unknown') as index_type
+            FROM pg_index ix
+            JOIN pg_class t ON t.oid = ix.indrelid
+            JOIN pg_class i ON i.oid = ix.indexrelid
+            LEFT JOIN pg_am am ON am.oid = i.relam
+            CROSS JOIN LATERAL unnest(ix.indkey) WITH ORDINALITY AS x(attnum, n)
+            JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = x.attnum
+            WHERE t.relname = $1
+            GROUP BY i.relname, ix.indisunique, ix.indisprimary, am.amname
+            ORDER BY i.relname
+        "#
+        };
+
+        let constraints_sql = r#"
+            SELECT 
+                tc.constraint_name::TEXT,
+                tc.constraint_type::TEXT,
+                COALESCE(
+                    (
+                        SELECT array_agg(kcu.column_name::TEXT ORDER BY kcu.ordinal_position)
+                        FROM information_schema.key_column_usage kcu
+                        WHERE kcu.constraint_name = tc.constraint_name
+                            AND kcu.table_schema = tc.table_schema
+                            AND kcu.table_name = tc.table_name
+                    ),
+                    ARRAY[]::TEXT[]
+                ) as columns,
+                (
+                    SELECT ccu.table_name::TEXT
+                    FROM information_schema.constraint_column_usage ccu
+                    WHERE ccu.constraint_name = tc.constraint_name
+                        AND ccu.constraint_schema = tc.constraint_schema
+                        AND tc.constraint_type = 'FOREIGN KEY'
+                    LIMIT 1
+                ) as foreign_table,
+                COALESCE(
+                    (
+                        SELECT array_agg(rk.column_name::TEXT ORDER BY kcu.ordinal_position)
+                        FROM information_schema.key_column_usage kcu
+                        JOIN information_schema.referential_constraints rc
+                            ON rc.constraint_name = kcu.constraint_name
+                            AND rc.constraint_schema = kcu.constraint_schema
+                        JOIN information_schema.key_column_usage rk
+                            ON rk.constraint_name = rc.unique_constraint_name
+                            AND rk.constraint_schema = rc.unique_constraint_schema
+                            AND rk.ordinal_position = kcu.position_in_unique_constraint
+                        WHERE kcu.constraint_name = tc.constraint_name
+                            AND kcu.table_schema = tc.table_schema
+                            AND kcu.table_name = tc.table_name
+                    ),
+                    ARRAY[]::TEXT[]
+                ) as foreign_columns,
+                cc.check_clause::TEXT
+            FROM information_schema.table_constraints tc
+            LEFT JOIN information_schema.check_constraints cc 
+                ON tc.constraint_name = cc.constraint_name
+                AND tc.constraint_schema = cc.constraint_schema
+            WHERE tc.table_name = $1
+              AND tc.table_schema NOT IN ('pg_catalog', 'information_schema')
+            ORDER BY tc.constraint_type, tc.constraint_name
+        "#;
+
+        let columns: Vec<ColumnInfo> =
+            match sqlx::query_as::<_, PostgresTableColumnRow>(columns_sql)
+                .bind(table_name)
+                .fetch_all(pool)
+                .await
+            {
+                Ok(rows) => rows
+                    .into_iter()
+                    .map(
+                        |(
+                            name,
+                            data_type,
+                            nullable,
+                            default_value,
+                            is_primary_key,
+                            identity_generation,
+                            generation_expression,
+                            enum_values,
+                            collation,
+                            comment,
+                        )| ColumnInfo {
+                            name,
+                            data_type,
+                            nullable,
+                            default_value,
+                            is_primary_key,
+                            identity_generation,
+                            generation_expression,
+                            enum_values: enum_values.unwrap_or_default(),
+                            collation,
+                            comment,
+                            column_type: None,
+                            extra: None,
+                        },
+                    )
+                    .collect(),
+                Err(e) => return DbResponse::Error(e.to_string()),
+            };
+
+        let indexes: Vec<IndexInfo> =
+            match sqlx::query_as::<_, (String, Vec<String>, bool, bool, String)>(indexes_sql)
+                .bind(table_name)
+                .fetch_all(pool)
+                .await
+            {
+                Ok(rows) => rows
+                    .into_iter()
+                    .map(
+                        |(name, columns, is_unique, is_primary, index_type)| IndexInfo {
+                            name,
+                            columns,
+                            is_unique,
+                            is_primary,
+                            index_type,
+                        },
+                    )
+                    .collect(),
+                Err(e) => return DbResponse::Error(e.to_string()),
+            };
+
+        let constraints: Vec<ConstraintInfo> = match sqlx::query_as::<
+            _,
+            (
+                String,
+                String,
+                Vec<String>,
+                Option<String>,
+                Vec<String>,
+                Option<String>,
+            ),
+        >(constraints_sql)
+        .bind(table_name)
+        .fetch_all(pool)
+        .await
+        {
+            Ok(rows) => rows
+                .into_iter()
+                .map(
+                    |(
+                        name,
+                        constraint_type,
+                        columns,
+                        foreign_table,
+                        foreign_columns,
                         check_clause,
                     )| {
-                        let columns = columns_str
-                            .map(|s| s.split(',').map(|c| c.to_string()).collect())
-                            .unwrap_or_default();
-                        let foreign_columns = foreign_columns_str
-                            .map(|s| s.split(',').map(|c| c.to_string()).collect());
+                        let foreign_columns = if foreign_columns.is_empty() {
+                            None
+                        } else {
+                            Some(foreign_columns)
+                        };
+
                         ConstraintInfo {
                             name,
                             constraint_type,
@@ -960,400 +2644,2498 @@ impl DbWorker {
             Err(e) => return DbResponse::Error(e.to_string()),
         };
 
-        DbResponse::TableDetails(TableInfo {
-            name: table_name.to_string(),
-            columns,
-            indexes,
-            constraints,
-            row_estimate: 0,
-        })
+        let comment: Option<String> =
+            sqlx::query_scalar("SELECT obj_description(to_regclass($1), 'pg_class')")
+                .bind(table_name)
+                .fetch_one(pool)
+                .await
+                .ok()
+                .flatten();
+
+        DbResponse::TableDetails(TableInfo {
+            name: table_name.to_string(),
+            columns,
+            indexes,
+            constraints,
+            row_estimate: 0,
+            collation: None,
+            comment,
+        })
+    }
+
+    async fn fetch_table_details_mysql(&self, pool: &MySqlPool, table_name: &str) -> DbResponse {
+        let db_name: Option<String> = match sqlx::query_scalar("SELECT DATABASE()")
+            .fetch_one(pool)
+            .await
+        {
+            Ok(name) => name,
+            Err(e) => return DbResponse::Error(e.to_string()),
+        };
+
+        let db_name = match db_name {
+            Some(n) => n,
+            None => return DbResponse::Error("No database selected".into()),
+        };
+
+        let columns_sql = format!(
+            r#"
+            SELECT 
+                COLUMN_NAME as name,
+                DATA_TYPE as data_type,
+                (IS_NULLABLE = 'YES') as nullable,
+                {} as default_value,
+                (COLUMN_KEY = 'PRI') as is_primary_key,
+                IF(EXTRA LIKE '%auto_increment%', 'auto_increment', NULL) as identity_generation,
+                NULLIF(GENERATION_EXPRESSION, '') as generation_expression,
+                IF(DATA_TYPE = 'enum', COLUMN_TYPE, NULL) as enum_values,
+                COLLATION_NAME as collation,
+                NULLIF(COLUMN_COMMENT, '') as comment,
+                COLUMN_TYPE as column_type,
+                NULLIF(EXTRA, '') as extra
+            FROM information_schema.COLUMNS
+            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+            ORDER BY ORDINAL_POSITION
+        "#,
+            self.mysql_column_default("COLUMN_DEFAULT")
+        );
+
+        let indexes_sql = r#"
+            SELECT 
+                INDEX_NAME as name,
+                GROUP_CONCAT(COLUMN_NAME ORDER BY SEQ_IN_INDEX) as columns,
+                (NON_UNIQUE = 0) as is_unique,
+                (INDEX_NAME = 'PRIMARY') as is_primary,
+                INDEX_TYPE as index_type
+            FROM information_schema.STATISTICS
+            WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
+            GROUP BY INDEX_NAME, NON_UNIQUE, INDEX_TYPE
+            ORDER BY INDEX_NAME
+        "#;
+
+        let constraints_sql = r#"
+            SELECT 
+                tc.CONSTRAINT_NAME as name,
+                tc.CONSTRAINT_TYPE as constraint_type,
+                GROUP_CONCAT(kcu.COLUMN_NAME ORDER BY kcu.ORDINAL_POSITION) as columns,
+                kcu.REFERENCED_TABLE_NAME as foreign_table,
+                GROUP_CONCAT(kcu.REFERENCED_COLUMN_NAME ORDER BY kcu.ORDINAL_POSITION) as foreign_columns,
+                NULL as check_clause
+            FROM information_schema.TABLE_CONSTRAINTS tc
+            LEFT JOIN information_schema.KEY_COLUMN_USAGE kcu 
+                ON tc.CONSTRAINT_NAME = kcu.CONSTRAINT_NAME 
+                AND tc.TABLE_SCHEMA = kcu.TABLE_SCHEMA
+                AND tc.TABLE_NAME = kcu.TABLE_NAME
+            WHERE tc.TABLE_SCHEMA = ? AND tc.TABLE_NAME = ?
+            GROUP BY tc.CONSTRAINT_NAME, tc.CONSTRAINT_TYPE, kcu.REFERENCED_TABLE_NAME
+            ORDER BY tc.CONSTRAINT_TYPE, tc.CONSTRAINT_NAME
+        "#;
+
+        let columns: Vec<ColumnInfo> = match sqlx::query_as::<_, MySqlTableColumnRow>(&columns_sql)
+            .bind(&db_name)
+            .bind(table_name)
+            .fetch_all(pool)
+            .await
+        {
+            Ok(rows) => rows
+                .into_iter()
+                .map(
+                    |(
+                        name,
+                        data_type,
+                        nullable,
+                        default_value,
+                        is_primary_key,
+                        identity_generation,
+                        generation_expression,
+                        enum_values,
+                        collation,
+                        comment,
+                        column_type,
+                        extra,
+                    )| ColumnInfo {
+                        name,
+                        data_type,
+                        nullable,
+                        default_value,
+                        is_primary_key,
+                        identity_generation,
+                        generation_expression,
+                        enum_values: enum_values
+                            .as_deref()
+                            .map(parse_enum_values)
+                            .unwrap_or_default(),
+                        collation,
+                        comment,
+                        column_type,
+                        extra,
+                    },
+                )
+                .collect(),
+            Err(e) => return DbResponse::Error(e.to_string()),
+        };
+
+        let indexes: Vec<IndexInfo> =
+            match sqlx::query_as::<_, (String, String, bool, bool, String)>(indexes_sql)
+                .bind(&db_name)
+                .bind(table_name)
+                .fetch_all(pool)
+                .await
+            {
+                Ok(rows) => rows
+                    .into_iter()
+                    .map(
+                        |(name, columns_str, is_unique, is_primary, index_type)| IndexInfo {
+                            name,
+                            columns: columns_str.split(',').map(|s| s.to_string()).collect(),
+                            is_unique,
+                            is_primary,
+                            index_type,
+                        },
+                    )
+                    .collect(),
+                Err(e) => return DbResponse::Error(e.to_string()),
+            };
+
+        let constraints: Vec<ConstraintInfo> = match sqlx::query_as::<
+            _,
+            (
+                String,
+                String,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+            ),
+        >(constraints_sql)
+        .bind(&db_name)
+        .bind(table_name)
+        .fetch_all(pool)
+        .await
+        {
+            Ok(rows) => rows
+                .into_iter()
+                .map(
+                    |(
+                        name,
+                        constraint_type,
+                        columns_str,
+                        foreign_table,
+                        foreign_columns_str,
+                        check_clause,
+                    )| {
+                        let columns = columns_str
+                            .map(|s| s.split(',').map(|c| c.to_string()).collect())
+                            .unwrap_or_default();
+                        let foreign_columns = foreign_columns_str
+                            .map(|s| s.split(',').map(|c| c.to_string()).collect());
+                        ConstraintInfo {
+                            name,
+                            constraint_type,
+                            columns,
+                            foreign_table,
+                            foreign_columns,
+                            check_clause,
+                        }
+                    },
+                )
+                .collect(),
+            Err(e) => return DbResponse::Error(e.to_string()),
+        };
+
+        let comment: Option<String> = sqlx::query_scalar(
+            "SELECT NULLIF(TABLE_COMMENT, '') FROM information_schema.TABLES \
+             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?",
+        )
+        .bind(&db_name)
+        .bind(table_name)
+        .fetch_optional(pool)
+        .await
+        .ok()
+        .flatten()
+        .flatten();
+
+        DbResponse::TableDetails(TableInfo {
+            name: table_name.to_string(),
+            columns,
+            indexes,
+            constraints,
+            row_estimate: 0,
+            collation: None,
+            comment,
+        })
+    }
+
+    async fn list_tables(&self) -> DbResponse {
+        match (&self.pool, self.db_type) {
+            (Some(DbPool::Postgres(_)), Some(DatabaseType::PostgreSQL)) => {
+                let sql = r#"
+                    SELECT table_schema, table_name 
+                    FROM information_schema.tables 
+                    WHERE table_type = 'BASE TABLE' 
+                      AND table_schema NOT IN ('pg_catalog', 'information_schema')
+                    ORDER BY table_schema, table_name
+                "#;
+                self.execute(sql).await
+            }
+            (Some(DbPool::MySQL(_)), Some(DatabaseType::MySQL)) => {
+                let sql = "SHOW TABLES";
+                self.execute(sql).await
+            }
+            (Some(DbPool::SQLite(_)), Some(DatabaseType::SQLite)) => {
+                let sql = "SELECT name FROM sqlite_master \
+                           WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name";
+                self.execute(sql).await
+            }
+            (Some(DbPool::DuckDB(_)), Some(DatabaseType::DuckDB)) => {
+                let sql = "SELECT schema_name, table_name FROM duckdb_tables() \
+                           ORDER BY schema_name, table_name";
+                self.execute(sql).await
+            }
+            (Some(DbPool::ClickHouse(_)), Some(DatabaseType::ClickHouse)) => {
+                self.execute("SHOW TABLES").await
+            }
+            // INFORMATION_SCHEMA queries are billed, the schema fetch is not
+            (Some(DbPool::BigQuery(pool)), Some(DatabaseType::BigQuery)) => {
+                list_schema_tables(pool.schema().await)
+            }
+            // There is no catalog query that works everywhere
+            (Some(DbPool::Odbc(pool)), Some(DatabaseType::Odbc)) => {
+                list_schema_tables(pool.schema(self.schema.clone()).await)
+            }
+            _ => DbResponse::Error("Not connected".into()),
+        }
+    }
+
+    async fn execute(&self, sql: &str) -> DbResponse {
+        match &self.pool {
+            Some(DbPool::Postgres(pool)) => self.execute_postgres(pool, sql, &[], None).await,
+            Some(DbPool::MySQL(pool)) => self.execute_mysql(pool, sql, &[], None).await,
+            Some(DbPool::SQLite(pool)) => self.execute_sqlite(pool, sql, &[]).await,
+            Some(DbPool::DuckDB(pool)) => self.execute_duckdb(pool, sql).await,
+            Some(DbPool::ClickHouse(pool)) => self.execute_clickhouse(pool, sql).await,
+            Some(DbPool::BigQuery(pool)) => self.execute_bigquery(pool, sql).await,
+            Some(DbPool::Odbc(pool)) => self.execute_odbc(pool, sql).await,
+            None => DbResponse::Error("Not connected".into()),
+        }
+    }
+
+    /// Receive the rows of `stream`, turning the cells past the first `skip` columns
+    /// into text with `format_value` as they arrive. With an editor query's id, the rows
+    /// received since the last report go to the UI every FETCH_REPORT_MS, and a stop
+    /// asked for with `StopFetching` ends the fetch then, even while no row arrives.
+    async fn collect_rows<R, S>(
+        &self,
+        mut stream: S,
+        progress: Option<RequestId>,
+        skip: usize,
+        format_value: fn(&R, usize) -> Option<String>,
+    ) -> Result<FetchedRows<R>, sqlx::Error>
+    where
+        R: Row,
+        S: futures_util::Stream<Item = Result<R, sqlx::Error>> + Unpin,
+    {
+        let mut fetched = FetchedRows {
+            rows: Vec::new(),
+            cells: Vec::new(),
+            stopped: false,
+            format_ms: 0.0,
+        };
+        let mut reported = 0;
+        let mut report = progress.map(|id| (id, interval(Duration::from_millis(FETCH_REPORT_MS))));
+        loop {
+            tokio::select! {
+                row = stream.try_next() => {
+                    let Some(row) = row? else {
+                        break;
+                    };
+                    let format_start = std::time::Instant::now();
+                    fetched
+                        .cells
+                        .push((skip..row.len()).map(|i| format_value(&row, i)).collect());
+                    fetched.format_ms += elapsed_ms(format_start);
+                    fetched.rows.push(row);
+                }
+                Some(id) = async {
+                    let (id, report) = report.as_mut()?;
+                    report.tick().await;
+                    Some(*id)
+                } => {
+                    if fetched.rows.len() > reported {
+                        let columns = &fetched.rows[0].columns()[skip..];
+                        let _ = self.response_tx.send(DbResponse::QueryResultChunk {
+                            id,
+                            columns: columns.iter().map(|c| c.name().to_string()).collect(),
+                            column_types: columns
+                                .iter()
+                                .map(|c| c.type_info().to_string())
+                                .collect(),
+                            rows: fetched.cells[reported..].to_vec(),
+                        });
+                        reported = fetched.rows.len();
+                    }
+                    let stop = self
+                        .running_queries
+                        .lock()
+                        .unwrap()
+                        .get(&id)
+                        .is_some_and(|h| h.stop_fetching);
+                    if stop {
+                        // Dropping the stream leaves the rest of the result unread; the
+                        // connection skips it before its next statement
+                        fetched.stopped = true;
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(fetched)
+    }
+
+    /// Run `sql` and collect its rows. For an editor query (`progress`) the rows are
+    /// streamed, sent to the UI as they arrive, and fetching can be stopped early.
+    async fn execute_postgres<'e, E>(
+        &self,
+        executor: E,
+        sql: &str,
+        params: &[ParamValue],
+        progress: Option<RequestId>,
+    ) -> DbResponse
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Postgres>,
+    {
+        let start = std::time::Instant::now();
+        // The row version is read as an extra first column, split off again below
+        let versioned_sql = self.row_version_sql(sql);
+        let stream = bind_params(sqlx::query(versioned_sql.as_deref().unwrap_or(sql)), params)
+            .fetch(executor);
+        let skip = usize::from(versioned_sql.is_some());
+        match self
+            .collect_rows(stream, progress, skip, format_pg_value)
+            .await
+        {
+            Ok(FetchedRows {
+                rows,
+                cells,
+                stopped: fetch_stopped,
+                format_ms,
+            }) => {
+                let columns: Vec<String> = if rows.is_empty() {
+                    vec![]
+                } else {
+                    rows[0]
+                        .columns()
+                        .iter()
+                        .skip(skip)
+                        .map(|c| c.name().to_string())
+                        .collect()
+                };
+
+                let column_types: Vec<String> = if rows.is_empty() {
+                    vec![]
+                } else {
+                    rows[0]
+                        .columns()
+                        .iter()
+                        .skip(skip)
+                        .map(|c| c.type_info().to_string())
+                        .collect()
+                };
+
+                let row_versions: Vec<String> = if versioned_sql.is_some() {
+                    rows.iter()
+                        .map(|row| row.try_get::<String, _>(0).unwrap_or_default())
+                        .collect()
+                } else {
+                    vec![]
+                };
+                let (data, null_cells) = split_nulls(cells);
+                let timing = QueryTiming {
+                    fetch_ms: elapsed_ms(start) - format_ms,
+                    format_ms: Some(format_ms),
+                };
+
+                let source_table = crate::db::extract_source_table(sql);
+                let primary_keys = source_table
+                    .as_ref()
+                    .and_then(|t| self.get_primary_keys(t))
+                    .unwrap_or_default();
+
+                DbResponse::QueryResult(QueryResult {
+                    sql: sql.to_string(),
+                    columns,
+                    column_types,
+                    rows: data,
+                    null_cells,
+                    execution_time_ms: start.elapsed().as_millis() as u64,
+                    source_table,
+                    primary_keys,
+                    bytes_processed: None,
+                    timing,
+                    fetch_stopped,
+                    limited_to: None,
+                    row_versions,
+                })
+            }
+            Err(e) => {
+                let error_str = e.to_string();
+                // The message mentions a timeout, which would pass for a lost connection
+                if Self::is_timeout_error(&error_str) {
+                    return DbResponse::QueryTimedOut {
+                        timeout_secs: self.statement_timeout_secs,
+                    };
+                }
+                if Self::is_connection_error(&error_str) {
+                    return DbResponse::ConnectionLost;
+                }
+                DbResponse::Error(error_str)
+            }
+        }
+    }
+
+    /// Run `sql` and collect its rows. For an editor query (`progress`) the rows are
+    /// streamed, sent to the UI as they arrive, and fetching can be stopped early.
+    async fn execute_mysql<'e, E>(
+        &self,
+        executor: E,
+        sql: &str,
+        params: &[ParamValue],
+        progress: Option<RequestId>,
+    ) -> DbResponse
+    where
+        E: sqlx::Executor<'e, Database = sqlx::MySql>,
+    {
+        let start = std::time::Instant::now();
+        let stream = bind_params(sqlx::query(sql), params).fetch(executor);
+        match self
+            .collect_rows(stream, progress, 0, format_mysql_value)
+            .await
+        {
+            Ok(FetchedRows {
+                rows,
+                cells,
+                stopped: fetch_stopped,
+                format_ms,
+            }) => {
+                let columns: Vec<String> = if rows.is_empty() {
+                    vec![]
+                } else {
+                    rows[0]
+                        .columns()
+                        .iter()
+                        .map(|c| c.name().to_string())
+                        .collect()
+                };
+
+                let column_types: Vec<String> = if rows.is_empty() {
+                    vec![]
+                } else {
+                    rows[0]
+                        .columns()
+                        .iter()
+                        .map(|c| c.type_info().to_string())
+                        .collect()
+                };
+
+                let (data, null_cells) = split_nulls(cells);
+                let timing = QueryTiming {
+                    fetch_ms: elapsed_ms(start) - format_ms,
+                    format_ms: Some(format_ms),
+                };
+
+                let source_table = crate::db::extract_source_table(sql);
+                let primary_keys = source_table
+                    .as_ref()
+                    .and_then(|t| self.get_primary_keys(t))
+                    .unwrap_or_default();
+
+                DbResponse::QueryResult(QueryResult {
+                    sql: sql.to_string(),
+                    columns,
+                    column_types,
+                    rows: data,
+                    null_cells,
+                    execution_time_ms: start.elapsed().as_millis() as u64,
+                    source_table,
+                    primary_keys,
+                    bytes_processed: None,
+                    timing,
+                    fetch_stopped,
+                    limited_to: None,
+                    row_versions: vec![],
+                })
+            }
+            Err(e) => {
+                let error_str = e.to_string();
+                // The message mentions a timeout, which would pass for a lost connection
+                if Self::is_timeout_error(&error_str) {
+                    return DbResponse::QueryTimedOut {
+                        timeout_secs: self.statement_timeout_secs,
+                    };
+                }
+                if Self::is_connection_error(&error_str) {
+                    return DbResponse::ConnectionLost;
+                }
+                DbResponse::Error(error_str)
+            }
+        }
+    }
+
+    async fn execute_sqlite<'e, E>(
+        &self,
+        executor: E,
+        sql: &str,
+        params: &[ParamValue],
+    ) -> DbResponse
+    where
+        E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+    {
+        let start = std::time::Instant::now();
+        match bind_params(sqlx::query(sql), params)
+            .fetch_all(executor)
+            .await
+        {
+            Ok(rows) => {
+                let fetch_ms = elapsed_ms(start);
+                let format_start = std::time::Instant::now();
+                let columns: Vec<String> = if rows.is_empty() {
+                    vec![]
+                } else {
+                    rows[0]
+                        .columns()
+                        .iter()
+                        .map(|c| c.name().to_string())
+                        .collect()
+                };
+
+                let column_types: Vec<String> = if rows.is_empty() {
+                    vec![]
+                } else {
+                    rows[0]
+                        .columns()
+                        .iter()
+                        .map(|c| c.type_info().to_string())
+                        .collect()
+                };
+
+                let mut data: Vec<Vec<Option<String>>> = Vec::with_capacity(rows.len());
+                for row in &rows {
+                    let mut row_data: Vec<Option<String>> = Vec::with_capacity(row.len());
+                    for i in 0..row.len() {
+                        row_data.push(format_sqlite_value(row, i));
+                    }
+                    data.push(row_data);
+                }
+                let (data, null_cells) = split_nulls(data);
+                let timing = QueryTiming {
+                    fetch_ms,
+                    format_ms: Some(elapsed_ms(format_start)),
+                };
+
+                let source_table = crate::db::extract_source_table(sql);
+                let primary_keys = source_table
+                    .as_ref()
+                    .and_then(|t| self.get_primary_keys(t))
+                    .unwrap_or_default();
+
+                DbResponse::QueryResult(QueryResult {
+                    sql: sql.to_string(),
+                    columns,
+                    column_types,
+                    rows: data,
+                    null_cells,
+                    execution_time_ms: start.elapsed().as_millis() as u64,
+                    source_table,
+                    primary_keys,
+                    bytes_processed: None,
+                    timing,
+                    fetch_stopped: false,
+                    limited_to: None,
+                    row_versions: vec![],
+                })
+            }
+            Err(e) => {
+                let error_str = e.to_string();
+                if Self::is_connection_error(&error_str) {
+                    return DbResponse::ConnectionLost;
+                }
+                DbResponse::Error(error_str)
+            }
+        }
+    }
+
+    async fn execute_duckdb(&self, pool: &DuckDbPool, sql: &str) -> DbResponse {
+        let start = std::time::Instant::now();
+        match pool.query(sql).await {
+            Ok((columns, column_types, rows)) => {
+                let (rows, null_cells) = split_nulls(rows);
+                let timing = QueryTiming {
+                    fetch_ms: elapsed_ms(start),
+                    format_ms: None,
+                };
+                let source_table = crate::db::extract_source_table(sql);
+                let primary_keys = source_table
+                    .as_ref()
+                    .and_then(|t| self.get_primary_keys(t))
+                    .unwrap_or_default();
+
+                DbResponse::QueryResult(QueryResult {
+                    sql: sql.to_string(),
+                    columns,
+                    column_types,
+                    rows,
+                    null_cells,
+                    execution_time_ms: start.elapsed().as_millis() as u64,
+                    source_table,
+                    primary_keys,
+                    bytes_processed: None,
+                    timing,
+                    fetch_stopped: false,
+                    limited_to: None,
+                    row_versions: vec![],
+                })
+            }
+            Err(e) => DbResponse::Error(e),
+        }
+    }
+
+    async fn execute_clickhouse(&self, pool: &ClickHousePool, sql: &str) -> DbResponse {
+        let start = std::time::Instant::now();
+        match pool.query(sql).await {
+            Ok((columns, column_types, rows)) => {
+                let (rows, null_cells) = split_nulls(rows);
+                let timing = QueryTiming {
+                    fetch_ms: elapsed_ms(start),
+                    format_ms: None,
+                };
+                let source_table = crate::db::extract_source_table(sql);
+                let primary_keys = source_table
+                    .as_ref()
+                    .and_then(|t| self.get_primary_keys(t))
+                    .unwrap_or_default();
+
+                DbResponse::QueryResult(QueryResult {
+                    sql: sql.to_string(),
+                    columns,
+                    column_types,
+                    rows,
+                    null_cells,
+                    execution_time_ms: start.elapsed().as_millis() as u64,
+                    source_table,
+                    primary_keys,
+                    bytes_processed: None,
+                    timing,
+                    fetch_stopped: false,
+                    limited_to: None,
+                    row_versions: vec![],
+                })
+            }
+            Err(e) => DbResponse::Error(e),
+        }
+    }
+
+    async fn execute_bigquery(&self, pool: &BigQueryPool, sql: &str) -> DbResponse {
+        let start = std::time::Instant::now();
+        match pool.query(sql).await {
+            Ok((columns, column_types, rows, bytes_processed)) => {
+                let (rows, null_cells) = split_nulls(rows);
+                let timing = QueryTiming {
+                    fetch_ms: elapsed_ms(start),
+                    format_ms: None,
+                };
+                DbResponse::QueryResult(QueryResult {
+                    sql: sql.to_string(),
+                    columns,
+                    column_types,
+                    rows,
+                    null_cells,
+                    execution_time_ms: start.elapsed().as_millis() as u64,
+                    // Read-only, so results are never editable
+                    source_table: None,
+                    primary_keys: Vec::new(),
+                    bytes_processed,
+                    timing,
+                    fetch_stopped: false,
+                    limited_to: None,
+                    row_versions: vec![],
+                })
+            }
+            Err(e) => DbResponse::Error(e),
+        }
+    }
+
+    async fn execute_odbc(&self, pool: &OdbcPool, sql: &str) -> DbResponse {
+        let start = std::time::Instant::now();
+        match pool.query(sql).await {
+            Ok((columns, column_types, rows)) => {
+                let (rows, null_cells) = split_nulls(rows);
+                let timing = QueryTiming {
+                    fetch_ms: elapsed_ms(start),
+                    format_ms: None,
+                };
+                let source_table = crate::db::extract_source_table(sql);
+                let primary_keys = source_table
+                    .as_ref()
+                    .and_then(|t| self.get_primary_keys(t))
+                    .unwrap_or_default();
+
+                DbResponse::QueryResult(QueryResult {
+                    sql: sql.to_string(),
+                    columns,
+                    column_types,
+                    rows,
+                    null_cells,
+                    execution_time_ms: start.elapsed().as_millis() as u64,
+                    source_table,
+                    primary_keys,
+                    bytes_processed: None,
+                    timing,
+                    fetch_stopped: false,
+                    limited_to: None,
+                    row_versions: vec![],
+                })
+            }
+            Err(e) => DbResponse::Error(e),
+        }
+    }
+
+    /// The over-budget response for a read query whose estimated plan exceeds the
+    /// connection's budget, or None when it may run.
+    async fn check_budget(&self, sql: &str) -> Option<DbResponse> {
+        let lower = sql.trim_start().to_lowercase();
+        if !self.plan_budget.is_set() || !(lower.starts_with("select") || lower.starts_with("with"))
+        {
+            return None;
+        }
+
+        // If the estimate itself fails, let the query run and report its own error
+        let (cost, rows) = self.estimate_plan(sql).await.ok()?;
+        let message = self.plan_budget.exceeded_by(cost, rows)?;
+        Some(DbResponse::OverBudget {
+            sql: sql.to_string(),
+            message,
+        })
+    }
+
+    async fn estimate_plan(&self, sql: &str) -> Result<(Option<f64>, Option<f64>), String> {
+        let sql = sql.trim().trim_end_matches(';');
+        let plan: serde_json::Value = match &self.pool {
+            // No JSON plans on CockroachDB
+            Some(DbPool::Postgres(_)) if self.dialect == SqlDialect::CockroachDB => {
+                return Ok((None, None))
+            }
+            Some(DbPool::Postgres(pool)) => {
+                sqlx::query_scalar(&format!("EXPLAIN (FORMAT JSON) {}", sql))
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| e.to_string())?
+            }
+            Some(DbPool::MySQL(pool)) => {
+                let json: String = sqlx::query_scalar(&format!("EXPLAIN FORMAT=JSON {}", sql))
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                serde_json::from_str(&json).map_err(|e| e.to_string())?
+            }
+            Some(DbPool::SQLite(_))
+            | Some(DbPool::DuckDB(_))
+            | Some(DbPool::ClickHouse(_))
+            | Some(DbPool::BigQuery(_))
+            | Some(DbPool::Odbc(_)) => return Ok((None, None)),
+            None => return Err("Not connected".into()),
+        };
+        let db_type = self.db_type.unwrap_or_default();
+        Ok(plan_estimate(db_type, &plan))
+    }
+
+    async fn explain(&self, sql: &str) -> DbResponse {
+        match (&self.pool, self.db_type) {
+            (Some(DbPool::Postgres(pool)), Some(DatabaseType::PostgreSQL)) => {
+                self.explain_postgres(pool, sql).await
+            }
+            (Some(DbPool::MySQL(pool)), Some(DatabaseType::MySQL)) => {
+                self.explain_mysql(pool, sql).await
+            }
+            (Some(DbPool::SQLite(pool)), Some(DatabaseType::SQLite)) => {
+                self.explain_sqlite(pool, sql).await
+            }
+            (Some(DbPool::DuckDB(pool)), Some(DatabaseType::DuckDB)) => {
+                // The second column holds the rendered plan tree
+                match pool.query(&format!("EXPLAIN ANALYZE {}", sql)).await {
+                    Ok((_, _, rows)) => DbResponse::ExplainResult(
+                        rows.into_iter()
+                            .filter_map(|row| row.into_iter().nth(1).flatten())
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    ),
+                    Err(e) => DbResponse::Error(format!("Explain failed: {}", e)),
+                }
+            }
+            (Some(DbPool::ClickHouse(pool)), Some(DatabaseType::ClickHouse)) => {
+                // ClickHouse can't run and profile a query in one go; show the plan with index usage
+                match pool.query(&format!("EXPLAIN indexes = 1 {}", sql)).await {
+                    Ok((_, _, rows)) => DbResponse::ExplainResult(
+                        rows.into_iter()
+                            .filter_map(|row| row.into_iter().next().flatten())
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    ),
+                    Err(e) => DbResponse::Error(format!("Explain failed: {}", e)),
+                }
+            }
+            // BigQuery has no EXPLAIN; a dry run reports what the query would scan
+            (Some(DbPool::BigQuery(pool)), Some(DatabaseType::BigQuery)) => {
+                match pool.dry_run(sql).await {
+                    Ok(bytes) => DbResponse::ExplainResult(format!(
+                        "Dry run: this query will process {} ({} bytes).",
+                        super::format_bytes(bytes),
+                        bytes
+                    )),
+                    Err(e) => DbResponse::Error(format!("Explain failed: {}", e)),
+                }
+            }
+            (Some(DbPool::Odbc(pool)), Some(DatabaseType::Odbc)) => DbResponse::Error(format!(
+                "Plans can't be shown for {} over ODBC; run its own EXPLAIN statement instead",
+                pool.dbms_name
+            )),
+            _ => DbResponse::Error("Not connected".into()),
+        }
+    }
+
+    /// Run a read query under EXPLAIN ANALYZE for the server's own timings. Other
+    /// statements would be executed again, so they are refused, including a WITH query
+    /// whose clauses write rows.
+    async fn profile_timing(&self, sql: &str) -> Result<ServerTiming, String> {
+        let lower = sql.trim_start().to_lowercase();
+        if !(lower.starts_with("select") || lower.starts_with("with")) || modifies_data(sql) {
+            return Err("Only SELECT queries can be profiled".into());
+        }
+        match (&self.pool, self.db_type) {
+            (Some(DbPool::Postgres(pool)), Some(DatabaseType::PostgreSQL))
+                if self.dialect != SqlDialect::CockroachDB =>
+            {
+                let row = sqlx::query(&format!("EXPLAIN (ANALYZE, SUMMARY, FORMAT JSON) {}", sql))
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let plan: serde_json::Value = row.try_get(0).map_err(|e| e.to_string())?;
+                let summary = &plan[0];
+                Ok(ServerTiming {
+                    planning_ms: summary["Planning Time"].as_f64(),
+                    execution_ms: summary["Execution Time"]
+                        .as_f64()
+                        .ok_or("The plan has no execution time")?,
+                })
+            }
+            (Some(DbPool::MySQL(pool)), Some(DatabaseType::MySQL))
+                if self.dialect != SqlDialect::MariaDB =>
+            {
+                let row = sqlx::query(&format!("EXPLAIN ANALYZE {}", sql))
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let tree: String = row.try_get(0).map_err(|e| e.to_string())?;
+                Ok(ServerTiming {
+                    planning_ms: None,
+                    execution_ms: mysql_root_time(&tree).ok_or("The plan has no timings")?,
+                })
+            }
+            (Some(_), _) => Err("Timing breakdown is not available for this database".into()),
+            _ => Err("Not connected".into()),
+        }
+    }
+
+    async fn explain_postgres(&self, pool: &PgPool, sql: &str) -> DbResponse {
+        // CockroachDB's plan ends with a link to the diagram of its distributed execution
+        let (explain_sql, plan_column) = if self.dialect == SqlDialect::CockroachDB {
+            (format!("EXPLAIN ANALYZE (DISTSQL) {}", sql), "info")
+        } else {
+            (
+                format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT TEXT) {}", sql),
+                "QUERY PLAN",
+            )
+        };
+        match sqlx::query(&explain_sql).fetch_all(pool).await {
+            Ok(rows) => {
+                let plan: String = rows
+                    .iter()
+                    .filter_map(|row| row.try_get::<String, _>(plan_column).ok())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                DbResponse::ExplainResult(plan)
+            }
+            Err(e) => {
+                let error_str = e.to_string();
+                if Self::is_connection_error(&error_str) {
+                    return DbResponse::ConnectionLost;
+                }
+                DbResponse::Error(format!("Explain failed: {}", e))
+            }
+        }
+    }
+
+    async fn explain_mysql(&self, pool: &MySqlPool, sql: &str) -> DbResponse {
+        // MariaDB has no EXPLAIN ANALYZE; its ANALYZE statement runs the query the same way
+        let explain_sql = if self.dialect == SqlDialect::MariaDB {
+            format!("ANALYZE {}", sql)
+        } else {
+            format!("EXPLAIN ANALYZE {}", sql)
+        };
+        match sqlx::query(&explain_sql).fetch_all(pool).await {
+            Ok(rows) => {
+                let plan: String = rows
+                    .iter()
+                    .map(|row| {
+                        let cols: Vec<String> = (0..row.columns().len())
+                            .filter_map(|i| {
+                                row.try_get::<String, _>(i)
+                                    .ok()
+                                    .or_else(|| {
+                                        row.try_get::<i64, _>(i).ok().map(|v| v.to_string())
+                                    })
+                                    .or_else(|| {
+                                        row.try_get::<f64, _>(i).ok().map(|v| v.to_string())
+                                    })
+                            })
+                            .collect();
+                        cols.join("\t")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                DbResponse::ExplainResult(plan)
+            }
+            Err(e) => {
+                let error_str = e.to_string();
+                if Self::is_connection_error(&error_str) {
+                    return DbResponse::ConnectionLost;
+                }
+                DbResponse::Error(format!("Explain failed: {}", e))
+            }
+        }
+    }
+
+    /// EXPLAIN QUERY PLAN rows indented by their parent, like the sqlite3 shell prints them.
+    async fn explain_sqlite(&self, pool: &SqlitePool, sql: &str) -> DbResponse {
+        let explain_sql = format!("EXPLAIN QUERY PLAN {}", sql);
+        let rows: Vec<(i64, i64, i64, String)> =
+            match sqlx::query_as(&explain_sql).fetch_all(pool).await {
+                Ok(rows) => rows,
+                Err(e) => {
+                    if Self::is_connection_error(&e.to_string()) {
+                        return DbResponse::ConnectionLost;
+                    }
+                    return DbResponse::Error(format!("Explain failed: {}", e));
+                }
+            };
+        let mut depths: HashMap<i64, usize> = HashMap::new();
+        let plan = rows
+            .into_iter()
+            .map(|(id, parent, _, detail)| {
+                let depth = depths.get(&parent).map(|d| d + 1).unwrap_or(0);
+                depths.insert(id, depth);
+                format!("{}{}", "  ".repeat(depth), detail)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        DbResponse::ExplainResult(plan)
+    }
+
+    /// MariaDB reports literal NULL defaults as the string 'NULL' in information_schema.
+    fn mysql_column_default(&self, column: &str) -> String {
+        if self.dialect == SqlDialect::MariaDB {
+            format!("NULLIF({}, 'NULL')", column)
+        } else {
+            column.to_string()
+        }
+    }
+
+    fn is_connection_error(error: &str) -> bool {
+        let error_lower = error.to_lowercase();
+        error_lower.contains("connection")
+            || error_lower.contains("closed")
+            || error_lower.contains("broken pipe")
+            || error_lower.contains("network")
+            || error_lower.contains("timeout")
+            || error_lower.contains("refused")
+            || error_lower.contains("reset")
+    }
+
+    /// Errors raised by the server's statement timeout (`statement_timeout` on
+    /// PostgreSQL, `max_execution_time` on MySQL).
+    fn is_timeout_error(error: &str) -> bool {
+        let error_lower = error.to_lowercase();
+        error_lower.contains("statement timeout")
+            || error_lower.contains("maximum statement execution time exceeded")
+    }
+
+    /// A failed query's response: a lost connection, or the error itself.
+    fn query_error(error: String) -> DbResponse {
+        if Self::is_connection_error(&error) {
+            DbResponse::ConnectionLost
+        } else {
+            DbResponse::Error(error)
+        }
+    }
+
+    async fn execute_mutation(&self, sql: &str) -> DbResponse {
+        if let Some(result) = self
+            .run_in_transaction(&[sql.to_string()], false, &BatchReporter::quiet())
+            .await
+        {
+            return match result {
+                Ok(BatchOutcome::Committed(affected_rows)) => {
+                    DbResponse::MutationResult { affected_rows }
+                }
+                // Only grid saves require a matched row
+                Ok(BatchOutcome::Unmatched(_)) => DbResponse::MutationResult { affected_rows: 0 },
+                Err(e) => DbResponse::Error(e),
+            };
+        }
+        match &self.pool {
+            Some(DbPool::Postgres(pool)) => match sqlx::query(sql).execute(pool).await {
+                Ok(result) => DbResponse::MutationResult {
+                    affected_rows: result.rows_affected(),
+                },
+                Err(e) => DbResponse::Error(e.to_string()),
+            },
+            Some(DbPool::MySQL(pool)) => match sqlx::query(sql).execute(pool).await {
+                Ok(result) => DbResponse::MutationResult {
+                    affected_rows: result.rows_affected(),
+                },
+                Err(e) => DbResponse::Error(e.to_string()),
+            },
+            Some(DbPool::SQLite(pool)) => match sqlx::query(sql).execute(pool).await {
+                Ok(result) => DbResponse::MutationResult {
+                    affected_rows: result.rows_affected(),
+                },
+                Err(e) => DbResponse::Error(e.to_string()),
+            },
+            Some(DbPool::DuckDB(pool)) => match pool.execute(sql).await {
+                Ok(affected_rows) => DbResponse::MutationResult { affected_rows },
+                Err(e) => DbResponse::Error(e),
+            },
+            Some(DbPool::ClickHouse(pool)) => match pool.execute(sql).await {
+                Ok(affected_rows) => DbResponse::MutationResult { affected_rows },
+                Err(e) => DbResponse::Error(e),
+            },
+            Some(DbPool::BigQuery(_)) => DbResponse::Error(READ_ONLY_ERROR.into()),
+            Some(DbPool::Odbc(pool)) => match pool.execute(sql).await {
+                Ok(affected_rows) => DbResponse::MutationResult { affected_rows },
+                Err(e) => DbResponse::Error(e),
+            },
+            None => DbResponse::Error("Not connected".into()),
+        }
+    }
+
+    /// Reporter announcing each of a batch's `total` statements to the UI.
+    fn batch_reporter(&self, total: usize) -> BatchReporter {
+        BatchReporter::new(self.response_tx.clone(), total, "statements")
+    }
+
+    /// Run `statements` in one transaction, rerunning it after conflicts. With
+    /// `row_edits` they are a grid save whose every statement must match its row.
+    async fn execute_batch(
+        &self,
+        statements: &[String],
+        options: TransactionOptions,
+        row_edits: bool,
+        reporter: &BatchReporter,
+    ) -> DbResponse {
+        let Some(pool) = &self.pool else {
+            return DbResponse::Error("Not connected".into());
+        };
+        // The open transaction's isolation level already applies
+        if let Some(result) = self
+            .run_in_transaction(statements, row_edits, reporter)
+            .await
+        {
+            return match result {
+                Ok(outcome) => batch_response(outcome, statements.len(), 0),
+                Err(e) => DbResponse::Error(e),
+            };
+        }
+
+        let mut retries = 0;
+        loop {
+            match run_batch(pool, statements, options, row_edits, reporter).await {
+                Ok(outcome) => return batch_response(outcome, statements.len(), retries),
+                Err((context, e)) if is_transaction_conflict(&e) => {
+                    if retries < options.max_retries {
+                        retries += 1;
+                        tracing::warn!(
+                            "Transaction conflict, retrying ({}/{}): {}",
+                            retries,
+                            options.max_retries,
+                            e
+                        );
+                        tokio::time::sleep(Duration::from_millis(100 * retries as u64)).await;
+                        continue;
+                    }
+                    return DbResponse::BatchConflict {
+                        statements: statements.to_vec(),
+                        options,
+                        row_edits,
+                        error: format!("{}: {}", context, e),
+                        retries,
+                    };
+                }
+                Err((context, e)) => return DbResponse::Error(format!("{}: {}", context, e)),
+            }
+        }
+    }
+
+    async fn execute_import(
+        &self,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<String>],
+        batch_size: usize,
+        conflict: ConflictStrategy,
+    ) {
+        let total = rows.len();
+        let Some(db_type) = self.db_type else {
+            let _ = self
+                .response_tx
+                .send(DbResponse::Error("Not connected".to_string()));
+            return;
+        };
+
+        let progress = BatchReporter::new(self.response_tx.clone(), total, "rows");
+        for (batch_idx, chunk) in rows.chunks(batch_size).enumerate() {
+            let statements = match self.import_statements(db_type, table, columns, chunk, conflict)
+            {
+                Ok(statements) => statements,
+                Err(e) => {
+                    let _ = self.response_tx.send(DbResponse::Error(e));
+                    return;
+                }
+            };
+            let done = batch_idx * batch_size;
+            if progress
+                .start(done, statements.first().map_or("", String::as_str))
+                .is_err()
+            {
+                let _ = self.response_tx.send(DbResponse::Error(format!(
+                    "Import cancelled after {} rows",
+                    done
+                )));
+                return;
+            }
+
+            let batch_resp = self
+                .execute_batch(
+                    &statements,
+                    TransactionOptions::default(),
+                    false,
+                    &BatchReporter::quiet(),
+                )
+                .await;
+            match batch_resp {
+                DbResponse::BatchResult { .. } => {
+                    let inserted = ((batch_idx + 1) * batch_size).min(total);
+                    let _ = self
+                        .response_tx
+                        .send(DbResponse::ImportProgress { inserted, total });
+                }
+                DbResponse::Error(e) | DbResponse::BatchConflict { error: e, .. } => {
+                    let _ = self.response_tx.send(DbResponse::Error(format!(
+                        "Import failed at row {}: {}",
+                        batch_idx * batch_size,
+                        e
+                    )));
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        let _ = self.response_tx.send(DbResponse::ImportComplete { total });
+    }
+
+    /// Import several tables one after another, or all in one transaction with
+    /// constraints deferred so their order doesn't matter.
+    async fn execute_table_imports(
+        &self,
+        tables: &[TableImport],
+        batch_size: usize,
+        defer_constraints: bool,
+    ) {
+        let total: usize = tables.iter().map(|t| t.rows.len()).sum();
+        let Some(db_type) = self.db_type else {
+            let _ = self
+                .response_tx
+                .send(DbResponse::Error("Not connected".to_string()));
+            return;
+        };
+
+        if defer_constraints {
+            if db_type != DatabaseType::PostgreSQL {
+                let _ = self.response_tx.send(DbResponse::Error(
+                    "Deferring constraints is only supported on PostgreSQL".to_string(),
+                ));
+                return;
+            }
+            let mut statements = vec!["SET CONSTRAINTS ALL DEFERRED".to_string()];
+            for import in tables {
+                match self.import_statements(
+                    db_type,
+                    &import.table,
+                    &import.columns,
+                    &import.rows,
+                    import.conflict,
+                ) {
+                    Ok(table_statements) => statements.extend(table_statements),
+                    Err(e) => {
+                        let _ = self.response_tx.send(DbResponse::Error(e));
+                        return;
+                    }
+                }
+            }
+            let reporter = self.batch_reporter(statements.len());
+            match self
+                .execute_batch(&statements, TransactionOptions::default(), false, &reporter)
+                .await
+            {
+                DbResponse::BatchResult { .. } => {
+                    let _ = self.response_tx.send(DbResponse::ImportComplete { total });
+                }
+                DbResponse::Error(e) | DbResponse::BatchConflict { error: e, .. } => {
+                    let _ = self
+                        .response_tx
+                        .send(DbResponse::Error(format!("nothing was imported: {}", e)));
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let progress = BatchReporter::new(self.response_tx.clone(), total, "rows");
+        let mut inserted = 0;
+        for import in tables {
+            for (batch_idx, chunk) in import.rows.chunks(batch_size).enumerate() {
+                let statements = match self.import_statements(
+                    db_type,
+                    &import.table,
+                    &import.columns,
+                    chunk,
+                    import.conflict,
+                ) {
+                    Ok(statements) => statements,
+                    Err(e) => {
+                        let _ = self.response_tx.send(DbResponse::Error(e));
+                        return;
+                    }
+                };
+                if progress
+                    .start(inserted, statements.first().map_or("", String::as_str))
+                    .is_err()
+                {
+                    let _ = self.response_tx.send(DbResponse::Error(format!(
+                        "Import cancelled after {} rows",
+                        inserted
+                    )));
+                    return;
+                }
+                match self
+                    .execute_batch(
+                        &statements,
+                        TransactionOptions::default(),
+                        false,
+                        &BatchReporter::quiet(),
+                    )
+                    .await
+                {
+                    DbResponse::BatchResult { .. } => {
+                        inserted += chunk.len();
+                        let _ = self
+                            .response_tx
+                            .send(DbResponse::ImportProgress { inserted, total });
+                    }
+                    DbResponse::Error(e) | DbResponse::BatchConflict { error: e, .. } => {
+                        let _ = self.response_tx.send(DbResponse::Error(format!(
+                            "{} at row {}: {}",
+                            import.table,
+                            batch_idx * batch_size,
+                            e
+                        )));
+                        return;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let _ = self.response_tx.send(DbResponse::ImportComplete { total });
+    }
+
+    /// Insert every row of a watched file in one transaction.
+    async fn import_watched_file(&self, import: &TableImport) -> Result<usize, String> {
+        let db_type = self.db_type.ok_or("Not connected")?;
+        let statements = self.import_statements(
+            db_type,
+            &import.table,
+            &import.columns,
+            &import.rows,
+            import.conflict,
+        )?;
+        match self
+            .execute_batch(
+                &statements,
+                TransactionOptions::default(),
+                false,
+                &BatchReporter::quiet(),
+            )
+            .await
+        {
+            DbResponse::BatchResult { .. } => Ok(import.rows.len()),
+            DbResponse::Error(e) | DbResponse::BatchConflict { error: e, .. } => Err(e),
+            _ => Err("Unexpected response".to_string()),
+        }
+    }
+
+    async fn validate_import(&self, tables: &[TableImport]) -> DbResponse {
+        let (Some(pool), Some(db_type)) = (&self.pool, self.db_type) else {
+            return DbResponse::ImportValidation(Err("Not connected".to_string()));
+        };
+
+        let mut statements = Vec::new();
+        // (table, row) of each statement
+        let mut origins = Vec::new();
+        for import in tables {
+            match self.import_statements(
+                db_type,
+                &import.table,
+                &import.columns,
+                &import.rows,
+                import.conflict,
+            ) {
+                Ok(table_statements) => {
+                    origins.extend((1..=table_statements.len()).map(|row| (&import.table, row)));
+                    statements.extend(table_statements);
+                }
+                Err(e) => return DbResponse::ImportValidation(Err(e)),
+            }
+        }
+
+        let outcomes = match dry_run(pool, &statements).await {
+            Ok(outcomes) => outcomes,
+            Err(e) => return DbResponse::ImportValidation(Err(e.to_string())),
+        };
+        let mut validation = ImportValidation::default();
+        for ((table, row), outcome) in origins.into_iter().zip(outcomes) {
+            match outcome {
+                None => validation.succeeded += 1,
+                Some(error) => {
+                    validation.failed += 1;
+                    if validation.failures.len() < MAX_REPORTED_IMPORT_FAILURES {
+                        validation.failures.push((table.clone(), row, error));
+                    }
+                }
+            }
+        }
+        DbResponse::ImportValidation(Ok(validation))
+    }
+
+    async fn fetch_seed_rows(&self, queries: Vec<(String, String)>) -> DbResponse {
+        let mut tables = Vec::with_capacity(queries.len());
+        for (table, sql) in queries {
+            match self.execute(&sql).await {
+                DbResponse::QueryResult(result) => tables.push((table, result)),
+                DbResponse::Error(e) => {
+                    return DbResponse::SeedRows(Err(format!("{}: {}", table, e)))
+                }
+                other => return other,
+            }
+        }
+        DbResponse::SeedRows(Ok(tables))
+    }
+
+    async fn fetch_distribution(
+        &self,
+        table: String,
+        column: String,
+        kind: DistributionKind,
+    ) -> Result<Distribution, String> {
+        let db_type = self.db_type.ok_or("Not connected")?;
+        let run = |sql: String| async move {
+            match self.execute(&sql).await {
+                DbResponse::QueryResult(result) => Ok(result),
+                DbResponse::Error(e) => Err(e),
+                DbResponse::QueryTimedOut { .. } => Err("Query timed out".to_string()),
+                _ => Err("The query failed".to_string()),
+            }
+        };
+
+        let stats = run(distribution_stats_sql(db_type, &table, &column, kind)).await?;
+        let row = stats.rows.first().ok_or("The table returned no counts")?;
+        let number = |i: usize| row.get(i).and_then(|v| v.trim().parse::<f64>().ok());
+        let total = number(0).unwrap_or(0.0) as u64;
+        let non_null = number(1).unwrap_or(0.0) as u64;
+        let bounds = match kind {
+            DistributionKind::Ranges => match (number(2), number(3)) {
+                (Some(low), Some(high)) => Some((low, high)),
+                // Only NULLs, so there is nothing to lay out
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let buckets = if non_null == 0 {
+            Vec::new()
+        } else {
+            let result = run(distribution_sql(db_type, &table, &column, kind, bounds)).await?;
+            distribution_buckets(kind, &result, bounds)
+        };
+        let shown: u64 = buckets.iter().map(|b| b.count).sum();
+        Ok(Distribution {
+            table,
+            column,
+            kind,
+            buckets,
+            total,
+            nulls: total.saturating_sub(non_null),
+            other: non_null.saturating_sub(shown),
+        })
+    }
+
+    /// Read the tables, anonymize them and, given a target, insert them there in one
+    /// transaction, parents first. The target is opened on a worker of its own that
+    /// lives only for the copy.
+    async fn copy_tables(
+        &self,
+        tables: &[CopyTable],
+        options: &CopyOptions,
+        target: Option<ConnectionConfig>,
+    ) -> Result<CopySummary, String> {
+        let db_type = self.db_type.ok_or("Not connected")?;
+        let target_db_type = target.as_ref().map_or(db_type, |t| t.db_type);
+        let schema = self.cached_schema.clone().unwrap_or_default();
+        let names: Vec<String> = tables.iter().map(|t| t.table.clone()).collect();
+        let (order, _) = fk_dependency_order(&names, &schema);
+
+        // One salt per copy, so a value hashes the same in every table
+        let anonymizer = Anonymizer::new(uuid::Uuid::new_v4().as_u64_pair().0);
+        let mut summary = CopySummary {
+            tables: Vec::with_capacity(tables.len()),
+            copied: false,
+        };
+        let mut inserts = Vec::new();
+        for &i in &order {
+            let table = &tables[i];
+            let mut sql = format!("SELECT * FROM {}", quote_identifier(db_type, &table.table));
+            if let Some(limit) = options.row_limit {
+                sql.push_str(&format!(" LIMIT {}", limit));
+            }
+            let mut result = match self.execute(&sql).await {
+                DbResponse::QueryResult(result) => result,
+                DbResponse::Error(e) => return Err(format!("{}: {}", table.table, e)),
+                _ => return Err(format!("{}: the rows could not be read", table.table)),
+            };
+            let transforms = anonymizer.apply(&mut result, &table.rules);
+            let info = schema
+                .tables
+                .iter()
+                .find(|t| t.name == super::normalize_table_name(&table.table));
+            inserts.extend(table_insert_statements(
+                target_db_type,
+                &table.table,
+                &result,
+                info,
+                false,
+            ));
+            summary.tables.push(TableCopySummary {
+                table: table.table.clone(),
+                rows: result.rows.len(),
+                transforms,
+            });
+        }
+
+        let Some(target) = target else {
+            return Ok(summary);
+        };
+        // Children are emptied before the parents they reference
+        let mut statements: Vec<String> = if options.replace_existing {
+            order
+                .iter()
+                .rev()
+                .map(|&i| {
+                    format!(
+                        "DELETE FROM {}",
+                        quote_identifier(target_db_type, &tables[i].table)
+                    )
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        statements.extend(inserts);
+
+        let (_request_tx, request_rx) = mpsc::unbounded_channel();
+        let (response_tx, _response_rx) = mpsc::unbounded_channel();
+        let mut worker = DbWorker::new(request_rx, response_tx);
+        if let DbResponse::ConnectionFailed(e) = worker.connect(target).await {
+            return Err(format!("Could not connect to the target: {}", e));
+        }
+        let result = if statements.is_empty() {
+            DbResponse::BatchResult {
+                affected_rows: 0,
+                statement_count: 0,
+                retries: 0,
+            }
+        } else {
+            worker
+                .execute_batch(
+                    &statements,
+                    TransactionOptions::default(),
+                    false,
+                    &BatchReporter::quiet(),
+                )
+                .await
+        };
+        worker.disconnect().await;
+        match result {
+            DbResponse::BatchResult { .. } => {
+                summary.copied = true;
+                Ok(summary)
+            }
+            DbResponse::Error(e) | DbResponse::BatchConflict { error: e, .. } => Err(e),
+            _ => Err("The copy was rolled back".to_string()),
+        }
+    }
+
+    async fn run_query_tests(&self, tests: &[QueryTestCase]) -> DbResponse {
+        if self.pool.is_none() {
+            return DbResponse::QueryTestResults(Err("Not connected".to_string()));
+        }
+        let mut outcomes = Vec::with_capacity(tests.len());
+        for test in tests {
+            outcomes.push(QueryTestOutcome {
+                name: test.name.clone(),
+                result: self.run_query_test(test).await,
+            });
+        }
+        DbResponse::QueryTestResults(Ok(outcomes))
+    }
+
+    /// Insert the fixtures and run the query in one transaction, then roll it back.
+    /// Returning early drops the transaction, which rolls it back as well.
+    async fn run_query_test(&self, test: &QueryTestCase) -> Result<(), String> {
+        let db_type = self.db_type.ok_or("Not connected")?;
+        let mut fixtures = Vec::new();
+        for fixture in &test.fixtures {
+            let statements = match &fixture.data {
+                FixtureData::Values(values) => {
+                    let values = values.trim().trim_end_matches(';');
+                    if values.is_empty() {
+                        continue;
+                    }
+                    vec![format!("INSERT INTO {} VALUES {}", fixture.table, values)]
+                }
+                FixtureData::Csv(csv) => {
+                    let (columns, rows) = parse_test_csv(csv)
+                        .map_err(|e| format!("Fixture for {}: {}", fixture.table, e))?;
+                    self.import_statements(
+                        db_type,
+                        &fixture.table,
+                        &columns,
+                        &rows,
+                        ConflictStrategy::Fail,
+                    )?
+                }
+            };
+            fixtures.extend(statements.into_iter().map(|sql| (&fixture.table, sql)));
+        }
+        let fixture_error =
+            |table: &String, e: sqlx::Error| format!("Fixture for {}: {}", table, e);
+
+        let response = match &self.pool {
+            Some(DbPool::Postgres(pool)) => {
+                let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+                for (table, sql) in &fixtures {
+                    sqlx::query(sql)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| fixture_error(table, e))?;
+                }
+                let response = self.execute_postgres(&mut *tx, &test.sql, &[], None).await;
+                tx.rollback().await.map_err(|e| e.to_string())?;
+                response
+            }
+            Some(DbPool::MySQL(pool)) => {
+                // Anything else commits implicitly, which would keep the fixtures
+                if !only_dml(&test.sql) {
+                    return Err(
+                        "On MySQL and MariaDB a tested query can only select, insert, \
+                         update or delete rows; other statements commit the fixtures"
+                            .to_string(),
+                    );
+                }
+                let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+                for (table, sql) in &fixtures {
+                    sqlx::query(sql)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| fixture_error(table, e))?;
+                }
+                let response = self.execute_mysql(&mut *tx, &test.sql, &[], None).await;
+                tx.rollback().await.map_err(|e| e.to_string())?;
+                response
+            }
+            Some(DbPool::SQLite(pool)) => {
+                let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+                for (table, sql) in &fixtures {
+                    sqlx::query(sql)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(|e| fixture_error(table, e))?;
+                }
+                let response = self.execute_sqlite(&mut *tx, &test.sql, &[]).await;
+                tx.rollback().await.map_err(|e| e.to_string())?;
+                response
+            }
+            Some(_) => {
+                return Err(
+                    "Query tests need transactions, available on PostgreSQL, MySQL and SQLite"
+                        .to_string(),
+                )
+            }
+            None => return Err("Not connected".to_string()),
+        };
+
+        match response {
+            DbResponse::QueryResult(result) => {
+                check_test_result(&test.expected_csv, &result, test.ordered)
+            }
+            DbResponse::Error(e) => Err(e),
+            DbResponse::QueryTimedOut { .. } => Err("Timed out".to_string()),
+            _ => Err("Unexpected response".to_string()),
+        }
+    }
+
+    /// INSERT statements for imported rows.
+    fn import_statements(
+        &self,
+        db_type: DatabaseType,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<String>],
+        conflict: ConflictStrategy,
+    ) -> Result<Vec<String>, String> {
+        let primary_keys = self.get_primary_keys(table).unwrap_or_default();
+        rows.iter()
+            .map(|row| {
+                let values: Vec<String> = row
+                    .iter()
+                    .map(|v| {
+                        if v == "NULL" {
+                            "NULL".to_string()
+                        } else {
+                            format!("'{}'", v.replace('\'', "''"))
+                        }
+                    })
+                    .collect();
+                import_insert_sql(db_type, table, columns, &values, conflict, &primary_keys)
+            })
+            .collect()
+    }
+
+    /// `sql` also selecting each row's version, when it reads a table with a primary
+    /// key so its edits can be saved. Only PostgreSQL itself has `xmin`.
+    fn row_version_sql(&self, sql: &str) -> Option<String> {
+        if self.dialect != SqlDialect::PostgreSQL {
+            return None;
+        }
+        let table = super::extract_source_table(sql)?;
+        self.get_primary_keys(&table)?;
+        with_row_version(sql)
+    }
+
+    fn get_primary_keys(&self, table_name: &str) -> Option<Vec<String>> {
+        let schema = self.cached_schema.as_ref()?;
+        let normalized = super::normalize_table_name(table_name);
+        let table = schema.tables.iter().find(|t| t.name == normalized)?;
+        let pks = table.primary_key();
+        if pks.is_empty() {
+            None
+        } else {
+            Some(pks)
+        }
+    }
+
+    async fn fetch_server_variables(&self) -> DbResponse {
+        match &self.pool {
+            Some(DbPool::Postgres(pool)) => {
+                let sql = r#"
+                    SELECT
+                        name::TEXT,
+                        setting::TEXT,
+                        boot_val::TEXT,
+                        unit::TEXT,
+                        short_desc::TEXT,
+                        (context IN ('user', 'superuser')) as session_settable
+                    FROM pg_settings
+                    ORDER BY name
+                "#;
+                let rows: Vec<PostgresSettingRow> = match sqlx::query_as(sql).fetch_all(pool).await
+                {
+                    Ok(r) => r,
+                    Err(e) => return DbResponse::Error(e.to_string()),
+                };
+                DbResponse::ServerVariables(
+                    rows.into_iter()
+                        .map(
+                            |(name, value, default_value, unit, description, session_settable)| {
+                                ServerVariable {
+                                    name,
+                                    value,
+                                    default_value,
+                                    unit,
+                                    description,
+                                    session_settable,
+                                }
+                            },
+                        )
+                        .collect(),
+                )
+            }
+            Some(DbPool::MySQL(pool)) => {
+                let session: Vec<(String, Option<String>)> =
+                    match sqlx::query_as("SHOW SESSION VARIABLES")
+                        .fetch_all(pool)
+                        .await
+                    {
+                        Ok(r) => r,
+                        Err(e) => return DbResponse::Error(e.to_string()),
+                    };
+                let global: HashMap<String, Option<String>> =
+                    match sqlx::query_as("SHOW GLOBAL VARIABLES")
+                        .fetch_all(pool)
+                        .await
+                    {
+                        Ok(r) => r.into_iter().collect(),
+                        Err(e) => return DbResponse::Error(e.to_string()),
+                    };
+                DbResponse::ServerVariables(
+                    session
+                        .into_iter()
+                        .map(|(name, value)| ServerVariable {
+                            default_value: global.get(&name).cloned().flatten(),
+                            name,
+                            value: value.unwrap_or_default(),
+                            unit: None,
+                            description: None,
+                            session_settable: true,
+                        })
+                        .collect(),
+                )
+            }
+            Some(DbPool::SQLite(pool)) => match sqlite_pragmas(pool).await {
+                Ok(pragmas) => DbResponse::ServerVariables(
+                    pragmas
+                        .into_iter()
+                        .map(|(name, value)| ServerVariable {
+                            name,
+                            value: value.unwrap_or_default(),
+                            default_value: None,
+                            unit: None,
+                            description: None,
+                            session_settable: true,
+                        })
+                        .collect(),
+                ),
+                Err(e) => DbResponse::Error(e.to_string()),
+            },
+            Some(DbPool::DuckDB(pool)) => match pool.settings().await {
+                Ok(settings) => DbResponse::ServerVariables(
+                    settings
+                        .into_iter()
+                        .map(|(name, value, description)| ServerVariable {
+                            name,
+                            value: value.unwrap_or_default(),
+                            default_value: None,
+                            unit: None,
+                            description,
+                            session_settable: true,
+                        })
+                        .collect(),
+                ),
+                Err(e) => DbResponse::Error(e),
+            },
+            Some(DbPool::ClickHouse(pool)) => match pool.settings().await {
+                Ok(settings) => DbResponse::ServerVariables(
+                    settings
+                        .into_iter()
+                        .filter_map(|row| <[String; 4]>::try_from(row).ok())
+                        .map(|[name, value, default_value, description]| ServerVariable {
+                            name,
+                            value,
+                            default_value: Some(default_value),
+                            unit: None,
+                            description: Some(description),
+                            session_settable: true,
+                        })
+                        .collect(),
+                ),
+                Err(e) => DbResponse::Error(e),
+            },
+            // Project settings live in the console, not in SQL
+            Some(DbPool::BigQuery(_)) => DbResponse::ServerVariables(Vec::new()),
+            // Every database keeps them somewhere else
+            Some(DbPool::Odbc(_)) => DbResponse::ServerVariables(Vec::new()),
+            None => DbResponse::Error("Not connected".into()),
+        }
+    }
+
+    async fn fetch_session_variables(&self) -> DbResponse {
+        let values: Vec<(String, Option<String>)> = match &self.pool {
+            Some(DbPool::Postgres(pool)) => {
+                let names: Vec<String> =
+                    PG_SESSION_VARIABLES.iter().map(|n| n.to_string()).collect();
+                match sqlx::query_as(
+                    "SELECT name::TEXT, setting::TEXT FROM pg_settings WHERE name = ANY($1)",
+                )
+                .bind(names)
+                .fetch_all(pool)
+                .await
+                {
+                    Ok(r) => r,
+                    Err(e) => return DbResponse::Error(e.to_string()),
+                }
+            }
+            Some(DbPool::MySQL(pool)) => {
+                let names = MYSQL_SESSION_VARIABLES
+                    .iter()
+                    .map(|n| format!("'{}'", n))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let sql = format!("SHOW SESSION VARIABLES WHERE Variable_name IN ({})", names);
+                match sqlx::query_as(&sql).fetch_all(pool).await {
+                    Ok(r) => r,
+                    Err(e) => return DbResponse::Error(e.to_string()),
+                }
+            }
+            Some(DbPool::SQLite(pool)) => match sqlite_pragmas(pool).await {
+                Ok(r) => r,
+                Err(e) => return DbResponse::Error(e.to_string()),
+            },
+            Some(DbPool::DuckDB(pool)) => match pool.settings().await {
+                Ok(settings) => settings
+                    .into_iter()
+                    .filter(|(name, _, _)| DUCKDB_SESSION_VARIABLES.contains(&name.as_str()))
+                    .map(|(name, value, _)| (name, value))
+                    .collect(),
+                Err(e) => return DbResponse::Error(e),
+            },
+            Some(DbPool::ClickHouse(pool)) => match pool.settings().await {
+                Ok(settings) => settings
+                    .into_iter()
+                    .filter(|row| CLICKHOUSE_SESSION_VARIABLES.contains(&row[0].as_str()))
+                    .map(|row| (row[0].clone(), Some(row[1].clone())))
+                    .collect(),
+                Err(e) => return DbResponse::Error(e),
+            },
+            Some(DbPool::BigQuery(_)) | Some(DbPool::Odbc(_)) => Vec::new(),
+            None => return DbResponse::Error("Not connected".into()),
+        };
+
+        let order = match self.db_type {
+            Some(DatabaseType::MySQL) => MYSQL_SESSION_VARIABLES,
+            Some(DatabaseType::SQLite) => SQLITE_PRAGMAS,
+            Some(DatabaseType::DuckDB) => DUCKDB_SESSION_VARIABLES,
+            Some(DatabaseType::ClickHouse) => CLICKHOUSE_SESSION_VARIABLES,
+            _ => PG_SESSION_VARIABLES,
+        };
+        let overridden = overridden_variables(&self.session_settings);
+        let mut variables: Vec<SessionVariable> = values
+            .into_iter()
+            .map(|(name, value)| SessionVariable {
+                overridden: overridden.contains(&name.to_lowercase()),
+                name,
+                value: value.unwrap_or_default(),
+            })
+            .collect();
+        variables.sort_by_key(|v| order.iter().position(|n| *n == v.name));
+        DbResponse::SessionVariables(variables)
     }
 
-    async fn list_tables(&self) -> DbResponse {
-        match (&self.pool, self.db_type) {
-            (Some(DbPool::Postgres(_)), Some(DatabaseType::PostgreSQL)) => {
-                let sql = r#"
-                    SELECT table_schema, table_name 
-                    FROM information_schema.tables 
-                    WHERE table_type = 'BASE TABLE' 
-                      AND table_schema NOT IN ('pg_catalog', 'information_schema')
-                    ORDER BY table_schema, table_name
-                "#;
-                self.execute(sql).await
+    async fn set_session_variable(&self, name: &str, value: &str) -> DbResponse {
+        let Some(db_type) = self.db_type else {
+            return DbResponse::Error("Not connected".into());
+        };
+        let sql = match format_set_session_sql(db_type, name, value) {
+            Ok(sql) => sql,
+            Err(e) => return DbResponse::Error(e),
+        };
+
+        // Tried on one connection first, so a bad value is refused before the rest get it.
+        // Connections busy with a query at this moment keep their old value.
+        let statement = [sql.clone()];
+        let result = match &self.pool {
+            Some(DbPool::Postgres(pool)) => match sqlx::query(&sql).execute(pool).await {
+                Ok(_) => execute_on_idle(pool, &statement).await,
+                Err(e) => Err(e),
+            },
+            Some(DbPool::MySQL(pool)) => match sqlx::query(&sql).execute(pool).await {
+                Ok(_) => execute_on_idle(pool, &statement).await,
+                Err(e) => Err(e),
+            },
+            Some(DbPool::SQLite(pool)) => match sqlx::query(&sql).execute(pool).await {
+                Ok(_) => execute_on_idle(pool, &statement).await,
+                Err(e) => Err(e),
+            },
+            Some(DbPool::DuckDB(pool)) => {
+                pool.execute(&sql).await.map(|_| ()).map_err(driver_error)
             }
-            (Some(DbPool::MySQL(_)), Some(DatabaseType::MySQL)) => {
-                let sql = "SHOW TABLES";
-                self.execute(sql).await
+            Some(DbPool::ClickHouse(pool)) => {
+                pool.execute(&sql).await.map(|_| ()).map_err(driver_error)
             }
-            _ => DbResponse::Error("Not connected".into()),
+            Some(DbPool::BigQuery(_)) => return DbResponse::Error(READ_ONLY_ERROR.into()),
+            // format_set_session_sql has already refused
+            Some(DbPool::Odbc(_)) => return DbResponse::Error("Not supported over ODBC".into()),
+            None => return DbResponse::Error("Not connected".into()),
+        };
+        if let Err(e) = result {
+            return DbResponse::Error(e.to_string());
         }
-    }
 
-    async fn execute(&self, sql: &str) -> DbResponse {
-        match &self.pool {
-            Some(DbPool::Postgres(pool)) => self.execute_postgres(pool, sql).await,
-            Some(DbPool::MySQL(pool)) => self.execute_mysql(pool, sql).await,
-            None => DbResponse::Error("Not connected".into()),
+        // New connections start out with the server default, so a reset is simply forgotten
+        if let Ok(mut settings) = self.session_settings.write() {
+            settings.retain(|(n, _)| n != name);
+            if !value.trim().eq_ignore_ascii_case("DEFAULT") {
+                settings.push((name.to_string(), sql));
+            }
         }
-    }
-
-    async fn execute_postgres(&self, pool: &PgPool, sql: &str) -> DbResponse {
-        let start = std::time::Instant::now();
-        match sqlx::query(sql).fetch_all(pool).await {
-            Ok(rows) => {
-                let columns: Vec<String> = if rows.is_empty() {
-                    vec![]
-                } else {
-                    rows[0]
-                        .columns()
-                        .iter()
-                        .map(|c| c.name().to_string())
-                        .collect()
-                };
 
-                let column_types: Vec<String> = if rows.is_empty() {
-                    vec![]
-                } else {
-                    rows[0]
-                        .columns()
-                        .iter()
-                        .map(|c| c.type_info().to_string())
-                        .collect()
-                };
+        DbResponse::SessionVariableSet {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
 
-                let mut data: Vec<Vec<String>> = Vec::with_capacity(rows.len());
-                for row in &rows {
-                    let mut row_data: Vec<String> = Vec::with_capacity(row.len());
-                    for i in 0..row.len() {
-                        row_data.push(format_pg_value(row, i));
-                    }
-                    data.push(row_data);
-                }
+    async fn fetch_extensions(&self) -> DbResponse {
+        let Some(DbPool::Postgres(pool)) = &self.pool else {
+            return DbResponse::Error("Extensions are only available on PostgreSQL".into());
+        };
 
-                let source_table = crate::db::extract_source_table(sql);
-                let primary_keys = source_table
-                    .as_ref()
-                    .and_then(|t| self.get_primary_keys(t))
-                    .unwrap_or_default();
+        let sql = r#"
+            SELECT
+                name::TEXT,
+                default_version::TEXT,
+                installed_version::TEXT,
+                comment::TEXT
+            FROM pg_available_extensions
+            ORDER BY (installed_version IS NULL), name
+        "#;
 
-                DbResponse::QueryResult(QueryResult {
-                    sql: sql.to_string(),
-                    columns,
-                    column_types,
-                    rows: data,
-                    execution_time_ms: start.elapsed().as_millis() as u64,
-                    source_table,
-                    primary_keys,
-                })
-            }
-            Err(e) => {
-                let error_str = e.to_string();
-                if Self::is_connection_error(&error_str) {
-                    return DbResponse::ConnectionLost;
-                }
-                DbResponse::Error(error_str)
-            }
+        match sqlx::query_as::<_, (String, Option<String>, Option<String>, Option<String>)>(sql)
+            .fetch_all(pool)
+            .await
+        {
+            Ok(rows) => DbResponse::Extensions(
+                rows.into_iter()
+                    .map(
+                        |(name, default_version, installed_version, comment)| ExtensionInfo {
+                            name,
+                            default_version,
+                            installed_version,
+                            comment,
+                        },
+                    )
+                    .collect(),
+            ),
+            Err(e) => DbResponse::Error(e.to_string()),
         }
     }
 
-    async fn execute_mysql(&self, pool: &MySqlPool, sql: &str) -> DbResponse {
-        let start = std::time::Instant::now();
-        match sqlx::query(sql).fetch_all(pool).await {
-            Ok(rows) => {
-                let columns: Vec<String> = if rows.is_empty() {
-                    vec![]
-                } else {
-                    rows[0]
-                        .columns()
-                        .iter()
-                        .map(|c| c.name().to_string())
-                        .collect()
-                };
-
-                let column_types: Vec<String> = if rows.is_empty() {
-                    vec![]
-                } else {
-                    rows[0]
-                        .columns()
-                        .iter()
-                        .map(|c| c.type_info().to_string())
-                        .collect()
-                };
-
-                let mut data: Vec<Vec<String>> = Vec::with_capacity(rows.len());
-                for row in &rows {
-                    let mut row_data: Vec<String> = Vec::with_capacity(row.len());
-                    for i in 0..row.len() {
-                        row_data.push(format_mysql_value(row, i));
-                    }
-                    data.push(row_data);
-                }
+    async fn change_extension(&self, sql: &str) -> DbResponse {
+        let Some(DbPool::Postgres(pool)) = &self.pool else {
+            return DbResponse::Error("Extensions are only available on PostgreSQL".into());
+        };
 
-                let source_table = crate::db::extract_source_table(sql);
-                let primary_keys = source_table
-                    .as_ref()
-                    .and_then(|t| self.get_primary_keys(t))
-                    .unwrap_or_default();
+        if let Err(e) = sqlx::query(sql).execute(pool).await {
+            return DbResponse::Error(e.to_string());
+        }
+        self.fetch_extensions().await
+    }
 
-                DbResponse::QueryResult(QueryResult {
-                    sql: sql.to_string(),
-                    columns,
-                    column_types,
-                    rows: data,
-                    execution_time_ms: start.elapsed().as_millis() as u64,
-                    source_table,
-                    primary_keys,
-                })
-            }
-            Err(e) => {
-                let error_str = e.to_string();
-                if Self::is_connection_error(&error_str) {
-                    return DbResponse::ConnectionLost;
-                }
-                DbResponse::Error(error_str)
-            }
+    /// Columns (name, data type) of a table, resolving an unqualified name against the
+    /// current schema. Empty when the table does not exist.
+    async fn audit_table_columns(&self, qualified: &str) -> Result<Vec<(String, String)>, String> {
+        let (schema, name) = match qualified.split_once('.') {
+            Some((schema, name)) => (Some(schema.to_string()), name.to_string()),
+            None => (None, qualified.to_string()),
+        };
+        match &self.pool {
+            Some(DbPool::Postgres(pool)) => sqlx::query_as(
+                "SELECT column_name::TEXT, data_type::TEXT FROM information_schema.columns \
+                 WHERE table_name = $1 AND table_schema = COALESCE($2, current_schema()) \
+                 ORDER BY ordinal_position",
+            )
+            .bind(name)
+            .bind(schema)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string()),
+            Some(DbPool::MySQL(pool)) => sqlx::query_as(
+                "SELECT COLUMN_NAME, DATA_TYPE FROM information_schema.COLUMNS \
+                 WHERE TABLE_NAME = ? AND TABLE_SCHEMA = COALESCE(?, DATABASE()) \
+                 ORDER BY ORDINAL_POSITION",
+            )
+            .bind(name)
+            .bind(schema)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string()),
+            // An attached database is the schema part of schema.table
+            Some(DbPool::SQLite(pool)) => sqlx::query_as(
+                "SELECT name, type FROM pragma_table_info(?1, COALESCE(?2, 'main')) ORDER BY cid",
+            )
+            .bind(name)
+            .bind(schema)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string()),
+            Some(DbPool::DuckDB(pool)) => pool.columns(&name, schema).await,
+            Some(DbPool::ClickHouse(pool)) => pool.columns(&name, schema).await,
+            Some(DbPool::BigQuery(pool)) => match schema {
+                Some(dataset) => pool.columns(&format!("{}.{}", dataset, name)).await,
+                None => pool.columns(&name).await,
+            },
+            Some(DbPool::Odbc(pool)) => pool.columns(&name, schema).await,
+            None => Err("Not connected".into()),
         }
     }
 
-    async fn explain(&self, sql: &str) -> DbResponse {
-        match (&self.pool, self.db_type) {
-            (Some(DbPool::Postgres(pool)), Some(DatabaseType::PostgreSQL)) => {
-                self.explain_postgres(pool, sql).await
-            }
-            (Some(DbPool::MySQL(pool)), Some(DatabaseType::MySQL)) => {
-                self.explain_mysql(pool, sql).await
-            }
-            _ => DbResponse::Error("Not connected".into()),
+    async fn fetch_referencing_counts(&self, statements: &[String]) -> DbResponse {
+        let mut counts = Vec::with_capacity(statements.len());
+        for sql in statements {
+            counts.push(match self.execute(sql).await {
+                DbResponse::QueryResult(result) => first_count(&result),
+                DbResponse::Error(e) => Err(e),
+                other => return other,
+            });
         }
+        DbResponse::ReferencingCounts(counts)
     }
 
-    async fn explain_postgres(&self, pool: &PgPool, sql: &str) -> DbResponse {
-        let explain_sql = format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT TEXT) {}", sql);
-        match sqlx::query(&explain_sql).fetch_all(pool).await {
-            Ok(rows) => {
-                let plan: String = rows
+    async fn fetch_row_history(
+        &self,
+        table: &str,
+        keys: &[(String, String)],
+        patterns: &[String],
+    ) -> DbResponse {
+        let Some(db_type) = self.db_type else {
+            return DbResponse::RowHistory(Err("Not connected".into()));
+        };
+        let table = super::normalize_table_name(table);
+
+        let mut tried = Vec::new();
+        for pattern in patterns {
+            let candidate = pattern.replace("{table}", &table);
+            let columns = match self.audit_table_columns(&candidate).await {
+                Ok(columns) => columns,
+                Err(e) => return DbResponse::RowHistory(Err(e)),
+            };
+            tried.push(candidate.clone());
+            // The audit table must carry the row's key columns to be matched against
+            if columns.is_empty()
+                || !keys
                     .iter()
-                    .filter_map(|row| row.try_get::<String, _>("QUERY PLAN").ok())
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                DbResponse::ExplainResult(plan)
-            }
-            Err(e) => {
-                let error_str = e.to_string();
-                if Self::is_connection_error(&error_str) {
-                    return DbResponse::ConnectionLost;
-                }
-                DbResponse::Error(format!("Explain failed: {}", e))
+                    .all(|(key, _)| columns.iter().any(|(name, _)| name == key))
+            {
+                continue;
             }
+
+            let where_clause = keys
+                .iter()
+                .map(|(key, value)| {
+                    format!(
+                        "{} = '{}'",
+                        quote_identifier(db_type, key),
+                        value.replace('\'', "''")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" AND ");
+            let order_by = columns
+                .iter()
+                .find(|(_, data_type)| {
+                    let t = data_type.to_lowercase();
+                    t.contains("timestamp") || t.contains("datetime")
+                })
+                .map(|(name, _)| format!(" ORDER BY {}", quote_identifier(db_type, name)))
+                .unwrap_or_default();
+            let sql = format!(
+                "SELECT * FROM {} WHERE {}{}",
+                quote_identifier(db_type, &candidate),
+                where_clause,
+                order_by
+            );
+
+            return match self.execute(&sql).await {
+                DbResponse::QueryResult(result) => DbResponse::RowHistory(Ok((candidate, result))),
+                DbResponse::Error(e) => DbResponse::RowHistory(Err(e)),
+                other => other,
+            };
         }
+
+        DbResponse::RowHistory(Err(format!(
+            "No audit table found for {} (tried: {})",
+            table,
+            tried.join(", ")
+        )))
     }
 
-    async fn explain_mysql(&self, pool: &MySqlPool, sql: &str) -> DbResponse {
-        let explain_sql = format!("EXPLAIN ANALYZE {}", sql);
-        match sqlx::query(&explain_sql).fetch_all(pool).await {
-            Ok(rows) => {
-                let plan: String = rows
-                    .iter()
-                    .map(|row| {
-                        let cols: Vec<String> = (0..row.columns().len())
-                            .filter_map(|i| {
-                                row.try_get::<String, _>(i)
-                                    .ok()
-                                    .or_else(|| {
-                                        row.try_get::<i64, _>(i).ok().map(|v| v.to_string())
-                                    })
-                                    .or_else(|| {
-                                        row.try_get::<f64, _>(i).ok().map(|v| v.to_string())
-                                    })
-                            })
-                            .collect();
-                        cols.join("\t")
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                DbResponse::ExplainResult(plan)
-            }
-            Err(e) => {
-                let error_str = e.to_string();
-                if Self::is_connection_error(&error_str) {
-                    return DbResponse::ConnectionLost;
-                }
-                DbResponse::Error(format!("Explain failed: {}", e))
+    async fn disconnect(&mut self) -> DbResponse {
+        self.last_config = None;
+        // Closing the pool waits for the transaction's connection to come back
+        self.close_transaction().await;
+        if let Some(pool) = self.pool.take() {
+            match pool {
+                DbPool::Postgres(p) => p.close().await,
+                DbPool::MySQL(p) => p.close().await,
+                DbPool::SQLite(p) => p.close().await,
+                // The database is closed once the last handle is dropped
+                DbPool::DuckDB(_) => {}
+                // The server-side session expires on its own
+                DbPool::ClickHouse(_) => {}
+                DbPool::BigQuery(_) => {}
+                // Connections are opened per request
+                DbPool::Odbc(_) => {}
             }
         }
+        self.tunnel = None;
+        self.db_type = None;
+        self.schema = None;
+        if let Ok(mut settings) = self.session_settings.write() {
+            settings.clear();
+        }
+        DbResponse::Disconnected
     }
+}
 
-    fn is_connection_error(error: &str) -> bool {
-        let error_lower = error.to_lowercase();
-        error_lower.contains("connection")
-            || error_lower.contains("closed")
-            || error_lower.contains("broken pipe")
-            || error_lower.contains("network")
-            || error_lower.contains("timeout")
-            || error_lower.contains("refused")
-            || error_lower.contains("reset")
+/// The value of a `SELECT COUNT(*)` result.
+fn first_count(result: &QueryResult) -> Result<i64, String> {
+    result
+        .rows
+        .first()
+        .and_then(|row| row.first())
+        .and_then(|v| v.parse::<i64>().ok())
+        .ok_or_else(|| "Unexpected count result".to_string())
+}
+
+/// Deadlocks and serialization failures, where running the transaction again can succeed.
+fn is_transaction_conflict(error: &sqlx::Error) -> bool {
+    error
+        .as_database_error()
+        .and_then(|e| e.code())
+        .is_some_and(|code| code == "40001" || code == "40P01")
+}
+
+fn batch_response(outcome: BatchOutcome, statement_count: usize, retries: u32) -> DbResponse {
+    match outcome {
+        BatchOutcome::Committed(affected_rows) => DbResponse::BatchResult {
+            affected_rows,
+            statement_count,
+            retries,
+        },
+        BatchOutcome::Unmatched(statement) => DbResponse::RowEditConflict { statement },
     }
+}
 
-    async fn execute_mutation(&self, sql: &str) -> DbResponse {
-        match &self.pool {
-            Some(DbPool::Postgres(pool)) => match sqlx::query(sql).execute(pool).await {
-                Ok(result) => DbResponse::MutationResult {
-                    affected_rows: result.rows_affected(),
-                },
-                Err(e) => DbResponse::Error(e.to_string()),
-            },
-            Some(DbPool::MySQL(pool)) => match sqlx::query(sql).execute(pool).await {
-                Ok(result) => DbResponse::MutationResult {
-                    affected_rows: result.rows_affected(),
-                },
-                Err(e) => DbResponse::Error(e.to_string()),
-            },
-            None => DbResponse::Error("Not connected".into()),
+/// Set by `cancel_batch`. Batches run one at a time, so one flag serves them all.
+static BATCH_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Stop the running batch or import before its next statement, rolling back what it
+/// has not committed.
+pub fn cancel_batch() {
+    BATCH_CANCELLED.store(true, Ordering::Relaxed);
+}
+
+/// Announces each statement of a batch as it starts, and stops the batch once it is
+/// cancelled.
+#[derive(Clone)]
+pub(super) struct BatchReporter {
+    /// None for batches that are part of a bigger job, which reports on its own
+    response_tx: Option<mpsc::UnboundedSender<DbResponse>>,
+    total: usize,
+    unit: &'static str,
+}
+
+impl BatchReporter {
+    /// Reporter for a job of `total` `unit`s, starting uncancelled.
+    fn new(
+        response_tx: mpsc::UnboundedSender<DbResponse>,
+        total: usize,
+        unit: &'static str,
+    ) -> Self {
+        BATCH_CANCELLED.store(false, Ordering::Relaxed);
+        Self {
+            response_tx: Some(response_tx),
+            total,
+            unit,
         }
     }
 
-    async fn execute_batch(&self, statements: &[String]) -> DbResponse {
-        let mut total_affected = 0u64;
-        let count = statements.len();
+    /// Reporter for batches with no progress shown, such as each chunk of an import.
+    /// Only jobs that report can be cancelled.
+    fn quiet() -> Self {
+        Self {
+            response_tx: None,
+            total: 0,
+            unit: "statements",
+        }
+    }
 
-        match &self.pool {
-            Some(DbPool::Postgres(pool)) => {
-                let mut tx = match pool.begin().await {
-                    Ok(tx) => tx,
-                    Err(e) => return DbResponse::Error(e.to_string()),
-                };
-                for sql in statements {
-                    match sqlx::query(sql).execute(&mut *tx).await {
-                        Ok(r) => total_affected += r.rows_affected(),
-                        Err(e) => {
-                            let _ = tx.rollback().await;
-                            return DbResponse::Error(format!("Batch failed: {}", e));
-                        }
-                    }
+    /// Report that `done` units are finished and `current` is next, or fail once
+    /// the job was cancelled.
+    pub(super) fn start(&self, done: usize, current: &str) -> Result<(), String> {
+        let Some(tx) = &self.response_tx else {
+            return Ok(());
+        };
+        if BATCH_CANCELLED.load(Ordering::Relaxed) {
+            return Err(format!("stopped after {} {}", done, self.unit));
+        }
+        let _ = tx.send(DbResponse::BatchProgress {
+            done,
+            total: self.total,
+            unit: self.unit,
+            current: current.to_string(),
+        });
+        Ok(())
+    }
+}
+
+/// How a batch that didn't fail ended.
+pub(super) enum BatchOutcome {
+    /// Committed, having affected this many rows
+    Committed(u64),
+    /// Statement `n` of a grid save matched no row, so the batch was rolled back
+    Unmatched(usize),
+}
+
+/// Run statements in one transaction, returning the affected row count
+/// or the failing step along with its error. With `row_edits` each statement
+/// must match a row, or nothing is committed.
+async fn run_batch(
+    pool: &DbPool,
+    statements: &[String],
+    options: TransactionOptions,
+    row_edits: bool,
+    reporter: &BatchReporter,
+) -> Result<BatchOutcome, (&'static str, sqlx::Error)> {
+    let mut total_affected = 0u64;
+    let set_transaction = options.set_transaction_sql();
+
+    match pool {
+        DbPool::Postgres(pool) => {
+            let mut tx = pool.begin().await.map_err(|e| ("Begin failed", e))?;
+            if let Some(sql) = &set_transaction {
+                if let Err(e) = sqlx::query(sql).execute(&mut *tx).await {
+                    let _ = tx.rollback().await;
+                    return Err(("Begin failed", e));
+                }
+            }
+            for (i, sql) in statements.iter().enumerate() {
+                if let Err(e) = reporter.start(i, sql) {
+                    let _ = tx.rollback().await;
+                    return Err(("Batch cancelled", driver_error(e)));
                 }
-                if let Err(e) = tx.commit().await {
-                    return DbResponse::Error(format!("Commit failed: {}", e));
+                match sqlx::query(sql).execute(&mut *tx).await {
+                    Ok(r) if row_edits && r.rows_affected() == 0 => {
+                        let _ = tx.rollback().await;
+                        return Ok(BatchOutcome::Unmatched(i));
+                    }
+                    Ok(r) => total_affected += r.rows_affected(),
+                    Err(e) => {
+                        let _ = tx.rollback().await;
+                        return Err(("Batch failed", e));
+                    }
                 }
             }
-            Some(DbPool::MySQL(pool)) => {
-                let mut tx = match pool.begin().await {
-                    Ok(tx) => tx,
-                    Err(e) => return DbResponse::Error(e.to_string()),
-                };
-                for sql in statements {
-                    match sqlx::query(sql).execute(&mut *tx).await {
-                        Ok(r) => total_affected += r.rows_affected(),
-                        Err(e) => {
-                            let _ = tx.rollback().await;
-                            return DbResponse::Error(format!("Batch failed: {}", e));
-                        }
+            tx.commit().await.map_err(|e| ("Commit failed", e))?;
+        }
+        DbPool::MySQL(pool) => {
+            let mut conn = pool.acquire().await.map_err(|e| ("Begin failed", e))?;
+            // Applies to the next transaction started on this connection
+            if let Some(sql) = &set_transaction {
+                sqlx::query(sql)
+                    .execute(&mut *conn)
+                    .await
+                    .map_err(|e| ("Begin failed", e))?;
+            }
+            let mut tx = conn.begin().await.map_err(|e| ("Begin failed", e))?;
+            for (i, sql) in statements.iter().enumerate() {
+                if let Err(e) = reporter.start(i, sql) {
+                    let _ = tx.rollback().await;
+                    return Err(("Batch cancelled", driver_error(e)));
+                }
+                match sqlx::query(sql).execute(&mut *tx).await {
+                    Ok(r) if row_edits && r.rows_affected() == 0 => {
+                        let _ = tx.rollback().await;
+                        return Ok(BatchOutcome::Unmatched(i));
                     }
+                    Ok(r) => total_affected += r.rows_affected(),
+                    Err(e) => {
+                        let _ = tx.rollback().await;
+                        return Err(("Batch failed", e));
+                    }
+                }
+            }
+            tx.commit().await.map_err(|e| ("Commit failed", e))?;
+        }
+        // SQLite transactions are always serializable, so there is nothing to set
+        DbPool::SQLite(pool) => {
+            let mut tx = pool.begin().await.map_err(|e| ("Begin failed", e))?;
+            for (i, sql) in statements.iter().enumerate() {
+                if let Err(e) = reporter.start(i, sql) {
+                    let _ = tx.rollback().await;
+                    return Err(("Batch cancelled", driver_error(e)));
                 }
-                if let Err(e) = tx.commit().await {
-                    return DbResponse::Error(format!("Commit failed: {}", e));
+                match sqlx::query(sql).execute(&mut *tx).await {
+                    Ok(r) if row_edits && r.rows_affected() == 0 => {
+                        let _ = tx.rollback().await;
+                        return Ok(BatchOutcome::Unmatched(i));
+                    }
+                    Ok(r) => total_affected += r.rows_affected(),
+                    Err(e) => {
+                        let _ = tx.rollback().await;
+                        return Err(("Batch failed", e));
+                    }
                 }
             }
-            None => return DbResponse::Error("Not connected".into()),
+            tx.commit().await.map_err(|e| ("Commit failed", e))?;
         }
-
-        DbResponse::BatchResult {
-            affected_rows: total_affected,
-            statement_count: count,
+        // A single embedded connection never conflicts, and DuckDB only has snapshot isolation
+        DbPool::DuckDB(pool) => {
+            return pool
+                .batch(statements, row_edits, reporter.clone())
+                .await
+                .map_err(|(context, e)| (context, driver_error(e)));
+        }
+        // Nothing could be rolled back, and mutations don't report the rows they
+        // matched, so edits aren't checked
+        DbPool::ClickHouse(pool) => {
+            total_affected = pool
+                .batch(statements, reporter)
+                .await
+                .map_err(|(context, e)| (context, driver_error(e)))?;
+        }
+        DbPool::BigQuery(_) => {
+            return Err(("Batch failed", driver_error(READ_ONLY_ERROR.to_string())));
+        }
+        DbPool::Odbc(pool) => {
+            return pool
+                .batch(statements, row_edits, reporter.clone())
+                .await
+                .map_err(|(context, e)| (context, driver_error(e)));
         }
     }
 
-    async fn execute_import(
-        &self,
-        table: &str,
-        columns: &[String],
-        rows: &[Vec<String>],
-        batch_size: usize,
-    ) {
-        let total = rows.len();
-        let col_list = columns.join(", ");
+    Ok(BatchOutcome::Committed(total_affected))
+}
 
-        for (batch_idx, chunk) in rows.chunks(batch_size).enumerate() {
-            let mut statements = Vec::with_capacity(chunk.len());
-            for row in chunk {
-                let values: Vec<String> = row
-                    .iter()
-                    .map(|v| {
-                        if v == "NULL" {
-                            "NULL".to_string()
-                        } else {
-                            format!("'{}'", v.replace('\'', "''"))
-                        }
-                    })
-                    .collect();
-                statements.push(format!(
-                    "INSERT INTO {} ({}) VALUES ({})",
-                    table,
-                    col_list,
-                    values.join(", ")
-                ));
+/// Run each statement under its own savepoint, then roll everything back.
+/// Returns the error of each failed statement, None for those that succeeded.
+async fn dry_run(pool: &DbPool, statements: &[String]) -> Result<Vec<Option<String>>, sqlx::Error> {
+    let mut outcomes = Vec::with_capacity(statements.len());
+    match pool {
+        DbPool::Postgres(pool) => {
+            let mut tx = pool.begin().await?;
+            // Report foreign key violations on the row instead of at commit
+            sqlx::query("SET CONSTRAINTS ALL IMMEDIATE")
+                .execute(&mut *tx)
+                .await?;
+            for sql in statements {
+                sqlx::query("SAVEPOINT import_row")
+                    .execute(&mut *tx)
+                    .await?;
+                match sqlx::query(sql).execute(&mut *tx).await {
+                    Ok(_) => {
+                        sqlx::query("RELEASE SAVEPOINT import_row")
+                            .execute(&mut *tx)
+                            .await?;
+                        outcomes.push(None);
+                    }
+                    Err(e) => {
+                        sqlx::query("ROLLBACK TO SAVEPOINT import_row")
+                            .execute(&mut *tx)
+                            .await?;
+                        outcomes.push(Some(e.to_string()));
+                    }
+                }
             }
-
-            let batch_resp = self.execute_batch(&statements).await;
-            match batch_resp {
-                DbResponse::BatchResult { .. } => {
-                    let inserted = ((batch_idx + 1) * batch_size).min(total);
-                    let _ = self
-                        .response_tx
-                        .send(DbResponse::ImportProgress { inserted, total });
+            tx.rollback().await?;
+        }
+        DbPool::MySQL(pool) => {
+            let mut tx = pool.begin().await?;
+            for sql in statements {
+                sqlx::query("SAVEPOINT import_row")
+                    .execute(&mut *tx)
+                    .await?;
+                match sqlx::query(sql).execute(&mut *tx).await {
+                    Ok(_) => {
+                        sqlx::query("RELEASE SAVEPOINT import_row")
+                            .execute(&mut *tx)
+                            .await?;
+                        outcomes.push(None);
+                    }
+                    Err(e) => {
+                        sqlx::query("ROLLBACK TO SAVEPOINT import_row")
+                            .execute(&mut *tx)
+                            .await?;
+                        outcomes.push(Some(e.to_string()));
+                    }
                 }
-                DbResponse::Error(e) => {
-                    let _ = self.response_tx.send(DbResponse::Error(format!(
-                        "Import failed at row {}: {}",
-                        batch_idx * batch_size,
-                        e
-                    )));
-                    return;
+            }
+            tx.rollback().await?;
+        }
+        DbPool::SQLite(pool) => {
+            let mut tx = pool.begin().await?;
+            for sql in statements {
+                sqlx::query("SAVEPOINT import_row")
+                    .execute(&mut *tx)
+                    .await?;
+                match sqlx::query(sql).execute(&mut *tx).await {
+                    Ok(_) => {
+                        sqlx::query("RELEASE SAVEPOINT import_row")
+                            .execute(&mut *tx)
+                            .await?;
+                        outcomes.push(None);
+                    }
+                    Err(e) => {
+                        sqlx::query("ROLLBACK TO SAVEPOINT import_row")
+                            .execute(&mut *tx)
+                            .await?;
+                        outcomes.push(Some(e.to_string()));
+                    }
                 }
-                _ => {}
             }
+            tx.rollback().await?;
         }
-
-        let _ = self.response_tx.send(DbResponse::ImportComplete { total });
-    }
-
-    fn get_primary_keys(&self, table_name: &str) -> Option<Vec<String>> {
-        let schema = self.cached_schema.as_ref()?;
-        let normalized = super::normalize_table_name(table_name);
-        let table = schema.tables.iter().find(|t| t.name == normalized)?;
-        let pks: Vec<String> = table
-            .columns
-            .iter()
-            .filter(|c| c.is_primary_key)
-            .map(|c| c.name.clone())
-            .collect();
-        if pks.is_empty() {
-            None
-        } else {
-            Some(pks)
+        DbPool::DuckDB(pool) => {
+            outcomes = pool.dry_run(statements).await.map_err(driver_error)?;
         }
-    }
-
-    async fn disconnect(&mut self) -> DbResponse {
-        if let Some(pool) = self.pool.take() {
-            match pool {
-                DbPool::Postgres(p) => p.close().await,
-                DbPool::MySQL(p) => p.close().await,
-            }
+        DbPool::ClickHouse(_) => {
+            return Err(driver_error(
+                "ClickHouse has no transactions to roll a dry run back".to_string(),
+            ));
+        }
+        DbPool::BigQuery(_) => return Err(driver_error(READ_ONLY_ERROR.to_string())),
+        DbPool::Odbc(pool) => {
+            outcomes = pool.dry_run(statements).await.map_err(driver_error)?;
         }
-        self.db_type = None;
-        self.schema = None;
-        DbResponse::Disconnected
     }
+    Ok(outcomes)
 }
 
-fn format_pg_value(row: &PgRow, i: usize) -> String {
+fn format_pg_value(row: &PgRow, i: usize) -> Option<String> {
     let raw = match row.try_get_raw(i) {
         Ok(v) => v,
-        Err(_) => return "?".to_string(),
+        Err(_) => return Some("?".to_string()),
     };
 
     if raw.is_null() {
-        return "NULL".to_string();
+        return None;
     }
 
     let value = row
@@ -1417,17 +5199,17 @@ fn format_pg_value(row: &PgRow, i: usize) -> String {
         })
         .unwrap_or_else(|| "?".to_string());
 
-    truncate_value(value)
+    Some(truncate_value(value))
 }
 
-fn format_mysql_value(row: &MySqlRow, i: usize) -> String {
+fn format_mysql_value(row: &MySqlRow, i: usize) -> Option<String> {
     let raw = match row.try_get_raw(i) {
         Ok(v) => v,
-        Err(_) => return "?".to_string(),
+        Err(_) => return Some("?".to_string()),
     };
 
     if raw.is_null() {
-        return "NULL".to_string();
+        return None;
     }
 
     let value = row
@@ -1480,10 +5262,393 @@ fn format_mysql_value(row: &MySqlRow, i: usize) -> String {
         })
         .unwrap_or_else(|| "?".to_string());
 
-    truncate_value(value)
+    Some(truncate_value(value))
+}
+
+fn format_sqlite_value(row: &SqliteRow, i: usize) -> Option<String> {
+    let raw = match row.try_get_raw(i) {
+        Ok(v) => v,
+        Err(_) => return Some("?".to_string()),
+    };
+
+    if raw.is_null() {
+        return None;
+    }
+
+    let value = row
+        .try_get::<String, _>(i)
+        .ok()
+        .or_else(|| row.try_get::<i64, _>(i).ok().map(|n| n.to_string()))
+        .or_else(|| row.try_get::<f64, _>(i).ok().map(format_float))
+        .or_else(|| row.try_get::<bool, _>(i).ok().map(|b| b.to_string()))
+        .or_else(|| {
+            row.try_get::<Vec<u8>, _>(i)
+                .ok()
+                .map(|bytes| format_blob(&bytes))
+        })
+        .unwrap_or_else(|| "?".to_string());
+
+    Some(truncate_value(value))
+}
+
+fn elapsed_ms(since: std::time::Instant) -> f64 {
+    since.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Time until the last row of the top node in MySQL's EXPLAIN ANALYZE tree,
+/// e.g. `-> Filter: ... (actual time=0.05..1.23 rows=10 loops=1)`.
+fn mysql_root_time(tree: &str) -> Option<f64> {
+    let (_, rest) = tree.lines().next()?.split_once("actual time=")?;
+    let (_, last_row) = rest.split_once("..")?;
+    last_row.split_whitespace().next()?.parse().ok()
+}
+
+/// CockroachDB's pg_index can't be unnested into key columns; information_schema.statistics
+/// lists them in order. The table name comes first when `with_table` is set.
+fn cockroach_indexes_sql(filter: &str, with_table: bool) -> String {
+    format!(
+        r#"
+            SELECT
+                {}index_name::TEXT,
+                array_agg(column_name::TEXT ORDER BY seq_in_index) as columns,
+                bool_and(non_unique = 'NO') as is_unique,
+                (index_name = 'primary' OR index_name = table_name || '_pkey') as is_primary,
+                'prefix'::TEXT as index_type
+            FROM information_schema.statistics
+            WHERE storing = 'NO' AND implicit = 'NO' AND {}
+            GROUP BY table_name, index_name
+            ORDER BY table_name, index_name
+        "#,
+        if with_table { "table_name::TEXT, " } else { "" },
+        filter
+    )
+}
+
+/// Table names of a fetched schema, for backends without a catalog query to list them.
+fn list_schema_tables(schema: Result<SchemaInfo, String>) -> DbResponse {
+    match schema {
+        Ok(schema) => DbResponse::QueryResult(QueryResult {
+            sql: String::new(),
+            columns: vec!["table_name".to_string()],
+            column_types: vec!["TEXT".to_string()],
+            rows: schema.tables.into_iter().map(|t| vec![t.name]).collect(),
+            null_cells: HashSet::new(),
+            execution_time_ms: 0,
+            source_table: None,
+            primary_keys: Vec::new(),
+            bytes_processed: None,
+            timing: QueryTiming::default(),
+            fetch_stopped: false,
+            limited_to: None,
+            row_versions: vec![],
+        }),
+        Err(e) => DbResponse::Error(e),
+    }
+}
+
+/// DuckDB, ClickHouse, BigQuery and ODBC don't go through sqlx; their errors are passed on as sqlx driver
+/// errors so the shared connect, batch and dry-run paths handle them like the other backends.
+fn driver_error(message: String) -> sqlx::Error {
+    sqlx::Error::AnyDriverError(message.into())
+}
+
+/// MariaDB and CockroachDB identify themselves in their version string; everything else
+/// follows the pool type. Also returns the server version, when there is one to show.
+async fn detect_server(pool: &DbPool) -> (SqlDialect, Option<String>) {
+    match pool {
+        DbPool::Postgres(pool) => {
+            match sqlx::query_scalar::<_, String>("SELECT version()")
+                .fetch_one(pool)
+                .await
+            {
+                // "CockroachDB CCL v23.1.11 (x86_64-pc-linux-gnu, ...)"
+                Ok(version) if version.starts_with("CockroachDB") => (
+                    SqlDialect::CockroachDB,
+                    version
+                        .split_whitespace()
+                        .find(|part| part.starts_with('v'))
+                        .map(str::to_string),
+                ),
+                // "PostgreSQL 16.2 on x86_64-pc-linux-gnu, ..."
+                Ok(version) => (
+                    SqlDialect::PostgreSQL,
+                    version.split_whitespace().nth(1).map(str::to_string),
+                ),
+                Err(e) => {
+                    tracing::warn!("Could not read the server version: {}", e);
+                    (SqlDialect::PostgreSQL, None)
+                }
+            }
+        }
+        DbPool::SQLite(_) => (SqlDialect::SQLite, None),
+        DbPool::DuckDB(_) => (SqlDialect::DuckDB, None),
+        DbPool::ClickHouse(_) => (SqlDialect::ClickHouse, None),
+        DbPool::BigQuery(_) => (SqlDialect::BigQuery, None),
+        // Shown in place of a version, since the dialect alone says nothing
+        DbPool::Odbc(pool) => (SqlDialect::Odbc, Some(pool.dbms_name.clone())),
+        DbPool::MySQL(pool) => {
+            match sqlx::query_scalar::<_, String>("SELECT VERSION()")
+                .fetch_one(pool)
+                .await
+            {
+                // "10.11.6-MariaDB-1:10.11.6+maria~ubu2204"
+                Ok(version) => {
+                    let dialect = if version.contains("MariaDB") {
+                        SqlDialect::MariaDB
+                    } else {
+                        SqlDialect::MySQL
+                    };
+                    let number = version.split('-').next().unwrap_or(&version).to_string();
+                    (dialect, Some(number))
+                }
+                Err(e) => {
+                    tracing::warn!("Could not read the server version: {}", e);
+                    (SqlDialect::MySQL, None)
+                }
+            }
+        }
+    }
+}
+
+/// Blobs as an `X'..'` hex literal, the way the sqlite3 shell quotes them.
+pub(super) fn format_blob(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+    format!("X'{}'", hex)
+}
+
+fn sqlite_options(config: &ConnectionConfig) -> SqliteConnectOptions {
+    SqliteConnectOptions::new()
+        .filename(&config.database)
+        .foreign_keys(true)
+}
+
+/// Whether the server replicates from a source, which shows as a replica status row.
+/// MySQL before 8.0.22 and MariaDB before 10.5 only know the SLAVE spelling. None when
+/// neither is allowed, as both need the REPLICATION CLIENT privilege.
+async fn mysql_is_replica(pool: &MySqlPool) -> Option<bool> {
+    for sql in ["SHOW REPLICA STATUS", "SHOW SLAVE STATUS"] {
+        if let Ok(rows) = sqlx::query(sql).fetch_all(pool).await {
+            return Some(!rows.is_empty());
+        }
+    }
+    None
+}
+
+/// Current values of `SQLITE_PRAGMAS`.
+async fn sqlite_pragmas(pool: &SqlitePool) -> Result<Vec<(String, Option<String>)>, sqlx::Error> {
+    let mut values = Vec::with_capacity(SQLITE_PRAGMAS.len());
+    for name in SQLITE_PRAGMAS {
+        let row = sqlx::query(&format!("PRAGMA {}", name))
+            .fetch_optional(pool)
+            .await?;
+        let value = row.and_then(|row| format_sqlite_value(&row, 0));
+        values.push((name.to_string(), value));
+    }
+    Ok(values)
+}
+
+/// Columns, indexes and constraints of a table from its `pragma_*` table-valued functions.
+async fn sqlite_table_info(pool: &SqlitePool, table: &str) -> Result<TableInfo, sqlx::Error> {
+    let create_sql: Option<String> =
+        sqlx::query_scalar("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+            .bind(table)
+            .fetch_optional(pool)
+            .await?
+            .flatten();
+    let create_sql = create_sql.unwrap_or_default();
+    let autoincrement = create_sql.to_uppercase().contains("AUTOINCREMENT");
+
+    // hidden is 2 for virtual and 3 for stored generated columns
+    let columns: Vec<(String, String, bool, Option<String>, i64, i64)> = sqlx::query_as(
+        "SELECT name, type, \"notnull\", dflt_value, pk, hidden \
+         FROM pragma_table_xinfo(?) WHERE hidden != 1 ORDER BY cid",
+    )
+    .bind(table)
+    .fetch_all(pool)
+    .await?;
+
+    let pk_count = columns.iter().filter(|c| c.4 > 0).count();
+    let mut pk_columns: Vec<(i64, String)> = Vec::new();
+    let columns = columns
+        .into_iter()
+        .map(|(name, data_type, not_null, default_value, pk, hidden)| {
+            if pk > 0 {
+                pk_columns.push((pk, name.clone()));
+            }
+            // A lone INTEGER PRIMARY KEY is an alias for the rowid
+            let identity_generation =
+                (pk > 0 && pk_count == 1 && data_type.eq_ignore_ascii_case("INTEGER")).then(|| {
+                    if autoincrement {
+                        "AUTOINCREMENT".to_string()
+                    } else {
+                        "rowid".to_string()
+                    }
+                });
+            let generation_expression = if hidden >= 2 {
+                sqlite_generated_expression(&create_sql, &name)
+            } else {
+                None
+            };
+            ColumnInfo {
+                nullable: !not_null && pk == 0,
+                is_primary_key: pk > 0,
+                name,
+                data_type,
+                default_value,
+                identity_generation,
+                generation_expression,
+                enum_values: Vec::new(),
+                collation: None,
+                comment: None,
+                column_type: None,
+                extra: None,
+            }
+        })
+        .collect();
+    pk_columns.sort();
+
+    let index_list: Vec<(String, bool, String)> =
+        sqlx::query_as("SELECT name, \"unique\", origin FROM pragma_index_list(?) ORDER BY seq")
+            .bind(table)
+            .fetch_all(pool)
+            .await?;
+    let mut indexes = Vec::new();
+    let mut constraints = Vec::new();
+    for (name, is_unique, origin) in index_list {
+        let index_columns: Vec<String> = sqlx::query_scalar(
+            "SELECT COALESCE(name, '<expression>') FROM pragma_index_info(?) ORDER BY seqno",
+        )
+        .bind(&name)
+        .fetch_all(pool)
+        .await?;
+        if origin == "u" {
+            constraints.push(ConstraintInfo {
+                name: name.clone(),
+                constraint_type: "UNIQUE".to_string(),
+                columns: index_columns.clone(),
+                foreign_table: None,
+                foreign_columns: None,
+                check_clause: None,
+            });
+        }
+        indexes.push(IndexInfo {
+            name,
+            columns: index_columns,
+            is_unique,
+            is_primary: origin == "pk",
+            index_type: "btree".to_string(),
+        });
+    }
+
+    if !pk_columns.is_empty() {
+        let columns: Vec<String> = pk_columns.into_iter().map(|(_, name)| name).collect();
+        // A rowid primary key has no index of its own, so list it like the others
+        if !indexes.iter().any(|i| i.is_primary) {
+            indexes.insert(
+                0,
+                IndexInfo {
+                    name: format!("{}_pkey", table),
+                    columns: columns.clone(),
+                    is_unique: true,
+                    is_primary: true,
+                    index_type: "rowid".to_string(),
+                },
+            );
+        }
+        constraints.insert(
+            0,
+            ConstraintInfo {
+                name: format!("{}_pkey", table),
+                constraint_type: "PRIMARY KEY".to_string(),
+                columns,
+                foreign_table: None,
+                foreign_columns: None,
+                check_clause: None,
+            },
+        );
+    }
+
+    let foreign_keys: Vec<(i64, String, String, Option<String>)> = sqlx::query_as(
+        "SELECT id, \"table\", \"from\", \"to\" FROM pragma_foreign_key_list(?) ORDER BY id, seq",
+    )
+    .bind(table)
+    .fetch_all(pool)
+    .await?;
+    let mut foreign: Vec<(i64, ConstraintInfo)> = Vec::new();
+    for (id, foreign_table, from, to) in foreign_keys {
+        if let Some((_, fk)) = foreign.iter_mut().find(|(fk_id, _)| *fk_id == id) {
+            fk.columns.push(from);
+            if let (Some(cols), Some(to)) = (fk.foreign_columns.as_mut(), to) {
+                cols.push(to);
+            }
+            continue;
+        }
+        foreign.push((
+            id,
+            ConstraintInfo {
+                name: format!("{}_fk{}", table, id),
+                constraint_type: "FOREIGN KEY".to_string(),
+                columns: vec![from],
+                foreign_table: Some(foreign_table),
+                // A missing target means the referenced table's primary key
+                foreign_columns: to.map(|to| vec![to]),
+                check_clause: None,
+            },
+        ));
+    }
+    constraints.extend(foreign.into_iter().map(|(_, fk)| fk));
+
+    Ok(TableInfo {
+        name: table.to_string(),
+        columns,
+        indexes,
+        constraints,
+        row_estimate: 0,
+        collation: None,
+        comment: None,
+    })
+}
+
+/// The expression inside `GENERATED ALWAYS AS (...)` / `AS (...)` for a column, read from
+/// the table's CREATE statement since no pragma reports it.
+fn sqlite_generated_expression(create_sql: &str, column: &str) -> Option<String> {
+    let upper = create_sql.to_ascii_uppercase();
+    let column_upper = column.to_ascii_uppercase();
+    let mut search_from = 0;
+    while let Some(found) = upper[search_from..].find(&column_upper) {
+        let start = search_from + found;
+        search_from = start + column_upper.len();
+        // Must be the start of a column definition: after '(' or ',' and whitespace/quotes
+        let before = upper[..start].trim_end_matches(['"', '`', '[']).trim_end();
+        if !(before.ends_with('(') || before.ends_with(',')) {
+            continue;
+        }
+        let definition = &upper[search_from..];
+        let paren = definition.find('(')?;
+        let head = &definition[..paren];
+        if head.contains(',') || !head.trim_end().ends_with(" AS") {
+            return None;
+        }
+        let open = search_from + paren;
+        let mut depth = 0;
+        for (offset, c) in create_sql[open..].char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(create_sql[open + 1..open + offset].trim().to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        return None;
+    }
+    None
 }
 
-fn truncate_value(value: String) -> String {
+pub(super) fn truncate_value(value: String) -> String {
     if value.len() > MAX_VALUE_LEN {
         let mut truncated = value[..MAX_VALUE_LEN].to_string();
         truncated.push_str("...[truncated]");
@@ -1494,7 +5659,7 @@ fn truncate_value(value: String) -> String {
 }
 
 #[inline]
-fn format_float(n: f64) -> String {
+pub(super) fn format_float(n: f64) -> String {
     if n.fract() == 0.0 {
         format!("{:.0}", n)
     } else {
@@ -0,0 +1,134 @@
+//! Passwords from the files the command line clients read, so they need not be saved here:
+//! `~/.pgpass` (or `PGPASSFILE`) for PostgreSQL and `~/.my.cnf` for MySQL.
+
+use std::fs;
+use std::path::PathBuf;
+
+/// Password of the first `.pgpass` line matching the connection, as libpq picks it.
+pub(crate) fn pgpass_password(host: &str, port: u16, database: &str, user: &str) -> Option<String> {
+    let content = fs::read_to_string(pgpass_path()?).ok()?;
+    find_pgpass_password(&content, host, port, database, user)
+}
+
+/// Password for `user` at `host` from the `[client]` and `[mysql]` groups of `~/.my.cnf`.
+pub(crate) fn mycnf_password(host: &str, user: &str) -> Option<String> {
+    let content = fs::read_to_string(home_file(".my.cnf")?).ok()?;
+    find_mycnf_password(&content, host, user)
+}
+
+fn pgpass_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("PGPASSFILE") {
+        return Some(PathBuf::from(path));
+    }
+    if cfg!(windows) {
+        directories::BaseDirs::new().map(|d| d.data_dir().join("postgresql").join("pgpass.conf"))
+    } else {
+        home_file(".pgpass")
+    }
+}
+
+fn home_file(name: &str) -> Option<PathBuf> {
+    directories::BaseDirs::new().map(|d| d.home_dir().join(name))
+}
+
+/// Lines are `host:port:database:user:password`; `*` matches anything and `\` escapes
+/// `:` and `\`.
+fn find_pgpass_password(
+    content: &str,
+    host: &str,
+    port: u16,
+    database: &str,
+    user: &str,
+) -> Option<String> {
+    let port = port.to_string();
+    let wanted = [host, port.as_str(), database, user];
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| {
+            let fields = split_pgpass_line(line);
+            (fields.len() == 5).then_some(fields)
+        })
+        .find(|fields| {
+            fields[..4]
+                .iter()
+                .zip(wanted)
+                .all(|(field, value)| field == "*" || field == value)
+        })
+        .map(|mut fields| fields.remove(4))
+}
+
+fn split_pgpass_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => field.extend(chars.next()),
+            ':' => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Settings of one `[client]` or `[mysql]` group of an option file
+#[derive(Default)]
+struct OptionGroup {
+    user: Option<String>,
+    host: Option<String>,
+    password: Option<String>,
+}
+
+/// Later groups and lines override earlier ones, like the client's own option parsing.
+/// A group naming another `user` or `host` is skipped.
+fn find_mycnf_password(content: &str, host: &str, user: &str) -> Option<String> {
+    let mut groups = Vec::new();
+    let mut group: Option<OptionGroup> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            groups.extend(group.take());
+            if matches!(name.trim(), "client" | "mysql") {
+                group = Some(OptionGroup::default());
+            }
+            continue;
+        }
+        let Some(group) = group.as_mut() else {
+            continue;
+        };
+        let (key, value) = line.split_once('=').unwrap_or((line, ""));
+        let value = Some(unquote(value.trim()).to_string());
+        match key.trim() {
+            "user" => group.user = value,
+            "host" => group.host = value,
+            "password" => group.password = value,
+            _ => {}
+        }
+    }
+    groups.extend(group);
+
+    groups
+        .into_iter()
+        .rev()
+        .filter(|g| g.user.as_deref().is_none_or(|u| u == user))
+        .filter(|g| g.host.as_deref().is_none_or(|h| h == host))
+        .find_map(|g| g.password)
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
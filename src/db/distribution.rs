@@ -0,0 +1,205 @@
+use super::{quote_identifier, DatabaseType, QueryResult};
+
+/// Bars in a numeric range distribution
+const RANGE_BUCKETS: usize = 20;
+/// Most frequent values shown for a top-values distribution
+const TOP_VALUES: usize = 20;
+
+/// How a column's values are grouped into bars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistributionKind {
+    /// Dates and timestamps by calendar month
+    Months,
+    /// Numbers in equal-width ranges between their minimum and maximum
+    Ranges,
+    /// The most frequent values
+    TopValues,
+}
+
+impl DistributionKind {
+    pub const ALL: [DistributionKind; 3] = [
+        DistributionKind::Months,
+        DistributionKind::Ranges,
+        DistributionKind::TopValues,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DistributionKind::Months => "By month",
+            DistributionKind::Ranges => "By range",
+            DistributionKind::TopValues => "Top values",
+        }
+    }
+
+    /// Grouping suited to a column of `data_type`.
+    pub fn for_column(db_type: DatabaseType, data_type: &str) -> Self {
+        let lower = data_type.to_lowercase();
+        // No portable month expression over ODBC
+        if (lower.contains("date") || lower.contains("timestamp")) && db_type != DatabaseType::Odbc
+        {
+            DistributionKind::Months
+        } else if [
+            "int", "serial", "numeric", "decimal", "real", "double", "float",
+        ]
+        .iter()
+        .any(|t| lower.contains(t))
+            && !lower.contains("interval")
+        {
+            DistributionKind::Ranges
+        } else {
+            DistributionKind::TopValues
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistributionBucket {
+    pub label: String,
+    pub count: u64,
+}
+
+/// Row counts of one column's values, grouped into bars.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    pub table: String,
+    pub column: String,
+    pub kind: DistributionKind,
+    pub buckets: Vec<DistributionBucket>,
+    pub total: u64,
+    pub nulls: u64,
+    /// Non-null rows outside the shown top values
+    pub other: u64,
+}
+
+/// Query counting the table's rows and the column's nulls, plus the column's bounds
+/// when they are needed to lay out ranges.
+pub fn distribution_stats_sql(
+    db_type: DatabaseType,
+    table: &str,
+    column: &str,
+    kind: DistributionKind,
+) -> String {
+    let column = quote_identifier(db_type, column);
+    let bounds = if kind == DistributionKind::Ranges {
+        format!(", MIN({0}), MAX({0})", column)
+    } else {
+        String::new()
+    };
+    format!(
+        "SELECT COUNT(*), COUNT({}){} FROM {}",
+        column,
+        bounds,
+        quote_identifier(db_type, table)
+    )
+}
+
+/// Grouping query for the bars. Ranges need the column's `bounds` from the stats query.
+pub fn distribution_sql(
+    db_type: DatabaseType,
+    table: &str,
+    column: &str,
+    kind: DistributionKind,
+    bounds: Option<(f64, f64)>,
+) -> String {
+    let quoted_table = quote_identifier(db_type, table);
+    let column = quote_identifier(db_type, column);
+    match kind {
+        DistributionKind::Months => {
+            let month = match db_type {
+                DatabaseType::PostgreSQL => format!("to_char({}, 'YYYY-MM')", column),
+                DatabaseType::MySQL => format!("DATE_FORMAT({}, '%Y-%m')", column),
+                DatabaseType::SQLite => format!("strftime('%Y-%m', {})", column),
+                DatabaseType::DuckDB => format!("strftime({}, '%Y-%m')", column),
+                DatabaseType::ClickHouse => format!("formatDateTime({}, '%Y-%m')", column),
+                DatabaseType::BigQuery => {
+                    format!("FORMAT_TIMESTAMP('%Y-%m', CAST({} AS TIMESTAMP))", column)
+                }
+                DatabaseType::Odbc => column.clone(),
+            };
+            format!(
+                "SELECT {} AS month, COUNT(*) FROM {} WHERE {} IS NOT NULL GROUP BY 1 ORDER BY 1",
+                month, quoted_table, column
+            )
+        }
+        DistributionKind::Ranges => {
+            let (low, high) = bounds.unwrap_or((0.0, 0.0));
+            let width = range_width(low, high);
+            // The offset is never negative, so truncating is flooring
+            let bucket = match db_type {
+                DatabaseType::SQLite => {
+                    format!("CAST(({} - {}) / {} AS INTEGER)", column, low, width)
+                }
+                _ => format!("FLOOR(({} - {}) / {})", column, low, width),
+            };
+            format!(
+                "SELECT {} AS bucket, COUNT(*) FROM {} WHERE {} IS NOT NULL GROUP BY 1 ORDER BY 1",
+                bucket, quoted_table, column
+            )
+        }
+        DistributionKind::TopValues => format!(
+            "SELECT {0} AS value, COUNT(*) AS n FROM {1} WHERE {0} IS NOT NULL GROUP BY {0} ORDER BY n DESC LIMIT {2}",
+            column, quoted_table, TOP_VALUES
+        ),
+    }
+}
+
+/// Range covered by each bar. A column holding one value gets a single bar.
+fn range_width(low: f64, high: f64) -> f64 {
+    if high > low {
+        (high - low) / RANGE_BUCKETS as f64
+    } else {
+        1.0
+    }
+}
+
+/// Turn the grouping query's (label or bucket index, count) rows into bars. Ranges get
+/// every bar between the bounds, empty ones included, so gaps show.
+pub fn distribution_buckets(
+    kind: DistributionKind,
+    result: &QueryResult,
+    bounds: Option<(f64, f64)>,
+) -> Vec<DistributionBucket> {
+    let rows = result
+        .rows
+        .iter()
+        .map(|row| (row[0].as_str(), row[1].trim().parse::<u64>().unwrap_or(0)));
+    if kind != DistributionKind::Ranges {
+        return rows
+            .map(|(label, count)| DistributionBucket {
+                label: label.to_string(),
+                count,
+            })
+            .collect();
+    }
+
+    let (low, high) = bounds.unwrap_or((0.0, 0.0));
+    let width = range_width(low, high);
+    let bars = if high > low { RANGE_BUCKETS } else { 1 };
+    let mut counts = vec![0u64; bars];
+    for (index, count) in rows {
+        let index = index.trim().parse::<f64>().unwrap_or(0.0).max(0.0) as usize;
+        // The maximum itself lands one past the last bar
+        counts[index.min(bars - 1)] += count;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let start = low + width * i as f64;
+            let label = if bars == 1 {
+                format_bound(low)
+            } else {
+                format!("{} – {}", format_bound(start), format_bound(start + width))
+            };
+            DistributionBucket { label, count }
+        })
+        .collect()
+}
+
+fn format_bound(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.2}", value)
+    }
+}
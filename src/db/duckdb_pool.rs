@@ -0,0 +1,478 @@
+use duckdb::arrow::datatypes::DataType;
+use duckdb::types::{TimeUnit, Value};
+use duckdb::Connection;
+use std::sync::{Arc, Mutex};
+
+use super::connection::{format_blob, format_float, truncate_value, BatchOutcome, BatchReporter};
+use super::{ColumnInfo, ConstraintInfo, IndexInfo, SchemaInfo, TableInfo};
+
+/// Columns, column types and formatted rows of a query, with NULL cells as None.
+pub(super) type DuckDbRows = (Vec<String>, Vec<String>, Vec<Vec<Option<String>>>);
+
+/// An embedded DuckDB database. DuckDB has no sqlx driver, so the blocking
+/// connection is shared behind a mutex and every call runs on the blocking pool.
+#[derive(Clone)]
+pub(super) struct DuckDbPool {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl DuckDbPool {
+    /// Open a database file, or an in-memory database when `path` is empty or `:memory:`.
+    pub(super) async fn open(path: &str) -> Result<Self, String> {
+        let path = path.trim().to_string();
+        let conn = tokio::task::spawn_blocking(move || {
+            if path.is_empty() || path == ":memory:" {
+                Connection::open_in_memory()
+            } else {
+                Connection::open(&path)
+            }
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    async fn run<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> duckdb::Result<T> + Send + 'static,
+    ) -> Result<T, String> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn.lock().map_err(|e| e.to_string())?;
+            f(&mut conn).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    pub(super) async fn query(&self, sql: &str) -> Result<DuckDbRows, String> {
+        let sql = sql.to_string();
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(&sql)?;
+            let mut rows = stmt.query([])?;
+            let mut data = Vec::new();
+            while let Some(row) = rows.next()? {
+                let count = row.as_ref().column_count();
+                let mut values = Vec::with_capacity(count);
+                for i in 0..count {
+                    values.push(match row.get::<_, Value>(i)? {
+                        Value::Null => None,
+                        value => Some(format_duckdb_value(value)),
+                    });
+                }
+                data.push(values);
+            }
+            // Column metadata is only known once the statement has run
+            let (columns, types) = match rows.as_ref() {
+                Some(stmt) => (
+                    stmt.column_names(),
+                    (0..stmt.column_count())
+                        .map(|i| duckdb_type_name(&stmt.column_type(i)))
+                        .collect(),
+                ),
+                None => (Vec::new(), Vec::new()),
+            };
+            Ok((columns, types, data))
+        })
+        .await
+    }
+
+    pub(super) async fn execute(&self, sql: &str) -> Result<u64, String> {
+        let sql = sql.to_string();
+        self.run(move |conn| conn.execute(&sql, []).map(|n| n as u64))
+            .await
+    }
+
+    /// Run statements in one transaction, returning the affected row count
+    /// or the failing step along with its error. With `row_edits` each statement
+    /// must match a row, or nothing is committed.
+    pub(super) async fn batch(
+        &self,
+        statements: &[String],
+        row_edits: bool,
+        reporter: BatchReporter,
+    ) -> Result<BatchOutcome, (&'static str, String)> {
+        let statements = statements.to_vec();
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn.lock().map_err(|e| ("Begin failed", e.to_string()))?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| ("Begin failed", e.to_string()))?;
+            let mut total_affected = 0u64;
+            for (i, sql) in statements.iter().enumerate() {
+                // Dropping the transaction rolls it back
+                reporter.start(i, sql).map_err(|e| ("Batch cancelled", e))?;
+                let affected =
+                    tx.execute(sql, [])
+                        .map_err(|e| ("Batch failed", e.to_string()))? as u64;
+                if row_edits && affected == 0 {
+                    return Ok(BatchOutcome::Unmatched(i));
+                }
+                total_affected += affected;
+            }
+            tx.commit().map_err(|e| ("Commit failed", e.to_string()))?;
+            Ok(BatchOutcome::Committed(total_affected))
+        })
+        .await
+        .map_err(|e| ("Batch failed", e.to_string()))?
+    }
+
+    /// Try each statement in a transaction of its own and roll it back. DuckDB has no
+    /// savepoints, so later statements don't see the rows of earlier ones.
+    pub(super) async fn dry_run(
+        &self,
+        statements: &[String],
+    ) -> Result<Vec<Option<String>>, String> {
+        let statements = statements.to_vec();
+        self.run(move |conn| {
+            let mut outcomes = Vec::with_capacity(statements.len());
+            for sql in &statements {
+                let tx = conn.transaction()?;
+                outcomes.push(tx.execute(sql, []).err().map(|e| e.to_string()));
+                tx.rollback()?;
+            }
+            Ok(outcomes)
+        })
+        .await
+    }
+
+    /// Tables of `schema` (the current schema when None) with their columns,
+    /// constraints and indexes, plus its views.
+    pub(super) async fn schema(&self, schema: Option<String>) -> Result<SchemaInfo, String> {
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT table_name, COALESCE(estimated_size, 0) FROM duckdb_tables() \
+                 WHERE database_name = current_database() \
+                   AND schema_name = COALESCE(?, current_schema()) \
+                 ORDER BY table_name",
+            )?;
+            let names: Vec<(String, i64)> = stmt
+                .query_map(duckdb::params![schema], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?
+                .collect::<duckdb::Result<_>>()?;
+
+            let mut tables = Vec::with_capacity(names.len());
+            for (name, row_estimate) in names {
+                let mut table = table_info(conn, &name, schema.as_deref())?;
+                table.row_estimate = row_estimate;
+                tables.push(table);
+            }
+
+            let mut stmt = conn.prepare(
+                "SELECT view_name FROM duckdb_views() \
+                 WHERE NOT internal AND database_name = current_database() \
+                   AND schema_name = COALESCE(?, current_schema()) \
+                 ORDER BY view_name",
+            )?;
+            let views = stmt
+                .query_map(duckdb::params![schema], |row| row.get(0))?
+                .collect::<duckdb::Result<_>>()?;
+
+            let mut stmt = conn.prepare(
+                "SELECT sequence_name FROM duckdb_sequences() \
+                 WHERE database_name = current_database() \
+                   AND schema_name = COALESCE(?, current_schema()) \
+                 ORDER BY sequence_name",
+            )?;
+            let sequences = stmt
+                .query_map(duckdb::params![schema], |row| row.get(0))?
+                .collect::<duckdb::Result<_>>()?;
+
+            Ok(SchemaInfo {
+                tables,
+                views,
+                sequences,
+            })
+        })
+        .await
+    }
+
+    /// (name, value, description) of every setting.
+    pub(super) async fn settings(
+        &self,
+    ) -> Result<Vec<(String, Option<String>, Option<String>)>, String> {
+        self.run(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT name, value, description FROM duckdb_settings() ORDER BY name")?;
+            let settings = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect::<duckdb::Result<_>>()?;
+            Ok(settings)
+        })
+        .await
+    }
+
+    /// Columns (name, data type) of a table; empty when it does not exist.
+    pub(super) async fn columns(
+        &self,
+        table: &str,
+        schema: Option<String>,
+    ) -> Result<Vec<(String, String)>, String> {
+        let table = table.to_string();
+        self.run(move |conn| {
+            let mut stmt = conn.prepare(
+                "SELECT column_name, data_type FROM information_schema.columns \
+                 WHERE table_name = ? AND table_schema = COALESCE(?, current_schema()) \
+                 ORDER BY ordinal_position",
+            )?;
+            let columns = stmt
+                .query_map(duckdb::params![table, schema], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })?
+                .collect::<duckdb::Result<_>>()?;
+            Ok(columns)
+        })
+        .await
+    }
+
+    pub(super) async fn table_info(
+        &self,
+        table: &str,
+        schema: Option<String>,
+    ) -> Result<TableInfo, String> {
+        let table = table.to_string();
+        self.run(move |conn| table_info(conn, &table, schema.as_deref()))
+            .await
+    }
+}
+
+fn table_info(conn: &Connection, table: &str, schema: Option<&str>) -> duckdb::Result<TableInfo> {
+    let mut stmt = conn.prepare(
+        "SELECT constraint_type, COALESCE(constraint_name, constraint_type || '_' || constraint_index), \
+                array_to_string(constraint_column_names, ','), \
+                referenced_table, array_to_string(referenced_column_names, ','), expression \
+         FROM duckdb_constraints() \
+         WHERE table_name = ? AND database_name = current_database() \
+           AND schema_name = COALESCE(?, current_schema()) \
+           AND constraint_type <> 'NOT NULL' \
+         ORDER BY constraint_index",
+    )?;
+    let constraints: Vec<ConstraintInfo> = stmt
+        .query_map(duckdb::params![table, schema], |row| {
+            let constraint_type: String = row.get(0)?;
+            let columns: Option<String> = row.get(2)?;
+            let foreign_columns: Option<String> = row.get(4)?;
+            Ok(ConstraintInfo {
+                name: row.get(1)?,
+                columns: split_list(columns),
+                foreign_table: row.get(3)?,
+                foreign_columns: foreign_columns.map(|c| split_list(Some(c))),
+                check_clause: if constraint_type == "CHECK" {
+                    row.get(5)?
+                } else {
+                    None
+                },
+                constraint_type,
+            })
+        })?
+        .collect::<duckdb::Result<_>>()?;
+    let primary_key: Vec<String> = constraints
+        .iter()
+        .find(|c| c.constraint_type == "PRIMARY KEY")
+        .map(|c| c.columns.clone())
+        .unwrap_or_default();
+
+    let mut stmt = conn.prepare(
+        "SELECT column_name, data_type, is_nullable, column_default, comment \
+         FROM duckdb_columns() \
+         WHERE table_name = ? AND database_name = current_database() \
+           AND schema_name = COALESCE(?, current_schema()) \
+         ORDER BY column_index",
+    )?;
+    let columns: Vec<ColumnInfo> = stmt
+        .query_map(duckdb::params![table, schema], |row| {
+            let name: String = row.get(0)?;
+            let data_type: String = row.get(1)?;
+            let is_primary_key = primary_key.contains(&name);
+            Ok(ColumnInfo {
+                nullable: row.get::<_, bool>(2)? && !is_primary_key,
+                default_value: row.get(3)?,
+                is_primary_key,
+                identity_generation: None,
+                generation_expression: None,
+                enum_values: enum_values(&data_type),
+                collation: None,
+                comment: row.get(4)?,
+                column_type: None,
+                extra: None,
+                name,
+                data_type,
+            })
+        })?
+        .collect::<duckdb::Result<_>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT index_name, is_unique, is_primary, sql FROM duckdb_indexes() \
+         WHERE table_name = ? AND database_name = current_database() \
+           AND schema_name = COALESCE(?, current_schema()) \
+         ORDER BY index_name",
+    )?;
+    let mut indexes: Vec<IndexInfo> = stmt
+        .query_map(duckdb::params![table, schema], |row| {
+            let sql: Option<String> = row.get(3)?;
+            Ok(IndexInfo {
+                name: row.get(0)?,
+                columns: sql.as_deref().map(index_columns).unwrap_or_default(),
+                is_unique: row.get(1)?,
+                is_primary: row.get(2)?,
+                index_type: "art".to_string(),
+            })
+        })?
+        .collect::<duckdb::Result<_>>()?;
+    // Key constraints are backed by indexes that duckdb_indexes() leaves out
+    for constraint in &constraints {
+        if matches!(
+            constraint.constraint_type.as_str(),
+            "PRIMARY KEY" | "UNIQUE"
+        ) {
+            indexes.push(IndexInfo {
+                name: constraint.name.clone(),
+                columns: constraint.columns.clone(),
+                is_unique: true,
+                is_primary: constraint.constraint_type == "PRIMARY KEY",
+                index_type: "art".to_string(),
+            });
+        }
+    }
+
+    let comment: Option<String> = conn
+        .query_row(
+            "SELECT comment FROM duckdb_tables() \
+             WHERE table_name = ? AND database_name = current_database() \
+               AND schema_name = COALESCE(?, current_schema())",
+            duckdb::params![table, schema],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten();
+
+    Ok(TableInfo {
+        name: table.to_string(),
+        columns,
+        indexes,
+        constraints,
+        row_estimate: 0,
+        collation: None,
+        comment,
+    })
+}
+
+fn split_list(list: Option<String>) -> Vec<String> {
+    list.map(|l| l.split(',').map(|c| c.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// Labels of an inline `ENUM('a', 'b')` column type.
+fn enum_values(data_type: &str) -> Vec<String> {
+    let Some(list) = data_type
+        .strip_prefix("ENUM(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    else {
+        return Vec::new();
+    };
+    list.split("', '")
+        .map(|label| label.trim_matches('\'').replace("''", "'"))
+        .collect()
+}
+
+/// Column list of a `CREATE INDEX ... ON table(a, b)` statement.
+fn index_columns(sql: &str) -> Vec<String> {
+    let (Some(start), Some(end)) = (sql.find('('), sql.rfind(')')) else {
+        return Vec::new();
+    };
+    if end <= start {
+        return Vec::new();
+    }
+    sql[start + 1..end]
+        .split(',')
+        .map(|c| c.trim().trim_matches('"').to_string())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+/// SQL names for the Arrow types DuckDB reports, so they read like the other backends'.
+fn duckdb_type_name(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Boolean => "BOOLEAN".to_string(),
+        DataType::Int8 => "TINYINT".to_string(),
+        DataType::Int16 => "SMALLINT".to_string(),
+        DataType::Int32 => "INTEGER".to_string(),
+        DataType::Int64 => "BIGINT".to_string(),
+        DataType::UInt8 => "UTINYINT".to_string(),
+        DataType::UInt16 => "USMALLINT".to_string(),
+        DataType::UInt32 => "UINTEGER".to_string(),
+        DataType::UInt64 => "UBIGINT".to_string(),
+        DataType::Float32 => "FLOAT".to_string(),
+        DataType::Float64 => "DOUBLE".to_string(),
+        DataType::Decimal128(p, s) => format!("DECIMAL({},{})", p, s),
+        DataType::Utf8 | DataType::LargeUtf8 => "VARCHAR".to_string(),
+        DataType::Binary | DataType::LargeBinary => "BLOB".to_string(),
+        DataType::Date32 => "DATE".to_string(),
+        DataType::Time64(_) => "TIME".to_string(),
+        DataType::Timestamp(_, Some(_)) => "TIMESTAMPTZ".to_string(),
+        DataType::Timestamp(_, None) => "TIMESTAMP".to_string(),
+        DataType::Interval(_) => "INTERVAL".to_string(),
+        other => other.to_string().to_uppercase(),
+    }
+}
+
+fn to_micros(unit: TimeUnit, value: i64) -> i64 {
+    match unit {
+        TimeUnit::Second => value * 1_000_000,
+        TimeUnit::Millisecond => value * 1_000,
+        TimeUnit::Microsecond => value,
+        TimeUnit::Nanosecond => value / 1_000,
+    }
+}
+
+fn format_duckdb_value(value: Value) -> String {
+    let text = match value {
+        Value::Null => return "NULL".to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::TinyInt(n) => n.to_string(),
+        Value::SmallInt(n) => n.to_string(),
+        Value::Int(n) => n.to_string(),
+        Value::BigInt(n) => n.to_string(),
+        Value::HugeInt(n) => n.to_string(),
+        Value::UTinyInt(n) => n.to_string(),
+        Value::USmallInt(n) => n.to_string(),
+        Value::UInt(n) => n.to_string(),
+        Value::UBigInt(n) => n.to_string(),
+        Value::Float(n) => format_float(n as f64),
+        Value::Double(n) => format_float(n),
+        Value::Decimal(d) => d.to_string(),
+        Value::Text(s) | Value::Enum(s) => s,
+        Value::Blob(bytes) => format_blob(&bytes),
+        Value::Date32(days) => chrono::NaiveDate::from_ymd_opt(1970, 1, 1)
+            .and_then(|epoch| epoch.checked_add_signed(chrono::Duration::days(days as i64)))
+            .map(|d| d.to_string())
+            .unwrap_or_else(|| days.to_string()),
+        Value::Time64(unit, t) => {
+            let micros = to_micros(unit, t);
+            chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+                (micros / 1_000_000) as u32,
+                (micros % 1_000_000) as u32 * 1_000,
+            )
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| t.to_string())
+        }
+        Value::Timestamp(unit, t) => chrono::DateTime::from_timestamp_micros(to_micros(unit, t))
+            .map(|ts| ts.naive_utc().to_string())
+            .unwrap_or_else(|| t.to_string()),
+        Value::List(items) | Value::Array(items) => format!(
+            "[{}]",
+            items
+                .into_iter()
+                .map(format_duckdb_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        other => format!("{:?}", other),
+    };
+    truncate_value(text)
+}
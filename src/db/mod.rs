@@ -1,15 +1,151 @@
+mod anonymize;
+mod bigquery_pool;
+mod clickhouse_pool;
 mod connection;
+mod credential_files;
+mod distribution;
+mod duckdb_pool;
+mod odbc_pool;
 mod query;
+mod query_tests;
+mod seed;
+mod sql_analysis;
+mod ssh_tunnel;
+mod translate;
 
+pub use anonymize::*;
 pub use connection::*;
+pub use distribution::*;
+pub use query::*;
+pub use query_tests::*;
+pub use seed::*;
+pub use sql_analysis::*;
+pub use ssh_tunnel::trust_host_key;
+pub use translate::*;
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum DatabaseType {
     #[default]
     PostgreSQL,
     MySQL,
+    /// A database file; `ConnectionConfig::database` holds its path
+    SQLite,
+    /// An embedded analytical database file, or in-memory when the path is empty
+    DuckDB,
+    /// Reached over its HTTP interface (port 8123, or 8443 for HTTPS)
+    ClickHouse,
+    /// Google BigQuery, read-only. `ConnectionConfig::user` holds the path of the service
+    /// account JSON key, `database` the project and `schema` the default dataset.
+    BigQuery,
+    /// Fallback for databases without a native backend, through an installed ODBC driver.
+    /// `ConnectionConfig::database` holds the DSN or driver string, see `OdbcMode`.
+    Odbc,
+}
+
+impl DatabaseType {
+    /// Opened from a local file rather than a server
+    pub fn is_file_based(&self) -> bool {
+        matches!(self, DatabaseType::SQLite | DatabaseType::DuckDB)
+    }
+
+    /// Reached at a host and port
+    pub fn has_server(&self) -> bool {
+        !self.is_file_based() && !matches!(self, DatabaseType::BigQuery | DatabaseType::Odbc)
+    }
+
+    /// Authenticates with a password, as opposed to a file or key
+    pub fn uses_password(&self) -> bool {
+        !self.is_file_based() && *self != DatabaseType::BigQuery
+    }
+}
+
+/// SQL flavor spoken by the server. MariaDB connects through the MySQL driver and
+/// CockroachDB through the PostgreSQL one, but both are detected on connect since
+/// their features differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SqlDialect {
+    #[default]
+    PostgreSQL,
+    MySQL,
+    MariaDB,
+    SQLite,
+    DuckDB,
+    ClickHouse,
+    CockroachDB,
+    BigQuery,
+    /// Whatever sits behind an ODBC driver
+    Odbc,
+}
+
+impl SqlDialect {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SqlDialect::PostgreSQL => "PostgreSQL",
+            SqlDialect::MySQL => "MySQL",
+            SqlDialect::MariaDB => "MariaDB",
+            SqlDialect::SQLite => "SQLite",
+            SqlDialect::DuckDB => "DuckDB",
+            SqlDialect::ClickHouse => "ClickHouse",
+            SqlDialect::CockroachDB => "CockroachDB",
+            SqlDialect::BigQuery => "BigQuery",
+            SqlDialect::Odbc => "ODBC",
+        }
+    }
+
+    /// Dialect features the LLM should know to use (or avoid) when writing SQL.
+    pub fn prompt_notes(&self) -> &'static str {
+        match self {
+            SqlDialect::PostgreSQL => {
+                "Supports RETURNING, CTEs, window functions, FILTER, ILIKE and JSONB operators."
+            }
+            SqlDialect::MySQL => {
+                "Quote identifiers with backticks. No RETURNING, FULL OUTER JOIN or sequences; \
+                 use LAST_INSERT_ID() for generated keys."
+            }
+            SqlDialect::MariaDB => {
+                "Quote identifiers with backticks. Supports INSERT/DELETE ... RETURNING, \
+                 sequences (CREATE SEQUENCE, NEXTVAL(seq), LASTVAL(seq)), INTERSECT/EXCEPT \
+                 and system-versioned tables (FOR SYSTEM_TIME AS OF). JSON is stored as LONGTEXT; \
+                 use JSON_VALUE/JSON_EXTRACT, not the ->> operator."
+            }
+            SqlDialect::SQLite => {
+                "Supports RETURNING and window functions. No RIGHT/FULL joins before 3.39; \
+                 dates are text, use date()/strftime()."
+            }
+            SqlDialect::DuckDB => {
+                "PostgreSQL-like syntax. Parquet, CSV and JSON files can be queried directly, \
+                 e.g. FROM 'data.parquet' or read_csv_auto('data.csv'). Supports QUALIFY, \
+                 GROUP BY ALL, SELECT * EXCLUDE (...) and list/struct types."
+            }
+            SqlDialect::ClickHouse => {
+                "Column-oriented analytics database. No transactions, foreign keys or unique \
+                 constraints; UPDATE/DELETE are ALTER TABLE ... UPDATE/DELETE mutations. \
+                 Function names are case-sensitive (toDate, toStartOfHour, countIf, uniqExact, \
+                 arrayJoin); supports LIMIT BY, FINAL, SAMPLE and PREWHERE."
+            }
+            SqlDialect::CockroachDB => {
+                "PostgreSQL-compatible, but prefer UUID keys with gen_random_uuid() over SERIAL \
+                 sequences. Supports UPSERT, AS OF SYSTEM TIME for historical reads and \
+                 RETURNING. No stored procedures before v23.1, triggers before v24.3 or \
+                 advisory locks."
+            }
+            SqlDialect::BigQuery => {
+                "GoogleSQL. Quote identifiers with backticks and qualify tables as \
+                 `dataset.table`. The connection is read-only: write SELECT queries only. \
+                 Queries are billed by bytes scanned, so select only the needed columns and \
+                 filter on partition columns. Supports QUALIFY, SAFE_CAST, UNNEST for arrays, \
+                 STRUCT and ARRAY types; use DATE_TRUNC/TIMESTAMP_TRUNC for time buckets."
+            }
+            SqlDialect::Odbc => {
+                "Reached through a generic ODBC driver, so the database may be anything. \
+                 Stick to ANSI SQL: double-quoted identifiers, standard joins and functions, \
+                 and no LIMIT unless the schema suggests a database that has it."
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -21,23 +157,510 @@ pub struct ConnectionConfig {
     pub password: String,
     pub database: String,
     pub schema: String,
+    pub plan_budget: PlanBudget,
+    /// How `database` names an ODBC data source
+    pub odbc_mode: OdbcMode,
+    /// Reach `host` through a bastion instead of directly
+    pub ssh_tunnel: Option<SshTunnel>,
+    pub pool: PoolSettings,
+    /// Extra `key=value&...` URL parameters for PostgreSQL and MySQL, e.g. `sslmode=require`
+    pub url_params: String,
+}
+
+/// Queries a connection runs at once when `PoolSettings::max_concurrent_queries` is unset
+pub const DEFAULT_QUERY_CONCURRENCY: usize = 4;
+
+/// Connection pool limits for the PostgreSQL, MySQL and SQLite backends.
+/// Unset values keep sqlx's defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PoolSettings {
+    pub max_connections: Option<u32>,
+    /// Queries from the editor that run at once, on every backend; schema and
+    /// table lookups never wait behind them
+    pub max_concurrent_queries: Option<u32>,
+    pub acquire_timeout_secs: Option<u64>,
+    /// Idle connections are closed after this long
+    pub idle_timeout_secs: Option<u64>,
+    /// Set on every new connection, overriding the global default; PostgreSQL and
+    /// MySQL only (MySQL limits SELECTs only)
+    pub statement_timeout_secs: Option<u64>,
+    /// Ping idle connections this often so firewalls don't drop them; unset or 0 is off.
+    /// PostgreSQL, MySQL and ODBC only
+    pub keepalive_secs: Option<u64>,
+}
+
+impl PoolSettings {
+    pub fn query_concurrency(&self) -> usize {
+        self.max_concurrent_queries
+            .map_or(DEFAULT_QUERY_CONCURRENCY, |n| n.max(1) as usize)
+    }
+
+    /// Apply the limits to a sqlx pool builder.
+    pub(crate) fn apply<DB: sqlx::Database>(
+        &self,
+        mut options: sqlx::pool::PoolOptions<DB>,
+    ) -> sqlx::pool::PoolOptions<DB> {
+        if let Some(max) = self.max_connections {
+            options = options.max_connections(max.max(1));
+        }
+        if let Some(secs) = self.acquire_timeout_secs {
+            options = options.acquire_timeout(std::time::Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.idle_timeout_secs {
+            options = options.idle_timeout(std::time::Duration::from_secs(secs));
+        }
+        options
+    }
+}
+
+/// SSH server that forwards connections to the database host, which is resolved
+/// from the SSH server's side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SshTunnel {
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    /// Private key file without a passphrase; the SSH agent's keys are used when empty
+    #[serde(default)]
+    pub key_file: String,
+}
+
+impl Default for SshTunnel {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            port: 22,
+            user: String::new(),
+            key_file: String::new(),
+        }
+    }
+}
+
+/// An SSH server whose host key isn't in ~/.ssh/known_hosts yet. Connecting through it
+/// waits until the user has compared the fingerprint and trusted the key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownHostKey {
+    pub host: String,
+    pub port: u16,
+    /// Key type and SHA-256 fingerprint, as `ssh-keygen -l` shows them
+    pub fingerprint: String,
+    /// The key in OpenSSH format, for known_hosts
+    key: String,
+}
+
+/// Whether an ODBC connection goes through a data source configured in the driver
+/// manager, or spells out the driver and its options itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OdbcMode {
+    /// `database` is a DSN name from odbc.ini or the ODBC Data Source Administrator
+    #[default]
+    Dsn,
+    /// `database` is a full connection string, e.g. `Driver={...};Server=...;`
+    DriverString,
+}
+
+/// Per-connection limits on a query's estimated plan; queries over budget ask before running.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct PlanBudget {
+    pub max_cost: Option<f64>,
+    pub max_rows: Option<f64>,
+}
+
+impl PlanBudget {
+    pub fn is_set(&self) -> bool {
+        self.max_cost.is_some() || self.max_rows.is_some()
+    }
+
+    /// Describe how an estimate exceeds the budget, if it does.
+    pub fn exceeded_by(&self, cost: Option<f64>, rows: Option<f64>) -> Option<String> {
+        let mut reasons = Vec::new();
+        if let (Some(max), Some(cost)) = (self.max_cost, cost) {
+            if cost > max {
+                reasons.push(format!(
+                    "estimated cost {:.0} exceeds budget {:.0}",
+                    cost, max
+                ));
+            }
+        }
+        if let (Some(max), Some(rows)) = (self.max_rows, rows) {
+            if rows > max {
+                reasons.push(format!(
+                    "estimated rows {:.0} exceed budget {:.0}",
+                    rows, max
+                ));
+            }
+        }
+        (!reasons.is_empty()).then(|| reasons.join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IsolationLevel {
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    pub const ALL: [IsolationLevel; 3] = [
+        IsolationLevel::ReadCommitted,
+        IsolationLevel::RepeatableRead,
+        IsolationLevel::Serializable,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// Isolation level and access mode for transactions the worker opens, such as saving grid edits.
+/// `None` keeps the server's default isolation level.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TransactionOptions {
+    pub isolation: Option<IsolationLevel>,
+    pub read_only: bool,
+    /// How many times to rerun the whole transaction after a deadlock or serialization failure
+    /// before giving up and offering a manual retry.
+    pub max_retries: u32,
+}
+
+impl TransactionOptions {
+    /// `SET TRANSACTION` statement for these options, or None when they are the defaults.
+    /// PostgreSQL expects it as the first statement inside the transaction,
+    /// MySQL right before the transaction starts.
+    pub fn set_transaction_sql(&self) -> Option<String> {
+        let mut modes = Vec::new();
+        if let Some(level) = self.isolation {
+            modes.push(format!("ISOLATION LEVEL {}", level.label()));
+        }
+        if self.read_only {
+            modes.push("READ ONLY".to_string());
+        }
+        (!modes.is_empty()).then(|| format!("SET TRANSACTION {}", modes.join(", ")))
+    }
+}
+
+/// Rows for one table of a multi-table import.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableImport {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub conflict: ConflictStrategy,
+}
+
+/// Outcome of a dry-run import.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImportValidation {
+    pub succeeded: usize,
+    pub failed: usize,
+    /// The first failing rows: (table, 1-based row in the file, error)
+    pub failures: Vec<(String, usize, String)>,
+}
+
+/// What an import does with rows that collide with an existing key.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ConflictStrategy {
+    /// Abort the import
+    #[default]
+    Fail,
+    /// Keep the existing row
+    Skip,
+    /// Overwrite the existing row with the imported values
+    Update,
+}
+
+impl ConflictStrategy {
+    pub const ALL: [ConflictStrategy; 3] = [
+        ConflictStrategy::Fail,
+        ConflictStrategy::Skip,
+        ConflictStrategy::Update,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConflictStrategy::Fail => "Stop on conflict",
+            ConflictStrategy::Skip => "Skip conflicting rows",
+            ConflictStrategy::Update => "Update existing rows",
+        }
+    }
+}
+
+/// INSERT statement for one imported row, handling key conflicts per `conflict`.
+/// Updating on PostgreSQL needs the table's primary key as the conflict target.
+pub fn import_insert_sql(
+    db_type: DatabaseType,
+    table: &str,
+    columns: &[String],
+    values: &[String],
+    conflict: ConflictStrategy,
+    primary_keys: &[String],
+) -> Result<String, String> {
+    let col_list = columns.join(", ");
+    let value_list = values.join(", ");
+    let updates = |excluded: &dyn Fn(&str) -> String| {
+        columns
+            .iter()
+            .filter(|c| !primary_keys.contains(c))
+            .map(|c| format!("{} = {}", c, excluded(c)))
+            .collect::<Vec<_>>()
+    };
+    let sql = match (db_type, conflict) {
+        (_, ConflictStrategy::Fail) => format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table, col_list, value_list
+        ),
+        (
+            DatabaseType::PostgreSQL | DatabaseType::SQLite | DatabaseType::DuckDB,
+            ConflictStrategy::Skip,
+        ) => format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT DO NOTHING",
+            table, col_list, value_list
+        ),
+        (
+            DatabaseType::PostgreSQL | DatabaseType::SQLite | DatabaseType::DuckDB,
+            ConflictStrategy::Update,
+        ) => {
+            if primary_keys.is_empty() {
+                return Err(format!(
+                    "{} has no primary key to detect existing rows",
+                    table
+                ));
+            }
+            let updates = updates(&|c| format!("EXCLUDED.{}", c));
+            let action = if updates.is_empty() {
+                "DO NOTHING".to_string()
+            } else {
+                format!("DO UPDATE SET {}", updates.join(", "))
+            };
+            format!(
+                "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) {}",
+                table,
+                col_list,
+                value_list,
+                primary_keys.join(", "),
+                action
+            )
+        }
+        (DatabaseType::MySQL, ConflictStrategy::Skip) => format!(
+            "INSERT IGNORE INTO {} ({}) VALUES ({})",
+            table, col_list, value_list
+        ),
+        (DatabaseType::MySQL, ConflictStrategy::Update) => {
+            // Assigning a key column to itself keeps the statement valid when only keys are imported
+            let mut updates = updates(&|c| format!("VALUES({})", c));
+            if updates.is_empty() {
+                updates = columns.iter().map(|c| format!("{} = {}", c, c)).collect();
+            }
+            format!(
+                "INSERT INTO {} ({}) VALUES ({}) ON DUPLICATE KEY UPDATE {}",
+                table,
+                col_list,
+                value_list,
+                updates.join(", ")
+            )
+        }
+        (DatabaseType::ClickHouse, _) => return Err(
+            "ClickHouse has no unique keys to detect conflicts; import with \"Stop on conflict\""
+                .to_string(),
+        ),
+        (DatabaseType::BigQuery, _) => return Err("BigQuery connections are read-only".to_string()),
+        (DatabaseType::Odbc, _) => return Err(
+            "Conflict handling differs between ODBC databases; import with \"Stop on conflict\""
+                .to_string(),
+        ),
+    };
+    Ok(sql)
+}
+
+/// Total cost and row estimate from an `EXPLAIN` JSON plan.
+/// PostgreSQL reports both on the root plan node; MySQL reports a query cost and
+/// per-table row estimates, of which the largest is used.
+pub fn plan_estimate(
+    db_type: DatabaseType,
+    plan: &serde_json::Value,
+) -> (Option<f64>, Option<f64>) {
+    fn as_number(value: &serde_json::Value) -> Option<f64> {
+        value
+            .as_f64()
+            .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+    }
+    fn max_rows(value: &serde_json::Value) -> Option<f64> {
+        match value {
+            serde_json::Value::Object(map) => map
+                .iter()
+                .filter_map(|(key, v)| {
+                    // MySQL reports rows_examined_per_scan, MariaDB just rows
+                    if key == "rows_examined_per_scan" || key == "rows" {
+                        as_number(v)
+                    } else {
+                        max_rows(v)
+                    }
+                })
+                .reduce(f64::max),
+            serde_json::Value::Array(items) => items.iter().filter_map(max_rows).reduce(f64::max),
+            _ => None,
+        }
+    }
+
+    match db_type {
+        DatabaseType::PostgreSQL => {
+            let root = &plan[0]["Plan"];
+            (
+                as_number(&root["Total Cost"]),
+                as_number(&root["Plan Rows"]),
+            )
+        }
+        DatabaseType::MySQL => (
+            as_number(&plan["query_block"]["cost_info"]["query_cost"])
+                .or_else(|| as_number(&plan["query_block"]["cost"])),
+            max_rows(plan),
+        ),
+        // EXPLAIN QUERY PLAN has no cost or row estimates
+        DatabaseType::SQLite
+        | DatabaseType::DuckDB
+        | DatabaseType::ClickHouse
+        | DatabaseType::BigQuery
+        | DatabaseType::Odbc => (None, None),
+    }
 }
 
 impl ConnectionConfig {
+    /// Fill an empty password from `~/.pgpass` or `~/.my.cnf`, as psql and mysql would.
+    pub fn with_file_password(mut self) -> Self {
+        if self.password.is_empty() {
+            let found = match self.db_type {
+                DatabaseType::PostgreSQL => credential_files::pgpass_password(
+                    &self.host,
+                    self.port,
+                    &self.database,
+                    &self.user,
+                ),
+                DatabaseType::MySQL => credential_files::mycnf_password(&self.host, &self.user),
+                _ => None,
+            };
+            if let Some(password) = found {
+                self.password = password;
+            }
+        }
+        self
+    }
+
     pub fn connection_string(&self) -> String {
         match self.db_type {
-            DatabaseType::PostgreSQL => format!(
-                "postgres://{}:{}@{}:{}/{}",
-                self.user, self.password, self.host, self.port, self.database
-            ),
-            DatabaseType::MySQL => format!(
-                "mysql://{}:{}@{}:{}/{}",
+            DatabaseType::PostgreSQL | DatabaseType::MySQL => ConnectionUrl {
+                db_type: self.db_type,
+                host: self.host.clone(),
+                port: self.port,
+                user: self.user.clone(),
+                password: self.password.clone(),
+                database: self.database.clone(),
+                params: self.url_params.clone(),
+            }
+            .to_url(),
+            DatabaseType::SQLite => format!("sqlite://{}", self.database),
+            DatabaseType::DuckDB => format!("duckdb://{}", self.database),
+            DatabaseType::ClickHouse => format!(
+                "clickhouse://{}:{}@{}:{}/{}",
                 self.user, self.password, self.host, self.port, self.database
             ),
+            DatabaseType::BigQuery => format!("bigquery://{}/{}", self.database, self.schema),
+            DatabaseType::Odbc => match self.odbc_mode {
+                OdbcMode::Dsn => format!("odbc://{}", self.database),
+                // The driver string may carry a password
+                OdbcMode::DriverString => "odbc://(driver string)".to_string(),
+            },
         }
     }
 }
 
+/// The parts of a `postgres://` or `mysql://` URL that make up a connection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionUrl {
+    pub db_type: DatabaseType,
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub password: String,
+    pub database: String,
+    /// The query string, such as `sslmode=require`, passed on to the driver as is
+    pub params: String,
+}
+
+impl ConnectionUrl {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let url = reqwest::Url::parse(text.trim()).map_err(|e| format!("Invalid URL: {}", e))?;
+        let (db_type, default_port) = match url.scheme() {
+            "postgres" | "postgresql" => (DatabaseType::PostgreSQL, 5432),
+            "mysql" | "mariadb" => (DatabaseType::MySQL, 3306),
+            other => return Err(format!("Unsupported URL scheme '{}://'", other)),
+        };
+        Ok(Self {
+            db_type,
+            host: url.host_str().unwrap_or("localhost").to_string(),
+            port: url.port().unwrap_or(default_port),
+            user: percent_decode(url.username()),
+            password: percent_decode(url.password().unwrap_or_default()),
+            database: percent_decode(url.path().trim_start_matches('/')),
+            params: url.query().unwrap_or_default().to_string(),
+        })
+    }
+
+    /// The URL with user, password and database percent-encoded; the password is left
+    /// out when empty.
+    pub fn to_url(&self) -> String {
+        let scheme = match self.db_type {
+            DatabaseType::MySQL => "mysql",
+            _ => "postgres",
+        };
+        let base = format!("{}://{}:{}/", scheme, self.host, self.port);
+        let Ok(mut url) = reqwest::Url::parse(&base) else {
+            return base;
+        };
+        let _ = url.set_username(&self.user);
+        if !self.password.is_empty() {
+            let _ = url.set_password(Some(&self.password));
+        }
+        url.path_segments_mut()
+            .map(|mut path| {
+                path.clear().push(&self.database);
+            })
+            .ok();
+        if !self.params.trim().is_empty() {
+            url.set_query(Some(self.params.trim().trim_start_matches('?')));
+        }
+        url.to_string()
+    }
+}
+
+/// Undo `%XX` escapes; invalid escapes and non-UTF-8 results are kept as written.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| text.to_string())
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ColumnInfo {
     pub name: String,
@@ -45,6 +668,40 @@ pub struct ColumnInfo {
     pub nullable: bool,
     pub default_value: Option<String>,
     pub is_primary_key: bool,
+    /// Identity kind: `ALWAYS` / `BY DEFAULT` on PostgreSQL, `auto_increment` on MySQL.
+    pub identity_generation: Option<String>,
+    /// Expression of a generated (stored or virtual) column.
+    pub generation_expression: Option<String>,
+    /// Labels of an enum-typed column, in declaration order.
+    pub enum_values: Vec<String>,
+    pub collation: Option<String>,
+    /// `COMMENT` set on the column
+    pub comment: Option<String>,
+    /// Full declared type with its length and modifiers, e.g. `varchar(255)` or
+    /// `int unsigned`. MySQL only; `data_type` holds just the base type there.
+    pub column_type: Option<String>,
+    /// MySQL column attributes such as `auto_increment` or `on update CURRENT_TIMESTAMP`
+    pub extra: Option<String>,
+}
+
+impl ColumnInfo {
+    pub fn is_identity(&self) -> bool {
+        self.identity_generation.is_some()
+    }
+
+    pub fn is_generated(&self) -> bool {
+        self.generation_expression.is_some()
+    }
+
+    /// Columns the database fills in itself; the insert form leaves these out.
+    pub fn is_auto_filled(&self) -> bool {
+        self.is_identity() || self.is_generated()
+    }
+
+    /// Character set implied by the collation name (e.g. `utf8mb4` for `utf8mb4_general_ci`).
+    pub fn charset(&self) -> Option<&str> {
+        self.collation.as_deref().and_then(|c| c.split('_').next())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -73,20 +730,245 @@ pub struct TableInfo {
     pub indexes: Vec<IndexInfo>,
     pub constraints: Vec<ConstraintInfo>,
     pub row_estimate: i64,
+    pub collation: Option<String>,
+    /// `COMMENT` set on the table
+    pub comment: Option<String>,
+}
+
+impl TableInfo {
+    /// Primary key columns in key order. Column order is only a fallback, since a
+    /// composite key may list its columns differently than the table does.
+    pub fn primary_key(&self) -> Vec<String> {
+        if let Some(index) = self.indexes.iter().find(|i| i.is_primary) {
+            return index.columns.clone();
+        }
+        if let Some(constraint) = self
+            .constraints
+            .iter()
+            .find(|c| c.constraint_type == "PRIMARY KEY")
+        {
+            return constraint.columns.clone();
+        }
+        self.columns
+            .iter()
+            .filter(|c| c.is_primary_key)
+            .map(|c| c.name.clone())
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct SchemaInfo {
     pub tables: Vec<TableInfo>,
     pub views: Vec<String>,
+    /// Sequence objects (MariaDB, DuckDB)
+    pub sequences: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerVariable {
+    pub name: String,
+    pub value: String,
+    /// Boot value on PostgreSQL, global value on MySQL.
+    pub default_value: Option<String>,
+    pub unit: Option<String>,
+    pub description: Option<String>,
+    /// Whether the variable can be changed for the current session.
+    pub session_settable: bool,
+}
+
+/// A variable shown in the session panel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionVariable {
+    pub name: String,
+    pub value: String,
+    /// Whether the value was changed from fbench and is replayed on every pooled connection.
+    pub overridden: bool,
+}
+
+/// A PostgreSQL extension from pg_available_extensions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtensionInfo {
+    pub name: String,
+    pub default_version: Option<String>,
+    pub installed_version: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl ExtensionInfo {
+    pub fn has_update(&self) -> bool {
+        self.installed_version.is_some() && self.installed_version != self.default_version
+    }
+}
+
+/// Result of the periodic health check.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConnectionHealth {
+    pub latency_ms: u64,
+    /// None when the server doesn't tell us (MySQL without replication privileges).
+    pub is_replica: Option<bool>,
+    pub read_only: bool,
+}
+
+/// A PostgreSQL NOTIFY received on a channel we LISTEN on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub channel: String,
+    pub payload: String,
+    pub process_id: u32,
+    pub received_at: chrono::DateTime<chrono::Local>,
+}
+
+/// A row change decoded from the WAL by PostgreSQL's `test_decoding` plugin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    pub lsn: String,
+    pub xid: String,
+    /// Schema-qualified, as the plugin prints it
+    pub table: String,
+    /// INSERT, UPDATE, DELETE or TRUNCATE
+    pub operation: String,
+    /// (column, value) pairs of the new row, or of the deleted row's key
+    pub values: Vec<(String, String)>,
+    /// Key before an UPDATE changed it; only sent when the key itself changed
+    pub old_key: Vec<(String, String)>,
+    pub received_at: chrono::DateTime<chrono::Local>,
+}
+
+impl ChangeEvent {
+    /// Whether the change belongs to one of `tables`, given bare or schema-qualified.
+    pub fn matches_table(&self, tables: &[String]) -> bool {
+        let table = self.table.replace('"', "");
+        let bare = table.rsplit('.').next().unwrap_or(&table);
+        tables.iter().any(|t| {
+            let t = t.replace('"', "");
+            t == table || t == bare
+        })
+    }
+}
+
+/// Parse one `test_decoding` line such as
+/// `table public.users: UPDATE: id[integer]:1 name[text]:'Ann'`.
+/// BEGIN/COMMIT lines and anything else that isn't a row change give None.
+pub fn parse_test_decoding(lsn: &str, xid: &str, data: &str) -> Option<ChangeEvent> {
+    let rest = data.strip_prefix("table ")?;
+    let (table, rest) = rest.split_once(": ")?;
+    let (operation, tuple) = match rest.split_once(": ") {
+        Some((operation, tuple)) => (operation, tuple),
+        None => (rest.trim_end_matches(':'), ""),
+    };
+    let (old_key, values) = match tuple.strip_prefix("old-key: ") {
+        Some(tuple) => match tuple.split_once(" new-tuple: ") {
+            Some((old, new)) => (parse_decoded_tuple(old), parse_decoded_tuple(new)),
+            None => (parse_decoded_tuple(tuple), Vec::new()),
+        },
+        None => (Vec::new(), parse_decoded_tuple(tuple)),
+    };
+    Some(ChangeEvent {
+        lsn: lsn.to_string(),
+        xid: xid.to_string(),
+        table: table.to_string(),
+        operation: operation.to_string(),
+        values,
+        old_key,
+        received_at: chrono::Local::now(),
+    })
+}
+
+/// `name[type]:value` pairs, where text values are single-quoted with `''` escapes.
+fn parse_decoded_tuple(tuple: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut rest = tuple.trim();
+    // (no-tuple-data), (no-flags) and similar placeholders carry no columns
+    if rest.starts_with('(') {
+        return pairs;
+    }
+    while !rest.is_empty() {
+        let Some(open) = rest.find('[') else {
+            break;
+        };
+        let name = rest[..open].trim().trim_matches('"').to_string();
+        let Some(close) = rest[open..].find("]:") else {
+            break;
+        };
+        rest = &rest[open + close + 2..];
+        let value = if let Some(quoted) = rest.strip_prefix('\'') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices().peekable();
+            let mut end = quoted.len();
+            while let Some((i, c)) = chars.next() {
+                if c == '\'' {
+                    if chars.peek().is_some_and(|(_, next)| *next == '\'') {
+                        chars.next();
+                    } else {
+                        end = i + 1;
+                        break;
+                    }
+                }
+                value.push(c);
+            }
+            rest = &quoted[end..];
+            value
+        } else {
+            let end = rest.find(' ').unwrap_or(rest.len());
+            let value = match &rest[..end] {
+                "null" => "NULL".to_string(),
+                other => other.to_string(),
+            };
+            rest = &rest[end..];
+            value
+        };
+        pairs.push((name, value));
+        rest = rest.trim_start();
+    }
+    pairs
+}
+
+/// Tags a request so its response can be routed back to whoever sent it
+pub type RequestId = u64;
+
+/// A fresh id, unique across the database and LLM workers.
+pub fn next_request_id() -> RequestId {
+    static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
 }
 
 #[derive(Debug)]
 pub enum DbRequest {
+    /// `request` with an id chosen by the sender; its response comes back as `Finished`
+    Tagged {
+        id: RequestId,
+        request: Box<DbRequest>,
+    },
     Connect(ConnectionConfig),
     TestConnection(ConnectionConfig),
+    /// Statement timeout for connections whose pool settings leave it unset,
+    /// applied from the next connect
+    SetDefaultStatementTimeout(Option<u64>),
+    /// Rows that editor SELECTs without a LIMIT are cut at; None runs them unlimited
+    SetAutoLimit(Option<usize>),
     Execute(String),
+    /// Execute without checking the plan budget, after the user overrode the warning.
+    ExecuteOverBudget(String),
+    /// Execute with values bound to the statement's `$n` or `?` placeholders.
+    ExecuteWithParams {
+        sql: String,
+        params: Vec<ParamValue>,
+    },
+    /// Run an editor SELECT again with a larger auto-limit, after it was cut short.
+    FetchMore {
+        sql: String,
+        limit: usize,
+    },
+    /// Stop the running query reported by `QueryStarted` with this id.
+    Cancel(RequestId),
+    /// Stop receiving the rows of a running query and show those fetched so far.
+    /// Unlike `Cancel` the query is not aborted on the server.
+    StopFetching(RequestId),
     Explain(String),
+    /// Planning and execution time of a read query through EXPLAIN ANALYZE
+    /// (PostgreSQL and MySQL).
+    ProfileTiming(String),
     #[allow(dead_code)]
     ListTables,
     FetchSchema,
@@ -96,28 +978,181 @@ pub enum DbRequest {
     Disconnect,
     // Phase 2: Data mutations
     ExecuteMutation(String),
-    ExecuteBatch(Vec<String>),
+    /// Run statements in one transaction opened with the given options.
+    ExecuteBatch(Vec<String>, TransactionOptions),
+    /// `ExecuteBatch` for a grid save: each UPDATE also matches the values the row had
+    /// when loaded, and if one matches no row nothing is committed.
+    SaveRowEdits(Vec<String>, TransactionOptions),
+    /// Open a transaction on a connection of its own that editor queries, mutations and
+    /// batches then run in until `Commit` or `Rollback` (PostgreSQL, MySQL and SQLite).
+    Begin,
+    Commit,
+    Rollback,
     ImportData {
         table: String,
         columns: Vec<String>,
         rows: Vec<Vec<String>>,
         batch_size: usize,
+        conflict: ConflictStrategy,
+    },
+    /// Import into several tables in the given order. Deferring constraints runs the whole
+    /// import in one transaction with `SET CONSTRAINTS ALL DEFERRED` (PostgreSQL only).
+    ImportTables {
+        tables: Vec<TableImport>,
+        batch_size: usize,
+        defer_constraints: bool,
+    },
+    /// Insert every row under its own savepoint and roll the whole transaction back,
+    /// reporting which rows would fail.
+    ValidateImport(Vec<TableImport>),
+    /// Run each test's fixtures and query in a transaction that is rolled back afterwards.
+    RunQueryTests(Vec<QueryTestCase>),
+    /// Import a file from a watched folder in one transaction, so it lands completely or not at all.
+    ImportWatchedFile {
+        path: String,
+        import: TableImport,
+    },
+    FetchServerVariables,
+    /// Fetch the session variables shown in the session panel.
+    FetchSessionVariables,
+    SetSessionVariable {
+        name: String,
+        value: String,
+    },
+    FetchExtensions,
+    InstallExtension(String),
+    UpdateExtension(String),
+    /// Look up the change history of one row in its audit table.
+    /// `keys` are the row's primary key (column, value) pairs.
+    FetchRowHistory {
+        table: String,
+        keys: Vec<(String, String)>,
+        patterns: Vec<String>,
+    },
+    /// Fetch the row a foreign key value points at, for the hover card cached under `key`.
+    FetchFkPreview {
+        key: String,
+        sql: String,
+    },
+    /// Run `SELECT COUNT(*)` statements, one per referencing table.
+    FetchReferencingCounts(Vec<String>),
+    /// Run a single `SELECT COUNT(*)`, answered together with the statement.
+    FetchRowCount(String),
+    /// A few rows of `table` for the LLM to describe it from.
+    FetchTableSample {
+        table: String,
+        sql: String,
+    },
+    /// Rows for a seed script: (table, query) pairs, answered together.
+    FetchSeedRows(Vec<(String, String)>),
+    /// Count a column's values grouped into bars, for the distribution chart.
+    FetchDistribution {
+        table: String,
+        column: String,
+        kind: DistributionKind,
     },
+    /// Copy tables to another connection, anonymizing their columns on the way.
+    /// Without a target it's a dry run that only reads and transforms the rows.
+    CopyTables {
+        tables: Vec<CopyTable>,
+        options: CopyOptions,
+        target: Option<ConnectionConfig>,
+    },
+    /// Start receiving notifications on a channel (PostgreSQL only).
+    Listen(String),
+    Unlisten(String),
+    /// Stream row changes of these tables through a temporary logical replication slot
+    /// (PostgreSQL only). Replaces any running capture.
+    StartCapture(Vec<String>),
+    StopCapture,
+}
+
+impl DbRequest {
+    /// Read-only metadata requests that can wait in the offline queue until the server returns.
+    pub fn is_informational(&self) -> bool {
+        if let DbRequest::Tagged { request, .. } = self {
+            return request.is_informational();
+        }
+        matches!(
+            self,
+            DbRequest::ListTables
+                | DbRequest::FetchSchema
+                | DbRequest::FetchTableDetails(_)
+                | DbRequest::FetchServerVariables
+                | DbRequest::FetchSessionVariables
+                | DbRequest::FetchExtensions
+        )
+    }
+
+    /// SQL of requests that run the user's statements, reported while they run.
+    pub fn query_sql(&self) -> Option<String> {
+        match self {
+            DbRequest::Execute(sql)
+            | DbRequest::ExecuteOverBudget(sql)
+            | DbRequest::ExecuteWithParams { sql, .. }
+            | DbRequest::FetchMore { sql, .. }
+            | DbRequest::ExecuteMutation(sql) => Some(sql.clone()),
+            DbRequest::ExecuteBatch(statements, _) | DbRequest::SaveRowEdits(statements, _) => {
+                Some(statements.join(";\n"))
+            }
+            DbRequest::Tagged { request, .. } => request.query_sql(),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum DbResponse {
-    Connected(DatabaseType, String),
+    /// Type, database name, dialect and server version (when known)
+    Connected(DatabaseType, String, SqlDialect, Option<String>),
     ConnectionFailed(String),
     TestResult(Result<(), String>),
+    /// `request`, a connect or test, reached an SSH server that isn't known yet
+    UnknownHostKey {
+        key: UnknownHostKey,
+        request: Box<DbRequest>,
+    },
+    /// A request with `query_sql` started running; its response arrives as `Finished` with the same id
+    QueryStarted(RequestId, String),
+    /// Rows received since the last chunk by the running query with this id
+    /// (PostgreSQL and MySQL); its full result still arrives as `Finished`
+    QueryResultChunk {
+        id: RequestId,
+        columns: Vec<String>,
+        column_types: Vec<String>,
+        rows: Vec<Vec<Option<String>>>,
+    },
+    /// `done` of the `total` statements of the running script with this id have run
+    StatementFinished {
+        id: RequestId,
+        done: usize,
+        total: usize,
+    },
+    /// Reconnect attempt `attempt` failed; the next one follows in `retry_in_secs`
+    Reconnecting {
+        attempt: u32,
+        retry_in_secs: u64,
+    },
+    /// The response to a tagged request, or to the query reported by `QueryStarted` with this id
+    Finished {
+        id: RequestId,
+        response: Box<DbResponse>,
+    },
     QueryResult(QueryResult),
+    /// An editor query of several statements, run one at a time
+    ScriptResult(ScriptResult),
     ExplainResult(String),
+    TimingProfile {
+        sql: String,
+        timing: Result<ServerTiming, String>,
+    },
     Schema(SchemaInfo),
     #[allow(dead_code)]
     TableDetails(TableInfo),
     Error(String),
     Disconnected,
     ConnectionLost,
+    ConnectionRestored,
     // Phase 2: Mutation responses
     MutationResult {
         affected_rows: u64,
@@ -125,7 +1160,33 @@ pub enum DbResponse {
     BatchResult {
         affected_rows: u64,
         statement_count: usize,
+        /// Reruns needed after deadlocks or serialization failures
+        retries: u32,
+    },
+    /// A batch that still hit a deadlock or serialization failure after `retries` reruns
+    BatchConflict {
+        statements: Vec<String>,
+        options: TransactionOptions,
+        /// The batch was a grid save
+        row_edits: bool,
+        error: String,
+        retries: u32,
     },
+    /// `done` of the `total` `unit`s of the running batch or import are finished, and
+    /// `current` runs next
+    BatchProgress {
+        done: usize,
+        total: usize,
+        unit: &'static str,
+        current: String,
+    },
+    /// Statement `statement` of a grid save matched no row, since the row changed or was
+    /// deleted after it was loaded; nothing was saved
+    RowEditConflict {
+        statement: usize,
+    },
+    /// Statements run in the open manual transaction, or None once there is none
+    TransactionStatus(Option<usize>),
     ImportProgress {
         inserted: usize,
         total: usize,
@@ -133,17 +1194,281 @@ pub enum DbResponse {
     ImportComplete {
         total: usize,
     },
+    ImportValidation(Result<ImportValidation, String>),
+    QueryTestResults(Result<Vec<QueryTestOutcome>, String>),
+    WatchedFileImported {
+        path: String,
+        rows: Result<usize, String>,
+    },
+    ServerVariables(Vec<ServerVariable>),
+    SessionVariables(Vec<SessionVariable>),
+    SessionVariableSet {
+        name: String,
+        value: String,
+    },
+    Extensions(Vec<ExtensionInfo>),
+    Health(ConnectionHealth),
+    /// Idle connections answered a keepalive ping; `latency_ms` is the slowest round trip
+    Keepalive {
+        latency_ms: u64,
+        connections: usize,
+    },
+    /// Audit table name and the matching history rows
+    RowHistory(Result<(String, QueryResult), String>),
+    /// (column, value) pairs of the referenced row, or None when no row matches
+    FkPreview {
+        key: String,
+        row: Result<Option<Vec<(String, String)>>, String>,
+    },
+    ReferencingCounts(Vec<Result<i64, String>>),
+    RowCount {
+        sql: String,
+        count: Result<i64, String>,
+    },
+    TableSample {
+        table: String,
+        sample: Result<QueryResult, String>,
+    },
+    /// (table, rows) of each table asked for, or the first error
+    SeedRows(Result<Vec<(String, QueryResult)>, String>),
+    TablesCopied(Result<CopySummary, String>),
+    Distribution(Result<Distribution, String>),
+    /// Channels currently listened on
+    Listening(Vec<String>),
+    Notification(Notification),
+    /// Tables whose changes are being captured; empty once capture stops
+    Capturing(Vec<String>),
+    ChangeEvents(Vec<ChangeEvent>),
+    /// The server stopped the query at the statement timeout
+    QueryTimedOut {
+        timeout_secs: Option<u64>,
+    },
+    /// The query was not run because its estimated plan exceeds the connection's budget
+    OverBudget {
+        sql: String,
+        message: String,
+    },
 }
 
-#[derive(Debug, Clone)]
+impl DbResponse {
+    /// Responses that end every running query, since their connection is gone.
+    pub fn ends_all_queries(&self) -> bool {
+        matches!(self, DbResponse::ConnectionLost | DbResponse::Disconnected)
+    }
+
+    /// Whether this answers a running batch or import, so its progress is done.
+    pub fn ends_batch(&self) -> bool {
+        matches!(
+            self,
+            DbResponse::BatchResult { .. }
+                | DbResponse::BatchConflict { .. }
+                | DbResponse::RowEditConflict { .. }
+                | DbResponse::ImportComplete { .. }
+                | DbResponse::Error(_)
+        ) || self.ends_all_queries()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct QueryResult {
     pub sql: String,
     pub columns: Vec<String>,
     pub column_types: Vec<String>,
     pub rows: Vec<Vec<String>>,
+    /// (row, column) of the cells that are SQL NULL; their text in `rows` is `NULL`
+    pub null_cells: HashSet<(usize, usize)>,
     pub execution_time_ms: u64,
     pub source_table: Option<String>,
     pub primary_keys: Vec<String>,
+    /// Bytes the query scanned, on backends that bill by it (BigQuery)
+    pub bytes_processed: Option<u64>,
+    pub timing: QueryTiming,
+    /// Fetching was stopped early, so `rows` holds only the rows received until then
+    pub fetch_stopped: bool,
+    /// The auto-limit cut the result at this many rows, and the query has more
+    pub limited_to: Option<usize>,
+    /// PostgreSQL row version (`xmin`) of each row as it was read, empty when the
+    /// query couldn't select it; saving edits matches it to detect changed rows
+    pub row_versions: Vec<String>,
+}
+
+/// One statement of a script and what it returned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementResult {
+    pub sql: String,
+    pub outcome: Result<QueryResult, String>,
+    pub execution_time_ms: u64,
+}
+
+/// Results of a script's statements in order. The run stops at the first failure, so
+/// there may be fewer than `total`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptResult {
+    pub statements: Vec<StatementResult>,
+    pub total: usize,
+}
+
+impl ScriptResult {
+    /// Statement to show first: the one that failed, else the last that returned
+    /// columns, else the last one.
+    pub fn default_statement(&self) -> usize {
+        let last = self.statements.len().saturating_sub(1);
+        if self.statements.last().is_some_and(|s| s.outcome.is_err()) {
+            return last;
+        }
+        self.statements
+            .iter()
+            .rposition(|s| s.outcome.as_ref().is_ok_and(|r| !r.columns.is_empty()))
+            .unwrap_or(last)
+    }
+}
+
+/// Where the time of a query went on the client side.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct QueryTiming {
+    /// Sending the query and receiving every row
+    pub fetch_ms: f64,
+    /// Converting the rows to text; None when the backend does it while fetching
+    pub format_ms: Option<f64>,
+}
+
+/// Server-side phases of a query, from a separate EXPLAIN ANALYZE run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ServerTiming {
+    /// Only PostgreSQL reports planning separately
+    pub planning_ms: Option<f64>,
+    pub execution_ms: f64,
+}
+
+impl QueryResult {
+    /// The rows a running query has received so far, before its full result arrives.
+    pub fn fetching(
+        sql: &str,
+        columns: Vec<String>,
+        column_types: Vec<String>,
+        rows: Vec<Vec<Option<String>>>,
+    ) -> Self {
+        let (rows, null_cells) = split_nulls(rows);
+        Self {
+            sql: sql.to_string(),
+            columns,
+            column_types,
+            rows,
+            null_cells,
+            execution_time_ms: 0,
+            source_table: None,
+            primary_keys: Vec::new(),
+            bytes_processed: None,
+            timing: QueryTiming::default(),
+            fetch_stopped: false,
+            limited_to: None,
+            row_versions: Vec::new(),
+        }
+    }
+
+    /// Add the rows of a later chunk of the same query.
+    pub fn append(&mut self, chunk: QueryResult) {
+        let offset = self.rows.len();
+        self.rows.extend(chunk.rows);
+        self.null_cells.extend(
+            chunk
+                .null_cells
+                .into_iter()
+                .map(|(row, col)| (row + offset, col)),
+        );
+    }
+
+    /// Whether a cell is SQL NULL, as opposed to the text `NULL`.
+    pub fn is_null(&self, row: usize, col: usize) -> bool {
+        self.null_cells.contains(&(row, col))
+    }
+
+    /// Whether every primary key column is in the result, so each row can be told apart.
+    /// Matching on part of a composite key would update or delete its sibling rows too.
+    pub fn has_full_key(&self) -> bool {
+        !self.primary_keys.is_empty()
+            && self.primary_keys.iter().all(|pk| self.columns.contains(pk))
+    }
+
+    /// `(column, value)` of each primary key column of `row`, in key order. `None` when
+    /// the result lacks a key column.
+    pub fn row_key(&self, row: usize) -> Option<Vec<(&str, &str)>> {
+        if !self.has_full_key() {
+            return None;
+        }
+        let values = self.rows.get(row)?;
+        self.primary_keys
+            .iter()
+            .map(|pk| {
+                let col_idx = self.columns.iter().position(|c| c == pk)?;
+                Some((pk.as_str(), values.get(col_idx)?.as_str()))
+            })
+            .collect()
+    }
+}
+
+/// Rows as text, with `None` cells written as `NULL` and their positions collected
+/// for `QueryResult::null_cells`.
+pub(crate) fn split_nulls(
+    rows: Vec<Vec<Option<String>>>,
+) -> (Vec<Vec<String>>, HashSet<(usize, usize)>) {
+    let mut null_cells = HashSet::new();
+    let rows = rows
+        .into_iter()
+        .enumerate()
+        .map(|(row_idx, row)| {
+            row.into_iter()
+                .enumerate()
+                .map(|(col_idx, value)| {
+                    value.unwrap_or_else(|| {
+                        null_cells.insert((row_idx, col_idx));
+                        "NULL".to_string()
+                    })
+                })
+                .collect()
+        })
+        .collect();
+    (rows, null_cells)
+}
+
+/// `1.5 GB` style size, in powers of 1024.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// `1,234,567` style count.
+pub fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// `value` as a string literal. MySQL and ClickHouse also read backslashes as escapes.
+pub fn quote_literal(db_type: DatabaseType, value: &str) -> String {
+    let escaped = value.replace('\'', "''");
+    match db_type {
+        DatabaseType::MySQL | DatabaseType::ClickHouse => {
+            format!("'{}'", escaped.replace('\\', "\\\\"))
+        }
+        _ => format!("'{}'", escaped),
+    }
 }
 
 pub fn quote_identifier(db_type: DatabaseType, identifier: &str) -> String {
@@ -157,8 +1482,15 @@ pub fn quote_identifier(db_type: DatabaseType, identifier: &str) -> String {
                 part.to_string()
             } else {
                 match db_type {
-                    DatabaseType::PostgreSQL => format!("\"{}\"", part.replace('"', "\"\"")),
-                    DatabaseType::MySQL => format!("`{}`", part.replace('`', "``")),
+                    DatabaseType::PostgreSQL
+                    | DatabaseType::SQLite
+                    | DatabaseType::DuckDB
+                    | DatabaseType::Odbc => {
+                        format!("\"{}\"", part.replace('"', "\"\""))
+                    }
+                    DatabaseType::MySQL | DatabaseType::ClickHouse | DatabaseType::BigQuery => {
+                        format!("`{}`", part.replace('`', "``"))
+                    }
                 }
             }
         })
@@ -166,14 +1498,583 @@ pub fn quote_identifier(db_type: DatabaseType, identifier: &str) -> String {
         .join(".")
 }
 
-pub fn format_select_all_sql(db_type: DatabaseType, table: &str, limit: usize) -> String {
-    format!(
-        "SELECT * FROM {} LIMIT {};",
+/// Build the statement that changes a variable for the current session.
+pub fn format_set_session_sql(
+    db_type: DatabaseType,
+    name: &str,
+    value: &str,
+) -> Result<String, String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    {
+        return Err(format!("Invalid variable name: {}", name));
+    }
+
+    let value = value.trim();
+    let literal = if value.eq_ignore_ascii_case("DEFAULT") {
+        if db_type == DatabaseType::SQLite {
+            return Err("SQLite pragmas have no DEFAULT; set a value instead".to_string());
+        }
+        if db_type == DatabaseType::DuckDB {
+            return Ok(format!("RESET {}", name));
+        }
+        "DEFAULT".to_string()
+    } else if db_type == DatabaseType::PostgreSQL && name.eq_ignore_ascii_case("search_path") {
+        // A single quoted string would name one schema called "a, b"
+        value
+            .split(',')
+            .map(|schema| format!("'{}'", schema.trim().trim_matches('"').replace('\'', "''")))
+            .collect::<Vec<_>>()
+            .join(", ")
+    } else if db_type != DatabaseType::PostgreSQL
+        && (value.parse::<f64>().is_ok()
+            || value.eq_ignore_ascii_case("ON")
+            || value.eq_ignore_ascii_case("OFF"))
+    {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    };
+
+    Ok(match db_type {
+        DatabaseType::PostgreSQL => format!("SET {} TO {}", name, literal),
+        DatabaseType::MySQL => format!("SET SESSION {} = {}", name, literal),
+        DatabaseType::SQLite => format!("PRAGMA {} = {}", name, literal),
+        DatabaseType::DuckDB | DatabaseType::ClickHouse => format!("SET {} = {}", name, literal),
+        DatabaseType::BigQuery => return Err("BigQuery connections are read-only".to_string()),
+        DatabaseType::Odbc => {
+            return Err(
+                "Session variables can't be set through a generic ODBC connection".to_string(),
+            )
+        }
+    })
+}
+
+/// `soft_delete_column` hides soft-deleted rows with an `IS NULL` filter on that column.
+pub fn format_select_all_sql(
+    db_type: DatabaseType,
+    table: &str,
+    limit: usize,
+    soft_delete_column: Option<&str>,
+) -> String {
+    match soft_delete_column {
+        Some(column) => format!(
+            "SELECT * FROM {} WHERE {} IS NULL LIMIT {};",
+            quote_identifier(db_type, table),
+            quote_identifier(db_type, column),
+            limit
+        ),
+        None => format!(
+            "SELECT * FROM {} LIMIT {};",
+            quote_identifier(db_type, table),
+            limit
+        ),
+    }
+}
+
+/// Build a single UPDATE setting `column` on every row identified by `key_values`,
+/// one entry per row holding its primary key values in `primary_keys` order.
+/// `value` is quoted as a literal unless `is_expression` is set; None sets SQL NULL.
+pub fn format_bulk_update_sql(
+    db_type: DatabaseType,
+    table: &str,
+    column: &str,
+    value: Option<&str>,
+    is_expression: bool,
+    primary_keys: &[String],
+    key_values: &[Vec<String>],
+) -> Option<String> {
+    if primary_keys.is_empty() || key_values.is_empty() {
+        return None;
+    }
+
+    let new_value = match value {
+        None => "NULL".to_string(),
+        Some(value) if is_expression => value.trim().to_string(),
+        Some(value) => quote_literal(db_type, value),
+    };
+    if new_value.is_empty() {
+        return None;
+    }
+
+    let keys: Vec<String> = primary_keys
+        .iter()
+        .map(|pk| quote_identifier(db_type, pk))
+        .collect();
+    let condition = if keys.len() == 1 {
+        let values: Vec<String> = key_values
+            .iter()
+            .map(|k| quote_literal(db_type, &k[0]))
+            .collect();
+        format!("{} IN ({})", keys[0], values.join(", "))
+    } else {
+        let tuples: Vec<String> = key_values
+            .iter()
+            .map(|k| {
+                let values: Vec<String> = k.iter().map(|v| quote_literal(db_type, v)).collect();
+                format!("({})", values.join(", "))
+            })
+            .collect();
+        format!("({}) IN ({})", keys.join(", "), tuples.join(", "))
+    };
+
+    Some(format!(
+        "UPDATE {} SET {} = {} WHERE {}",
+        quote_identifier(db_type, table),
+        quote_identifier(db_type, column),
+        new_value,
+        condition
+    ))
+}
+
+/// Build an UPDATE replacing every occurrence of `find` with `replace` in `column`,
+/// restricted to rows that contain it.
+pub fn format_replace_sql(
+    db_type: DatabaseType,
+    table: &str,
+    column: &str,
+    find: &str,
+    replace: &str,
+) -> Option<String> {
+    if find.is_empty() {
+        return None;
+    }
+    let column = quote_identifier(db_type, column);
+    Some(format!(
+        "UPDATE {} SET {column} = REPLACE({column}, {}, {}) WHERE {}",
         quote_identifier(db_type, table),
-        limit
+        quote_literal(db_type, find),
+        quote_literal(db_type, replace),
+        contains_condition(db_type, &column, find)
+    ))
+}
+
+/// Count the rows `format_replace_sql` would change.
+pub fn format_replace_count_sql(
+    db_type: DatabaseType,
+    table: &str,
+    column: &str,
+    find: &str,
+) -> Option<String> {
+    if find.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "SELECT COUNT(*) FROM {} WHERE {}",
+        quote_identifier(db_type, table),
+        contains_condition(db_type, &quote_identifier(db_type, column), find)
+    ))
+}
+
+/// `column LIKE '%find%'`, with LIKE wildcards in `find` escaped by an explicit escape
+/// character every dialect accepts.
+fn contains_condition(db_type: DatabaseType, column: &str, find: &str) -> String {
+    let pattern = find
+        .replace('!', "!!")
+        .replace('%', "!%")
+        .replace('_', "!_");
+    format!(
+        "{} LIKE {} ESCAPE '!'",
+        column,
+        quote_literal(db_type, &format!("%{}%", pattern))
     )
 }
 
+/// A change to one column, offered from the table details in the schema panel.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnChange {
+    Rename(String),
+    SetType(String),
+    /// A default expression, or `None` to drop it
+    SetDefault(Option<String>),
+    SetNullable(bool),
+    /// Move after the named column, or first when `None`. MySQL only.
+    MoveAfter(Option<String>),
+    /// A comment, or `None` to clear it
+    SetComment(Option<String>),
+    Drop,
+}
+
+/// ALTER TABLE statement applying `change` to `column` of `table`, or `None` when the
+/// database can't make that change in place (SQLite only renames and drops, and
+/// BigQuery connections are read-only).
+pub fn format_alter_column_sql(
+    db_type: DatabaseType,
+    table: &str,
+    column: &ColumnInfo,
+    change: &ColumnChange,
+) -> Option<String> {
+    let table_name = quote_identifier(db_type, table);
+    let name = quote_identifier(db_type, &column.name);
+    // MySQL redefines the whole column to change its type, nullability, position or
+    // comment, so everything else about it is carried over
+    let mysql_modify =
+        |new_type: Option<&str>, nullable: bool, comment: Option<&str>, position: &str| {
+            // A new type takes the table's character set, as the old one may not apply to it
+            let definition = match new_type {
+                Some(new_type) => new_type.to_string(),
+                None => {
+                    let column_type = column.column_type.as_deref().unwrap_or(&column.data_type);
+                    match (column.charset(), column.collation.as_deref()) {
+                        (Some(charset), Some(collation)) => format!(
+                            "{} CHARACTER SET {} COLLATE {}",
+                            column_type, charset, collation
+                        ),
+                        _ => column_type.to_string(),
+                    }
+                }
+            };
+            let generated = column
+                .generation_expression
+                .as_deref()
+                .map(|expression| {
+                    let stored = column
+                        .extra
+                        .as_deref()
+                        .is_some_and(|e| e.to_uppercase().contains("STORED"));
+                    format!(
+                        " AS ({}) {}",
+                        expression,
+                        if stored { "STORED" } else { "VIRTUAL" }
+                    )
+                })
+                .unwrap_or_default();
+            // MySQL lists string defaults unquoted, MariaDB quoted
+            let default = match column.default_value.as_deref() {
+                _ if column.is_generated() => String::new(),
+                Some(d)
+                    if d.starts_with('\'')
+                        || d.parse::<f64>().is_ok()
+                        || d.eq_ignore_ascii_case("NULL")
+                        || d.to_uppercase().starts_with("CURRENT_TIMESTAMP")
+                        || d.contains('(') =>
+                {
+                    format!(" DEFAULT {}", d)
+                }
+                Some(d) => format!(" DEFAULT '{}'", d.replace('\'', "''")),
+                None => String::new(),
+            };
+            let extra = column.extra.as_deref().unwrap_or_default();
+            let on_update = extra
+                .to_lowercase()
+                .find("on update ")
+                .map(|at| format!(" ON UPDATE {}", extra[at + "on update ".len()..].trim()))
+                .unwrap_or_default();
+            let auto_increment = if extra.to_lowercase().contains("auto_increment") {
+                " AUTO_INCREMENT"
+            } else {
+                ""
+            };
+            let comment = comment
+                .map(|c| format!(" COMMENT '{}'", c.replace('\'', "''")))
+                .unwrap_or_default();
+            format!(
+                "ALTER TABLE {} MODIFY COLUMN {} {}{} {}{}{}{}{}{};",
+                table_name,
+                name,
+                definition,
+                generated,
+                if nullable { "NULL" } else { "NOT NULL" },
+                default,
+                on_update,
+                auto_increment,
+                comment,
+                position
+            )
+        };
+
+    let sql = match (db_type, change) {
+        (DatabaseType::BigQuery | DatabaseType::Odbc, _) => return None,
+        (_, ColumnChange::Rename(new_name)) => format!(
+            "ALTER TABLE {} RENAME COLUMN {} TO {};",
+            table_name,
+            name,
+            quote_identifier(db_type, new_name.trim())
+        ),
+        (_, ColumnChange::Drop) => format!("ALTER TABLE {} DROP COLUMN {};", table_name, name),
+        (DatabaseType::SQLite, _) => return None,
+        (DatabaseType::MySQL, ColumnChange::SetType(data_type)) => mysql_modify(
+            Some(data_type.trim()),
+            column.nullable,
+            column.comment.as_deref(),
+            "",
+        ),
+        (DatabaseType::MySQL, ColumnChange::SetNullable(nullable)) => {
+            mysql_modify(None, *nullable, column.comment.as_deref(), "")
+        }
+        (DatabaseType::MySQL, ColumnChange::MoveAfter(after)) => {
+            let position = match after {
+                Some(after) => format!(" AFTER {}", quote_identifier(db_type, after)),
+                None => " FIRST".to_string(),
+            };
+            mysql_modify(None, column.nullable, column.comment.as_deref(), &position)
+        }
+        (DatabaseType::MySQL, ColumnChange::SetComment(comment)) => {
+            mysql_modify(None, column.nullable, comment.as_deref(), "")
+        }
+        (DatabaseType::ClickHouse, ColumnChange::SetComment(comment)) => format!(
+            "ALTER TABLE {} COMMENT COLUMN {} '{}';",
+            table_name,
+            name,
+            comment.as_deref().unwrap_or_default().replace('\'', "''")
+        ),
+        (_, ColumnChange::SetComment(comment)) => format!(
+            "COMMENT ON COLUMN {}.{} IS {};",
+            table_name,
+            name,
+            comment
+                .as_ref()
+                .map(|c| format!("'{}'", c.replace('\'', "''")))
+                .unwrap_or_else(|| "NULL".to_string())
+        ),
+        (_, ColumnChange::MoveAfter(_)) => return None,
+        (DatabaseType::ClickHouse, ColumnChange::SetType(data_type)) => format!(
+            "ALTER TABLE {} MODIFY COLUMN {} {};",
+            table_name,
+            name,
+            data_type.trim()
+        ),
+        (DatabaseType::ClickHouse, ColumnChange::SetDefault(default)) => match default {
+            Some(default) => format!(
+                "ALTER TABLE {} MODIFY COLUMN {} DEFAULT {};",
+                table_name, name, default
+            ),
+            None => format!(
+                "ALTER TABLE {} MODIFY COLUMN {} REMOVE DEFAULT;",
+                table_name, name
+            ),
+        },
+        // Nullability is part of the column type there
+        (DatabaseType::ClickHouse, ColumnChange::SetNullable(_)) => return None,
+        (_, ColumnChange::SetType(data_type)) => format!(
+            "ALTER TABLE {} ALTER COLUMN {} TYPE {};",
+            table_name,
+            name,
+            data_type.trim()
+        ),
+        (_, ColumnChange::SetDefault(Some(default))) => format!(
+            "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
+            table_name, name, default
+        ),
+        (_, ColumnChange::SetDefault(None)) => format!(
+            "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
+            table_name, name
+        ),
+        (_, ColumnChange::SetNullable(nullable)) => format!(
+            "ALTER TABLE {} ALTER COLUMN {} {} NOT NULL;",
+            table_name,
+            name,
+            if *nullable { "DROP" } else { "SET" }
+        ),
+    };
+    Some(sql)
+}
+
+/// Statement setting the comment of `table`, or clearing it when `comment` is `None`.
+/// `None` on databases without table comments.
+pub fn format_table_comment_sql(
+    db_type: DatabaseType,
+    table: &str,
+    comment: Option<&str>,
+) -> Option<String> {
+    let table_name = quote_identifier(db_type, table);
+    let literal = format!("'{}'", comment.unwrap_or_default().replace('\'', "''"));
+    match db_type {
+        DatabaseType::PostgreSQL | DatabaseType::DuckDB => Some(format!(
+            "COMMENT ON TABLE {} IS {};",
+            table_name,
+            if comment.is_some() { &literal } else { "NULL" }
+        )),
+        DatabaseType::MySQL => Some(format!("ALTER TABLE {} COMMENT = {};", table_name, literal)),
+        DatabaseType::ClickHouse => Some(format!(
+            "ALTER TABLE {} MODIFY COMMENT {};",
+            table_name, literal
+        )),
+        DatabaseType::SQLite | DatabaseType::BigQuery | DatabaseType::Odbc => None,
+    }
+}
+
+/// Default LISTEN channel for change notifications on a table: `orders` -> `orders_changes`.
+pub fn notify_channel_name(table: &str) -> String {
+    let name: String = normalize_table_name(table)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}_changes", name)
+}
+
+/// Build a reviewable PostgreSQL script adding a trigger that NOTIFYs `channel` with the
+/// operation and row as JSON on every insert, update and delete.
+pub fn format_notify_trigger_sql(table: &str, channel: &str) -> String {
+    let quoted_table = quote_identifier(DatabaseType::PostgreSQL, table);
+    let function = quote_identifier(DatabaseType::PostgreSQL, &format!("{}_notify", channel));
+    let channel_literal = channel.replace('\'', "''");
+    format!(
+        r#"-- Send a notification on '{channel}' for every change to {table}.
+-- Payloads over the 8000 byte NOTIFY limit leave out the row.
+CREATE OR REPLACE FUNCTION {function}() RETURNS trigger AS $$
+DECLARE
+    payload text;
+BEGIN
+    payload := json_build_object('table', TG_TABLE_NAME, 'op', TG_OP, 'row', row_to_json(COALESCE(NEW, OLD)))::text;
+    IF octet_length(payload) > 7900 THEN
+        payload := json_build_object('table', TG_TABLE_NAME, 'op', TG_OP)::text;
+    END IF;
+    PERFORM pg_notify('{channel_literal}', payload);
+    RETURN COALESCE(NEW, OLD);
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS fbench_notify ON {quoted_table};
+CREATE TRIGGER fbench_notify
+    AFTER INSERT OR UPDATE OR DELETE ON {quoted_table}
+    FOR EACH ROW EXECUTE FUNCTION {function}();
+"#
+    )
+}
+
+/// Build a reviewable script converting a MySQL table to utf8mb4.
+pub fn format_utf8mb4_conversion_sql(table: &TableInfo, collation: &str) -> String {
+    let mut sql = format!(
+        "-- Convert {} to utf8mb4 ({})\n-- Review before running: this rewrites the table.\n",
+        table.name, collation
+    );
+
+    if let Some(current) = &table.collation {
+        sql.push_str(&format!("-- Current table collation: {}\n", current));
+    }
+
+    let affected: Vec<&ColumnInfo> = table
+        .columns
+        .iter()
+        .filter(|c| c.charset().is_some_and(|cs| cs != "utf8mb4"))
+        .collect();
+    if !affected.is_empty() {
+        sql.push_str("-- Columns changing character set:\n");
+        for col in &affected {
+            sql.push_str(&format!(
+                "--   {} {} ({})\n",
+                col.name,
+                col.data_type,
+                col.collation.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+
+    let indexed_text = table.indexes.iter().any(|idx| {
+        idx.columns
+            .iter()
+            .any(|name| affected.iter().any(|c| &c.name == name))
+    });
+    if indexed_text {
+        sql.push_str(
+            "-- Note: indexed text columns grow to 4 bytes per character; \
+             check index key length limits.\n",
+        );
+    }
+
+    sql.push_str(&format!(
+        "\nALTER TABLE {} CONVERT TO CHARACTER SET utf8mb4 COLLATE {};\n",
+        quote_identifier(DatabaseType::MySQL, &table.name),
+        collation
+    ));
+    sql
+}
+
+/// Foreign keys of `table` whose columns use a different collation than the referenced ones.
+/// Joins across such columns cannot use indexes and may fail with "Illegal mix of collations".
+pub fn collation_mismatches(schema: &SchemaInfo, table: &TableInfo) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for constraint in &table.constraints {
+        let (Some(foreign_table), Some(foreign_columns)) =
+            (&constraint.foreign_table, &constraint.foreign_columns)
+        else {
+            continue;
+        };
+        let Some(foreign) = schema.tables.iter().find(|t| &t.name == foreign_table) else {
+            continue;
+        };
+
+        for (local, remote) in constraint.columns.iter().zip(foreign_columns) {
+            let local_collation = table
+                .columns
+                .iter()
+                .find(|c| &c.name == local)
+                .and_then(|c| c.collation.as_deref());
+            let remote_collation = foreign
+                .columns
+                .iter()
+                .find(|c| &c.name == remote)
+                .and_then(|c| c.collation.as_deref());
+
+            if let (Some(l), Some(r)) = (local_collation, remote_collation) {
+                if l != r {
+                    warnings.push(format!(
+                        "{} ({}) vs {}.{} ({})",
+                        local, l, foreign_table, remote, r
+                    ));
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Order `tables` so each comes after the tables its foreign keys reference.
+/// Returns indexes into `tables`, and whether a reference cycle had to be broken,
+/// in which case the constraints must be deferred for the import to succeed.
+pub fn fk_dependency_order(tables: &[String], schema: &SchemaInfo) -> (Vec<usize>, bool) {
+    let names: Vec<String> = tables.iter().map(|t| normalize_table_name(t)).collect();
+    let parents: Vec<Vec<usize>> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let Some(table) = schema.tables.iter().find(|t| &t.name == name) else {
+                return Vec::new();
+            };
+            table
+                .constraints
+                .iter()
+                .filter(|c| c.constraint_type == "FOREIGN KEY")
+                .filter_map(|c| c.foreign_table.as_deref())
+                .filter_map(|foreign| {
+                    let foreign = normalize_table_name(foreign);
+                    names.iter().position(|n| *n == foreign)
+                })
+                .filter(|&parent| parent != i)
+                .collect()
+        })
+        .collect();
+
+    let mut order = Vec::with_capacity(tables.len());
+    let mut placed = vec![false; tables.len()];
+    let mut has_cycle = false;
+    while order.len() < tables.len() {
+        let ready =
+            (0..tables.len()).find(|&i| !placed[i] && parents[i].iter().all(|&p| placed[p]));
+        let next = match ready {
+            Some(i) => i,
+            None => {
+                has_cycle = true;
+                (0..tables.len()).find(|&i| !placed[i]).unwrap_or_default()
+            }
+        };
+        placed[next] = true;
+        order.push(next);
+    }
+    (order, has_cycle)
+}
+
 pub fn normalize_table_name(table: &str) -> String {
     table
         .trim()
@@ -229,3 +2130,142 @@ pub fn extract_source_table(sql: &str) -> Option<String> {
 
     Some(table_orig.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mysql_column(name: &str, data_type: &str, column_type: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            default_value: None,
+            is_primary_key: false,
+            identity_generation: None,
+            generation_expression: None,
+            enum_values: Vec::new(),
+            collation: None,
+            comment: None,
+            column_type: Some(column_type.to_string()),
+            extra: None,
+        }
+    }
+
+    #[test]
+    fn mysql_modify_keeps_the_full_column_definition() {
+        let name = ColumnInfo {
+            collation: Some("utf8mb4_general_ci".to_string()),
+            comment: Some("Shown on the user's profile".to_string()),
+            ..mysql_column("name", "varchar", "varchar(255)")
+        };
+        assert_eq!(
+            format_alter_column_sql(
+                DatabaseType::MySQL,
+                "users",
+                &name,
+                &ColumnChange::SetNullable(false)
+            )
+            .unwrap(),
+            "ALTER TABLE `users` MODIFY COLUMN `name` varchar(255) CHARACTER SET utf8mb4 \
+             COLLATE utf8mb4_general_ci NOT NULL COMMENT 'Shown on the user''s profile';"
+        );
+
+        let id = ColumnInfo {
+            nullable: false,
+            is_primary_key: true,
+            identity_generation: Some("auto_increment".to_string()),
+            comment: Some("Row id".to_string()),
+            extra: Some("auto_increment".to_string()),
+            ..mysql_column("id", "int", "int unsigned")
+        };
+        assert_eq!(
+            format_alter_column_sql(
+                DatabaseType::MySQL,
+                "users",
+                &id,
+                &ColumnChange::MoveAfter(None)
+            )
+            .unwrap(),
+            "ALTER TABLE `users` MODIFY COLUMN `id` int unsigned NOT NULL AUTO_INCREMENT \
+             COMMENT 'Row id' FIRST;"
+        );
+
+        let updated_at = ColumnInfo {
+            default_value: Some("CURRENT_TIMESTAMP".to_string()),
+            extra: Some("DEFAULT_GENERATED on update CURRENT_TIMESTAMP".to_string()),
+            ..mysql_column("updated_at", "timestamp", "timestamp")
+        };
+        assert_eq!(
+            format_alter_column_sql(
+                DatabaseType::MySQL,
+                "users",
+                &updated_at,
+                &ColumnChange::SetComment(Some("Last change".to_string()))
+            )
+            .unwrap(),
+            "ALTER TABLE `users` MODIFY COLUMN `updated_at` timestamp NULL DEFAULT \
+             CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT 'Last change';"
+        );
+    }
+
+    #[test]
+    fn replace_escapes_wildcards_and_mysql_backslashes() {
+        assert_eq!(
+            format_replace_sql(DatabaseType::MySQL, "files", "path", "a\\b_%", "c").unwrap(),
+            "UPDATE `files` SET `path` = REPLACE(`path`, 'a\\\\b_%', 'c') \
+             WHERE `path` LIKE '%a\\\\b!_!%%' ESCAPE '!'"
+        );
+        assert_eq!(
+            format_replace_count_sql(DatabaseType::PostgreSQL, "public.files", "Path", "it's")
+                .unwrap(),
+            "SELECT COUNT(*) FROM \"public\".\"files\" WHERE \"Path\" LIKE '%it''s%' ESCAPE '!'"
+        );
+        assert!(format_replace_sql(DatabaseType::SQLite, "files", "path", "", "x").is_none());
+    }
+
+    #[test]
+    fn bulk_update_quotes_identifiers_and_literals() {
+        let keys = vec!["order".to_string()];
+        let key_values = vec![vec!["1".to_string()], vec!["it's".to_string()]];
+        assert_eq!(
+            format_bulk_update_sql(
+                DatabaseType::PostgreSQL,
+                "public.User Notes",
+                "text",
+                Some("NULL"),
+                false,
+                &keys,
+                &key_values
+            )
+            .unwrap(),
+            "UPDATE \"public\".\"User Notes\" SET \"text\" = 'NULL' WHERE \"order\" IN ('1', 'it''s')"
+        );
+        assert_eq!(
+            format_bulk_update_sql(
+                DatabaseType::MySQL,
+                "notes",
+                "path",
+                Some("C:\\temp"),
+                false,
+                &keys,
+                &key_values[..1]
+            )
+            .unwrap(),
+            "UPDATE `notes` SET `path` = 'C:\\\\temp' WHERE `order` IN ('1')"
+        );
+        assert_eq!(
+            format_bulk_update_sql(
+                DatabaseType::MySQL,
+                "notes",
+                "path",
+                None,
+                false,
+                &keys,
+                &key_values[..1]
+            )
+            .unwrap(),
+            "UPDATE `notes` SET `path` = NULL WHERE `order` IN ('1')"
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use odbc_api::buffers::TextRowSet;
+use odbc_api::{Connection, ConnectionOptions, Cursor, DataType, Environment, ResultSetMetadata};
+use std::sync::{Arc, OnceLock};
+
+use super::connection::{truncate_value, BatchOutcome, BatchReporter};
+use super::{ColumnInfo, ConnectionConfig, IndexInfo, OdbcMode, SchemaInfo, TableInfo};
+
+/// Columns, column types and formatted rows of a query, with NULL cells as None.
+pub(super) type OdbcRows = (Vec<String>, Vec<String>, Vec<Vec<Option<String>>>);
+
+/// Rows fetched from the driver per round trip
+const BATCH_SIZE: usize = 500;
+
+/// Longest text fetched per value; longer values are cut by the driver before `truncate_value`
+const MAX_TEXT_BYTES: usize = 4096;
+
+/// The driver manager is loaded once for the whole process.
+fn environment() -> Result<&'static Environment, String> {
+    static ENVIRONMENT: OnceLock<Result<Environment, String>> = OnceLock::new();
+    ENVIRONMENT
+        .get_or_init(|| Environment::new().map_err(|e| e.to_string()))
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+/// Any database with an ODBC driver, for the ones FBench has no native backend for.
+/// Every call opens its own connection on the blocking pool; driver managers that pool
+/// connections make this cheap. Values are fetched as text, whatever their type.
+#[derive(Clone)]
+pub(super) struct OdbcPool {
+    connection_string: Arc<str>,
+    /// Database product reported by the driver, e.g. "Snowflake" or "Microsoft SQL Server"
+    pub(super) dbms_name: String,
+}
+
+impl OdbcPool {
+    pub(super) async fn open(config: &ConnectionConfig) -> Result<Self, String> {
+        let connection_string: Arc<str> = connection_string(config).into();
+        let target = connection_string.clone();
+        let dbms_name = tokio::task::spawn_blocking(move || {
+            let conn = connect(&target)?;
+            conn.database_management_system_name()
+                .map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        Ok(Self {
+            connection_string,
+            dbms_name,
+        })
+    }
+
+    async fn run<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Connection<'static>) -> Result<T, odbc_api::Error> + Send + 'static,
+    ) -> Result<T, String> {
+        let connection_string = self.connection_string.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = connect(&connection_string)?;
+            f(&conn).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    /// Opening a connection is the only check that works on every driver;
+    /// `SELECT 1` is not valid SQL everywhere.
+    pub(super) async fn ping(&self) -> Result<(), String> {
+        self.run(|_| Ok(())).await
+    }
+
+    pub(super) async fn query(&self, sql: &str) -> Result<OdbcRows, String> {
+        let sql = sql.to_string();
+        self.run(move |conn| match conn.execute(&sql, (), None)? {
+            Some(cursor) => read_text(cursor),
+            None => Ok((Vec::new(), Vec::new(), Vec::new())),
+        })
+        .await
+    }
+
+    pub(super) async fn execute(&self, sql: &str) -> Result<u64, String> {
+        let sql = sql.to_string();
+        self.run(move |conn| {
+            let mut statement = conn.preallocate()?;
+            statement.execute(&sql, ())?;
+            Ok(statement.row_count()?.unwrap_or(0) as u64)
+        })
+        .await
+    }
+
+    /// Run statements in one transaction, returning the affected row count
+    /// or the failing step along with its error. With `row_edits` each statement
+    /// must match a row, or nothing is committed.
+    pub(super) async fn batch(
+        &self,
+        statements: &[String],
+        row_edits: bool,
+        reporter: BatchReporter,
+    ) -> Result<BatchOutcome, (&'static str, String)> {
+        let statements = statements.to_vec();
+        let connection_string = self.connection_string.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = connect(&connection_string).map_err(|e| ("Begin failed", e))?;
+            conn.set_autocommit(false)
+                .map_err(|e| ("Begin failed", e.to_string()))?;
+            let mut total_affected = 0u64;
+            for (i, sql) in statements.iter().enumerate() {
+                if let Err(e) = reporter.start(i, sql) {
+                    conn.rollback().ok();
+                    return Err(("Batch cancelled", e));
+                }
+                let affected = conn.preallocate().and_then(|mut statement| {
+                    statement.execute(sql, ())?;
+                    statement.row_count()
+                });
+                match affected {
+                    // Drivers that don't count rows answer None, which isn't a mismatch
+                    Ok(Some(0)) if row_edits => {
+                        conn.rollback().ok();
+                        return Ok(BatchOutcome::Unmatched(i));
+                    }
+                    Ok(affected) => total_affected += affected.unwrap_or(0) as u64,
+                    Err(e) => {
+                        conn.rollback().ok();
+                        return Err(("Batch failed", e.to_string()));
+                    }
+                }
+            }
+            conn.commit()
+                .map_err(|e| ("Commit failed", e.to_string()))?;
+            Ok(BatchOutcome::Committed(total_affected))
+        })
+        .await
+        .map_err(|e| ("Batch failed", e.to_string()))?
+    }
+
+    /// Try each statement in a transaction of its own and roll it back. Savepoint syntax
+    /// differs between databases, so later statements don't see the rows of earlier ones.
+    pub(super) async fn dry_run(
+        &self,
+        statements: &[String],
+    ) -> Result<Vec<Option<String>>, String> {
+        let statements = statements.to_vec();
+        self.run(move |conn| {
+            conn.set_autocommit(false)?;
+            let mut outcomes = Vec::with_capacity(statements.len());
+            for sql in &statements {
+                outcomes.push(conn.execute(sql, (), None).err().map(|e| e.to_string()));
+                conn.rollback()?;
+            }
+            Ok(outcomes)
+        })
+        .await
+    }
+
+    /// Tables of `schema` (every schema the user can see when None) with their columns
+    /// and primary keys, plus its views.
+    pub(super) async fn schema(&self, schema: Option<String>) -> Result<SchemaInfo, String> {
+        self.run(move |conn| {
+            let pattern = schema.as_deref().unwrap_or("%");
+            let (_, _, rows) = read_text(conn.tables("", pattern, "%", "TABLE,VIEW")?)?;
+
+            let mut tables = Vec::new();
+            let mut views = Vec::new();
+            // TABLE_CAT, TABLE_SCHEM, TABLE_NAME, TABLE_TYPE
+            for row in rows {
+                let [_, table_schema, Some(name), Some(table_type), ..] = row.as_slice() else {
+                    continue;
+                };
+                // Databases without schemas report NULL
+                let table_schema = table_schema.as_deref();
+                // Without a schema filter, names are qualified so they can be queried
+                let qualified = match table_schema {
+                    Some(table_schema) if schema.is_none() => {
+                        format!("{}.{}", table_schema, name)
+                    }
+                    _ => name.clone(),
+                };
+                if table_type == "VIEW" {
+                    views.push(qualified);
+                } else {
+                    let mut table = table_info(conn, name, table_schema)?;
+                    table.name = qualified;
+                    tables.push(table);
+                }
+            }
+            Ok(SchemaInfo {
+                tables,
+                views,
+                sequences: Vec::new(),
+            })
+        })
+        .await
+    }
+
+    /// Columns (name, data type) of a table; empty when it does not exist.
+    pub(super) async fn columns(
+        &self,
+        table: &str,
+        schema: Option<String>,
+    ) -> Result<Vec<(String, String)>, String> {
+        let table = table.to_string();
+        self.run(move |conn| {
+            let (_, _, rows) =
+                read_text(conn.columns("", schema.as_deref().unwrap_or("%"), &table, "%")?)?;
+            Ok(rows
+                .into_iter()
+                .filter_map(|row| Some((row.get(3)?.clone()?, row.get(5)?.clone()?)))
+                .collect())
+        })
+        .await
+    }
+
+    pub(super) async fn table_info(
+        &self,
+        table: &str,
+        schema: Option<String>,
+    ) -> Result<TableInfo, String> {
+        let table = table.to_string();
+        self.run(move |conn| table_info(conn, &table, schema.as_deref()))
+            .await
+    }
+}
+
+fn connect(connection_string: &str) -> Result<Connection<'static>, String> {
+    environment()?
+        .connect_with_connection_string(connection_string, ConnectionOptions::default())
+        .map_err(|e| e.to_string())
+}
+
+/// Braces let a value contain `;` and `=`.
+fn attribute_value(value: &str) -> String {
+    format!("{{{}}}", value.replace('}', "}}"))
+}
+
+/// `DSN=...;` or the driver string as entered, plus the username and password when given.
+fn connection_string(config: &ConnectionConfig) -> String {
+    let source = config.database.trim();
+    let mut connection_string = match config.odbc_mode {
+        OdbcMode::Dsn => format!("DSN={};", attribute_value(source)),
+        OdbcMode::DriverString => format!("{};", source.trim_end_matches(';')),
+    };
+    if !config.user.is_empty() {
+        connection_string.push_str(&format!("UID={};", attribute_value(&config.user)));
+    }
+    if !config.password.is_empty() {
+        connection_string.push_str(&format!("PWD={};", attribute_value(&config.password)));
+    }
+    connection_string
+}
+
+/// Columns, their types and every row of a result set, each value as text or None for NULL.
+fn read_text(mut cursor: impl Cursor) -> Result<OdbcRows, odbc_api::Error> {
+    let count = cursor.num_result_cols()?.max(0) as u16;
+    let mut columns = Vec::with_capacity(count as usize);
+    let mut types = Vec::with_capacity(count as usize);
+    for i in 1..=count {
+        columns.push(cursor.col_name(i)?);
+        types.push(odbc_type_name(&cursor.col_data_type(i)?));
+    }
+    if count == 0 {
+        return Ok((columns, types, Vec::new()));
+    }
+
+    let buffer = TextRowSet::for_cursor(BATCH_SIZE, &mut cursor, Some(MAX_TEXT_BYTES))?;
+    let mut row_set = cursor.bind_buffer(buffer)?;
+    let mut rows = Vec::new();
+    while let Some(batch) = row_set.fetch()? {
+        for row in 0..batch.num_rows() {
+            rows.push(
+                (0..batch.num_cols())
+                    .map(|col| format_odbc_value(batch.at(col, row)))
+                    .collect(),
+            );
+        }
+    }
+    Ok((columns, types, rows))
+}
+
+fn table_info(
+    conn: &Connection<'_>,
+    table: &str,
+    schema: Option<&str>,
+) -> Result<TableInfo, odbc_api::Error> {
+    // Not every driver implements SQLPrimaryKeys; the table is then shown without keys
+    let primary_key: Vec<String> = conn
+        .primary_keys(None, schema, table)
+        .and_then(read_text)
+        .map(|(_, _, rows)| {
+            // TABLE_CAT, TABLE_SCHEM, TABLE_NAME, COLUMN_NAME, KEY_SEQ, PK_NAME
+            rows.into_iter()
+                .filter_map(|row| row.get(3).cloned().flatten())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let (_, _, rows) = read_text(conn.columns("", schema.unwrap_or("%"), table, "%")?)?;
+    // COLUMN_NAME, TYPE_NAME, NULLABLE, REMARKS and COLUMN_DEF of the SQLColumns result
+    let columns: Vec<ColumnInfo> = rows
+        .into_iter()
+        .filter_map(|row| {
+            let name = row.get(3)?.clone()?;
+            let is_primary_key = primary_key.contains(&name);
+            Some(ColumnInfo {
+                data_type: row.get(5)?.clone()?,
+                nullable: row.get(10).and_then(Option::as_deref) != Some("0") && !is_primary_key,
+                default_value: row.get(12).cloned().flatten(),
+                is_primary_key,
+                identity_generation: None,
+                generation_expression: None,
+                enum_values: Vec::new(),
+                collation: None,
+                comment: row.get(11).cloned().flatten().filter(|r| !r.is_empty()),
+                column_type: None,
+                extra: None,
+                name,
+            })
+        })
+        .collect();
+
+    let indexes = if primary_key.is_empty() {
+        Vec::new()
+    } else {
+        vec![IndexInfo {
+            name: "PRIMARY KEY".to_string(),
+            columns: primary_key,
+            is_unique: true,
+            is_primary: true,
+            index_type: "primary key".to_string(),
+        }]
+    };
+
+    Ok(TableInfo {
+        name: table.to_string(),
+        columns,
+        indexes,
+        constraints: Vec::new(),
+        row_estimate: 0,
+        collation: None,
+        comment: None,
+    })
+}
+
+/// SQL names for the ODBC types drivers report.
+fn odbc_type_name(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Bit => "BIT".to_string(),
+        DataType::TinyInt => "TINYINT".to_string(),
+        DataType::SmallInt => "SMALLINT".to_string(),
+        DataType::Integer => "INTEGER".to_string(),
+        DataType::BigInt => "BIGINT".to_string(),
+        DataType::Real => "REAL".to_string(),
+        DataType::Float { .. } => "FLOAT".to_string(),
+        DataType::Double => "DOUBLE".to_string(),
+        DataType::Decimal { precision, scale } | DataType::Numeric { precision, scale } => {
+            format!("DECIMAL({},{})", precision, scale)
+        }
+        DataType::Char { .. } | DataType::WChar { .. } => "CHAR".to_string(),
+        DataType::Varchar { .. } | DataType::WVarchar { .. } => "VARCHAR".to_string(),
+        DataType::LongVarchar { .. } | DataType::WLongVarchar { .. } => "TEXT".to_string(),
+        DataType::Binary { .. } | DataType::Varbinary { .. } | DataType::LongVarbinary { .. } => {
+            "BINARY".to_string()
+        }
+        DataType::Date => "DATE".to_string(),
+        DataType::Time { .. } => "TIME".to_string(),
+        DataType::Timestamp { .. } => "TIMESTAMP".to_string(),
+        other => format!("{:?}", other).to_uppercase(),
+    }
+}
+
+fn format_odbc_value(value: Option<&[u8]>) -> Option<String> {
+    value.map(|bytes| truncate_value(String::from_utf8_lossy(bytes).into_owned()))
+}
@@ -1 +1,1222 @@
-// Query utilities - placeholder for future query parsing/validation
+// Query utilities - parsing and validation helpers
+
+use super::sql_analysis::{modifies_data, nested_code_words};
+use super::{DatabaseType, TableInfo};
+use std::ops::Range;
+
+/// A restriction on the values a column accepts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRule {
+    OneOf(Vec<String>),
+    Compare { op: &'static str, bound: f64 },
+}
+
+impl ValueRule {
+    fn check(&self, value: &str) -> Result<(), String> {
+        match self {
+            ValueRule::OneOf(allowed) => {
+                if allowed.iter().any(|a| a == value) {
+                    Ok(())
+                } else {
+                    Err(format!("'{}' is not one of: {}", value, allowed.join(", ")))
+                }
+            }
+            ValueRule::Compare { op, bound } => {
+                let n: f64 = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a number", value))?;
+                let ok = match *op {
+                    ">" => n > *bound,
+                    ">=" => n >= *bound,
+                    "<" => n < *bound,
+                    "<=" => n <= *bound,
+                    _ => n != *bound,
+                };
+                if ok {
+                    Ok(())
+                } else {
+                    Err(format!("{} must be {} {}", value, op, bound))
+                }
+            }
+        }
+    }
+}
+
+/// Allowed-value hints and local checks for one column, from its enum type and CHECK constraints.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnRules {
+    pub nullable: bool,
+    pub enum_values: Vec<String>,
+    pub hints: Vec<String>,
+    pub rules: Vec<ValueRule>,
+}
+
+impl ColumnRules {
+    pub fn for_column(table: &TableInfo, column: &str) -> Self {
+        let Some(col) = table.columns.iter().find(|c| c.name == column) else {
+            return Self {
+                nullable: true,
+                ..Default::default()
+            };
+        };
+
+        let mut rules = Self {
+            nullable: col.nullable,
+            enum_values: col.enum_values.clone(),
+            ..Default::default()
+        };
+
+        if !col.enum_values.is_empty() {
+            rules
+                .hints
+                .push(format!("enum: {}", col.enum_values.join(", ")));
+            rules.rules.push(ValueRule::OneOf(col.enum_values.clone()));
+        }
+
+        for constraint in &table.constraints {
+            if constraint.constraint_type != "CHECK" {
+                continue;
+            }
+            let Some(clause) = &constraint.check_clause else {
+                continue;
+            };
+            // PostgreSQL reports NOT NULL as a CHECK constraint too
+            if is_not_null_check(clause) || !mentions_column(clause, column) {
+                continue;
+            }
+            rules.hints.push(format!("CHECK {}", clause));
+            rules.rules.extend(parse_check_clause(clause, column));
+        }
+
+        rules
+    }
+
+    /// Check a new value for the column, None being SQL NULL.
+    pub fn validate(&self, value: Option<&str>) -> Result<(), String> {
+        match value {
+            Some(value) => self.rules.iter().try_for_each(|rule| rule.check(value)),
+            None if self.nullable => Ok(()),
+            None => Err("Column does not accept NULL".into()),
+        }
+    }
+}
+
+/// Parse the labels out of a MySQL `enum('a','b')` column type.
+pub fn parse_enum_values(column_type: &str) -> Vec<String> {
+    let (Some(start), Some(end)) = (column_type.find('('), column_type.rfind(')')) else {
+        return Vec::new();
+    };
+    if start >= end {
+        return Vec::new();
+    }
+    parse_literal_list(&column_type[start + 1..end]).unwrap_or_default()
+}
+
+/// Extract the rules a CHECK clause places on `column`.
+/// Only simple comparisons, IN lists, `= ANY (ARRAY[...])` and ORs of equalities are understood;
+/// anything else yields no rules and is left for the server to enforce.
+pub fn parse_check_clause(clause: &str, column: &str) -> Vec<ValueRule> {
+    let simplified = simplify_clause(clause);
+
+    let disjuncts = split_keyword(&simplified, "OR");
+    if disjuncts.len() > 1 {
+        let mut allowed = Vec::new();
+        for part in disjuncts {
+            match parse_comparison(part, column) {
+                Some(ValueRule::OneOf(values)) => allowed.extend(values),
+                _ => return Vec::new(),
+            }
+        }
+        return vec![ValueRule::OneOf(allowed)];
+    }
+
+    split_keyword(&simplified, "AND")
+        .into_iter()
+        .filter_map(|part| parse_comparison(part, column))
+        .collect()
+}
+
+fn parse_comparison(expr: &str, column: &str) -> Option<ValueRule> {
+    let (ident, rest) = take_identifier(expr.trim())?;
+    if !ident.eq_ignore_ascii_case(column) {
+        return None;
+    }
+    let rest = rest.trim_start();
+    let upper = rest.to_uppercase();
+
+    if upper.starts_with("= ANY") {
+        let list = rest[5..].trim_start();
+        let list = if list.to_uppercase().starts_with("ARRAY") {
+            &list[5..]
+        } else {
+            list
+        };
+        return parse_literal_list(list).map(ValueRule::OneOf);
+    }
+    if upper.starts_with("IN ") {
+        return parse_literal_list(&rest[3..]).map(ValueRule::OneOf);
+    }
+
+    for op in [">=", "<=", "<>", "!=", ">", "<", "="] {
+        if let Some(value) = rest.strip_prefix(op) {
+            let literal = parse_literal(value)?;
+            return if op == "=" {
+                Some(ValueRule::OneOf(vec![literal]))
+            } else {
+                let bound = literal.parse().ok()?;
+                Some(ValueRule::Compare { op, bound })
+            };
+        }
+    }
+
+    None
+}
+
+/// Drop parentheses, array brackets and `::type` casts outside of string literals.
+fn simplify_clause(clause: &str) -> String {
+    let mut out = String::with_capacity(clause.len());
+    let mut chars = clause.chars().peekable();
+    let mut in_quote = false;
+
+    while let Some(c) = chars.next() {
+        if c == '\'' {
+            in_quote = !in_quote;
+            out.push(c);
+            continue;
+        }
+        if in_quote {
+            out.push(c);
+            continue;
+        }
+        match c {
+            '(' | ')' | '[' | ']' => {}
+            ':' if chars.peek() == Some(&':') => {
+                chars.next();
+                let mut type_name = String::new();
+                while let Some(&n) = chars.peek() {
+                    if n.is_alphanumeric() || n == '_' || n == '[' || n == ']' {
+                        type_name.push(n);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                // Multi-word type names
+                if matches!(type_name.as_str(), "character" | "double") {
+                    let rest: String = chars.clone().collect();
+                    let lower = rest.to_lowercase();
+                    for suffix in [" varying", " precision"] {
+                        if lower.starts_with(suffix) {
+                            for _ in 0..suffix.len() {
+                                chars.next();
+                            }
+                        }
+                    }
+                    while matches!(chars.peek(), Some('[') | Some(']')) {
+                        chars.next();
+                    }
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Split on a keyword (case-insensitive, whitespace-delimited) outside of string literals.
+fn split_keyword<'a>(text: &'a str, keyword: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let bytes = text.as_bytes();
+    let kw = keyword.as_bytes();
+    let mut in_quote = false;
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'\'' {
+            in_quote = !in_quote;
+        } else if !in_quote
+            && bytes[i].is_ascii_whitespace()
+            && i + kw.len() + 1 < bytes.len()
+            && bytes[i + 1..i + 1 + kw.len()].eq_ignore_ascii_case(kw)
+            && bytes[i + 1 + kw.len()].is_ascii_whitespace()
+        {
+            parts.push(&text[start..i]);
+            i += kw.len() + 1;
+            start = i;
+            continue;
+        }
+        i += 1;
+    }
+    parts.push(&text[start..]);
+
+    parts
+}
+
+fn take_identifier(expr: &str) -> Option<(String, &str)> {
+    let first = expr.chars().next()?;
+    if first == '"' || first == '`' {
+        let end = expr[1..].find(first)? + 1;
+        return Some((expr[1..end].to_string(), &expr[end + 1..]));
+    }
+    let end = expr
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(expr.len());
+    if end == 0 {
+        return None;
+    }
+    Some((expr[..end].to_string(), &expr[end..]))
+}
+
+fn parse_literal_list(text: &str) -> Option<Vec<String>> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut in_quote = false;
+
+    for c in text.chars() {
+        match c {
+            '\'' => {
+                in_quote = !in_quote;
+                current.push(c);
+            }
+            ',' if !in_quote => items.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    items.push(current);
+
+    items.iter().map(|item| parse_literal(item)).collect()
+}
+
+fn parse_literal(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.len() >= 2 && text.starts_with('\'') && text.ends_with('\'') {
+        return Some(text[1..text.len() - 1].replace("''", "'"));
+    }
+    text.parse::<f64>().ok().map(|_| text.to_string())
+}
+
+/// Whether a CHECK clause is only `column IS NOT NULL`, the form a NOT NULL constraint
+/// takes, rather than a condition that ends with one.
+fn is_not_null_check(clause: &str) -> bool {
+    let mut clause = clause.trim();
+    while let Some(inner) = clause.strip_prefix('(').and_then(|c| c.strip_suffix(')')) {
+        clause = inner.trim();
+    }
+    let Some((_, rest)) = take_identifier(clause) else {
+        return false;
+    };
+    let words: Vec<&str> = rest.split_whitespace().collect();
+    words.len() == 3
+        && words
+            .iter()
+            .zip(["IS", "NOT", "NULL"])
+            .all(|(word, keyword)| word.eq_ignore_ascii_case(keyword))
+}
+
+fn mentions_column(clause: &str, column: &str) -> bool {
+    let mut in_quote = false;
+    let mut word = String::new();
+
+    for c in clause.chars().chain(std::iter::once(' ')) {
+        if c == '\'' {
+            in_quote = !in_quote;
+            word.clear();
+        } else if !in_quote && (c.is_alphanumeric() || c == '_') {
+            word.push(c);
+        } else {
+            if word.eq_ignore_ascii_case(column) {
+                return true;
+            }
+            word.clear();
+        }
+    }
+
+    false
+}
+
+/// A `-- #region name` ... `-- #endregion` section of a script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlRegion {
+    pub name: String,
+    /// Zero-based line of the `#region` marker
+    pub line: usize,
+    /// Nesting level, 0 for top-level regions
+    pub depth: usize,
+    /// Text between the markers, including nested regions
+    pub body: String,
+}
+
+/// Find region markers in a script, in order of appearance.
+/// Regions may nest; an unclosed region runs to the end of the script.
+pub fn parse_regions(script: &str) -> Vec<SqlRegion> {
+    let lines: Vec<&str> = script.lines().collect();
+    let mut regions = Vec::new();
+    // (index into regions, first body line) of each open region
+    let mut open: Vec<(usize, usize)> = Vec::new();
+
+    let close = |regions: &mut Vec<SqlRegion>, (idx, start): (usize, usize), end: usize| {
+        regions[idx].body = lines[start..end].join("\n");
+    };
+
+    for (line_idx, line) in lines.iter().enumerate() {
+        let Some(comment) = line.trim_start().strip_prefix("--") else {
+            continue;
+        };
+        let marker = comment.trim();
+        if let Some(name) = marker.strip_prefix("#region") {
+            let name = name.trim();
+            regions.push(SqlRegion {
+                name: if name.is_empty() {
+                    format!("Region {}", regions.len() + 1)
+                } else {
+                    name.to_string()
+                },
+                line: line_idx,
+                depth: open.len(),
+                body: String::new(),
+            });
+            open.push((regions.len() - 1, line_idx + 1));
+        } else if marker.starts_with("#endregion") {
+            if let Some(region) = open.pop() {
+                close(&mut regions, region, line_idx);
+            }
+        }
+    }
+    while let Some(region) = open.pop() {
+        close(&mut regions, region, lines.len());
+    }
+
+    regions
+}
+
+/// Split a script into statements on `;`, ignoring semicolons inside quotes,
+/// comments and PostgreSQL dollar-quoted bodies. Comment-only pieces are dropped.
+pub fn split_statements(script: &str) -> Vec<String> {
+    statement_spans(script)
+        .into_iter()
+        .map(|(_, statement)| statement)
+        .collect()
+}
+
+/// The statement under the cursor, given as a char offset. A cursor later on the
+/// line that ends a statement, after its `;`, still picks that statement.
+pub fn statement_at(script: &str, cursor: usize) -> Option<String> {
+    span_at(script, cursor).map(|(_, statement)| statement)
+}
+
+/// Char range of the statement `statement_at` picks, without the whitespace around it.
+pub fn statement_range_at(script: &str, cursor: usize) -> Option<Range<usize>> {
+    let (range, _) = span_at(script, cursor)?;
+    let chars: Vec<char> = script.chars().collect();
+    let text = &chars[range.clone()];
+    let start = text.iter().position(|c| !c.is_whitespace())?;
+    let end = text.iter().rposition(|c| !c.is_whitespace())? + 1;
+    Some(range.start + start..range.start + end)
+}
+
+fn span_at(script: &str, cursor: usize) -> Option<(Range<usize>, String)> {
+    let chars: Vec<char> = script.chars().collect();
+    let cursor = cursor.min(chars.len());
+    let spans = statement_spans(script);
+    spans
+        .iter()
+        .rev()
+        .find(|(range, _)| range.end < cursor && !chars[range.end..cursor].contains(&'\n'))
+        .or_else(|| spans.iter().find(|(range, _)| cursor <= range.end))
+        .or(spans.last())
+        .cloned()
+}
+
+/// Statements with the char range each one covers, from just after the previous `;`
+/// up to its own `;`.
+pub(super) fn statement_spans(script: &str) -> Vec<(Range<usize>, String)> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut has_code = false;
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == ';' {
+            if has_code {
+                statements.push((start..i, current.trim().to_string()));
+            }
+            current.clear();
+            has_code = false;
+            i += 1;
+            start = i;
+            continue;
+        }
+        let is_comment = matches!((c, chars.get(i + 1)), ('-', Some('-')) | ('/', Some('*')));
+        if !is_comment && !c.is_whitespace() {
+            has_code = true;
+        }
+        let end = literal_end(&chars, i).unwrap_or(i + 1);
+        current.extend(&chars[i..end]);
+        i = end;
+    }
+    if has_code {
+        statements.push((start..chars.len(), current.trim().to_string()));
+    }
+
+    statements
+}
+
+/// End of the comment, quoted string or identifier, or dollar-quoted body that starts
+/// at `i`, if one does. A line comment ends before its newline.
+pub(super) fn literal_end(chars: &[char], i: usize) -> Option<usize> {
+    let c = chars[i];
+    match c {
+        '-' if chars.get(i + 1) == Some(&'-') => Some(
+            chars[i..]
+                .iter()
+                .position(|&ch| ch == '\n')
+                .map_or(chars.len(), |p| i + p),
+        ),
+        '/' if chars.get(i + 1) == Some(&'*') => {
+            Some(find_chars(chars, i + 2, &['*', '/']).map_or(chars.len(), |p| p + 2))
+        }
+        '\'' | '"' | '`' => {
+            let mut j = i + 1;
+            Some(loop {
+                match chars.get(j) {
+                    None => break chars.len(),
+                    // A doubled quote is an escaped quote
+                    Some(&ch) if ch == c && chars.get(j + 1) == Some(&c) => j += 2,
+                    Some(&ch) if ch == c => break j + 1,
+                    Some(_) => j += 1,
+                }
+            })
+        }
+        '$' => dollar_tag(chars, i).map(|tag| {
+            find_chars(chars, i + tag.len(), &tag).map_or(chars.len(), |p| p + tag.len())
+        }),
+        _ => None,
+    }
+}
+
+/// Char offsets of the parenthesis beside the cursor and the one it pairs with,
+/// ignoring parentheses in comments and quotes. The one just before the cursor wins
+/// over the one just after it.
+pub fn matching_paren(script: &str, cursor: usize) -> Option<(usize, usize)> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut open = Vec::new();
+    let mut pairs = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if let Some(end) = literal_end(&chars, i) {
+            i = end;
+            continue;
+        }
+        match chars[i] {
+            '(' => open.push(i),
+            ')' => {
+                if let Some(start) = open.pop() {
+                    pairs.push((start, i));
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    let partner = |at: usize| {
+        pairs.iter().find_map(|&(start, end)| {
+            if start == at {
+                Some((start, end))
+            } else if end == at {
+                Some((end, start))
+            } else {
+                None
+            }
+        })
+    };
+    cursor
+        .checked_sub(1)
+        .and_then(partner)
+        .or_else(|| partner(cursor))
+}
+
+/// The `$tag$` opening a dollar-quoted string at `start`, if there is one.
+fn dollar_tag(chars: &[char], start: usize) -> Option<Vec<char>> {
+    let mut end = start + 1;
+    while let Some(&c) = chars.get(end) {
+        if c == '$' {
+            return Some(chars[start..=end].to_vec());
+        }
+        let valid = c == '_' || c.is_alphabetic() || (end > start + 1 && c.is_ascii_digit());
+        if !valid {
+            return None;
+        }
+        end += 1;
+    }
+    None
+}
+
+pub(super) fn find_chars(chars: &[char], from: usize, needle: &[char]) -> Option<usize> {
+    if from > chars.len() {
+        return None;
+    }
+    chars[from..]
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| from + p)
+}
+
+/// A bind parameter placeholder in a statement.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Placeholder {
+    /// `:name`
+    Named(String),
+    /// `$1` (PostgreSQL)
+    Numbered(usize),
+    /// The nth `?` (MySQL and SQLite), counting from 1
+    Positional(usize),
+}
+
+impl Placeholder {
+    pub fn label(&self) -> String {
+        match self {
+            Placeholder::Named(name) => format!(":{}", name),
+            Placeholder::Numbered(n) => format!("${}", n),
+            Placeholder::Positional(n) => format!("?{}", n),
+        }
+    }
+}
+
+/// A value bound to a placeholder. Text is sent as text, so a PostgreSQL placeholder
+/// compared with a date or similar column needs a cast such as `$1::date`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl ParamValue {
+    /// Value typed into a parameter prompt. Numbers with leading zeros stay text,
+    /// so codes like `007` keep their digits.
+    pub fn guess(text: &str) -> Self {
+        let trimmed = text.trim();
+        if trimmed.eq_ignore_ascii_case("null") {
+            return ParamValue::Null;
+        }
+        if trimmed.eq_ignore_ascii_case("true") || trimmed.eq_ignore_ascii_case("false") {
+            return ParamValue::Bool(trimmed.eq_ignore_ascii_case("true"));
+        }
+        let digits = trimmed.strip_prefix('-').unwrap_or(trimmed);
+        let leading_zero = digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.");
+        if !leading_zero {
+            if let Ok(n) = trimmed.parse::<i64>() {
+                return ParamValue::Int(n);
+            }
+            if trimmed.contains('.') {
+                if let Ok(f) = trimmed.parse::<f64>() {
+                    if f.is_finite() {
+                        return ParamValue::Float(f);
+                    }
+                }
+            }
+        }
+        ParamValue::Text(text.to_string())
+    }
+
+    pub fn type_label(&self) -> &'static str {
+        match self {
+            ParamValue::Null => "NULL",
+            ParamValue::Bool(_) => "boolean",
+            ParamValue::Int(_) => "integer",
+            ParamValue::Float(_) => "number",
+            ParamValue::Text(_) => "text",
+        }
+    }
+}
+
+/// Placeholders in `sql` with their char ranges, skipping literals, comments, `::`
+/// casts and array slices. `?` only counts on MySQL and SQLite, where PostgreSQL
+/// would read it as a JSON operator, and `$1` only on PostgreSQL.
+pub fn find_placeholders(sql: &str, db_type: DatabaseType) -> Vec<(Range<usize>, Placeholder)> {
+    let chars: Vec<char> = sql.chars().collect();
+    let is_word = |c: char| c == '_' || c.is_alphanumeric();
+    let mut found = Vec::new();
+    let mut positional = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        i = match c {
+            '-' if next == Some('-') => chars[i..]
+                .iter()
+                .position(|&ch| ch == '\n')
+                .map_or(chars.len(), |p| i + p),
+            '/' if next == Some('*') => {
+                find_chars(&chars, i + 2, &['*', '/']).map_or(chars.len(), |p| p + 2)
+            }
+            '\'' | '"' | '`' => {
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => break chars.len(),
+                        Some(&ch) if ch == c && chars.get(j + 1) == Some(&c) => j += 2,
+                        Some(&ch) if ch == c => break j + 1,
+                        Some(_) => j += 1,
+                    }
+                }
+            }
+            '$' if db_type == DatabaseType::PostgreSQL => {
+                if let Some(tag) = dollar_tag(&chars, i) {
+                    find_chars(&chars, i + tag.len(), &tag).map_or(chars.len(), |p| p + tag.len())
+                } else {
+                    let end = (i + 1..chars.len())
+                        .find(|&j| !chars[j].is_ascii_digit())
+                        .unwrap_or(chars.len());
+                    let number: String = chars[i + 1..end].iter().collect();
+                    if let Ok(n) = number.parse::<usize>() {
+                        found.push((i..end, Placeholder::Numbered(n)));
+                    }
+                    end.max(i + 1)
+                }
+            }
+            ':' if next.is_some_and(|n| n == '_' || n.is_alphabetic())
+                && !prev.is_some_and(|p| p == ':' || p == ']' || is_word(p)) =>
+            {
+                let end = (i + 1..chars.len())
+                    .find(|&j| !is_word(chars[j]))
+                    .unwrap_or(chars.len());
+                let name: String = chars[i + 1..end].iter().collect();
+                found.push((i..end, Placeholder::Named(name)));
+                end
+            }
+            '?' if matches!(db_type, DatabaseType::MySQL | DatabaseType::SQLite) => {
+                positional += 1;
+                found.push((i..i + 1, Placeholder::Positional(positional)));
+                i + 1
+            }
+            _ => i + 1,
+        };
+    }
+    found
+}
+
+/// Distinct placeholders of `sql` in the order they first appear.
+pub fn query_placeholders(sql: &str, db_type: DatabaseType) -> Vec<Placeholder> {
+    let mut placeholders = Vec::new();
+    for (_, placeholder) in find_placeholders(sql, db_type) {
+        if !placeholders.contains(&placeholder) {
+            placeholders.push(placeholder);
+        }
+    }
+    placeholders
+}
+
+/// Rewrite `sql` to the driver's own placeholders and list the values in bind order.
+/// PostgreSQL numbers `:name` after the highest `$n`, reusing the number for repeats;
+/// MySQL and SQLite turn `:name` into `?` and bind the value once per use.
+/// Placeholders without a value are bound as NULL.
+pub fn bind_placeholders(
+    sql: &str,
+    db_type: DatabaseType,
+    values: &[(Placeholder, ParamValue)],
+) -> (String, Vec<ParamValue>) {
+    let value_of = |placeholder: &Placeholder| {
+        values
+            .iter()
+            .find(|(p, _)| p == placeholder)
+            .map_or(ParamValue::Null, |(_, v)| v.clone())
+    };
+    let found = find_placeholders(sql, db_type);
+    let chars: Vec<char> = sql.chars().collect();
+    let mut rewritten = String::with_capacity(sql.len());
+    let mut params = Vec::new();
+    let mut last = 0;
+
+    if db_type == DatabaseType::PostgreSQL {
+        let highest = found
+            .iter()
+            .filter_map(|(_, p)| match p {
+                Placeholder::Numbered(n) => Some(*n),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        // $n binds the nth value, so gaps are filled with NULL
+        let mut numbered: Vec<ParamValue> = (1..=highest)
+            .map(|n| value_of(&Placeholder::Numbered(n)))
+            .collect();
+        let mut names: Vec<&Placeholder> = Vec::new();
+        for (range, placeholder) in &found {
+            rewritten.extend(&chars[last..range.start]);
+            last = range.end;
+            if let Placeholder::Named(_) = placeholder {
+                let index = match names.iter().position(|p| *p == placeholder) {
+                    Some(i) => i,
+                    None => {
+                        names.push(placeholder);
+                        numbered.push(value_of(placeholder));
+                        names.len() - 1
+                    }
+                };
+                rewritten.push_str(&format!("${}", highest + index + 1));
+            } else {
+                rewritten.extend(&chars[range.clone()]);
+            }
+        }
+        params = numbered;
+    } else {
+        for (range, placeholder) in &found {
+            rewritten.extend(&chars[last..range.start]);
+            last = range.end;
+            rewritten.push('?');
+            params.push(value_of(placeholder));
+        }
+    }
+    rewritten.extend(&chars[last..]);
+    (rewritten, params)
+}
+
+/// Stable key of a statement for remembering its parameter values and run times,
+/// ignoring differences in whitespace and a trailing semicolon.
+pub fn query_hash(sql: &str) -> String {
+    // FNV-1a, which stays the same across builds unlike the std hasher
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for word in sql.trim().trim_end_matches(';').split_whitespace() {
+        for byte in word.bytes().chain(std::iter::once(b' ')) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+/// `sql` with a LIMIT of one row more than `limit` appended, so the result shows
+/// whether more rows exist. None unless it is a single SELECT (or WITH ... SELECT)
+/// that doesn't already limit, lock or store its rows.
+pub fn auto_limit_sql(sql: &str, limit: usize) -> Option<String> {
+    let statements = split_statements(sql);
+    let [statement] = statements.as_slice() else {
+        return None;
+    };
+    let words: Vec<String> = nested_code_words(statement)
+        .into_iter()
+        .filter(|(_, depth)| *depth == 0)
+        .map(|(word, _)| word)
+        .collect();
+    let reads = match words.first().map(String::as_str) {
+        Some("SELECT") => true,
+        Some("WITH") => words.iter().any(|w| w == "SELECT") && !modifies_data(statement),
+        _ => false,
+    };
+    const LIMITING: &[&str] = &["LIMIT", "FETCH", "TOP", "OFFSET", "INTO", "FOR"];
+    if !reads || words.iter().any(|w| LIMITING.contains(&w.as_str())) {
+        return None;
+    }
+    // On its own line, in case the statement ends with a line comment
+    Some(format!(
+        "{}\nLIMIT {}",
+        statement.trim().trim_end_matches(';').trim_end(),
+        limit + 1
+    ))
+}
+
+/// Aggregate functions that collapse rows without a GROUP BY. A column with the same
+/// name only skips the row version, which is harmless.
+const AGGREGATES: &[&str] = &[
+    "COUNT",
+    "SUM",
+    "AVG",
+    "MIN",
+    "MAX",
+    "ARRAY_AGG",
+    "STRING_AGG",
+    "JSON_AGG",
+    "JSONB_AGG",
+    "JSON_OBJECT_AGG",
+    "JSONB_OBJECT_AGG",
+    "XMLAGG",
+    "BOOL_AND",
+    "BOOL_OR",
+    "EVERY",
+    "BIT_AND",
+    "BIT_OR",
+    "STDDEV",
+    "STDDEV_POP",
+    "STDDEV_SAMP",
+    "VARIANCE",
+    "VAR_POP",
+    "VAR_SAMP",
+    "PERCENTILE_CONT",
+    "PERCENTILE_DISC",
+    "MODE",
+];
+
+/// Name of the column `with_row_version` adds ahead of the query's own.
+pub const ROW_VERSION_COLUMN: &str = "fbench_row_version";
+
+/// `sql` with the PostgreSQL row version (`xmin`) selected as its first column, so a
+/// save can tell whether the row changed since it was read. None unless it is a
+/// single SELECT whose rows the extra column leaves as they are, i.e. one that doesn't
+/// group, aggregate, use window functions, remove duplicates or order by column number.
+pub fn with_row_version(sql: &str) -> Option<String> {
+    let statements = split_statements(sql);
+    let [statement] = statements.as_slice() else {
+        return None;
+    };
+    let words: Vec<String> = nested_code_words(statement)
+        .into_iter()
+        .filter(|(_, depth)| *depth == 0)
+        .map(|(word, _)| word)
+        .collect();
+    const REGROUPING: &[&str] = &[
+        "DISTINCT",
+        "GROUP",
+        "HAVING",
+        "UNION",
+        "INTERSECT",
+        "EXCEPT",
+        "OVER",
+        "WINDOW",
+    ];
+    if words.first().map(String::as_str) != Some("SELECT")
+        || words
+            .iter()
+            .any(|w| REGROUPING.contains(&w.as_str()) || AGGREGATES.contains(&w.as_str()))
+    {
+        return None;
+    }
+    // `ORDER BY 1` would sort by the added column
+    if let Some(order) = words.iter().position(|w| w == "ORDER") {
+        let ordinal = words[order + 1..]
+            .iter()
+            .take_while(|w| !["LIMIT", "OFFSET", "FETCH", "FOR"].contains(&w.as_str()))
+            .any(|w| w.bytes().all(|b| b.is_ascii_digit()));
+        if ordinal {
+            return None;
+        }
+    }
+    let statement = statement.trim_start();
+    // The statement starts with the keyword itself, a comment would come first as a word
+    let rest = statement.get(6..).filter(|_| {
+        statement
+            .get(..6)
+            .is_some_and(|s| s.eq_ignore_ascii_case("select"))
+    })?;
+    Some(format!("SELECT xmin::text AS {ROW_VERSION_COLUMN},{rest}"))
+}
+
+/// Table names that follow FROM, JOIN, UPDATE and INTO. Quoted parts of a name are
+/// kept as written without their quotes, the rest is lowercased like the database folds it.
+pub fn referenced_tables(sql: &str) -> Vec<String> {
+    let mut tables: Vec<String> = Vec::new();
+    let mut expect_table = false;
+
+    for token in sql.split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')')) {
+        if token.is_empty() {
+            continue;
+        }
+        if expect_table {
+            expect_table = false;
+            let name = token
+                .split('.')
+                .map(|part| {
+                    ['"', '`']
+                        .iter()
+                        .find_map(|&q| part.strip_prefix(q)?.strip_suffix(q))
+                        .map_or_else(|| part.to_lowercase(), str::to_string)
+                })
+                .collect::<Vec<_>>()
+                .join(".");
+            let is_identifier = name
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_');
+            if is_identifier
+                && !matches!(name.as_str(), "select" | "lateral" | "only")
+                && !tables.contains(&name)
+            {
+                tables.push(name);
+            }
+            continue;
+        }
+        expect_table = matches!(
+            token.to_uppercase().as_str(),
+            "FROM" | "JOIN" | "UPDATE" | "INTO"
+        );
+    }
+
+    tables
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_check_clause_handles_postgres_forms() {
+        let any = "((status)::text = ANY ((ARRAY['active'::character varying, 'archived'::character varying])::text[]))";
+        assert_eq!(
+            parse_check_clause(any, "status"),
+            vec![ValueRule::OneOf(vec!["active".into(), "archived".into()])]
+        );
+
+        let range = "((price >= (0)::numeric) AND (price <= (100)::numeric))";
+        let rules = parse_check_clause(range, "price");
+        assert_eq!(rules.len(), 2);
+        assert!(rules.iter().all(|r| r.check("50").is_ok()));
+        assert!(rules.iter().any(|r| r.check("150").is_err()));
+
+        assert_eq!(
+            parse_enum_values("enum('small','it''s large')"),
+            vec!["small".to_string(), "it's large".to_string()]
+        );
+    }
+
+    #[test]
+    fn only_bare_not_null_checks_are_skipped() {
+        assert!(is_not_null_check("id IS NOT NULL"));
+        assert!(is_not_null_check("((\"Email\" is not null))"));
+        assert!(!is_not_null_check("(price > 0) OR (note IS NOT NULL)"));
+        assert!(!is_not_null_check(
+            "(kind <> 'gift') OR (recipient IS NOT NULL)"
+        ));
+        assert!(!is_not_null_check("coalesce(a, b) IS NOT NULL"));
+    }
+
+    #[test]
+    fn modifies_data_ignores_literals_and_comments() {
+        assert!(modifies_data("-- cleanup\ndelete from users where id = 1"));
+        assert!(modifies_data("SELECT 1; UPDATE t SET a = 2"));
+        assert!(modifies_data(
+            "WITH gone AS (DELETE FROM t RETURNING *) SELECT * FROM gone"
+        ));
+        assert!(!modifies_data(
+            "SELECT 'delete' AS \"update\" FROM t /* insert */"
+        ));
+        assert!(!modifies_data("WITH x AS (SELECT 1) SELECT * FROM x"));
+    }
+
+    #[test]
+    fn modifies_data_recognizes_each_write_statement() {
+        let cases = [
+            ("INSERT INTO t VALUES (1)", true),
+            ("REPLACE INTO `t` VALUES (1)", true),
+            ("UPSERT INTO t VALUES (1)", true),
+            (
+                "MERGE INTO t USING u ON t.id = u.id WHEN MATCHED THEN DELETE",
+                true,
+            ),
+            ("TRUNCATE t", true),
+            ("EXPLAIN SELECT * FROM t", false),
+            ("SHOW TABLES", false),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(modifies_data(sql), expected, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn split_statements_respects_quotes_and_comments() {
+        let script = "SELECT 'a;b'; -- trailing; comment\n\
+            CREATE FUNCTION f() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql;\n\
+            /* only; a comment */";
+        assert_eq!(
+            split_statements(script),
+            vec![
+                "SELECT 'a;b'".to_string(),
+                "-- trailing; comment\nCREATE FUNCTION f() RETURNS int AS $$ SELECT 1; $$ LANGUAGE sql"
+                    .to_string(),
+            ]
+        );
+        assert_eq!(
+            split_statements("SELECT $1; SELECT 2"),
+            vec!["SELECT $1", "SELECT 2"]
+        );
+    }
+
+    #[test]
+    fn placeholders_skip_literals_and_casts() {
+        let sql = "SELECT a::text, arr[lo:hi] FROM t WHERE id = :id AND b = ':no' -- :no\n AND c = :id AND d = $2";
+        assert_eq!(
+            query_placeholders(sql, DatabaseType::PostgreSQL),
+            vec![Placeholder::Named("id".into()), Placeholder::Numbered(2)]
+        );
+        let (rewritten, params) = bind_placeholders(
+            sql,
+            DatabaseType::PostgreSQL,
+            &[
+                (Placeholder::Named("id".into()), ParamValue::Int(7)),
+                (Placeholder::Numbered(2), ParamValue::Text("x".into())),
+            ],
+        );
+        assert!(rewritten.ends_with("id = $3 AND b = ':no' -- :no\n AND c = $3 AND d = $2"));
+        assert_eq!(
+            params,
+            vec![
+                ParamValue::Null,
+                ParamValue::Text("x".into()),
+                ParamValue::Int(7)
+            ]
+        );
+
+        let (rewritten, params) = bind_placeholders(
+            "SELECT ? , :a, :a",
+            DatabaseType::MySQL,
+            &[(Placeholder::Named("a".into()), ParamValue::Bool(true))],
+        );
+        assert_eq!(rewritten, "SELECT ? , ?, ?");
+        assert_eq!(
+            params,
+            vec![
+                ParamValue::Null,
+                ParamValue::Bool(true),
+                ParamValue::Bool(true)
+            ]
+        );
+        assert_eq!(ParamValue::guess("007"), ParamValue::Text("007".into()));
+        assert_eq!(ParamValue::guess("-1.5"), ParamValue::Float(-1.5));
+    }
+
+    #[test]
+    fn statement_at_picks_statement_under_cursor() {
+        let script = "SELECT 1;\nSELECT 2; \n\nSELECT 3";
+        assert_eq!(statement_at(script, 3).as_deref(), Some("SELECT 1"));
+        // Right after the `;`, and later on the same line
+        assert_eq!(statement_at(script, 9).as_deref(), Some("SELECT 1"));
+        assert_eq!(statement_at(script, 20).as_deref(), Some("SELECT 2"));
+        // Blank line before the next statement
+        assert_eq!(statement_at(script, 21).as_deref(), Some("SELECT 3"));
+        assert_eq!(statement_at(script, 100).as_deref(), Some("SELECT 3"));
+        assert_eq!(statement_at("-- nothing", 3), None);
+        assert_eq!(statement_range_at(script, 20), Some(10..18));
+    }
+
+    #[test]
+    fn matching_paren_skips_literals() {
+        let script = "SELECT f(a, ')', (b)) -- (";
+        assert_eq!(matching_paren(script, 9), Some((8, 20)));
+        assert_eq!(matching_paren(script, 21), Some((20, 8)));
+        assert_eq!(matching_paren(script, 17), Some((17, 19)));
+        assert_eq!(matching_paren(script, 3), None);
+        assert_eq!(matching_paren(script, 26), None);
+    }
+
+    #[test]
+    fn alias_spans_color_each_name_consistently() {
+        let script = "WITH recent AS (SELECT * FROM orders o) SELECT r.id, o.r FROM recent r JOIN (SELECT 1) AS x ON x.r = r.id";
+        let names: Vec<(String, usize)> = super::super::alias_spans(script)
+            .into_iter()
+            .map(|(range, color)| (script[range].to_string(), color))
+            .collect();
+        let expected = [
+            ("recent", 0),
+            ("o", 1),
+            ("r", 2),
+            ("o", 1),
+            ("recent", 0),
+            ("r", 2),
+            ("x", 3),
+            ("x", 3),
+            ("r", 2),
+        ];
+        assert_eq!(
+            names,
+            expected.map(|(name, color)| (name.to_string(), color))
+        );
+    }
+
+    #[test]
+    fn parse_regions_handles_nesting() {
+        let script = "-- #region cleanup\nDELETE FROM a;\n-- #region inner\nDELETE FROM b;\n-- #endregion\n-- #endregion\n-- #region\nSELECT 1;";
+        let regions = parse_regions(script);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions[0].name, "cleanup");
+        assert_eq!(
+            regions[0].body,
+            "DELETE FROM a;\n-- #region inner\nDELETE FROM b;\n-- #endregion"
+        );
+        assert_eq!((regions[1].depth, regions[1].line), (1, 2));
+        assert_eq!(regions[1].body, "DELETE FROM b;");
+        assert_eq!(regions[2].name, "Region 3");
+        assert_eq!(regions[2].body, "SELECT 1;");
+    }
+
+    #[test]
+    fn referenced_tables_finds_each_table_once() {
+        assert_eq!(
+            referenced_tables(
+                "SELECT * FROM Public.\"Users\" u JOIN ORDERS o ON o.user_id = u.id \
+                 WHERE u.id IN (SELECT user_id FROM orders) AND EXISTS (SELECT 1 FROM `Log`)"
+            ),
+            vec![
+                "public.Users".to_string(),
+                "orders".to_string(),
+                "Log".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn row_version_is_selected_first() {
+        assert_eq!(
+            with_row_version("select * from users where id = 1").as_deref(),
+            Some("SELECT xmin::text AS fbench_row_version, * from users where id = 1")
+        );
+        assert_eq!(
+            with_row_version("SELECT\n  id, name\nFROM users;").as_deref(),
+            Some("SELECT xmin::text AS fbench_row_version,\n  id, name\nFROM users")
+        );
+        assert!(
+            with_row_version("SELECT id FROM t WHERE id IN (SELECT DISTINCT id FROM u)").is_some()
+        );
+        assert!(with_row_version("SELECT DISTINCT id FROM users").is_none());
+        assert!(with_row_version("SELECT id, count(*) FROM users GROUP BY id").is_none());
+        assert!(with_row_version("-- all\nSELECT * FROM users").is_none());
+        assert!(with_row_version("SELECT 1; SELECT 2").is_none());
+        assert!(with_row_version("UPDATE users SET name = 'a'").is_none());
+    }
+
+    #[test]
+    fn row_version_skips_aggregates_and_window_functions() {
+        assert!(with_row_version("SELECT count(*) FROM users").is_none());
+        assert!(with_row_version("SELECT max(id) FROM users WHERE active").is_none());
+        assert!(
+            with_row_version("SELECT id, row_number() OVER (ORDER BY id) FROM users").is_none()
+        );
+        // Aggregates inside a subquery don't change the outer rows
+        assert!(with_row_version(
+            "SELECT * FROM users WHERE id = (SELECT max(user_id) FROM orders)"
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn row_version_skips_order_by_column_number() {
+        assert!(with_row_version("SELECT name, id FROM users ORDER BY 1").is_none());
+        assert!(with_row_version("SELECT name, id FROM users ORDER BY name, 2 DESC").is_none());
+        assert!(with_row_version("SELECT * FROM users ORDER BY name LIMIT 10").is_some());
+    }
+
+    #[test]
+    fn auto_limit_only_unbounded_reads() {
+        assert_eq!(
+            auto_limit_sql("select * from t -- all\n;", 1000).as_deref(),
+            Some("select * from t -- all\nLIMIT 1001")
+        );
+        assert!(auto_limit_sql(
+            "WITH recent AS (SELECT * FROM t LIMIT 5) SELECT * FROM recent",
+            10
+        )
+        .is_some());
+        assert!(auto_limit_sql("SELECT * FROM t WHERE id IN (SELECT id FROM u)", 10).is_some());
+        assert!(auto_limit_sql("SELECT * FROM t LIMIT 5", 10).is_none());
+        assert!(auto_limit_sql("SELECT * FROM t FOR UPDATE", 10).is_none());
+        assert!(auto_limit_sql("SELECT 'limit' FROM t; SELECT 2", 10).is_none());
+        assert!(auto_limit_sql("DELETE FROM t", 10).is_none());
+    }
+}
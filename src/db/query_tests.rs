@@ -0,0 +1,140 @@
+use super::QueryResult;
+use serde::{Deserialize, Serialize};
+
+/// Rows a query test inserts before running its query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FixtureData {
+    /// Tuples as written after `VALUES`, e.g. `(1, 'a'), (2, 'b')`
+    Values(String),
+    /// CSV with a header row naming the columns; a `NULL` cell inserts NULL
+    Csv(String),
+}
+
+impl FixtureData {
+    pub fn text(&self) -> &str {
+        match self {
+            FixtureData::Values(text) | FixtureData::Csv(text) => text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestFixture {
+    pub table: String,
+    pub data: FixtureData,
+}
+
+/// A query test ready to run: the saved query's SQL, its fixtures and the expected rows
+/// as CSV. The fixtures and the query run in one transaction that is always rolled back.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryTestCase {
+    pub name: String,
+    pub sql: String,
+    pub fixtures: Vec<TestFixture>,
+    pub expected_csv: String,
+    /// Compare rows in order instead of as a set
+    pub ordered: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryTestOutcome {
+    pub name: String,
+    /// Why the test failed
+    pub result: Result<(), String>,
+}
+
+/// Header and records of CSV typed into a test. Unlike imported files, zero rows is fine.
+pub fn parse_test_csv(text: &str) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(text.trim().as_bytes());
+    let columns: Vec<String> = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(String::from)
+        .collect();
+    if columns.is_empty() {
+        return Err("The CSV needs a header row naming the columns".to_string());
+    }
+    let rows = reader
+        .records()
+        .map(|record| {
+            record
+                .map(|r| r.iter().map(String::from).collect())
+                .map_err(|e| e.to_string())
+        })
+        .collect::<Result<Vec<Vec<String>>, String>>()?;
+    Ok((columns, rows))
+}
+
+/// Compare a query result with the expected CSV. Rows are matched as a multiset unless
+/// `ordered`, so tests of queries without ORDER BY don't depend on the plan.
+pub fn check_test_result(
+    expected_csv: &str,
+    result: &QueryResult,
+    ordered: bool,
+) -> Result<(), String> {
+    let (columns, expected) = parse_test_csv(expected_csv)?;
+    // Backends report no columns for an empty result
+    if !result.rows.is_empty() && columns != result.columns {
+        return Err(format!(
+            "Expected columns ({}), got ({})",
+            columns.join(", "),
+            result.columns.join(", ")
+        ));
+    }
+
+    if ordered {
+        for (i, (want, got)) in expected.iter().zip(&result.rows).enumerate() {
+            if want != got {
+                return Err(format!(
+                    "Row {}: expected ({}), got ({})",
+                    i + 1,
+                    want.join(", "),
+                    got.join(", ")
+                ));
+            }
+        }
+        if expected.len() != result.rows.len() {
+            return Err(format!(
+                "Expected {} rows, got {}",
+                expected.len(),
+                result.rows.len()
+            ));
+        }
+        return Ok(());
+    }
+
+    let mut unexpected: Vec<&Vec<String>> = result.rows.iter().collect();
+    let missing: Vec<&Vec<String>> = expected
+        .iter()
+        .filter(|row| match unexpected.iter().position(|r| r == row) {
+            Some(i) => {
+                unexpected.swap_remove(i);
+                false
+            }
+            None => true,
+        })
+        .collect();
+    let mut problems = Vec::new();
+    if let Some(row) = missing.first() {
+        problems.push(format!(
+            "{} expected rows missing, e.g. ({})",
+            missing.len(),
+            row.join(", ")
+        ));
+    }
+    if let Some(row) = unexpected.first() {
+        problems.push(format!(
+            "{} unexpected rows, e.g. ({})",
+            unexpected.len(),
+            row.join(", ")
+        ));
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems.join("; "))
+    }
+}
@@ -0,0 +1,231 @@
+use super::{
+    fk_dependency_order, normalize_table_name, quote_identifier, DatabaseType, QueryResult,
+    SchemaInfo, TableInfo,
+};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Rows per INSERT statement, keeping statements a reasonable size for large tables
+const INSERT_CHUNK_ROWS: usize = 500;
+
+/// Query fetching the rows of `table` for a seed script, in primary key order so the
+/// same rows are picked on every run.
+pub fn seed_select_sql(db_type: DatabaseType, table: &TableInfo, limit: usize) -> String {
+    let keys: Vec<String> = table
+        .columns
+        .iter()
+        .filter(|c| c.is_primary_key)
+        .map(|c| quote_identifier(db_type, &c.name))
+        .collect();
+    let order_by = if keys.is_empty() {
+        String::new()
+    } else {
+        format!(" ORDER BY {}", keys.join(", "))
+    };
+    format!(
+        "SELECT * FROM {}{} LIMIT {}",
+        quote_identifier(db_type, &table.name),
+        order_by,
+        limit
+    )
+}
+
+/// INSERT script recreating `tables`, parents before the tables referencing them.
+/// Rows are sorted by primary key (by every column without one) so regenerating
+/// from the same data gives the same file. With `anonymize`, text outside keys and
+/// foreign keys becomes `<column>_<n>`, equal values keeping equal replacements.
+pub fn seed_script(
+    db_type: DatabaseType,
+    tables: &[(String, QueryResult)],
+    schema: &SchemaInfo,
+    anonymize: bool,
+) -> String {
+    let names: Vec<String> = tables.iter().map(|(name, _)| name.clone()).collect();
+    let (order, has_cycle) = fk_dependency_order(&names, schema);
+
+    let mut script = format!("-- Seed data for {}\n", names.join(", "));
+    if has_cycle {
+        script.push_str(
+            "-- The foreign keys between these tables form a cycle; load with constraint checks deferred\n",
+        );
+    }
+    for i in order {
+        let (name, result) = &tables[i];
+        let info = schema
+            .tables
+            .iter()
+            .find(|t| t.name == normalize_table_name(name));
+        script.push('\n');
+        let statements = table_insert_statements(db_type, name, result, info, anonymize);
+        if statements.is_empty() {
+            script.push_str(&format!("-- {}: no rows\n", name));
+        }
+        for sql in statements {
+            script.push_str(&sql);
+            script.push_str(";\n");
+        }
+    }
+    script
+}
+
+/// INSERT statements for the rows of `result`, sorted by primary key, followed on
+/// PostgreSQL by moving identity sequences past the inserted ids.
+pub fn table_insert_statements(
+    db_type: DatabaseType,
+    table: &str,
+    result: &QueryResult,
+    info: Option<&TableInfo>,
+    anonymize: bool,
+) -> Vec<String> {
+    let column_info = |name: &str| info.and_then(|t| t.columns.iter().find(|c| c.name == name));
+    // The database computes generated columns and rejects values for them
+    let columns: Vec<usize> = (0..result.columns.len())
+        .filter(|&i| !column_info(&result.columns[i]).is_some_and(|c| c.is_generated()))
+        .collect();
+    if result.rows.is_empty() || columns.is_empty() {
+        return Vec::new();
+    }
+
+    let key_columns: Vec<usize> = columns
+        .iter()
+        .copied()
+        .filter(|&i| column_info(&result.columns[i]).is_some_and(|c| c.is_primary_key))
+        .collect();
+    let sort_columns = if key_columns.is_empty() {
+        columns.clone()
+    } else {
+        key_columns
+    };
+    let mut rows: Vec<usize> = (0..result.rows.len()).collect();
+    rows.sort_by(|&a, &b| {
+        sort_columns
+            .iter()
+            .map(|&c| compare_values(&result.rows[a][c], &result.rows[b][c]))
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let foreign_key_columns: Vec<&str> = info
+        .map(|t| {
+            t.constraints
+                .iter()
+                .filter(|c| c.constraint_type == "FOREIGN KEY")
+                .flat_map(|c| c.columns.iter().map(String::as_str))
+                .collect()
+        })
+        .unwrap_or_default();
+    let anonymized: Vec<bool> = (0..result.columns.len())
+        .map(|i| {
+            anonymize
+                && !foreign_key_columns.contains(&result.columns[i].as_str())
+                && column_info(&result.columns[i]).map_or_else(
+                    || is_text_type(result.column_types.get(i).map_or("", String::as_str)),
+                    |c| !c.is_primary_key && is_text_type(&c.data_type),
+                )
+        })
+        .collect();
+    let mut replacements: Vec<HashMap<&str, String>> = vec![HashMap::new(); result.columns.len()];
+
+    let values: Vec<String> = rows
+        .iter()
+        .map(|&r| {
+            let cells: Vec<String> = columns
+                .iter()
+                .map(|&c| {
+                    let value = result.rows[r][c].as_str();
+                    if result.null_cells.contains(&(r, c)) {
+                        return "NULL".to_string();
+                    }
+                    if !anonymized[c] {
+                        return seed_literal(db_type, value);
+                    }
+                    let seen = &mut replacements[c];
+                    let n = seen.len() + 1;
+                    let fake = seen
+                        .entry(value)
+                        .or_insert_with(|| fake_value(&result.columns[c], value, n));
+                    seed_literal(db_type, fake)
+                })
+                .collect();
+            format!("  ({})", cells.join(", "))
+        })
+        .collect();
+
+    let quoted_table = quote_identifier(db_type, table);
+    let column_list: Vec<String> = columns
+        .iter()
+        .map(|&c| quote_identifier(db_type, &result.columns[c]))
+        .collect();
+    let identity: Vec<&str> = columns
+        .iter()
+        .map(|&c| result.columns[c].as_str())
+        .filter(|&c| column_info(c).is_some_and(|c| c.is_identity()))
+        .collect();
+    let overriding = if db_type == DatabaseType::PostgreSQL
+        && identity.iter().any(|&c| {
+            column_info(c).and_then(|c| c.identity_generation.as_deref()) == Some("ALWAYS")
+        }) {
+        " OVERRIDING SYSTEM VALUE"
+    } else {
+        ""
+    };
+    let mut statements: Vec<String> = values
+        .chunks(INSERT_CHUNK_ROWS)
+        .map(|chunk| {
+            format!(
+                "INSERT INTO {} ({}){} VALUES\n{}",
+                quoted_table,
+                column_list.join(", "),
+                overriding,
+                chunk.join(",\n")
+            )
+        })
+        .collect();
+    // Explicit ids don't advance PostgreSQL sequences, so later inserts would collide
+    if db_type == DatabaseType::PostgreSQL {
+        for column in identity {
+            let quoted = quote_identifier(db_type, column);
+            statements.push(format!(
+                "SELECT setval(pg_get_serial_sequence('{}', '{}'), (SELECT MAX({}) FROM {}))",
+                quoted_table.replace('\'', "''"),
+                column.replace('\'', "''"),
+                quoted,
+                quoted_table
+            ));
+        }
+    }
+    statements
+}
+
+/// Numbers compare by value so ids 9 and 10 keep their order.
+fn compare_values(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(x), Ok(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+fn is_text_type(data_type: &str) -> bool {
+    let lower = data_type.to_lowercase();
+    ["char", "text", "string", "clob"]
+        .iter()
+        .any(|t| lower.contains(t))
+}
+
+/// Stand-in for an anonymized value; addresses stay addresses.
+fn fake_value(column: &str, original: &str, n: usize) -> String {
+    if original.contains('@') {
+        format!("{}_{}@example.com", column, n)
+    } else {
+        format!("{}_{}", column, n)
+    }
+}
+
+fn seed_literal(db_type: DatabaseType, value: &str) -> String {
+    let escaped = value.replace('\'', "''");
+    match db_type {
+        // Backslash starts an escape in MySQL string literals
+        DatabaseType::MySQL => format!("'{}'", escaped.replace('\\', "\\\\")),
+        _ => format!("'{}'", escaped),
+    }
+}
@@ -0,0 +1,706 @@
+// Statement analysis - what a script does and the names it defines
+
+use super::query::{find_chars, literal_end, statement_spans};
+use super::split_statements;
+use std::ops::Range;
+
+/// A statement that destroys or rewrites data and objects wholesale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DestructiveKind {
+    Drop,
+    Truncate,
+    /// DELETE without a WHERE clause, emptying the table
+    DeleteAll,
+    /// UPDATE without a WHERE clause, changing every row
+    UpdateAll,
+    Alter,
+}
+
+impl DestructiveKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DestructiveKind::Drop => "DROP",
+            DestructiveKind::Truncate => "TRUNCATE",
+            DestructiveKind::DeleteAll => "DELETE without WHERE",
+            DestructiveKind::UpdateAll => "UPDATE without WHERE",
+            DestructiveKind::Alter => "ALTER",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DestructiveStatement {
+    pub kind: DestructiveKind,
+    pub statement: String,
+    /// Names of what it drops, empties or changes, unquoted
+    pub objects: Vec<String>,
+    /// Whether `objects` are tables, so their row counts apply
+    pub on_tables: bool,
+}
+
+/// Words between DROP or ALTER and the name of the object
+const OBJECT_TYPE_WORDS: &[&str] = &[
+    "TABLE",
+    "VIEW",
+    "MATERIALIZED",
+    "FOREIGN",
+    "TEMPORARY",
+    "TEMP",
+    "INDEX",
+    "UNIQUE",
+    "SCHEMA",
+    "DATABASE",
+    "SEQUENCE",
+    "FUNCTION",
+    "PROCEDURE",
+    "TRIGGER",
+    "TYPE",
+    "DOMAIN",
+    "EXTENSION",
+    "ROLE",
+    "USER",
+    "IF",
+    "EXISTS",
+    "CONCURRENTLY",
+    "ONLY",
+];
+
+/// The statements of `sql` that drop, truncate or alter objects, or delete or update
+/// every row of a table, in script order.
+pub fn destructive_statements(sql: &str) -> Vec<DestructiveStatement> {
+    split_statements(sql)
+        .into_iter()
+        .filter_map(classify_destructive)
+        .collect()
+}
+
+fn classify_destructive(statement: String) -> Option<DestructiveStatement> {
+    let words: Vec<String> = nested_code_words(&statement)
+        .into_iter()
+        .filter(|(_, depth)| *depth == 0)
+        .map(|(word, _)| word)
+        .collect();
+    // The main statement of a WITH query follows its parenthesized CTEs
+    let verb_at = match words.first()?.as_str() {
+        "WITH" => words.iter().position(|w| {
+            matches!(
+                w.as_str(),
+                "SELECT" | "INSERT" | "UPDATE" | "DELETE" | "MERGE"
+            )
+        })?,
+        _ => 0,
+    };
+    let has_where = words[verb_at..].iter().any(|w| w == "WHERE");
+    let kind = match words[verb_at].as_str() {
+        "DROP" => DestructiveKind::Drop,
+        "TRUNCATE" => DestructiveKind::Truncate,
+        "ALTER" => DestructiveKind::Alter,
+        "DELETE" if !has_where => DestructiveKind::DeleteAll,
+        "UPDATE" if !has_where => DestructiveKind::UpdateAll,
+        _ => return None,
+    };
+
+    let tokens = name_tokens(&statement);
+    let is = |i: usize, words: &[&str]| {
+        tokens
+            .get(i)
+            .is_some_and(|t| words.iter().any(|w| t.eq_ignore_ascii_case(w)))
+    };
+    let verb = &words[verb_at];
+    let mut depth = 0usize;
+    let mut i = tokens.iter().position(|t| {
+        match t.as_str() {
+            "(" => depth += 1,
+            ")" => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        depth == 0 && t.eq_ignore_ascii_case(verb)
+    })? + 1;
+    let mut on_tables = true;
+    match kind {
+        DestructiveKind::Drop | DestructiveKind::Alter => {
+            on_tables = false;
+            while is(i, OBJECT_TYPE_WORDS) {
+                on_tables |= is(i, &["TABLE"]);
+                i += 1;
+            }
+        }
+        DestructiveKind::Truncate => {
+            while is(i, &["TABLE", "ONLY"]) {
+                i += 1;
+            }
+        }
+        DestructiveKind::DeleteAll => {
+            while is(i, &["LOW_PRIORITY", "QUICK", "IGNORE"]) {
+                i += 1;
+            }
+            if is(i, &["FROM"]) {
+                i += 1;
+            }
+            if is(i, &["ONLY"]) {
+                i += 1;
+            }
+        }
+        DestructiveKind::UpdateAll => {
+            while is(i, &["LOW_PRIORITY", "IGNORE", "ONLY"]) {
+                i += 1;
+            }
+        }
+    }
+
+    // A list of names, except ALTER which changes one object
+    let mut objects = Vec::new();
+    while let Some(name) = tokens
+        .get(i)
+        .filter(|t| !matches!(t.as_str(), "," | "(" | ")" | ";"))
+    {
+        objects.push(name.clone());
+        if kind == DestructiveKind::Alter || !is(i + 1, &[","]) {
+            break;
+        }
+        i += 2;
+    }
+
+    Some(DestructiveStatement {
+        kind,
+        statement,
+        objects,
+        on_tables,
+    })
+}
+
+/// Words and names of a statement as written, with quotes removed, and its commas,
+/// semicolons and parentheses as tokens of their own. Comments and string literals
+/// are left out.
+fn name_tokens(statement: &str) -> Vec<String> {
+    let chars: Vec<char> = statement.chars().collect();
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let skip_to = match c {
+            '"' | '`' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&q| q == c)
+                    .map_or(chars.len(), |p| i + 1 + p);
+                token.extend(&chars[i + 1..end]);
+                i = end + 1;
+                continue;
+            }
+            '\'' => chars[i + 1..]
+                .iter()
+                .position(|&q| q == c)
+                .map_or(chars.len(), |p| i + p + 2),
+            '-' if chars.get(i + 1) == Some(&'-') => chars[i..]
+                .iter()
+                .position(|&n| n == '\n')
+                .map_or(chars.len(), |p| i + p + 1),
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                find_chars(&chars, i + 2, &['*', '/']).map_or(chars.len(), |p| p + 2)
+            }
+            c if c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')') => i + 1,
+            _ => {
+                token.push(c);
+                i += 1;
+                continue;
+            }
+        };
+        if !token.is_empty() {
+            tokens.push(std::mem::take(&mut token));
+        }
+        if matches!(c, ',' | ';' | '(' | ')') {
+            tokens.push(c.to_string());
+        }
+        i = skip_to;
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Whether any statement in `sql` writes rows: INSERT, UPDATE, DELETE, MERGE, REPLACE,
+/// UPSERT or TRUNCATE, including inside a WITH query.
+pub fn modifies_data(sql: &str) -> bool {
+    const WRITES: &[&str] = &[
+        "INSERT", "UPDATE", "DELETE", "MERGE", "REPLACE", "UPSERT", "TRUNCATE",
+    ];
+    split_statements(sql).iter().any(|statement| {
+        let words = code_words(statement);
+        match words.first().map(String::as_str) {
+            Some("WITH") => words.iter().any(|w| WRITES.contains(&w.as_str())),
+            Some(first) => WRITES.contains(&first),
+            None => false,
+        }
+    })
+}
+
+/// Whether every statement of `sql` only reads or writes rows. Anything else, such as
+/// DDL, TRUNCATE or CALL, commits the open transaction on MySQL and MariaDB.
+pub fn only_dml(sql: &str) -> bool {
+    const DML: &[&str] = &[
+        "SELECT", "WITH", "VALUES", "TABLE", "INSERT", "UPDATE", "DELETE", "REPLACE",
+    ];
+    let statements = split_statements(sql);
+    !statements.is_empty()
+        && statements.iter().all(|statement| {
+            code_words(statement)
+                .first()
+                .is_some_and(|word| DML.contains(&word.as_str()))
+        })
+}
+
+/// Uppercased bare words of a statement, skipping string literals, quoted identifiers
+/// and comments.
+fn code_words(statement: &str) -> Vec<String> {
+    nested_code_words(statement)
+        .into_iter()
+        .map(|(word, _)| word)
+        .collect()
+}
+
+/// `code_words` paired with how many parentheses each word is nested in.
+pub(super) fn nested_code_words(statement: &str) -> Vec<(String, usize)> {
+    let chars: Vec<char> = statement.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        let skip_to = match c {
+            '\'' | '"' | '`' => chars[i + 1..]
+                .iter()
+                .position(|&q| q == c)
+                .map_or(chars.len(), |p| i + p + 2),
+            '-' if chars.get(i + 1) == Some(&'-') => chars[i..]
+                .iter()
+                .position(|&n| n == '\n')
+                .map_or(chars.len(), |p| i + p + 1),
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                find_chars(&chars, i + 2, &['*', '/']).map_or(chars.len(), |p| p + 2)
+            }
+            _ => i,
+        };
+        if c == '_' || c.is_alphanumeric() {
+            word.push(c.to_ascii_uppercase());
+            i += 1;
+            continue;
+        }
+        if !word.is_empty() {
+            // The word ended at `c`, so a parenthesis there doesn't enclose it
+            let word_depth = match c {
+                '(' => depth - 1,
+                ')' => depth + 1,
+                _ => depth,
+            };
+            words.push((std::mem::take(&mut word), word_depth));
+        }
+        i = skip_to.max(i + 1);
+    }
+    if !word.is_empty() {
+        words.push((word, depth));
+    }
+    words
+}
+
+/// Words that end a table reference, so they are never taken for its alias
+const NOT_ALIASES: &[&str] = &[
+    "WHERE",
+    "JOIN",
+    "INNER",
+    "LEFT",
+    "RIGHT",
+    "FULL",
+    "OUTER",
+    "CROSS",
+    "NATURAL",
+    "ON",
+    "USING",
+    "GROUP",
+    "ORDER",
+    "HAVING",
+    "LIMIT",
+    "OFFSET",
+    "FETCH",
+    "UNION",
+    "EXCEPT",
+    "INTERSECT",
+    "WINDOW",
+    "FOR",
+    "SET",
+    "VALUES",
+    "RETURNING",
+    "LATERAL",
+    "TABLESAMPLE",
+    "SELECT",
+    "FROM",
+    "AS",
+    "WITH",
+    "AND",
+    "OR",
+    "NOT",
+    "ONLY",
+    "DEFAULT",
+    "QUALIFY",
+    "PREWHERE",
+    "FINAL",
+    "SAMPLE",
+    "STRAIGHT_JOIN",
+    "FORCE",
+    "IGNORE",
+    "USE",
+    "PARTITION",
+    "INTO",
+    "DO",
+    "CONFLICT",
+    "WHEN",
+    "THEN",
+    "ELSE",
+    "END",
+    "ASOF",
+    "GLOBAL",
+    "ANY",
+    "ALL",
+    "ARRAY",
+    "SETTINGS",
+    "FORMAT",
+    "SEMI",
+    "ANTI",
+    "MATERIALIZED",
+];
+
+#[derive(Debug)]
+enum NameToken {
+    /// A bare word, uppercased, or the text of a quoted identifier
+    Word {
+        text: String,
+        quoted: bool,
+        span: Range<usize>,
+    },
+    Punct(char),
+}
+
+impl NameToken {
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self, NameToken::Word { text, quoted: false, .. } if text == word)
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        matches!(self, NameToken::Punct(p) if *p == c)
+    }
+
+    /// The name of an identifier that can't be a keyword
+    fn name(&self) -> Option<&str> {
+        match self {
+            NameToken::Word { text, quoted, .. }
+                if *quoted || !NOT_ALIASES.contains(&text.as_str()) =>
+            {
+                Some(text)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Char ranges of the table aliases and CTE names in `script`, each with a color
+/// index. A name keeps its index throughout its statement; indexes follow the order
+/// names are defined in, so neighbouring names differ.
+pub fn alias_spans(script: &str) -> Vec<(Range<usize>, usize)> {
+    let chars: Vec<char> = script.chars().collect();
+    let mut spans = Vec::new();
+    for (range, _) in statement_spans(script) {
+        let tokens = statement_name_tokens(&chars, range);
+        let names = defined_names(&tokens);
+        for (i, token) in tokens.iter().enumerate() {
+            let NameToken::Word {
+                text,
+                quoted: false,
+                span,
+            } = token
+            else {
+                continue;
+            };
+            // A column of the same name, as in `alias.name`, isn't the alias
+            if i > 0 && tokens[i - 1].is_punct('.') {
+                continue;
+            }
+            if let Some(color) = names.iter().position(|n| n == text) {
+                spans.push((span.clone(), color));
+            }
+        }
+    }
+    spans
+}
+
+/// Words and punctuation of the statement at `range` of `chars`, without literals and
+/// comments. Quoted identifiers are kept as names.
+fn statement_name_tokens(chars: &[char], range: Range<usize>) -> Vec<NameToken> {
+    let mut tokens = Vec::new();
+    let mut i = range.start;
+    while i < range.end {
+        let c = chars[i];
+        if c == '_' || c.is_alphabetic() {
+            let start = i;
+            while i < range.end
+                && (chars[i] == '_' || chars[i] == '$' || chars[i].is_alphanumeric())
+            {
+                i += 1;
+            }
+            tokens.push(NameToken::Word {
+                text: chars[start..i].iter().collect::<String>().to_uppercase(),
+                quoted: false,
+                span: start..i,
+            });
+            continue;
+        }
+        if let Some(end) = literal_end(chars, i) {
+            if c == '"' || c == '`' {
+                let inner = chars[i + 1..end.saturating_sub(1).max(i + 1)]
+                    .iter()
+                    .collect::<String>();
+                tokens.push(NameToken::Word {
+                    text: inner.to_uppercase(),
+                    quoted: true,
+                    span: i..end,
+                });
+            }
+            i = end;
+            continue;
+        }
+        if c.is_ascii_digit() {
+            // Keeps `1a` from reading as a word
+            while i < range.end && chars[i].is_alphanumeric() {
+                i += 1;
+            }
+            continue;
+        }
+        if !c.is_whitespace() {
+            tokens.push(NameToken::Punct(c));
+        }
+        i += 1;
+    }
+    tokens
+}
+
+/// Index just past the parenthesized group opening at `open`.
+fn skip_parens(tokens: &[NameToken], open: usize) -> usize {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_punct('(') {
+            depth += 1;
+        } else if token.is_punct(')') {
+            depth -= 1;
+            if depth == 0 {
+                return i + 1;
+            }
+        }
+    }
+    tokens.len()
+}
+
+/// Uppercased CTE names and table aliases defined in a statement, in order.
+fn defined_names(tokens: &[NameToken]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut define = |name: &str| {
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    };
+    let at = |i: usize| tokens.get(i);
+
+    for i in 0..tokens.len() {
+        // `name [(columns)] AS [NOT] [MATERIALIZED] (` defines a CTE
+        if let Some(name) = tokens[i].name() {
+            let mut j = i + 1;
+            if at(j).is_some_and(|t| t.is_punct('('))
+                && i > 0
+                && (tokens[i - 1].is_word("WITH")
+                    || tokens[i - 1].is_word("RECURSIVE")
+                    || tokens[i - 1].is_punct(','))
+            {
+                j = skip_parens(tokens, j);
+            }
+            if at(j).is_some_and(|t| t.is_word("AS")) {
+                j += 1;
+                while at(j).is_some_and(|t| t.is_word("NOT") || t.is_word("MATERIALIZED")) {
+                    j += 1;
+                }
+                if at(j).is_some_and(|t| t.is_punct('(')) {
+                    define(name);
+                    continue;
+                }
+            }
+        }
+
+        // A table reference follows FROM, JOIN and UPDATE, and commas in a FROM list
+        if !(tokens[i].is_word("FROM") || tokens[i].is_word("JOIN") || tokens[i].is_word("UPDATE"))
+        {
+            continue;
+        }
+        let mut j = i + 1;
+        loop {
+            while at(j).is_some_and(|t| t.is_word("LATERAL") || t.is_word("ONLY")) {
+                j += 1;
+            }
+            if at(j).is_some_and(|t| t.is_punct('(')) {
+                j = skip_parens(tokens, j);
+            } else if at(j).and_then(NameToken::name).is_some() {
+                j += 1;
+                while at(j).is_some_and(|t| t.is_punct('.'))
+                    && at(j + 1).and_then(NameToken::name).is_some()
+                {
+                    j += 2;
+                }
+                // Table functions such as generate_series(...)
+                if at(j).is_some_and(|t| t.is_punct('(')) {
+                    j = skip_parens(tokens, j);
+                }
+            } else {
+                break;
+            }
+            if at(j).is_some_and(|t| t.is_word("AS")) {
+                j += 1;
+            }
+            if let Some(alias) = at(j).and_then(NameToken::name) {
+                define(alias);
+                j += 1;
+            }
+            if !(tokens[i].is_word("FROM") && at(j).is_some_and(|t| t.is_punct(','))) {
+                break;
+            }
+            j += 1;
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DestructiveKind::*;
+
+    #[test]
+    fn destructive_statements_list_dropped_and_emptied_objects() {
+        // Kind, objects and whether they are tables, of the first destructive statement
+        type Expected = Option<(DestructiveKind, &'static [&'static str], bool)>;
+        let cases: [(&str, Expected); 13] = [
+            (
+                r#"DROP TABLE IF EXISTS public.users, "Audit Log" CASCADE"#,
+                Some((Drop, &["public.users", "Audit Log"], true)),
+            ),
+            (
+                "drop materialized view concurrently stats",
+                Some((Drop, &["stats"], false)),
+            ),
+            (
+                "DROP INDEX idx_users_email",
+                Some((Drop, &["idx_users_email"], false)),
+            ),
+            (
+                "TRUNCATE TABLE ONLY orders, items",
+                Some((Truncate, &["orders", "items"], true)),
+            ),
+            (
+                "DELETE LOW_PRIORITY QUICK FROM `orders`",
+                Some((DeleteAll, &["orders"], true)),
+            ),
+            (
+                "DELETE FROM ONLY sessions -- where expired\n",
+                Some((DeleteAll, &["sessions"], true)),
+            ),
+            (
+                "UPDATE IGNORE `users` SET active = 0",
+                Some((UpdateAll, &["users"], true)),
+            ),
+            (
+                "ALTER TABLE accounts ADD COLUMN note text",
+                Some((Alter, &["accounts"], true)),
+            ),
+            (
+                "WITH old AS (SELECT id FROM t WHERE a < 1) DELETE FROM logs",
+                Some((DeleteAll, &["logs"], true)),
+            ),
+            // A WHERE inside a subquery doesn't limit the UPDATE
+            (
+                "UPDATE t SET a = (SELECT 1 WHERE true)",
+                Some((UpdateAll, &["t"], true)),
+            ),
+            ("DELETE FROM t WHERE id = 1", None),
+            ("SELECT 'DROP TABLE x' -- DROP TABLE y", None),
+            ("INSERT INTO t SELECT * FROM u", None),
+        ];
+        for (sql, expected) in cases {
+            let found = destructive_statements(sql);
+            let found = found
+                .iter()
+                .map(|d| {
+                    (
+                        d.kind,
+                        d.objects.iter().map(String::as_str).collect(),
+                        d.on_tables,
+                    )
+                })
+                .next();
+            let expected =
+                expected.map(|(kind, objects, on_tables)| (kind, objects.to_vec(), on_tables));
+            assert_eq!(found, expected, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn alias_spans_color_each_alias_with_its_table() {
+        let cases: [(&str, &[(&str, usize)]); 3] = [
+            (
+                "SELECT u.name FROM users u JOIN orders AS o ON o.user_id = u.id",
+                &[("u", 0), ("u", 0), ("o", 1), ("o", 1), ("u", 0)],
+            ),
+            (
+                "WITH recent AS (SELECT 1) SELECT r.x FROM recent r",
+                &[("recent", 0), ("r", 1), ("recent", 0), ("r", 1)],
+            ),
+            // ClickHouse clauses end the table reference
+            (
+                "SELECT * FROM events e PREWHERE e.day = today() FINAL",
+                &[("e", 0), ("e", 0)],
+            ),
+        ];
+        for (sql, expected) in cases {
+            let chars: Vec<char> = sql.chars().collect();
+            let found: Vec<(String, usize)> = alias_spans(sql)
+                .into_iter()
+                .map(|(range, color)| (chars[range].iter().collect(), color))
+                .collect();
+            let expected: Vec<(String, usize)> = expected
+                .iter()
+                .map(|&(name, color)| (name.to_string(), color))
+                .collect();
+            assert_eq!(found, expected, "{}", sql);
+        }
+    }
+
+    #[test]
+    fn only_dml_rejects_statements_that_commit() {
+        let cases = [
+            ("SELECT * FROM t", true),
+            ("(SELECT 1) UNION (SELECT 2)", true),
+            ("WITH x AS (SELECT 1) SELECT * FROM x", true),
+            ("INSERT INTO t VALUES (1); DELETE FROM t WHERE a = 1", true),
+            ("/* CREATE */ UPDATE t SET a = 'DROP'", true),
+            ("CREATE TABLE t (a INT)", false),
+            ("SELECT 1; TRUNCATE t", false),
+            ("CALL refresh()", false),
+            ("", false),
+        ];
+        for (sql, expected) in cases {
+            assert_eq!(only_dml(sql), expected, "{}", sql);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use russh::client::{self, Handle};
+use russh::keys::{self, HashAlg, PrivateKeyWithHashAlg, PublicKey};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use super::{ConnectionConfig, SshTunnel, UnknownHostKey};
+
+/// Bastions often drop idle sessions; keepalives hold the tunnel open between queries
+const KEEPALIVE_SECS: u64 = 30;
+
+/// Local end of an SSH tunnel to the database server. Forwarding stops when it is dropped.
+pub(super) struct ForwardedPort {
+    accept_task: JoinHandle<()>,
+}
+
+impl Drop for ForwardedPort {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+/// Why a tunnel could not be opened.
+pub(super) enum TunnelError {
+    /// The bastion isn't in known_hosts; the user has to trust its key first
+    UnknownHostKey(UnknownHostKey),
+    Failed(String),
+}
+
+impl From<String> for TunnelError {
+    fn from(message: String) -> Self {
+        TunnelError::Failed(message)
+    }
+}
+
+/// Checks the bastion's host key against ~/.ssh/known_hosts.
+struct KnownHostsCheck {
+    host: String,
+    port: u16,
+    /// The key of a host not in known_hosts, kept to show its fingerprint
+    unknown_key: Arc<Mutex<Option<PublicKey>>>,
+}
+
+impl client::Handler for KnownHostsCheck {
+    type Error = russh::Error;
+
+    /// Like OpenSSH's `ask`: an unknown host is refused until the user has compared its
+    /// fingerprint and trusted it through `trust_host_key`, a changed key is refused.
+    async fn check_server_key(&mut self, key: &PublicKey) -> Result<bool, Self::Error> {
+        match keys::check_known_hosts(&self.host, self.port, key) {
+            Ok(true) => Ok(true),
+            Ok(false) => {
+                *self.unknown_key.lock().unwrap() = Some(key.clone());
+                Ok(false)
+            }
+            Err(e) => {
+                tracing::error!("Host key check failed for {}: {}", self.host, e);
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// Add the bastion's key to ~/.ssh/known_hosts once the user has confirmed its fingerprint.
+pub fn trust_host_key(unknown: &UnknownHostKey) -> Result<(), String> {
+    let key = PublicKey::from_openssh(&unknown.key).map_err(|e| e.to_string())?;
+    keys::known_hosts::learn_known_hosts(&unknown.host, unknown.port, &key)
+        .map_err(|e| format!("Could not add {} to known_hosts: {}", unknown.host, e))
+}
+
+/// Open the tunnel when `config` asks for one and point the config at its local end,
+/// so the driver connects to a local port that is forwarded to the database server.
+pub(super) async fn forward(
+    mut config: ConnectionConfig,
+) -> Result<(ConnectionConfig, Option<ForwardedPort>), TunnelError> {
+    let Some(tunnel) = config
+        .ssh_tunnel
+        .clone()
+        .filter(|_| config.db_type.has_server())
+    else {
+        return Ok((config, None));
+    };
+
+    let session = Arc::new(open_session(&tunnel).await?);
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .map_err(|e| e.to_string())?;
+    let local_port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let host = config.host.clone();
+    let port = config.port;
+    let accept_task = tokio::spawn(async move {
+        while let Ok((socket, peer)) = listener.accept().await {
+            let session = session.clone();
+            let host = host.clone();
+            tokio::spawn(async move {
+                if let Err(e) = pipe(&session, socket, peer, &host, port).await {
+                    tracing::warn!("SSH tunnel to {}:{} failed: {}", host, port, e);
+                }
+            });
+        }
+    });
+
+    config.host = Ipv4Addr::LOCALHOST.to_string();
+    config.port = local_port;
+    Ok((config, Some(ForwardedPort { accept_task })))
+}
+
+async fn open_session(tunnel: &SshTunnel) -> Result<Handle<KnownHostsCheck>, TunnelError> {
+    let ssh_config = Arc::new(client::Config {
+        keepalive_interval: Some(Duration::from_secs(KEEPALIVE_SECS)),
+        ..Default::default()
+    });
+    let unknown_key = Arc::new(Mutex::new(None));
+    let handler = KnownHostsCheck {
+        host: tunnel.host.clone(),
+        port: tunnel.port,
+        unknown_key: unknown_key.clone(),
+    };
+    let connected = client::connect(ssh_config, (tunnel.host.as_str(), tunnel.port), handler).await;
+    let unknown = unknown_key.lock().unwrap().take();
+    if let Some(key) = unknown {
+        return Err(TunnelError::UnknownHostKey(UnknownHostKey {
+            host: tunnel.host.clone(),
+            port: tunnel.port,
+            fingerprint: format!(
+                "{} {}",
+                key.algorithm().as_str(),
+                key.fingerprint(HashAlg::Sha256)
+            ),
+            key: key.to_openssh().map_err(|e| e.to_string())?,
+        }));
+    }
+    let mut session =
+        connected.map_err(|e| format!("Could not reach {}:{}: {}", tunnel.host, tunnel.port, e))?;
+
+    let authenticated = if tunnel.key_file.trim().is_empty() {
+        authenticate_with_agent(&mut session, &tunnel.user).await?
+    } else {
+        let key = keys::load_secret_key(tunnel.key_file.trim(), None).map_err(|e| match e {
+            keys::Error::KeyIsEncrypted => "The key file is protected by a passphrase; add the \
+                 key to your SSH agent with ssh-add and leave the key file empty"
+                .to_string(),
+            e => format!("Could not read the key file: {}", e),
+        })?;
+        let hash = session
+            .best_supported_rsa_hash()
+            .await
+            .map_err(|e| e.to_string())?
+            .flatten();
+        session
+            .authenticate_publickey(
+                &tunnel.user,
+                PrivateKeyWithHashAlg::new(Arc::new(key), hash),
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .success()
+    };
+    if !authenticated {
+        return Err(format!("{}@{} did not accept the key", tunnel.user, tunnel.host).into());
+    }
+    Ok(session)
+}
+
+/// Try each identity the running SSH agent holds.
+#[cfg(unix)]
+async fn authenticate_with_agent(
+    session: &mut Handle<KnownHostsCheck>,
+    user: &str,
+) -> Result<bool, String> {
+    let mut agent = keys::agent::client::AgentClient::connect_env()
+        .await
+        .map_err(|e| format!("No SSH agent available ({}); choose a key file", e))?;
+    let identities = agent
+        .request_identities()
+        .await
+        .map_err(|e| e.to_string())?;
+    for key in identities {
+        let result = session
+            .authenticate_publickey_with(user, key, None, &mut agent)
+            .await
+            .map_err(|e| e.to_string())?;
+        if result.success() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(not(unix))]
+async fn authenticate_with_agent(
+    _session: &mut Handle<KnownHostsCheck>,
+    _user: &str,
+) -> Result<bool, String> {
+    Err("SSH agent authentication is not available on this platform; choose a key file".into())
+}
+
+/// Carry one local connection to the database server over the SSH session.
+async fn pipe(
+    session: &Handle<KnownHostsCheck>,
+    mut socket: TcpStream,
+    peer: SocketAddr,
+    host: &str,
+    port: u16,
+) -> Result<(), String> {
+    let channel = session
+        .channel_open_direct_tcpip(host, port as u32, peer.ip().to_string(), peer.port() as u32)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut stream = channel.into_stream();
+    tokio::io::copy_bidirectional(&mut socket, &mut stream)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
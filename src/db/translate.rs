@@ -0,0 +1,971 @@
+//! Rule-based conversion of SQL between the MySQL and PostgreSQL dialects, used
+//! when no LLM is available or as the baseline its answer is compared against.
+
+use super::SqlDialect;
+
+/// MySQL's largest LIMIT, standing in for "no limit" before an OFFSET.
+const MYSQL_NO_LIMIT: &str = "18446744073709551615";
+
+/// SQL rewritten for another dialect, with the constructs that still need a look.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Translation {
+    pub sql: String,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Family {
+    Postgres,
+    MySql,
+}
+
+impl Family {
+    /// Everything that is not MySQL-like is read with the standard quoting rules.
+    fn of(dialect: SqlDialect) -> Self {
+        match dialect {
+            SqlDialect::MySQL | SqlDialect::MariaDB => Family::MySql,
+            _ => Family::Postgres,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    /// Quoted identifier, unescaped
+    Ident(String),
+    /// String literal, unescaped
+    Str(String),
+    Number(String),
+    Space(String),
+    Comment(String),
+    Symbol(String),
+}
+
+impl Token {
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self, Token::Symbol(s) if s == symbol)
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self, Token::Word(w) if w.eq_ignore_ascii_case(word))
+    }
+
+    fn is_blank(&self) -> bool {
+        matches!(self, Token::Space(_) | Token::Comment(_))
+    }
+}
+
+/// Convert `sql` written for `from` into `to`, handling LIMIT/OFFSET forms, identifier
+/// and string quoting, casts and the common function differences.
+pub fn translate_sql(sql: &str, from: SqlDialect, to: SqlDialect) -> Translation {
+    let (from, to) = (Family::of(from), Family::of(to));
+    if from == to {
+        return Translation {
+            sql: sql.to_string(),
+            notes: Vec::new(),
+        };
+    }
+    let tokens = tokenize(sql, from);
+    let mut translator = Translator {
+        to,
+        notes: Vec::new(),
+    };
+    let rewritten = translator.rewrite(&tokens);
+    Translation {
+        sql: render(&rewritten, to),
+        notes: translator.notes,
+    }
+}
+
+fn tokenize(sql: &str, from: Family) -> Vec<Token> {
+    let chars: Vec<char> = sql.chars().collect();
+    let backslash_escapes = from == Family::MySql;
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let start = i;
+        let token = if c.is_whitespace() {
+            while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+                i += 1;
+            }
+            Token::Space(chars[start..i].iter().collect())
+        } else if (c == '-' && next == Some('-')) || (c == '#' && from == Family::MySql) {
+            while chars.get(i).is_some_and(|&c| c != '\n') {
+                i += 1;
+            }
+            Token::Comment(chars[start..i].iter().collect())
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i = (i + 2).min(chars.len());
+            Token::Comment(chars[start..i].iter().collect())
+        } else if c == '\'' {
+            let (value, end) = read_quoted(&chars, i, '\'', backslash_escapes);
+            i = end;
+            Token::Str(value)
+        } else if (c == 'E' || c == 'e') && next == Some('\'') && from == Family::Postgres {
+            let (value, end) = read_quoted(&chars, i + 1, '\'', true);
+            i = end;
+            Token::Str(value)
+        } else if c == '"' {
+            // MySQL reads double quotes as strings unless ANSI_QUOTES is set
+            let (value, end) = read_quoted(&chars, i, '"', backslash_escapes);
+            i = end;
+            if from == Family::MySql {
+                Token::Str(value)
+            } else {
+                Token::Ident(value)
+            }
+        } else if c == '`' {
+            let (value, end) = read_quoted(&chars, i, '`', false);
+            i = end;
+            Token::Ident(value)
+        } else if c == '$' && next.is_some_and(|c| c.is_ascii_digit()) {
+            i += 1;
+            while chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+                i += 1;
+            }
+            Token::Word(chars[start..i].iter().collect())
+        } else if let Some(tag) = (c == '$').then(|| dollar_tag(&chars, i)).flatten() {
+            let body = i + tag.len();
+            let close = chars[body..]
+                .windows(tag.len())
+                .position(|w| w == tag.as_slice())
+                .map(|p| body + p);
+            match close {
+                Some(close) => {
+                    i = close + tag.len();
+                    Token::Str(chars[body..close].iter().collect())
+                }
+                None => {
+                    i = chars.len();
+                    Token::Str(chars[body..].iter().collect())
+                }
+            }
+        } else if c.is_ascii_digit() || (c == '.' && next.is_some_and(|c| c.is_ascii_digit())) {
+            while chars
+                .get(i)
+                .is_some_and(|&c| c.is_ascii_alphanumeric() || c == '.')
+            {
+                i += 1;
+            }
+            Token::Number(chars[start..i].iter().collect())
+        } else if c.is_alphabetic() || c == '_' {
+            while chars
+                .get(i)
+                .is_some_and(|&c| c.is_alphanumeric() || c == '_' || c == '$')
+            {
+                i += 1;
+            }
+            Token::Word(chars[start..i].iter().collect())
+        } else {
+            let pair: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            if matches!(pair.as_str(), "::" | "||" | "<=" | ">=" | "<>" | "!=") {
+                i += 2;
+                Token::Symbol(pair)
+            } else {
+                i += 1;
+                Token::Symbol(c.to_string())
+            }
+        };
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Value of the quoted text opening at `start`, and the index just past it.
+fn read_quoted(
+    chars: &[char],
+    start: usize,
+    quote: char,
+    backslash_escapes: bool,
+) -> (String, usize) {
+    let mut value = String::new();
+    let mut i = start + 1;
+    while let Some(&c) = chars.get(i) {
+        if c == quote {
+            // A doubled quote is an escaped quote
+            if chars.get(i + 1) == Some(&quote) {
+                value.push(quote);
+                i += 2;
+                continue;
+            }
+            return (value, i + 1);
+        }
+        if c == '\\' && backslash_escapes {
+            match chars.get(i + 1) {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some('0') => value.push('\0'),
+                Some('b') => value.push('\u{8}'),
+                Some('Z') => value.push('\u{1a}'),
+                // LIKE wildcards keep their backslash
+                Some(&escaped @ ('%' | '_')) => {
+                    value.push('\\');
+                    value.push(escaped);
+                }
+                Some(&escaped) => value.push(escaped),
+                None => {}
+            }
+            i += 2;
+            continue;
+        }
+        value.push(c);
+        i += 1;
+    }
+    (value, chars.len())
+}
+
+/// The `$tag$` opening a dollar-quoted string at `start`, if there is one.
+fn dollar_tag(chars: &[char], start: usize) -> Option<Vec<char>> {
+    let mut end = start + 1;
+    while let Some(&c) = chars.get(end) {
+        if c == '$' {
+            return Some(chars[start..=end].to_vec());
+        }
+        if !(c == '_' || c.is_alphabetic() || (end > start + 1 && c.is_ascii_digit())) {
+            return None;
+        }
+        end += 1;
+    }
+    None
+}
+
+fn render(tokens: &[Token], to: Family) -> String {
+    let mut sql = String::new();
+    for token in tokens {
+        match token {
+            Token::Word(text) | Token::Number(text) | Token::Space(text) | Token::Symbol(text) => {
+                sql.push_str(text)
+            }
+            Token::Ident(name) => match to {
+                Family::Postgres => sql.push_str(&format!("\"{}\"", name.replace('"', "\"\""))),
+                Family::MySql => sql.push_str(&format!("`{}`", name.replace('`', "``"))),
+            },
+            Token::Str(value) => {
+                let escaped = value.replace('\'', "''");
+                match to {
+                    Family::Postgres => sql.push_str(&format!("'{}'", escaped)),
+                    Family::MySql => {
+                        sql.push('\'');
+                        for c in escaped.chars() {
+                            match c {
+                                '\\' => sql.push_str("\\\\"),
+                                '\n' => sql.push_str("\\n"),
+                                '\r' => sql.push_str("\\r"),
+                                '\t' => sql.push_str("\\t"),
+                                '\0' => sql.push_str("\\0"),
+                                c => sql.push(c),
+                            }
+                        }
+                        sql.push('\'');
+                    }
+                }
+            }
+            Token::Comment(text) => {
+                let text = match text.strip_prefix('#') {
+                    Some(rest) => format!("--{}", rest),
+                    None => text.clone(),
+                };
+                // MySQL only reads `--` as a comment when whitespace follows
+                match text.strip_prefix("--") {
+                    Some(rest)
+                        if to == Family::MySql && !rest.is_empty() && !rest.starts_with(' ') =>
+                    {
+                        sql.push_str("-- ");
+                        sql.push_str(rest);
+                    }
+                    _ => sql.push_str(&text),
+                }
+            }
+        }
+    }
+    sql
+}
+
+/// Index of the `)` closing the `(` at `open`.
+fn matching_paren(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_symbol("(") {
+            depth += 1;
+        } else if token.is_symbol(")") {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// Split a call's arguments on the commas outside nested parentheses.
+fn split_args(tokens: &[Token]) -> Vec<&[Token]> {
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_symbol("(") {
+            depth += 1;
+        } else if token.is_symbol(")") {
+            depth -= 1;
+        } else if token.is_symbol(",") && depth == 0 {
+            args.push(&tokens[start..i]);
+            start = i + 1;
+        }
+    }
+    args.push(&tokens[start..]);
+    args
+}
+
+/// Position of the first top-level word among `words` in `tokens`.
+fn find_top_level(tokens: &[Token], words: &[&str]) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        if token.is_symbol("(") {
+            depth += 1;
+        } else if token.is_symbol(")") {
+            depth -= 1;
+        } else if depth == 0 && words.iter().any(|w| token.is_word(w)) {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn trim(tokens: &[Token]) -> &[Token] {
+    let start = tokens
+        .iter()
+        .position(|t| !t.is_blank())
+        .unwrap_or(tokens.len());
+    let end = tokens
+        .iter()
+        .rposition(|t| !t.is_blank())
+        .map_or(start, |p| p + 1);
+    &tokens[start..end]
+}
+
+fn next_code(tokens: &[Token], from: usize) -> usize {
+    (from..tokens.len())
+        .find(|&i| !tokens[i].is_blank())
+        .unwrap_or(tokens.len())
+}
+
+fn space() -> Token {
+    Token::Space(" ".to_string())
+}
+
+fn word(text: &str) -> Token {
+    Token::Word(text.to_string())
+}
+
+fn symbol(text: &str) -> Token {
+    Token::Symbol(text.to_string())
+}
+
+struct Translator {
+    to: Family,
+    notes: Vec<String>,
+}
+
+impl Translator {
+    fn note(&mut self, note: &str) {
+        if !self.notes.iter().any(|n| n == note) {
+            self.notes.push(note.to_string());
+        }
+    }
+
+    fn rewrite(&mut self, tokens: &[Token]) -> Vec<Token> {
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = &tokens[i];
+            if let Token::Word(name) = token {
+                let upper = name.to_ascii_uppercase();
+                if tokens.get(i + 1).is_some_and(|t| t.is_symbol("(")) {
+                    if let Some(close) = matching_paren(tokens, i + 1) {
+                        if let Some(call) = self.rewrite_call(&upper, &tokens[i + 2..close]) {
+                            out.extend(call);
+                            i = close + 1;
+                            continue;
+                        }
+                    }
+                }
+                if matches!(upper.as_str(), "LIMIT" | "OFFSET" | "FETCH") {
+                    if let Some((clauses, next)) = self.rewrite_limit(tokens, i) {
+                        out.extend(clauses);
+                        i = next;
+                        continue;
+                    }
+                }
+                out.push(self.rewrite_word(name, &upper));
+            } else if token.is_symbol("::") && self.to == Family::MySql {
+                match self.rewrite_cast_operator(&mut out, tokens, i + 1) {
+                    Some(next) => {
+                        i = next;
+                        continue;
+                    }
+                    None => out.push(token.clone()),
+                }
+            } else {
+                if token.is_symbol("||") && self.to == Family::MySql {
+                    self.note(
+                        "`||` is logical OR in MySQL unless PIPES_AS_CONCAT is set; use CONCAT()",
+                    );
+                }
+                out.push(token.clone());
+            }
+            i += 1;
+        }
+        out
+    }
+
+    fn rewrite_word(&mut self, name: &str, upper: &str) -> Token {
+        match (self.to, upper) {
+            (Family::MySql, "ILIKE") => {
+                self.note(
+                    "ILIKE became LIKE, which ignores case only under a case-insensitive collation",
+                );
+                return word("LIKE");
+            }
+            (Family::MySql, "RETURNING") => self.note("MySQL has no RETURNING clause"),
+            (Family::MySql, "CONFLICT") => {
+                self.note("Rewrite ON CONFLICT as ON DUPLICATE KEY UPDATE")
+            }
+            (Family::MySql, "NULLS") => {
+                self.note("MySQL has no NULLS FIRST/LAST; order by `column IS NULL` first")
+            }
+            (Family::Postgres, "DUPLICATE") => {
+                self.note("Rewrite ON DUPLICATE KEY UPDATE as ON CONFLICT (...) DO UPDATE")
+            }
+            _ => {}
+        }
+        Token::Word(name.to_string())
+    }
+
+    /// Rewrite a function call, or None to leave it as written.
+    fn rewrite_call(&mut self, name: &str, inner: &[Token]) -> Option<Vec<Token>> {
+        let renamed = match (self.to, name) {
+            (Family::Postgres, "IFNULL") => Some("COALESCE"),
+            (Family::Postgres, "RAND") => Some("RANDOM"),
+            (Family::Postgres, "LCASE") => Some("LOWER"),
+            (Family::Postgres, "UCASE") => Some("UPPER"),
+            (Family::MySql, "RANDOM") => Some("RAND"),
+            _ => None,
+        };
+        if let Some(renamed) = renamed {
+            let mut call = vec![word(renamed), symbol("(")];
+            call.extend(self.rewrite(inner));
+            call.push(symbol(")"));
+            return Some(call);
+        }
+
+        match (self.to, name) {
+            (Family::Postgres, "CURDATE") if trim(inner).is_empty() => {
+                Some(vec![word("CURRENT_DATE")])
+            }
+            (Family::Postgres, "CURTIME") if trim(inner).is_empty() => {
+                Some(vec![word("CURRENT_TIME")])
+            }
+            (Family::Postgres, "IF") => {
+                let args = split_args(inner);
+                let [condition, then, otherwise] = args.as_slice() else {
+                    return None;
+                };
+                let mut case = vec![word("CASE"), space(), word("WHEN"), space()];
+                case.extend(self.rewrite(trim(condition)));
+                case.extend([space(), word("THEN"), space()]);
+                case.extend(self.rewrite(trim(then)));
+                case.extend([space(), word("ELSE"), space()]);
+                case.extend(self.rewrite(trim(otherwise)));
+                case.extend([space(), word("END")]);
+                Some(case)
+            }
+            (Family::Postgres, "GROUP_CONCAT") => self.group_concat_to_string_agg(inner),
+            (Family::MySql, "STRING_AGG") => self.string_agg_to_group_concat(inner),
+            (_, "CAST") => {
+                let at = inner.iter().rposition(|t| t.is_word("AS"))?;
+                let target = self.cast_type(trim(&inner[at + 1..]))?;
+                let mut cast = vec![word("CAST"), symbol("(")];
+                cast.extend(self.rewrite(&inner[..at]));
+                cast.extend([word("AS"), space(), Token::Word(target), symbol(")")]);
+                Some(cast)
+            }
+            _ => None,
+        }
+    }
+
+    /// `GROUP_CONCAT([DISTINCT] expr [ORDER BY ...] [SEPARATOR sep])`
+    fn group_concat_to_string_agg(&mut self, inner: &[Token]) -> Option<Vec<Token>> {
+        let separator_at = find_top_level(inner, &["SEPARATOR"]);
+        let body = &inner[..separator_at.unwrap_or(inner.len())];
+        let order_at = find_top_level(body, &["ORDER"]);
+        let value = trim(&body[..order_at.unwrap_or(body.len())]);
+        if split_args(value).len() > 1 {
+            self.note("GROUP_CONCAT of several expressions was left as is; wrap them in CONCAT()");
+            return None;
+        }
+
+        let mut call = vec![word("STRING_AGG"), symbol("(")];
+        call.extend(self.rewrite(value));
+        call.extend([symbol(","), space()]);
+        match separator_at {
+            Some(at) => call.extend(self.rewrite(trim(&inner[at + 1..]))),
+            None => call.push(Token::Str(",".to_string())),
+        }
+        if let Some(at) = order_at {
+            call.push(space());
+            call.extend(self.rewrite(trim(&body[at..])));
+        }
+        call.push(symbol(")"));
+        Some(call)
+    }
+
+    /// `STRING_AGG([DISTINCT] expr, sep [ORDER BY ...])`
+    fn string_agg_to_group_concat(&mut self, inner: &[Token]) -> Option<Vec<Token>> {
+        let args = split_args(inner);
+        let [value, rest] = args.as_slice() else {
+            return None;
+        };
+        let order_at = find_top_level(rest, &["ORDER"]);
+        let separator = trim(&rest[..order_at.unwrap_or(rest.len())]);
+
+        let mut call = vec![word("GROUP_CONCAT"), symbol("(")];
+        call.extend(self.rewrite(trim(value)));
+        if let Some(at) = order_at {
+            call.push(space());
+            call.extend(self.rewrite(trim(&rest[at..])));
+        }
+        call.extend([space(), word("SEPARATOR"), space()]);
+        call.extend(self.rewrite(separator));
+        call.push(symbol(")"));
+        Some(call)
+    }
+
+    /// Turn `operand::type` into `CAST(operand AS type)`, taking the operand back off
+    /// `out`. Returns the index after the type.
+    fn rewrite_cast_operator(
+        &mut self,
+        out: &mut Vec<Token>,
+        tokens: &[Token],
+        type_start: usize,
+    ) -> Option<usize> {
+        let mut start = out.len().checked_sub(1)?;
+        match &out[start] {
+            Token::Symbol(s) if s == ")" => {
+                let mut depth = 0;
+                loop {
+                    if out[start].is_symbol(")") {
+                        depth += 1;
+                    } else if out[start].is_symbol("(") {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    start = start.checked_sub(1)?;
+                }
+                if start > 0 && matches!(out[start - 1], Token::Word(_)) {
+                    start -= 1;
+                }
+            }
+            Token::Word(_) | Token::Ident(_) | Token::Str(_) | Token::Number(_) => {}
+            _ => return None,
+        }
+        // Qualified names such as `t.created_at`
+        while start >= 2
+            && out[start - 1].is_symbol(".")
+            && matches!(out[start - 2], Token::Word(_) | Token::Ident(_))
+        {
+            start -= 2;
+        }
+
+        // The type: a word, or `double precision` / `character varying`
+        let Some(Token::Word(first)) = tokens.get(type_start) else {
+            return None;
+        };
+        let mut end = type_start + 1;
+        if let (Some(gap), Some(Token::Word(second))) = (tokens.get(end), tokens.get(end + 1)) {
+            let pair = (first.to_ascii_lowercase(), second.to_ascii_lowercase());
+            if gap.is_blank()
+                && matches!(
+                    (pair.0.as_str(), pair.1.as_str()),
+                    ("double", "precision") | ("character", "varying")
+                )
+            {
+                end += 2;
+            }
+        }
+        if tokens.get(end).is_some_and(|t| t.is_symbol("(")) {
+            end = matching_paren(tokens, end)? + 1;
+        }
+        if tokens.get(end).is_some_and(|t| t.is_symbol("[")) {
+            self.note("MySQL has no array types; array casts were left as is");
+            return None;
+        }
+        let target = self.cast_type(&tokens[type_start..end])?;
+
+        let operand = out.split_off(start);
+        out.extend([word("CAST"), symbol("(")]);
+        out.extend(operand);
+        out.extend([
+            space(),
+            word("AS"),
+            space(),
+            Token::Word(target),
+            symbol(")"),
+        ]);
+        Some(end)
+    }
+
+    /// The target dialect's spelling of a cast type.
+    fn cast_type(&mut self, tokens: &[Token]) -> Option<String> {
+        let split = tokens
+            .iter()
+            .position(|t| t.is_symbol("("))
+            .unwrap_or(tokens.len());
+        let name = tokens[..split]
+            .iter()
+            .filter_map(|t| match t {
+                Token::Word(w) => Some(w.to_ascii_lowercase()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        if name.is_empty() {
+            return None;
+        }
+        let precision = render(&tokens[split..], self.to);
+
+        let mapped = match self.to {
+            Family::MySql => match name.as_str() {
+                "int" | "int2" | "int4" | "int8" | "integer" | "smallint" | "bigint" => {
+                    "SIGNED".to_string()
+                }
+                "text" | "varchar" | "character varying" | "char" | "character" | "bpchar"
+                | "name" => format!("CHAR{}", precision),
+                "uuid" => "CHAR(36)".to_string(),
+                "numeric" | "decimal" => format!("DECIMAL{}", precision),
+                "real" | "float" | "float4" | "float8" | "double precision" => "DOUBLE".to_string(),
+                "date" => "DATE".to_string(),
+                "timestamp" | "timestamptz" => "DATETIME".to_string(),
+                "time" => "TIME".to_string(),
+                "json" | "jsonb" => "JSON".to_string(),
+                "signed" | "unsigned" | "datetime" | "double" | "signed integer"
+                | "unsigned integer" | "binary" => format!("{}{}", name.to_uppercase(), precision),
+                _ => {
+                    self.note(&format!("MySQL cannot CAST to {}; left as is", name));
+                    return None;
+                }
+            },
+            Family::Postgres => match name.as_str() {
+                "signed" | "signed integer" | "unsigned" | "unsigned integer" => {
+                    "BIGINT".to_string()
+                }
+                "char" if precision.is_empty() => "TEXT".to_string(),
+                "char" => format!("VARCHAR{}", precision),
+                "datetime" => "TIMESTAMP".to_string(),
+                "double" => "DOUBLE PRECISION".to_string(),
+                "binary" => "BYTEA".to_string(),
+                _ => format!("{}{}", name.to_uppercase(), precision),
+            },
+        };
+        Some(mapped)
+    }
+
+    /// Rewrite a run of LIMIT / OFFSET / FETCH clauses starting at `start` into
+    /// `LIMIT n OFFSET m` when the target does not accept them as written.
+    /// Returns the clauses and the index after them, or None if none start there.
+    fn rewrite_limit(&mut self, tokens: &[Token], start: usize) -> Option<(Vec<Token>, usize)> {
+        let is_arg = |t: Option<&Token>| {
+            matches!(t, Some(Token::Number(_) | Token::Word(_)))
+                || t.is_some_and(|t| t.is_symbol("?"))
+        };
+
+        let mut limit: Option<Token> = None;
+        let mut offset: Option<Token> = None;
+        let mut limit_all = false;
+        // Forms the target does not accept
+        let mut foreign = false;
+        let mut next = start;
+        loop {
+            let at = next_code(tokens, next);
+            let Some(Token::Word(keyword)) = tokens.get(at) else {
+                break;
+            };
+            match keyword.to_ascii_uppercase().as_str() {
+                "LIMIT" if limit.is_none() && !limit_all => {
+                    let first = next_code(tokens, at + 1);
+                    if tokens.get(first).is_some_and(|t| t.is_word("ALL")) {
+                        limit_all = true;
+                        foreign |= self.to == Family::MySql;
+                        next = first + 1;
+                        continue;
+                    }
+                    if !is_arg(tokens.get(first)) {
+                        break;
+                    }
+                    let comma = next_code(tokens, first + 1);
+                    let second = next_code(tokens, comma + 1);
+                    if tokens.get(comma).is_some_and(|t| t.is_symbol(","))
+                        && is_arg(tokens.get(second))
+                    {
+                        // MySQL's `LIMIT offset, count`
+                        offset = Some(tokens[first].clone());
+                        limit = Some(tokens[second].clone());
+                        foreign |= self.to == Family::Postgres;
+                        next = second + 1;
+                    } else {
+                        limit = Some(tokens[first].clone());
+                        next = first + 1;
+                    }
+                }
+                "OFFSET" if offset.is_none() => {
+                    let value = next_code(tokens, at + 1);
+                    if !is_arg(tokens.get(value)) {
+                        break;
+                    }
+                    offset = Some(tokens[value].clone());
+                    next = value + 1;
+                    // MySQL wants LIMIT first, and always wants one
+                    foreign |= self.to == Family::MySql && limit.is_none();
+                    let rows = next_code(tokens, next);
+                    if tokens
+                        .get(rows)
+                        .is_some_and(|t| t.is_word("ROW") || t.is_word("ROWS"))
+                    {
+                        foreign |= self.to == Family::MySql;
+                        next = rows + 1;
+                    }
+                }
+                "FETCH" if limit.is_none() => {
+                    // FETCH {FIRST | NEXT} [n] {ROW | ROWS} ONLY
+                    let first = next_code(tokens, at + 1);
+                    if !tokens
+                        .get(first)
+                        .is_some_and(|t| t.is_word("FIRST") || t.is_word("NEXT"))
+                    {
+                        break;
+                    }
+                    let mut word_at = next_code(tokens, first + 1);
+                    let count = if is_arg(tokens.get(word_at))
+                        && !tokens[word_at].is_word("ROW")
+                        && !tokens[word_at].is_word("ROWS")
+                    {
+                        let count = tokens[word_at].clone();
+                        word_at = next_code(tokens, word_at + 1);
+                        count
+                    } else {
+                        Token::Number("1".to_string())
+                    };
+                    if !tokens
+                        .get(word_at)
+                        .is_some_and(|t| t.is_word("ROW") || t.is_word("ROWS"))
+                    {
+                        break;
+                    }
+                    let only = next_code(tokens, word_at + 1);
+                    if !tokens.get(only).is_some_and(|t| t.is_word("ONLY")) {
+                        break;
+                    }
+                    limit = Some(count);
+                    foreign |= self.to == Family::MySql;
+                    next = only + 1;
+                }
+                _ => break,
+            }
+        }
+        if next == start {
+            return None;
+        }
+        if !foreign {
+            return Some((tokens[start..next].to_vec(), next));
+        }
+
+        let mut clauses = Vec::new();
+        match (&limit, &offset, self.to) {
+            (Some(count), _, _) => clauses.extend([word("LIMIT"), space(), count.clone()]),
+            (None, Some(_), Family::MySql) => {
+                clauses.extend([word("LIMIT"), space(), word(MYSQL_NO_LIMIT)])
+            }
+            (None, _, Family::Postgres) if limit_all => {
+                clauses.extend([word("LIMIT"), space(), word("ALL")])
+            }
+            _ => {}
+        }
+        if let Some(skip) = offset {
+            if !clauses.is_empty() {
+                clauses.push(space());
+            }
+            clauses.extend([word("OFFSET"), space(), skip]);
+        }
+        Some((clauses, next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SqlDialect::*;
+
+    #[test]
+    fn translates_each_dialect_pair() {
+        let cases = [
+            (
+                MySQL,
+                PostgreSQL,
+                r"SELECT `name` FROM users WHERE note = 'it\'s' LIMIT 10, 5",
+                r#"SELECT "name" FROM users WHERE note = 'it''s' LIMIT 5 OFFSET 10"#,
+            ),
+            (
+                MariaDB,
+                PostgreSQL,
+                r#"SELECT IFNULL(a, 0), IF(b > 1, "x", 'y'), UCASE(c) FROM t # all"#,
+                "SELECT COALESCE(a, 0), CASE WHEN b > 1 THEN 'x' ELSE 'y' END, UPPER(c) FROM t -- all",
+            ),
+            (
+                MySQL,
+                CockroachDB,
+                "SELECT CAST(id AS CHAR), CURDATE() FROM t",
+                "SELECT CAST(id AS TEXT), CURRENT_DATE FROM t",
+            ),
+            (
+                MySQL,
+                SQLite,
+                "SELECT GROUP_CONCAT(name ORDER BY name SEPARATOR '; ') FROM t",
+                "SELECT STRING_AGG(name, '; ' ORDER BY name) FROM t",
+            ),
+            (
+                MySQL,
+                DuckDB,
+                "SELECT RAND(), CAST(x AS DATETIME) FROM t LIMIT 3",
+                "SELECT RANDOM(), CAST(x AS TIMESTAMP) FROM t LIMIT 3",
+            ),
+            (
+                PostgreSQL,
+                MySQL,
+                r#"SELECT "name", created_at::date FROM users OFFSET 5 ROWS FETCH FIRST 10 ROWS ONLY"#,
+                "SELECT `name`, CAST(created_at AS DATE) FROM users LIMIT 10 OFFSET 5",
+            ),
+            (
+                PostgreSQL,
+                MariaDB,
+                "SELECT STRING_AGG(name, ', ' ORDER BY name), RANDOM() FROM t LIMIT ALL OFFSET 2",
+                "SELECT GROUP_CONCAT(name ORDER BY name SEPARATOR ', '), RAND() FROM t LIMIT 18446744073709551615 OFFSET 2",
+            ),
+            (
+                CockroachDB,
+                MySQL,
+                r"SELECT E'a\nb', (price * 2)::numeric(10,2) FROM t OFFSET 4",
+                r"SELECT 'a\nb', CAST((price * 2) AS DECIMAL(10,2)) FROM t LIMIT 18446744073709551615 OFFSET 4",
+            ),
+            (
+                SQLite,
+                MySQL,
+                "SELECT 'C:\\dir' --x\nFROM t",
+                "SELECT 'C:\\\\dir' -- x\nFROM t",
+            ),
+            (
+                DuckDB,
+                MariaDB,
+                "SELECT t.total::double precision FROM t",
+                "SELECT CAST(t.total AS DOUBLE) FROM t",
+            ),
+            (
+                BigQuery,
+                MySQL,
+                "SELECT CAST(x AS varchar(20)) FROM t",
+                "SELECT CAST(x AS CHAR(20)) FROM t",
+            ),
+            (Odbc, MySQL, "SELECT $1::uuid", "SELECT CAST($1 AS CHAR(36))"),
+            (ClickHouse, MySQL, "SELECT x FROM t LIMIT 5", "SELECT x FROM t LIMIT 5"),
+            // Dialects of one family are left as written
+            (
+                PostgreSQL,
+                CockroachDB,
+                r#"SELECT "a"::int FROM t LIMIT 1"#,
+                r#"SELECT "a"::int FROM t LIMIT 1"#,
+            ),
+            (
+                MySQL,
+                MariaDB,
+                "SELECT `a` FROM t LIMIT 1, 2",
+                "SELECT `a` FROM t LIMIT 1, 2",
+            ),
+            (
+                SQLite,
+                PostgreSQL,
+                r#"SELECT "a" FROM t"#,
+                r#"SELECT "a" FROM t"#,
+            ),
+        ];
+        for (from, to, sql, expected) in cases {
+            let translation = translate_sql(sql, from, to);
+            assert_eq!(translation.sql, expected, "{:?} -> {:?}", from, to);
+            assert!(translation.notes.is_empty(), "{:?} -> {:?}", from, to);
+        }
+    }
+
+    #[test]
+    fn untranslatable_constructs_are_left_with_a_note() {
+        let cases = [
+            (
+                PostgreSQL,
+                MySQL,
+                "SELECT * FROM t WHERE a ILIKE 'x%' ORDER BY b NULLS LAST",
+                "SELECT * FROM t WHERE a LIKE 'x%' ORDER BY b NULLS LAST",
+                vec![
+                    "ILIKE became LIKE, which ignores case only under a case-insensitive collation",
+                    "MySQL has no NULLS FIRST/LAST; order by `column IS NULL` first",
+                ],
+            ),
+            (
+                PostgreSQL,
+                MariaDB,
+                "INSERT INTO t (a) VALUES (1) ON CONFLICT (a) DO NOTHING RETURNING id",
+                "INSERT INTO t (a) VALUES (1) ON CONFLICT (a) DO NOTHING RETURNING id",
+                vec![
+                    "Rewrite ON CONFLICT as ON DUPLICATE KEY UPDATE",
+                    "MySQL has no RETURNING clause",
+                ],
+            ),
+            (
+                PostgreSQL,
+                MySQL,
+                "SELECT tags::text[], a || b, CAST(d AS interval) FROM t",
+                "SELECT tags::text[], a || b, CAST(d AS interval) FROM t",
+                vec![
+                    "MySQL has no array types; array casts were left as is",
+                    "`||` is logical OR in MySQL unless PIPES_AS_CONCAT is set; use CONCAT()",
+                    "MySQL cannot CAST to interval; left as is",
+                ],
+            ),
+            (
+                MySQL,
+                PostgreSQL,
+                "SELECT GROUP_CONCAT(a, b) FROM t",
+                "SELECT GROUP_CONCAT(a, b) FROM t",
+                vec!["GROUP_CONCAT of several expressions was left as is; wrap them in CONCAT()"],
+            ),
+            (
+                MariaDB,
+                CockroachDB,
+                "INSERT INTO t (a) VALUES (1) ON DUPLICATE KEY UPDATE a = 2",
+                "INSERT INTO t (a) VALUES (1) ON DUPLICATE KEY UPDATE a = 2",
+                vec!["Rewrite ON DUPLICATE KEY UPDATE as ON CONFLICT (...) DO UPDATE"],
+            ),
+        ];
+        for (from, to, sql, expected, notes) in cases {
+            let translation = translate_sql(sql, from, to);
+            assert_eq!(translation.sql, expected, "{:?} -> {:?}", from, to);
+            assert_eq!(translation.notes, notes, "{:?} -> {:?}", from, to);
+        }
+    }
+}
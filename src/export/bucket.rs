@@ -0,0 +1,215 @@
+use super::{
+    start_job, update_job, write_export, ExportDestination, ExportJobStatus, ExportOptions,
+};
+use crate::db::QueryResult;
+use dioxus::prelude::*;
+use object_store::aws::AmazonS3Builder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, WriteMultipart};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Export chunks buffered between the writer thread and the upload
+const UPLOAD_QUEUE_CHUNKS: usize = 8;
+/// Multipart parts uploaded concurrently
+const MAX_PARTS_IN_FLIGHT: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub enum CloudProvider {
+    #[default]
+    S3,
+    Gcs,
+}
+
+impl CloudProvider {
+    pub const ALL: [CloudProvider; 2] = [CloudProvider::S3, CloudProvider::Gcs];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CloudProvider::S3 => "Amazon S3",
+            CloudProvider::Gcs => "Google Cloud Storage",
+        }
+    }
+
+    fn scheme(&self) -> &'static str {
+        match self {
+            CloudProvider::S3 => "s3",
+            CloudProvider::Gcs => "gs",
+        }
+    }
+}
+
+/// Bucket and credentials that exports are uploaded to. Empty credentials fall back
+/// to the provider's usual environment variables.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BucketTarget {
+    pub provider: CloudProvider,
+    pub bucket: String,
+    /// Object key, e.g. `extracts/orders.csv`
+    pub key: String,
+    /// S3 region
+    pub region: String,
+    /// Custom S3 endpoint for MinIO, R2 and other compatible stores
+    pub endpoint: String,
+    /// S3 access key id; the secret key lives in the OS keyring
+    pub access_key_id: String,
+    /// GCS service account JSON key file
+    pub service_account_path: String,
+}
+
+impl BucketTarget {
+    /// `s3://bucket/key` or `gs://bucket/key`
+    pub fn url(&self) -> String {
+        format!(
+            "{}://{}/{}",
+            self.provider.scheme(),
+            self.bucket,
+            self.key.trim_start_matches('/')
+        )
+    }
+
+    fn object_store(&self, secret_key: Option<String>) -> Result<Arc<dyn ObjectStore>, String> {
+        match self.provider {
+            CloudProvider::S3 => {
+                let mut builder = AmazonS3Builder::from_env().with_bucket_name(&self.bucket);
+                if !self.region.is_empty() {
+                    builder = builder.with_region(&self.region);
+                }
+                if !self.endpoint.is_empty() {
+                    builder = builder
+                        .with_endpoint(&self.endpoint)
+                        .with_allow_http(self.endpoint.starts_with("http://"));
+                }
+                if !self.access_key_id.is_empty() {
+                    builder = builder.with_access_key_id(&self.access_key_id);
+                }
+                if let Some(secret_key) = secret_key {
+                    builder = builder.with_secret_access_key(secret_key);
+                }
+                Ok(Arc::new(builder.build().map_err(|e| e.to_string())?))
+            }
+            CloudProvider::Gcs => {
+                let mut builder =
+                    GoogleCloudStorageBuilder::from_env().with_bucket_name(&self.bucket);
+                if !self.service_account_path.is_empty() {
+                    builder = builder.with_service_account_path(&self.service_account_path);
+                }
+                Ok(Arc::new(builder.build().map_err(|e| e.to_string())?))
+            }
+        }
+    }
+}
+
+/// Upload the export straight to the bucket in the background, listed with the export jobs.
+/// Nothing is written to local disk, except Parquet, which DuckDB writes to a temporary file
+/// before it is uploaded.
+pub fn export_to_bucket(
+    result: QueryResult,
+    options: &ExportOptions,
+    target: BucketTarget,
+    secret_key: Option<String>,
+) {
+    let options = options.clone();
+    tracing::info!("Uploading {} rows to {}", result.rows.len(), target.url());
+    spawn(async move {
+        let (id, cancel) = start_job(
+            ExportDestination::Bucket(target.url()),
+            options.format,
+            result.rows.len(),
+        );
+        let status = match upload_export(result, options, &target, secret_key, id, cancel).await {
+            Ok(true) => {
+                tracing::info!("Export uploaded to {}", target.url());
+                ExportJobStatus::Done
+            }
+            Ok(false) => ExportJobStatus::Cancelled,
+            Err(e) => {
+                tracing::error!("Failed to upload export: {}", e);
+                ExportJobStatus::Failed(e)
+            }
+        };
+        update_job(id, |job| job.status = status);
+    });
+}
+
+/// Stream the formatted export from a blocking writer thread into a multipart upload.
+/// Returns false when stopped through `cancel`; the partial upload is aborted.
+async fn upload_export(
+    result: QueryResult,
+    options: ExportOptions,
+    target: &BucketTarget,
+    secret_key: Option<String>,
+    id: u64,
+    cancel: Arc<AtomicBool>,
+) -> Result<bool, String> {
+    let store = target.object_store(secret_key)?;
+    let path = ObjectPath::from(target.key.trim_start_matches('/'));
+    let upload = store
+        .put_multipart(&path)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut upload = WriteMultipart::new(upload);
+
+    let (tx, mut rx) = mpsc::channel(UPLOAD_QUEUE_CHUNKS);
+    let rows_written = Arc::new(AtomicUsize::new(0));
+    let task = tokio::task::spawn_blocking({
+        let rows_written = rows_written.clone();
+        move || -> Result<bool, String> {
+            let mut out = ChunkSender(tx);
+            write_export(&result, &options, &mut out, &rows_written, &cancel)
+                .map_err(|e| e.to_string())
+        }
+    });
+
+    while let Some(chunk) = rx.recv().await {
+        upload.write(&chunk);
+        if let Err(e) = upload.wait_for_capacity(MAX_PARTS_IN_FLIGHT).await {
+            // Dropping the receiver stops the writer thread
+            drop(rx);
+            let _ = upload.abort().await;
+            return Err(e.to_string());
+        }
+        update_job(id, |job| {
+            job.rows_written = rows_written.load(Ordering::Relaxed)
+        });
+    }
+
+    match task.await.map_err(|e| e.to_string()).and_then(|r| r) {
+        Ok(true) => {
+            upload.finish().await.map_err(|e| e.to_string())?;
+            update_job(id, |job| {
+                job.rows_written = rows_written.load(Ordering::Relaxed)
+            });
+            Ok(true)
+        }
+        Ok(false) => {
+            let _ = upload.abort().await;
+            Ok(false)
+        }
+        Err(e) => {
+            let _ = upload.abort().await;
+            Err(e)
+        }
+    }
+}
+
+/// Hands each write to the upload task, blocking while its queue is full.
+struct ChunkSender(mpsc::Sender<Vec<u8>>);
+
+impl Write for ChunkSender {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .blocking_send(buf.to_vec())
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::BrokenPipe, "Upload stopped"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use super::{write_export, write_parquet, ExportFormat, ExportOptions};
+use crate::db::QueryResult;
+use crate::state::EXPORT_JOBS;
+use dioxus::prelude::*;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::time::Duration;
+
+static NEXT_EXPORT_JOB_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportJobStatus {
+    Running,
+    Done,
+    Cancelled,
+    Failed(String),
+}
+
+/// Where an export job sends its rows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportDestination {
+    File(PathBuf),
+    /// Object in an S3 or GCS bucket, as an `s3://` or `gs://` URL
+    Bucket(String),
+    /// One JSON message per row on a Kafka topic
+    Kafka {
+        brokers: String,
+        topic: String,
+    },
+}
+
+impl ExportDestination {
+    /// Short name for the jobs list
+    pub fn name(&self) -> String {
+        match self {
+            ExportDestination::File(path) => path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.display().to_string()),
+            ExportDestination::Bucket(url) => {
+                url.rsplit('/').next().unwrap_or(url.as_str()).to_string()
+            }
+            ExportDestination::Kafka { topic, .. } => topic.clone(),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            ExportDestination::File(path) => path.display().to_string(),
+            ExportDestination::Bucket(url) => url.clone(),
+            ExportDestination::Kafka { brokers, topic } => {
+                format!("Kafka topic {} on {}", topic, brokers)
+            }
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            ExportDestination::File(path) => Some(path),
+            ExportDestination::Bucket(_) | ExportDestination::Kafka { .. } => None,
+        }
+    }
+
+    /// Shown for finished jobs that have no local file to reveal
+    pub fn done_label(&self) -> &'static str {
+        match self {
+            ExportDestination::File(_) => "Saved",
+            ExportDestination::Bucket(_) => "Uploaded",
+            ExportDestination::Kafka { .. } => "Published",
+        }
+    }
+}
+
+/// An export being written in the background, listed in the jobs panel.
+#[derive(Debug, Clone)]
+pub struct ExportJob {
+    pub id: u64,
+    pub destination: ExportDestination,
+    pub format: ExportFormat,
+    pub total_rows: usize,
+    pub rows_written: usize,
+    pub status: ExportJobStatus,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ExportJob {
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// List a new running job, returning its id and cancellation flag.
+pub(super) fn start_job(
+    destination: ExportDestination,
+    format: ExportFormat,
+    total_rows: usize,
+) -> (u64, Arc<AtomicBool>) {
+    let id = NEXT_EXPORT_JOB_ID.fetch_add(1, Ordering::Relaxed);
+    let cancel = Arc::new(AtomicBool::new(false));
+    EXPORT_JOBS.write().push(ExportJob {
+        id,
+        destination,
+        format,
+        total_rows,
+        rows_written: 0,
+        status: ExportJobStatus::Running,
+        cancel: cancel.clone(),
+    });
+    (id, cancel)
+}
+
+/// Write the export on a blocking thread, publishing progress until it finishes.
+pub(super) async fn run_export_job(result: QueryResult, options: ExportOptions, path: PathBuf) {
+    let (id, cancel) = start_job(
+        ExportDestination::File(path.clone()),
+        options.format,
+        result.rows.len(),
+    );
+    let rows_written = Arc::new(AtomicUsize::new(0));
+
+    let mut task = tokio::task::spawn_blocking({
+        let path = path.clone();
+        let rows_written = rows_written.clone();
+        move || -> Result<bool, String> {
+            if options.format == ExportFormat::Parquet {
+                return write_parquet(&result, &path, &rows_written, &cancel);
+            }
+            let file = std::fs::File::create(&path).map_err(|e| e.to_string())?;
+            let mut out = BufWriter::new(file);
+            write_export(&result, &options, &mut out, &rows_written, &cancel)
+                .map_err(|e| e.to_string())
+        }
+    });
+
+    let outcome = loop {
+        tokio::select! {
+            outcome = &mut task => break outcome.map_err(|e| e.to_string()).and_then(|r| r),
+            _ = tokio::time::sleep(Duration::from_millis(200)) => {
+                update_job(id, |job| job.rows_written = rows_written.load(Ordering::Relaxed));
+            }
+        }
+    };
+
+    let status = match outcome {
+        Ok(true) => {
+            tracing::info!("Export written to {:?}", path);
+            ExportJobStatus::Done
+        }
+        Ok(false) => {
+            let _ = std::fs::remove_file(&path);
+            ExportJobStatus::Cancelled
+        }
+        Err(e) => {
+            tracing::error!("Failed to export: {}", e);
+            ExportJobStatus::Failed(e)
+        }
+    };
+    update_job(id, |job| {
+        job.rows_written = rows_written.load(Ordering::Relaxed);
+        job.status = status;
+    });
+}
+
+pub(super) fn update_job(id: u64, update: impl FnOnce(&mut ExportJob)) {
+    if let Some(job) = EXPORT_JOBS.write().iter_mut().find(|j| j.id == id) {
+        update(job);
+    }
+}
+
+/// Drop finished, failed and cancelled jobs from the list.
+pub fn clear_finished_export_jobs() {
+    EXPORT_JOBS
+        .write()
+        .retain(|job| job.status == ExportJobStatus::Running);
+}
+
+/// Open the file manager at the exported file.
+pub fn reveal_in_folder(path: &Path) {
+    #[cfg(target_os = "macos")]
+    let result = Command::new("open").arg("-R").arg(path).spawn();
+    #[cfg(target_os = "windows")]
+    let result = Command::new("explorer")
+        .arg(format!("/select,{}", path.display()))
+        .spawn();
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let result = Command::new("xdg-open")
+        .arg(path.parent().unwrap_or(path))
+        .spawn();
+
+    if let Err(e) = result {
+        tracing::error!("Failed to open folder: {}", e);
+    }
+}
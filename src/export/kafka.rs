@@ -0,0 +1,128 @@
+use super::{
+    json_column_kinds, json_row, start_job, update_job, ExportDestination, ExportFormat,
+    ExportJobStatus,
+};
+use crate::db::QueryResult;
+use dioxus::prelude::*;
+use rdkafka::config::ClientConfig;
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::time::Duration;
+
+/// Messages queued before waiting for their delivery reports
+const KAFKA_CHUNK_ROWS: usize = 1_000;
+
+/// Topic that result rows are published to.
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KafkaTarget {
+    /// Comma-separated bootstrap servers, e.g. `localhost:9092`
+    pub brokers: String,
+    pub topic: String,
+    /// Column whose value becomes the message key; messages are unkeyed when None
+    pub key_column: Option<String>,
+}
+
+/// Publish each row as a JSON message in the background, listed with the export jobs.
+pub fn publish_to_kafka(result: QueryResult, target: KafkaTarget) {
+    tracing::info!(
+        "Publishing {} rows to Kafka topic {}",
+        result.rows.len(),
+        target.topic
+    );
+    spawn(async move {
+        let (id, cancel) = start_job(
+            ExportDestination::Kafka {
+                brokers: target.brokers.clone(),
+                topic: target.topic.clone(),
+            },
+            ExportFormat::Json,
+            result.rows.len(),
+        );
+        let status = match publish_rows(&result, &target, id, &cancel).await {
+            Ok(true) => {
+                tracing::info!("Published to Kafka topic {}", target.topic);
+                ExportJobStatus::Done
+            }
+            Ok(false) => ExportJobStatus::Cancelled,
+            Err(e) => {
+                tracing::error!("Failed to publish to Kafka: {}", e);
+                ExportJobStatus::Failed(e)
+            }
+        };
+        update_job(id, |job| job.status = status);
+    });
+}
+
+/// Send the rows chunk by chunk, waiting for each chunk to be acknowledged.
+/// Returns false when stopped through `cancel`.
+async fn publish_rows(
+    result: &QueryResult,
+    target: &KafkaTarget,
+    id: u64,
+    cancel: &AtomicBool,
+) -> Result<bool, String> {
+    let key_index = match &target.key_column {
+        Some(column) => Some(
+            result
+                .columns
+                .iter()
+                .position(|c| c == column)
+                .ok_or_else(|| format!("Key column '{}' is not in the results", column))?,
+        ),
+        None => None,
+    };
+    let producer: FutureProducer = ClientConfig::new()
+        .set("bootstrap.servers", &target.brokers)
+        .set("message.timeout.ms", "30000")
+        .create()
+        .map_err(|e| e.to_string())?;
+    let kinds = json_column_kinds(result);
+
+    let mut published = 0;
+    for (chunk_idx, chunk) in result.rows.chunks(KAFKA_CHUNK_ROWS).enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        let payloads: Vec<String> = (0..chunk.len())
+            .map(|offset| {
+                json_row(result, &kinds, chunk_idx * KAFKA_CHUNK_ROWS + offset).to_string()
+            })
+            .collect();
+
+        let mut deliveries = Vec::with_capacity(chunk.len());
+        for (row, payload) in chunk.iter().zip(&payloads) {
+            let mut record = FutureRecord::<str, str>::to(&target.topic).payload(payload);
+            if let Some(value) = key_index.and_then(|i| row.get(i)) {
+                record = record.key(value);
+            }
+            loop {
+                match producer.send_result(record) {
+                    Ok(delivery) => {
+                        deliveries.push(delivery);
+                        break;
+                    }
+                    // The local queue drains as the brokers acknowledge earlier messages
+                    Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), returned)) => {
+                        record = returned;
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                    }
+                    Err((e, _)) => return Err(e.to_string()),
+                }
+            }
+        }
+        for delivery in deliveries {
+            match delivery.await {
+                Ok(Ok(_)) => {}
+                Ok(Err((e, _))) => return Err(e.to_string()),
+                Err(_) => return Err("Producer stopped before delivering".to_string()),
+            }
+        }
+
+        published += chunk.len();
+        update_job(id, |job| job.rows_written = published);
+    }
+    Ok(true)
+}
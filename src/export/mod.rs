@@ -1,76 +1,383 @@
 use crate::db::QueryResult;
 use dioxus::prelude::*;
-use std::fs;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
-#[derive(Clone, Copy, Debug)]
+mod bucket;
+mod jobs;
+mod kafka;
+mod parquet;
+
+pub use bucket::*;
+pub use jobs::*;
+pub use kafka::*;
+use parquet::{stream_parquet, write_parquet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ExportFormat {
     Csv,
-    #[allow(dead_code)]
     Json,
-    #[allow(dead_code)]
     Xml,
+    Parquet,
 }
 
-pub fn export_results(result: QueryResult, format: ExportFormat) {
-    tracing::info!("Starting export with format {:?}", format);
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Csv,
+        ExportFormat::Json,
+        ExportFormat::Xml,
+        ExportFormat::Parquet,
+    ];
 
-    let (extension, content) = match format {
-        ExportFormat::Csv => ("csv", export_csv(&result)),
-        ExportFormat::Json => ("json", export_json(&result)),
-        ExportFormat::Xml => ("xml", export_xml(&result)),
-    };
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Xml => "XML",
+            ExportFormat::Parquet => "Parquet",
+        }
+    }
+
+    /// The text format `ExportWriter` produces, or None for Parquet, which DuckDB writes.
+    fn text_format(&self) -> Option<TextFormat> {
+        match self {
+            ExportFormat::Csv => Some(TextFormat::Csv),
+            ExportFormat::Json => Some(TextFormat::Json),
+            ExportFormat::Xml => Some(TextFormat::Xml),
+            ExportFormat::Parquet => None,
+        }
+    }
+}
+
+/// Export formats written row by row as text.
+#[derive(Clone, Copy, PartialEq)]
+enum TextFormat {
+    Csv,
+    Json,
+    Xml,
+}
+
+/// How XML export writes each column of a row.
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub enum XmlLayout {
+    /// `<row><id>1</id></row>`
+    #[default]
+    Elements,
+    /// `<row id="1"/>`, leaving out NULL columns
+    Attributes,
+}
+
+/// Character encoding of CSV exports.
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub enum CsvEncoding {
+    #[default]
+    Utf8,
+    /// UTF-8 with a byte order mark, so Excel on Windows detects the encoding
+    Utf8Bom,
+    /// Legacy Excel encoding; characters outside it are written as `?`
+    Windows1252,
+}
+
+impl CsvEncoding {
+    pub const ALL: [CsvEncoding; 3] = [
+        CsvEncoding::Utf8,
+        CsvEncoding::Utf8Bom,
+        CsvEncoding::Windows1252,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CsvEncoding::Utf8 => "UTF-8",
+            CsvEncoding::Utf8Bom => "UTF-8 with BOM",
+            CsvEncoding::Windows1252 => "Windows-1252",
+        }
+    }
+}
 
-    let filter_name = match format {
-        ExportFormat::Csv => "CSV files",
-        ExportFormat::Json => "JSON files",
-        ExportFormat::Xml => "XML files",
+#[derive(Clone, Copy, Debug, PartialEq, Default, Serialize, Deserialize)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub xml_layout: XmlLayout,
+    pub csv_encoding: CsvEncoding,
+    pub line_ending: LineEnding,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            xml_layout: XmlLayout::default(),
+            csv_encoding: CsvEncoding::default(),
+            line_ending: LineEnding::default(),
+        }
+    }
+}
+
+/// Ask where to save, then write the export in the background as a job.
+pub fn export_results(result: QueryResult, options: &ExportOptions) {
+    let options = options.clone();
+    tracing::info!("Starting export with format {:?}", options.format);
+
+    let (extension, filter_name) = match options.format {
+        ExportFormat::Csv => ("csv", "CSV files"),
+        ExportFormat::Json => ("json", "JSON files"),
+        ExportFormat::Xml => ("xml", "XML files"),
+        ExportFormat::Parquet => ("parquet", "Parquet files"),
     };
 
-    // Run file dialog in a spawn block to avoid blocking the UI thread
     spawn(async move {
-        tracing::info!("Opening file dialog for {} export", extension);
-
-        if let Some(path) = rfd::FileDialog::new()
+        let Some(file) = rfd::AsyncFileDialog::new()
             .add_filter(filter_name, &[extension])
             .set_file_name(format!("export.{}", extension))
             .save_file()
-        {
-            tracing::info!("Selected path: {:?}", path);
-            if let Err(e) = fs::write(&path, content) {
-                tracing::error!("Failed to export: {}", e);
-            } else {
-                tracing::info!("Export successful");
-            }
-        } else {
+            .await
+        else {
             tracing::info!("File dialog cancelled");
-        }
+            return;
+        };
+        run_export_job(result, options, file.path().to_path_buf()).await;
     });
 }
 
-fn export_csv(result: &QueryResult) -> String {
-    let mut output = String::with_capacity(result.rows.len() * 100);
+/// Rows written between progress and cancellation checks
+const EXPORT_CHUNK_ROWS: usize = 1_000;
+
+/// Write the export to `out`, counting rows in `rows_written`.
+/// Returns false when stopped through `cancel`.
+fn write_export(
+    result: &QueryResult,
+    options: &ExportOptions,
+    out: &mut impl Write,
+    rows_written: &AtomicUsize,
+    cancel: &AtomicBool,
+) -> std::io::Result<bool> {
+    let Some(format) = options.format.text_format() else {
+        return stream_parquet(result, out, rows_written, cancel);
+    };
+    let writer = ExportWriter::new(result, options, format);
+    out.write_all(&writer.header())?;
+    for (chunk_idx, chunk) in result.rows.chunks(EXPORT_CHUNK_ROWS).enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+        let mut text = String::with_capacity(chunk.len() * 100);
+        for (offset, row) in chunk.iter().enumerate() {
+            writer.push_row(&mut text, chunk_idx * EXPORT_CHUNK_ROWS + offset, row);
+        }
+        out.write_all(&writer.encode(&text))?;
+        rows_written.fetch_add(chunk.len(), Ordering::Relaxed);
+    }
+    out.write_all(&writer.footer())?;
+    out.flush()?;
+    Ok(true)
+}
+
+/// Formats a result row by row in the chosen export format.
+struct ExportWriter<'a> {
+    result: &'a QueryResult,
+    options: &'a ExportOptions,
+    format: TextFormat,
+    json_kinds: Vec<JsonColumnKind>,
+    xml_names: Vec<String>,
+    xml_root: String,
+    xml_schema: Option<String>,
+}
+
+impl<'a> ExportWriter<'a> {
+    fn new(result: &'a QueryResult, options: &'a ExportOptions, format: TextFormat) -> Self {
+        let json_kinds = json_column_kinds(result);
 
-    output.push_str(
-        &result
-            .columns
-            .iter()
-            .map(|c| escape_csv(c))
-            .collect::<Vec<_>>()
-            .join(","),
-    );
-    output.push('\n');
+        // The XML root element is named after the source table, with its schema as an attribute
+        let (schema, table) = match result.source_table.as_deref() {
+            Some(source) => match source.rsplit_once('.') {
+                Some((schema, table)) => (Some(schema), Some(table)),
+                None => (None, Some(source)),
+            },
+            None => (None, None),
+        };
+        let xml_root = table
+            .map(|t| sanitize_xml_name(t.trim_matches(['"', '`'])))
+            .unwrap_or_else(|| "results".to_string());
+
+        Self {
+            result,
+            options,
+            format,
+            json_kinds,
+            xml_names: xml_column_names(&result.columns),
+            xml_root,
+            xml_schema: schema.map(|s| s.trim_matches(['"', '`']).to_string()),
+        }
+    }
 
-    for row in &result.rows {
-        output.push_str(
-            &row.iter()
-                .map(|c| escape_csv(c))
-                .collect::<Vec<_>>()
-                .join(","),
-        );
-        output.push('\n');
+    fn header(&self) -> Vec<u8> {
+        match self.format {
+            TextFormat::Csv => {
+                let mut header = self
+                    .result
+                    .columns
+                    .iter()
+                    .map(|c| escape_csv(c))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                header.push_str(self.options.line_ending.as_str());
+                let mut bytes = Vec::new();
+                if self.options.csv_encoding == CsvEncoding::Utf8Bom {
+                    bytes.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+                }
+                bytes.extend(self.encode(&header));
+                bytes
+            }
+            TextFormat::Json => b"[".to_vec(),
+            TextFormat::Xml => {
+                let mut header = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<");
+                header.push_str(&self.xml_root);
+                if let Some(schema) = &self.xml_schema {
+                    header.push_str(&format!(" schema=\"{}\"", escape_xml(schema)));
+                }
+                header.push_str(">\n");
+                header.into_bytes()
+            }
+        }
     }
 
-    output
+    fn push_row(&self, output: &mut String, index: usize, row: &[String]) {
+        match self.format {
+            TextFormat::Csv => {
+                // NULL is an empty field and an empty string a quoted one, as in COPY ... CSV
+                output.push_str(
+                    &row.iter()
+                        .enumerate()
+                        .map(|(col, c)| {
+                            if self.result.is_null(index, col) {
+                                String::new()
+                            } else if c.is_empty() {
+                                "\"\"".to_string()
+                            } else {
+                                escape_csv(c)
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
+                output.push_str(self.options.line_ending.as_str());
+            }
+            TextFormat::Json => {
+                let obj = json_row(self.result, &self.json_kinds, index);
+                let pretty = serde_json::to_string_pretty(&obj).unwrap_or_default();
+                output.push_str(if index == 0 { "\n" } else { ",\n" });
+                // Indent to match a pretty-printed array
+                for (i, line) in pretty.lines().enumerate() {
+                    if i > 0 {
+                        output.push('\n');
+                    }
+                    output.push_str("  ");
+                    output.push_str(line);
+                }
+            }
+            TextFormat::Xml => match self.options.xml_layout {
+                XmlLayout::Elements => {
+                    output.push_str("  <row>\n");
+                    // NULL columns are left out, as in the attribute layout
+                    for (col, (name, val)) in self.xml_names.iter().zip(row.iter()).enumerate() {
+                        if !self.result.is_null(index, col) {
+                            output.push_str(&format!(
+                                "    <{}>{}</{}>\n",
+                                name,
+                                escape_xml(val),
+                                name
+                            ));
+                        }
+                    }
+                    output.push_str("  </row>\n");
+                }
+                XmlLayout::Attributes => {
+                    output.push_str("  <row");
+                    for (col, (name, val)) in self.xml_names.iter().zip(row.iter()).enumerate() {
+                        if !self.result.is_null(index, col) {
+                            output.push_str(&format!(" {}=\"{}\"", name, escape_xml(val)));
+                        }
+                    }
+                    output.push_str("/>\n");
+                }
+            },
+        }
+    }
+
+    fn footer(&self) -> Vec<u8> {
+        match self.format {
+            TextFormat::Csv => Vec::new(),
+            TextFormat::Json if self.result.rows.is_empty() => b"]".to_vec(),
+            TextFormat::Json => b"\n]".to_vec(),
+            TextFormat::Xml => format!("</{}>", self.xml_root).into_bytes(),
+        }
+    }
+
+    /// CSV honours the chosen encoding; JSON and XML are always UTF-8.
+    fn encode(&self, text: &str) -> Vec<u8> {
+        match (self.format, self.options.csv_encoding) {
+            (TextFormat::Csv, CsvEncoding::Windows1252) => {
+                text.chars().map(windows_1252_byte).collect()
+            }
+            _ => text.as_bytes().to_vec(),
+        }
+    }
+}
+
+/// Windows-1252 matches Latin-1 except for 0x80-0x9F, which holds typographic characters.
+fn windows_1252_byte(c: char) -> u8 {
+    match c as u32 {
+        0x00..=0x7F | 0xA0..=0xFF => c as u8,
+        _ => match c {
+            '€' => 0x80,
+            '‚' => 0x82,
+            'ƒ' => 0x83,
+            '„' => 0x84,
+            '…' => 0x85,
+            '†' => 0x86,
+            '‡' => 0x87,
+            'ˆ' => 0x88,
+            '‰' => 0x89,
+            'Š' => 0x8A,
+            '‹' => 0x8B,
+            'Œ' => 0x8C,
+            'Ž' => 0x8E,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '˜' => 0x98,
+            '™' => 0x99,
+            'š' => 0x9A,
+            '›' => 0x9B,
+            'œ' => 0x9C,
+            'ž' => 0x9E,
+            'Ÿ' => 0x9F,
+            _ => b'?',
+        },
+    }
 }
 
 fn escape_csv(s: &str) -> String {
@@ -81,73 +388,132 @@ fn escape_csv(s: &str) -> String {
     }
 }
 
-fn export_json(result: &QueryResult) -> String {
-    let rows: Vec<serde_json::Value> = result
-        .rows
+/// JSON shape of a column, derived from its database type name.
+#[derive(Clone, Copy, PartialEq)]
+enum JsonColumnKind {
+    Integer,
+    Float,
+    Bool,
+    Json,
+    Text,
+}
+
+fn json_column_kinds(result: &QueryResult) -> Vec<JsonColumnKind> {
+    (0..result.columns.len())
+        .map(|i| {
+            result
+                .column_types
+                .get(i)
+                .map(|t| json_column_kind(t))
+                .unwrap_or(JsonColumnKind::Text)
+        })
+        .collect()
+}
+
+/// Row `index` as a JSON object keyed by column name.
+fn json_row(result: &QueryResult, kinds: &[JsonColumnKind], index: usize) -> serde_json::Value {
+    let row = &result.rows[index];
+    let obj: serde_json::Map<String, serde_json::Value> = result
+        .columns
         .iter()
-        .map(|row| {
-            let obj: serde_json::Map<String, serde_json::Value> = result
-                .columns
-                .iter()
-                .zip(row.iter())
-                .map(|(col, val)| {
-                    let json_val = if val == "NULL" {
-                        serde_json::Value::Null
-                    } else if let Ok(n) = val.parse::<i64>() {
-                        serde_json::Value::Number(n.into())
-                    } else if let Ok(n) = val.parse::<f64>() {
-                        serde_json::Number::from_f64(n)
-                            .map(serde_json::Value::Number)
-                            .unwrap_or(serde_json::Value::String(val.clone()))
-                    } else if val == "true" {
-                        serde_json::Value::Bool(true)
-                    } else if val == "false" {
-                        serde_json::Value::Bool(false)
-                    } else {
-                        serde_json::Value::String(val.clone())
-                    };
-                    (col.clone(), json_val)
-                })
-                .collect();
-            serde_json::Value::Object(obj)
+        .zip(row.iter())
+        .zip(kinds.iter())
+        .enumerate()
+        .map(|(col_idx, ((col, val), kind))| {
+            let value = if result.is_null(index, col_idx) {
+                serde_json::Value::Null
+            } else {
+                typed_json_value(val, *kind)
+            };
+            (col.clone(), value)
         })
         .collect();
-
-    serde_json::to_string_pretty(&rows).unwrap_or_default()
+    serde_json::Value::Object(obj)
 }
 
-fn export_xml(result: &QueryResult) -> String {
-    let mut output = String::with_capacity(result.rows.len() * 200);
-    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<results>\n");
-
-    for row in &result.rows {
-        output.push_str("  <row>\n");
-        for (col, val) in result.columns.iter().zip(row.iter()) {
-            let safe_col = sanitize_xml_tag(col);
-            let escaped_val = escape_xml(val);
-            output.push_str(&format!(
-                "    <{}>{}</{}>\n",
-                safe_col, escaped_val, safe_col
-            ));
+fn json_column_kind(type_name: &str) -> JsonColumnKind {
+    let type_name = type_name.to_ascii_uppercase();
+    let base = type_name
+        .split(|c: char| c == '(' || c == ' ')
+        .next()
+        .unwrap_or_default();
+    match base {
+        "INT2" | "INT4" | "INT8" | "SMALLINT" | "INT" | "INTEGER" | "BIGINT" | "TINYINT"
+        | "MEDIUMINT" | "SMALLSERIAL" | "SERIAL" | "BIGSERIAL" | "YEAR" => JsonColumnKind::Integer,
+        "FLOAT4" | "FLOAT8" | "REAL" | "FLOAT" | "DOUBLE" | "NUMERIC" | "DECIMAL" => {
+            JsonColumnKind::Float
         }
-        output.push_str("  </row>\n");
+        "BOOL" | "BOOLEAN" => JsonColumnKind::Bool,
+        "JSON" | "JSONB" => JsonColumnKind::Json,
+        _ => JsonColumnKind::Text,
     }
+}
 
-    output.push_str("</results>");
-    output
+/// Convert a formatted cell back to JSON, keeping the string when it doesn't parse
+/// (e.g. a truncated value).
+fn typed_json_value(value: &str, kind: JsonColumnKind) -> serde_json::Value {
+    let typed = match kind {
+        JsonColumnKind::Integer => value
+            .parse::<i64>()
+            .map(serde_json::Value::from)
+            .or_else(|_| value.parse::<u64>().map(serde_json::Value::from))
+            .ok(),
+        JsonColumnKind::Float => value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(serde_json::Value::Number),
+        JsonColumnKind::Bool => match value {
+            "true" | "1" => Some(serde_json::Value::Bool(true)),
+            "false" | "0" => Some(serde_json::Value::Bool(false)),
+            _ => None,
+        },
+        JsonColumnKind::Json => serde_json::from_str(value).ok(),
+        JsonColumnKind::Text => None,
+    };
+    typed.unwrap_or_else(|| serde_json::Value::String(value.to_string()))
 }
 
-fn sanitize_xml_tag(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    for (i, c) in s.chars().enumerate() {
-        if i == 0 && c.is_ascii_digit() {
-            result.push('_');
-        }
-        if c.is_alphanumeric() || c == '_' || c == '-' {
-            result.push(c);
-        } else {
-            result.push('_');
-        }
+/// Sanitized, unique element/attribute names for the columns.
+fn xml_column_names(columns: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    columns
+        .iter()
+        .map(|col| {
+            let base = sanitize_xml_name(col);
+            let mut name = base.clone();
+            let mut n = 2;
+            while !seen.insert(name.clone()) {
+                name = format!("{}_{}", base, n);
+                n += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+/// Turn an arbitrary column name like `count(*)` or `2fa` into a valid XML name.
+fn sanitize_xml_name(s: &str) -> String {
+    let mut result: String = s
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric()
+                || matches!(c, '_' | '-' | '.')
+                || (!c.is_ascii() && c.is_alphanumeric())
+            {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let starts_badly = result
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_digit() || c == '-' || c == '.');
+    // Names starting with "xml" are reserved
+    if starts_badly || result.to_ascii_lowercase().starts_with("xml") {
+        result.insert(0, '_');
     }
     if result.is_empty() {
         result.push_str("column");
@@ -0,0 +1,170 @@
+use super::{json_column_kinds, JsonColumnKind, EXPORT_CHUNK_ROWS};
+use crate::db::QueryResult;
+use duckdb::types::Value;
+use duckdb::{appender_params_from_iter, Connection};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use uuid::Uuid;
+
+/// Write the result to a Parquet file at `path` through an in-memory DuckDB database,
+/// counting rows in `rows_written`. Returns false when stopped through `cancel`.
+pub(super) fn write_parquet(
+    result: &QueryResult,
+    path: &Path,
+    rows_written: &AtomicUsize,
+    cancel: &AtomicBool,
+) -> Result<bool, String> {
+    let kinds = parquet_column_kinds(result);
+    let columns = unique_column_names(&result.columns)
+        .iter()
+        .zip(&kinds)
+        .map(|(name, kind)| format!("{} {}", quote_identifier(name), kind.sql_type()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+    conn.execute_batch(&format!("CREATE TABLE rows ({})", columns))
+        .map_err(|e| e.to_string())?;
+
+    {
+        let mut appender = conn.appender("rows").map_err(|e| e.to_string())?;
+        for (chunk_idx, chunk) in result.rows.chunks(EXPORT_CHUNK_ROWS).enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(false);
+            }
+            for (offset, row) in chunk.iter().enumerate() {
+                let index = chunk_idx * EXPORT_CHUNK_ROWS + offset;
+                let values = row
+                    .iter()
+                    .zip(&kinds)
+                    .enumerate()
+                    .map(|(col, (val, kind))| {
+                        if result.is_null(index, col) {
+                            Value::Null
+                        } else {
+                            kind.value(val).unwrap_or_else(|| Value::Text(val.clone()))
+                        }
+                    });
+                appender
+                    .append_row(appender_params_from_iter(values))
+                    .map_err(|e| e.to_string())?;
+            }
+            rows_written.fetch_add(chunk.len(), Ordering::Relaxed);
+        }
+        appender.flush().map_err(|e| e.to_string())?;
+    }
+
+    if cancel.load(Ordering::Relaxed) {
+        return Ok(false);
+    }
+    let path = path.to_string_lossy().replace('\'', "''");
+    conn.execute_batch(&format!("COPY rows TO '{}' (FORMAT PARQUET)", path))
+        .map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// Parquet type of a column.
+#[derive(Clone, Copy, PartialEq)]
+enum ParquetColumnKind {
+    BigInt,
+    Double,
+    Boolean,
+    Varchar,
+}
+
+impl ParquetColumnKind {
+    fn sql_type(&self) -> &'static str {
+        match self {
+            ParquetColumnKind::BigInt => "BIGINT",
+            ParquetColumnKind::Double => "DOUBLE",
+            ParquetColumnKind::Boolean => "BOOLEAN",
+            ParquetColumnKind::Varchar => "VARCHAR",
+        }
+    }
+
+    /// The typed value of a formatted cell, or None when it doesn't parse.
+    fn value(&self, value: &str) -> Option<Value> {
+        match self {
+            ParquetColumnKind::BigInt => value.parse::<i64>().ok().map(Value::BigInt),
+            ParquetColumnKind::Double => value.parse::<f64>().ok().map(Value::Double),
+            ParquetColumnKind::Boolean => match value {
+                "true" | "1" => Some(Value::Boolean(true)),
+                "false" | "0" => Some(Value::Boolean(false)),
+                _ => None,
+            },
+            ParquetColumnKind::Varchar => Some(Value::Text(value.to_string())),
+        }
+    }
+}
+
+/// Typed columns keep their type only when every non-NULL cell parses, so values like
+/// truncated text or integers beyond BIGINT fall back to VARCHAR instead of being lost.
+fn parquet_column_kinds(result: &QueryResult) -> Vec<ParquetColumnKind> {
+    json_column_kinds(result)
+        .into_iter()
+        .enumerate()
+        .map(|(col, kind)| {
+            let kind = match kind {
+                JsonColumnKind::Integer => ParquetColumnKind::BigInt,
+                JsonColumnKind::Float => ParquetColumnKind::Double,
+                JsonColumnKind::Bool => ParquetColumnKind::Boolean,
+                JsonColumnKind::Json | JsonColumnKind::Text => ParquetColumnKind::Varchar,
+            };
+            let all_parse = result.rows.iter().enumerate().all(|(index, row)| {
+                result.is_null(index, col) || row.get(col).is_some_and(|v| kind.value(v).is_some())
+            });
+            if all_parse {
+                kind
+            } else {
+                ParquetColumnKind::Varchar
+            }
+        })
+        .collect()
+}
+
+/// Column names with duplicates numbered, as Parquet requires unique names.
+fn unique_column_names(columns: &[String]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    columns
+        .iter()
+        .map(|col| {
+            let base = if col.is_empty() { "column" } else { col };
+            let mut name = base.to_string();
+            let mut n = 2;
+            while !seen.insert(name.to_lowercase()) {
+                name = format!("{}_{}", base, n);
+                n += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Write the Parquet file to a temporary path and copy it into `out`, for destinations
+/// without a local path. DuckDB can only write Parquet to a file.
+pub(super) fn stream_parquet(
+    result: &QueryResult,
+    out: &mut impl Write,
+    rows_written: &AtomicUsize,
+    cancel: &AtomicBool,
+) -> std::io::Result<bool> {
+    let path = std::env::temp_dir().join(format!("fbench-export-{}.parquet", Uuid::new_v4()));
+    let copied = write_parquet(result, &path, rows_written, cancel)
+        .map_err(std::io::Error::other)
+        .and_then(|written| {
+            if written {
+                std::io::copy(&mut File::open(&path)?, out)?;
+                out.flush()?;
+            }
+            Ok(written)
+        });
+    let _ = std::fs::remove_file(&path);
+    copied
+}
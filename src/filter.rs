@@ -112,6 +112,9 @@ pub struct FilterState {
     pub filters: Vec<ColumnFilter>,
     pub sort: Option<SortColumn>,
     pub limit: usize,
+    /// Soft delete column of the table; rows with it set are hidden unless `show_deleted`
+    pub soft_delete_column: Option<String>,
+    pub show_deleted: bool,
 }
 
 impl FilterState {
@@ -121,6 +124,8 @@ impl FilterState {
             filters: vec![],
             sort: None,
             limit: 100,
+            soft_delete_column: None,
+            show_deleted: false,
         }
     }
 
@@ -134,23 +139,31 @@ impl FilterState {
             .filter(|f| !f.column.is_empty())
             .collect();
 
-        if !active_filters.is_empty() {
+        let mut clauses: Vec<String> = active_filters
+            .iter()
+            .map(|f| {
+                if f.operator.needs_value() {
+                    format!(
+                        "{} {} '{}'",
+                        f.column,
+                        f.operator.sql_operator(),
+                        f.value.replace('\'', "''")
+                    )
+                } else {
+                    format!("{} {}", f.column, f.operator.sql_operator())
+                }
+            })
+            .collect();
+        if let Some(column) = self
+            .soft_delete_column
+            .as_ref()
+            .filter(|_| !self.show_deleted)
+        {
+            clauses.push(format!("{} IS NULL", column));
+        }
+
+        if !clauses.is_empty() {
             sql.push_str(" WHERE ");
-            let clauses: Vec<String> = active_filters
-                .iter()
-                .map(|f| {
-                    if f.operator.needs_value() {
-                        format!(
-                            "{} {} '{}'",
-                            f.column,
-                            f.operator.sql_operator(),
-                            f.value.replace('\'', "''")
-                        )
-                    } else {
-                        format!("{} {}", f.column, f.operator.sql_operator())
-                    }
-                })
-                .collect();
             sql.push_str(&clauses.join(" AND "));
         }
 
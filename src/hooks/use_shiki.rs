@@ -1,19 +1,84 @@
+use crate::db::SqlDialect;
 use dioxus::prelude::*;
-use serde_json::Value;
+use serde_json::{json, Value};
+use std::ops::Range;
+
+/// Colors given to table aliases and CTE names, picked to stand out from the nord theme
+const ALIAS_COLORS: [&str; 8] = [
+    "#d08770", "#b48ead", "#a3be8c", "#ebcb8b", "#88c0d0", "#bf616a", "#8fbcbb", "#5e81ac",
+];
+
+/// TextMate grammar layering ClickHouse keywords, functions and types over plain SQL.
+const CLICKHOUSE_GRAMMAR: &str = r##"{
+    "name": "clickhouse",
+    "scopeName": "source.clickhouse",
+    "embeddedLangs": ["sql"],
+    "patterns": [
+        { "include": "#keywords" },
+        { "include": "#functions" },
+        { "include": "#types" },
+        { "include": "source.sql" }
+    ],
+    "repository": {
+        "keywords": {
+            "name": "keyword.other.clickhouse",
+            "match": "(?i)\\b(FINAL|PREWHERE|SAMPLE|ARRAY\\s+JOIN|LIMIT\\s+\\d+\\s+BY|GLOBAL|ASOF|SETTINGS|FORMAT|ENGINE|PARTITION\\s+BY|TTL|OPTIMIZE|CODEC|MATERIALIZED|ALIAS)\\b"
+        },
+        "functions": {
+            "name": "support.function.clickhouse",
+            "match": "\\b(to[A-Z]\\w*|array[A-Z]\\w*|arrayJoin|uniq\\w*|\\w+If|groupArray\\w*|quantiles?\\w*|argM(in|ax)|any(Last|Heavy)?|dictGet\\w*|JSONExtract\\w*|formatDateTime|parseDateTime\\w*|now64)(?=\\s*\\()"
+        },
+        "types": {
+            "name": "storage.type.clickhouse",
+            "match": "\\b(U?Int(8|16|32|64|128|256)|Float(32|64)|Decimal(32|64|128|256)?|FixedString|String|UUID|Date32|DateTime64|DateTime|Enum(8|16)|Array|Tuple|Map|Nullable|LowCardinality|IPv4|IPv6|Bool)\\b"
+        }
+    }
+}"##;
+
+/// Shiki language for the connected database's SQL.
+pub fn shiki_language(dialect: SqlDialect) -> &'static str {
+    match dialect {
+        SqlDialect::ClickHouse => "clickhouse",
+        _ => "sql",
+    }
+}
+
+/// Shiki decorations coloring the `names` char ranges of `code`. Shiki offsets count
+/// UTF-16 units, as JavaScript strings do.
+fn name_decorations(code: &str, names: &[(Range<usize>, usize)]) -> Value {
+    let mut offsets = Vec::with_capacity(code.len() + 1);
+    let mut offset = 0;
+    for c in code.chars() {
+        offsets.push(offset);
+        offset += c.len_utf16();
+    }
+    offsets.push(offset);
+    let decorations: Vec<Value> = names
+        .iter()
+        .filter(|(range, _)| range.end < offsets.len())
+        .map(|(range, color)| {
+            json!({
+                "start": offsets[range.start],
+                "end": offsets[range.end],
+                "properties": { "style": format!("color:{}", ALIAS_COLORS[color % ALIAS_COLORS.len()]) },
+            })
+        })
+        .collect();
+    Value::Array(decorations)
+}
 
 pub struct ShikiHighlighter;
 
 impl ShikiHighlighter {
     pub async fn new() -> Result<Self, document::EvalError> {
-        let mut eval = document::eval(
-            r#"
+        let script = r#"
             try {
                 if (typeof window.shikiHighlighter === 'undefined') {
                     const shiki = await import('https://esm.sh/shiki@3.0.0');
                     
                     const highlighter = await shiki.createHighlighter({
                         themes: ['nord'],
-                        langs: ['sql'],
+                        langs: ['sql', CLICKHOUSE_GRAMMAR],
                     });
                     
                     window.shikiHighlighter = highlighter;
@@ -24,8 +89,9 @@ impl ShikiHighlighter {
                 console.error('Shiki initialization error:', err);
                 dioxus.send({ success: false, error: err.toString() });
             }
-        "#,
-        );
+        "#
+        .replace("CLICKHOUSE_GRAMMAR", CLICKHOUSE_GRAMMAR);
+        let mut eval = document::eval(&script);
 
         let result = eval.recv::<Value>().await?;
         if result["success"].as_bool() != Some(true) {
@@ -39,7 +105,14 @@ impl ShikiHighlighter {
         Ok(Self)
     }
 
-    pub async fn highlight(&self, code: &str) -> Result<String, document::EvalError> {
+    /// Highlight `code`, giving each of the `names` char ranges its color from
+    /// `ALIAS_COLORS`.
+    pub async fn highlight(
+        &self,
+        code: &str,
+        lang: &str,
+        names: &[(Range<usize>, usize)],
+    ) -> Result<String, document::EvalError> {
         // Escape special characters for JavaScript template literal
         let escaped_code = code
             .replace('\\', "\\\\")
@@ -48,14 +121,20 @@ impl ShikiHighlighter {
             .replace('"', "\\\"")
             .replace('\n', "\\n")
             .replace('\r', "\\r");
+        let decorations = name_decorations(code, names);
 
         let mut eval = document::eval(&format!(
             r#"
             try {{
-                const html = window.shikiHighlighter.codeToHtml(`{escaped_code}`, {{
-                    lang: 'sql',
-                    theme: window.shikiTheme,
-                }});
+                const code = `{escaped_code}`;
+                const options = {{ lang: '{lang}', theme: window.shikiTheme }};
+                let html;
+                try {{
+                    html = window.shikiHighlighter.codeToHtml(code, {{ ...options, decorations: {decorations} }});
+                }} catch (err) {{
+                    // Name colors are a nicety; plain highlighting still beats none
+                    html = window.shikiHighlighter.codeToHtml(code, options);
+                }}
                 dioxus.send({{ success: true, html: html }});
             }} catch (err) {{
                 console.error('Shiki highlight error:', err);
@@ -107,13 +186,18 @@ pub fn use_shiki() -> UseShiki {
 }
 
 impl UseShiki {
-    pub async fn highlight(&self, code: &str) -> Option<String> {
+    pub async fn highlight(
+        &self,
+        code: &str,
+        lang: &str,
+        names: &[(Range<usize>, usize)],
+    ) -> Option<String> {
         if !*self.ready.read() {
             return None;
         }
         let highlighter = self.highlighter.read();
         if let Some(ref h) = *highlighter {
-            match h.highlight(code).await {
+            match h.highlight(code, lang, names).await {
                 Ok(html) => Some(html),
                 Err(e) => {
                     tracing::error!("Highlight error: {:?}", e);
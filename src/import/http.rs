@@ -0,0 +1,60 @@
+use super::{parse_json_str, ImportData, ImportError};
+
+/// JSON endpoint to pull import rows from, e.g. an API returning fixtures.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HttpSource {
+    pub url: String,
+    /// One `Name: value` header per line
+    pub headers: String,
+    /// Sent as `Authorization: Bearer <token>` when not empty
+    pub bearer_token: String,
+}
+
+impl HttpSource {
+    fn header_pairs(&self) -> Result<Vec<(String, String)>, ImportError> {
+        self.headers
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.split_once(':')
+                    .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                    .ok_or_else(|| {
+                        ImportError::RequestError(format!("Header '{}' is not Name: value", line))
+                    })
+            })
+            .collect()
+    }
+}
+
+/// GET the URL and parse the JSON body the same way as an imported .json file.
+pub async fn fetch_json(source: &HttpSource) -> Result<ImportData, ImportError> {
+    let url = reqwest::Url::parse(source.url.trim())
+        .map_err(|e| ImportError::RequestError(e.to_string()))?;
+    let mut request = reqwest::Client::new()
+        .get(url.clone())
+        .header(reqwest::header::ACCEPT, "application/json");
+    for (name, value) in source.header_pairs()? {
+        request = request.header(name, value);
+    }
+    if !source.bearer_token.trim().is_empty() {
+        request = request.bearer_auth(source.bearer_token.trim());
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ImportError::RequestError(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(ImportError::RequestError(format!(
+            "{} returned {}",
+            url,
+            response.status()
+        )));
+    }
+    let body = response
+        .text()
+        .await
+        .map_err(|e| ImportError::RequestError(e.to_string()))?;
+    parse_json_str(&body)
+}
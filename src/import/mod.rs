@@ -1,6 +1,15 @@
+use crate::db::{ConflictStrategy, TableImport};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
-#[derive(Debug, Clone)]
+mod http;
+mod watch;
+
+pub use http::*;
+pub use watch::*;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ImportData {
     pub columns: Vec<String>,
     pub rows: Vec<Vec<String>>,
@@ -10,6 +19,7 @@ pub struct ImportData {
 pub enum ImportError {
     IoError(String),
     ParseError(String),
+    RequestError(String),
     EmptyFile,
 }
 
@@ -18,6 +28,7 @@ impl std::fmt::Display for ImportError {
         match self {
             Self::IoError(e) => write!(f, "IO error: {}", e),
             Self::ParseError(e) => write!(f, "Parse error: {}", e),
+            Self::RequestError(e) => write!(f, "Request failed: {}", e),
             Self::EmptyFile => write!(f, "File is empty"),
         }
     }
@@ -33,6 +44,7 @@ pub fn parse_file(path: &Path) -> Result<ImportData, ImportError> {
     match ext.as_str() {
         "csv" => parse_csv(path),
         "json" => parse_json(path),
+        "parquet" => parse_parquet(path),
         _ => Err(ImportError::ParseError(format!(
             "Unsupported file type: .{}",
             ext
@@ -71,9 +83,25 @@ fn parse_csv(path: &Path) -> Result<ImportData, ImportError> {
 
 fn parse_json(path: &Path) -> Result<ImportData, ImportError> {
     let content = std::fs::read_to_string(path).map_err(|e| ImportError::IoError(e.to_string()))?;
+    parse_json_str(&content)
+}
+
+/// An array of objects, one row per object. An object wrapping such an array,
+/// as in `{"data": [...]}`, is unwrapped to its first array field.
+fn parse_json_str(content: &str) -> Result<ImportData, ImportError> {
+    let value: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| ImportError::ParseError(e.to_string()))?;
+    let value = match value {
+        serde_json::Value::Object(object) => object
+            .into_iter()
+            .map(|(_, v)| v)
+            .find(|v| v.is_array())
+            .ok_or_else(|| ImportError::ParseError("No array of objects found".to_string()))?,
+        value => value,
+    };
 
     let array: Vec<serde_json::Map<String, serde_json::Value>> =
-        serde_json::from_str(&content).map_err(|e| ImportError::ParseError(e.to_string()))?;
+        serde_json::from_value(value).map_err(|e| ImportError::ParseError(e.to_string()))?;
 
     if array.is_empty() {
         return Err(ImportError::EmptyFile);
@@ -99,6 +127,50 @@ fn parse_json(path: &Path) -> Result<ImportData, ImportError> {
     Ok(ImportData { columns, rows })
 }
 
+/// Read a Parquet file through an in-memory DuckDB database, casting every column to text.
+fn parse_parquet(path: &Path) -> Result<ImportData, ImportError> {
+    let conn =
+        duckdb::Connection::open_in_memory().map_err(|e| ImportError::IoError(e.to_string()))?;
+    let path = path.to_string_lossy().to_string();
+
+    let columns: Vec<String> = conn
+        .prepare("SELECT column_name FROM (DESCRIBE SELECT * FROM read_parquet(?))")
+        .and_then(|mut stmt| {
+            stmt.query_map([&path], |row| row.get(0))?
+                .collect::<duckdb::Result<_>>()
+        })
+        .map_err(|e| ImportError::ParseError(e.to_string()))?;
+    if columns.is_empty() {
+        return Err(ImportError::EmptyFile);
+    }
+
+    let select = columns
+        .iter()
+        .map(|c| format!("CAST(\"{}\" AS VARCHAR)", c.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rows: Vec<Vec<String>> = conn
+        .prepare(&format!("SELECT {} FROM read_parquet(?)", select))
+        .and_then(|mut stmt| {
+            stmt.query_map([&path], |row| {
+                (0..columns.len())
+                    .map(|i| {
+                        row.get::<_, Option<String>>(i)
+                            .map(|v| v.unwrap_or_else(|| "NULL".to_string()))
+                    })
+                    .collect()
+            })?
+            .collect::<duckdb::Result<_>>()
+        })
+        .map_err(|e| ImportError::ParseError(e.to_string()))?;
+
+    if rows.is_empty() {
+        return Err(ImportError::EmptyFile);
+    }
+
+    Ok(ImportData { columns, rows })
+}
+
 /// Validate import columns against a target table's columns.
 /// Returns a list of (file_column_index, table_column_name) mappings.
 pub fn auto_map_columns(
@@ -117,3 +189,167 @@ pub fn auto_map_columns(
     }
     mapping
 }
+
+/// Conversion applied to a file value before it is inserted.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ImportCoercion {
+    #[default]
+    None,
+    Trim,
+    /// Empty strings become NULL
+    EmptyAsNull,
+    /// Strip thousands separators, spaces and currency symbols: "$1,234.50" -> 1234.50
+    Number,
+    /// yes/no, y/n, 1/0, on/off -> true/false
+    Boolean,
+    /// 31/12/2024 -> 2024-12-31
+    DateDayFirst,
+    /// 12/31/2024 -> 2024-12-31
+    DateMonthFirst,
+}
+
+impl ImportCoercion {
+    pub const ALL: [ImportCoercion; 7] = [
+        ImportCoercion::None,
+        ImportCoercion::Trim,
+        ImportCoercion::EmptyAsNull,
+        ImportCoercion::Number,
+        ImportCoercion::Boolean,
+        ImportCoercion::DateDayFirst,
+        ImportCoercion::DateMonthFirst,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportCoercion::None => "As is",
+            ImportCoercion::Trim => "Trim",
+            ImportCoercion::EmptyAsNull => "Empty as NULL",
+            ImportCoercion::Number => "Number",
+            ImportCoercion::Boolean => "Boolean",
+            ImportCoercion::DateDayFirst => "Date (DD/MM/YYYY)",
+            ImportCoercion::DateMonthFirst => "Date (MM/DD/YYYY)",
+        }
+    }
+
+    /// Convert `value`, leaving it unchanged when it doesn't fit so the database reports it.
+    pub fn apply(&self, value: &str) -> String {
+        if value == "NULL" {
+            return value.to_string();
+        }
+        let trimmed = value.trim();
+        let converted = match self {
+            ImportCoercion::None => None,
+            ImportCoercion::Trim => Some(trimmed.to_string()),
+            ImportCoercion::EmptyAsNull => trimmed.is_empty().then(|| "NULL".to_string()),
+            ImportCoercion::Number => {
+                let digits: String = trimmed
+                    .chars()
+                    .filter(|c| c.is_ascii_digit() || matches!(c, '.' | '-'))
+                    .collect();
+                digits.parse::<f64>().is_ok().then_some(digits)
+            }
+            ImportCoercion::Boolean => match trimmed.to_lowercase().as_str() {
+                "yes" | "y" | "1" | "on" | "true" | "t" => Some("true".to_string()),
+                "no" | "n" | "0" | "off" | "false" | "f" => Some("false".to_string()),
+                _ => None,
+            },
+            ImportCoercion::DateDayFirst => {
+                parse_date(trimmed, &["%d/%m/%Y", "%d-%m-%Y", "%d.%m.%Y"])
+            }
+            ImportCoercion::DateMonthFirst => parse_date(trimmed, &["%m/%d/%Y", "%m-%d-%Y"]),
+        };
+        converted.unwrap_or_else(|| value.to_string())
+    }
+}
+
+fn parse_date(value: &str, formats: &[&str]) -> Option<String> {
+    formats
+        .iter()
+        .find_map(|format| chrono::NaiveDate::parse_from_str(value, format).ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// One file column imported into a table column.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportColumn {
+    /// Header of the file column; profiles match by name so reordered files still map
+    pub source: String,
+    pub target: String,
+    #[serde(default)]
+    pub coercion: ImportCoercion,
+}
+
+/// A remembered import setup for a target table, reused for recurring feeds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportProfile {
+    pub name: String,
+    pub table: String,
+    pub columns: Vec<ImportColumn>,
+    #[serde(default)]
+    pub conflict: ConflictStrategy,
+}
+
+impl ImportProfile {
+    /// Mapping and coercions for a file, by file column index. Columns missing from the file are skipped.
+    pub fn resolve(
+        &self,
+        file_columns: &[String],
+    ) -> (Vec<(usize, String)>, HashMap<usize, ImportCoercion>) {
+        let mut mapping = Vec::new();
+        let mut coercions = HashMap::new();
+        for column in &self.columns {
+            if let Some(idx) = file_columns
+                .iter()
+                .position(|c| c.eq_ignore_ascii_case(&column.source))
+            {
+                mapping.push((idx, column.target.clone()));
+                if column.coercion != ImportCoercion::None {
+                    coercions.insert(idx, column.coercion);
+                }
+            }
+        }
+        (mapping, coercions)
+    }
+
+    /// Rows of `data` as they will be inserted, or an error naming the profile columns
+    /// the file lacks.
+    pub fn table_import(&self, data: &ImportData) -> Result<TableImport, String> {
+        let missing: Vec<&str> = self
+            .columns
+            .iter()
+            .filter(|column| {
+                !data
+                    .columns
+                    .iter()
+                    .any(|c| c.eq_ignore_ascii_case(&column.source))
+            })
+            .map(|column| column.source.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!("Missing column(s): {}", missing.join(", ")));
+        }
+
+        let (mapping, coercions) = self.resolve(&data.columns);
+        Ok(TableImport {
+            table: self.table.clone(),
+            columns: mapping.iter().map(|(_, c)| c.clone()).collect(),
+            rows: data
+                .rows
+                .iter()
+                .map(|row| {
+                    mapping
+                        .iter()
+                        .map(|(idx, _)| {
+                            let value = row.get(*idx).map(String::as_str).unwrap_or("NULL");
+                            coercions
+                                .get(idx)
+                                .map(|c| c.apply(value))
+                                .unwrap_or_else(|| value.to_string())
+                        })
+                        .collect()
+                })
+                .collect(),
+            conflict: self.conflict,
+        })
+    }
+}
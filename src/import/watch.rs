@@ -0,0 +1,212 @@
+use super::parse_file;
+use crate::config::{
+    ImportProfileStore, ImportWatch, ImportWatchStore, ProcessedImportFile, WatchOutcome,
+};
+use crate::db::{DbRequest, TableImport};
+use crate::state::*;
+use dioxus::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::Duration;
+
+const WATCH_INTERVAL_SECS: u64 = 5;
+/// Files changed more recently than this may still be being written
+const SETTLE_SECS: u64 = 2;
+
+/// A file picked up from a watched folder, waiting for approval or being imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchedFile {
+    pub path: PathBuf,
+    pub modified: u64,
+    pub import: TableImport,
+    pub importing: bool,
+}
+
+impl WatchedFile {
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+/// Scan the watched folders every few seconds while connected.
+pub async fn run_import_watch() {
+    loop {
+        tokio::time::sleep(Duration::from_secs(WATCH_INTERVAL_SECS)).await;
+        if matches!(*CONNECTION.read(), ConnectionState::Connected { .. }) {
+            scan_watched_folders().await;
+        }
+    }
+}
+
+/// Look for new files in every watched folder once.
+pub async fn scan_watched_folders() {
+    let store = ImportWatchStore::new();
+    let watches = store.load();
+    if watches.is_empty() {
+        return;
+    }
+    let log = store.load_log();
+    let profiles = ImportProfileStore::new().load();
+
+    for watch in watches {
+        let folder = PathBuf::from(&watch.folder);
+        let files = tokio::task::spawn_blocking(move || new_files(&folder))
+            .await
+            .unwrap_or_default();
+        for (path, modified) in files {
+            let known = log
+                .iter()
+                .any(|entry| entry.modified == modified && Path::new(&entry.path) == path)
+                || WATCHED_FILES
+                    .read()
+                    .iter()
+                    .any(|file| file.path == path && file.modified == modified);
+            if known {
+                continue;
+            }
+
+            let import = profiles
+                .iter()
+                .find(|p| p.table == watch.table && p.name == watch.profile)
+                .ok_or_else(|| format!("Import profile '{}' no longer exists", watch.profile))
+                .and_then(|profile| {
+                    let data = parse_file(&path).map_err(|e| e.to_string())?;
+                    profile.table_import(&data)
+                });
+            match import {
+                Ok(import) => {
+                    let file = WatchedFile {
+                        path,
+                        modified,
+                        import,
+                        importing: false,
+                    };
+                    tracing::info!(
+                        "Watched file ready for {}: {}",
+                        watch.table,
+                        file.file_name()
+                    );
+                    WATCHED_FILES.write().push(file.clone());
+                    if watch.auto_import {
+                        approve_watched_file(&file.path);
+                    }
+                }
+                Err(e) => record_watched_file(&watch, &path, modified, WatchOutcome::Failed(e)),
+            }
+        }
+    }
+}
+
+/// CSV, JSON and Parquet files directly inside `folder` that have stopped changing, with their
+/// modification times.
+fn new_files(folder: &Path) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = std::fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut files: Vec<(PathBuf, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let supported = path.extension().is_some_and(|e| {
+                ["csv", "json", "parquet"]
+                    .iter()
+                    .any(|ext| e.eq_ignore_ascii_case(ext))
+            });
+            if !supported || !path.is_file() {
+                return None;
+            }
+            let modified = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()?
+                .duration_since(UNIX_EPOCH)
+                .ok()?
+                .as_secs();
+            (now.saturating_sub(modified) >= SETTLE_SECS).then_some((path, modified))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Start importing a queued file.
+pub fn approve_watched_file(path: &Path) {
+    let mut files = WATCHED_FILES.write();
+    let Some(file) = files.iter_mut().find(|f| f.path == path && !f.importing) else {
+        return;
+    };
+    file.importing = true;
+    send_db_request(DbRequest::ImportWatchedFile {
+        path: file.path.to_string_lossy().to_string(),
+        import: file.import.clone(),
+    });
+}
+
+/// Drop a queued file without importing it; it won't be picked up again unless it changes.
+pub fn dismiss_watched_file(path: &Path) {
+    let file = {
+        let mut files = WATCHED_FILES.write();
+        let Some(idx) = files.iter().position(|f| f.path == path && !f.importing) else {
+            return;
+        };
+        files.remove(idx)
+    };
+    log_watched_file(&file, WatchOutcome::Dismissed);
+}
+
+/// Log the outcome of an import started by `approve_watched_file`.
+pub fn finish_watched_import(path: &str, rows: Result<usize, String>) {
+    let file = {
+        let mut files = WATCHED_FILES.write();
+        let Some(idx) = files
+            .iter()
+            .position(|f| f.importing && f.path == Path::new(path))
+        else {
+            return;
+        };
+        files.remove(idx)
+    };
+    let outcome = match rows {
+        Ok(rows) => WatchOutcome::Imported(rows),
+        Err(e) => WatchOutcome::Failed(e),
+    };
+    log_watched_file(&file, outcome);
+}
+
+fn log_watched_file(file: &WatchedFile, outcome: WatchOutcome) {
+    record(ProcessedImportFile {
+        path: file.path.to_string_lossy().to_string(),
+        modified: file.modified,
+        table: file.import.table.clone(),
+        processed_at: chrono::Local::now(),
+        outcome,
+    });
+}
+
+fn record_watched_file(watch: &ImportWatch, path: &Path, modified: u64, outcome: WatchOutcome) {
+    record(ProcessedImportFile {
+        path: path.to_string_lossy().to_string(),
+        modified,
+        table: watch.table.clone(),
+        processed_at: chrono::Local::now(),
+        outcome,
+    });
+}
+
+fn record(entry: ProcessedImportFile) {
+    match &entry.outcome {
+        WatchOutcome::Failed(e) => tracing::warn!("Watched file {} failed: {}", entry.path, e),
+        _ => tracing::info!("Watched file {} processed", entry.path),
+    }
+    if let Err(e) = ImportWatchStore::new().record(entry) {
+        tracing::error!("Failed to write import watch log: {}", e);
+    }
+    *IMPORT_WATCH_LOG_REVISION.write() += 1;
+}
@@ -0,0 +1,234 @@
+//! Single running instance. The first instance listens on a local port; later launches
+//! hand their files and `fbench://` links to it and exit instead of opening another window.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Extensions opened from the command line or the OS "Open With"
+pub const OPENABLE_EXTENSIONS: [&str; 2] = ["sql", "fbench"];
+
+/// URL scheme registered for links from runbooks and dashboards
+pub const DEEP_LINK_SCHEME: &str = "fbench";
+
+/// `fbench://connect?name=staging&query=select+1` asks to connect to a saved connection
+/// and load a query. Nothing happens until the user confirms it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeepLink {
+    /// Name of a saved connection
+    pub connection: Option<String>,
+    /// SQL loaded into a new tab; it is never run automatically
+    pub query: Option<String>,
+}
+
+impl DeepLink {
+    pub fn parse(link: &str) -> Result<Self, String> {
+        let url = reqwest::Url::parse(link).map_err(|e| e.to_string())?;
+        if url.scheme() != DEEP_LINK_SCHEME {
+            return Err(format!("Not an {}:// link", DEEP_LINK_SCHEME));
+        }
+        if url.host_str() != Some("connect") {
+            return Err(format!(
+                "Unknown link action '{}'",
+                url.host_str().unwrap_or_default()
+            ));
+        }
+        let mut deep_link = DeepLink {
+            connection: None,
+            query: None,
+        };
+        for (key, value) in url.query_pairs() {
+            let value = value.trim().to_string();
+            if value.is_empty() {
+                continue;
+            }
+            match key.as_ref() {
+                "name" => deep_link.connection = Some(value),
+                "query" => deep_link.query = Some(value),
+                _ => {}
+            }
+        }
+        if deep_link.connection.is_none() && deep_link.query.is_none() {
+            return Err("The link names no connection or query".to_string());
+        }
+        Ok(deep_link)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OpenRequest {
+    File(PathBuf),
+    Link(DeepLink),
+}
+
+impl OpenRequest {
+    /// A command line argument or forwarded line: a link, an openable file, or nothing.
+    fn from_arg(arg: &str) -> Option<Self> {
+        if arg.starts_with(&format!("{}:", DEEP_LINK_SCHEME)) {
+            return match DeepLink::parse(arg) {
+                Ok(link) => Some(OpenRequest::Link(link)),
+                Err(e) => {
+                    tracing::warn!("Ignored link {}: {}", arg, e);
+                    None
+                }
+            };
+        }
+        let path = PathBuf::from(arg);
+        is_openable(&path).then_some(OpenRequest::File(path))
+    }
+}
+
+/// Each request is a batch of files and links; an empty one only brings the window to the front
+type OpenSender = mpsc::UnboundedSender<Vec<OpenRequest>>;
+pub type OpenReceiver = mpsc::UnboundedReceiver<Vec<OpenRequest>>;
+
+static OPEN_SENDER: OnceLock<OpenSender> = OnceLock::new();
+static OPEN_RECEIVER: Mutex<Option<OpenReceiver>> = Mutex::new(None);
+
+/// Holds the port and a token that forwarded requests must present.
+fn instance_file() -> PathBuf {
+    let dir = directories::ProjectDirs::from("com", "fbench", "fbench")
+        .map(|d| d.data_local_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."));
+    std::fs::create_dir_all(&dir).ok();
+    dir.join("instance")
+}
+
+pub fn is_openable(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| OPENABLE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Files and links passed on the command line. Files are made absolute so that
+/// another instance can open them.
+pub fn args_to_open() -> Vec<String> {
+    std::env::args()
+        .skip(1)
+        .filter(|arg| OpenRequest::from_arg(arg).is_some())
+        .map(|arg| {
+            if arg.starts_with(&format!("{}:", DEEP_LINK_SCHEME)) {
+                arg
+            } else {
+                std::fs::canonicalize(&arg)
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or(arg)
+            }
+        })
+        .collect()
+}
+
+/// Send `args` to an instance that is already running.
+/// Returns false when none is, so this process should start the app itself.
+pub fn forward_to_running_instance(args: &[String]) -> bool {
+    let Ok(contents) = std::fs::read_to_string(instance_file()) else {
+        return false;
+    };
+    let Some((port, token)) = contents.trim().split_once(' ') else {
+        return false;
+    };
+    let Ok(port) = port.parse::<u16>() else {
+        return false;
+    };
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&address, Duration::from_millis(500)) else {
+        return false;
+    };
+
+    stream.set_read_timeout(Some(Duration::from_secs(2))).ok();
+
+    let mut message = format!("{}\n", token);
+    for arg in args {
+        message.push_str(arg);
+        message.push('\n');
+    }
+    if stream.write_all(message.as_bytes()).is_err() || stream.shutdown(Shutdown::Write).is_err() {
+        return false;
+    }
+    // The running instance acknowledges once it has read the request
+    let mut ack = String::new();
+    BufReader::new(stream).read_line(&mut ack).is_ok() && ack.trim() == "ok"
+}
+
+/// Become the running instance: listen for files and links forwarded by later launches
+/// and queue `initial` to be opened once the app starts.
+pub fn start_listening(initial: Vec<String>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let initial: Vec<OpenRequest> = initial
+        .iter()
+        .filter_map(|arg| OpenRequest::from_arg(arg))
+        .collect();
+    if !initial.is_empty() {
+        tx.send(initial).ok();
+    }
+    *OPEN_RECEIVER.lock().unwrap() = Some(rx);
+    OPEN_SENDER.set(tx.clone()).ok();
+
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::warn!("Could not listen for other instances: {}", e);
+            return;
+        }
+    };
+    let Ok(port) = listener.local_addr().map(|a| a.port()) else {
+        return;
+    };
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    if let Err(e) = std::fs::write(instance_file(), format!("{} {}", port, token)) {
+        tracing::warn!("Could not record the running instance: {}", e);
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = read_forwarded_files(stream, &token, &tx) {
+                tracing::warn!("Ignored a request from another instance: {}", e);
+            }
+        }
+    });
+}
+
+fn read_forwarded_files(stream: TcpStream, token: &str, tx: &OpenSender) -> Result<(), String> {
+    stream
+        .set_read_timeout(Some(Duration::from_secs(2)))
+        .map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    // The sender closes its side after the last line
+    while reader.read_line(&mut line).map_err(|e| e.to_string())? > 0 {
+        lines.push(line.trim_end_matches(['\r', '\n']).to_string());
+        line.clear();
+    }
+    if lines.first().map(String::as_str) != Some(token) {
+        return Err("Wrong token".to_string());
+    }
+    reader
+        .get_mut()
+        .write_all(b"ok\n")
+        .map_err(|e| e.to_string())?;
+
+    let requests = lines
+        .iter()
+        .skip(1)
+        .filter_map(|l| OpenRequest::from_arg(l))
+        .collect();
+    tx.send(requests).ok();
+    Ok(())
+}
+
+/// Queue a file or link handed over by the OS, e.g. macOS "Open With".
+pub fn request_open(request: OpenRequest) {
+    if let Some(tx) = OPEN_SENDER.get() {
+        tx.send(vec![request]).ok();
+    }
+}
+
+/// Receiver for the files and links to open; taken once by the app.
+pub fn take_open_requests() -> Option<OpenReceiver> {
+    OPEN_RECEIVER.lock().unwrap().take()
+}
@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
-use crate::db::{ConstraintInfo, IndexInfo, SchemaInfo};
+use crate::db::{ConstraintInfo, IndexInfo, QueryResult, RequestId, SchemaInfo, SqlDialect};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub enum LlmProvider {
@@ -12,6 +14,51 @@ pub enum LlmProvider {
     OpenRouter,
 }
 
+/// Seconds a request may take when `LlmConfig::timeout_secs` is unset
+pub const DEFAULT_LLM_TIMEOUT_SECS: u64 = 120;
+/// Retries after a 429 or 5xx answer when `LlmConfig::max_retries` is unset
+pub const DEFAULT_LLM_RETRIES: u32 = 2;
+/// Wait before the first retry; doubled for each one after
+const RETRY_INITIAL_MS: u64 = 1000;
+/// Sample values sent to describe a table are cut at this many characters
+const DESCRIBE_SAMPLE_CELL_CHARS: usize = 80;
+
+/// What a request asks the model to do, so each can have its own system prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LlmAction {
+    Generate,
+    Explain,
+    Optimize,
+    FixError,
+    SuggestQueries,
+    DescribeTable,
+    Translate,
+}
+
+impl LlmAction {
+    pub const ALL: [LlmAction; 7] = [
+        LlmAction::Generate,
+        LlmAction::Explain,
+        LlmAction::Optimize,
+        LlmAction::FixError,
+        LlmAction::SuggestQueries,
+        LlmAction::DescribeTable,
+        LlmAction::Translate,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LlmAction::Generate => "Generate SQL",
+            LlmAction::Explain => "Explain query",
+            LlmAction::Optimize => "Optimize query",
+            LlmAction::FixError => "Fix error",
+            LlmAction::SuggestQueries => "Suggest queries",
+            LlmAction::DescribeTable => "Describe table",
+            LlmAction::Translate => "Convert dialect",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmConfig {
     pub provider: LlmProvider,
@@ -19,6 +66,23 @@ pub struct LlmConfig {
     pub ollama_model: String,
     pub openrouter_key: String,
     pub openrouter_model: String,
+    /// Warn once the month's estimated spend reaches this many dollars
+    #[serde(default)]
+    pub monthly_budget_usd: Option<f64>,
+    /// Sampling parameters; `None` leaves the provider's default
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    /// System prompt sent ahead of each kind of request; missing or blank sends none
+    #[serde(default)]
+    pub system_prompts: HashMap<LlmAction, String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub max_retries: Option<u32>,
 }
 
 impl Default for LlmConfig {
@@ -29,6 +93,13 @@ impl Default for LlmConfig {
             ollama_model: "llama3.2".into(),
             openrouter_key: String::new(),
             openrouter_model: "openai/gpt-4o-mini".into(),
+            monthly_budget_usd: None,
+            temperature: None,
+            max_tokens: None,
+            top_p: None,
+            system_prompts: HashMap::new(),
+            timeout_secs: None,
+            max_retries: None,
         }
     }
 }
@@ -47,6 +118,21 @@ impl LlmConfig {
         fs::write(path, json).map_err(|e| e.to_string())
     }
 
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_LLM_TIMEOUT_SECS).max(1))
+    }
+
+    pub fn retries(&self) -> u32 {
+        self.max_retries.unwrap_or(DEFAULT_LLM_RETRIES)
+    }
+
+    pub fn system_prompt(&self, action: LlmAction) -> Option<&str> {
+        self.system_prompts
+            .get(&action)
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+    }
+
     fn config_path() -> Option<PathBuf> {
         directories::ProjectDirs::from("com", "fbench", "fbench").map(|d| {
             let dir = d.config_dir().to_path_buf();
@@ -61,16 +147,24 @@ pub enum LlmRequest {
     Generate {
         prompt: String,
         schema: SchemaInfo,
+        dialect: SqlDialect,
         config: LlmConfig,
     },
     Explain {
         sql: String,
         config: LlmConfig,
     },
+    /// Part of a query, explained in the light of the statement around it
+    ExplainFragment {
+        fragment: String,
+        context: String,
+        config: LlmConfig,
+    },
     #[allow(dead_code)]
     Optimize {
         sql: String,
         schema: SchemaInfo,
+        dialect: SqlDialect,
         config: LlmConfig,
     },
     #[allow(dead_code)]
@@ -78,12 +172,30 @@ pub enum LlmRequest {
         sql: String,
         error: String,
         schema: SchemaInfo,
+        dialect: SqlDialect,
         config: LlmConfig,
     },
     SuggestQueries {
         table: crate::db::TableInfo,
+        dialect: SqlDialect,
+        config: LlmConfig,
+    },
+    /// What the table stores, judged from its columns and a few sample rows
+    DescribeTable {
+        table: crate::db::TableInfo,
+        sample: QueryResult,
+        dialect: SqlDialect,
+        config: LlmConfig,
+    },
+    /// The same statement written for another dialect
+    Translate {
+        sql: String,
+        from: SqlDialect,
+        to: SqlDialect,
         config: LlmConfig,
     },
+    /// Abort the request in flight; its id gets `LlmResponse::Cancelled`
+    Cancel,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -92,9 +204,48 @@ pub struct QuerySuggestion {
     pub sql: String,
 }
 
+/// SQL keywords that can open a statement in a model's reply
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "SELECT", "WITH", "INSERT", "UPDATE", "DELETE", "MERGE", "CREATE", "ALTER", "DROP", "TRUNCATE",
+    "EXPLAIN", "SHOW", "DESCRIBE", "PRAGMA", "VALUES", "GRANT", "REVOKE", "BEGIN", "COMMIT",
+    "ROLLBACK", "CALL", "COPY",
+];
+
+/// A model's reply split into the SQL it proposes and the prose around it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExtractedSql {
+    /// Every statement found, each ending in `;` when there are several
+    pub sql: String,
+    pub explanation: String,
+}
+
+/// Tokens billed for one or more requests, with the provider's cost estimate.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost_usd: f64,
+}
+
+impl TokenUsage {
+    pub fn add(&mut self, other: TokenUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.cost_usd += other.cost_usd;
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
 #[derive(Debug)]
 pub enum LlmResponse {
-    Generated(String),
+    /// `sql` is empty when the model answered without any SQL
+    Generated {
+        sql: String,
+        explanation: String,
+    },
     Explanation(String),
     Optimization {
         explanation: String,
@@ -105,7 +256,14 @@ pub enum LlmResponse {
         sql: Option<String>,
     },
     QuerySuggestions(Vec<QuerySuggestion>),
+    TableDescription(String),
+    /// `sql` is empty when the model answered without any SQL
+    Translated(String),
     Error(String),
+    /// Sent after a paid request's answer with what it cost
+    Usage(TokenUsage),
+    /// The request was aborted by `LlmRequest::Cancel`
+    Cancelled,
 }
 
 #[derive(Serialize)]
@@ -113,6 +271,19 @@ struct OllamaRequest {
     model: String,
     prompt: String,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    options: OllamaOptions,
+}
+
+#[derive(Serialize)]
+struct OllamaOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
 }
 
 #[derive(Deserialize)]
@@ -124,6 +295,19 @@ struct OllamaResponse {
 struct OpenRouterRequest {
     model: String,
     messages: Vec<ChatMessage>,
+    usage: UsageAccounting,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+}
+
+/// Asks OpenRouter to report token counts and cost in the response
+#[derive(Serialize)]
+struct UsageAccounting {
+    include: bool,
 }
 
 #[derive(Serialize)]
@@ -135,6 +319,18 @@ struct ChatMessage {
 #[derive(Deserialize)]
 struct OpenRouterResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<OpenRouterUsage>,
+}
+
+#[derive(Deserialize)]
+struct OpenRouterUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+    #[serde(default)]
+    cost: Option<f64>,
 }
 
 #[derive(Deserialize)]
@@ -149,47 +345,120 @@ struct MessageContent {
 
 pub struct LlmWorker {
     client: reqwest::Client,
-    request_rx: mpsc::UnboundedReceiver<LlmRequest>,
-    response_tx: mpsc::UnboundedSender<LlmResponse>,
+    request_rx: mpsc::UnboundedReceiver<(RequestId, LlmRequest)>,
+    response_tx: mpsc::UnboundedSender<(RequestId, LlmResponse)>,
+    /// Usage reported by the provider while answering the current request
+    usage: std::sync::Mutex<TokenUsage>,
 }
 
 impl LlmWorker {
     pub fn new(
-        request_rx: mpsc::UnboundedReceiver<LlmRequest>,
-        response_tx: mpsc::UnboundedSender<LlmResponse>,
+        request_rx: mpsc::UnboundedReceiver<(RequestId, LlmRequest)>,
+        response_tx: mpsc::UnboundedSender<(RequestId, LlmResponse)>,
     ) -> Self {
         Self {
             client: reqwest::Client::new(),
             request_rx,
             response_tx,
+            usage: std::sync::Mutex::new(TokenUsage::default()),
         }
     }
 
     pub async fn run(mut self) {
-        while let Some(request) = self.request_rx.recv().await {
-            let response = match request {
-                LlmRequest::Generate {
-                    prompt,
-                    schema,
-                    config,
-                } => self.generate(&prompt, &schema, &config).await,
-                LlmRequest::Explain { sql, config } => self.explain(&sql, &config).await,
-                LlmRequest::Optimize {
-                    sql,
-                    schema,
-                    config,
-                } => self.optimize(&sql, &schema, &config).await,
-                LlmRequest::FixError {
-                    sql,
-                    error,
-                    schema,
-                    config,
-                } => self.fix_error(&sql, &error, &schema, &config).await,
-                LlmRequest::SuggestQueries { table, config } => {
-                    self.suggest_queries(&table, &config).await
-                }
+        // Requests that arrive while one is in flight wait here, except Cancel
+        let (_, closed) = mpsc::unbounded_channel();
+        let mut request_rx = std::mem::replace(&mut self.request_rx, closed);
+        let mut queued = VecDeque::new();
+
+        loop {
+            let next = match queued.pop_front() {
+                Some(next) => Some(next),
+                None => request_rx.recv().await,
+            };
+            let Some((id, request)) = next else {
+                break;
             };
-            let _ = self.response_tx.send(response);
+            if matches!(request, LlmRequest::Cancel) {
+                continue; // nothing in flight
+            }
+
+            let response = tokio::select! {
+                response = self.respond(request) => response,
+                _ = Self::cancelled(&mut request_rx, &mut queued) => LlmResponse::Cancelled,
+            };
+            // Sent back with the request's id so the UI knows which tab or panel asked
+            let _ = self.response_tx.send((id, response));
+
+            let usage = std::mem::take(&mut *self.usage.lock().unwrap());
+            if usage != TokenUsage::default() {
+                let _ = self.response_tx.send((id, LlmResponse::Usage(usage)));
+            }
+        }
+    }
+
+    /// Resolves once a Cancel arrives, queueing any other request received meanwhile.
+    async fn cancelled(
+        request_rx: &mut mpsc::UnboundedReceiver<(RequestId, LlmRequest)>,
+        queued: &mut VecDeque<(RequestId, LlmRequest)>,
+    ) {
+        loop {
+            match request_rx.recv().await {
+                Some((_, LlmRequest::Cancel)) => return,
+                Some(request) => queued.push_back(request),
+                // The app is closing; let the request in flight finish
+                None => std::future::pending::<()>().await,
+            }
+        }
+    }
+
+    async fn respond(&self, request: LlmRequest) -> LlmResponse {
+        match request {
+            LlmRequest::Generate {
+                prompt,
+                schema,
+                dialect,
+                config,
+            } => self.generate(&prompt, &schema, dialect, &config).await,
+            LlmRequest::Explain { sql, config } => self.explain(&sql, &config).await,
+            LlmRequest::ExplainFragment {
+                fragment,
+                context,
+                config,
+            } => self.explain_fragment(&fragment, &context, &config).await,
+            LlmRequest::Optimize {
+                sql,
+                schema,
+                dialect,
+                config,
+            } => self.optimize(&sql, &schema, dialect, &config).await,
+            LlmRequest::FixError {
+                sql,
+                error,
+                schema,
+                dialect,
+                config,
+            } => {
+                self.fix_error(&sql, &error, &schema, dialect, &config)
+                    .await
+            }
+            LlmRequest::SuggestQueries {
+                table,
+                dialect,
+                config,
+            } => self.suggest_queries(&table, dialect, &config).await,
+            LlmRequest::DescribeTable {
+                table,
+                sample,
+                dialect,
+                config,
+            } => self.describe_table(&table, &sample, dialect, &config).await,
+            LlmRequest::Translate {
+                sql,
+                from,
+                to,
+                config,
+            } => self.translate(&sql, from, to, &config).await,
+            LlmRequest::Cancel => LlmResponse::Cancelled,
         }
     }
 
@@ -197,17 +466,24 @@ impl LlmWorker {
         &self,
         user_prompt: &str,
         schema: &SchemaInfo,
+        dialect: SqlDialect,
         config: &LlmConfig,
     ) -> LlmResponse {
-        let prompt = self.build_prompt(user_prompt, schema);
+        let prompt = self.build_prompt(user_prompt, schema, dialect);
 
         let result = match config.provider {
-            LlmProvider::Ollama => self.call_ollama(&prompt, config).await,
-            LlmProvider::OpenRouter => self.call_openrouter(&prompt, config).await,
+            LlmProvider::Ollama => self.call_ollama(&prompt, LlmAction::Generate, config).await,
+            LlmProvider::OpenRouter => {
+                self.call_openrouter(&prompt, LlmAction::Generate, config)
+                    .await
+            }
         };
 
         match result {
-            Ok(sql) => LlmResponse::Generated(sql),
+            Ok(text) => {
+                let ExtractedSql { sql, explanation } = Self::extract_sql(&text);
+                LlmResponse::Generated { sql, explanation }
+            }
             Err(e) => LlmResponse::Error(e),
         }
     }
@@ -220,8 +496,40 @@ impl LlmWorker {
         );
 
         let result = match config.provider {
-            LlmProvider::Ollama => self.call_ollama(&prompt, config).await,
-            LlmProvider::OpenRouter => self.call_openrouter(&prompt, config).await,
+            LlmProvider::Ollama => self.call_ollama(&prompt, LlmAction::Explain, config).await,
+            LlmProvider::OpenRouter => {
+                self.call_openrouter(&prompt, LlmAction::Explain, config)
+                    .await
+            }
+        };
+
+        match result {
+            Ok(text) => LlmResponse::Explanation(text.trim().to_string()),
+            Err(e) => LlmResponse::Error(e),
+        }
+    }
+
+    async fn explain_fragment(
+        &self,
+        fragment: &str,
+        context: &str,
+        config: &LlmConfig,
+    ) -> LlmResponse {
+        let prompt = format!(
+            "Explain what this fragment of a SQL query does in plain English. \
+             Focus on the fragment itself and use the full statement only to \
+             resolve aliases, columns and how its result is used. Be concise \
+             (2-4 sentences).\n\n\
+             Fragment:\n{}\n\nFull statement:\n{}\n\nExplanation:",
+            fragment, context
+        );
+
+        let result = match config.provider {
+            LlmProvider::Ollama => self.call_ollama(&prompt, LlmAction::Explain, config).await,
+            LlmProvider::OpenRouter => {
+                self.call_openrouter(&prompt, LlmAction::Explain, config)
+                    .await
+            }
         };
 
         match result {
@@ -230,10 +538,17 @@ impl LlmWorker {
         }
     }
 
-    async fn optimize(&self, sql: &str, schema: &SchemaInfo, config: &LlmConfig) -> LlmResponse {
+    async fn optimize(
+        &self,
+        sql: &str,
+        schema: &SchemaInfo,
+        dialect: SqlDialect,
+        config: &LlmConfig,
+    ) -> LlmResponse {
         let schema_text = self.format_schema(schema);
         let prompt = format!(
-            "Analyze this SQL query for performance improvements.\n\n\
+            "Analyze this SQL query for performance improvements.\n\
+             {}\n\n\
              Schema:\n{}\n\n\
              Query:\n{}\n\n\
              Provide:\
@@ -242,12 +557,17 @@ impl LlmWorker {
              Format your response as:\
              EXPLANATION: <your explanation>\n\
              SQL: <optimized query or 'NO_CHANGE' if already optimal>",
-            schema_text, sql
+            Self::dialect_line(dialect),
+            schema_text,
+            sql
         );
 
         let result = match config.provider {
-            LlmProvider::Ollama => self.call_ollama(&prompt, config).await,
-            LlmProvider::OpenRouter => self.call_openrouter(&prompt, config).await,
+            LlmProvider::Ollama => self.call_ollama(&prompt, LlmAction::Optimize, config).await,
+            LlmProvider::OpenRouter => {
+                self.call_openrouter(&prompt, LlmAction::Optimize, config)
+                    .await
+            }
         };
 
         match result {
@@ -269,7 +589,7 @@ impl LlmWorker {
             if after.is_empty() || after == "NO_CHANGE" {
                 None
             } else {
-                Some(Self::extract_sql(after))
+                Some(Self::extract_sql(after).sql).filter(|sql| !sql.is_empty())
             }
         });
 
@@ -281,23 +601,31 @@ impl LlmWorker {
         sql: &str,
         error: &str,
         schema: &SchemaInfo,
+        dialect: SqlDialect,
         config: &LlmConfig,
     ) -> LlmResponse {
         let schema_text = self.format_schema(schema);
         let prompt = format!(
-            "This SQL query failed with an error. Explain the problem and provide a fix.\n\n\
+            "This SQL query failed with an error. Explain the problem and provide a fix.\n\
+             {}\n\n\
              Schema:\n{}\n\n\
              Query:\n{}\n\n\
              Error:\n{}\n\n\
              Format your response as:\
              EXPLANATION: <what went wrong>\n\
              SQL: <corrected query>",
-            schema_text, sql, error
+            Self::dialect_line(dialect),
+            schema_text,
+            sql,
+            error
         );
 
         let result = match config.provider {
-            LlmProvider::Ollama => self.call_ollama(&prompt, config).await,
-            LlmProvider::OpenRouter => self.call_openrouter(&prompt, config).await,
+            LlmProvider::Ollama => self.call_ollama(&prompt, LlmAction::FixError, config).await,
+            LlmProvider::OpenRouter => {
+                self.call_openrouter(&prompt, LlmAction::FixError, config)
+                    .await
+            }
         };
 
         match result {
@@ -317,7 +645,7 @@ impl LlmWorker {
         let sql = sql_start
             .map(|i| {
                 let after = &response[i + 4..];
-                Self::extract_sql(after.trim())
+                Self::extract_sql(after.trim()).sql
             })
             .filter(|s| !s.is_empty());
 
@@ -327,6 +655,7 @@ impl LlmWorker {
     async fn suggest_queries(
         &self,
         table: &crate::db::TableInfo,
+        dialect: SqlDialect,
         config: &LlmConfig,
     ) -> LlmResponse {
         let columns: Vec<String> = table
@@ -339,7 +668,8 @@ impl LlmWorker {
             .collect();
 
         let prompt = format!(
-            "Suggest 3 useful SQL queries for this table. Keep labels short (3-5 words).\n\n\
+            "Suggest 3 useful SQL queries for this table. Keep labels short (3-5 words).\n\
+             {}\n\n\
              Table: {}\n\
              Columns:\n{}\n\
              Row estimate: {}\n\n\
@@ -347,14 +677,21 @@ impl LlmWorker {
              LABEL: <short description>\n\
              SQL: <query>\n\
              ---",
+            Self::dialect_line(dialect),
             table.name,
             columns.join("\n"),
             table.row_estimate
         );
 
         let result = match config.provider {
-            LlmProvider::Ollama => self.call_ollama(&prompt, config).await,
-            LlmProvider::OpenRouter => self.call_openrouter(&prompt, config).await,
+            LlmProvider::Ollama => {
+                self.call_ollama(&prompt, LlmAction::SuggestQueries, config)
+                    .await
+            }
+            LlmProvider::OpenRouter => {
+                self.call_openrouter(&prompt, LlmAction::SuggestQueries, config)
+                    .await
+            }
         };
 
         match result {
@@ -363,6 +700,103 @@ impl LlmWorker {
         }
     }
 
+    async fn describe_table(
+        &self,
+        table: &crate::db::TableInfo,
+        sample: &QueryResult,
+        dialect: SqlDialect,
+        config: &LlmConfig,
+    ) -> LlmResponse {
+        let table_text = self.format_schema(&SchemaInfo {
+            tables: vec![table.clone()],
+            ..Default::default()
+        });
+        // Long values say little about the table and cost tokens
+        let sample_rows: Vec<String> = sample
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(
+                        |value| match value.char_indices().nth(DESCRIBE_SAMPLE_CELL_CHARS) {
+                            Some((end, _)) => format!("{}…", &value[..end]),
+                            None => value.clone(),
+                        },
+                    )
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            })
+            .collect();
+
+        let prompt = format!(
+            "Describe what this database table stores, for a developer seeing it for the first time.\n\
+             {}\n\n\
+             {}\
+             Sample rows ({}):\n{}\n{}\n\n\
+             Answer in plain text with three short sections:\n\
+             Purpose: what one row represents and what the table is used for.\n\
+             Pitfalls: surprising values, nullable or overloaded columns, units and time zones.\n\
+             Join keys: columns likely used to join other tables, and which tables.",
+            Self::dialect_line(dialect),
+            table_text,
+            sample_rows.len(),
+            sample.columns.join(" | "),
+            sample_rows.join("\n"),
+        );
+
+        let result = match config.provider {
+            LlmProvider::Ollama => {
+                self.call_ollama(&prompt, LlmAction::DescribeTable, config)
+                    .await
+            }
+            LlmProvider::OpenRouter => {
+                self.call_openrouter(&prompt, LlmAction::DescribeTable, config)
+                    .await
+            }
+        };
+
+        match result {
+            Ok(text) => LlmResponse::TableDescription(text.trim().to_string()),
+            Err(e) => LlmResponse::Error(e),
+        }
+    }
+
+    async fn translate(
+        &self,
+        sql: &str,
+        from: SqlDialect,
+        to: SqlDialect,
+        config: &LlmConfig,
+    ) -> LlmResponse {
+        let prompt = format!(
+            "Convert this {} SQL statement to {}, keeping its meaning. Adjust LIMIT/OFFSET \
+             syntax, identifier and string quoting, casts and functions that differ.\n\
+             {}\n\n\
+             Statement:\n{}\n\n\
+             Return only the converted statement in a ```sql code block.",
+            from.label(),
+            to.label(),
+            Self::dialect_line(to),
+            sql
+        );
+
+        let result = match config.provider {
+            LlmProvider::Ollama => {
+                self.call_ollama(&prompt, LlmAction::Translate, config)
+                    .await
+            }
+            LlmProvider::OpenRouter => {
+                self.call_openrouter(&prompt, LlmAction::Translate, config)
+                    .await
+            }
+        };
+
+        match result {
+            Ok(text) => LlmResponse::Translated(Self::extract_sql(&text).sql),
+            Err(e) => LlmResponse::Error(e),
+        }
+    }
+
     fn parse_suggestions_response(response: &str) -> LlmResponse {
         let mut suggestions = Vec::new();
         let mut current_label = String::new();
@@ -375,7 +809,7 @@ impl LlmWorker {
                 if !current_label.is_empty() && !current_sql.is_empty() {
                     suggestions.push(QuerySuggestion {
                         label: current_label.clone(),
-                        sql: Self::extract_sql(&current_sql),
+                        sql: Self::extract_sql(&current_sql).sql,
                     });
                 }
                 current_label = trimmed.trim_start_matches("LABEL:").trim().to_string();
@@ -388,7 +822,7 @@ impl LlmWorker {
                 if !current_label.is_empty() && !current_sql.is_empty() {
                     suggestions.push(QuerySuggestion {
                         label: current_label.clone(),
-                        sql: Self::extract_sql(&current_sql),
+                        sql: Self::extract_sql(&current_sql).sql,
                     });
                 }
                 current_label.clear();
@@ -404,7 +838,7 @@ impl LlmWorker {
         if !current_label.is_empty() && !current_sql.is_empty() {
             suggestions.push(QuerySuggestion {
                 label: current_label,
-                sql: Self::extract_sql(&current_sql),
+                sql: Self::extract_sql(&current_sql).sql,
             });
         }
 
@@ -493,66 +927,152 @@ impl LlmWorker {
             }
         }
 
+        if !schema.sequences.is_empty() {
+            text.push_str("Sequences:\n");
+            for sequence in &schema.sequences {
+                text.push_str(&format!("  {}\n", sequence));
+            }
+        }
+
         text
     }
 
-    fn build_prompt(&self, user_prompt: &str, schema: &SchemaInfo) -> String {
+    /// Names the server flavor so generated SQL uses the features it actually has.
+    fn dialect_line(dialect: SqlDialect) -> String {
+        format!(
+            "Target database: {}. {}",
+            dialect.label(),
+            dialect.prompt_notes()
+        )
+    }
+
+    fn build_prompt(&self, user_prompt: &str, schema: &SchemaInfo, dialect: SqlDialect) -> String {
         let schema_text = self.format_schema(schema);
 
         format!(
             "You are a SQL expert. Generate a SQL query based on the user's request.\n\
              Only output the raw SQL query, no explanations, no markdown.\n\
+             {}\n\
              Use only tables, views, columns, and relationships listed below.\n\
              When a join is needed, prefer the foreign-key relationships from the schema.\n\n\
              Database schema:\n{}\n\
              User request: {}\n\nSQL:",
-            schema_text, user_prompt
+            Self::dialect_line(dialect),
+            schema_text,
+            user_prompt
         )
     }
 
-    async fn call_ollama(&self, prompt: &str, config: &LlmConfig) -> Result<String, String> {
+    /// Send with the configured timeout, retrying rate limits (429) and server errors (5xx)
+    /// with exponential backoff. The last answer is returned whatever its status.
+    async fn send_with_retry(
+        &self,
+        request: reqwest::RequestBuilder,
+        config: &LlmConfig,
+    ) -> Result<reqwest::Response, String> {
+        let request = request.timeout(config.timeout());
+        let mut delay = Duration::from_millis(RETRY_INITIAL_MS);
+        let mut attempt = 0;
+        loop {
+            let Some(this_try) = request.try_clone() else {
+                return request
+                    .send()
+                    .await
+                    .map_err(|e| format!("Request failed: {}", e));
+            };
+            let response = match this_try.send().await {
+                Ok(response) => response,
+                Err(e) if e.is_timeout() => {
+                    return Err(format!(
+                        "Request timed out after {}s",
+                        config.timeout().as_secs()
+                    ))
+                }
+                Err(e) => return Err(format!("Request failed: {}", e)),
+            };
+            let status = response.status();
+            let retryable =
+                status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error();
+            if !retryable || attempt >= config.retries() {
+                return Ok(response);
+            }
+            attempt += 1;
+            tracing::warn!(
+                "LLM request got {}, retry {} in {:?}",
+                status,
+                attempt,
+                delay
+            );
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+
+    async fn call_ollama(
+        &self,
+        prompt: &str,
+        action: LlmAction,
+        config: &LlmConfig,
+    ) -> Result<String, String> {
         let url = format!("{}/api/generate", config.ollama_url);
 
-        let response = self
-            .client
-            .post(&url)
-            .json(&OllamaRequest {
-                model: config.ollama_model.clone(),
-                prompt: prompt.to_string(),
-                stream: false,
-            })
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
+        let request = self.client.post(&url).json(&OllamaRequest {
+            model: config.ollama_model.clone(),
+            prompt: prompt.to_string(),
+            stream: false,
+            system: config.system_prompt(action).map(str::to_string),
+            options: OllamaOptions {
+                temperature: config.temperature,
+                num_predict: config.max_tokens,
+                top_p: config.top_p,
+            },
+        });
+        let response = self.send_with_retry(request, config).await?;
 
         if !response.status().is_success() {
             return Err(format!("Ollama error: {}", response.status()));
         }
 
         let result: OllamaResponse = response.json().await.map_err(|e| e.to_string())?;
-        Ok(Self::extract_sql(&result.response))
+        Ok(result.response)
     }
 
-    async fn call_openrouter(&self, prompt: &str, config: &LlmConfig) -> Result<String, String> {
+    async fn call_openrouter(
+        &self,
+        prompt: &str,
+        action: LlmAction,
+        config: &LlmConfig,
+    ) -> Result<String, String> {
         if config.openrouter_key.is_empty() {
             return Err("OpenRouter API key not configured".into());
         }
 
-        let response = self
+        let mut messages = Vec::new();
+        if let Some(system) = config.system_prompt(action) {
+            messages.push(ChatMessage {
+                role: "system",
+                content: system.to_string(),
+            });
+        }
+        messages.push(ChatMessage {
+            role: "user",
+            content: prompt.to_string(),
+        });
+
+        let request = self
             .client
             .post("https://openrouter.ai/api/v1/chat/completions")
             .header("Authorization", format!("Bearer {}", config.openrouter_key))
             .header("Content-Type", "application/json")
             .json(&OpenRouterRequest {
                 model: config.openrouter_model.clone(),
-                messages: vec![ChatMessage {
-                    role: "user",
-                    content: prompt.to_string(),
-                }],
-            })
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
+                messages,
+                usage: UsageAccounting { include: true },
+                temperature: config.temperature,
+                max_tokens: config.max_tokens,
+                top_p: config.top_p,
+            });
+        let response = self.send_with_retry(request, config).await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -561,39 +1081,168 @@ impl LlmWorker {
         }
 
         let result: OpenRouterResponse = response.json().await.map_err(|e| e.to_string())?;
+        if let Some(usage) = &result.usage {
+            self.usage.lock().unwrap().add(TokenUsage {
+                prompt_tokens: usage.prompt_tokens,
+                completion_tokens: usage.completion_tokens,
+                cost_usd: usage.cost.unwrap_or(0.0),
+            });
+        }
         let content = result
             .choices
             .first()
             .map(|c| c.message.content.clone())
             .unwrap_or_default();
 
-        Ok(Self::extract_sql(&content))
+        Ok(content)
     }
 
-    fn extract_sql(response: &str) -> String {
-        let trimmed = response.trim();
+    /// Pull SQL out of a reply. Fenced code blocks win; without them the statements
+    /// start at the first line opening with an SQL keyword. Everything else is explanation.
+    fn extract_sql(response: &str) -> ExtractedSql {
+        let (blocks, prose) = Self::split_fenced_blocks(response);
+        if !blocks.is_empty() {
+            return ExtractedSql {
+                sql: Self::join_statements(&blocks),
+                explanation: Self::tidy_prose(&prose),
+            };
+        }
 
-        if let Some(start) = trimmed.find("```sql") {
-            let after = &trimmed[start + 6..];
-            if let Some(end) = after.find("```") {
-                return after[..end].trim().to_string();
+        let lines: Vec<&str> = response.lines().collect();
+        let Some(start) = lines.iter().position(|l| Self::opens_statement(l)) else {
+            return ExtractedSql {
+                sql: String::new(),
+                explanation: response.trim().to_string(),
+            };
+        };
+
+        // The SQL runs on through paragraphs that continue it or open another statement
+        let mut end = start + 1;
+        while end < lines.len() {
+            if lines[end].trim().is_empty() {
+                match lines[end..].iter().position(|l| !l.trim().is_empty()) {
+                    Some(next) if Self::opens_statement(lines[end + next]) => end += next,
+                    _ => break,
+                }
+            } else {
+                end += 1;
+            }
+        }
+
+        let mut sql = lines[start..end].join("\n");
+        let mut after = lines[end..].join("\n");
+        // Commentary after the final semicolon, as in "SELECT 1; This returns one row."
+        if let Some(semicolon) = sql.rfind(';') {
+            let tail = sql[semicolon + 1..].trim().to_string();
+            if !tail.is_empty() && !tail.starts_with("--") && !Self::opens_statement(&tail) {
+                after = format!("{}\n{}", tail, after);
+                sql.truncate(semicolon + 1);
             }
         }
 
-        if let Some(start) = trimmed.find("```") {
-            let after = &trimmed[start + 3..];
-            if let Some(end) = after.find("```") {
-                return after[..end].trim().to_string();
+        let prose = format!("{}\n{}", lines[..start].join("\n"), after);
+        ExtractedSql {
+            sql: sql.trim().to_string(),
+            explanation: Self::tidy_prose(&prose),
+        }
+    }
+
+    /// Code blocks whose language is SQL or unspecified, and the text outside all blocks.
+    fn split_fenced_blocks(response: &str) -> (Vec<String>, String) {
+        let mut blocks = Vec::new();
+        let mut prose = String::new();
+        let mut rest = response;
+        while let Some(open) = rest.find("```") {
+            prose.push_str(&rest[..open]);
+            let after_fence = &rest[open + 3..];
+            let (language, body) = after_fence.split_once('\n').unwrap_or(("", after_fence));
+            let Some(close) = body.find("```") else {
+                // An unclosed fence, as when the reply was cut off
+                rest = "";
+                if Self::is_sql_language(language) {
+                    blocks.push(body.trim().to_string());
+                }
+                break;
+            };
+            if Self::is_sql_language(language) {
+                let block = body[..close].trim().to_string();
+                if !block.is_empty() && !blocks.contains(&block) {
+                    blocks.push(block);
+                }
+            } else {
+                prose.push_str(&body[..close]);
             }
+            rest = &body[close + 3..];
         }
+        prose.push_str(rest);
+        (blocks, prose)
+    }
 
-        trimmed.to_string()
+    fn is_sql_language(language: &str) -> bool {
+        let language = language.trim().to_lowercase();
+        language.is_empty()
+            || language.contains("sql")
+            || matches!(
+                language.as_str(),
+                "postgres"
+                    | "postgresql"
+                    | "mysql"
+                    | "mariadb"
+                    | "sqlite"
+                    | "duckdb"
+                    | "clickhouse"
+            )
+    }
+
+    /// Whether a line starts a statement: a keyword in upper or lower case, since a
+    /// capitalized "With" or "Select" usually begins a sentence.
+    fn opens_statement(line: &str) -> bool {
+        let word: String = line
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_ascii_alphabetic())
+            .collect();
+        (word == word.to_uppercase() || word == word.to_lowercase())
+            && STATEMENT_KEYWORDS.contains(&word.to_uppercase().as_str())
+    }
+
+    fn join_statements(statements: &[String]) -> String {
+        if let [single] = statements {
+            return single.trim().to_string();
+        }
+        statements
+            .iter()
+            .map(|s| {
+                let s = s.trim();
+                if s.ends_with(';') {
+                    s.to_string()
+                } else {
+                    format!("{};", s)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Trim the prose and drop the empty lines left where code blocks were.
+    fn tidy_prose(prose: &str) -> String {
+        let mut tidy = String::new();
+        for paragraph in prose.split("\n\n") {
+            let paragraph = paragraph.trim();
+            if !paragraph.is_empty() {
+                if !tidy.is_empty() {
+                    tidy.push_str("\n\n");
+                }
+                tidy.push_str(paragraph);
+            }
+        }
+        tidy
     }
 }
 
 pub fn spawn_llm_worker() -> (
-    mpsc::UnboundedSender<LlmRequest>,
-    mpsc::UnboundedReceiver<LlmResponse>,
+    mpsc::UnboundedSender<(RequestId, LlmRequest)>,
+    mpsc::UnboundedReceiver<(RequestId, LlmResponse)>,
 ) {
     let (request_tx, request_rx) = mpsc::unbounded_channel();
     let (response_tx, response_rx) = mpsc::unbounded_channel();
@@ -630,6 +1279,13 @@ mod tests {
                         nullable: false,
                         default_value: None,
                         is_primary_key: true,
+                        identity_generation: None,
+                        generation_expression: None,
+                        enum_values: Vec::new(),
+                        collation: None,
+                        comment: None,
+                        column_type: None,
+                        extra: None,
                     },
                     ColumnInfo {
                         name: "customer_id".into(),
@@ -637,6 +1293,13 @@ mod tests {
                         nullable: false,
                         default_value: None,
                         is_primary_key: false,
+                        identity_generation: None,
+                        generation_expression: None,
+                        enum_values: Vec::new(),
+                        collation: None,
+                        comment: None,
+                        column_type: None,
+                        extra: None,
                     },
                 ],
                 indexes: vec![IndexInfo {
@@ -655,11 +1318,18 @@ mod tests {
                     check_clause: None,
                 }],
                 row_estimate: 0,
+                collation: None,
+                comment: None,
             }],
             views: vec!["recent_orders".into()],
+            sequences: Vec::new(),
         };
 
-        let prompt = worker.build_prompt("list recent orders with customer names", &schema);
+        let prompt = worker.build_prompt(
+            "list recent orders with customer names",
+            &schema,
+            SqlDialect::PostgreSQL,
+        );
 
         assert!(
             prompt.contains("FOREIGN KEY: orders_customer_id_fkey (customer_id) -> customers (id)")
@@ -667,4 +1337,29 @@ mod tests {
         assert!(prompt.contains("orders_customer_id_idx [btree] (customer_id)"));
         assert!(prompt.contains("Views:\n  recent_orders"));
     }
+
+    #[test]
+    fn extract_sql_separates_statements_from_prose() {
+        let fenced = "Here is the query:\n\n```sql\nSELECT * FROM orders\n```\n\nAnd the count:\n```\nSELECT count(*) FROM orders;\n```\nBoth use the index.";
+        let extracted = LlmWorker::extract_sql(fenced);
+        assert_eq!(
+            extracted.sql,
+            "SELECT * FROM orders;\n\nSELECT count(*) FROM orders;"
+        );
+        assert_eq!(
+            extracted.explanation,
+            "Here is the query:\n\nAnd the count:\n\nBoth use the index."
+        );
+
+        let chatty = "Sure! With the orders table you can do this:\nSELECT id\nFROM orders; This lists every order.";
+        let extracted = LlmWorker::extract_sql(chatty);
+        assert_eq!(extracted.sql, "SELECT id\nFROM orders;");
+        assert_eq!(
+            extracted.explanation,
+            "Sure! With the orders table you can do this:\nThis lists every order."
+        );
+
+        let prose = LlmWorker::extract_sql("I need to know which table holds customers.");
+        assert!(prose.sql.is_empty());
+    }
 }
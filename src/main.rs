@@ -3,29 +3,61 @@
 mod app;
 mod components;
 mod config;
+mod crash;
 mod db;
 mod export;
 mod filter;
 mod hooks;
 mod import;
+mod instance;
 mod llm;
 mod services;
 mod state;
 
 use app::App;
-use dioxus::desktop::muda::{Menu, MenuItem, PredefinedMenuItem, Submenu};
-use dioxus::desktop::tao::{dpi::LogicalSize, window::Theme};
+use dioxus::desktop::muda::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use dioxus::desktop::tao::{dpi::LogicalSize, event::Event, window::Theme};
 use dioxus::desktop::{Config, WindowBuilder};
 
 fn main() {
+    crash::init_logging();
+    crash::install_panic_hook();
+
+    let args = instance::args_to_open();
+    if instance::forward_to_running_instance(&args) {
+        return;
+    }
+    instance::start_listening(args);
+
     dioxus::LaunchBuilder::desktop()
         .with_cfg(
-            Config::new().with_menu(app_menu()).with_window(
-                WindowBuilder::new()
-                    .with_title("FBench")
-                    .with_theme(Some(Theme::Dark))
-                    .with_inner_size(LogicalSize::new(1440.0, 900.0)),
-            ),
+            Config::new()
+                .with_menu(app_menu())
+                .with_window(
+                    WindowBuilder::new()
+                        .with_title("FBench")
+                        .with_theme(Some(Theme::Dark))
+                        .with_inner_size(LogicalSize::new(1440.0, 900.0)),
+                )
+                .with_custom_event_handler(|event, _| {
+                    // macOS delivers "Open With", double-clicked files and fbench:// links as events
+                    if let Event::Opened { urls } = event {
+                        for url in urls {
+                            if url.scheme() == instance::DEEP_LINK_SCHEME {
+                                match instance::DeepLink::parse(url.as_str()) {
+                                    Ok(link) => {
+                                        instance::request_open(instance::OpenRequest::Link(link))
+                                    }
+                                    Err(e) => tracing::warn!("Ignored link {}: {}", url, e),
+                                }
+                            } else if let Ok(path) = url.to_file_path() {
+                                if instance::is_openable(&path) {
+                                    instance::request_open(instance::OpenRequest::File(path));
+                                }
+                            }
+                        }
+                    }
+                }),
         )
         .launch(App);
 }
@@ -33,6 +65,36 @@ fn main() {
 fn app_menu() -> Menu {
     let menu = Menu::new();
 
+    // macOS makes the first submenu the application menu, whatever its title
+    #[cfg(target_os = "macos")]
+    {
+        let app_menu = Submenu::new("FBench", true);
+        app_menu
+            .append_items(&[
+                &PredefinedMenuItem::about(None, None),
+                &PredefinedMenuItem::separator(),
+                &PredefinedMenuItem::services(None),
+                &PredefinedMenuItem::separator(),
+                &PredefinedMenuItem::hide(None),
+                &PredefinedMenuItem::hide_others(None),
+                &PredefinedMenuItem::show_all(None),
+                &PredefinedMenuItem::separator(),
+                &PredefinedMenuItem::quit(None),
+            ])
+            .unwrap();
+        menu.append(&app_menu).unwrap();
+    }
+
+    let file_menu = Submenu::new("File", true);
+    file_menu
+        .append_items(&[
+            &MenuItem::with_id(components::MENU_OPEN_FILE, "Open...", true, None),
+            &PredefinedMenuItem::separator(),
+            &MenuItem::with_id(components::MENU_SAVE_FILE, "Save", true, None),
+            &MenuItem::with_id(components::MENU_SAVE_FILE_AS, "Save As...", true, None),
+        ])
+        .unwrap();
+
     let edit_menu = Submenu::new("Edit", true);
     edit_menu
         .append_items(&[
@@ -47,11 +109,41 @@ fn app_menu() -> Menu {
         ])
         .unwrap();
 
-    menu.append_items(&[&edit_menu]).unwrap();
+    let tray = config::TrayStore::new().load();
+    let window_menu = Submenu::new("Window", true);
+    window_menu
+        .append_items(&[
+            &CheckMenuItem::with_id(
+                components::MENU_TRAY_ICON,
+                "Show in System Tray",
+                true,
+                tray.show_tray_icon,
+                None,
+            ),
+            &CheckMenuItem::with_id(
+                components::MENU_MINIMIZE_TO_TRAY,
+                "Minimize to Tray",
+                true,
+                tray.minimize_to_tray,
+                None,
+            ),
+        ])
+        .unwrap();
+
+    menu.append_items(&[&file_menu, &edit_menu, &window_menu])
+        .unwrap();
 
-    if cfg!(debug_assertions) {
-        let help_menu = Submenu::new("Help", true);
+    let help_menu = Submenu::new("Help", true);
+    help_menu
+        .append_items(&[&MenuItem::with_id(
+            components::MENU_EXPORT_DIAGNOSTICS,
+            "Export Diagnostics...",
+            true,
+            None,
+        )])
+        .unwrap();
 
+    if cfg!(debug_assertions) {
         help_menu
             .append_items(&[&MenuItem::with_id(
                 "dioxus-toggle-dev-tools",
@@ -69,9 +161,9 @@ fn app_menu() -> Menu {
                 None,
             )])
             .unwrap();
-
-        menu.append_items(&[&help_menu]).unwrap();
     }
 
+    menu.append_items(&[&help_menu]).unwrap();
+
     menu
 }
@@ -1,20 +1,45 @@
 use dioxus::prelude::*;
 use tokio::sync::mpsc;
 
+mod open_files;
+mod team_library;
+
+pub use open_files::open_file;
+pub use team_library::refresh_team_library;
+
+/// Notifications kept in the LISTEN feed
+const MAX_NOTIFICATIONS: usize = 500;
+/// Row changes kept in the change capture viewer
+const MAX_CHANGE_EVENTS: usize = 2000;
+/// Share of the monthly AI budget at which every paid request warns
+const BUDGET_WARNING_FRACTION: f64 = 0.8;
+
 pub type DbSender = mpsc::UnboundedSender<crate::db::DbRequest>;
-pub type LlmSender = mpsc::UnboundedSender<crate::llm::LlmRequest>;
+pub type LlmSender = mpsc::UnboundedSender<(crate::db::RequestId, crate::llm::LlmRequest)>;
 
 pub fn init_services() -> (DbSender, LlmSender) {
     let (db_tx, db_rx) = crate::db::spawn_db_worker();
+    let settings = crate::config::QuerySettingsStore::new().load();
+    let _ = db_tx.send(crate::db::DbRequest::SetDefaultStatementTimeout(
+        settings.statement_timeout_secs,
+    ));
+    let _ = db_tx.send(crate::db::DbRequest::SetAutoLimit(settings.auto_limit));
     let (llm_tx, llm_rx) = crate::llm::spawn_llm_worker();
 
     let db_tx_clone = db_tx.clone();
+    let llm_tx_clone = llm_tx.clone();
     spawn(async move {
-        handle_db_responses(db_rx, db_tx_clone).await;
+        handle_db_responses(db_rx, db_tx_clone, llm_tx_clone).await;
     });
     spawn(async move {
         handle_llm_responses(llm_rx).await;
     });
+    spawn(team_library::run_team_library_sync());
+    spawn(crate::import::run_import_watch());
+    spawn(open_files::run_file_watch());
+    if let Some(rx) = crate::instance::take_open_requests() {
+        spawn(open_files::run_open_requests(rx));
+    }
 
     (db_tx, llm_tx)
 }
@@ -22,25 +47,120 @@ pub fn init_services() -> (DbSender, LlmSender) {
 async fn handle_db_responses(
     mut rx: mpsc::UnboundedReceiver<crate::db::DbResponse>,
     db_tx: DbSender,
+    llm_tx: LlmSender,
 ) {
-    use crate::config::QueryHistory;
+    use crate::config::{QueryHistory, UsageKind, UsageLog};
     use crate::db::DbResponse;
     use crate::state::*;
 
     let mut query_history = QueryHistory::new();
+    let usage = UsageLog::new();
 
     while let Some(response) = rx.recv().await {
+        // Queries run concurrently, so a response is matched to its request by id
+        // and lands in the tab the request was sent from
+        let (response, query_tab) = match response {
+            DbResponse::Finished { id, response } => {
+                let mut running = RUNNING_QUERIES.write();
+                let started_in = running
+                    .iter()
+                    .position(|q| q.id == id)
+                    .and_then(|i| running.remove(i).tab_id);
+                let tab_id = REQUEST_TABS.write().remove(&id).or(started_in);
+                (*response, tab_id)
+            }
+            other => (other, None),
+        };
+        if response.ends_all_queries() {
+            RUNNING_QUERIES.write().clear();
+        }
+        if response.ends_batch() {
+            *BATCH_PROGRESS.write() = None;
+        }
+        let is_active_tab = query_tab.is_none() || query_tab == EDITOR_TABS.read().active_tab_id;
         match response {
-            DbResponse::Connected(db_type, db_name) => {
+            DbResponse::QueryStarted(id, sql) => {
+                let tab_id = REQUEST_TABS
+                    .read()
+                    .get(&id)
+                    .cloned()
+                    .or_else(|| EDITOR_TABS.read().active_tab_id.clone());
+                RUNNING_QUERIES.write().push(RunningQuery {
+                    id,
+                    sql,
+                    tab_id,
+                    started: std::time::Instant::now(),
+                    rows_fetched: 0,
+                    statements: None,
+                });
+            }
+            DbResponse::QueryResultChunk {
+                id,
+                columns,
+                column_types,
+                rows,
+            } => {
+                let mut running = RUNNING_QUERIES.write();
+                let Some(query) = running.iter_mut().find(|q| q.id == id) else {
+                    continue;
+                };
+                // The first chunk of a query, or of a script's next statement, starts a new result
+                let first = query.rows_fetched == 0;
+                query.rows_fetched += rows.len();
+                let sql = query.sql.clone();
+                let tab_id = query.tab_id.clone();
+                drop(running);
+
+                let chunk = crate::db::QueryResult::fetching(&sql, columns, column_types, rows);
+                let add_chunk = |result: &mut Option<crate::db::QueryResult>, chunk| match result {
+                    Some(result) if !first => result.append(chunk),
+                    _ => *result = Some(chunk),
+                };
+                if tab_id.is_none() || tab_id == EDITOR_TABS.read().active_tab_id {
+                    let mut result = QUERY_RESULT.write();
+                    add_chunk(&mut *result, chunk.clone());
+                    *ROW_COUNT.write() = result.as_ref().map(|r| r.rows.len());
+                }
+                if let Some(tab) = EDITOR_TABS.write().query_tab_mut(tab_id.as_deref()) {
+                    add_chunk(&mut tab.result, chunk);
+                }
+            }
+            DbResponse::StatementFinished { id, done, total } => {
+                if let Some(query) = RUNNING_QUERIES.write().iter_mut().find(|q| q.id == id) {
+                    query.statements = Some((done, total));
+                    query.rows_fetched = 0;
+                }
+            }
+            DbResponse::Connected(db_type, db_name, dialect, server_version) => {
                 let db_type_enum = match db_type {
                     crate::db::DatabaseType::PostgreSQL => DatabaseType::PostgreSQL,
                     crate::db::DatabaseType::MySQL => DatabaseType::MySQL,
+                    crate::db::DatabaseType::SQLite => DatabaseType::SQLite,
+                    crate::db::DatabaseType::DuckDB => DatabaseType::DuckDB,
+                    crate::db::DatabaseType::ClickHouse => DatabaseType::ClickHouse,
+                    crate::db::DatabaseType::BigQuery => DatabaseType::BigQuery,
+                    crate::db::DatabaseType::Odbc => DatabaseType::Odbc,
                 };
                 *CONNECTION.write() = ConnectionState::Connected {
                     db_type: db_type_enum,
                     db_name,
                 };
                 *CURRENT_DB_TYPE.write() = Some(db_type_enum);
+                *CURRENT_DIALECT.write() = dialect;
+                *SERVER_VERSION.write() = server_version;
+                *LOST_CONNECTION.write() = None;
+                *KEEPALIVE.write() = None;
+                TABLE_DESCRIPTIONS.write().clear();
+                *RECONNECT_STATUS.write() = None;
+                FK_PREVIEWS.write().clear();
+                LISTEN_CHANNELS.write().clear();
+                CAPTURE_TABLES.write().clear();
+                *SESSION_VARIABLES.write() = None;
+                *MANUAL_COMMIT.write() = false;
+                *OPEN_TRANSACTION.write() = None;
+                if *SHOW_SESSION_PANEL.read() {
+                    let _ = db_tx.send(crate::db::DbRequest::FetchSessionVariables);
+                }
                 // Close dialog and reset test status on successful connection
                 *SHOW_CONNECTION_DIALOG.write() = false;
                 *TEST_CONNECTION_STATUS.write() = TestConnectionStatus::Idle;
@@ -48,52 +168,318 @@ async fn handle_db_responses(
             }
             DbResponse::ConnectionFailed(e) => {
                 *CONNECTION.write() = ConnectionState::Error(e.clone());
+                *CURRENT_ENVIRONMENT.write() = None;
+                *CURRENT_SAVED_CONNECTION.write() = None;
                 // Show error in test status area so user sees it
                 *TEST_CONNECTION_STATUS.write() = TestConnectionStatus::Failed(e);
             }
             DbResponse::Schema(schema) => *SCHEMA.write() = schema,
             DbResponse::QueryResult(result) => {
+                *PLAN_BUDGET_WARNING.write() = None;
                 // Record in history
                 query_history.add_entry(
                     result.sql.clone(),
                     Some(result.rows.len()),
                     Some(result.execution_time_ms),
                 );
+                usage.record(UsageKind::Query {
+                    tables: crate::db::referenced_tables(&result.sql),
+                    execution_time_ms: result.execution_time_ms,
+                    query_hash: Some(crate::db::query_hash(&result.sql)),
+                });
                 // Notify UI that history changed
                 *HISTORY_REVISION.write() += 1;
-                // Update active tab with result
-                if let Some(tab) = EDITOR_TABS.write().active_tab_mut() {
+                // Update the tab the query ran from
+                if let Some(tab) = EDITOR_TABS.write().query_tab_mut(query_tab.as_deref()) {
+                    // Fetching more of a script's statement keeps the rest of the script
+                    let index = tab.script_statement;
+                    match tab
+                        .script
+                        .as_mut()
+                        .and_then(|s| s.statements.get_mut(index))
+                    {
+                        Some(statement) if statement.sql == result.sql => {
+                            statement.outcome = Ok(result.clone());
+                        }
+                        _ => tab.script = None,
+                    }
                     tab.result = Some(result.clone());
+                    // Row indexes of an unconfirmed save don't carry over to a new result
+                    tab.saving = None;
                     tab.last_error = None;
+                    tab.timed_out = false;
                     tab.execution_time_ms = Some(result.execution_time_ms);
                     tab.unsaved_changes = false;
                 }
+                if !is_active_tab {
+                    continue;
+                }
                 // Also update global for backward compatibility during migration
                 *QUERY_RESULT.write() = Some(result.clone());
                 *EXECUTION_TIME_MS.write() = Some(result.execution_time_ms);
                 *ROW_COUNT.write() = Some(result.rows.len());
                 *LAST_ERROR.write() = None;
             }
+            DbResponse::ScriptResult(script) => {
+                *PLAN_BUDGET_WARNING.write() = None;
+                for statement in script.statements.iter() {
+                    match &statement.outcome {
+                        Ok(result) => {
+                            query_history.add_entry(
+                                result.sql.clone(),
+                                Some(result.rows.len()),
+                                Some(result.execution_time_ms),
+                            );
+                            usage.record(UsageKind::Query {
+                                tables: crate::db::referenced_tables(&result.sql),
+                                execution_time_ms: result.execution_time_ms,
+                                query_hash: Some(crate::db::query_hash(&result.sql)),
+                            });
+                        }
+                        Err(_) => usage.record(UsageKind::QueryError),
+                    }
+                }
+                *HISTORY_REVISION.write() += 1;
+                let shown = {
+                    let mut tabs = EDITOR_TABS.write();
+                    tabs.query_tab_mut(query_tab.as_deref()).map(|tab| {
+                        let index = script.default_statement();
+                        tab.script = Some(script);
+                        tab.show_script_statement(index);
+                        tab.unsaved_changes = false;
+                        (tab.result.clone(), tab.last_error.clone())
+                    })
+                };
+                if !is_active_tab {
+                    continue;
+                }
+                if let Some((result, error)) = shown {
+                    *EXECUTION_TIME_MS.write() = result.as_ref().map(|r| r.execution_time_ms);
+                    *ROW_COUNT.write() = result.as_ref().map(|r| r.rows.len());
+                    *QUERY_RESULT.write() = result;
+                    *LAST_ERROR.write() = error;
+                }
+            }
             DbResponse::Error(e) => {
-                // Update active tab with error
-                if let Some(tab) = EDITOR_TABS.write().active_tab_mut() {
+                usage.record(UsageKind::QueryError);
+                // Update the tab the query ran from
+                if let Some(tab) = EDITOR_TABS.write().query_tab_mut(query_tab.as_deref()) {
                     tab.last_error = Some(e.clone());
+                    tab.timed_out = false;
                     tab.result = None;
+                    tab.script = None;
+                    tab.saving = None;
                 }
                 if IMPORT_PROGRESS.read().is_some() {
                     *IMPORT_PROGRESS.write() = None;
                     *IMPORT_MESSAGE.write() = Some(format!("Import failed: {}", e));
                 }
+                if !is_active_tab {
+                    continue;
+                }
                 *LAST_ERROR.write() = Some(e);
                 *QUERY_RESULT.write() = None;
             }
+            DbResponse::QueryTimedOut { timeout_secs } => {
+                usage.record(UsageKind::QueryError);
+                let message = match timeout_secs {
+                    Some(secs) => format!("Query timed out after {}s", secs),
+                    None => "Query timed out".to_string(),
+                };
+                if let Some(tab) = EDITOR_TABS.write().query_tab_mut(query_tab.as_deref()) {
+                    tab.last_error = Some(message.clone());
+                    tab.timed_out = true;
+                    tab.result = None;
+                    tab.script = None;
+                }
+                if !is_active_tab {
+                    continue;
+                }
+                *LAST_ERROR.write() = Some(message);
+                *QUERY_RESULT.write() = None;
+            }
             DbResponse::Disconnected => {
                 *CONNECTION.write() = ConnectionState::Disconnected;
+                *RECONNECT_STATUS.write() = None;
+                *CURRENT_ENVIRONMENT.write() = None;
+                *CURRENT_SAVED_CONNECTION.write() = None;
+                *LOST_CONNECTION.write() = None;
+                OFFLINE_QUEUE.write().clear();
                 *SCHEMA.write() = Default::default();
                 *CURRENT_DB_TYPE.write() = None;
+                *SERVER_VARIABLES.write() = None;
+                *SESSION_VARIABLES.write() = None;
+                *EXTENSIONS.write() = None;
+                *CONNECTION_HEALTH.write() = None;
+                *KEEPALIVE.write() = None;
+                FK_PREVIEWS.write().clear();
+                LISTEN_CHANNELS.write().clear();
+                CAPTURE_TABLES.write().clear();
+                *MANUAL_COMMIT.write() = false;
+                *OPEN_TRANSACTION.write() = None;
             }
             DbResponse::ConnectionLost => {
-                *CONNECTION.write() = ConnectionState::ConnectionLost;
+                // Keep the cached schema so it stays browsable while offline
+                let previous =
+                    std::mem::replace(&mut *CONNECTION.write(), ConnectionState::ConnectionLost);
+                if matches!(previous, ConnectionState::Connected { .. }) {
+                    *LOST_CONNECTION.write() = Some(previous);
+                }
+                *CONNECTION_HEALTH.write() = None;
+                *KEEPALIVE.write() = None;
+            }
+            DbResponse::Reconnecting {
+                attempt,
+                retry_in_secs,
+            } => {
+                *RECONNECT_STATUS.write() = Some((attempt, retry_in_secs));
+            }
+            DbResponse::ConnectionRestored => {
+                *RECONNECT_STATUS.write() = None;
+                if let Some(previous) = LOST_CONNECTION.write().take() {
+                    *CONNECTION.write() = previous;
+                }
+                let queued = std::mem::take(&mut *OFFLINE_QUEUE.write());
+                let refetch_schema = !queued
+                    .iter()
+                    .any(|r| matches!(r, crate::db::DbRequest::FetchSchema));
+                for request in queued {
+                    let _ = db_tx.send(request);
+                }
+                if refetch_schema {
+                    let _ = db_tx.send(crate::db::DbRequest::FetchSchema);
+                }
+            }
+            DbResponse::FkPreview { key, row } => {
+                let preview = match row {
+                    Ok(Some(row)) => FkPreview::Row(row),
+                    Ok(None) => FkPreview::Missing,
+                    Err(e) => FkPreview::Failed(e),
+                };
+                FK_PREVIEWS.write().insert(key, preview);
+            }
+            DbResponse::TableSample { table, sample } => {
+                let info = SCHEMA
+                    .read()
+                    .tables
+                    .iter()
+                    .find(|t| t.name == table)
+                    .cloned();
+                match (sample, info) {
+                    (Ok(sample), Some(info)) => send_llm_request(
+                        &llm_tx,
+                        crate::llm::LlmRequest::DescribeTable {
+                            table: info,
+                            sample,
+                            dialect: *CURRENT_DIALECT.read(),
+                            config: LLM_CONFIG.read().clone(),
+                        },
+                        LlmOrigin::TableDescription(table),
+                    ),
+                    (Err(e), _) => {
+                        TABLE_DESCRIPTIONS
+                            .write()
+                            .insert(table, TableDescriptionState::Failed(e));
+                    }
+                    (Ok(_), None) => {
+                        TABLE_DESCRIPTIONS.write().remove(&table);
+                    }
+                }
+            }
+            DbResponse::SeedRows(rows) => {
+                let Some(mut state) = SEED_SCRIPT.read().clone() else {
+                    continue;
+                };
+                let Some(anonymize) = state.generating.take() else {
+                    continue;
+                };
+                match rows {
+                    Ok(mut tables) => {
+                        if let Some(result) = state.result.take() {
+                            tables.push((state.table.clone(), result));
+                        }
+                        let db_type = CURRENT_DB_TYPE.read().unwrap_or(DatabaseType::PostgreSQL);
+                        let script =
+                            crate::db::seed_script(db_type, &tables, &SCHEMA.read(), anonymize);
+                        let mut tabs = EDITOR_TABS.write();
+                        let id = tabs.add_tab(format!("{} seed", state.table));
+                        if let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == id) {
+                            tab.content = script;
+                        }
+                        *SEED_SCRIPT.write() = None;
+                    }
+                    Err(e) => {
+                        state.error = Some(e);
+                        *SEED_SCRIPT.write() = Some(state);
+                    }
+                }
+            }
+            DbResponse::TransactionStatus(statements) => {
+                *OPEN_TRANSACTION.write() = statements;
+                // Manual-commit mode begins the next transaction once one ends
+                if statements.is_none() && *MANUAL_COMMIT.read() {
+                    let _ = db_tx.send(crate::db::DbRequest::Begin);
+                }
+            }
+            DbResponse::Distribution(result) => {
+                if let Some(state) = COLUMN_DISTRIBUTION.write().as_mut() {
+                    state.loading = false;
+                    state.result = Some(result);
+                }
+            }
+            DbResponse::TablesCopied(result) => {
+                if let Some(state) = ANONYMIZED_COPY.write().as_mut() {
+                    state.running = false;
+                    match result {
+                        Ok(summary) => state.summary = Some(summary),
+                        Err(e) => state.error = Some(e),
+                    }
+                }
+            }
+            DbResponse::OverBudget { sql, message } => {
+                *PLAN_BUDGET_WARNING.write() = Some((sql, message));
+            }
+            DbResponse::Listening(channels) => *LISTEN_CHANNELS.write() = channels,
+            DbResponse::Notification(notification) => {
+                let mut notifications = NOTIFICATIONS.write();
+                notifications.insert(0, notification);
+                notifications.truncate(MAX_NOTIFICATIONS);
+            }
+            DbResponse::Capturing(tables) => *CAPTURE_TABLES.write() = tables,
+            DbResponse::ChangeEvents(events) => {
+                let mut changes = CHANGE_EVENTS.write();
+                for event in events {
+                    changes.insert(0, event);
+                }
+                changes.truncate(MAX_CHANGE_EVENTS);
+            }
+            DbResponse::ReferencingCounts(counts) => {
+                if let Some((_, tables)) = REFERENCING_ROWS.write().as_mut() {
+                    for (table, count) in tables.iter_mut().zip(counts) {
+                        table.count = Some(count);
+                    }
+                }
+            }
+            DbResponse::RowCount { sql, count } => {
+                if let Some((pending, result)) = REPLACE_MATCH_COUNT.write().as_mut() {
+                    if *pending == sql {
+                        *result = Some(count);
+                    }
+                }
+            }
+            DbResponse::RowHistory(result) => {
+                if let Some(state) = ROW_HISTORY.write().as_mut() {
+                    match result {
+                        Ok((audit_table, entries)) => {
+                            state.audit_table = Some(audit_table);
+                            state.entries = Some(entries);
+                        }
+                        Err(e) => state.error = Some(e),
+                    }
+                }
+            }
+            DbResponse::UnknownHostKey { key, request } => {
+                *PENDING_HOST_KEY.write() = Some((key, *request));
             }
             DbResponse::TestResult(result) => {
                 *TEST_CONNECTION_STATUS.write() = match result {
@@ -102,37 +488,104 @@ async fn handle_db_responses(
                 };
             }
             DbResponse::ExplainResult(plan) => {
-                if let Some(tab) = EDITOR_TABS.write().active_tab_mut() {
+                if let Some(tab) = EDITOR_TABS.write().query_tab_mut(query_tab.as_deref()) {
                     tab.execution_plan = Some(plan);
                 }
-                *SHOW_EXECUTION_PLAN.write() = true;
+                if is_active_tab {
+                    *SHOW_EXECUTION_PLAN.write() = true;
+                }
+            }
+            DbResponse::TimingProfile { sql, timing } => {
+                *TIMING_PROFILE.write() = Some((sql, Some(timing)));
             }
             DbResponse::MutationResult { affected_rows } => {
                 tracing::info!("Mutation: {} rows affected", affected_rows);
                 // Re-execute the last query to refresh results
-                if let Some(tab) = EDITOR_TABS.read().active_tab() {
-                    if let Some(result) = &tab.result {
-                        let sql = result.sql.clone();
-                        let _ = db_tx.send(crate::db::DbRequest::Execute(sql));
-                    }
-                }
+                refresh_tab_result(&db_tx, query_tab);
             }
             DbResponse::BatchResult {
                 affected_rows,
                 statement_count,
+                retries,
             } => {
                 tracing::info!(
                     "Batch: {} statements, {} rows affected",
                     statement_count,
                     affected_rows
                 );
-                // Re-execute to refresh
-                if let Some(tab) = EDITOR_TABS.read().active_tab() {
-                    if let Some(result) = &tab.result {
-                        let sql = result.sql.clone();
-                        let _ = db_tx.send(crate::db::DbRequest::Execute(sql));
-                    }
+                *TRANSACTION_RETRY.write() =
+                    (retries > 0).then_some(TransactionRetry::Succeeded(retries));
+                // Show the saved values right away, then re-execute to pick up what the
+                // database made of them
+                let result = EDITOR_TABS
+                    .write()
+                    .query_tab_mut(query_tab.as_deref())
+                    .and_then(|tab| {
+                        tab.apply_saved_change();
+                        tab.result.clone()
+                    });
+                if is_active_tab && result.is_some() {
+                    *QUERY_RESULT.write() = result;
+                }
+                refresh_tab_result(&db_tx, query_tab);
+            }
+            DbResponse::BatchConflict {
+                statements,
+                options,
+                row_edits,
+                error,
+                retries,
+            } => {
+                tracing::warn!("Batch conflict after {} retries: {}", retries, error);
+                *TRANSACTION_RETRY.write() = Some(TransactionRetry::Failed {
+                    statements,
+                    options,
+                    row_edits,
+                    error,
+                    retries,
+                });
+            }
+            DbResponse::BatchProgress {
+                done,
+                total,
+                unit,
+                current,
+            } => {
+                let mut progress = BATCH_PROGRESS.write();
+                if progress.is_none() {
+                    *SHOW_BATCH_PROGRESS.write() = true;
                 }
+                let (started, cancelling) = progress
+                    .as_ref()
+                    .map(|p| (p.started, p.cancelling))
+                    .unwrap_or((std::time::Instant::now(), false));
+                *progress = Some(BatchProgressState {
+                    done,
+                    total,
+                    unit,
+                    current,
+                    started,
+                    cancelling,
+                });
+            }
+            DbResponse::RowEditConflict { statement } => {
+                tracing::warn!("Grid save matched no row for statement {}", statement);
+                // Nothing was saved, so the edits are pending again
+                let row = EDITOR_TABS
+                    .write()
+                    .query_tab_mut(query_tab.as_deref())
+                    .and_then(|tab| match tab.saving.take()? {
+                        SavingChange::Edits { edits, rows } => {
+                            tab.pending_edits = edits;
+                            tab.edit_mode = true;
+                            rows.get(statement).copied()
+                        }
+                        SavingChange::Deletes(_) => None,
+                    });
+                *EDIT_CONFLICT.write() = Some(EditConflict {
+                    tab_id: query_tab,
+                    row,
+                });
             }
             DbResponse::ImportProgress { inserted, total } => {
                 *IMPORT_MESSAGE.write() = None;
@@ -143,24 +596,127 @@ async fn handle_db_responses(
                 *IMPORT_MESSAGE.write() = Some(format!("Import complete: {} rows", total));
                 tracing::info!("Import complete: {} rows", total);
             }
+            DbResponse::ImportValidation(validation) => {
+                *VALIDATING_IMPORT.write() = false;
+                *IMPORT_VALIDATION.write() = Some(validation);
+            }
+            DbResponse::QueryTestResults(results) => {
+                *RUNNING_QUERY_TESTS.write() = false;
+                *QUERY_TEST_RESULTS.write() = Some(results);
+            }
+            DbResponse::WatchedFileImported { path, rows } => {
+                crate::import::finish_watched_import(&path, rows);
+            }
+            DbResponse::ServerVariables(variables) => {
+                *SERVER_VARIABLES.write() = Some(variables);
+            }
+            DbResponse::SessionVariables(variables) => {
+                *SESSION_VARIABLES.write() = Some(variables);
+            }
+            DbResponse::SessionVariableSet { name, value } => {
+                tracing::info!("Session variable {} set to {}", name, value);
+                if *SHOW_SERVER_VARIABLES.read() {
+                    let _ = db_tx.send(crate::db::DbRequest::FetchServerVariables);
+                }
+                if *SHOW_SESSION_PANEL.read() {
+                    let _ = db_tx.send(crate::db::DbRequest::FetchSessionVariables);
+                }
+            }
+            DbResponse::Extensions(extensions) => {
+                *EXTENSIONS.write() = Some(extensions);
+            }
+            DbResponse::Health(health) => {
+                *CONNECTION_HEALTH.write() = Some(health);
+            }
+            DbResponse::Keepalive {
+                latency_ms,
+                connections,
+            } => {
+                *KEEPALIVE.write() = Some((latency_ms, connections));
+            }
             _ => {}
         }
     }
 }
 
-async fn handle_llm_responses(mut rx: mpsc::UnboundedReceiver<crate::llm::LlmResponse>) {
+/// Re-run the query behind a tab's results, by default the active tab's, after it changed data.
+fn refresh_tab_result(db_tx: &DbSender, tab_id: Option<String>) {
+    use crate::state::{tag_for_tab, EDITOR_TABS};
+
+    let target = EDITOR_TABS
+        .write()
+        .query_tab_mut(tab_id.as_deref())
+        .and_then(|tab| Some((tab.id.clone(), tab.result.as_ref()?.sql.clone())));
+    if let Some((tab_id, sql)) = target {
+        let request = crate::db::DbRequest::Execute(sql);
+        let _ = db_tx.send(tag_for_tab(request, Some(tab_id)));
+    }
+}
+
+async fn handle_llm_responses(
+    mut rx: mpsc::UnboundedReceiver<(crate::db::RequestId, crate::llm::LlmResponse)>,
+) {
+    use crate::config::{LlmSpendStore, UsageKind, UsageLog};
     use crate::llm::LlmResponse;
     use crate::state::*;
 
-    while let Some(response) = rx.recv().await {
+    let usage = UsageLog::new();
+    let spend = LlmSpendStore::new();
+
+    while let Some((id, response)) = rx.recv().await {
+        // Token usage trails the answer it belongs to and is not a request of its own
+        if let LlmResponse::Usage(tokens) = response {
+            LLM_SESSION_USAGE.write().add(tokens);
+            let month = match spend.record(tokens) {
+                Ok(month) => month,
+                Err(e) => {
+                    tracing::error!("Failed to record LLM spend: {}", e);
+                    continue;
+                }
+            };
+            if let Some(budget) = LLM_CONFIG.read().monthly_budget_usd {
+                if budget > 0.0 && month.cost_usd >= budget * BUDGET_WARNING_FRACTION {
+                    *LLM_STATUS.write() = LlmStatus::Error(format!(
+                        "${:.2} of the ${:.2} monthly AI budget spent",
+                        month.cost_usd, budget
+                    ));
+                }
+            }
+            continue;
+        }
+
+        usage.record(UsageKind::AiRequest);
+        let origin = LLM_REQUESTS.write().remove(&id);
         match response {
-            LlmResponse::Generated(sql) => {
-                // Replace editor content with generated SQL
-                if let Some(tab) = EDITOR_TABS.write().active_tab_mut() {
+            LlmResponse::Generated { sql, explanation } => {
+                *LLM_GENERATING.write() = false;
+                // The model's notes go to the AI panel, the SQL into the editor
+                if !explanation.is_empty() {
+                    *AI_PANEL.write() = AiPanelState {
+                        visible: true,
+                        loading: false,
+                        title: if sql.is_empty() {
+                            "No SQL returned".to_string()
+                        } else {
+                            "Generated query".to_string()
+                        },
+                        content: explanation,
+                        suggested_sql: None,
+                    };
+                }
+                if sql.is_empty() {
+                    *LLM_STATUS.write() = LlmStatus::Error("The model answered without SQL".into());
+                    continue;
+                }
+                // Replace the content of the tab the prompt was written in
+                let tab_id = match &origin {
+                    Some(LlmOrigin::Tab(tab_id)) => Some(tab_id.as_str()),
+                    _ => None,
+                };
+                if let Some(tab) = EDITOR_TABS.write().query_tab_mut(tab_id) {
                     tab.content = sql;
                     tab.unsaved_changes = true;
                 }
-                *LLM_GENERATING.write() = false;
                 *LLM_PROMPT.write() = String::new();
                 *LLM_STATUS.write() = LlmStatus::Success("Query generated successfully".into());
             }
@@ -196,6 +752,12 @@ async fn handle_llm_responses(mut rx: mpsc::UnboundedReceiver<crate::llm::LlmRes
             }
             LlmResponse::QuerySuggestions(suggestions) => {
                 let table_name = SCHEMA_SUGGESTIONS.read().table_name.clone();
+                // Suggestions for a table the panel has since moved away from are dropped
+                if let Some(LlmOrigin::Suggestions(table)) = &origin {
+                    if table_name.as_ref() != Some(table) {
+                        continue;
+                    }
+                }
                 *SCHEMA_SUGGESTIONS.write() = SuggestionsState {
                     suggestions,
                     loading: false,
@@ -203,8 +765,82 @@ async fn handle_llm_responses(mut rx: mpsc::UnboundedReceiver<crate::llm::LlmRes
                 };
                 *LLM_GENERATING.write() = false;
             }
+            // Handled before the match
+            LlmResponse::Usage(_) => {}
+            LlmResponse::Cancelled => {
+                *LLM_GENERATING.write() = false;
+                match origin {
+                    Some(LlmOrigin::Suggestions(_)) => SCHEMA_SUGGESTIONS.write().loading = false,
+                    Some(LlmOrigin::AiPanel) => *AI_PANEL.write() = AiPanelState::default(),
+                    Some(LlmOrigin::TableDescription(table)) => {
+                        TABLE_DESCRIPTIONS.write().remove(&table);
+                    }
+                    Some(LlmOrigin::DialectConversion(original)) => {
+                        if let Some(conversion) = DIALECT_CONVERSION
+                            .write()
+                            .as_mut()
+                            .filter(|c| c.original == original)
+                        {
+                            conversion.ai = Some(Err("Cancelled".to_string()));
+                        }
+                    }
+                    _ => {}
+                }
+                *LLM_STATUS.write() = LlmStatus::Error("Cancelled".into());
+            }
+            LlmResponse::TableDescription(text) => {
+                let Some(LlmOrigin::TableDescription(table)) = origin else {
+                    continue;
+                };
+                let description = crate::config::TableDescription {
+                    text,
+                    described_at: chrono::Local::now(),
+                };
+                if let Some(database) = described_database() {
+                    let store = crate::config::TableDescriptionStore::new();
+                    if let Err(e) = store.set(&database, &table, &description) {
+                        tracing::error!("Failed to cache table description: {}", e);
+                    }
+                }
+                TABLE_DESCRIPTIONS
+                    .write()
+                    .insert(table, TableDescriptionState::Ready(description));
+            }
+            LlmResponse::Translated(sql) => {
+                let Some(LlmOrigin::DialectConversion(original)) = origin else {
+                    continue;
+                };
+                let mut conversion = DIALECT_CONVERSION.write();
+                // The dialog may have moved on to another statement
+                if let Some(conversion) = conversion.as_mut().filter(|c| c.original == original) {
+                    let sql = sql.trim().trim_end_matches(';').trim_end().to_string();
+                    conversion.ai = Some(if sql.is_empty() {
+                        Err("The model answered without SQL".to_string())
+                    } else {
+                        Ok(sql)
+                    });
+                }
+            }
             LlmResponse::Error(e) => {
                 *LLM_GENERATING.write() = false;
+                match &origin {
+                    Some(LlmOrigin::Suggestions(_)) => SCHEMA_SUGGESTIONS.write().loading = false,
+                    Some(LlmOrigin::DialectConversion(original)) => {
+                        if let Some(conversion) = DIALECT_CONVERSION
+                            .write()
+                            .as_mut()
+                            .filter(|c| &c.original == original)
+                        {
+                            conversion.ai = Some(Err(e.clone()));
+                        }
+                    }
+                    Some(LlmOrigin::TableDescription(table)) => {
+                        TABLE_DESCRIPTIONS
+                            .write()
+                            .insert(table.clone(), TableDescriptionState::Failed(e.clone()));
+                    }
+                    _ => {}
+                }
                 *LLM_STATUS.write() = LlmStatus::Error(e.clone());
                 // Also show error in AI panel if it's visible
                 if AI_PANEL.read().visible {
@@ -0,0 +1,112 @@
+use crate::config::{DraftData, RecentFilesStore};
+use crate::instance::{OpenReceiver, OpenRequest};
+use crate::state::{file_modified, EDITOR_TABS, PENDING_DEEP_LINK};
+use dioxus::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Seconds between checks of the files behind open tabs
+const FILE_CHECK_INTERVAL_SECS: u64 = 2;
+
+/// Open files from the command line, the OS and later launches of the app in new tabs.
+/// Links wait in the confirmation dialog until the user accepts them.
+pub async fn run_open_requests(mut rx: OpenReceiver) {
+    while let Some(requests) = rx.recv().await {
+        for request in requests {
+            match request {
+                OpenRequest::File(path) => {
+                    if let Err(e) = open_file(&path) {
+                        tracing::error!("Failed to open {}: {}", path.display(), e);
+                    }
+                }
+                OpenRequest::Link(link) => *PENDING_DEEP_LINK.write() = Some(link),
+            }
+        }
+        let window = dioxus::desktop::window();
+        window.set_minimized(false);
+        window.set_focus();
+    }
+}
+
+/// A .sql file becomes a tab backed by the file; a .fbench workbook restores each of
+/// its saved tabs.
+pub fn open_file(path: &Path) -> Result<(), String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let is_workbook = path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("fbench"));
+    {
+        let mut editor_tabs = EDITOR_TABS.write();
+        if is_workbook {
+            let workbook: DraftData = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+            for draft in workbook.tabs {
+                let id = editor_tabs.add_tab(draft.title);
+                if let Some(tab) = editor_tabs.tabs.iter_mut().find(|t| t.id == id) {
+                    tab.content = draft.content;
+                }
+            }
+        } else {
+            editor_tabs.open_file_tab(path.clone(), content);
+        }
+    }
+    if let Err(e) = RecentFilesStore::new().add(&path) {
+        tracing::warn!("Failed to remember recent file: {}", e);
+    }
+    tracing::info!("Opened {}", path.display());
+    Ok(())
+}
+
+/// Flag tabs whose file was changed by another program, so the editor can offer to
+/// reload it.
+pub async fn run_file_watch() {
+    loop {
+        tokio::time::sleep(Duration::from_secs(FILE_CHECK_INTERVAL_SECS)).await;
+        let files: Vec<(String, PathBuf)> = EDITOR_TABS
+            .read()
+            .tabs
+            .iter()
+            .filter(|t| !t.file_changed)
+            .filter_map(|t| Some((t.id.clone(), t.file_path.clone()?)))
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+        let modified = tokio::task::spawn_blocking(move || {
+            files
+                .into_iter()
+                .map(|(id, path)| (id, file_modified(&path)))
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+
+        // Only write when something changed, as every write re-renders the editor
+        let changes: Vec<_> = {
+            let tabs = EDITOR_TABS.read();
+            modified
+                .into_iter()
+                // A deleted or unreadable file leaves the tab as it is
+                .filter_map(|(id, modified)| Some((id, modified?)))
+                .filter(|(id, modified)| {
+                    tabs.tabs
+                        .iter()
+                        .any(|t| t.id == *id && t.file_modified != Some(*modified))
+                })
+                .collect()
+        };
+        if changes.is_empty() {
+            continue;
+        }
+        let mut tabs = EDITOR_TABS.write();
+        for (id, modified) in changes {
+            let Some(tab) = tabs.tabs.iter_mut().find(|t| t.id == id) else {
+                continue;
+            };
+            match tab.file_modified {
+                Some(_) => tab.file_changed = true,
+                None => tab.file_modified = Some(modified),
+            }
+        }
+    }
+}
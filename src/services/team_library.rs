@@ -0,0 +1,180 @@
+use crate::config::{SavedQuery, TeamLibrarySource, TeamLibraryStore};
+use crate::state::{TEAM_LIBRARY_STATUS, TEAM_QUERIES};
+use dioxus::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tokio::time::Duration;
+
+/// Refresh the team library now and then on its configured interval.
+pub async fn run_team_library_sync() {
+    loop {
+        refresh_team_library().await;
+        let minutes = TeamLibraryStore::new().load().refresh_minutes.max(1);
+        tokio::time::sleep(Duration::from_secs(minutes * 60)).await;
+    }
+}
+
+/// Fetch the configured team library and publish its queries.
+pub async fn refresh_team_library() {
+    let store = TeamLibraryStore::new();
+    let Some(source) = store.load().source else {
+        TEAM_QUERIES.write().clear();
+        *TEAM_LIBRARY_STATUS.write() = None;
+        return;
+    };
+
+    match fetch_team_queries(&source, &store).await {
+        Ok(queries) => {
+            if let Err(e) = store.save_cached(&queries) {
+                tracing::error!("Failed to cache team queries: {}", e);
+            }
+            tracing::info!("Team library synced: {} queries", queries.len());
+            *TEAM_QUERIES.write() = queries;
+            *TEAM_LIBRARY_STATUS.write() =
+                Some(Ok(chrono::Local::now().format("%H:%M").to_string()));
+        }
+        Err(e) => {
+            tracing::error!("Team library sync failed: {}", e);
+            *TEAM_LIBRARY_STATUS.write() = Some(Err(e));
+        }
+    }
+}
+
+async fn fetch_team_queries(
+    source: &TeamLibrarySource,
+    store: &TeamLibraryStore,
+) -> Result<Vec<SavedQuery>, String> {
+    match source {
+        TeamLibrarySource::Git(url) => {
+            let url = url.clone();
+            let dir = store.checkout_dir();
+            tokio::task::spawn_blocking(move || {
+                sync_git_checkout(&url, &dir)?;
+                let mut queries = Vec::new();
+                collect_sql_files(&dir, &dir, &mut queries);
+                queries.sort_by(|a, b| a.name.cmp(&b.name));
+                Ok(queries)
+            })
+            .await
+            .map_err(|e| e.to_string())?
+        }
+        TeamLibrarySource::Url(url) => fetch_url_queries(url).await,
+    }
+}
+
+/// Clone the repository, or fast-forward an existing clone of the same remote.
+fn sync_git_checkout(url: &str, dir: &Path) -> Result<(), String> {
+    if dir.join(".git").exists() {
+        let origin = git(&["-C", &dir.to_string_lossy(), "remote", "get-url", "origin"])?;
+        if origin.trim() == url {
+            git(&["-C", &dir.to_string_lossy(), "pull", "--ff-only", "--quiet"])?;
+            return Ok(());
+        }
+        std::fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    // `--` keeps a url starting with a dash from being read as an option
+    git(&[
+        "clone",
+        "--depth",
+        "1",
+        "--quiet",
+        "--",
+        url,
+        &dir.to_string_lossy(),
+    ])?;
+    Ok(())
+}
+
+fn git(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Could not run git: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Every .sql file under `dir`, named by its path relative to `root` without the extension.
+fn collect_sql_files(root: &Path, dir: &Path, queries: &mut Vec<SavedQuery>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.file_name().is_some_and(|n| n == ".git") {
+            continue;
+        }
+        if path.is_dir() {
+            collect_sql_files(root, &path, queries);
+        } else if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("sql"))
+        {
+            let Ok(sql) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let name = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .with_extension("")
+                .to_string_lossy()
+                .replace('\\', "/");
+            queries.push(SavedQuery {
+                name,
+                sql,
+                is_bookmarked: false,
+            });
+        }
+    }
+}
+
+/// A URL ending in .sql is one query; any other URL is an index with one .sql path per line,
+/// resolved relative to the index. Blank lines and lines starting with # are skipped.
+async fn fetch_url_queries(url: &str) -> Result<Vec<SavedQuery>, String> {
+    let client = reqwest::Client::new();
+    let base = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+
+    let file_urls = if base.path().to_lowercase().ends_with(".sql") {
+        vec![base]
+    } else {
+        fetch_text(&client, base.clone())
+            .await?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| base.join(line).map_err(|e| e.to_string()))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut queries = Vec::with_capacity(file_urls.len());
+    for file_url in file_urls {
+        let name = file_url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default()
+            .trim_end_matches(".sql")
+            .to_string();
+        let sql = fetch_text(&client, file_url).await?;
+        queries.push(SavedQuery {
+            name,
+            sql,
+            is_bookmarked: false,
+        });
+    }
+    Ok(queries)
+}
+
+async fn fetch_text(client: &reqwest::Client, url: reqwest::Url) -> Result<String, String> {
+    let response = client
+        .get(url.clone())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    response.text().await.map_err(|e| e.to_string())
+}
@@ -1,5 +1,9 @@
-use crate::db::SchemaInfo;
+use crate::db::{
+    ChangeEvent, ConnectionHealth, ExtensionInfo, Notification, SchemaInfo, ServerTiming,
+    ServerVariable, SessionVariable, SqlDialect, TransactionOptions,
+};
 use dioxus::prelude::*;
+use std::collections::HashMap;
 use tokio::sync::mpsc;
 
 // Re-export DatabaseType from db module
@@ -8,7 +12,82 @@ pub use crate::db::DatabaseType;
 pub static DB_SENDER: GlobalSignal<Option<mpsc::UnboundedSender<crate::db::DbRequest>>> =
     Signal::global(|| None);
 
+/// Environment tag of the saved connection in use
+pub static CURRENT_ENVIRONMENT: GlobalSignal<Option<crate::config::Environment>> =
+    Signal::global(|| None);
+
+/// Name and schema of the saved connection in use; None for a connection that wasn't saved
+pub static CURRENT_SAVED_CONNECTION: GlobalSignal<Option<(String, String)>> =
+    Signal::global(|| None);
+
+/// A data-changing request held back until the user confirms it runs against production
+pub static PENDING_PRODUCTION_REQUEST: GlobalSignal<Option<(String, crate::db::DbRequest)>> =
+    Signal::global(|| None);
+
+/// A request with statements that drop, truncate, alter or empty tables, held back
+/// until the user confirms them
+pub static PENDING_DESTRUCTIVE_REQUEST: GlobalSignal<
+    Option<(Vec<crate::db::DestructiveStatement>, crate::db::DbRequest)>,
+> = Signal::global(|| None);
+
+/// A connect or test that reached an SSH server with an unknown host key, held back
+/// until the user trusts the key
+pub static PENDING_HOST_KEY: GlobalSignal<
+    Option<(crate::db::UnknownHostKey, crate::db::DbRequest)>,
+> = Signal::global(|| None);
+
+/// Tab each tagged request was sent from, until its response arrives
+pub static REQUEST_TABS: GlobalSignal<HashMap<crate::db::RequestId, String>> =
+    Signal::global(HashMap::new);
+
+/// Tag `request` so its response is applied to `tab_id` rather than whichever tab is active then.
+pub fn tag_for_tab(request: crate::db::DbRequest, tab_id: Option<String>) -> crate::db::DbRequest {
+    let id = crate::db::next_request_id();
+    if let Some(tab_id) = tab_id {
+        REQUEST_TABS.write().insert(id, tab_id);
+    }
+    crate::db::DbRequest::Tagged {
+        id,
+        request: Box::new(request),
+    }
+}
+
+/// Send a request whose response belongs to the active tab. Destructive statements,
+/// and on a production connection any statement that changes data, wait for confirmation.
+pub fn send_tab_request(request: crate::db::DbRequest) {
+    let tab_id = super::EDITOR_TABS.read().active_tab_id.clone();
+    send_request_for_tab(request, tab_id);
+}
+
+/// `send_tab_request` for a tab that may no longer be the active one.
+pub fn send_request_for_tab(request: crate::db::DbRequest, tab_id: Option<String>) {
+    let request = tag_for_tab(request, tab_id);
+    if let Some(sql) = request.query_sql() {
+        let destructive = crate::db::destructive_statements(&sql);
+        if !destructive.is_empty() {
+            // Its dialog also warns about production, so it isn't asked twice
+            *PENDING_DESTRUCTIVE_REQUEST.write() = Some((destructive, request));
+            return;
+        }
+    }
+    if *CURRENT_ENVIRONMENT.read() == Some(crate::config::Environment::Production) {
+        if let Some(sql) = request
+            .query_sql()
+            .filter(|sql| crate::db::modifies_data(sql))
+        {
+            *PENDING_PRODUCTION_REQUEST.write() = Some((sql, request));
+            return;
+        }
+    }
+    send_db_request(request);
+}
+
 pub fn send_db_request(request: crate::db::DbRequest) {
+    // While offline, hold metadata lookups until the server returns
+    if *CONNECTION.read() == ConnectionState::ConnectionLost && request.is_informational() {
+        OFFLINE_QUEUE.write().push(request);
+        return;
+    }
     if let Some(sender) = DB_SENDER.read().as_ref() {
         let _ = sender.send(request);
     }
@@ -33,4 +112,199 @@ pub static SCHEMA: GlobalSignal<SchemaInfo> = Signal::global(SchemaInfo::default
 
 pub static CURRENT_DB_TYPE: GlobalSignal<Option<DatabaseType>> = Signal::global(|| None);
 
+/// Server flavor of the connection, which tells MariaDB apart from MySQL
+pub static CURRENT_DIALECT: GlobalSignal<SqlDialect> = Signal::global(SqlDialect::default);
+
+/// Version reported by the server, e.g. the CockroachDB cluster version
+pub static SERVER_VERSION: GlobalSignal<Option<String>> = Signal::global(|| None);
+
 pub static RECENT_TABLES: GlobalSignal<Vec<String>> = Signal::global(Vec::new);
+
+/// Server settings for the variables dialog; None until fetched
+pub static SERVER_VARIABLES: GlobalSignal<Option<Vec<ServerVariable>>> = Signal::global(|| None);
+
+/// Variables in effect for the active session; None until fetched
+pub static SESSION_VARIABLES: GlobalSignal<Option<Vec<SessionVariable>>> = Signal::global(|| None);
+
+/// Channels the connection LISTENs on
+pub static LISTEN_CHANNELS: GlobalSignal<Vec<String>> = Signal::global(Vec::new);
+
+/// Notifications received on listened channels, newest first
+pub static NOTIFICATIONS: GlobalSignal<Vec<Notification>> = Signal::global(Vec::new);
+
+/// Tables whose row changes are being captured through logical decoding
+pub static CAPTURE_TABLES: GlobalSignal<Vec<String>> = Signal::global(Vec::new);
+
+/// Captured row changes, newest first
+pub static CHANGE_EVENTS: GlobalSignal<Vec<ChangeEvent>> = Signal::global(Vec::new);
+
+/// Isolation level and access mode used when saving grid changes
+pub static TRANSACTION_OPTIONS: GlobalSignal<TransactionOptions> =
+    Signal::global(TransactionOptions::default);
+
+/// Result of a save transaction that ran into deadlocks or serialization failures
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransactionRetry {
+    /// Committed after this many reruns
+    Succeeded(u32),
+    /// Still conflicting after `retries` reruns; the batch can be retried by hand
+    Failed {
+        statements: Vec<String>,
+        options: TransactionOptions,
+        /// The batch was a grid save, retried with its row checks
+        row_edits: bool,
+        error: String,
+        retries: u32,
+    },
+}
+
+/// Shown above the results until dismissed or the next save
+pub static TRANSACTION_RETRY: GlobalSignal<Option<TransactionRetry>> = Signal::global(|| None);
+
+/// Deletes of selected grid rows waiting for confirmation
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowDelete {
+    pub tab_id: String,
+    pub table: String,
+    /// One DELETE, or soft-delete UPDATE, per row
+    pub statements: Vec<String>,
+    /// Primary key values of each row, as `key = value` pairs
+    pub keys: Vec<String>,
+    pub rows: Vec<usize>,
+    pub soft_delete: bool,
+}
+
+pub static PENDING_ROW_DELETE: GlobalSignal<Option<RowDelete>> = Signal::global(|| None);
+
+/// Progress of the running batch or import, reported by the worker before each step
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchProgressState {
+    pub done: usize,
+    pub total: usize,
+    /// What `done` and `total` count, such as statements or rows
+    pub unit: &'static str,
+    pub current: String,
+    pub started: std::time::Instant,
+    /// Cancel was pressed; the batch stops before its next step
+    pub cancelling: bool,
+}
+
+pub static BATCH_PROGRESS: GlobalSignal<Option<BatchProgressState>> = Signal::global(|| None);
+
+/// A grid save that found a row changed or deleted since it was loaded, so nothing was
+/// saved. Its edits are pending again in the tab.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditConflict {
+    pub tab_id: Option<String>,
+    /// Row of the result that no longer matches
+    pub row: Option<usize>,
+}
+
+pub static EDIT_CONFLICT: GlobalSignal<Option<EditConflict>> = Signal::global(|| None);
+
+/// Manual-commit mode: statements run in a transaction that stays open until committed
+/// or rolled back, and a new one begins after each
+pub static MANUAL_COMMIT: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Statements run in the open manual transaction; None when there is none
+pub static OPEN_TRANSACTION: GlobalSignal<Option<usize>> = Signal::global(|| None);
+
+/// PostgreSQL extensions for the extension manager; None until fetched
+pub static EXTENSIONS: GlobalSignal<Option<Vec<ExtensionInfo>>> = Signal::global(|| None);
+
+/// Latest health check of the active connection
+pub static CONNECTION_HEALTH: GlobalSignal<Option<ConnectionHealth>> = Signal::global(|| None);
+
+/// (slowest round trip in ms, idle connections pinged) of the last keepalive
+pub static KEEPALIVE: GlobalSignal<Option<(u64, usize)>> = Signal::global(|| None);
+
+/// Connection that was active before it was lost, restored when the server comes back
+pub static LOST_CONNECTION: GlobalSignal<Option<ConnectionState>> = Signal::global(|| None);
+
+/// (failed attempts, seconds until the next) while reconnecting after a loss
+pub static RECONNECT_STATUS: GlobalSignal<Option<(u32, u64)>> = Signal::global(|| None);
+
+/// A query the worker has started and not yet answered
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunningQuery {
+    pub id: crate::db::RequestId,
+    pub sql: String,
+    /// Tab that was active when the query started; its response goes there
+    pub tab_id: Option<String>,
+    pub started: std::time::Instant,
+    /// Rows received so far, for backends that stream them
+    pub rows_fetched: usize,
+    /// Statements of a script run so far, and how many it has
+    pub statements: Option<(usize, usize)>,
+}
+
+/// Queries in flight, oldest first
+pub static RUNNING_QUERIES: GlobalSignal<Vec<RunningQuery>> = Signal::global(Vec::new);
+
+/// The query running for `tab_id`, if any.
+pub fn running_query_in(tab_id: Option<&str>) -> Option<RunningQuery> {
+    RUNNING_QUERIES
+        .read()
+        .iter()
+        .find(|q| q.tab_id.as_deref() == tab_id)
+        .cloned()
+}
+
+/// Ask the worker to stop a running query; it stays listed until its response arrives.
+pub fn cancel_query(id: crate::db::RequestId) {
+    send_db_request(crate::db::DbRequest::Cancel(id));
+}
+
+/// Keep the rows a running query has fetched so far and stop receiving more.
+pub fn stop_fetching(id: crate::db::RequestId) {
+    send_db_request(crate::db::DbRequest::StopFetching(id));
+}
+
+/// Informational requests issued while offline, replayed on reconnect
+pub static OFFLINE_QUEUE: GlobalSignal<Vec<crate::db::DbRequest>> = Signal::global(Vec::new);
+
+/// Tables configured for soft deletes, loaded from the config dir
+pub static SOFT_DELETE_TABLES: GlobalSignal<Vec<crate::config::SoftDeleteTable>> =
+    Signal::global(|| crate::config::SoftDeleteStore::new().load());
+
+/// Saved connection, schema and bare name that `table` is marked for soft deletes under.
+/// A schema-qualified name keeps its schema; others are in the connection's schema.
+/// None when the connection isn't saved.
+pub fn soft_delete_key(table: &str) -> Option<(String, String, String)> {
+    let (connection, default_schema) = CURRENT_SAVED_CONNECTION.read().clone()?;
+    let schema = match table.trim().rsplit_once('.') {
+        Some((qualifier, _)) => crate::db::normalize_table_name(qualifier),
+        None => default_schema,
+    };
+    Some((connection, schema, crate::db::normalize_table_name(table)))
+}
+
+/// Soft delete column configured for `table` on the connection in use, if any
+pub fn soft_delete_column(table: &str) -> Option<String> {
+    let (connection, schema, table) = soft_delete_key(table)?;
+    SOFT_DELETE_TABLES
+        .read()
+        .iter()
+        .find(|t| t.is_table(&connection, &schema, &table))
+        .map(|t| t.column.clone())
+}
+
+/// Hover card state for a foreign key target
+#[derive(Clone, Debug, PartialEq)]
+pub enum FkPreview {
+    Loading,
+    Row(Vec<(String, String)>),
+    Missing,
+    Failed(String),
+}
+
+/// FK hover previews fetched this session, keyed by referenced table and key values
+pub static FK_PREVIEWS: GlobalSignal<std::collections::HashMap<String, FkPreview>> =
+    Signal::global(Default::default);
+
+/// EXPLAIN ANALYZE timings of a query, keyed by its SQL; the result is None while measuring
+pub static TIMING_PROFILE: GlobalSignal<Option<(String, Option<Result<ServerTiming, String>>)>> =
+    Signal::global(|| None);
+
+/// Query held back by the plan budget: (sql, reason), until run anyway or dismissed
+pub static PLAN_BUDGET_WARNING: GlobalSignal<Option<(String, String)>> = Signal::global(|| None);
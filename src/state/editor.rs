@@ -1,5 +1,6 @@
-use crate::db::QueryResult;
+use crate::db::{QueryResult, SqlDialect, Translation};
 use dioxus::prelude::*;
+use std::ops::Range;
 
 pub static QUERY_RESULT: GlobalSignal<Option<QueryResult>> = Signal::global(|| None);
 
@@ -11,3 +12,73 @@ pub static ROW_COUNT: GlobalSignal<Option<usize>> = Signal::global(|| None);
 
 // Increments when query history is updated (for UI reactivity)
 pub static HISTORY_REVISION: GlobalSignal<u64> = Signal::global(|| 0);
+
+/// A statement converted to another dialect, shown as a diff before it replaces the original
+#[derive(Clone, Debug, PartialEq)]
+pub struct DialectConversion {
+    pub tab_id: Option<String>,
+    /// Char range of the statement in the tab's content
+    pub range: Range<usize>,
+    pub original: String,
+    pub target: SqlDialect,
+    /// Rule-based conversion, available right away
+    pub rule_based: Translation,
+    /// The model's conversion once it answers
+    pub ai: Option<Result<String, String>>,
+}
+
+pub static DIALECT_CONVERSION: GlobalSignal<Option<DialectConversion>> = Signal::global(|| None);
+
+/// Snippets Tab expands in the editor
+pub static SNIPPETS: GlobalSignal<Vec<crate::config::Snippet>> =
+    Signal::global(|| crate::config::SnippetStore::new().load());
+
+/// Tab stops of the snippet just expanded in a tab, visited in turn with Tab
+#[derive(Clone, Debug, PartialEq)]
+pub struct SnippetSession {
+    pub tab_id: Option<String>,
+    /// Char ranges in the tab's content, as they were when the current stop was selected
+    pub stops: Vec<Range<usize>>,
+    pub current: usize,
+    /// Length of the content in chars when the current stop was selected; typing into
+    /// the stop shifts the ones after it by the difference
+    pub content_len: usize,
+}
+
+pub static SNIPPET_SESSION: GlobalSignal<Option<SnippetSession>> = Signal::global(|| None);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompletionKind {
+    Snippet,
+}
+
+impl CompletionKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CompletionKind::Snippet => "snippet",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompletionItem {
+    pub label: String,
+    pub detail: String,
+    pub kind: CompletionKind,
+}
+
+/// Suggestions for the word before the caret in the editor
+#[derive(Clone, Debug, PartialEq)]
+pub struct Completion {
+    /// Char range of the word the accepted item replaces
+    pub word: Range<usize>,
+    /// 0-based line and column of the word's start, to place the popup under it
+    pub line: usize,
+    pub column: usize,
+    /// Editor scroll offsets (top, left) in pixels when the suggestions were made
+    pub scroll: (f64, f64),
+    pub items: Vec<CompletionItem>,
+    pub selected: usize,
+}
+
+pub static COMPLETION: GlobalSignal<Option<Completion>> = Signal::global(|| None);
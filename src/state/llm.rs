@@ -1,5 +1,10 @@
-use crate::llm::{LlmConfig, QuerySuggestion};
+use super::db::{send_db_request, ConnectionState, CONNECTION, CURRENT_DB_TYPE};
+use crate::config::{TableDescription, TableDescriptionStore};
+use crate::db::RequestId;
+use crate::llm::{LlmConfig, LlmRequest, QuerySuggestion, TokenUsage};
+use crate::services::LlmSender;
 use dioxus::prelude::*;
+use std::collections::HashMap;
 
 /// A preset prompt template for quick selection
 #[derive(Clone, Debug, PartialEq)]
@@ -72,9 +77,90 @@ pub enum LlmStatus {
 
 pub static LLM_STATUS: GlobalSignal<LlmStatus> = Signal::global(|| LlmStatus::None);
 
+/// Tokens and estimated cost of every paid request since the app started
+pub static LLM_SESSION_USAGE: GlobalSignal<TokenUsage> = Signal::global(TokenUsage::default);
+
 /// AI panel state for displaying explanations, optimizations, and fixes
 pub static AI_PANEL: GlobalSignal<AiPanelState> = Signal::global(AiPanelState::default);
 
 /// Schema-aware query suggestions
 pub static SCHEMA_SUGGESTIONS: GlobalSignal<SuggestionsState> =
     Signal::global(SuggestionsState::default);
+
+/// Where the response to an LLM request belongs
+#[derive(Clone, Debug, PartialEq)]
+pub enum LlmOrigin {
+    /// Generated SQL goes into this editor tab
+    Tab(String),
+    AiPanel,
+    /// Suggestions for this table in the schema panel
+    Suggestions(String),
+    /// Description of this table
+    TableDescription(String),
+    /// Conversion of this statement to another dialect
+    DialectConversion(String),
+}
+
+/// Origin of each LLM request still waiting for its response
+pub static LLM_REQUESTS: GlobalSignal<HashMap<RequestId, LlmOrigin>> = Signal::global(HashMap::new);
+
+pub fn send_llm_request(tx: &LlmSender, request: LlmRequest, origin: LlmOrigin) {
+    let id = crate::db::next_request_id();
+    LLM_REQUESTS.write().insert(id, origin);
+    let _ = tx.send((id, request));
+}
+
+/// Rows sampled when asking the LLM to describe a table
+pub const DESCRIBE_SAMPLE_ROWS: usize = 5;
+
+/// AI description of a table, shown in its details in the schema panel
+#[derive(Clone, Debug, PartialEq)]
+pub enum TableDescriptionState {
+    Loading,
+    Ready(TableDescription),
+    Failed(String),
+}
+
+/// Descriptions of the connected database's tables, by table name
+pub static TABLE_DESCRIPTIONS: GlobalSignal<HashMap<String, TableDescriptionState>> =
+    Signal::global(HashMap::new);
+
+/// Name of the connected database, which cached descriptions are stored under
+pub fn described_database() -> Option<String> {
+    match &*CONNECTION.read() {
+        ConnectionState::Connected { db_name, .. } => Some(db_name.clone()),
+        _ => None,
+    }
+}
+
+/// Sample a few rows of `table`; the LLM request follows once they arrive.
+pub fn describe_table(table: &str) {
+    TABLE_DESCRIPTIONS
+        .write()
+        .insert(table.to_string(), TableDescriptionState::Loading);
+    let db_type = CURRENT_DB_TYPE.read().unwrap_or_default();
+    send_db_request(crate::db::DbRequest::FetchTableSample {
+        table: table.to_string(),
+        sql: crate::db::format_select_all_sql(db_type, table, DESCRIBE_SAMPLE_ROWS, None),
+    });
+}
+
+/// Show the stored description of `table`, if it was described before.
+pub fn load_cached_description(table: &str) {
+    if TABLE_DESCRIPTIONS.read().contains_key(table) {
+        return;
+    }
+    let Some(database) = described_database() else {
+        return;
+    };
+    if let Some(description) = TableDescriptionStore::new().get(&database, table) {
+        TABLE_DESCRIPTIONS
+            .write()
+            .insert(table.to_string(), TableDescriptionState::Ready(description));
+    }
+}
+
+/// Abort the request in flight; the UI resets once its `Cancelled` response arrives.
+pub fn cancel_llm_request(tx: &LlmSender) {
+    let _ = tx.send((crate::db::next_request_id(), LlmRequest::Cancel));
+}
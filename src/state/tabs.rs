@@ -1,5 +1,7 @@
 use crate::config::DraftStore;
 use dioxus::prelude::*;
+use std::path::PathBuf;
+use std::time::SystemTime;
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -8,13 +10,31 @@ pub struct QueryTab {
     pub title: String,
     pub content: String,
     pub result: Option<crate::db::QueryResult>,
+    /// Per-statement results when the last run was a script; `result` and `last_error`
+    /// show statement `script_statement` of it
+    pub script: Option<crate::db::ScriptResult>,
+    pub script_statement: usize,
     pub execution_plan: Option<String>,
     pub last_error: Option<String>,
+    /// `last_error` is the server's statement timeout rather than a failure of the query
+    pub timed_out: bool,
     pub execution_time_ms: Option<u64>,
     pub unsaved_changes: bool,
     pub filter_state: Option<crate::filter::FilterState>,
     pub edit_mode: bool,
     pub pending_edits: Vec<CellEdit>,
+    /// Grid changes sent to the database, applied to `result` once they are saved
+    pub saving: Option<SavingChange>,
+    /// The .sql file the tab was opened from
+    pub file_path: Option<PathBuf>,
+    /// Modification time of `file_path` when the tab last read or wrote it
+    pub file_modified: Option<SystemTime>,
+    /// Content of `file_path` when the tab last read or wrote it, None if it couldn't
+    pub file_content: Option<String>,
+    /// `file_path` changed on disk since the tab read it
+    pub file_changed: bool,
+    /// Why the last read or write of `file_path` failed
+    pub file_error: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -22,7 +42,19 @@ pub struct CellEdit {
     pub row_idx: usize,
     pub column: String,
     pub old_value: String,
-    pub new_value: String,
+    /// None sets the cell to SQL NULL
+    pub new_value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SavingChange {
+    /// Edits saved with one UPDATE per row; `rows` holds the row of each statement
+    Edits {
+        edits: Vec<CellEdit>,
+        rows: Vec<usize>,
+    },
+    /// Indexes of the deleted rows
+    Deletes(Vec<usize>),
 }
 
 impl QueryTab {
@@ -32,13 +64,22 @@ impl QueryTab {
             title: title.into(),
             content: String::new(),
             result: None,
+            script: None,
+            script_statement: 0,
             execution_plan: None,
             last_error: None,
+            timed_out: false,
             execution_time_ms: None,
             unsaved_changes: false,
             filter_state: None,
             edit_mode: false,
             pending_edits: vec![],
+            saving: None,
+            file_path: None,
+            file_modified: None,
+            file_content: None,
+            file_changed: false,
+            file_error: None,
         }
     }
 
@@ -46,6 +87,141 @@ impl QueryTab {
         self.content = content.into();
         self
     }
+
+    /// Back the tab with the file at `path`, which held `file_content` when it was last
+    /// modified at `modified`.
+    pub fn with_file(
+        mut self,
+        path: PathBuf,
+        modified: Option<SystemTime>,
+        file_content: Option<String>,
+    ) -> Self {
+        self.file_path = Some(path);
+        self.file_modified = modified;
+        self.file_content = file_content;
+        self
+    }
+
+    /// The tab has a file and its content differs from what was last read or written.
+    pub fn is_modified(&self) -> bool {
+        self.file_path.is_some() && self.file_content.as_ref() != Some(&self.content)
+    }
+
+    /// Replace the content with `file_path` as it is on disk now.
+    pub fn reload_file(&mut self) -> Result<(), String> {
+        let Some(path) = &self.file_path else {
+            return Ok(());
+        };
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        self.file_modified = file_modified(path);
+        self.content = content.clone();
+        self.file_content = Some(content);
+        self.file_changed = false;
+        self.file_error = None;
+        Ok(())
+    }
+
+    /// Keep the content and stop warning about the current version on disk.
+    pub fn keep_content(&mut self) {
+        if let Some(path) = &self.file_path {
+            self.file_modified = file_modified(path);
+            self.file_content = std::fs::read_to_string(path).ok();
+        }
+        self.file_changed = false;
+    }
+
+    /// Write the content to `path` and make it the tab's file.
+    pub fn write_file(&mut self, path: PathBuf) -> Result<(), String> {
+        if let Err(e) = std::fs::write(&path, &self.content) {
+            let error = format!("Could not save {}: {}", path.display(), e);
+            self.file_error = Some(error.clone());
+            return Err(error);
+        }
+        self.title = file_title(&path);
+        self.file_modified = file_modified(&path);
+        self.file_content = Some(self.content.clone());
+        self.file_path = Some(path);
+        self.file_changed = false;
+        self.file_error = None;
+        Ok(())
+    }
+
+    /// Apply the saved grid change to `result`, so it shows the new values while the
+    /// query is re-run.
+    pub fn apply_saved_change(&mut self) {
+        let (Some(change), Some(result)) = (self.saving.take(), self.result.as_mut()) else {
+            return;
+        };
+        match change {
+            SavingChange::Edits { edits, .. } => {
+                for edit in edits {
+                    let Some(col_idx) = result.columns.iter().position(|c| *c == edit.column)
+                    else {
+                        continue;
+                    };
+                    let Some(cell) = result
+                        .rows
+                        .get_mut(edit.row_idx)
+                        .and_then(|row| row.get_mut(col_idx))
+                    else {
+                        continue;
+                    };
+                    match edit.new_value {
+                        Some(value) => {
+                            result.null_cells.remove(&(edit.row_idx, col_idx));
+                            *cell = value;
+                        }
+                        None => {
+                            result.null_cells.insert((edit.row_idx, col_idx));
+                            *cell = "NULL".to_string();
+                        }
+                    }
+                }
+            }
+            SavingChange::Deletes(mut rows) => {
+                rows.sort_unstable();
+                rows.dedup();
+                let kept = |row: usize| rows.binary_search(&row).is_err();
+                let mut row_idx = 0;
+                result.rows.retain(|_| {
+                    row_idx += 1;
+                    kept(row_idx - 1)
+                });
+                let mut row_idx = 0;
+                result.row_versions.retain(|_| {
+                    row_idx += 1;
+                    kept(row_idx - 1)
+                });
+                // Rows below a deleted one move up
+                result.null_cells = result
+                    .null_cells
+                    .iter()
+                    .filter(|(row, _)| kept(*row))
+                    .map(|&(row, col)| (row - rows.partition_point(|&r| r < row), col))
+                    .collect();
+            }
+        }
+    }
+
+    /// Show the result or error of statement `index` of the tab's script, leaving any
+    /// edits of the previous result behind.
+    pub fn show_script_statement(&mut self, index: usize) {
+        let Some(statement) = self.script.as_ref().and_then(|s| s.statements.get(index)) else {
+            return;
+        };
+        let (result, error) = match &statement.outcome {
+            Ok(result) => (Some(result.clone()), None),
+            Err(e) => (None, Some(e.clone())),
+        };
+        self.execution_time_ms = Some(statement.execution_time_ms);
+        self.result = result;
+        self.last_error = error;
+        self.timed_out = false;
+        self.script_statement = index;
+        self.edit_mode = false;
+        self.pending_edits.clear();
+        self.filter_state = None;
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -62,7 +238,18 @@ impl TabState {
             let tabs: Vec<QueryTab> = draft
                 .tabs
                 .iter()
-                .map(|d| QueryTab::new(d.title.clone()).with_content(d.content.clone()))
+                .map(|d| {
+                    let tab = QueryTab::new(d.title.clone()).with_content(d.content.clone());
+                    match &d.file_path {
+                        // Changes made on disk while the app was closed are flagged on the next check
+                        Some(path) => tab.with_file(
+                            path.clone(),
+                            d.file_modified,
+                            std::fs::read_to_string(path).ok(),
+                        ),
+                        None => tab,
+                    }
+                })
                 .collect();
 
             let active_id = tabs
@@ -98,6 +285,14 @@ impl TabState {
             .and_then(|id| self.tabs.iter_mut().find(|t| t.id == *id))
     }
 
+    /// The tab a query answers: the one it started from, or the active tab when unknown.
+    pub fn query_tab_mut(&mut self, id: Option<&str>) -> Option<&mut QueryTab> {
+        match id {
+            Some(id) => self.tabs.iter_mut().find(|t| t.id == id),
+            None => self.active_tab_mut(),
+        }
+    }
+
     pub fn add_tab(&mut self, title: impl Into<String>) -> String {
         let tab = QueryTab::new(title);
         let id = tab.id.clone();
@@ -106,6 +301,28 @@ impl TabState {
         id
     }
 
+    /// Open `content` read from `path` in a new tab, or switch to the tab already
+    /// showing that file.
+    pub fn open_file_tab(&mut self, path: PathBuf, content: String) -> String {
+        if let Some(tab) = self
+            .tabs
+            .iter()
+            .find(|t| t.file_path.as_ref() == Some(&path))
+        {
+            let id = tab.id.clone();
+            self.active_tab_id = Some(id.clone());
+            return id;
+        }
+        let modified = file_modified(&path);
+        let tab = QueryTab::new(file_title(&path))
+            .with_content(content.clone())
+            .with_file(path, modified, Some(content));
+        let id = tab.id.clone();
+        self.tabs.push(tab);
+        self.active_tab_id = Some(id.clone());
+        id
+    }
+
     pub fn close_tab(&mut self, id: &str) {
         if self.tabs.len() <= 1 {
             return; // Don't close last tab
@@ -127,4 +344,16 @@ impl TabState {
     }
 }
 
+/// Tab title for the file at `path`: its name.
+fn file_title(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Modification time of the file at `path`, if it can be read.
+pub fn file_modified(path: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
 pub static EDITOR_TABS: GlobalSignal<TabState> = Signal::global(TabState::new);
@@ -37,15 +37,51 @@ pub static EDITOR_PANEL_HEIGHT: GlobalSignal<f64> = Signal::global(|| 300.0);
 /// Increments when saved queries are updated (for UI reactivity)
 pub static QUERIES_REVISION: GlobalSignal<u64> = Signal::global(|| 0);
 
+/// Read-only queries from the team library, starting from the last fetched copy
+pub static TEAM_QUERIES: GlobalSignal<Vec<crate::config::SavedQuery>> =
+    Signal::global(|| crate::config::TeamLibraryStore::new().load_cached());
+
+/// Outcome of the last team library refresh: Ok(time of sync) or the error
+pub static TEAM_LIBRARY_STATUS: GlobalSignal<Option<Result<String, String>>> =
+    Signal::global(|| None);
+
+/// Result grid display preferences and UI zoom
+pub static DISPLAY_SETTINGS: GlobalSignal<crate::config::DisplaySettings> =
+    Signal::global(|| crate::config::DisplayStore::new().load());
+
+/// Query defaults shared by every connection
+pub static QUERY_SETTINGS: GlobalSignal<crate::config::QuerySettings> =
+    Signal::global(|| crate::config::QuerySettingsStore::new().load());
+
+/// System tray icon and minimize-to-tray preferences
+pub static TRAY_SETTINGS: GlobalSignal<crate::config::TraySettings> =
+    Signal::global(|| crate::config::TrayStore::new().load());
+
+/// Running query dialog visibility
+pub static SHOW_RUNNING_QUERY: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Only the editor, results and connection, in larger type, for walking others through queries
+pub static PRESENTATION_MODE: GlobalSignal<bool> = Signal::global(|| false);
+
 /// Whether we're currently resizing panels
 pub static IS_RESIZING_PANELS: GlobalSignal<bool> = Signal::global(|| false);
 
 /// Quick switcher visibility
 pub static SHOW_QUICK_SWITCHER: GlobalSignal<bool> = Signal::global(|| false);
 
+/// Search across tabs, saved queries, templates and history (Ctrl+Shift+F)
+pub static SHOW_WORKSPACE_SEARCH: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Find and replace bar above the editor (Ctrl+F, Ctrl+H)
+pub static SHOW_EDITOR_FIND: GlobalSignal<bool> = Signal::global(|| false);
+
 /// JSON viewer modal state
 pub static SHOW_JSON_VIEWER: GlobalSignal<bool> = Signal::global(|| false);
 pub static JSON_VIEWER_CONTENT: GlobalSignal<String> = Signal::global(String::new);
+/// Waiting for the user to pick a second cell to compare with the viewer content
+pub static PICKING_COMPARE_CELL: GlobalSignal<bool> = Signal::global(|| false);
+/// Second cell shown as a diff against the viewer content
+pub static JSON_VIEWER_COMPARE: GlobalSignal<Option<String>> = Signal::global(|| None);
 
 /// Execution plan modal state
 pub static SHOW_EXECUTION_PLAN: GlobalSignal<bool> = Signal::global(|| false);
@@ -56,5 +92,166 @@ pub static IMPORT_PROGRESS: GlobalSignal<Option<(usize, usize)>> = Signal::globa
 /// Import completion/error message shown in the import dialog
 pub static IMPORT_MESSAGE: GlobalSignal<Option<String>> = Signal::global(|| None);
 
+/// Dry-run import in progress
+pub static VALIDATING_IMPORT: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Result of the last dry-run import, shown in the import dialog
+pub static IMPORT_VALIDATION: GlobalSignal<Option<Result<crate::db::ImportValidation, String>>> =
+    Signal::global(|| None);
+
 /// Import dialog visibility
 pub static SHOW_IMPORT_DIALOG: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Watched folders dialog visibility
+pub static SHOW_IMPORT_WATCHES: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Files picked up from watched folders that are queued or importing
+pub static WATCHED_FILES: GlobalSignal<Vec<crate::import::WatchedFile>> = Signal::global(Vec::new);
+
+/// Bumped when the watched folder log changes
+pub static IMPORT_WATCH_LOG_REVISION: GlobalSignal<u64> = Signal::global(|| 0);
+
+/// Export options dialog visibility
+pub static SHOW_EXPORT_DIALOG: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Background exports, running and finished
+pub static EXPORT_JOBS: GlobalSignal<Vec<crate::export::ExportJob>> = Signal::global(Vec::new);
+
+/// Export jobs panel visibility
+pub static SHOW_EXPORT_JOBS: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Batch progress panel visibility; shown whenever a batch starts
+pub static SHOW_BATCH_PROGRESS: GlobalSignal<bool> = Signal::global(|| true);
+
+/// Server variables dialog visibility
+pub static SHOW_SERVER_VARIABLES: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Session variables panel visibility
+pub static SHOW_SESSION_PANEL: GlobalSignal<bool> = Signal::global(|| false);
+
+/// LISTEN/NOTIFY feed panel visibility
+pub static SHOW_NOTIFICATIONS_PANEL: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Change capture viewer visibility
+pub static SHOW_CHANGE_CAPTURE: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Extension manager dialog visibility
+pub static SHOW_EXTENSIONS: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Local usage stats dialog visibility
+pub static SHOW_USAGE_STATS: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Bulk edit dialog visibility
+pub static SHOW_BULK_EDIT: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Saved query tests dialog visibility
+pub static SHOW_QUERY_TESTS: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Saved query tests are running
+pub static RUNNING_QUERY_TESTS: GlobalSignal<bool> = Signal::global(|| false);
+
+/// Outcome of the last query test run, shown in the tests dialog
+pub static QUERY_TEST_RESULTS: GlobalSignal<
+    Option<Result<Vec<crate::db::QueryTestOutcome>, String>>,
+> = Signal::global(|| None);
+
+/// Seed script dialog, opened on a table or on a filtered result of it
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeedScriptState {
+    pub table: String,
+    /// Rows of a filtered result, used for `table` instead of fetching them
+    pub result: Option<crate::db::QueryResult>,
+    /// Set while the other tables' rows are fetched: whether to anonymize the script
+    pub generating: Option<bool>,
+    pub error: Option<String>,
+}
+
+pub static SEED_SCRIPT: GlobalSignal<Option<SeedScriptState>> = Signal::global(|| None);
+
+/// Column distribution chart, opened on a table
+#[derive(Clone, Debug, PartialEq)]
+pub struct DistributionState {
+    pub table: String,
+    pub loading: bool,
+    pub result: Option<Result<crate::db::Distribution, String>>,
+}
+
+pub static COLUMN_DISTRIBUTION: GlobalSignal<Option<DistributionState>> = Signal::global(|| None);
+
+/// A statement with placeholders waiting for its parameter values
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterPrompt {
+    pub sql: String,
+    /// Tab the statement runs for
+    pub tab_id: Option<String>,
+    pub placeholders: Vec<crate::db::Placeholder>,
+}
+
+pub static PARAMETER_PROMPT: GlobalSignal<Option<ParameterPrompt>> = Signal::global(|| None);
+
+/// Anonymized copy dialog, opened on the table it starts with selected
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnonymizedCopyState {
+    pub table: String,
+    /// True while the rows are read, transformed and, unless it's a dry run, written
+    pub running: bool,
+    pub summary: Option<crate::db::CopySummary>,
+    pub error: Option<String>,
+}
+
+pub static ANONYMIZED_COPY: GlobalSignal<Option<AnonymizedCopyState>> = Signal::global(|| None);
+
+/// `fbench://` link waiting for the user to confirm it; Some while the dialog is open
+pub static PENDING_DEEP_LINK: GlobalSignal<Option<crate::instance::DeepLink>> =
+    Signal::global(|| None);
+
+/// Column targeted by the find-and-replace dialog; Some while the dialog is open
+pub static REPLACE_COLUMN: GlobalSignal<Option<String>> = Signal::global(|| None);
+
+/// Rows the find-and-replace would change, keyed by its count statement; None while counting
+pub static REPLACE_MATCH_COUNT: GlobalSignal<Option<(String, Option<Result<i64, String>>)>> =
+    Signal::global(|| None);
+
+/// Row history dialog: the row being inspected and, once loaded, its audit entries
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowHistoryState {
+    pub table: String,
+    pub keys: Vec<(String, String)>,
+    pub audit_table: Option<String>,
+    pub entries: Option<crate::db::QueryResult>,
+    pub error: Option<String>,
+}
+
+pub static ROW_HISTORY: GlobalSignal<Option<RowHistoryState>> = Signal::global(|| None);
+
+/// A table whose foreign key points at the inspected row
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReferencingTable {
+    pub table: String,
+    pub columns: Vec<String>,
+    /// Query listing the referencing rows
+    pub select_sql: String,
+    /// None while the count is loading
+    pub count: Option<Result<i64, String>>,
+}
+
+/// Referencing rows dialog: label of the inspected row and its child tables
+pub static REFERENCING_ROWS: GlobalSignal<Option<(String, Vec<ReferencingTable>)>> =
+    Signal::global(|| None);
+
+/// Comment being edited on a table, or on one of its columns
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommentEdit {
+    pub table: String,
+    pub column: Option<crate::db::ColumnInfo>,
+    /// The current comment, empty when there is none
+    pub comment: String,
+}
+
+pub static EDITING_COMMENT: GlobalSignal<Option<CommentEdit>> = Signal::global(|| None);
+
+/// Tab with unsaved file changes waiting for the user to save or discard them before it closes
+pub static CLOSING_TAB: GlobalSignal<Option<String>> = Signal::global(|| None);
+
+/// Snippet editor dialog visibility
+pub static SHOW_SNIPPETS_DIALOG: GlobalSignal<bool> = Signal::global(|| false);